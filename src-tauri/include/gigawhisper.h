@@ -0,0 +1,55 @@
+/*
+ * GigaWhisper C API
+ *
+ * Exported by the gigawhisper_lib shared and static libraries. See
+ * src-tauri/src/ffi.rs for details.
+ *
+ * - Functions returning int32_t return 0 on success and -1 on failure.
+ * - Functions returning a pointer return NULL on failure.
+ * - After a failure, gw_last_error() describes it (per thread).
+ * - An internal panic is reported as a failure; it never unwinds into the caller.
+ * - Functions that block may not be called from a thread running a tokio runtime.
+ * - Results are UTF-8 JSON strings, freed with gw_string_free().
+ */
+
+#ifndef GIGAWHISPER_H
+#define GIGAWHISPER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct GwEngine GwEngine;
+
+/* Download progress: percentage (0-100) and the caller's user data */
+typedef void (*GwProgressCallback)(float percentage, void *user_data);
+
+/* Create an engine from settings.toml content, or the app's saved settings if NULL */
+GwEngine *gw_engine_new(const char *settings_toml);
+void gw_engine_free(GwEngine *engine);
+
+/* Load the configured model ahead of the first transcription */
+int32_t gw_engine_preload(const GwEngine *engine);
+
+/* Transcribe a WAV file or mono float samples; returns a JSON transcript */
+char *gw_transcribe_file(const GwEngine *engine, const char *path);
+char *gw_transcribe_buffer(const GwEngine *engine, const float *samples, size_t len,
+                           uint32_t sample_rate);
+
+/* Model management: "tiny", "base", "small", "medium", "large" */
+char *gw_models_list(void);
+int32_t gw_model_download(const char *model, GwProgressCallback progress, void *user_data);
+int32_t gw_model_delete(const char *model);
+
+const char *gw_last_error(void);
+void gw_string_free(char *string);
+const char *gw_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GIGAWHISPER_H */
@@ -0,0 +1,157 @@
+//! Audit Log
+//!
+//! Append-only record of calls made by external clients and of changes to
+//! their permissions, stored as JSON lines next to the settings.
+
+use super::{ApiClientInfo, ApiScope};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Entries kept when the log is trimmed
+pub const MAX_AUDIT_ENTRIES: usize = 1000;
+
+/// One audited call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AuditEntry {
+    pub timestamp: String,
+    /// What was called (e.g. `start_recording`, `client.create`)
+    pub action: String,
+    /// Calling client (None when the token was rejected or for local changes)
+    pub client_id: Option<String>,
+    pub client_name: Option<String>,
+    /// Scope the call required
+    pub scope: Option<ApiScope>,
+    pub allowed: bool,
+    /// Why the call was refused, or what changed
+    pub detail: Option<String>,
+}
+
+impl AuditEntry {
+    pub fn new(
+        action: &str,
+        client: Option<&ApiClientInfo>,
+        scope: Option<ApiScope>,
+        allowed: bool,
+        detail: Option<String>,
+    ) -> Self {
+        Self {
+            timestamp: crate::history::chrono_timestamp(),
+            action: action.to_string(),
+            client_id: client.map(|c| c.id.clone()),
+            client_name: client.map(|c| c.name.clone()),
+            scope,
+            allowed,
+            detail,
+        }
+    }
+}
+
+/// Audit log file
+pub struct AuditLog {
+    path: PathBuf,
+    /// Serializes appends and trimming
+    lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry, trimming the file once it holds twice the limit
+    pub fn append(&self, entry: &AuditEntry) {
+        let _guard = self.lock.lock();
+        if let Err(e) = self.try_append(entry) {
+            tracing::warn!("Failed to write audit log: {}", e);
+        }
+    }
+
+    fn try_append(&self, entry: &AuditEntry) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let line = serde_json::to_string(entry).map_err(std::io::Error::other)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        drop(file);
+
+        let content = std::fs::read_to_string(&self.path)?;
+        let lines: Vec<&str> = content.lines().collect();
+        if lines.len() > 2 * MAX_AUDIT_ENTRIES {
+            let kept = lines[lines.len() - MAX_AUDIT_ENTRIES..].join("\n");
+            std::fs::write(&self.path, kept + "\n")?;
+        }
+        Ok(())
+    }
+
+    /// Most recent entries, newest first
+    pub fn entries(&self, limit: usize) -> Vec<AuditEntry> {
+        let _guard = self.lock.lock();
+        let Ok(content) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect()
+    }
+}
+
+/// Get the global audit log
+pub fn audit_log() -> &'static AuditLog {
+    static AUDIT_LOG: OnceLock<AuditLog> = OnceLock::new();
+    AUDIT_LOG.get_or_init(|| {
+        AuditLog::new(crate::config::data_dir().join("api_audit.jsonl"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_append_and_read_newest_first() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        assert!(log.entries(10).is_empty());
+
+        log.append(&AuditEntry::new("get_history", None, Some(ApiScope::ReadHistory), false, None));
+        log.append(&AuditEntry::new("client.create", None, None, true, Some("Deck".into())));
+
+        let entries = log.entries(10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "client.create");
+        assert!(!entries[1].allowed);
+        assert_eq!(log.entries(1).len(), 1);
+    }
+
+    #[test]
+    fn test_log_is_trimmed() {
+        let dir = TempDir::new().unwrap();
+        let log = AuditLog::new(dir.path().join("audit.jsonl"));
+        for i in 0..=2 * MAX_AUDIT_ENTRIES {
+            log.append(&AuditEntry::new(&format!("call-{}", i), None, None, true, None));
+        }
+
+        let entries = log.entries(usize::MAX);
+        assert_eq!(entries.len(), MAX_AUDIT_ENTRIES);
+        assert_eq!(entries[0].action, format!("call-{}", 2 * MAX_AUDIT_ENTRIES));
+    }
+}
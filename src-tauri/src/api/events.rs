@@ -0,0 +1,122 @@
+//! API Events
+//!
+//! Recording and transcription events published to external clients (the
+//! local API's websocket stream). Incognito transcriptions are never
+//! published; their end is only announced as `ProcessingFinished`.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+/// Events buffered for slow subscribers before they start missing some
+const EVENT_CAPACITY: usize = 64;
+
+/// Something external clients can react to
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ApiEvent {
+    RecordingStarted {
+        profile: Option<String>,
+    },
+    /// The recording ended and is being transcribed
+    RecordingStopped,
+    /// Processing finished; the text is empty when nothing was said
+    TranscriptionCompleted(LatestTranscription),
+    TranscriptionFailed {
+        error: String,
+    },
+    /// Processing finished for an incognito recording, whose outcome is
+    /// not shared
+    ProcessingFinished,
+}
+
+/// Most recent transcription
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LatestTranscription {
+    pub text: String,
+    /// When it was transcribed (ISO 8601)
+    pub timestamp: String,
+    pub profile: Option<String>,
+}
+
+fn event_sender() -> &'static broadcast::Sender<ApiEvent> {
+    static SENDER: OnceLock<broadcast::Sender<ApiEvent>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(EVENT_CAPACITY).0)
+}
+
+fn latest() -> &'static Mutex<Option<LatestTranscription>> {
+    static LATEST: OnceLock<Mutex<Option<LatestTranscription>>> = OnceLock::new();
+    LATEST.get_or_init(|| Mutex::new(None))
+}
+
+/// Publish an event to every subscriber
+///
+/// Completed transcriptions with text are also kept as the latest
+/// transcription.
+pub fn publish_event(event: ApiEvent) {
+    if let ApiEvent::TranscriptionCompleted(transcription) = &event {
+        if !transcription.text.is_empty() {
+            *latest().lock() = Some(transcription.clone());
+        }
+    }
+    // No subscribers is not an error
+    let _ = event_sender().send(event);
+}
+
+/// Receive the events published from now on
+pub fn subscribe_events() -> broadcast::Receiver<ApiEvent> {
+    event_sender().subscribe()
+}
+
+/// The last completed transcription, if any since startup
+pub fn latest_transcription() -> Option<LatestTranscription> {
+    latest().lock().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_and_subscribe() {
+        let mut receiver = subscribe_events();
+        let transcription = LatestTranscription {
+            text: "Hello".to_string(),
+            timestamp: "2024-01-15T10:30:00Z".to_string(),
+            profile: None,
+        };
+
+        publish_event(ApiEvent::RecordingStopped);
+        publish_event(ApiEvent::TranscriptionCompleted(transcription.clone()));
+
+        assert_eq!(receiver.try_recv().unwrap(), ApiEvent::RecordingStopped);
+        assert_eq!(
+            receiver.try_recv().unwrap(),
+            ApiEvent::TranscriptionCompleted(transcription.clone())
+        );
+        assert_eq!(latest_transcription(), Some(transcription));
+    }
+
+    #[test]
+    fn test_event_json() {
+        let event = ApiEvent::RecordingStarted {
+            profile: Some("meeting".to_string()),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "recording-started", "profile": "meeting" })
+        );
+
+        let event = ApiEvent::TranscriptionCompleted(LatestTranscription {
+            text: "Hi".to_string(),
+            timestamp: "2024-01-15T10:30:00Z".to_string(),
+            profile: None,
+        });
+        assert_eq!(
+            serde_json::to_value(&event).unwrap()["type"],
+            "transcription-completed"
+        );
+        assert_eq!(serde_json::to_value(&event).unwrap()["text"], "Hi");
+    }
+}
@@ -0,0 +1,49 @@
+//! Local API Module
+//!
+//! Access control for external clients (the local API and plugins): client
+//! tokens limited to scopes, and an audit log of their calls. Also the local
+//! HTTP/websocket server itself, the events it streams and their MQTT
+//! forwarding.
+
+mod audit;
+mod events;
+mod mqtt;
+mod permissions;
+mod server;
+
+pub use audit::*;
+pub use events::*;
+pub use mqtt::*;
+pub use permissions::*;
+pub use server::*;
+
+/// Check `token` for `scope` and record the call in the audit log
+///
+/// Entry point for the local API and plugins: every external call goes
+/// through here, allowed or not.
+pub fn authorize_call(
+    token: &str,
+    scope: ApiScope,
+    action: &str,
+) -> Result<ApiClientInfo, PermissionError> {
+    let result = {
+        let mut registry = get_client_registry().write();
+        let result = registry.authorize(token, scope);
+        if let Ok(client) = &result {
+            if registry.last_used_save_due(&client.id) {
+                if let Err(e) = registry.save() {
+                    tracing::warn!("Failed to save API client last use: {}", e);
+                }
+            }
+        }
+        result
+    };
+
+    let entry = match &result {
+        Ok(client) => AuditEntry::new(action, Some(client), Some(scope), true, None),
+        Err(e) => AuditEntry::new(action, None, Some(scope), false, Some(e.to_string())),
+    };
+    audit_log().append(&entry);
+
+    result
+}
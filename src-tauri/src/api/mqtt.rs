@@ -0,0 +1,528 @@
+//! MQTT Publishing
+//!
+//! Forwards API events to an MQTT broker for home automation: recording
+//! state to `<topic>/state` and final transcripts to `<topic>/transcript`.
+//! Speaks just enough MQTT 3.1.1 for that: one connection is kept open
+//! while publishing is enabled, with a last will setting the state to
+//! "offline" when the app goes away without disconnecting. Messages are
+//! published at QoS 0.
+
+use super::{subscribe_events, ApiEvent};
+use crate::config::{MqttSettings, SecretsManager};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+
+/// Time allowed to connect to the broker and for each read or write
+const BROKER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Keep-alive announced to the broker, in seconds
+const KEEP_ALIVE_SECS: u16 = 30;
+
+/// State announced by the last will, and when publishing stops
+const OFFLINE_STATE: &str = "offline";
+
+/// MQTT errors
+#[derive(Debug, thiserror::Error)]
+pub enum MqttError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Cannot resolve broker address {0}")]
+    Resolve(String),
+
+    #[error("Broker refused the connection: {0}")]
+    Refused(&'static str),
+
+    #[error("Unexpected reply from broker")]
+    Protocol,
+}
+
+/// Message to publish
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttMessage {
+    pub topic: String,
+    pub payload: String,
+    pub retain: bool,
+}
+
+/// Topic prefix without a trailing slash
+fn topic_prefix(settings: &MqttSettings) -> &str {
+    settings.topic.trim().trim_end_matches('/')
+}
+
+/// Message setting `<topic>/state`
+pub fn state_message(settings: &MqttSettings, state: &str) -> MqttMessage {
+    MqttMessage {
+        topic: format!("{}/state", topic_prefix(settings)),
+        payload: state.to_string(),
+        retain: settings.retain,
+    }
+}
+
+/// State the app is in after an event
+pub fn state_after_event(event: &ApiEvent) -> &'static str {
+    match event {
+        ApiEvent::RecordingStarted { .. } => "recording",
+        ApiEvent::RecordingStopped => "processing",
+        ApiEvent::TranscriptionCompleted(_) | ApiEvent::ProcessingFinished => "idle",
+        ApiEvent::TranscriptionFailed { .. } => "error",
+    }
+}
+
+/// Messages announcing an event under the topic prefix
+///
+/// Only state messages are retained, so new subscribers learn the current
+/// state but not an old transcript.
+pub fn messages_for_event(event: &ApiEvent, settings: &MqttSettings) -> Vec<MqttMessage> {
+    let mut messages = Vec::new();
+    if let ApiEvent::TranscriptionCompleted(transcription) = event {
+        if !transcription.text.is_empty() {
+            messages.push(MqttMessage {
+                topic: format!("{}/transcript", topic_prefix(settings)),
+                payload: transcription.text.clone(),
+                retain: false,
+            });
+        }
+    }
+    messages.push(state_message(settings, state_after_event(event)));
+    messages
+}
+
+/// Remaining length field of a fixed header
+fn encode_length(mut length: usize, packet: &mut Vec<u8>) {
+    loop {
+        let mut byte = (length % 128) as u8;
+        length /= 128;
+        if length > 0 {
+            byte |= 0x80;
+        }
+        packet.push(byte);
+        if length == 0 {
+            break;
+        }
+    }
+}
+
+/// Length-prefixed UTF-8 string
+fn encode_str(value: &str, packet: &mut Vec<u8>) {
+    packet.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    packet.extend_from_slice(value.as_bytes());
+}
+
+/// Packet of `packet_type` with `body` as variable header and payload
+fn packet(packet_type: u8, body: &[u8]) -> Vec<u8> {
+    let mut packet = vec![packet_type];
+    encode_length(body.len(), &mut packet);
+    packet.extend_from_slice(body);
+    packet
+}
+
+/// CONNECT packet, starting a clean session
+///
+/// The broker publishes `will` at QoS 0 if the connection drops without a
+/// DISCONNECT.
+pub fn connect_packet(
+    client_id: &str,
+    username: Option<&str>,
+    password: Option<&str>,
+    will: Option<&MqttMessage>,
+) -> Vec<u8> {
+    let mut flags = 0x02;
+    if let Some(will) = will {
+        flags |= 0x04;
+        if will.retain {
+            flags |= 0x20;
+        }
+    }
+    if username.is_some() {
+        flags |= 0x80;
+        if password.is_some() {
+            flags |= 0x40;
+        }
+    }
+
+    let mut body = Vec::new();
+    encode_str("MQTT", &mut body);
+    body.push(4); // protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&KEEP_ALIVE_SECS.to_be_bytes());
+    encode_str(client_id, &mut body);
+    if let Some(will) = will {
+        encode_str(&will.topic, &mut body);
+        encode_str(&will.payload, &mut body);
+    }
+    if let Some(username) = username {
+        encode_str(username, &mut body);
+        if let Some(password) = password {
+            encode_str(password, &mut body);
+        }
+    }
+    packet(0x10, &body)
+}
+
+/// PUBLISH packet at QoS 0
+pub fn publish_packet(message: &MqttMessage) -> Vec<u8> {
+    let mut body = Vec::new();
+    encode_str(&message.topic, &mut body);
+    body.extend_from_slice(message.payload.as_bytes());
+    packet(0x30 | u8::from(message.retain), &body)
+}
+
+/// Check a CONNACK packet
+pub fn check_connack(reply: &[u8; 4]) -> Result<(), MqttError> {
+    if reply[0] != 0x20 || reply[1] != 0x02 {
+        return Err(MqttError::Protocol);
+    }
+    match reply[3] {
+        0 => Ok(()),
+        1 => Err(MqttError::Refused("unsupported protocol version")),
+        2 => Err(MqttError::Refused("client ID rejected")),
+        3 => Err(MqttError::Refused("server unavailable")),
+        4 => Err(MqttError::Refused("bad user name or password")),
+        5 => Err(MqttError::Refused("not authorized")),
+        _ => Err(MqttError::Protocol),
+    }
+}
+
+/// Open connection to the broker
+///
+/// Dropping it without [`MqttConnection::close`] leaves the broker to
+/// publish the "offline" last will.
+pub struct MqttConnection {
+    stream: TcpStream,
+    settings: MqttSettings,
+}
+
+impl MqttConnection {
+    /// Connect with a last will setting the state to "offline"
+    pub fn open(settings: &MqttSettings, password: Option<&str>) -> Result<Self, MqttError> {
+        let host = settings.host.trim();
+        let address = (host, settings.port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addresses| addresses.next())
+            .ok_or_else(|| MqttError::Resolve(format!("{}:{}", host, settings.port)))?;
+
+        let mut stream = TcpStream::connect_timeout(&address, BROKER_TIMEOUT)?;
+        stream.set_read_timeout(Some(BROKER_TIMEOUT))?;
+        stream.set_write_timeout(Some(BROKER_TIMEOUT))?;
+
+        let username = settings.username.as_deref().filter(|u| !u.is_empty());
+        let will = state_message(settings, OFFLINE_STATE);
+        stream.write_all(&connect_packet(
+            settings.client_id.trim(),
+            username,
+            password,
+            Some(&will),
+        ))?;
+        let mut reply = [0u8; 4];
+        stream.read_exact(&mut reply)?;
+        check_connack(&reply)?;
+
+        Ok(Self {
+            stream,
+            settings: settings.clone(),
+        })
+    }
+
+    /// Publish `messages` in order
+    pub fn publish(&mut self, messages: &[MqttMessage]) -> Result<(), MqttError> {
+        for message in messages {
+            self.stream.write_all(&publish_packet(message))?;
+        }
+        Ok(())
+    }
+
+    /// Keep the connection alive and check the broker still answers
+    pub fn ping(&mut self) -> Result<(), MqttError> {
+        self.stream.write_all(&[0xC0, 0x00])?;
+        let mut reply = [0u8; 2];
+        self.stream.read_exact(&mut reply)?;
+        if reply != [0xD0, 0x00] {
+            return Err(MqttError::Protocol);
+        }
+        Ok(())
+    }
+
+    /// Set the state to "offline" and disconnect
+    ///
+    /// The broker discards the last will on a DISCONNECT, so the state is
+    /// published first.
+    pub fn close(mut self) -> Result<(), MqttError> {
+        let offline = state_message(&self.settings, OFFLINE_STATE);
+        self.publish(std::slice::from_ref(&offline))?;
+        self.stream.write_all(&[0xE0, 0x00])?;
+        Ok(())
+    }
+}
+
+/// Bring the connection in line with `settings` and publish `messages`
+///
+/// A connection opened with other settings is closed first. A new
+/// connection announces `state`, replacing a last will published earlier.
+/// With no messages, an open connection is pinged instead.
+fn sync_connection(
+    connection: Option<MqttConnection>,
+    settings: &MqttSettings,
+    state: &str,
+    messages: &[MqttMessage],
+) -> (Option<MqttConnection>, Result<(), MqttError>) {
+    let connection = match connection {
+        Some(connection) if connection.settings == *settings => Some(connection),
+        Some(connection) => {
+            if let Err(e) = connection.close() {
+                tracing::debug!("MQTT disconnect failed: {}", e);
+            }
+            None
+        }
+        None => None,
+    };
+    if !settings.enabled {
+        return (None, Ok(()));
+    }
+
+    let (mut connection, fresh) = match connection {
+        Some(connection) => (connection, false),
+        None => {
+            let password = settings
+                .username
+                .is_some()
+                .then(|| SecretsManager::get_mqtt_password().ok())
+                .flatten();
+            match MqttConnection::open(settings, password.as_deref()) {
+                Ok(connection) => (connection, true),
+                Err(e) => return (None, Err(e)),
+            }
+        }
+    };
+
+    let result = match (messages.is_empty(), fresh) {
+        (false, _) => connection.publish(messages),
+        (true, true) => connection.publish(&[state_message(settings, state)]),
+        (true, false) => connection.ping(),
+    };
+    match result {
+        Ok(()) => (Some(connection), Ok(())),
+        // Reconnect on the next event or keep-alive
+        Err(e) => (None, Err(e)),
+    }
+}
+
+/// Publish API events to the broker while MQTT is enabled
+///
+/// Reads the settings for each event and keep-alive, so changes apply
+/// without a restart.
+pub fn spawn_mqtt_publisher(app: &AppHandle) {
+    let app_handle = app.clone();
+    app.state::<crate::AppState>()
+        .tasks
+        .spawn("mqtt-publisher", async move {
+            let mut events = subscribe_events();
+            let mut keep_alive =
+                tokio::time::interval(Duration::from_secs(u64::from(KEEP_ALIVE_SECS) / 2));
+            let mut connection: Option<MqttConnection> = None;
+            let mut state = "idle";
+            loop {
+                let event = tokio::select! {
+                    received = events.recv() => match received {
+                        Ok(event) => Some(event),
+                        Err(RecvError::Lagged(missed)) => {
+                            tracing::warn!("MQTT publisher missed {} events", missed);
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                    _ = keep_alive.tick() => None,
+                };
+
+                let settings = app_handle
+                    .state::<crate::AppState>()
+                    .config
+                    .read()
+                    .mqtt
+                    .clone();
+                let messages = match &event {
+                    Some(event) => {
+                        state = state_after_event(event);
+                        messages_for_event(event, &settings)
+                    }
+                    None => Vec::new(),
+                };
+                if !settings.enabled && connection.is_none() {
+                    continue;
+                }
+
+                // Published in order, one event at a time
+                let previous = connection.take();
+                let result = tokio::task::spawn_blocking(move || {
+                    sync_connection(previous, &settings, state, &messages)
+                })
+                .await;
+                match result {
+                    Ok((open, result)) => {
+                        connection = open;
+                        if let Err(e) = result {
+                            tracing::warn!("MQTT publish failed: {}", e);
+                        }
+                    }
+                    Err(e) => tracing::warn!("MQTT publisher task failed: {}", e),
+                }
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::LatestTranscription;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_messages_for_event() {
+        let settings = MqttSettings {
+            topic: "home/desk/".to_string(),
+            retain: true,
+            ..Default::default()
+        };
+
+        let messages = messages_for_event(&ApiEvent::RecordingStopped, &settings);
+        assert_eq!(
+            messages,
+            vec![MqttMessage {
+                topic: "home/desk/state".to_string(),
+                payload: "processing".to_string(),
+                retain: true,
+            }]
+        );
+
+        let completed = |text: &str| {
+            ApiEvent::TranscriptionCompleted(LatestTranscription {
+                text: text.to_string(),
+                timestamp: "2024-01-15T10:30:00Z".to_string(),
+                profile: None,
+            })
+        };
+        let messages = messages_for_event(&completed("Lights off"), &settings);
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].topic, "home/desk/transcript");
+        assert_eq!(messages[0].payload, "Lights off");
+        assert!(!messages[0].retain);
+        assert_eq!(messages[1].payload, "idle");
+
+        // Nothing was said: only the state changes
+        assert_eq!(messages_for_event(&completed(""), &settings).len(), 1);
+
+        // Incognito dictations only return the state to idle
+        let messages = messages_for_event(&ApiEvent::ProcessingFinished, &settings);
+        assert_eq!(messages, vec![state_message(&settings, "idle")]);
+    }
+
+    #[test]
+    fn test_encode_length() {
+        let encoded = |length| {
+            let mut packet = Vec::new();
+            encode_length(length, &mut packet);
+            packet
+        };
+        assert_eq!(encoded(0), vec![0x00]);
+        assert_eq!(encoded(127), vec![0x7F]);
+        assert_eq!(encoded(128), vec![0x80, 0x01]);
+        assert_eq!(encoded(16_383), vec![0xFF, 0x7F]);
+        assert_eq!(encoded(321), vec![0xC1, 0x02]);
+    }
+
+    #[test]
+    fn test_packets() {
+        let connect = connect_packet("gw", Some("user"), Some("pw"), None);
+        assert_eq!(connect[0], 0x10);
+        assert_eq!(connect[1] as usize, connect.len() - 2);
+        assert_eq!(&connect[2..10], b"\x00\x04MQTT\x04\xC2");
+        assert!(connect.ends_with(b"\x00\x02gw\x00\x04user\x00\x02pw"));
+
+        // A password without a user name is not sent
+        assert_eq!(connect_packet("gw", None, Some("pw"), None)[9], 0x02);
+
+        // The last will follows the client ID
+        let will = MqttMessage {
+            topic: "a/state".to_string(),
+            payload: "offline".to_string(),
+            retain: true,
+        };
+        let connect = connect_packet("gw", None, None, Some(&will));
+        assert_eq!(connect[9], 0x26);
+        assert!(connect.ends_with(b"\x00\x02gw\x00\x07a/state\x00\x07offline"));
+
+        let publish = publish_packet(&MqttMessage {
+            topic: "a/b".to_string(),
+            payload: "hi".to_string(),
+            retain: true,
+        });
+        assert_eq!(publish, b"\x31\x07\x00\x03a/bhi");
+    }
+
+    #[test]
+    fn test_check_connack() {
+        assert!(check_connack(&[0x20, 0x02, 0x00, 0x00]).is_ok());
+        assert!(matches!(
+            check_connack(&[0x20, 0x02, 0x00, 0x04]),
+            Err(MqttError::Refused(_))
+        ));
+        assert!(matches!(
+            check_connack(&[0x30, 0x02, 0x00, 0x00]),
+            Err(MqttError::Protocol)
+        ));
+    }
+
+    #[test]
+    fn test_connection() {
+        let message = MqttMessage {
+            topic: "gigawhisper/state".to_string(),
+            payload: "idle".to_string(),
+            retain: false,
+        };
+        let publish_len = publish_packet(&message).len();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let broker = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut connect = [0u8; 2];
+            stream.read_exact(&mut connect).unwrap();
+            let mut rest = vec![0u8; connect[1] as usize];
+            stream.read_exact(&mut rest).unwrap();
+            stream.write_all(&[0x20, 0x02, 0x00, 0x00]).unwrap();
+
+            // The publish, then a ping to answer
+            let mut published = vec![0u8; publish_len + 2];
+            stream.read_exact(&mut published).unwrap();
+            stream.write_all(&[0xD0, 0x00]).unwrap();
+
+            let mut closed = Vec::new();
+            stream.read_to_end(&mut closed).unwrap();
+            (rest, published, closed)
+        });
+
+        let settings = MqttSettings {
+            enabled: true,
+            host: "127.0.0.1".to_string(),
+            port,
+            ..Default::default()
+        };
+        let mut connection = MqttConnection::open(&settings, None).unwrap();
+        connection.publish(std::slice::from_ref(&message)).unwrap();
+        connection.ping().unwrap();
+        connection.close().unwrap();
+
+        let (connect, published, closed) = broker.join().unwrap();
+        assert!(connect.ends_with(b"\x00\x11gigawhisper/state\x00\x07offline"));
+        let mut expected = publish_packet(&message);
+        expected.extend_from_slice(&[0xC0, 0x00]);
+        assert_eq!(published, expected);
+        let mut expected = publish_packet(&state_message(&settings, "offline"));
+        expected.extend_from_slice(&[0xE0, 0x00]);
+        assert_eq!(closed, expected);
+    }
+}
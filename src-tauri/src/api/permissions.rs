@@ -0,0 +1,407 @@
+//! Client Permissions
+//!
+//! Registered API clients and the scopes they were granted. Tokens are
+//! shown once when issued; only their SHA-256 hash is stored.
+
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+/// Prefix of issued tokens, to recognize them in config files and logs
+pub const TOKEN_PREFIX: &str = "gw_";
+
+/// Maximum length of a client name
+pub const MAX_CLIENT_NAME_LEN: usize = 64;
+
+/// Maximum number of registered clients
+pub const MAX_CLIENTS: usize = 32;
+
+/// Minimum time between saves that only record a client's last call
+const LAST_USED_SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Global client registry
+static CLIENT_REGISTRY: OnceLock<RwLock<ClientRegistry>> = OnceLock::new();
+
+/// Permission errors
+#[derive(Debug, Error)]
+pub enum PermissionError {
+    #[error("Invalid or revoked token")]
+    InvalidToken,
+
+    #[error("Client lacks the '{0}' scope")]
+    MissingScope(ApiScope),
+
+    #[error("Unknown client: {0}")]
+    UnknownClient(String),
+
+    #[error("Invalid client: {0}")]
+    InvalidClient(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// What a client may do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiScope {
+    /// Read transcription history
+    ReadHistory,
+    /// Start and stop recordings
+    TriggerRecording,
+    /// Read recorded audio
+    ReadAudio,
+}
+
+impl std::fmt::Display for ApiScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::ReadHistory => "read-history",
+            Self::TriggerRecording => "trigger-recording",
+            Self::ReadAudio => "read-audio",
+        })
+    }
+}
+
+/// Registered client, as stored on disk
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiClient {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// SHA-256 of the token (hex)
+    token_hash: String,
+    /// When the client was registered (ISO 8601)
+    pub created_at: String,
+    /// Last authorized call (ISO 8601)
+    #[serde(default)]
+    pub last_used_at: Option<String>,
+}
+
+/// Client as shown in the UI, without its token hash
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ApiClientInfo {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+impl From<&ApiClient> for ApiClientInfo {
+    fn from(client: &ApiClient) -> Self {
+        Self {
+            id: client.id.clone(),
+            name: client.name.clone(),
+            scopes: client.scopes.clone(),
+            created_at: client.created_at.clone(),
+            last_used_at: client.last_used_at.clone(),
+        }
+    }
+}
+
+/// Newly issued token, returned once
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct IssuedToken {
+    pub client: ApiClientInfo,
+    pub token: String,
+}
+
+/// Registered API clients
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientRegistry {
+    clients: Vec<ApiClient>,
+    /// When the last call of each client was last saved
+    #[serde(skip)]
+    last_used_saved: HashMap<String, Instant>,
+}
+
+impl ClientRegistry {
+    /// Load the registry from disk
+    pub fn load() -> Self {
+        let path = clients_file_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(registry) => return registry,
+                    Err(e) => tracing::warn!("Failed to parse API clients file: {}", e),
+                },
+                Err(e) => tracing::warn!("Failed to read API clients file: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    /// Save the registry to disk
+    pub fn save(&self) -> Result<(), PermissionError> {
+        let path = clients_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, content)?;
+        Ok(())
+    }
+
+    /// Registered clients, oldest first
+    pub fn clients(&self) -> Vec<ApiClientInfo> {
+        self.clients.iter().map(ApiClientInfo::from).collect()
+    }
+
+    /// Register a client and issue its token
+    pub fn register(
+        &mut self,
+        name: &str,
+        scopes: Vec<ApiScope>,
+    ) -> Result<IssuedToken, PermissionError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_CLIENT_NAME_LEN {
+            return Err(PermissionError::InvalidClient(format!(
+                "name must be 1 to {} characters",
+                MAX_CLIENT_NAME_LEN
+            )));
+        }
+        if self.clients.iter().any(|c| c.name.eq_ignore_ascii_case(name)) {
+            return Err(PermissionError::InvalidClient(format!(
+                "a client named '{}' already exists",
+                name
+            )));
+        }
+        if self.clients.len() >= MAX_CLIENTS {
+            return Err(PermissionError::InvalidClient(format!(
+                "at most {} clients can be registered",
+                MAX_CLIENTS
+            )));
+        }
+
+        let token = generate_token();
+        let client = ApiClient {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            scopes: normalize_scopes(scopes)?,
+            token_hash: hash_token(&token),
+            created_at: crate::history::chrono_timestamp(),
+            last_used_at: None,
+        };
+        let issued = IssuedToken {
+            client: ApiClientInfo::from(&client),
+            token,
+        };
+        self.clients.push(client);
+        Ok(issued)
+    }
+
+    /// Replace the scopes of a client
+    pub fn set_scopes(
+        &mut self,
+        id: &str,
+        scopes: Vec<ApiScope>,
+    ) -> Result<ApiClientInfo, PermissionError> {
+        let scopes = normalize_scopes(scopes)?;
+        let client = self.client_mut(id)?;
+        client.scopes = scopes;
+        Ok(ApiClientInfo::from(&*client))
+    }
+
+    /// Issue a new token for a client, invalidating the old one
+    pub fn rotate_token(&mut self, id: &str) -> Result<IssuedToken, PermissionError> {
+        let token = generate_token();
+        let client = self.client_mut(id)?;
+        client.token_hash = hash_token(&token);
+        Ok(IssuedToken {
+            client: ApiClientInfo::from(&*client),
+            token,
+        })
+    }
+
+    /// Remove a client, revoking its token
+    pub fn remove(&mut self, id: &str) -> bool {
+        let before = self.clients.len();
+        self.clients.retain(|c| c.id != id);
+        self.last_used_saved.remove(id);
+        self.clients.len() != before
+    }
+
+    /// Find the client owning `token` and check it was granted `scope`
+    ///
+    /// Records the call time on success; `last_used_save_due` says when it
+    /// should be saved.
+    pub fn authorize(
+        &mut self,
+        token: &str,
+        scope: ApiScope,
+    ) -> Result<ApiClientInfo, PermissionError> {
+        let hash = hash_token(token);
+        let client = self
+            .clients
+            .iter_mut()
+            .find(|c| constant_time_eq(c.token_hash.as_bytes(), hash.as_bytes()))
+            .ok_or(PermissionError::InvalidToken)?;
+        if !client.scopes.contains(&scope) {
+            return Err(PermissionError::MissingScope(scope));
+        }
+        client.last_used_at = Some(crate::history::chrono_timestamp());
+        Ok(ApiClientInfo::from(&*client))
+    }
+
+    /// Whether the last call of client `id` should be saved now, at most once a minute
+    pub fn last_used_save_due(&mut self, id: &str) -> bool {
+        let now = Instant::now();
+        match self.last_used_saved.get(id) {
+            Some(saved) if now.duration_since(*saved) < LAST_USED_SAVE_INTERVAL => false,
+            _ => {
+                self.last_used_saved.insert(id.to_string(), now);
+                true
+            }
+        }
+    }
+
+    fn client_mut(&mut self, id: &str) -> Result<&mut ApiClient, PermissionError> {
+        self.clients
+            .iter_mut()
+            .find(|c| c.id == id)
+            .ok_or_else(|| PermissionError::UnknownClient(id.to_string()))
+    }
+}
+
+/// Deduplicate scopes, rejecting an empty list
+fn normalize_scopes(scopes: Vec<ApiScope>) -> Result<Vec<ApiScope>, PermissionError> {
+    let mut unique = Vec::with_capacity(scopes.len());
+    for scope in scopes {
+        if !unique.contains(&scope) {
+            unique.push(scope);
+        }
+    }
+    if unique.is_empty() {
+        return Err(PermissionError::InvalidClient(
+            "at least one scope is required".to_string(),
+        ));
+    }
+    Ok(unique)
+}
+
+/// Random token (about 240 bits of entropy)
+fn generate_token() -> String {
+    format!(
+        "{}{}{}",
+        TOKEN_PREFIX,
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+fn hash_token(token: &str) -> String {
+    format!("{:x}", Sha256::digest(token.as_bytes()))
+}
+
+/// Compare without exiting at the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Get the API clients file path
+fn clients_file_path() -> PathBuf {
+    crate::config::data_dir().join("api_clients.json")
+}
+
+/// Get or initialize the global client registry
+pub fn get_client_registry() -> &'static RwLock<ClientRegistry> {
+    CLIENT_REGISTRY.get_or_init(|| RwLock::new(ClientRegistry::load()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_checks_token_and_scope() {
+        let mut registry = ClientRegistry::default();
+        let issued = registry
+            .register("Stream Deck", vec![ApiScope::TriggerRecording])
+            .unwrap();
+        assert!(issued.token.starts_with(TOKEN_PREFIX));
+
+        let client = registry
+            .authorize(&issued.token, ApiScope::TriggerRecording)
+            .unwrap();
+        assert_eq!(client.name, "Stream Deck");
+        assert!(client.last_used_at.is_some());
+
+        assert!(matches!(
+            registry.authorize(&issued.token, ApiScope::ReadAudio),
+            Err(PermissionError::MissingScope(ApiScope::ReadAudio))
+        ));
+        assert!(matches!(
+            registry.authorize("gw_wrong", ApiScope::TriggerRecording),
+            Err(PermissionError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_last_used_save_is_throttled() {
+        let mut registry = ClientRegistry::default();
+        let first = registry
+            .register("Stream Deck", vec![ApiScope::ReadHistory])
+            .unwrap();
+        let second = registry
+            .register("Plugin", vec![ApiScope::ReadHistory])
+            .unwrap();
+
+        assert!(registry.last_used_save_due(&first.client.id));
+        assert!(!registry.last_used_save_due(&first.client.id));
+        assert!(registry.last_used_save_due(&second.client.id));
+
+        let saved = registry.last_used_saved.get_mut(&first.client.id).unwrap();
+        *saved -= LAST_USED_SAVE_INTERVAL;
+        assert!(registry.last_used_save_due(&first.client.id));
+    }
+
+    #[test]
+    fn test_rotate_and_remove_revoke_tokens() {
+        let mut registry = ClientRegistry::default();
+        let issued = registry.register("Plugin", vec![ApiScope::ReadHistory]).unwrap();
+        let id = issued.client.id.clone();
+
+        let rotated = registry.rotate_token(&id).unwrap();
+        assert_ne!(rotated.token, issued.token);
+        assert!(registry.authorize(&issued.token, ApiScope::ReadHistory).is_err());
+        assert!(registry.authorize(&rotated.token, ApiScope::ReadHistory).is_ok());
+
+        assert!(registry.remove(&id));
+        assert!(!registry.remove(&id));
+        assert!(registry.authorize(&rotated.token, ApiScope::ReadHistory).is_err());
+    }
+
+    #[test]
+    fn test_register_validation() {
+        let mut registry = ClientRegistry::default();
+        assert!(registry.register("  ", vec![ApiScope::ReadHistory]).is_err());
+        assert!(registry.register("Script", Vec::new()).is_err());
+
+        let issued = registry
+            .register("Script", vec![ApiScope::ReadHistory, ApiScope::ReadHistory])
+            .unwrap();
+        assert_eq!(issued.client.scopes, vec![ApiScope::ReadHistory]);
+        assert!(registry.register("script", vec![ApiScope::ReadAudio]).is_err());
+
+        // Token hashes never leave the registry
+        let json = serde_json::to_string(&registry.clients()).unwrap();
+        assert!(!json.contains("token_hash"));
+    }
+
+    #[test]
+    fn test_scope_names() {
+        assert_eq!(
+            serde_json::to_string(&ApiScope::TriggerRecording).unwrap(),
+            "\"trigger-recording\""
+        );
+        assert_eq!(ApiScope::ReadAudio.to_string(), "read-audio");
+    }
+}
@@ -0,0 +1,640 @@
+//! Local API Server
+//!
+//! Opt-in HTTP server on 127.0.0.1 letting external tools (Stream Deck,
+//! scripts) drive GigaWhisper. Every call is authorized with an API client
+//! token and audited (see `authorize_call`):
+//!
+//! - `POST /recording/start[?profile=<id>][&translate=true|false]` starts
+//!   recording, optionally overriding translation to English (trigger-recording)
+//! - `POST /recording/stop` stops and returns the transcription, or a null
+//!   text for an incognito recording (trigger-recording)
+//! - `GET /transcription/latest` returns the last transcription (read-history)
+//! - `GET /events` upgrades to a websocket streaming `ApiEvent`s as JSON (read-history)
+//!
+//! Clients send the token as `Authorization: Bearer <token>`, or as a
+//! `token` query parameter where headers can't be set (browser websockets).
+
+use super::{authorize_call, latest_transcription, subscribe_events, ApiScope, PermissionError};
+use crate::config::ApiServerSettings;
+use parking_lot::Mutex;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::TryRecvError;
+use tungstenite::protocol::Role;
+use tungstenite::{Message, WebSocket};
+
+/// Largest request head accepted
+const MAX_HEAD_BYTES: u64 = 16 * 1024;
+
+/// Time a client has to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a websocket connection checks for client frames and events
+const EVENT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Connections handled at once, event streams included; more are refused
+const MAX_CONNECTIONS: usize = 64;
+
+/// Running server, if enabled
+static SERVER: Mutex<Option<RunningServer>> = Mutex::new(None);
+
+/// API server errors
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Malformed request: {0}")]
+    BadRequest(String),
+}
+
+/// Head of an HTTP request
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    /// Decoded query parameters
+    pub query: Vec<(String, String)>,
+    /// Headers, with lowercase names
+    pub headers: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    /// Value of a header (`name` in lowercase)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Value of a query parameter
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Client token from the Authorization header or the `token` parameter
+    pub fn token(&self) -> Option<&str> {
+        self.header("authorization")
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim)
+            .or_else(|| self.query_param("token"))
+    }
+
+    /// Whether the client asks for a websocket
+    pub fn is_websocket_upgrade(&self) -> bool {
+        self.header("upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+            && self.header("sec-websocket-key").is_some()
+    }
+}
+
+/// Decode `%XX` escapes and `+` in a query string component
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+                match u8::from_str_radix(hex, 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Read a request head (request line and headers)
+///
+/// Request bodies are not used by any endpoint and are left unread.
+pub fn parse_request(reader: &mut impl BufRead) -> Result<HttpRequest, ServerError> {
+    let mut reader = reader.take(MAX_HEAD_BYTES);
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ServerError::BadRequest("invalid request line".to_string()));
+    };
+    if !version.starts_with("HTTP/1.") {
+        return Err(ServerError::BadRequest(format!(
+            "unsupported version {}",
+            version
+        )));
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut request = HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (percent_decode(name), percent_decode(value))
+            })
+            .collect(),
+        headers: Vec::new(),
+    };
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Err(ServerError::BadRequest(
+                "incomplete request head".to_string(),
+            ));
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            return Ok(request);
+        }
+        let (name, value) = header
+            .split_once(':')
+            .ok_or_else(|| ServerError::BadRequest(format!("invalid header {}", header)))?;
+        request
+            .headers
+            .push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+}
+
+/// API endpoint
+#[derive(Debug, Clone, PartialEq)]
+pub enum Route {
+    StartRecording {
+        profile: Option<String>,
+        translate: Option<bool>,
+    },
+    StopRecording,
+    LatestTranscription,
+    Events,
+}
+
+impl Route {
+    /// Scope a client needs to call the endpoint
+    pub fn scope(&self) -> ApiScope {
+        match self {
+            Self::StartRecording { .. } | Self::StopRecording => ApiScope::TriggerRecording,
+            Self::LatestTranscription | Self::Events => ApiScope::ReadHistory,
+        }
+    }
+
+    /// Action recorded in the audit log
+    pub fn action(&self) -> &'static str {
+        match self {
+            Self::StartRecording { .. } => "start_recording",
+            Self::StopRecording => "stop_recording",
+            Self::LatestTranscription => "latest_transcription",
+            Self::Events => "events",
+        }
+    }
+}
+
+/// Endpoint of a request, or the HTTP status refusing it
+pub fn route(request: &HttpRequest) -> Result<Route, u16> {
+    let route = match request.path.as_str() {
+        "/recording/start" => Route::StartRecording {
+            profile: request
+                .query_param("profile")
+                .filter(|p| !p.is_empty())
+                .map(str::to_string),
+            translate: request
+                .query_param("translate")
+                .map(str::parse)
+                .transpose()
+                .map_err(|_| 400u16)?,
+        },
+        "/recording/stop" => Route::StopRecording,
+        "/transcription/latest" => Route::LatestTranscription,
+        "/events" => Route::Events,
+        _ => return Err(404),
+    };
+
+    let method = match route {
+        Route::StartRecording { .. } | Route::StopRecording => "POST",
+        Route::LatestTranscription | Route::Events => "GET",
+    };
+    if request.method != method {
+        return Err(405);
+    }
+    Ok(route)
+}
+
+/// HTTP response with a JSON body; the connection is closed after it
+pub fn json_response(status: u16, body: &serde_json::Value) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+fn error_response(status: u16, message: &str) -> Vec<u8> {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
+
+/// One of the `MAX_CONNECTIONS` connections, released when dropped
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    /// Take a slot from the `open` count, None when all are in use
+    fn acquire(open: &Arc<AtomicUsize>) -> Option<Self> {
+        open.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+            (n < MAX_CONNECTIONS).then_some(n + 1)
+        })
+        .ok()
+        .map(|_| Self(open.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Listener thread of the running server
+struct RunningServer {
+    port: u16,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl RunningServer {
+    fn stop(self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect((Ipv4Addr::LOCALHOST, self.port));
+        tracing::info!("API server on port {} stopped", self.port);
+    }
+}
+
+/// Start, stop or move the server to match the settings
+pub fn apply_api_server_settings(app: &AppHandle, settings: &ApiServerSettings) {
+    let mut server = SERVER.lock();
+
+    let wanted = settings.enabled.then_some(settings.port);
+    if server.as_ref().map(|s| s.port) == wanted {
+        return;
+    }
+    if let Some(running) = server.take() {
+        running.stop();
+    }
+
+    if let Some(port) = wanted {
+        match start(app, port) {
+            Ok(running) => *server = Some(running),
+            Err(e) => tracing::error!("Failed to start API server on port {}: {}", port, e),
+        }
+    }
+}
+
+fn start(app: &AppHandle, port: u16) -> Result<RunningServer, ServerError> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    let shutdown = Arc::new(AtomicBool::new(false));
+
+    let app_handle = app.clone();
+    let stop = shutdown.clone();
+    let tasks = app.state::<crate::AppState>().tasks.clone();
+    let connections = tasks.clone();
+    let open = Arc::new(AtomicUsize::new(0));
+    tasks.spawn_thread("api-server", move || {
+        for stream in listener.incoming() {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            match stream {
+                Ok(mut stream) => {
+                    let Some(slot) = ConnectionSlot::acquire(&open) else {
+                        tracing::warn!("API server busy, refusing connection");
+                        let _ = stream.set_nonblocking(true);
+                        let _ = stream.write_all(&error_response(503, "Too many connections"));
+                        continue;
+                    };
+                    let app = app_handle.clone();
+                    let stop = stop.clone();
+                    connections.spawn_thread("api-connection", move || {
+                        let _slot = slot;
+                        handle_connection(&app, stream, &stop)
+                    });
+                }
+                Err(e) => tracing::warn!("API server connection failed: {}", e),
+            }
+        }
+    });
+
+    tracing::info!("API server listening on 127.0.0.1:{}", port);
+    Ok(RunningServer { port, shutdown })
+}
+
+fn handle_connection(app: &AppHandle, mut stream: TcpStream, shutdown: &AtomicBool) {
+    if shutdown.load(Ordering::SeqCst) {
+        return;
+    }
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+
+    let request = match parse_request(&mut BufReader::new(&stream)) {
+        Ok(request) => request,
+        Err(e) => {
+            let _ = stream.write_all(&error_response(400, &e.to_string()));
+            return;
+        }
+    };
+
+    let route = match route(&request) {
+        Ok(route) => route,
+        Err(status) => {
+            let message = if status == 400 {
+                "Invalid query parameter"
+            } else {
+                "No such endpoint"
+            };
+            let _ = stream.write_all(&error_response(status, message));
+            return;
+        }
+    };
+
+    let token = request.token().unwrap_or_default();
+    if let Err(e) = authorize_call(token, route.scope(), route.action()) {
+        let status = match e {
+            PermissionError::MissingScope(_) => 403,
+            _ => 401,
+        };
+        let _ = stream.write_all(&error_response(status, &e.to_string()));
+        return;
+    }
+
+    let response = match route {
+        Route::StartRecording { profile, translate } => {
+            match tauri::async_runtime::block_on(crate::shortcuts::start_recording_internal(
+                app, profile, false, None, translate,
+            )) {
+                Ok(()) => json_response(200, &serde_json::json!({ "recording": true })),
+                Err(e) => error_response(409, &e),
+            }
+        }
+        Route::StopRecording => {
+            // Incognito text is not shared, as for the event stream
+            let incognito = *app.state::<crate::AppState>().incognito.read();
+            match tauri::async_runtime::block_on(crate::shortcuts::stop_recording_internal(app)) {
+                Ok(text) => {
+                    let text = (!incognito).then_some(text);
+                    json_response(200, &serde_json::json!({ "text": text }))
+                }
+                Err(e) => error_response(409, &e),
+            }
+        }
+        Route::LatestTranscription => match latest_transcription() {
+            Some(latest) => json_response(200, &serde_json::json!(latest)),
+            None => error_response(404, "No transcription yet"),
+        },
+        Route::Events => {
+            stream_events(stream, &request, shutdown);
+            return;
+        }
+    };
+    let _ = stream.write_all(&response);
+}
+
+/// Upgrade to a websocket and forward events until the client leaves
+///
+/// Client frames are read between events so pings are answered and a close
+/// from the client ends the stream.
+fn stream_events(mut stream: TcpStream, request: &HttpRequest, shutdown: &AtomicBool) {
+    let Some(key) = request
+        .header("sec-websocket-key")
+        .filter(|_| request.is_websocket_upgrade())
+    else {
+        let _ = stream.write_all(&error_response(400, "Websocket upgrade required"));
+        return;
+    };
+
+    let accept = tungstenite::handshake::derive_accept_key(key.as_bytes());
+    let handshake = format!(
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+        accept
+    );
+    if stream.write_all(handshake.as_bytes()).is_err() {
+        return;
+    }
+    let _ = stream.set_read_timeout(Some(EVENT_POLL_INTERVAL));
+
+    let mut socket = WebSocket::from_raw_socket(stream, Role::Server, None);
+    let mut events = subscribe_events();
+    while !shutdown.load(Ordering::SeqCst) {
+        // Pongs and close replies are queued by `read` and sent by the next
+        // read or write; a closed connection then ends the loop
+        match socket.read() {
+            Ok(_) => {}
+            Err(tungstenite::Error::Io(e))
+                if matches!(
+                    e.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) => {}
+            Err(_) => return,
+        }
+
+        loop {
+            let event = match events.try_recv() {
+                Ok(event) => event,
+                Err(TryRecvError::Lagged(missed)) => {
+                    tracing::warn!("API event client missed {} events", missed);
+                    continue;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Closed) => return,
+            };
+            let Ok(json) = serde_json::to_string(&event) else {
+                continue;
+            };
+            if socket.send(Message::text(json)).is_err() {
+                return;
+            }
+        }
+    }
+    let _ = socket.close(None);
+    let _ = socket.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(raw: &str) -> Result<HttpRequest, ServerError> {
+        parse_request(&mut raw.as_bytes())
+    }
+
+    #[test]
+    fn test_parse_request() {
+        let request = parse(
+            "POST /recording/start?profile=team%20sync&x HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer gw_abc\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/recording/start");
+        assert_eq!(request.query_param("profile"), Some("team sync"));
+        assert_eq!(request.query_param("x"), Some(""));
+        assert_eq!(request.header("host"), Some("localhost"));
+        assert_eq!(request.token(), Some("gw_abc"));
+    }
+
+    #[test]
+    fn test_parse_request_rejects_malformed() {
+        assert!(parse("GARBAGE\r\n\r\n").is_err());
+        assert!(parse("GET / SPDY/3\r\n\r\n").is_err());
+        assert!(parse("GET / HTTP/1.1\r\nNo colon here\r\n\r\n").is_err());
+        assert!(parse("GET / HTTP/1.1\r\nHost: x\r\n").is_err());
+    }
+
+    #[test]
+    fn test_percent_decode() {
+        assert_eq!(percent_decode("a%2Fb+c"), "a/b c");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%zz"), "%zz");
+        assert_eq!(percent_decode("caf%C3%A9"), "café");
+    }
+
+    #[test]
+    fn test_websocket_token_from_query() {
+        let request = parse(
+            "GET /events?token=gw_q HTTP/1.1\r\nUpgrade: websocket\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\r\n",
+        )
+        .unwrap();
+        assert!(request.is_websocket_upgrade());
+        assert_eq!(request.token(), Some("gw_q"));
+        assert_eq!(route(&request), Ok(Route::Events));
+    }
+
+    #[test]
+    fn test_route() {
+        let request = |method: &str, target: &str| {
+            parse(&format!("{} {} HTTP/1.1\r\n\r\n", method, target)).unwrap()
+        };
+
+        assert_eq!(
+            route(&request("POST", "/recording/start?profile=meeting")),
+            Ok(Route::StartRecording {
+                profile: Some("meeting".to_string()),
+                translate: None,
+            })
+        );
+        assert_eq!(
+            route(&request("POST", "/recording/start")),
+            Ok(Route::StartRecording {
+                profile: None,
+                translate: None,
+            })
+        );
+        assert_eq!(
+            route(&request("POST", "/recording/start?translate=true")),
+            Ok(Route::StartRecording {
+                profile: None,
+                translate: Some(true),
+            })
+        );
+        assert_eq!(
+            route(&request("POST", "/recording/start?translate=yes")),
+            Err(400)
+        );
+        assert_eq!(route(&request("GET", "/recording/stop")), Err(405));
+        assert_eq!(route(&request("GET", "/nope")), Err(404));
+        assert_eq!(
+            route(&request("GET", "/transcription/latest"))
+                .unwrap()
+                .scope(),
+            ApiScope::ReadHistory
+        );
+        assert_eq!(Route::StopRecording.scope(), ApiScope::TriggerRecording);
+    }
+
+    #[test]
+    fn test_connection_slots_are_capped() {
+        let open = Arc::new(AtomicUsize::new(0));
+        let mut slots: Vec<_> = (0..MAX_CONNECTIONS)
+            .map(|_| ConnectionSlot::acquire(&open).unwrap())
+            .collect();
+        assert!(ConnectionSlot::acquire(&open).is_none());
+
+        slots.pop();
+        assert_eq!(open.load(Ordering::SeqCst), MAX_CONNECTIONS - 1);
+        assert!(ConnectionSlot::acquire(&open).is_some());
+        drop(slots);
+        assert_eq!(open.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_json_response() {
+        let response = json_response(404, &serde_json::json!({ "error": "x" }));
+        let response = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+        assert!(response.contains("Content-Length: 13\r\n"));
+        assert!(response.ends_with("\r\n\r\n{\"error\":\"x\"}"));
+    }
+
+    #[test]
+    fn test_event_stream_answers_ping_and_close() {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let request = parse_request(&mut BufReader::new(&stream)).unwrap();
+            stream_events(stream, &request, &AtomicBool::new(false));
+        });
+
+        let stream = TcpStream::connect((Ipv4Addr::LOCALHOST, port)).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let url = format!("ws://127.0.0.1:{}/events", port);
+        let (mut socket, _) = tungstenite::client(url.as_str(), stream).unwrap();
+
+        socket.send(Message::Ping("hi".into())).unwrap();
+        loop {
+            match socket.read().unwrap() {
+                Message::Pong(payload) => {
+                    assert_eq!(payload.as_ref(), b"hi");
+                    break;
+                }
+                Message::Text(_) => continue,
+                other => panic!("unexpected frame: {:?}", other),
+            }
+        }
+
+        socket.close(None).unwrap();
+        while socket.read().is_ok() {}
+        // The server leaves the stream once the client closed
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+//! VAD Calibration
+//!
+//! Analyze an ambient-noise sample and a speech sample from the same device
+//! and derive VAD settings suited to that microphone and room.
+
+use super::calculate_rms;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Analysis window length in milliseconds
+const WINDOW_MS: u32 = 20;
+
+/// Level reported for digital silence (dBFS)
+const SILENCE_DB: f32 = -100.0;
+
+/// Minimum speech/noise separation for a usable calibration (dB)
+pub const MIN_SNR_DB: f32 = 3.0;
+
+/// Calibration analysis result
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CalibrationReport {
+    /// Ambient noise level (90th percentile of silence windows, dBFS)
+    pub noise_floor_db: f32,
+    /// Typical speech level (75th percentile of speech windows, dBFS)
+    pub speech_level_db: f32,
+    /// Speech to noise separation in dB
+    pub snr_db: f32,
+    /// Recommended VAD aggressiveness (0-3)
+    pub aggressiveness: u8,
+    /// Recommended minimum speech duration in ms
+    pub min_speech_duration_ms: u32,
+    /// Recommended energy gate: recordings whose loudest window stays below are dropped
+    pub energy_threshold_db: f32,
+}
+
+/// Calibration errors
+#[derive(Debug, thiserror::Error)]
+pub enum CalibrationError {
+    #[error("Calibration sample is too short")]
+    TooShort,
+
+    #[error("Speech is not distinguishable from background noise ({snr_db:.1} dB). Is the microphone muted?")]
+    SpeechNotDetected { snr_db: f32 },
+}
+
+/// RMS level of each analysis window in dBFS
+pub fn window_levels_db(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    let window = (sample_rate * WINDOW_MS / 1000).max(1) as usize;
+    samples
+        .chunks_exact(window)
+        .map(|chunk| to_db(calculate_rms(chunk)))
+        .collect()
+}
+
+/// Level of the loudest analysis window in dBFS
+pub fn peak_window_db(samples: &[f32], sample_rate: u32) -> f32 {
+    window_levels_db(samples, sample_rate)
+        .into_iter()
+        .fold(SILENCE_DB, f32::max)
+}
+
+/// Analyze ambient silence and a speech sample recorded on the same device
+pub fn analyze_calibration(
+    silence: &[f32],
+    speech: &[f32],
+    sample_rate: u32,
+) -> Result<CalibrationReport, CalibrationError> {
+    let mut noise_levels = window_levels_db(silence, sample_rate);
+    let mut speech_levels = window_levels_db(speech, sample_rate);
+
+    if noise_levels.len() < 10 || speech_levels.len() < 10 {
+        return Err(CalibrationError::TooShort);
+    }
+
+    let noise_floor_db = percentile(&mut noise_levels, 0.9);
+    let speech_level_db = percentile(&mut speech_levels, 0.75);
+    let snr_db = speech_level_db - noise_floor_db;
+
+    if snr_db < MIN_SNR_DB {
+        return Err(CalibrationError::SpeechNotDetected { snr_db });
+    }
+
+    // Noisy rooms need stricter filtering, clean signals can keep soft speech
+    let (aggressiveness, min_speech_duration_ms) = if snr_db < 10.0 {
+        (3, 250)
+    } else if snr_db < 20.0 {
+        (2, 150)
+    } else if snr_db < 30.0 {
+        (1, 100)
+    } else {
+        (0, 100)
+    };
+
+    // Gate a third of the way from the noise floor towards speech
+    let energy_threshold_db = noise_floor_db + (snr_db / 3.0).max(MIN_SNR_DB);
+
+    Ok(CalibrationReport {
+        noise_floor_db,
+        speech_level_db,
+        snr_db,
+        aggressiveness,
+        min_speech_duration_ms,
+        energy_threshold_db,
+    })
+}
+
+/// Convert a linear amplitude to dBFS
+pub(crate) fn to_db(rms: f32) -> f32 {
+    if rms <= 0.0 {
+        SILENCE_DB
+    } else {
+        (20.0 * rms.log10()).max(SILENCE_DB)
+    }
+}
+
+fn percentile(values: &mut [f32], p: f32) -> f32 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let index = ((values.len() - 1) as f32 * p).round() as usize;
+    values[index]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const RATE: u32 = 16000;
+
+    fn tone(amplitude: f32, seconds: f32) -> Vec<f32> {
+        let n = (RATE as f32 * seconds) as usize;
+        (0..n)
+            .map(|i| amplitude * (2.0 * PI * 220.0 * i as f32 / RATE as f32).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_window_levels_db() {
+        let levels = window_levels_db(&vec![0.0; 3200], RATE);
+        assert_eq!(levels.len(), 10);
+        assert!(levels.iter().all(|&l| l == SILENCE_DB));
+    }
+
+    #[test]
+    fn test_peak_window_db() {
+        let mut samples = vec![0.0; 16000];
+        samples.extend(tone(0.5, 0.1));
+        let peak = peak_window_db(&samples, RATE);
+        // 0.5 amplitude sine -> RMS ~0.354 -> ~-9 dBFS
+        assert!((peak + 9.0).abs() < 0.5, "peak was {}", peak);
+    }
+
+    #[test]
+    fn test_clean_signal_recommends_gentle_vad() {
+        let report = analyze_calibration(&tone(0.001, 1.0), &tone(0.3, 1.0), RATE).unwrap();
+        assert!(report.snr_db > 30.0);
+        assert_eq!(report.aggressiveness, 0);
+        assert!(report.energy_threshold_db > report.noise_floor_db);
+        assert!(report.energy_threshold_db < report.speech_level_db);
+    }
+
+    #[test]
+    fn test_noisy_signal_recommends_aggressive_vad() {
+        let report = analyze_calibration(&tone(0.05, 1.0), &tone(0.1, 1.0), RATE).unwrap();
+        assert_eq!(report.aggressiveness, 3);
+        assert_eq!(report.min_speech_duration_ms, 250);
+    }
+
+    #[test]
+    fn test_muted_microphone_is_rejected() {
+        let result = analyze_calibration(&tone(0.01, 1.0), &tone(0.01, 1.0), RATE);
+        assert!(matches!(result, Err(CalibrationError::SpeechNotDetected { .. })));
+    }
+
+    #[test]
+    fn test_too_short_is_rejected() {
+        let result = analyze_calibration(&tone(0.01, 0.05), &tone(0.3, 1.0), RATE);
+        assert!(matches!(result, Err(CalibrationError::TooShort)));
+    }
+}
@@ -7,8 +7,22 @@ use parking_lot::Mutex;
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use super::buffer::RingBuffer;
+use super::loopback::system_audio_device;
+use super::virtual_input::{VirtualSource, VirtualStream};
+use crate::config::{AudioSource, CaptureMode};
+use schemars::JsonSchema;
+
+/// Device buffer requested in low-latency mode
+const LOW_LATENCY_BUFFER_MS: u32 = 10;
+
+/// How often a lost device is looked for while recording
+const RECONNECT_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Mode of the last device stream opened
+static LAST_CAPTURE_MODE: Mutex<Option<CaptureModeReport>> = Mutex::new(None);
 
 /// Audio capture configuration
 #[derive(Debug, Clone)]
@@ -19,6 +33,8 @@ pub struct AudioConfig {
     pub channels: u16,
     /// Buffer duration in milliseconds
     pub buffer_duration_ms: u32,
+    /// Device buffer mode
+    pub capture_mode: CaptureMode,
 }
 
 impl Default for AudioConfig {
@@ -27,6 +43,117 @@ impl Default for AudioConfig {
             sample_rate: 16000,
             channels: 1,
             buffer_duration_ms: 100,
+            capture_mode: CaptureMode::Shared,
+        }
+    }
+}
+
+/// Length of the windows audio levels are measured over
+pub const LEVEL_WINDOW_MS: u32 = 50;
+
+/// Audio level of one metering window, as linear amplitudes from 0.0 to 1.0
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize, schemars::JsonSchema)]
+pub struct AudioLevel {
+    /// Root mean square of the window
+    pub rms: f32,
+    /// Largest absolute sample of the window
+    pub peak: f32,
+}
+
+/// Measures captured audio in `LEVEL_WINDOW_MS` windows
+#[derive(Debug)]
+pub struct LevelMeter {
+    window: usize,
+    count: usize,
+    sum_squares: f32,
+    peak: f32,
+}
+
+impl LevelMeter {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            window: (sample_rate * LEVEL_WINDOW_MS / 1000).max(1) as usize,
+            count: 0,
+            sum_squares: 0.0,
+            peak: 0.0,
+        }
+    }
+
+    /// Add samples, returning the level of the last window they complete
+    pub fn push(&mut self, samples: &[f32]) -> Option<AudioLevel> {
+        let mut level = None;
+        for &sample in samples {
+            self.sum_squares += sample * sample;
+            self.peak = self.peak.max(sample.abs());
+            self.count += 1;
+            if self.count == self.window {
+                level = Some(AudioLevel {
+                    rms: (self.sum_squares / self.count as f32).sqrt().min(1.0),
+                    peak: self.peak.min(1.0),
+                });
+                self.count = 0;
+                self.sum_squares = 0.0;
+                self.peak = 0.0;
+            }
+        }
+        level
+    }
+}
+
+/// Meter live capture frames (see `AudioCapture::subscribe_frames`) until
+/// the capture stops, calling `on_level` for each window
+pub fn run_level_meter(
+    frames: mpsc::Receiver<Vec<f32>>,
+    sample_rate: u32,
+    mut on_level: impl FnMut(AudioLevel),
+) {
+    let mut meter = LevelMeter::new(sample_rate);
+    while let Ok(chunk) = frames.recv() {
+        if let Some(level) = meter.push(&chunk) {
+            on_level(level);
+        }
+    }
+}
+
+/// Buffer mode a device stream was opened with, for diagnostics
+#[derive(Debug, Clone, PartialEq, serde::Serialize, JsonSchema)]
+pub struct CaptureModeReport {
+    /// Mode asked for in the settings
+    pub requested: CaptureMode,
+    /// Mode the stream runs in
+    pub active: CaptureMode,
+    /// Device buffer size, when fixed
+    pub buffer_frames: Option<u32>,
+    /// Device buffer latency, when fixed
+    pub buffer_ms: Option<f32>,
+    /// Why low-latency mode fell back to shared mode
+    pub fallback_reason: Option<String>,
+}
+
+/// Buffer mode of the last device stream opened
+pub fn last_capture_mode() -> Option<CaptureModeReport> {
+    LAST_CAPTURE_MODE.lock().clone()
+}
+
+/// Device buffer size for `mode`, within what the device supports
+///
+/// Exclusive WASAPI streams are not available through cpal, so low-latency
+/// mode asks for the smallest practical buffer in shared mode instead (and
+/// a small period on ALSA and CoreAudio).
+fn buffer_size_for(
+    mode: CaptureMode,
+    sample_rate: u32,
+    supported: &cpal::SupportedBufferSize,
+) -> cpal::BufferSize {
+    match mode {
+        CaptureMode::Shared => cpal::BufferSize::Default,
+        CaptureMode::LowLatency => {
+            let frames = sample_rate * LOW_LATENCY_BUFFER_MS / 1000;
+            let frames = match supported {
+                cpal::SupportedBufferSize::Range { min, max } => frames.clamp(*min, *max),
+                cpal::SupportedBufferSize::Unknown => frames,
+            };
+            cpal::BufferSize::Fixed(frames)
         }
     }
 }
@@ -45,6 +172,73 @@ pub struct StreamError {
     pub is_disconnection: bool,
 }
 
+/// Input device change during a recording (see `AudioCapture::subscribe_device_events`)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum DeviceEvent {
+    /// The device stopped delivering audio; capture is reopened when possible
+    Disconnected { device: String },
+    /// Capture resumed on a device (the same one or the default device)
+    Reconnected { device: String },
+}
+
+/// Shared state a device stream writes into
+#[derive(Clone)]
+struct StreamSinks {
+    buffer: Arc<Mutex<RingBuffer>>,
+    frame_tap: Arc<Mutex<Vec<mpsc::Sender<Vec<f32>>>>>,
+    last_error: Arc<Mutex<Option<StreamError>>>,
+    is_recording: Arc<Mutex<bool>>,
+}
+
+/// Linear resampler for a stream of chunks
+///
+/// Used when a reconnected device runs at another rate than the one the
+/// capture started with, so the buffer keeps a single sample rate.
+#[derive(Debug)]
+struct LinearResampler {
+    /// Input samples per output sample
+    step: f64,
+    /// Position of the next output sample, where 0 is `last`
+    position: f64,
+    /// Last sample of the previous chunk
+    last: f32,
+}
+
+impl LinearResampler {
+    fn new(from_rate: u32, to_rate: u32) -> Self {
+        Self {
+            step: from_rate as f64 / to_rate as f64,
+            position: 1.0,
+            last: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: &[f32]) -> Vec<f32> {
+        if self.step == 1.0 {
+            return input.to_vec();
+        }
+
+        let mut output = Vec::with_capacity((input.len() as f64 / self.step) as usize + 1);
+        while self.position < input.len() as f64 {
+            let index = self.position as usize;
+            let fraction = (self.position - index as f64) as f32;
+            let before = if index == 0 {
+                self.last
+            } else {
+                input[index - 1]
+            };
+            output.push(before + (input[index] - before) * fraction);
+            self.position += self.step;
+        }
+        self.position -= input.len() as f64;
+        if let Some(&last) = input.last() {
+            self.last = last;
+        }
+        output
+    }
+}
+
 /// Audio capture handle (Send + Sync safe)
 ///
 /// All fields are wrapped in thread-safe containers to ensure proper
@@ -61,6 +255,10 @@ pub struct AudioCapture {
     device_sample_rate: u32,
     /// Last stream error (if any)
     last_error: Arc<Mutex<Option<StreamError>>>,
+    /// Live copy of captured mono chunks (see `subscribe_frames`)
+    frame_tap: Arc<Mutex<Vec<mpsc::Sender<Vec<f32>>>>>,
+    /// Device changes while recording (see `subscribe_device_events`)
+    device_events: Arc<Mutex<Vec<mpsc::Sender<DeviceEvent>>>>,
 }
 
 // AudioCapture is now automatically Send + Sync because:
@@ -114,24 +312,77 @@ impl AudioCapture {
         Self::with_device(device, config)
     }
 
-    /// Create audio capture with specific device
-    pub fn with_device(device: cpal::Device, config: AudioConfig) -> Result<Self, AudioError> {
-        let supported_config = device
-            .default_input_config()
-            .map_err(|e| AudioError::ConfigError(e.to_string()))?;
+    /// Create audio capture for a device ID from `list_devices` (None = default device)
+    ///
+    /// Falls back to the default device if the requested one is not available.
+    /// A virtual input (see `VirtualSource::select`) replaces the device when configured.
+    pub fn with_device_id(device_id: Option<&str>, config: AudioConfig) -> Result<Self, AudioError> {
+        if let Some(source) = VirtualSource::select(device_id) {
+            return Self::with_virtual_source(source?, config);
+        }
 
-        let device_sample_rate = supported_config.sample_rate().0;
-        let device_config = cpal::StreamConfig {
-            channels: supported_config.channels(),
-            sample_rate: supported_config.sample_rate(),
-            buffer_size: cpal::BufferSize::Default,
+        let Some(device_id) = device_id else {
+            return Self::new(config);
         };
 
+        let device = find_input_device(device_id).map_err(|_| AudioError::NoHost)?;
+
+        match device {
+            Some(device) => Self::with_device(device, config),
+            None => {
+                tracing::warn!("Input device '{}' not found, using default device", device_id);
+                Self::new(config)
+            }
+        }
+    }
+
+    /// Create audio capture for a source: the input device `device_id`
+    /// (see `with_device_id`) or the system audio output
+    pub fn with_source(
+        source: AudioSource,
+        device_id: Option<&str>,
+        config: AudioConfig,
+    ) -> Result<Self, AudioError> {
+        match source {
+            AudioSource::Microphone => Self::with_device_id(device_id, config),
+            AudioSource::SystemAudio => Self::system_audio(config),
+        }
+    }
+
+    /// Create audio capture of what the computer plays (see `system_audio_device`)
+    pub fn system_audio(config: AudioConfig) -> Result<Self, AudioError> {
+        let (device, loopback) = system_audio_device()?;
         tracing::info!(
-            "Audio device: {}Hz, {} channels",
-            device_sample_rate,
-            supported_config.channels()
+            "Capturing system audio from '{}'{}",
+            device.name().unwrap_or_default(),
+            if loopback { " (loopback)" } else { "" }
         );
+        Self::with_stream_device(device, loopback, config)
+    }
+
+    /// Create audio capture with specific device
+    ///
+    /// If the device is lost while recording, capture is reopened on a device
+    /// with the same name, or the default device, keeping the samples buffered
+    /// so far. Samples from a device with another rate are resampled to the
+    /// rate the capture was created with.
+    pub fn with_device(device: cpal::Device, config: AudioConfig) -> Result<Self, AudioError> {
+        Self::with_stream_device(device, false, config)
+    }
+
+    /// Create audio capture of an input device, or with `loopback` of what
+    /// an output device plays
+    fn with_stream_device(
+        device: cpal::Device,
+        loopback: bool,
+        config: AudioConfig,
+    ) -> Result<Self, AudioError> {
+        let supported_config = stream_config_of(&device, loopback)
+            .map_err(|e| AudioError::ConfigError(e.to_string()))?;
+
+        let device_sample_rate = supported_config.sample_rate().0;
+        let requested_mode = config.capture_mode;
+        let device_name = device.name().ok();
 
         // Calculate buffer size based on config duration
         // buffer_duration_ms is in milliseconds, convert to seconds
@@ -141,88 +392,72 @@ impl AudioCapture {
         let buffer = Arc::new(Mutex::new(RingBuffer::new(buffer_samples)));
         let is_recording = Arc::new(Mutex::new(false));
         let last_error: Arc<Mutex<Option<StreamError>>> = Arc::new(Mutex::new(None));
+        let frame_tap: Arc<Mutex<Vec<mpsc::Sender<Vec<f32>>>>> = Arc::new(Mutex::new(Vec::new()));
+        let device_events: Arc<Mutex<Vec<mpsc::Sender<DeviceEvent>>>> =
+            Arc::new(Mutex::new(Vec::new()));
 
         // Create channel for commands
         let (command_tx, command_rx) = mpsc::channel::<AudioCommand>();
 
         // Clone references for the worker thread
-        let buffer_clone = buffer.clone();
-        let is_recording_clone = is_recording.clone();
-        let last_error_clone = last_error.clone();
-        let channels = device_config.channels as usize;
+        let sinks = StreamSinks {
+            buffer: buffer.clone(),
+            frame_tap: frame_tap.clone(),
+            last_error: last_error.clone(),
+            is_recording: is_recording.clone(),
+        };
+        let device_events_clone = device_events.clone();
 
         // Spawn worker thread that owns the device and stream
         let worker_handle = std::thread::spawn(move || {
             let mut stream: Option<cpal::Stream> = None;
+            // Between a successful start and stop, even while the device is lost
+            let mut capturing = false;
+            // Name of the device lost while capturing
+            let mut lost_device: Option<String> = None;
 
             loop {
-                match command_rx.recv() {
-                    Ok(AudioCommand::Start(ready_tx)) => {
+                // Wake up regularly while capturing to notice a lost device
+                let command = if capturing {
+                    match command_rx.recv_timeout(RECONNECT_INTERVAL) {
+                        Ok(command) => Some(command),
+                        Err(mpsc::RecvTimeoutError::Timeout) => None,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                } else {
+                    match command_rx.recv() {
+                        Ok(command) => Some(command),
+                        Err(_) => break,
+                    }
+                };
+
+                match command {
+                    Some(AudioCommand::Start(ready_tx)) => {
                         if stream.is_some() {
                             let _ = ready_tx.send(()); // Signal ready even if already recording
                             continue; // Already recording
                         }
 
                         // Clear any previous error
-                        *last_error_clone.lock() = None;
-
-                        let buffer_for_callback = buffer_clone.clone();
-                        let channels_for_callback = channels;
-                        let error_for_callback = last_error_clone.clone();
-                        let is_recording_for_error = is_recording_clone.clone();
-
-                        match device.build_input_stream(
-                            &device_config,
-                            move |data: &[f32], _: &cpal::InputCallbackInfo| {
-                                // Convert to mono if stereo
-                                let mono: Vec<f32> = if channels_for_callback > 1 {
-                                    data.chunks(channels_for_callback)
-                                        .map(|chunk| {
-                                            chunk.iter().sum::<f32>() / channels_for_callback as f32
-                                        })
-                                        .collect()
-                                } else {
-                                    data.to_vec()
-                                };
-
-                                let mut buf = buffer_for_callback.lock();
-                                buf.write(&mono);
-                            },
-                            move |err| {
-                                let error_msg = err.to_string();
-                                tracing::error!("Audio stream error: {}", error_msg);
-
-                                // Detect disconnection errors
-                                let is_disconnection = error_msg.contains("disconnected")
-                                    || error_msg.contains("device")
-                                    || error_msg.contains("DeviceNotAvailable")
-                                    || error_msg.contains("lost")
-                                    || error_msg.contains("InvalidDevice");
-
-                                // Store the error
-                                *error_for_callback.lock() = Some(StreamError {
-                                    message: error_msg,
-                                    is_disconnection,
-                                });
-
-                                // Mark as no longer recording on critical errors
-                                if is_disconnection {
-                                    *is_recording_for_error.lock() = false;
-                                }
-                            },
-                            None,
+                        *sinks.last_error.lock() = None;
+
+                        match open_stream(
+                            &device,
+                            loopback,
+                            requested_mode,
+                            device_sample_rate,
+                            &sinks,
                         ) {
                             Ok(s) => {
-                                if s.play().is_ok() {
-                                    *is_recording_clone.lock() = true;
-                                    stream = Some(s);
-                                    tracing::info!("Audio capture started");
-                                }
+                                stream = Some(s);
+                                capturing = true;
+                                lost_device = None;
+                                tracing::info!("Audio capture started");
                             }
                             Err(e) => {
                                 tracing::error!("Failed to build audio stream: {}", e);
-                                *last_error_clone.lock() = Some(StreamError {
-                                    message: e.to_string(),
+                                *sinks.last_error.lock() = Some(StreamError {
+                                    message: e,
                                     is_disconnection: true,
                                 });
                             }
@@ -230,20 +465,154 @@ impl AudioCapture {
                         // Signal that start command has been processed
                         let _ = ready_tx.send(());
                     }
-                    Ok(AudioCommand::Stop(done_tx)) => {
+                    Some(AudioCommand::Stop(done_tx)) => {
                         stream = None; // Drop the stream to stop capture
-                        *is_recording_clone.lock() = false;
+                        capturing = false;
+                        lost_device = None;
+                        *sinks.is_recording.lock() = false;
                         tracing::info!("Audio capture stopped");
                         // Signal that stop command has been processed
                         let _ = done_tx.send(());
                     }
-                    Ok(AudioCommand::Shutdown) | Err(_) => {
-                        drop(stream.take()); // Explicitly drop stream to stop capture
+                    Some(AudioCommand::Shutdown) => break,
+                    None => {
+                        let disconnected = sinks
+                            .last_error
+                            .lock()
+                            .as_ref()
+                            .is_some_and(|e| e.is_disconnection);
+                        if lost_device.is_none() && disconnected {
+                            stream = None;
+                            let device = device_name
+                                .clone()
+                                .unwrap_or_else(|| "unknown device".to_string());
+                            tracing::warn!("Input device '{}' lost, reconnecting", device);
+                            lost_device = Some(device.clone());
+                            send_device_event(
+                                &device_events_clone,
+                                DeviceEvent::Disconnected { device },
+                            );
+                        }
+
+                        if lost_device.is_some() {
+                            if let Some((s, device)) = reconnect(
+                                device_name.as_deref(),
+                                loopback,
+                                requested_mode,
+                                device_sample_rate,
+                                &sinks,
+                            ) {
+                                stream = Some(s);
+                                lost_device = None;
+                                *sinks.last_error.lock() = None;
+                                tracing::info!("Audio capture resumed on '{}'", device);
+                                send_device_event(
+                                    &device_events_clone,
+                                    DeviceEvent::Reconnected { device },
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            drop(stream.take()); // Explicitly drop stream to stop capture
+            *sinks.is_recording.lock() = false;
+        });
+
+        Ok(Self {
+            command_tx: Mutex::new(command_tx),
+            buffer,
+            worker_handle: Mutex::new(Some(worker_handle)),
+            is_recording,
+            config,
+            last_error,
+            frame_tap,
+            device_events,
+            device_sample_rate,
+        })
+    }
+
+    /// Create audio capture fed by a virtual source instead of a microphone
+    ///
+    /// Samples are delivered in 20ms chunks at real-time pace, like a device.
+    pub fn with_virtual_source(
+        source: VirtualSource,
+        config: AudioConfig,
+    ) -> Result<Self, AudioError> {
+        const CHUNK_INTERVAL: Duration = Duration::from_millis(20);
+
+        let device_sample_rate = source.sample_rate();
+        let chunk_len = (device_sample_rate / 50).max(1) as usize;
+        tracing::info!(
+            "Virtual audio input: {} at {}Hz",
+            source_label(&source),
+            device_sample_rate
+        );
+
+        let buffer_seconds = (config.buffer_duration_ms as f64 / 1000.0).max(60.0);
+        let buffer = Arc::new(Mutex::new(RingBuffer::new(
+            (device_sample_rate as f64 * buffer_seconds) as usize,
+        )));
+        let is_recording = Arc::new(Mutex::new(false));
+        let last_error: Arc<Mutex<Option<StreamError>>> = Arc::new(Mutex::new(None));
+        let frame_tap: Arc<Mutex<Vec<mpsc::Sender<Vec<f32>>>>> = Arc::new(Mutex::new(Vec::new()));
+
+        let (command_tx, command_rx) = mpsc::channel::<AudioCommand>();
+
+        let buffer_clone = buffer.clone();
+        let is_recording_clone = is_recording.clone();
+        let frame_tap_clone = frame_tap.clone();
+
+        let worker_handle = std::thread::spawn(move || {
+            let mut stream: Option<VirtualStream> = None;
+            let mut next_chunk_at = Instant::now();
+
+            loop {
+                let command = if stream.is_some() {
+                    let wait = next_chunk_at.saturating_duration_since(Instant::now());
+                    match command_rx.recv_timeout(wait) {
+                        Ok(command) => Some(command),
+                        Err(mpsc::RecvTimeoutError::Timeout) => None,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                    }
+                } else {
+                    match command_rx.recv() {
+                        Ok(command) => Some(command),
+                        Err(_) => break,
+                    }
+                };
+
+                match command {
+                    Some(AudioCommand::Start(ready_tx)) => {
+                        if stream.is_none() {
+                            stream = Some(source.stream());
+                            next_chunk_at = Instant::now() + CHUNK_INTERVAL;
+                            *is_recording_clone.lock() = true;
+                            tracing::info!("Virtual audio capture started");
+                        }
+                        let _ = ready_tx.send(());
+                    }
+                    Some(AudioCommand::Stop(done_tx)) => {
+                        stream = None;
                         *is_recording_clone.lock() = false;
-                        break;
+                        tracing::info!("Virtual audio capture stopped");
+                        let _ = done_tx.send(());
+                    }
+                    Some(AudioCommand::Shutdown) => break,
+                    None => {
+                        if let Some(stream) = stream.as_mut() {
+                            let chunk = stream.next_chunk(chunk_len);
+                            buffer_clone.lock().write(&chunk);
+                            frame_tap_clone
+                                .lock()
+                                .retain(|tap| tap.send(chunk.clone()).is_ok());
+                        }
+                        next_chunk_at += CHUNK_INTERVAL;
                     }
                 }
             }
+            *is_recording_clone.lock() = false;
         });
 
         Ok(Self {
@@ -253,6 +622,8 @@ impl AudioCapture {
             is_recording,
             config,
             last_error,
+            frame_tap,
+            device_events: Arc::new(Mutex::new(Vec::new())),
             device_sample_rate,
         })
     }
@@ -295,8 +666,31 @@ impl AudioCapture {
         Ok(())
     }
 
+    /// Receive a copy of each captured mono chunk (at the device sample rate)
+    ///
+    /// The channel closes when capture stops.
+    pub fn subscribe_frames(&self) -> mpsc::Receiver<Vec<f32>> {
+        let (tx, rx) = mpsc::channel();
+        self.frame_tap.lock().push(tx);
+        rx
+    }
+
+    /// Receive the input device changes of this capture
+    ///
+    /// The device is reopened automatically when lost while recording. The
+    /// channel closes when capture stops.
+    pub fn subscribe_device_events(&self) -> mpsc::Receiver<DeviceEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.device_events.lock().push(tx);
+        rx
+    }
+
     /// Stop capturing and return captured samples with the device sample rate
     pub fn stop(&self) -> Result<(Vec<f32>, u32), AudioError> {
+        // Close the live frame and device channels so subscribers finish
+        self.frame_tap.lock().clear();
+        self.device_events.lock().clear();
+
         let (done_tx, done_rx) = mpsc::channel();
         self.command_tx
             .lock()
@@ -358,6 +752,218 @@ impl AudioCapture {
     }
 }
 
+/// Input device with this name, if connected
+fn find_input_device(name: &str) -> Result<Option<cpal::Device>, cpal::DevicesError> {
+    Ok(cpal::default_host()
+        .input_devices()?
+        .find(|d| d.name().map(|n| n == name).unwrap_or(false)))
+}
+
+/// Default stream config of an input device, or of an output device captured
+/// through loopback
+fn stream_config_of(
+    device: &cpal::Device,
+    loopback: bool,
+) -> Result<cpal::SupportedStreamConfig, cpal::DefaultStreamConfigError> {
+    if loopback {
+        device.default_output_config()
+    } else {
+        device.default_input_config()
+    }
+}
+
+/// Open and start a stream on `device`, delivering mono samples at `output_rate`
+///
+/// With `loopback`, `device` is an output device and its output is captured
+/// (WASAPI only).
+fn open_stream(
+    device: &cpal::Device,
+    loopback: bool,
+    requested_mode: CaptureMode,
+    output_rate: u32,
+    sinks: &StreamSinks,
+) -> Result<cpal::Stream, String> {
+    let supported_config = stream_config_of(device, loopback).map_err(|e| e.to_string())?;
+    let sample_rate = supported_config.sample_rate().0;
+    let channels = supported_config.channels() as usize;
+    let mut stream_config = cpal::StreamConfig {
+        channels: supported_config.channels(),
+        sample_rate: supported_config.sample_rate(),
+        buffer_size: buffer_size_for(requested_mode, sample_rate, supported_config.buffer_size()),
+    };
+
+    tracing::info!(
+        "Audio device: {}Hz, {} channels, {:?} buffer",
+        sample_rate,
+        channels,
+        stream_config.buffer_size
+    );
+    if sample_rate != output_rate {
+        tracing::info!("Resampling device input to {}Hz", output_rate);
+    }
+
+    let build = |stream_config: &cpal::StreamConfig| {
+        device.build_input_stream(
+            stream_config,
+            capture_callback(
+                sinks.buffer.clone(),
+                channels,
+                sinks.frame_tap.clone(),
+                LinearResampler::new(sample_rate, output_rate),
+            ),
+            error_callback(sinks.last_error.clone(), sinks.is_recording.clone()),
+            None,
+        )
+    };
+
+    // Fall back to shared mode when the device refuses the small buffer
+    let mut fallback_reason = None;
+    let mut built = build(&stream_config);
+    if let (Err(e), cpal::BufferSize::Fixed(_)) = (&built, &stream_config.buffer_size) {
+        tracing::warn!("Low-latency capture unavailable ({}), using shared mode", e);
+        fallback_reason = Some(e.to_string());
+        stream_config.buffer_size = cpal::BufferSize::Default;
+        built = build(&stream_config);
+    }
+
+    let stream = built.map_err(|e| e.to_string())?;
+    stream.play().map_err(|e| e.to_string())?;
+    *sinks.is_recording.lock() = true;
+    *LAST_CAPTURE_MODE.lock() = Some(capture_mode_report(
+        requested_mode,
+        &stream_config.buffer_size,
+        sample_rate,
+        fallback_reason,
+    ));
+    Ok(stream)
+}
+
+/// Reopen capture on the device named `device_name`, or the default device
+/// (the default output device with `loopback`)
+///
+/// Returns the stream with the name of the device it runs on, or None when
+/// no device can be opened yet.
+fn reconnect(
+    device_name: Option<&str>,
+    loopback: bool,
+    requested_mode: CaptureMode,
+    output_rate: u32,
+    sinks: &StreamSinks,
+) -> Option<(cpal::Stream, String)> {
+    let device = if loopback {
+        cpal::default_host().default_output_device()?
+    } else {
+        device_name
+            .and_then(|name| find_input_device(name).ok().flatten())
+            .or_else(|| cpal::default_host().default_input_device())?
+    };
+    let name = device
+        .name()
+        .unwrap_or_else(|_| "unknown device".to_string());
+
+    match open_stream(&device, loopback, requested_mode, output_rate, sinks) {
+        Ok(stream) => Some((stream, name)),
+        Err(e) => {
+            tracing::debug!("Input device '{}' not ready: {}", name, e);
+            None
+        }
+    }
+}
+
+/// Send a device event to the subscribers still listening
+fn send_device_event(subscribers: &Mutex<Vec<mpsc::Sender<DeviceEvent>>>, event: DeviceEvent) {
+    subscribers
+        .lock()
+        .retain(|tx| tx.send(event.clone()).is_ok());
+}
+
+/// Device callback: mix to mono, resample, buffer and forward to the frame taps
+fn capture_callback(
+    buffer: Arc<Mutex<RingBuffer>>,
+    channels: usize,
+    frame_tap: Arc<Mutex<Vec<mpsc::Sender<Vec<f32>>>>>,
+    mut resampler: LinearResampler,
+) -> impl FnMut(&[f32], &cpal::InputCallbackInfo) + Send + 'static {
+    move |data: &[f32], _: &cpal::InputCallbackInfo| {
+        // Convert to mono if stereo
+        let mono: Vec<f32> = if channels > 1 {
+            data.chunks(channels)
+                .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
+                .collect()
+        } else {
+            data.to_vec()
+        };
+        let mono = resampler.process(&mono);
+
+        buffer.lock().write(&mono);
+
+        let mut taps = frame_tap.lock();
+        taps.retain(|tap| tap.send(mono.clone()).is_ok());
+    }
+}
+
+/// Stream error callback: store the error and stop on disconnection
+fn error_callback(
+    last_error: Arc<Mutex<Option<StreamError>>>,
+    is_recording: Arc<Mutex<bool>>,
+) -> impl FnMut(cpal::StreamError) + Send + 'static {
+    move |err| {
+        let error_msg = err.to_string();
+        tracing::error!("Audio stream error: {}", error_msg);
+
+        // Detect disconnection errors
+        let is_disconnection = error_msg.contains("disconnected")
+            || error_msg.contains("device")
+            || error_msg.contains("DeviceNotAvailable")
+            || error_msg.contains("lost")
+            || error_msg.contains("InvalidDevice");
+
+        // Store the error
+        *last_error.lock() = Some(StreamError {
+            message: error_msg,
+            is_disconnection,
+        });
+
+        // Mark as no longer recording on critical errors
+        if is_disconnection {
+            *is_recording.lock() = false;
+        }
+    }
+}
+
+/// Describe the buffer mode a stream was opened with
+fn capture_mode_report(
+    requested: CaptureMode,
+    buffer_size: &cpal::BufferSize,
+    sample_rate: u32,
+    fallback_reason: Option<String>,
+) -> CaptureModeReport {
+    let buffer_frames = match buffer_size {
+        cpal::BufferSize::Fixed(frames) => Some(*frames),
+        cpal::BufferSize::Default => None,
+    };
+    CaptureModeReport {
+        requested,
+        active: if buffer_frames.is_some() {
+            CaptureMode::LowLatency
+        } else {
+            CaptureMode::Shared
+        },
+        buffer_frames,
+        buffer_ms: buffer_frames.map(|f| f as f32 * 1000.0 / sample_rate as f32),
+        fallback_reason,
+    }
+}
+
+/// Short description of a virtual source for logs
+fn source_label(source: &VirtualSource) -> String {
+    match source {
+        VirtualSource::Tone { frequency } => format!("{} Hz tone", frequency),
+        VirtualSource::Silence => "silence".to_string(),
+        VirtualSource::Wav { samples, .. } => format!("WAV file ({} samples)", samples.len()),
+    }
+}
+
 impl Drop for AudioCapture {
     fn drop(&mut self) {
         // Send shutdown command to worker thread
@@ -374,6 +980,80 @@ impl Drop for AudioCapture {
 mod tests {
     use super::*;
 
+    // =========================================================================
+    // Level Meter Tests
+    // =========================================================================
+
+    #[test]
+    fn test_level_meter_windows() {
+        // 50 ms windows of 800 samples at 16 kHz
+        let mut meter = LevelMeter::new(16000);
+        assert_eq!(meter.push(&[0.5; 500]), None);
+
+        let level = meter.push(&[-0.5; 300]).unwrap();
+        assert!((level.rms - 0.5).abs() < 1e-6);
+        assert_eq!(level.peak, 0.5);
+
+        // The next window starts empty
+        let mut samples = vec![0.0; 800];
+        samples[10] = -0.8;
+        let level = meter.push(&samples).unwrap();
+        assert_eq!(level.peak, 0.8);
+        assert!(level.rms < 0.05);
+    }
+
+    #[test]
+    fn test_run_level_meter_ends_with_capture() {
+        let (tx, rx) = mpsc::channel();
+        tx.send(vec![0.25; 1600]).unwrap();
+        tx.send(vec![0.0; 800]).unwrap();
+        drop(tx);
+
+        let mut levels = Vec::new();
+        run_level_meter(rx, 16000, |level| levels.push(level));
+        // One level per chunk: the last window each chunk completes
+        assert_eq!(levels.len(), 2);
+        assert_eq!(levels[0].peak, 0.25);
+        assert_eq!(levels[1], AudioLevel::default());
+    }
+
+    // =========================================================================
+    // Reconnection Tests
+    // =========================================================================
+
+    #[test]
+    fn test_linear_resampler_across_chunks() {
+        // 48 kHz ramp to 16 kHz, fed in uneven chunks
+        let input: Vec<f32> = (0..4800).map(|i| i as f32).collect();
+        let mut resampler = LinearResampler::new(48000, 16000);
+        let mut output = Vec::new();
+        for chunk in input.chunks(441) {
+            output.extend(resampler.process(chunk));
+        }
+
+        assert_eq!(output.len(), 1600);
+        for (i, sample) in output.iter().enumerate() {
+            assert!((sample - (i * 3) as f32).abs() < 1e-3, "sample {}", i);
+        }
+    }
+
+    #[test]
+    fn test_linear_resampler_same_rate() {
+        let mut resampler = LinearResampler::new(16000, 16000);
+        assert_eq!(resampler.process(&[0.1, 0.2, 0.3]), vec![0.1, 0.2, 0.3]);
+    }
+
+    #[test]
+    fn test_device_event_serialization() {
+        let event = DeviceEvent::Reconnected {
+            device: "USB Microphone".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "reconnected", "device": "USB Microphone" })
+        );
+    }
+
     // =========================================================================
     // AudioConfig Tests
     // =========================================================================
@@ -392,12 +1072,52 @@ mod tests {
             sample_rate: 44100,
             channels: 2,
             buffer_duration_ms: 200,
+            capture_mode: CaptureMode::LowLatency,
         };
         assert_eq!(config.sample_rate, 44100);
         assert_eq!(config.channels, 2);
         assert_eq!(config.buffer_duration_ms, 200);
     }
 
+    #[test]
+    fn test_low_latency_buffer_size() {
+        let range = cpal::SupportedBufferSize::Range { min: 256, max: 4096 };
+        assert_eq!(
+            buffer_size_for(CaptureMode::Shared, 48000, &range),
+            cpal::BufferSize::Default
+        );
+        assert_eq!(
+            buffer_size_for(CaptureMode::LowLatency, 48000, &range),
+            cpal::BufferSize::Fixed(480)
+        );
+        // Clamped to what the device supports
+        assert_eq!(
+            buffer_size_for(CaptureMode::LowLatency, 16000, &range),
+            cpal::BufferSize::Fixed(256)
+        );
+        assert_eq!(
+            buffer_size_for(CaptureMode::LowLatency, 16000, &cpal::SupportedBufferSize::Unknown),
+            cpal::BufferSize::Fixed(160)
+        );
+    }
+
+    #[test]
+    fn test_capture_mode_report() {
+        let report =
+            capture_mode_report(CaptureMode::LowLatency, &cpal::BufferSize::Fixed(480), 48000, None);
+        assert_eq!(report.active, CaptureMode::LowLatency);
+        assert_eq!(report.buffer_ms, Some(10.0));
+
+        let report = capture_mode_report(
+            CaptureMode::LowLatency,
+            &cpal::BufferSize::Default,
+            48000,
+            Some("unsupported".to_string()),
+        );
+        assert_eq!(report.active, CaptureMode::Shared);
+        assert_eq!(report.buffer_frames, None);
+    }
+
     #[test]
     fn test_audio_config_clone() {
         let config = AudioConfig::default();
@@ -552,6 +1272,7 @@ mod tests {
             sample_rate: 16000,
             channels: 1,
             buffer_duration_ms: 5000, // 5 seconds
+            capture_mode: CaptureMode::Shared,
         };
         let result = AudioCapture::new(config);
         // Just check it doesn't panic
@@ -638,6 +1359,7 @@ mod tests {
             sample_rate: 44100,
             channels: 2,
             buffer_duration_ms: 500,
+            capture_mode: CaptureMode::Shared,
         };
 
         let capture = match AudioCapture::new(custom_config.clone()) {
@@ -723,4 +1445,38 @@ mod tests {
         // Drop after stop - should not panic
         drop(capture);
     }
+
+    // =========================================================================
+    // Virtual Input Tests
+    // =========================================================================
+
+    #[test]
+    fn test_virtual_capture_records_in_real_time() {
+        let source = VirtualSource::Tone { frequency: 440.0 };
+        let capture = AudioCapture::with_virtual_source(source, AudioConfig::default()).unwrap();
+        let frames = capture.subscribe_frames();
+
+        capture.start().unwrap();
+        assert!(capture.is_recording());
+        std::thread::sleep(std::time::Duration::from_millis(200));
+        let (samples, sample_rate) = capture.stop().unwrap();
+
+        assert_eq!(sample_rate, 48000);
+        // ~200ms of audio, allowing for scheduling jitter
+        assert!(samples.len() >= 4800, "only {} samples", samples.len());
+        assert!(samples.len() <= 14400, "{} samples", samples.len());
+        assert!(frames.try_iter().count() > 0);
+        assert!(!capture.is_recording());
+    }
+
+    #[test]
+    fn test_virtual_device_id() {
+        if std::env::var(crate::audio::VIRTUAL_INPUT_ENV).is_ok() {
+            return; // The environment overrides the device id
+        }
+        let capture =
+            AudioCapture::with_device_id(Some("virtual:silence"), AudioConfig::default()).unwrap();
+        assert_eq!(capture.device_sample_rate(), 48000);
+        assert!(AudioCapture::with_device_id(Some("virtual:bogus"), AudioConfig::default()).is_err());
+    }
 }
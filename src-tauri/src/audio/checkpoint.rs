@@ -0,0 +1,317 @@
+//! Recording Checkpoints
+//!
+//! Periodically persist in-flight audio so a crash mid-recording doesn't lose
+//! it. The checkpoint is an append-only raw file: a small header followed by
+//! f32 little-endian samples, so each flush only writes the new audio.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Interval between checkpoint flushes
+pub const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// File header magic
+const MAGIC: &[u8; 4] = b"GWCK";
+
+/// Header length: magic + sample rate
+const HEADER_LEN: usize = 8;
+
+/// Checkpoint errors
+#[derive(Debug, thiserror::Error)]
+pub enum CheckpointError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Not a recording checkpoint")]
+    InvalidFormat,
+}
+
+/// Recording left behind by a previous session
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct OrphanedRecording {
+    /// Captured duration in seconds
+    pub duration_secs: f32,
+    /// Sample rate of the captured audio
+    pub sample_rate: u32,
+    /// Unix timestamp (seconds) of the last checkpoint flush
+    pub saved_at: u64,
+}
+
+/// Append-only checkpoint file writer
+pub struct CheckpointWriter {
+    writer: BufWriter<File>,
+}
+
+impl CheckpointWriter {
+    /// Create (or truncate) a checkpoint file
+    pub fn create(path: &Path, sample_rate: u32) -> Result<Self, CheckpointError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut writer = BufWriter::new(File::create(path)?);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&sample_rate.to_le_bytes())?;
+        writer.flush()?;
+        Ok(Self { writer })
+    }
+
+    /// Append samples and flush them to disk
+    pub fn append(&mut self, samples: &[f32]) -> Result<(), CheckpointError> {
+        for sample in samples {
+            self.writer.write_all(&sample.to_le_bytes())?;
+        }
+        self.writer.flush()?;
+        self.writer.get_ref().sync_data()?;
+        Ok(())
+    }
+}
+
+/// Read samples and sample rate from a checkpoint file
+///
+/// A trailing partial sample (crash mid-write) is ignored.
+pub fn read_checkpoint(path: &Path) -> Result<(Vec<f32>, u32), CheckpointError> {
+    let data = fs::read(path)?;
+    if data.len() < HEADER_LEN || &data[..4] != MAGIC {
+        return Err(CheckpointError::InvalidFormat);
+    }
+
+    let sample_rate = u32::from_le_bytes([data[4], data[5], data[6], data[7]]);
+    if sample_rate == 0 {
+        return Err(CheckpointError::InvalidFormat);
+    }
+
+    let samples = data[HEADER_LEN..]
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+
+    Ok((samples, sample_rate))
+}
+
+/// Describe a checkpoint file without loading its samples
+pub fn inspect_checkpoint(path: &Path) -> Result<OrphanedRecording, CheckpointError> {
+    let metadata = fs::metadata(path)?;
+    let mut header = [0u8; HEADER_LEN];
+    std::io::Read::read_exact(&mut File::open(path)?, &mut header)?;
+    if &header[..4] != MAGIC {
+        return Err(CheckpointError::InvalidFormat);
+    }
+
+    let sample_rate = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+    if sample_rate == 0 {
+        return Err(CheckpointError::InvalidFormat);
+    }
+
+    let sample_count = (metadata.len() as usize).saturating_sub(HEADER_LEN) / 4;
+    let saved_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+
+    Ok(OrphanedRecording {
+        duration_secs: sample_count as f32 / sample_rate as f32,
+        sample_rate,
+        saved_at,
+    })
+}
+
+/// Background checkpointing of an active recording
+pub struct RecordingCheckpoint {
+    handle: JoinHandle<()>,
+}
+
+impl RecordingCheckpoint {
+    /// Write incoming frames to the active checkpoint file every `CHECKPOINT_INTERVAL`
+    ///
+    /// The thread flushes what is left and exits once `frames` closes.
+    pub fn spawn(frames: mpsc::Receiver<Vec<f32>>, sample_rate: u32) -> Self {
+        let handle = std::thread::spawn(move || {
+            let path = active_checkpoint_path();
+            let mut writer: Option<CheckpointWriter> = None;
+            let mut pending: Vec<f32> = Vec::new();
+            let mut last_flush = Instant::now();
+
+            loop {
+                let closed = match frames.recv_timeout(CHECKPOINT_INTERVAL) {
+                    Ok(chunk) => {
+                        pending.extend_from_slice(&chunk);
+                        false
+                    }
+                    Err(mpsc::RecvTimeoutError::Timeout) => false,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => true,
+                };
+
+                // Short recordings finish before the first flush and never touch disk
+                if closed && writer.is_none() {
+                    break;
+                }
+
+                if closed || last_flush.elapsed() >= CHECKPOINT_INTERVAL {
+                    last_flush = Instant::now();
+                    if writer.is_none() {
+                        match CheckpointWriter::create(&path, sample_rate) {
+                            Ok(w) => writer = Some(w),
+                            Err(e) => {
+                                tracing::warn!("Recording checkpoint disabled: {}", e);
+                                return;
+                            }
+                        }
+                    }
+                    if let Some(w) = writer.as_mut() {
+                        if let Err(e) = w.append(&pending) {
+                            tracing::warn!("Failed to write recording checkpoint: {}", e);
+                        }
+                    }
+                    pending.clear();
+                }
+
+                if closed {
+                    break;
+                }
+            }
+        });
+
+        Self { handle }
+    }
+
+    /// Wait for the writer to finish and remove the checkpoint
+    ///
+    /// Call once the recording has been handled (transcribed or cancelled).
+    pub fn finish(self) {
+        let _ = self.handle.join();
+        let path = active_checkpoint_path();
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                tracing::warn!("Failed to remove recording checkpoint: {}", e);
+            }
+        }
+    }
+}
+
+/// Directory holding recording checkpoints
+fn checkpoint_dir() -> PathBuf {
+    crate::config::data_dir().join("checkpoints")
+}
+
+/// Checkpoint of the recording in progress
+fn active_checkpoint_path() -> PathBuf {
+    checkpoint_dir().join("recording.checkpoint")
+}
+
+/// Checkpoint recovered from a previous session
+pub fn recovered_checkpoint_path() -> PathBuf {
+    checkpoint_dir().join("recovered.checkpoint")
+}
+
+/// Move a checkpoint left by a crashed session aside for recovery
+///
+/// Call on startup, before any recording begins. Returns the recovered
+/// recording, including one found earlier but not yet handled.
+pub fn recover_orphaned_checkpoint() -> Option<OrphanedRecording> {
+    let active = active_checkpoint_path();
+    let recovered = recovered_checkpoint_path();
+
+    if active.exists() {
+        tracing::warn!("Found recording checkpoint from a previous session");
+        if let Err(e) = fs::rename(&active, &recovered) {
+            tracing::error!("Failed to move orphaned checkpoint: {}", e);
+            return None;
+        }
+    }
+
+    if !recovered.exists() {
+        return None;
+    }
+
+    match inspect_checkpoint(&recovered) {
+        Ok(info) if info.duration_secs > 0.0 => Some(info),
+        Ok(_) => {
+            discard_recovered_checkpoint();
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Discarding unreadable checkpoint: {}", e);
+            discard_recovered_checkpoint();
+            None
+        }
+    }
+}
+
+/// Delete the recovered checkpoint
+pub fn discard_recovered_checkpoint() {
+    let path = recovered_checkpoint_path();
+    if path.exists() {
+        if let Err(e) = fs::remove_file(&path) {
+            tracing::warn!("Failed to delete recovered checkpoint: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_checkpoint_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.checkpoint");
+
+        let mut writer = CheckpointWriter::create(&path, 48000).unwrap();
+        writer.append(&[0.1, -0.2]).unwrap();
+        writer.append(&[0.3]).unwrap();
+
+        let (samples, rate) = read_checkpoint(&path).unwrap();
+        assert_eq!(rate, 48000);
+        assert_eq!(samples, vec![0.1, -0.2, 0.3]);
+    }
+
+    #[test]
+    fn test_truncated_sample_is_ignored() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.checkpoint");
+
+        let mut writer = CheckpointWriter::create(&path, 16000).unwrap();
+        writer.append(&[0.5, 0.25]).unwrap();
+        drop(writer);
+
+        // Simulate a crash in the middle of a sample
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&[0x00, 0x01]).unwrap();
+
+        let (samples, _) = read_checkpoint(&path).unwrap();
+        assert_eq!(samples, vec![0.5, 0.25]);
+    }
+
+    #[test]
+    fn test_inspect_checkpoint() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.checkpoint");
+
+        let mut writer = CheckpointWriter::create(&path, 16000).unwrap();
+        writer.append(&vec![0.0; 32000]).unwrap();
+
+        let info = inspect_checkpoint(&path).unwrap();
+        assert_eq!(info.sample_rate, 16000);
+        assert!((info.duration_secs - 2.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_invalid_file_rejected() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("test.checkpoint");
+        fs::write(&path, b"RIFF0000WAVE").unwrap();
+
+        assert!(matches!(read_checkpoint(&path), Err(CheckpointError::InvalidFormat)));
+        assert!(matches!(inspect_checkpoint(&path), Err(CheckpointError::InvalidFormat)));
+    }
+}
@@ -0,0 +1,172 @@
+//! Device Test
+//!
+//! Summarize a short test recording so users can check a microphone before
+//! choosing it, and play the recording back on the default output device.
+
+use super::{calculate_rms, resample, to_db, AudioError};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Number of points in the test waveform
+pub const WAVEFORM_POINTS: usize = 64;
+
+/// Peak level treated as clipping
+const CLIPPING_PEAK: f32 = 0.99;
+
+/// Peak level below which the recording counts as silent (dBFS)
+const SILENT_PEAK_DB: f32 = -50.0;
+
+/// Extra time allowed for playback to drain the device buffer
+const PLAYBACK_GRACE: Duration = Duration::from_millis(500);
+
+/// Result of an input device test
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct DeviceTestReport {
+    /// Largest absolute sample (0.0 to 1.0)
+    pub peak: f32,
+    /// RMS level of the whole recording (0.0 to 1.0)
+    pub rms: f32,
+    /// Peak level in dBFS
+    pub peak_db: f32,
+    /// RMS level in dBFS
+    pub rms_db: f32,
+    /// The input reached full scale
+    pub clipping: bool,
+    /// Nothing above the noise was heard; the device may be muted
+    pub silent: bool,
+    /// Peak of each of `WAVEFORM_POINTS` equal slices of the recording
+    pub waveform: Vec<f32>,
+    /// Length of the recording in milliseconds
+    pub duration_ms: u32,
+    /// Whether the recording was played back
+    pub played_back: bool,
+}
+
+/// Summarize a test recording
+pub fn analyze_device_test(samples: &[f32], sample_rate: u32) -> DeviceTestReport {
+    let peak = peak_of(samples);
+    let rms = calculate_rms(samples).min(1.0);
+    let peak_db = to_db(peak);
+
+    DeviceTestReport {
+        peak,
+        rms,
+        peak_db,
+        rms_db: to_db(rms),
+        clipping: peak >= CLIPPING_PEAK,
+        silent: peak_db < SILENT_PEAK_DB,
+        waveform: waveform(samples, WAVEFORM_POINTS),
+        duration_ms: (samples.len() as u64 * 1000 / sample_rate.max(1) as u64) as u32,
+        played_back: false,
+    }
+}
+
+/// Peak of each of `points` equal slices of `samples`
+///
+/// Shorter recordings give one point per sample.
+pub fn waveform(samples: &[f32], points: usize) -> Vec<f32> {
+    if samples.is_empty() || points == 0 {
+        return Vec::new();
+    }
+    let slice = samples.len().div_ceil(points);
+    samples.chunks(slice).map(peak_of).collect()
+}
+
+/// Largest absolute sample, at most 1.0
+fn peak_of(samples: &[f32]) -> f32 {
+    samples
+        .iter()
+        .fold(0.0f32, |peak, s| peak.max(s.abs()))
+        .min(1.0)
+}
+
+/// Play mono samples on the default output device, returning when done
+pub fn play_samples(samples: &[f32], sample_rate: u32) -> Result<(), AudioError> {
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| AudioError::DeviceNotFound("default output device".to_string()))?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| AudioError::ConfigError(e.to_string()))?;
+    let output_rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+
+    let samples = resample(samples, sample_rate, output_rate).map_err(AudioError::StreamError)?;
+    let duration = Duration::from_secs_f64(samples.len() as f64 / output_rate as f64);
+
+    let (done_tx, done_rx) = mpsc::channel();
+    let mut position = 0;
+    let stream = device
+        .build_output_stream(
+            &config.into(),
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                // Same sample on every channel, silence once done
+                for frame in data.chunks_mut(channels) {
+                    frame.fill(samples.get(position).copied().unwrap_or(0.0));
+                    position += 1;
+                }
+                if position >= samples.len() {
+                    let _ = done_tx.send(());
+                }
+            },
+            |e| tracing::warn!("Playback stream error: {}", e),
+            None,
+        )
+        .map_err(|e| AudioError::StreamError(e.to_string()))?;
+    stream
+        .play()
+        .map_err(|e| AudioError::PlayError(e.to_string()))?;
+
+    let _ = done_rx.recv_timeout(duration + PLAYBACK_GRACE);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::PI;
+
+    const RATE: u32 = 16000;
+
+    #[test]
+    fn test_analyze_tone() {
+        let samples: Vec<f32> = (0..RATE * 2)
+            .map(|i| 0.5 * (2.0 * PI * 440.0 * i as f32 / RATE as f32).sin())
+            .collect();
+        let report = analyze_device_test(&samples, RATE);
+
+        assert!((report.peak - 0.5).abs() < 1e-3);
+        // Sine RMS is peak / sqrt(2)
+        assert!((report.rms - 0.354).abs() < 1e-2);
+        assert!((report.peak_db + 6.0).abs() < 0.1);
+        assert!(!report.clipping);
+        assert!(!report.silent);
+        assert_eq!(report.duration_ms, 2000);
+        assert_eq!(report.waveform.len(), WAVEFORM_POINTS);
+    }
+
+    #[test]
+    fn test_analyze_silent_and_clipping() {
+        let silent = analyze_device_test(&vec![0.001; 1600], RATE);
+        assert!(silent.silent);
+
+        let mut loud = vec![0.2; 1600];
+        loud[100] = -1.0;
+        let clipping = analyze_device_test(&loud, RATE);
+        assert!(clipping.clipping);
+        assert_eq!(clipping.peak, 1.0);
+    }
+
+    #[test]
+    fn test_waveform_slices() {
+        let mut samples = vec![0.0; 100];
+        samples[5] = 0.5;
+        samples[95] = -0.8;
+        assert_eq!(waveform(&samples, 4), vec![0.5, 0.0, 0.0, 0.8]);
+        assert_eq!(waveform(&[0.1, -0.2], 64), vec![0.1, 0.2]);
+        assert!(waveform(&[], 64).is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+//! Audio Filters
+//!
+//! Optional clean-up stages run before transcription.
+
+use super::resample;
+use nnnoiseless::DenoiseState;
+
+/// Peak level targeted by gain normalization (about -1 dBFS)
+const TARGET_PEAK: f32 = 0.89;
+
+/// Maximum amplification, so near-silent recordings don't turn into loud noise
+const MAX_GAIN: f32 = 10.0;
+
+/// High-pass cutoff removing rumble and DC offset (Hz)
+const HIGH_PASS_HZ: f32 = 80.0;
+
+/// Frames quieter than this multiple of the noise floor are attenuated
+const NOISE_GATE_RATIO: f32 = 2.0;
+
+/// Attenuation applied to frames at the noise floor (about -20 dB)
+const NOISE_ATTENUATION: f32 = 0.1;
+
+/// Sample rate RNNoise works at
+const RNNOISE_SAMPLE_RATE: u32 = 48000;
+
+/// Scale of RNNoise samples (16-bit PCM range)
+const RNNOISE_SCALE: f32 = i16::MAX as f32;
+
+/// Scale samples so the loudest one reaches the target peak
+///
+/// Returns the applied gain.
+pub fn apply_gain(samples: &mut [f32]) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+    if peak <= f32::EPSILON {
+        return 1.0;
+    }
+
+    let gain = (TARGET_PEAK / peak).min(MAX_GAIN);
+    for sample in samples.iter_mut() {
+        *sample *= gain;
+    }
+    gain
+}
+
+/// Remove low-frequency rumble and attenuate frames at the noise floor
+///
+/// The noise floor is the RMS of the quietest 10% of 20 ms frames, so the
+/// filter adapts to each recording without a separate calibration.
+pub fn suppress_noise(samples: &mut [f32], sample_rate: u32) {
+    if samples.is_empty() || sample_rate == 0 {
+        return;
+    }
+
+    high_pass(samples, sample_rate);
+
+    let frame_len = (sample_rate as usize / 50).max(1);
+    let frame_rms: Vec<f32> = samples.chunks(frame_len).map(rms).collect();
+    let mut sorted = frame_rms.clone();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let noise_floor = sorted[sorted.len() / 10];
+    let threshold = noise_floor * NOISE_GATE_RATIO;
+
+    // A recording without quiet passages has no noise floor to measure
+    if noise_floor <= f32::EPSILON || sorted[sorted.len() - 1] < threshold * NOISE_GATE_RATIO {
+        return;
+    }
+
+    for (frame, level) in samples.chunks_mut(frame_len).zip(frame_rms) {
+        if level < threshold {
+            for sample in frame.iter_mut() {
+                *sample *= NOISE_ATTENUATION;
+            }
+        }
+    }
+}
+
+/// Denoise with the RNNoise neural network
+///
+/// RNNoise works on 48 kHz audio, so other rates are resampled on the way in
+/// and out. The output has the same rate and length as the input.
+pub fn denoise_rnnoise(samples: &[f32], sample_rate: u32) -> Result<Vec<f32>, String> {
+    if samples.is_empty() || sample_rate == 0 {
+        return Ok(samples.to_vec());
+    }
+
+    let input = resample(samples, sample_rate, RNNOISE_SAMPLE_RATE)?;
+    let frame_len = DenoiseState::FRAME_SIZE;
+    let mut state = DenoiseState::new();
+    let mut in_frame = vec![0.0; frame_len];
+    let mut out_frame = vec![0.0; frame_len];
+    let mut output = Vec::with_capacity(input.len() + frame_len);
+
+    // Output lags input by one frame; a trailing silent frame flushes it
+    for chunk in input.chunks(frame_len).chain([&[][..]]) {
+        in_frame.fill(0.0);
+        for (dst, src) in in_frame.iter_mut().zip(chunk) {
+            *dst = src * RNNOISE_SCALE;
+        }
+        state.process_frame(&mut out_frame, &in_frame);
+        output.extend(
+            out_frame
+                .iter()
+                .map(|s| (s / RNNOISE_SCALE).clamp(-1.0, 1.0)),
+        );
+    }
+    output.drain(..frame_len);
+    output.truncate(input.len());
+
+    // Resampling pads to whole chunks
+    let mut output = resample(&output, RNNOISE_SAMPLE_RATE, sample_rate)?;
+    output.resize(samples.len(), 0.0);
+    Ok(output)
+}
+
+/// One-pole high-pass filter
+fn high_pass(samples: &mut [f32], sample_rate: u32) {
+    let rc = 1.0 / (2.0 * std::f32::consts::PI * HIGH_PASS_HZ);
+    let dt = 1.0 / sample_rate as f32;
+    let alpha = rc / (rc + dt);
+
+    let mut prev_input = samples[0];
+    let mut prev_output = 0.0;
+    for sample in samples.iter_mut() {
+        let input = *sample;
+        prev_output = alpha * (prev_output + input - prev_input);
+        prev_input = input;
+        *sample = prev_output;
+    }
+}
+
+fn rms(frame: &[f32]) -> f32 {
+    (frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, amplitude: f32, samples: usize) -> Vec<f32> {
+        (0..samples)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * freq * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_gain_normalizes_peak() {
+        let mut samples = vec![0.1, -0.2, 0.05];
+        let gain = apply_gain(&mut samples);
+        assert!((gain - TARGET_PEAK / 0.2).abs() < 1e-4);
+        assert!((samples[1] + TARGET_PEAK).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_gain_is_capped() {
+        let mut samples = vec![0.001, -0.001];
+        assert_eq!(apply_gain(&mut samples), MAX_GAIN);
+
+        let mut silence = vec![0.0; 4];
+        assert_eq!(apply_gain(&mut silence), 1.0);
+    }
+
+    #[test]
+    fn test_noise_suppression_keeps_speech_level() {
+        // Quiet hiss followed by a loud tone
+        let mut samples = tone(1000.0, 0.01, 8000);
+        samples.extend(tone(440.0, 0.5, 8000));
+
+        suppress_noise(&mut samples, 16000);
+
+        let quiet = rms(&samples[1000..7000]);
+        let loud = rms(&samples[9000..15000]);
+        assert!(quiet < 0.002, "noise not attenuated: {}", quiet);
+        assert!(loud > 0.3, "speech attenuated: {}", loud);
+    }
+
+    #[test]
+    fn test_noise_suppression_keeps_steady_signal() {
+        let mut samples = tone(440.0, 0.5, 16000);
+        suppress_noise(&mut samples, 16000);
+        assert!(rms(&samples[1000..15000]) > 0.3);
+    }
+
+    #[test]
+    fn test_rnnoise_attenuates_noise() {
+        // Pseudo-random hiss for two seconds, then the same hiss over a loud tone
+        let mut seed = 1u32;
+        let mut hiss = |len: usize| -> Vec<f32> {
+            (0..len)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    0.1 * ((seed >> 8) as f32 / (1 << 24) as f32 - 0.5)
+                })
+                .collect()
+        };
+        let mut samples = hiss(96000);
+        let voiced = (0..48000)
+            .map(|i| 0.5 * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 48000.0).sin());
+        samples.extend(hiss(48000).iter().zip(voiced).map(|(n, t)| n + t));
+
+        let denoised = denoise_rnnoise(&samples, 48000).unwrap();
+        assert_eq!(denoised.len(), samples.len());
+
+        // RNNoise needs a moment to estimate the noise
+        let noise_before = rms(&samples[48000..96000]);
+        let noise_after = rms(&denoised[48000..96000]);
+        assert!(
+            noise_after < noise_before * 0.75,
+            "noise not attenuated: {} -> {}",
+            noise_before,
+            noise_after
+        );
+        assert!(rms(&denoised[108000..138000]) > 0.2);
+    }
+
+    #[test]
+    fn test_rnnoise_resamples() {
+        let samples = tone(440.0, 0.5, 16000);
+        let denoised = denoise_rnnoise(&samples, 16000).unwrap();
+        assert_eq!(denoised.len(), samples.len());
+        assert!(rms(&denoised[4000..12000]) > 0.2);
+        assert!(denoise_rnnoise(&[], 16000).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_high_pass_removes_dc() {
+        let mut samples = vec![0.5; 16000];
+        suppress_noise(&mut samples, 16000);
+        assert!(samples[15999].abs() < 0.01);
+    }
+}
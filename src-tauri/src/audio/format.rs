@@ -2,7 +2,37 @@
 //!
 //! Conversion and encoding functions.
 
+use super::AudioError;
 use rubato::{FftFixedIn, Resampler};
+use std::path::Path;
+
+/// Read a WAV file downmixed to mono, returning the samples and sample rate
+pub fn read_wav_mono(path: &Path) -> Result<(Vec<f32>, u32), AudioError> {
+    let reader = hound::WavReader::open(path)
+        .map_err(|e| AudioError::DeviceNotFound(format!("{}: {}", path.display(), e)))?;
+    let spec = reader.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Result<Vec<f32>, hound::Error> = match spec.sample_format {
+        hound::SampleFormat::Float => reader.into_samples::<f32>().collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.max(1) - 1)) as f32;
+            reader
+                .into_samples::<i32>()
+                .map(|s| s.map(|s| s as f32 / scale))
+                .collect()
+        }
+    };
+    let interleaved = interleaved
+        .map_err(|e| AudioError::ConfigError(format!("{}: {}", path.display(), e)))?;
+
+    let samples = interleaved
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    Ok((samples, spec.sample_rate))
+}
 
 /// Encode samples as WAV format bytes
 pub fn encode_wav(samples: &[f32], sample_rate: u32, channels: u16) -> Vec<u8> {
@@ -69,8 +99,37 @@ pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32
         return Ok(samples.to_vec());
     }
 
+    let mut output = Vec::new();
+    resample_chunks(samples, from_rate, to_rate, |chunk| output.extend_from_slice(chunk))?;
+
+    tracing::info!(
+        "Resampled {} samples ({}Hz) to {} samples ({}Hz)",
+        samples.len(),
+        from_rate,
+        output.len(),
+        to_rate
+    );
+
+    Ok(output)
+}
+
+/// Resample audio, handing each resampled chunk to `sink` as it is produced
+///
+/// Lets the consumer start on the first chunks while later ones are still
+/// being resampled.
+pub fn resample_chunks(
+    samples: &[f32],
+    from_rate: u32,
+    to_rate: u32,
+    mut sink: impl FnMut(&[f32]),
+) -> Result<(), String> {
+    if from_rate == to_rate {
+        sink(samples);
+        return Ok(());
+    }
+
     if samples.is_empty() {
-        return Ok(Vec::new());
+        return Ok(());
     }
 
     // Calculate chunk size (must be power of 2 for FFT resampler)
@@ -87,7 +146,6 @@ pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32
     .map_err(|e| format!("Failed to create resampler: {}", e))?;
 
     // Process in chunks
-    let mut output = Vec::new();
     let mut pos = 0;
 
     while pos < samples.len() {
@@ -104,7 +162,7 @@ pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32
         match resampler.process(&input, None) {
             Ok(resampled) => {
                 if !resampled.is_empty() && !resampled[0].is_empty() {
-                    output.extend_from_slice(&resampled[0]);
+                    sink(&resampled[0]);
                 }
             }
             Err(e) => {
@@ -115,15 +173,7 @@ pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32
         pos += chunk_size;
     }
 
-    tracing::info!(
-        "Resampled {} samples ({}Hz) to {} samples ({}Hz)",
-        samples.len(),
-        from_rate,
-        output.len(),
-        to_rate
-    );
-
-    Ok(output)
+    Ok(())
 }
 
 /// Simple voice activity detection
@@ -0,0 +1,62 @@
+//! System Audio Capture
+//!
+//! Find the device that records what the computer plays, for transcribing
+//! calls and meetings. Windows captures the default output device through
+//! WASAPI loopback; other platforms need a monitor source (PulseAudio,
+//! PipeWire) or a virtual loopback device (BlackHole on macOS), which show
+//! up as input devices.
+
+use super::AudioError;
+use cpal::traits::{DeviceTrait, HostTrait};
+
+/// Name fragments of monitor and loopback input devices
+const LOOPBACK_NAME_HINTS: &[&str] = &[
+    "monitor",
+    "loopback",
+    "blackhole",
+    "soundflower",
+    "stereo mix",
+];
+
+/// Whether an input device name looks like a monitor or loopback device
+pub fn is_loopback_name(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_NAME_HINTS.iter().any(|hint| name.contains(hint))
+}
+
+/// Device recording the system audio, and whether it is an output device
+/// to capture through loopback (rather than an input device)
+pub fn system_audio_device() -> Result<(cpal::Device, bool), AudioError> {
+    let host = cpal::default_host();
+    if cfg!(windows) {
+        if let Some(device) = host.default_output_device() {
+            return Ok((device, true));
+        }
+    }
+
+    host.input_devices()
+        .map_err(|_| AudioError::NoHost)?
+        .find(|device| device.name().is_ok_and(|name| is_loopback_name(&name)))
+        .map(|device| (device, false))
+        .ok_or_else(|| {
+            AudioError::DeviceNotFound(
+                "system audio (no monitor or loopback input device, \
+                 e.g. BlackHole on macOS)"
+                    .to_string(),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_loopback_name() {
+        assert!(is_loopback_name("Monitor of Built-in Audio Analog Stereo"));
+        assert!(is_loopback_name("BlackHole 2ch"));
+        assert!(is_loopback_name("Stereo Mix (Realtek Audio)"));
+        assert!(!is_loopback_name("USB Microphone"));
+        assert!(!is_loopback_name("default"));
+    }
+}
@@ -3,11 +3,31 @@
 //! Audio capture and processing.
 
 mod buffer;
+mod calibration;
+mod checkpoint;
 mod capture;
+mod device_test;
+mod filters;
 mod format;
+mod loopback;
+mod model_cache;
+mod silero;
+mod speaker;
+mod transcode;
 mod vad;
+mod virtual_input;
 
 pub use buffer::*;
+pub use calibration::*;
+pub use checkpoint::*;
 pub use capture::*;
+pub use device_test::*;
+pub use filters::*;
 pub use format::*;
+pub use loopback::*;
+pub use model_cache::*;
+pub use silero::*;
+pub use speaker::*;
+pub use transcode::*;
 pub use vad::*;
+pub use virtual_input::*;
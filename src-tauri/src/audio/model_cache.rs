@@ -0,0 +1,77 @@
+//! Model Cache
+//!
+//! Keeps the last neural model loaded from disk so detectors created for
+//! each recording share it instead of parsing and optimizing the file again.
+//! The cache is keyed by path: pointing the settings at another file loads
+//! that one and drops the previous model once nothing holds it.
+
+use parking_lot::Mutex;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Last loaded model of one kind and its path
+pub struct ModelCache<M> {
+    /// Model name used in logs
+    name: &'static str,
+    last: Mutex<Option<(PathBuf, Arc<M>)>>,
+}
+
+impl<M> ModelCache<M> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Model at `path`, loaded with `load` unless it is the cached one
+    pub fn get_or_load<E>(
+        &self,
+        path: &Path,
+        load: impl FnOnce(&Path) -> Result<M, E>,
+    ) -> Result<Arc<M>, E> {
+        let mut last = self.last.lock();
+        if let Some((cached, model)) = last.as_ref() {
+            if cached == path {
+                return Ok(model.clone());
+            }
+        }
+
+        let model = Arc::new(load(path)?);
+        tracing::info!("Loaded {} model from {:?}", self.name, path);
+        *last = Some((path.to_path_buf(), model.clone()));
+        Ok(model)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_model_cache_reloads_on_new_path() {
+        let cache = ModelCache::new("test");
+        let loads = Cell::new(0);
+        let load = |path: &Path| -> Result<String, ()> {
+            loads.set(loads.get() + 1);
+            Ok(path.display().to_string())
+        };
+
+        let first = cache.get_or_load(Path::new("a.onnx"), load).unwrap();
+        let again = cache.get_or_load(Path::new("a.onnx"), load).unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+        assert_eq!(loads.get(), 1);
+
+        let other = cache.get_or_load(Path::new("b.onnx"), load).unwrap();
+        assert_eq!(*other, "b.onnx");
+        assert_eq!(loads.get(), 2);
+
+        // A failed load keeps the cached model
+        let failed = cache.get_or_load(Path::new("c.onnx"), |_| Err::<String, _>(()));
+        assert!(failed.is_err());
+        let kept = cache.get_or_load(Path::new("b.onnx"), load).unwrap();
+        assert!(Arc::ptr_eq(&other, &kept));
+        assert_eq!(loads.get(), 2);
+    }
+}
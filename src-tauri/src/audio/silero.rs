@@ -0,0 +1,210 @@
+//! Silero VAD
+//!
+//! Speech detection with the Silero VAD neural network (ONNX, run with
+//! tract). Slower than WebRTC VAD but far better at telling speech from
+//! music and at catching soft speech. The model file is downloaded by the
+//! `models` module.
+
+use super::{ModelCache, VadError};
+use std::path::Path;
+use std::sync::Arc;
+use tract_onnx::prelude::*;
+
+/// Sample rate the model is run at
+pub const SILERO_SAMPLE_RATE: u32 = 16000;
+
+/// Samples classified per model run (32 ms)
+pub const SILERO_WINDOW: usize = 512;
+
+/// Samples of the previous window prepended to each run
+const CONTEXT_SAMPLES: usize = 64;
+
+/// Shape of the recurrent state carried between runs
+const STATE_SHAPE: [usize; 3] = [2, 1, 128];
+
+type Plan = TypedRunnableModel<TypedModel>;
+
+/// Loaded Silero VAD model, shared by all detectors
+pub struct SileroModel {
+    plan: Plan,
+}
+
+impl SileroModel {
+    /// Load and optimize the ONNX model at `path`
+    pub fn load(path: &Path) -> Result<Self, VadError> {
+        let window = CONTEXT_SAMPLES + SILERO_WINDOW;
+        let plan = tract_onnx::onnx()
+            .model_for_path(path)
+            .and_then(|model| model.with_input_names(["input", "state", "sr"]))
+            .and_then(|model| model.with_input_fact(0, f32::fact([1, window]).into()))
+            .and_then(|model| model.with_input_fact(1, f32::fact(STATE_SHAPE).into()))
+            .and_then(|model| model.with_input_fact(2, i64::scalar_fact().into()))
+            .and_then(|model| model.into_optimized())
+            .and_then(|model| model.into_runnable())
+            .map_err(|e| VadError::ModelError(format!("{:#}", e)))?;
+        Ok(Self { plan })
+    }
+
+    /// Model at `path`, loaded once and reused while the path stays the same
+    pub fn load_cached(path: &Path) -> Result<Arc<Self>, VadError> {
+        static CACHE: ModelCache<SileroModel> = ModelCache::new("Silero VAD");
+        CACHE.get_or_load(path, Self::load)
+    }
+}
+
+/// Speech probability of consecutive windows of one recording
+pub struct SileroState {
+    model: Arc<SileroModel>,
+    state: Tensor,
+    /// Last samples of the previous window
+    context: Vec<f32>,
+}
+
+impl SileroState {
+    pub fn new(model: Arc<SileroModel>) -> Self {
+        Self {
+            model,
+            state: Tensor::zero::<f32>(&STATE_SHAPE).expect("static shape"),
+            context: vec![0.0; CONTEXT_SAMPLES],
+        }
+    }
+
+    /// Speech probability (0.0 to 1.0) of the next `SILERO_WINDOW` samples
+    pub fn probability(&mut self, window: &[f32]) -> Result<f32, VadError> {
+        let mut input = Vec::with_capacity(CONTEXT_SAMPLES + SILERO_WINDOW);
+        input.extend_from_slice(&self.context);
+        input.extend_from_slice(window);
+        input.resize(CONTEXT_SAMPLES + SILERO_WINDOW, 0.0);
+        self.context
+            .copy_from_slice(&input[input.len() - CONTEXT_SAMPLES..]);
+
+        let error = |e: TractError| VadError::ProcessingError(e.to_string());
+        let input = Tensor::from_shape(&[1, input.len()], &input).map_err(error)?;
+        let outputs = self
+            .model
+            .plan
+            .run(tvec!(
+                input.into(),
+                self.state.clone().into(),
+                tensor0(SILERO_SAMPLE_RATE as i64).into(),
+            ))
+            .map_err(error)?;
+
+        let probability = outputs[0]
+            .as_slice::<f32>()
+            .map_err(error)?
+            .first()
+            .copied()
+            .unwrap_or(0.0);
+        self.state = outputs[1].clone().into_tensor();
+        Ok(probability)
+    }
+}
+
+/// Classifies fixed-length frames from the model's 512-sample windows
+///
+/// A frame counts as speech when any window it overlaps reaches the
+/// threshold, so frame decisions line up with the WebRTC frames the rest of
+/// the VAD works with.
+pub struct SileroFrames {
+    model: SileroState,
+    threshold: f32,
+    frame_samples: usize,
+    /// Samples not yet classified
+    pending: Vec<f32>,
+    /// Speech decision of each classified window
+    windows: Vec<bool>,
+    /// Frames decided so far
+    frames_done: usize,
+}
+
+impl SileroFrames {
+    /// Classifier for 16 kHz audio in frames of `frame_samples`
+    pub fn new(model: Arc<SileroModel>, threshold: f32, frame_samples: usize) -> Self {
+        Self {
+            model: SileroState::new(model),
+            threshold,
+            frame_samples: frame_samples.max(1),
+            pending: Vec::with_capacity(SILERO_WINDOW),
+            windows: Vec::new(),
+            frames_done: 0,
+        }
+    }
+
+    /// Classify the complete windows of `audio`, appending decided frames
+    pub fn push(&mut self, audio: &[f32], frames: &mut Vec<bool>) {
+        for &sample in audio {
+            self.pending.push(sample);
+            if self.pending.len() == SILERO_WINDOW {
+                self.classify_pending();
+            }
+        }
+        self.emit_frames(self.windows.len() * SILERO_WINDOW, frames);
+    }
+
+    /// Classify the remaining samples; frames beyond `total_samples` are not
+    /// emitted, so the caller can treat a last partial frame as it likes
+    pub fn finish(mut self, total_samples: usize, frames: &mut Vec<bool>) {
+        if !self.pending.is_empty() {
+            self.classify_pending();
+        }
+        let complete = total_samples / self.frame_samples * self.frame_samples;
+        self.emit_frames(complete, frames);
+    }
+
+    fn classify_pending(&mut self) {
+        let probability = self.model.probability(&self.pending).unwrap_or_else(|e| {
+            tracing::warn!("Silero VAD failed, keeping window as speech: {}", e);
+            1.0
+        });
+        self.windows.push(probability >= self.threshold);
+        self.pending.clear();
+    }
+
+    /// Decide every frame that ends within the first `covered` samples
+    fn emit_frames(&mut self, covered: usize, frames: &mut Vec<bool>) {
+        while (self.frames_done + 1) * self.frame_samples <= covered {
+            frames.push(frame_decision(
+                &self.windows,
+                self.frame_samples,
+                self.frames_done,
+            ));
+            self.frames_done += 1;
+        }
+    }
+}
+
+/// Whether any window overlapping frame `index` is speech
+fn frame_decision(windows: &[bool], frame_samples: usize, index: usize) -> bool {
+    let start = index * frame_samples;
+    let end = start + frame_samples;
+    let first = start / SILERO_WINDOW;
+    let last = (end - 1) / SILERO_WINDOW;
+    windows
+        .iter()
+        .take(last + 1)
+        .skip(first)
+        .any(|&speech| speech)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_model() {
+        let result = SileroModel::load(Path::new("/nonexistent/silero_vad.onnx"));
+        assert!(matches!(result, Err(VadError::ModelError(_))));
+    }
+
+    #[test]
+    fn test_frame_decision_overlapping_windows() {
+        // Windows cover 0-511, 512-1023, 1024-1535
+        let windows = [false, true, false];
+        // 480-sample frames: 0-479, 480-959, 960-1439, 1440-1919
+        assert!(!frame_decision(&windows, 480, 0));
+        assert!(frame_decision(&windows, 480, 1));
+        assert!(frame_decision(&windows, 480, 2));
+        assert!(!frame_decision(&windows, 480, 3));
+    }
+}
@@ -0,0 +1,425 @@
+//! Speaker Verification
+//!
+//! Speaker embeddings from a neural speaker model (WeSpeaker ResNet34, ONNX,
+//! run with tract) compared against an enrolled profile, so speech from
+//! other people in the room can be dropped before transcription. The model
+//! file is downloaded by the `models` module.
+//!
+//! Embeddings are length-normalized, so similarity is the cosine between
+//! them. The similarity threshold is exposed as a sensitivity setting.
+
+use super::{ModelCache, SpeechMap};
+use std::f32::consts::PI;
+use std::path::Path;
+use std::sync::Arc;
+use thiserror::Error;
+use tract_onnx::prelude::*;
+
+/// Sample rate the analysis expects
+pub const SPEAKER_SAMPLE_RATE: u32 = 16000;
+
+/// Analysis frame length (25 ms at 16 kHz)
+const FRAME_LEN: usize = 400;
+
+/// Hop between frames (10 ms)
+const HOP_LEN: usize = 160;
+
+/// FFT length the frames are zero-padded to
+const FFT_LEN: usize = 512;
+
+/// Mel filterbank bins the model takes, between `MEL_LOW_HZ` and `MEL_HIGH_HZ`
+const FBANK_BINS: usize = 80;
+const MEL_LOW_HZ: f32 = 20.0;
+const MEL_HIGH_HZ: f32 = 8000.0;
+
+/// Pre-emphasis applied to each frame
+const PREEMPHASIS: f32 = 0.97;
+
+/// Frames more than this far below the loudest frame are treated as unvoiced (dB)
+const VOICED_RANGE_DB: f32 = 30.0;
+
+/// Voiced frames needed for a usable embedding (about 0.5 s)
+const MIN_VOICED_FRAMES: usize = 50;
+
+/// Length of the segments judged independently
+pub const SEGMENT_MS: u32 = 1500;
+
+type Plan = TypedRunnableModel<TypedModel>;
+
+/// Speaker model errors
+#[derive(Debug, Error)]
+pub enum SpeakerError {
+    #[error("Speaker model error: {0}")]
+    ModelError(String),
+
+    #[error("Speaker embedding failed: {0}")]
+    ProcessingError(String),
+}
+
+/// Length-normalized speaker embedding
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerEmbedding(pub Vec<f32>);
+
+impl SpeakerEmbedding {
+    /// Embedding scaled to unit length, None for a zero vector
+    pub fn normalized(mut values: Vec<f32>) -> Option<Self> {
+        let norm = values.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if !norm.is_finite() || norm <= f32::EPSILON {
+            return None;
+        }
+        values.iter_mut().for_each(|x| *x /= norm);
+        Some(Self(values))
+    }
+
+    /// Number of dimensions
+    pub fn dim(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Cosine similarity (-1.0 to 1.0), None when the dimensions differ
+    pub fn similarity(&self, other: &Self) -> Option<f32> {
+        if self.dim() != other.dim() {
+            return None;
+        }
+        Some(self.0.iter().zip(&other.0).map(|(a, b)| a * b).sum())
+    }
+}
+
+/// Loaded speaker-embedding model
+pub struct SpeakerModel {
+    plan: Plan,
+    /// Dimensions of the embeddings it produces
+    dim: usize,
+}
+
+impl SpeakerModel {
+    /// Load and optimize the ONNX model at `path`
+    pub fn load(path: &Path) -> Result<Self, SpeakerError> {
+        let plan = tract_onnx::onnx()
+            .model_for_path(path)
+            .and_then(|model| {
+                let shape = [1.into(), model.sym("T").into(), FBANK_BINS.into()];
+                model.with_input_fact(0, f32::fact::<&[TDim]>(&shape).into())
+            })
+            .and_then(|model| model.into_optimized())
+            .and_then(|model| model.into_runnable())
+            .map_err(|e| SpeakerError::ModelError(format!("{:#}", e)))?;
+        let dim = plan
+            .model()
+            .output_fact(0)
+            .ok()
+            .and_then(|fact| usize::try_from(fact.shape.last()?.as_i64()?).ok())
+            .ok_or_else(|| SpeakerError::ModelError("unknown embedding size".to_string()))?;
+        Ok(Self { plan, dim })
+    }
+
+    /// Model at `path`, loaded once and reused while the path stays the same
+    pub fn load_cached(path: &Path) -> Result<Arc<Self>, SpeakerError> {
+        static CACHE: ModelCache<SpeakerModel> = ModelCache::new("speaker");
+        CACHE.get_or_load(path, Self::load)
+    }
+
+    /// Dimensions of the embeddings the model produces
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Embedding of 16 kHz speech, None without enough voiced audio
+    pub fn embed(&self, samples: &[f32]) -> Result<Option<SpeakerEmbedding>, SpeakerError> {
+        let frames = voiced_fbank(samples);
+        if frames.len() < MIN_VOICED_FRAMES {
+            return Ok(None);
+        }
+
+        let error = |e: TractError| SpeakerError::ProcessingError(e.to_string());
+        let features: Vec<f32> = frames.concat();
+        let input = Tensor::from_shape(&[1, frames.len(), FBANK_BINS], &features).map_err(error)?;
+        let outputs = self.plan.run(tvec!(input.into())).map_err(error)?;
+        let values = outputs[0].as_slice::<f32>().map_err(error)?.to_vec();
+        Ok(SpeakerEmbedding::normalized(values))
+    }
+}
+
+/// Outcome of filtering a recording by speaker
+#[derive(Debug, Clone)]
+pub struct SpeakerFilterResult {
+    /// Segments kept, concatenated
+    pub audio: Vec<f32>,
+    pub segments_kept: usize,
+    pub segments_dropped: usize,
+    /// Where the kept segments sit in the input
+    pub kept: SpeechMap,
+}
+
+/// Drop segments whose voice doesn't match `profile`
+///
+/// `embed` computes the embedding of a segment (see `SpeakerModel::embed`).
+/// Segments it can't judge, too short, too quiet or failing, are kept.
+pub fn filter_by_speaker(
+    samples: &[f32],
+    profile: &SpeakerEmbedding,
+    threshold: f32,
+    mut embed: impl FnMut(&[f32]) -> Option<SpeakerEmbedding>,
+) -> SpeakerFilterResult {
+    let segment_len = (SPEAKER_SAMPLE_RATE * SEGMENT_MS / 1000) as usize;
+    let mut result = SpeakerFilterResult {
+        audio: Vec::with_capacity(samples.len()),
+        segments_kept: 0,
+        segments_dropped: 0,
+        kept: SpeechMap::default(),
+    };
+
+    for (index, segment) in samples.chunks(segment_len).enumerate() {
+        let matches = embed(segment)
+            .and_then(|embedding| embedding.similarity(profile))
+            .is_none_or(|similarity| similarity >= threshold);
+        if matches {
+            result.audio.extend_from_slice(segment);
+            result.segments_kept += 1;
+            let start = index * segment_len;
+            result.kept.push(start..start + segment.len());
+        } else {
+            result.segments_dropped += 1;
+        }
+    }
+    result
+}
+
+/// Log mel filterbank energies of the voiced frames, mean-normalized
+///
+/// Computed like Kaldi's fbank (which the model was trained on): samples
+/// scaled to the 16-bit range, DC removal, pre-emphasis and a Povey window.
+fn voiced_fbank(samples: &[f32]) -> Vec<Vec<f32>> {
+    if samples.len() < FRAME_LEN {
+        return Vec::new();
+    }
+
+    let window: Vec<f32> = (0..FRAME_LEN)
+        .map(|i| (0.5 - 0.5 * (2.0 * PI * i as f32 / (FRAME_LEN - 1) as f32).cos()).powf(0.85))
+        .collect();
+    let filters = mel_filterbank();
+
+    let mut frames = Vec::new();
+    let mut energies = Vec::new();
+    let mut start = 0;
+    while start + FRAME_LEN <= samples.len() {
+        let frame = &samples[start..start + FRAME_LEN];
+        let energy = frame.iter().map(|s| s * s).sum::<f32>() / FRAME_LEN as f32;
+
+        let mean = frame.iter().sum::<f32>() / FRAME_LEN as f32;
+        let mut re: Vec<f32> = frame.iter().map(|s| (s - mean) * 32768.0).collect();
+        for i in (1..FRAME_LEN).rev() {
+            re[i] -= PREEMPHASIS * re[i - 1];
+        }
+        re[0] -= PREEMPHASIS * re[0];
+        re.iter_mut().zip(&window).for_each(|(s, w)| *s *= w);
+        re.resize(FFT_LEN, 0.0);
+        let mut im = vec![0.0f32; FFT_LEN];
+        fft(&mut re, &mut im);
+        let power: Vec<f32> = re[..FFT_LEN / 2]
+            .iter()
+            .zip(&im)
+            .map(|(r, i)| r * r + i * i)
+            .collect();
+
+        let log_mel: Vec<f32> = filters
+            .iter()
+            .map(|filter| {
+                let energy: f32 = filter
+                    .iter()
+                    .map(|&(bin, weight)| power[bin] * weight)
+                    .sum();
+                energy.max(f32::EPSILON).ln()
+            })
+            .collect();
+
+        frames.push(log_mel);
+        energies.push(10.0 * (energy + 1e-12).log10());
+        start += HOP_LEN;
+    }
+
+    let loudest = energies.iter().copied().fold(f32::MIN, f32::max);
+    let mut voiced: Vec<Vec<f32>> = frames
+        .into_iter()
+        .zip(energies)
+        .filter(|(_, energy)| *energy >= loudest - VOICED_RANGE_DB && *energy > -70.0)
+        .map(|(frame, _)| frame)
+        .collect();
+
+    if !voiced.is_empty() {
+        let count = voiced.len() as f32;
+        let mut mean = vec![0.0f32; FBANK_BINS];
+        for frame in &voiced {
+            for (m, v) in mean.iter_mut().zip(frame) {
+                *m += v / count;
+            }
+        }
+        for frame in &mut voiced {
+            frame.iter_mut().zip(&mean).for_each(|(v, m)| *v -= m);
+        }
+    }
+    voiced
+}
+
+/// Triangular filters, evenly spaced on the mel scale, as (FFT bin, weight) lists
+fn mel_filterbank() -> Vec<Vec<(usize, f32)>> {
+    let to_mel = |hz: f32| 1127.0 * (1.0 + hz / 700.0).ln();
+    let bin_hz = SPEAKER_SAMPLE_RATE as f32 / FFT_LEN as f32;
+
+    let (low, high) = (to_mel(MEL_LOW_HZ), to_mel(MEL_HIGH_HZ));
+    let step = (high - low) / (FBANK_BINS + 1) as f32;
+
+    (0..FBANK_BINS)
+        .map(|i| {
+            let left = low + step * i as f32;
+            let (center, right) = (left + step, left + 2.0 * step);
+            (0..FFT_LEN / 2)
+                .filter_map(|bin| {
+                    let mel = to_mel(bin as f32 * bin_hz);
+                    let weight = if mel <= left || mel >= right {
+                        0.0
+                    } else if mel <= center {
+                        (mel - left) / (center - left)
+                    } else {
+                        (right - mel) / (right - center)
+                    };
+                    (weight > 0.0).then_some((bin, weight))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// In-place radix-2 FFT (length must be a power of two)
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Harmonic "voice" with a pitch and a formant peak
+    fn voice(pitch: f32, formant: f32, seconds: f32, phase: f32) -> Vec<f32> {
+        let len = (SPEAKER_SAMPLE_RATE as f32 * seconds) as usize;
+        (0..len)
+            .map(|i| {
+                let t = i as f32 / SPEAKER_SAMPLE_RATE as f32;
+                let mut sample = 0.0;
+                let mut harmonic = pitch;
+                while harmonic < 7000.0 {
+                    let gain = 1.0 / (1.0 + ((harmonic - formant) / 400.0).powi(2));
+                    sample += gain * (2.0 * PI * harmonic * t + phase * harmonic).sin();
+                    harmonic += pitch;
+                }
+                0.1 * sample
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fft_matches_dft() {
+        let mut re: Vec<f32> = (0..8).map(|i| (i as f32 * 0.7).sin()).collect();
+        let mut im = vec![0.0; 8];
+        let input = re.clone();
+        fft(&mut re, &mut im);
+
+        for k in 0..8 {
+            let (mut r, mut i) = (0.0, 0.0);
+            for (n, x) in input.iter().enumerate() {
+                let angle = -2.0 * PI * k as f32 * n as f32 / 8.0;
+                r += x * angle.cos();
+                i += x * angle.sin();
+            }
+            assert!((re[k] - r).abs() < 1e-4 && (im[k] - i).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_fbank_frames() {
+        // 1 s at a 10 ms hop, every frame voiced
+        let frames = voiced_fbank(&voice(120.0, 600.0, 1.0, 0.0));
+        assert_eq!(frames.len(), 1 + (16000 - FRAME_LEN) / HOP_LEN);
+        assert!(frames.iter().all(|frame| frame.len() == FBANK_BINS));
+        // Mean-normalized over time
+        let mean: f32 = frames.iter().map(|frame| frame[10]).sum::<f32>() / frames.len() as f32;
+        assert!(mean.abs() < 1e-3, "{}", mean);
+
+        assert!(voiced_fbank(&vec![0.0; 32000]).is_empty());
+        assert!(voiced_fbank(&voice(120.0, 600.0, 0.2, 0.0)).len() < MIN_VOICED_FRAMES);
+    }
+
+    #[test]
+    fn test_embedding_is_length_normalized() {
+        let a = SpeakerEmbedding::normalized(vec![3.0, 4.0]).unwrap();
+        assert_eq!(a.0, vec![0.6, 0.8]);
+        let b = SpeakerEmbedding::normalized(vec![30.0, 40.0]).unwrap();
+        assert!((a.similarity(&b).unwrap() - 1.0).abs() < 1e-6);
+
+        assert!(SpeakerEmbedding::normalized(vec![0.0, 0.0]).is_none());
+        let other_model = SpeakerEmbedding::normalized(vec![1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(a.similarity(&other_model), None);
+    }
+
+    #[test]
+    fn test_filter_drops_other_speaker() {
+        let profile = SpeakerEmbedding::normalized(vec![1.0, 0.0]).unwrap();
+        let mut samples = vec![0.1; 24000];
+        samples.extend(vec![0.2; 24000]);
+        samples.extend(vec![0.0; 24000]);
+        samples.extend(vec![0.1; 12000]);
+
+        // Segments are told apart by their level: 0.1 is the enrolled user,
+        // 0.2 someone else, silence can't be judged
+        let result = filter_by_speaker(&samples, &profile, 0.8, |segment| {
+            if segment[0] == 0.1 {
+                SpeakerEmbedding::normalized(vec![0.9, 0.1])
+            } else if segment[0] == 0.2 {
+                SpeakerEmbedding::normalized(vec![0.1, 0.9])
+            } else {
+                None
+            }
+        });
+        assert_eq!(result.segments_kept, 3);
+        assert_eq!(result.segments_dropped, 1);
+        assert_eq!(result.audio.len(), 2 * 24000 + 12000);
+        assert_eq!(result.kept, SpeechMap::new([0..24000, 48000..84000]));
+    }
+
+    #[test]
+    fn test_load_missing_model() {
+        let result = SpeakerModel::load(Path::new("/nonexistent/speaker.onnx"));
+        assert!(matches!(result, Err(SpeakerError::ModelError(_))));
+    }
+}
@@ -0,0 +1,730 @@
+//! Audio Transcoding
+//!
+//! Converts saved recordings for playback and export, and decodes audio
+//! files opened for transcription. WAV is handled in process, and MP3, AAC,
+//! FLAC and Ogg Vorbis files are decoded with symphonia; everything else goes
+//! through `ffmpeg` when it is installed. Transcoded files are cached so
+//! range requests don't re-encode. History recordings can also be stored as
+//! FLAC or Opus, encoded by `ffmpeg` once at save time.
+
+use crate::config::HistoryAudioCodec;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// Sample rates offered for transcoding (Hz)
+pub const TRANSCODE_SAMPLE_RATES: [u32; 6] = [8000, 12000, 16000, 24000, 44100, 48000];
+
+/// Lowest bitrate for compressed formats (kbps)
+pub const MIN_BITRATE_KBPS: u32 = 8;
+
+/// Highest bitrate for compressed formats (kbps)
+pub const MAX_BITRATE_KBPS: u32 = 320;
+
+/// Default bitrate for compressed formats (kbps, plenty for speech)
+pub const DEFAULT_BITRATE_KBPS: u32 = 32;
+
+/// Bitrate of history recordings stored as Opus (kbps)
+const STORAGE_BITRATE_KBPS: u32 = 24;
+
+/// Extensions of audio files that can be opened for transcription
+pub const IMPORT_EXTENSIONS: [&str; 9] =
+    ["wav", "mp3", "m4a", "aac", "ogg", "opus", "flac", "webm", "wma"];
+
+/// Import extensions decoded in process by symphonia
+const NATIVE_DECODE_EXTENSIONS: [&str; 5] = ["mp3", "m4a", "aac", "ogg", "flac"];
+
+/// Sample rate ffmpeg decodes imported files to (what Whisper expects)
+const IMPORT_SAMPLE_RATE: u32 = 16000;
+
+/// Cached transcodes not played for this long are removed at startup
+pub const TRANSCODE_CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Transcoding errors
+#[derive(Debug, Error)]
+pub enum TranscodeError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid transcode options: {0}")]
+    InvalidOptions(String),
+
+    #[error("Failed to read recording: {0}")]
+    Read(String),
+
+    #[error("ffmpeg is required for {0} output but was not found")]
+    EncoderMissing(AudioFormat),
+
+    #[error("ffmpeg is required for {0} storage but was not found")]
+    StorageEncoderMissing(&'static str),
+
+    #[error("Encoding failed: {0}")]
+    Encode(String),
+
+    #[error("ffmpeg is required to read .{0} files but was not found")]
+    DecoderMissing(String),
+}
+
+/// Output format of a transcoded recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    #[default]
+    Wav,
+    Opus,
+    Mp3,
+}
+
+impl AudioFormat {
+    /// Parse a format name (as used in protocol query strings)
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "wav" => Some(Self::Wav),
+            "opus" | "ogg" => Some(Self::Opus),
+            "mp3" => Some(Self::Mp3),
+            _ => None,
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Wav => "audio/wav",
+            Self::Opus => "audio/ogg",
+            Self::Mp3 => "audio/mpeg",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Opus => "opus",
+            Self::Mp3 => "mp3",
+        }
+    }
+}
+
+impl std::fmt::Display for AudioFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// How to transcode a recording
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TranscodeOptions {
+    pub format: AudioFormat,
+    /// Bitrate for compressed formats (None = `DEFAULT_BITRATE_KBPS`)
+    pub bitrate_kbps: Option<u32>,
+    /// Output sample rate (None = keep the recording's rate)
+    pub sample_rate: Option<u32>,
+}
+
+impl TranscodeOptions {
+    /// Parse options from a query string (`format=opus&bitrate=24&rate=16000`)
+    pub fn from_query(query: &str) -> Result<Self, TranscodeError> {
+        let mut options = Self::default();
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let invalid = || TranscodeError::InvalidOptions(format!("{}={}", key, value));
+            match key {
+                "format" => options.format = AudioFormat::parse(value).ok_or_else(invalid)?,
+                "bitrate" => options.bitrate_kbps = Some(value.parse().map_err(|_| invalid())?),
+                "rate" => options.sample_rate = Some(value.parse().map_err(|_| invalid())?),
+                _ => {}
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+
+    /// Check the bitrate and sample rate are supported by the format
+    pub fn validate(&self) -> Result<(), TranscodeError> {
+        if let Some(bitrate) = self.bitrate_kbps {
+            if !(MIN_BITRATE_KBPS..=MAX_BITRATE_KBPS).contains(&bitrate) {
+                return Err(TranscodeError::InvalidOptions(format!(
+                    "bitrate must be {} to {} kbps",
+                    MIN_BITRATE_KBPS, MAX_BITRATE_KBPS
+                )));
+            }
+        }
+        if let Some(rate) = self.sample_rate {
+            if !TRANSCODE_SAMPLE_RATES.contains(&rate) {
+                return Err(TranscodeError::InvalidOptions(format!(
+                    "unsupported sample rate {}Hz",
+                    rate
+                )));
+            }
+            // Opus only encodes at these rates; MP3 has no 12kHz mode
+            let supported = match self.format {
+                AudioFormat::Wav => true,
+                AudioFormat::Opus => [8000, 12000, 16000, 24000, 48000].contains(&rate),
+                AudioFormat::Mp3 => rate != 12000,
+            };
+            if !supported {
+                return Err(TranscodeError::InvalidOptions(format!(
+                    "{} does not support {}Hz",
+                    self.format, rate
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn bitrate(&self) -> u32 {
+        self.bitrate_kbps.unwrap_or(DEFAULT_BITRATE_KBPS)
+    }
+
+    /// Name of the cached file for a recording
+    fn cache_name(&self, stem: &str) -> String {
+        let rate = self
+            .sample_rate
+            .map_or_else(|| "orig".to_string(), |r| r.to_string());
+        match self.format {
+            AudioFormat::Wav => format!("{}-{}.wav", stem, rate),
+            format => format!("{}-{}-{}k.{}", stem, rate, self.bitrate(), format.extension()),
+        }
+    }
+}
+
+/// MIME type of a saved recording, from its extension
+pub fn recording_mime_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    match extension.as_str() {
+        "flac" => "audio/flac",
+        "opus" | "ogg" => "audio/ogg",
+        "mp3" => "audio/mpeg",
+        _ => "audio/wav",
+    }
+}
+
+/// Re-encode a saved WAV recording with `codec`, replacing it
+///
+/// Returns the path of the encoded file. The WAV is only deleted once the
+/// encoded file is complete, so a failure leaves the recording untouched.
+pub fn compress_recording(
+    source: &Path,
+    codec: HistoryAudioCodec,
+) -> Result<PathBuf, TranscodeError> {
+    let target = source.with_extension(codec.extension());
+    let partial = target.with_extension("partial");
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-nostdin", "-y", "-i"])
+        .arg(source)
+        .args(["-vn", "-ac", "1"]);
+    match codec {
+        HistoryAudioCodec::Wav => return Ok(source.to_path_buf()),
+        HistoryAudioCodec::Flac => command.args(["-c:a", "flac", "-f", "flac"]),
+        HistoryAudioCodec::Opus => command
+            .args(["-c:a", "libopus", "-application", "voip"])
+            .args(["-b:a", &format!("{}k", STORAGE_BITRATE_KBPS)])
+            .args(["-f", "ogg"]),
+    };
+    let output = command
+        .arg(&partial)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                TranscodeError::StorageEncoderMissing(codec.extension())
+            }
+            _ => TranscodeError::Io(e),
+        })?;
+    if !output.status.success() {
+        let _ = std::fs::remove_file(&partial);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TranscodeError::Encode(stderr.trim().to_string()));
+    }
+
+    std::fs::rename(&partial, &target)?;
+    std::fs::remove_file(source)?;
+    Ok(target)
+}
+
+/// Transcode a saved recording, returning the encoded bytes
+pub fn transcode(source: &Path, options: &TranscodeOptions) -> Result<Vec<u8>, TranscodeError> {
+    options.validate()?;
+    match options.format {
+        AudioFormat::Wav => transcode_wav(source, options.sample_rate),
+        format => transcode_ffmpeg(source, format, options),
+    }
+}
+
+fn transcode_wav(source: &Path, sample_rate: Option<u32>) -> Result<Vec<u8>, TranscodeError> {
+    // Recordings stored as FLAC or Opus are decoded first
+    let (samples, source_rate) = decode_audio_file(source)?;
+    let target_rate = sample_rate.unwrap_or(source_rate);
+    let samples =
+        super::resample(&samples, source_rate, target_rate).map_err(TranscodeError::Encode)?;
+    Ok(super::encode_wav(&samples, target_rate, 1))
+}
+
+fn transcode_ffmpeg(
+    source: &Path,
+    format: AudioFormat,
+    options: &TranscodeOptions,
+) -> Result<Vec<u8>, TranscodeError> {
+    let (codec, container) = match format {
+        AudioFormat::Opus => ("libopus", "ogg"),
+        _ => ("libmp3lame", "mp3"),
+    };
+
+    let mut command = Command::new("ffmpeg");
+    command
+        .args(["-hide_banner", "-loglevel", "error", "-nostdin", "-i"])
+        .arg(source)
+        .args(["-vn", "-ac", "1", "-c:a", codec])
+        .args(["-b:a", &format!("{}k", options.bitrate())]);
+    if let Some(rate) = options.sample_rate {
+        command.args(["-ar", &rate.to_string()]);
+    }
+    command
+        .args(["-f", container, "pipe:1"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    let output = command.output().map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => TranscodeError::EncoderMissing(format),
+        _ => TranscodeError::Io(e),
+    })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TranscodeError::Encode(stderr.trim().to_string()));
+    }
+    Ok(output.stdout)
+}
+
+/// Whether a file has the extension of an audio format that can be transcribed
+pub fn is_importable_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMPORT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+}
+
+/// Decode an audio file to mono samples, returning them with their sample rate
+///
+/// WAV is read with hound and the common compressed formats with symphonia.
+/// Other formats, and files symphonia can't decode (e.g. Opus in Ogg), are
+/// decoded by `ffmpeg`.
+pub fn decode_audio_file(path: &Path) -> Result<(Vec<f32>, u32), TranscodeError> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if extension == "wav" {
+        return super::read_wav_mono(path).map_err(|e| TranscodeError::Read(e.to_string()));
+    }
+
+    if NATIVE_DECODE_EXTENSIONS.contains(&extension.as_str()) {
+        match decode_native(path) {
+            Ok(decoded) => return Ok(decoded),
+            Err(native_error) => {
+                tracing::debug!("Falling back to ffmpeg for {}: {}", path.display(), native_error);
+                return match decode_with_ffmpeg(path, extension) {
+                    Err(TranscodeError::DecoderMissing(_)) => Err(native_error),
+                    result => result,
+                };
+            }
+        }
+    }
+    decode_with_ffmpeg(path, extension)
+}
+
+/// Decode the first audio track of a file with symphonia, mixed down to mono
+fn decode_native(path: &Path) -> Result<(Vec<f32>, u32), TranscodeError> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+    use symphonia::core::errors::Error as DecodeError;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::probe::Hint;
+
+    let read_error = |e: DecodeError| TranscodeError::Read(e.to_string());
+
+    let stream = MediaSourceStream::new(Box::new(std::fs::File::open(path)?), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut format = symphonia::default::get_probe()
+        .format(&hint, stream, &Default::default(), &Default::default())
+        .map_err(read_error)?
+        .format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| TranscodeError::Read("no audio track".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate.unwrap_or(0);
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(read_error)?;
+
+    let mut samples = Vec::new();
+    let mut buffer: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(read_error(e)),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            // A corrupt frame only loses that frame
+            Err(DecodeError::DecodeError(e)) => {
+                tracing::debug!("Skipping undecodable frame: {}", e);
+                continue;
+            }
+            Err(e) => return Err(read_error(e)),
+        };
+
+        let spec = *decoded.spec();
+        sample_rate = spec.rate;
+        let channels = spec.channels.count().max(1);
+        if buffer.as_ref().is_none_or(|b| b.capacity() < decoded.capacity()) {
+            buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+        }
+        let buffer = buffer.as_mut().expect("buffer was just created");
+        buffer.copy_interleaved_ref(decoded);
+        samples.extend(
+            buffer
+                .samples()
+                .chunks(channels)
+                .map(|frame| frame.iter().sum::<f32>() / channels as f32),
+        );
+    }
+
+    if sample_rate == 0 {
+        return Err(TranscodeError::Read("unknown sample rate".to_string()));
+    }
+    Ok((samples, sample_rate))
+}
+
+/// Decode a file to mono at `IMPORT_SAMPLE_RATE` with `ffmpeg`
+fn decode_with_ffmpeg(path: &Path, extension: String) -> Result<(Vec<f32>, u32), TranscodeError> {
+    let output = Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-nostdin", "-i"])
+        .arg(path)
+        .args(["-vn", "-ac", "1", "-ar", &IMPORT_SAMPLE_RATE.to_string()])
+        .args(["-f", "f32le", "pipe:1"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => TranscodeError::DecoderMissing(extension),
+            _ => TranscodeError::Io(e),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(TranscodeError::Read(stderr.trim().to_string()));
+    }
+
+    let samples = output
+        .stdout
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    Ok((samples, IMPORT_SAMPLE_RATE))
+}
+
+/// Directory holding transcoded recordings, private to the user
+pub fn transcode_cache_dir() -> PathBuf {
+    crate::config::cache_dir().join("transcoded")
+}
+
+/// Transcode a recording into the cache, reusing an earlier result
+pub fn cached_transcode(
+    source: &Path,
+    options: &TranscodeOptions,
+) -> Result<PathBuf, TranscodeError> {
+    let stem = source
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .ok_or_else(|| TranscodeError::Read(format!("{}", source.display())))?;
+    let dir = transcode_cache_dir();
+    let path = dir.join(options.cache_name(stem));
+    if path.exists() {
+        // Playing a transcode keeps it from being pruned
+        let _ = std::fs::File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()));
+        return Ok(path);
+    }
+
+    let bytes = transcode(source, options)?;
+    crate::config::create_private_dir(&dir)?;
+    write_replacing(&path, &bytes)?;
+    Ok(path)
+}
+
+/// Write `bytes` to a uniquely named file next to `path`, then rename it over `path`
+///
+/// Readers never see a partial file, and concurrent writers each rename a
+/// complete file instead of sharing (and truncating) one temporary file.
+fn write_replacing(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let partial = path.with_extension(format!("{}.partial", uuid::Uuid::new_v4().simple()));
+    let written = std::fs::File::create(&partial)
+        .and_then(|mut file| file.write_all(bytes))
+        .and_then(|()| std::fs::rename(&partial, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&partial);
+    }
+    written
+}
+
+/// Remove cached transcodes of a recording (by file stem, i.e. entry ID)
+pub fn remove_cached_transcodes(stem: &str) {
+    let Ok(entries) = std::fs::read_dir(transcode_cache_dir()) else {
+        return;
+    };
+    let prefix = format!("{}-", stem);
+    for entry in entries.flatten() {
+        if entry.file_name().to_string_lossy().starts_with(&prefix) {
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// Remove cached transcodes older than `max_age`
+///
+/// Also removes the cache of earlier versions, kept in the shared temporary
+/// directory.
+pub fn prune_transcode_cache(max_age: Duration) {
+    let _ = std::fs::remove_dir_all(std::env::temp_dir().join("gigawhisper-transcoded"));
+    let removed = prune_dir(&transcode_cache_dir(), max_age);
+    if removed > 0 {
+        tracing::debug!("Removed {} stale transcoded recording(s)", removed);
+    }
+}
+
+/// Remove the files of `dir` last modified more than `max_age` ago
+fn prune_dir(dir: &Path, max_age: Duration) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let now = SystemTime::now();
+    entries
+        .flatten()
+        .filter(|entry| {
+            entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| now.duration_since(modified).unwrap_or_default() > max_age)
+        })
+        .filter(|entry| std::fs::remove_file(entry.path()).is_ok())
+        .count()
+}
+
+/// Read the inclusive byte range `start..=end` of a file
+///
+/// Only the range is read, so seeking in a long recording doesn't load the
+/// whole file.
+pub fn read_byte_range(path: &Path, start: u64, end: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(start))?;
+    let mut bytes = Vec::new();
+    file.take(end - start + 1).read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Parse a single-range `Range` header against a body of `len` bytes
+///
+/// Returns the inclusive byte range, or None when the header is missing,
+/// malformed, asks for several ranges or is unsatisfiable.
+pub fn parse_byte_range(header: &str, len: u64) -> Option<(u64, u64)> {
+    let spec = header.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') || len == 0 {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix: u64 = suffix.parse().ok()?;
+            (len.saturating_sub(suffix), len - 1)
+        }
+        (start, "") => (start.parse().ok()?, len - 1),
+        (start, end) => (start.parse().ok()?, end.parse::<u64>().ok()?.min(len - 1)),
+    };
+    (start <= end && start < len).then_some((start, end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_is_importable_audio() {
+        assert!(is_importable_audio(Path::new("/tmp/meeting.MP3")));
+        assert!(is_importable_audio(Path::new("note.m4a")));
+        assert!(!is_importable_audio(Path::new("notes.txt")));
+        assert!(!is_importable_audio(Path::new("wav")));
+    }
+
+    #[test]
+    fn test_parse_byte_range() {
+        assert_eq!(parse_byte_range("bytes=0-99", 1000), Some((0, 99)));
+        assert_eq!(parse_byte_range("bytes=500-", 1000), Some((500, 999)));
+        assert_eq!(parse_byte_range("bytes=-100", 1000), Some((900, 999)));
+        assert_eq!(parse_byte_range("bytes=900-5000", 1000), Some((900, 999)));
+        assert_eq!(parse_byte_range("bytes=1000-", 1000), None);
+        assert_eq!(parse_byte_range("bytes=0-1,5-9", 1000), None);
+        assert_eq!(parse_byte_range("items=0-9", 1000), None);
+        assert_eq!(parse_byte_range("bytes=9-0", 1000), None);
+    }
+
+    #[test]
+    fn test_read_byte_range() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a.wav");
+        std::fs::write(&path, b"0123456789").unwrap();
+        assert_eq!(read_byte_range(&path, 2, 5).unwrap(), b"2345");
+        assert_eq!(read_byte_range(&path, 9, 9).unwrap(), b"9");
+    }
+
+    #[test]
+    fn test_write_replacing_concurrently() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("a-opus-32k.ogg");
+        let bytes = vec![7u8; 256 * 1024];
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| write_replacing(&path, &bytes).unwrap());
+            }
+        });
+
+        assert_eq!(std::fs::read(&path).unwrap(), bytes);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_prune_dir_keeps_recent_files() {
+        let dir = TempDir::new().unwrap();
+        let old = dir.path().join("old-orig.wav");
+        let recent = dir.path().join("recent-orig.wav");
+        std::fs::write(&old, b"old").unwrap();
+        std::fs::write(&recent, b"recent").unwrap();
+        let week_ago = SystemTime::now() - Duration::from_secs(8 * 24 * 60 * 60);
+        std::fs::File::options()
+            .append(true)
+            .open(&old)
+            .unwrap()
+            .set_modified(week_ago)
+            .unwrap();
+
+        assert_eq!(prune_dir(dir.path(), TRANSCODE_CACHE_MAX_AGE), 1);
+        assert!(!old.exists());
+        assert!(recent.exists());
+    }
+
+    #[test]
+    fn test_options_from_query() {
+        let options = TranscodeOptions::from_query("format=opus&bitrate=24&rate=16000").unwrap();
+        assert_eq!(options.format, AudioFormat::Opus);
+        assert_eq!(options.bitrate_kbps, Some(24));
+        assert_eq!(options.sample_rate, Some(16000));
+        assert_eq!(TranscodeOptions::from_query("").unwrap(), TranscodeOptions::default());
+
+        assert!(TranscodeOptions::from_query("format=flac").is_err());
+        assert!(TranscodeOptions::from_query("bitrate=1000").is_err());
+        assert!(TranscodeOptions::from_query("format=opus&rate=44100").is_err());
+        assert!(TranscodeOptions::from_query("format=mp3&rate=12000").is_err());
+        assert!(TranscodeOptions::from_query("format=wav&rate=12345").is_err());
+    }
+
+    #[test]
+    fn test_recording_mime_type() {
+        assert_eq!(recording_mime_type(Path::new("/audio/a.wav")), "audio/wav");
+        assert_eq!(recording_mime_type(Path::new("/audio/a.FLAC")), "audio/flac");
+        assert_eq!(recording_mime_type(Path::new("/audio/a.opus")), "audio/ogg");
+    }
+
+    #[test]
+    fn test_compress_recording_keeps_wav() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("entry.wav");
+        std::fs::write(&source, crate::audio::encode_wav(&[0.0; 160], 16000, 1)).unwrap();
+        assert_eq!(compress_recording(&source, HistoryAudioCodec::Wav).unwrap(), source);
+        assert!(source.exists());
+    }
+
+    #[test]
+    fn test_cache_names_differ_by_options() {
+        let wav = TranscodeOptions::default();
+        let opus = TranscodeOptions {
+            format: AudioFormat::Opus,
+            bitrate_kbps: None,
+            sample_rate: Some(16000),
+        };
+        assert_eq!(wav.cache_name("abc"), "abc-orig.wav");
+        assert_eq!(opus.cache_name("abc"), "abc-16000-32k.opus");
+    }
+
+    #[test]
+    fn test_transcode_wav_resamples() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("entry.wav");
+        let samples: Vec<f32> = (0..48000).map(|i| (i as f32 * 0.05).sin() * 0.5).collect();
+        std::fs::write(&source, crate::audio::encode_wav(&samples, 48000, 1)).unwrap();
+
+        let options = TranscodeOptions {
+            sample_rate: Some(16000),
+            ..Default::default()
+        };
+        let bytes = transcode(&source, &options).unwrap();
+        let reader = hound::WavReader::new(std::io::Cursor::new(bytes)).unwrap();
+        assert_eq!(reader.spec().sample_rate, 16000);
+        assert_eq!(reader.spec().channels, 1);
+        let len = reader.len() as i64;
+        assert!((len - 16000).abs() < 1024, "got {} samples", len);
+    }
+
+    #[test]
+    fn test_decode_native_mixes_to_mono() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("stereo.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 22050,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..22050 {
+            writer.write_sample(i16::MAX / 2).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let (samples, sample_rate) = decode_native(&path).unwrap();
+        assert_eq!(sample_rate, 22050);
+        assert_eq!(samples.len(), 22050);
+        assert!((samples[100] - 0.25).abs() < 0.01, "got {}", samples[100]);
+    }
+
+    #[test]
+    fn test_decode_native_rejects_garbage() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("broken.mp3");
+        std::fs::write(&path, b"not really audio").unwrap();
+        assert!(matches!(decode_native(&path), Err(TranscodeError::Read(_))));
+    }
+}
@@ -1,9 +1,15 @@
 //! Voice Activity Detection (VAD)
 //!
-//! Sophisticated voice activity detection using WebRTC VAD.
+//! Sophisticated voice activity detection using WebRTC VAD, or Silero VAD
+//! when its model is given.
 //! This helps reduce processing time by filtering out silent segments
 //! before sending audio to whisper.cpp.
 
+use super::{SileroFrames, SileroModel, SILERO_SAMPLE_RATE};
+use crate::config::VadSettings;
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use webrtc_vad::{Vad, SampleRate, VadMode};
 
 /// VAD aggressiveness level (maps to WebRTC VadMode)
@@ -21,6 +27,16 @@ pub enum VadAggressiveness {
 }
 
 impl VadAggressiveness {
+    /// Speech probability a Silero window needs to count as speech
+    pub fn silero_threshold(self) -> f32 {
+        match self {
+            Self::Quality => 0.3,
+            Self::LowBitrate => 0.4,
+            Self::Aggressive => 0.5,
+            Self::VeryAggressive => 0.65,
+        }
+    }
+
     /// Convert to WebRTC VadMode
     fn to_vad_mode(self) -> VadMode {
         match self {
@@ -58,6 +74,23 @@ impl Default for VadConfig {
     }
 }
 
+impl VadConfig {
+    /// Configuration for the VAD settings of an input device
+    pub fn from_settings(settings: &VadSettings) -> Self {
+        Self {
+            mode: match settings.aggressiveness {
+                0 => VadAggressiveness::Quality,
+                1 => VadAggressiveness::LowBitrate,
+                2 => VadAggressiveness::Aggressive,
+                _ => VadAggressiveness::VeryAggressive,
+            },
+            min_speech_duration_ms: settings.min_speech_duration_ms,
+            padding_ms: settings.padding_ms,
+            ..Self::default()
+        }
+    }
+}
+
 /// VAD processing result
 #[derive(Debug, Clone)]
 pub struct VadResult {
@@ -71,24 +104,122 @@ pub struct VadResult {
     pub speech_segments: usize,
     /// Percentage of audio that was speech
     pub speech_percentage: f32,
+    /// Where the kept audio sits in the input
+    pub kept: SpeechMap,
+}
+
+/// Where the samples kept by an audio filter sit in its input
+///
+/// Holds the input ranges in the order they were kept, so a position in the
+/// filtered audio can be traced back to the recording.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpeechMap {
+    spans: Vec<Range<usize>>,
+}
+
+impl SpeechMap {
+    /// Map of the input ranges kept, in order
+    pub fn new(spans: impl IntoIterator<Item = Range<usize>>) -> Self {
+        let mut map = Self::default();
+        spans.into_iter().for_each(|span| map.push(span));
+        map
+    }
+
+    /// Map of a filter that kept all `len` samples
+    pub fn identity(len: usize) -> Self {
+        let mut map = Self::default();
+        map.push(0..len);
+        map
+    }
+
+    /// Add the next kept range, merging it with the previous one when contiguous
+    pub fn push(&mut self, span: Range<usize>) {
+        if span.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.end == span.start => last.end = span.end,
+            _ => self.spans.push(span),
+        }
+    }
+
+    /// Input position of sample `kept` of the filtered audio
+    ///
+    /// Positions past the kept audio continue after the last range.
+    pub fn to_input(&self, kept: usize) -> usize {
+        let mut offset = kept;
+        for span in &self.spans {
+            if offset < span.len() {
+                return span.start + offset;
+            }
+            offset -= span.len();
+        }
+        self.spans.last().map_or(kept, |span| span.end + offset)
+    }
+
+    /// Input time of the span `start_ms..end_ms` of the filtered audio
+    ///
+    /// The end is taken from the last sample of the span, so a span ending at
+    /// a cut doesn't stretch over the audio removed there.
+    pub fn to_input_ms(&self, start_ms: u64, end_ms: u64, sample_rate: u32) -> (u64, u64) {
+        let to_sample = |ms: u64| (ms * sample_rate as u64 / 1000) as usize;
+        let to_ms = |sample: usize| sample as u64 * 1000 / sample_rate as u64;
+
+        let start = self.to_input(to_sample(start_ms));
+        let end = match to_sample(end_ms) {
+            end if end > to_sample(start_ms) => self.to_input(end - 1) + 1,
+            _ => start,
+        };
+        (to_ms(start), to_ms(end))
+    }
+
+    /// Map of `later`, a filter run on this filter's output, onto this input
+    pub fn then(&self, later: &SpeechMap) -> SpeechMap {
+        let mut map = SpeechMap::default();
+        for range in &later.spans {
+            let mut offset = 0;
+            for span in &self.spans {
+                let start = range.start.max(offset);
+                let end = range.end.min(offset + span.len());
+                if start < end {
+                    map.push(span.start + start - offset..span.start + end - offset);
+                }
+                offset += span.len();
+            }
+        }
+        map
+    }
 }
 
-/// Voice Activity Detector using WebRTC VAD
+/// Voice Activity Detector using WebRTC VAD or Silero VAD
 pub struct VoiceActivityDetector {
     config: VadConfig,
+    /// Silero model replacing WebRTC VAD (16 kHz audio only)
+    silero: Option<Arc<SileroModel>>,
 }
 
 impl VoiceActivityDetector {
     /// Create a new VAD with default configuration
     pub fn new() -> Self {
-        Self {
-            config: VadConfig::default(),
-        }
+        Self::with_config(VadConfig::default())
     }
 
     /// Create a new VAD with custom configuration
     pub fn with_config(config: VadConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            silero: None,
+        }
+    }
+
+    /// Create a VAD classifying frames with the Silero model
+    ///
+    /// `config.mode` picks the speech probability threshold.
+    pub fn with_silero(config: VadConfig, model: Arc<SileroModel>) -> Self {
+        Self {
+            config,
+            silero: Some(model),
+        }
     }
 
     /// Process audio and return only the speech segments
@@ -98,42 +229,33 @@ impl VoiceActivityDetector {
     /// - 16kHz sample rate (required by WebRTC VAD and Whisper)
     /// - f32 format (will be converted internally)
     pub fn filter_speech(&self, audio: &[f32], sample_rate: u32) -> Result<VadResult, VadError> {
-        // WebRTC VAD only supports 8kHz, 16kHz, 32kHz, 48kHz
-        let vad_sample_rate = match sample_rate {
-            8000 => SampleRate::Rate8kHz,
-            16000 => SampleRate::Rate16kHz,
-            32000 => SampleRate::Rate32kHz,
-            48000 => SampleRate::Rate48kHz,
-            _ => return Err(VadError::UnsupportedSampleRate(sample_rate)),
-        };
+        let mut classifier = self.classifier(sample_rate)?;
+        classifier.push(audio);
+        Ok(self.extract_speech(audio, sample_rate, &classifier.finish()))
+    }
 
-        // Create VAD instance
-        let mut vad = Vad::new_with_rate_and_mode(vad_sample_rate, self.config.mode.to_vad_mode());
+    /// Frame classifier for audio at `sample_rate`, to be fed incrementally
+    pub fn classifier(&self, sample_rate: u32) -> Result<FrameClassifier, VadError> {
+        match &self.silero {
+            Some(model) => FrameClassifier::with_silero(&self.config, model.clone(), sample_rate),
+            None => FrameClassifier::new(&self.config, sample_rate),
+        }
+    }
 
-        // Calculate frame size in samples
+    /// Keep the speech of `audio` given the classification of its frames
+    ///
+    /// `speech_frames` comes from a `FrameClassifier` fed with `audio`.
+    pub fn extract_speech(
+        &self,
+        audio: &[f32],
+        sample_rate: u32,
+        speech_frames: &[bool],
+    ) -> VadResult {
         let frame_samples = (sample_rate * self.config.frame_duration_ms / 1000) as usize;
 
-        // Convert f32 to i16 for WebRTC VAD
-        let audio_i16: Vec<i16> = audio
-            .iter()
-            .map(|&s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
-            .collect();
-
-        // Process frames and detect speech
-        let mut speech_frames: Vec<bool> = Vec::new();
-        for chunk in audio_i16.chunks(frame_samples) {
-            if chunk.len() == frame_samples {
-                let is_speech = vad.is_voice_segment(chunk).unwrap_or(false);
-                speech_frames.push(is_speech);
-            } else {
-                // For the last partial frame, assume it's speech to avoid cutting off
-                speech_frames.push(true);
-            }
-        }
-
         // Apply minimum speech duration filter
         let min_frames = (self.config.min_speech_duration_ms / self.config.frame_duration_ms) as usize;
-        let speech_frames = filter_short_segments(&speech_frames, min_frames);
+        let speech_frames = filter_short_segments(speech_frames, min_frames);
 
         // Apply padding around speech segments
         let padding_frames = (self.config.padding_ms / self.config.frame_duration_ms) as usize;
@@ -141,6 +263,7 @@ impl VoiceActivityDetector {
 
         // Extract speech segments
         let mut result_audio: Vec<f32> = Vec::new();
+        let mut kept = SpeechMap::default();
         let mut speech_segments = 0;
         let mut in_speech = false;
 
@@ -153,7 +276,10 @@ impl VoiceActivityDetector {
                     speech_segments += 1;
                     in_speech = true;
                 }
-                result_audio.extend_from_slice(&audio[start_sample..end_sample]);
+                if start_sample < end_sample {
+                    result_audio.extend_from_slice(&audio[start_sample..end_sample]);
+                    kept.push(start_sample..end_sample);
+                }
             } else {
                 in_speech = false;
             }
@@ -176,17 +302,57 @@ impl VoiceActivityDetector {
             speech_duration_ms
         );
 
-        Ok(VadResult {
+        VadResult {
             audio: result_audio,
             original_duration_ms,
             speech_duration_ms,
             speech_segments,
             speech_percentage,
-        })
+            kept,
+        }
     }
 
     /// Check if audio contains any speech (quick check without filtering)
     pub fn contains_speech(&self, audio: &[f32], sample_rate: u32) -> Result<bool, VadError> {
+        let mut classifier = self.classifier(sample_rate)?;
+        let frame_samples = (sample_rate * self.config.frame_duration_ms / 1000) as usize;
+
+        // Check first few frames only for quick detection
+        let frames_to_check = 10.min(audio.len() / frame_samples);
+        classifier.push(&audio[..frames_to_check * frame_samples]);
+        let speech_count = classifier.frames.iter().filter(|&&speech| speech).count();
+
+        // Consider it has speech if at least 20% of checked frames have speech
+        Ok(speech_count > frames_to_check / 5)
+    }
+}
+
+impl Default for VoiceActivityDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incremental frame classification of recorded audio
+///
+/// Classifies each complete frame as soon as it is pushed, so the work can
+/// overlap with producing the audio (e.g. resampling it).
+pub struct FrameClassifier {
+    vad: Vad,
+    /// Silero classification replacing `vad`
+    silero: Option<SileroFrames>,
+    frame_samples: usize,
+    /// Samples of the incomplete frame
+    pending: Vec<i16>,
+    /// Samples pushed so far
+    pushed: usize,
+    frames: Vec<bool>,
+}
+
+impl FrameClassifier {
+    /// Create a classifier for samples at `sample_rate`
+    pub fn new(config: &VadConfig, sample_rate: u32) -> Result<Self, VadError> {
+        // WebRTC VAD only supports 8kHz, 16kHz, 32kHz, 48kHz
         let vad_sample_rate = match sample_rate {
             8000 => SampleRate::Rate8kHz,
             16000 => SampleRate::Rate16kHz,
@@ -194,38 +360,285 @@ impl VoiceActivityDetector {
             48000 => SampleRate::Rate48kHz,
             _ => return Err(VadError::UnsupportedSampleRate(sample_rate)),
         };
+        let frame_samples = (sample_rate * config.frame_duration_ms / 1000) as usize;
+        Ok(Self {
+            vad: Vad::new_with_rate_and_mode(vad_sample_rate, config.mode.to_vad_mode()),
+            silero: None,
+            frame_samples,
+            pending: Vec::with_capacity(frame_samples),
+            pushed: 0,
+            frames: Vec::new(),
+        })
+    }
 
-        let mut vad = Vad::new_with_rate_and_mode(vad_sample_rate, self.config.mode.to_vad_mode());
+    /// Create a classifier running the Silero model on 16 kHz samples
+    pub fn with_silero(
+        config: &VadConfig,
+        model: Arc<SileroModel>,
+        sample_rate: u32,
+    ) -> Result<Self, VadError> {
+        if sample_rate != SILERO_SAMPLE_RATE {
+            return Err(VadError::UnsupportedSampleRate(sample_rate));
+        }
+        let mut classifier = Self::new(config, sample_rate)?;
+        let threshold = config.mode.silero_threshold();
+        classifier.silero = Some(SileroFrames::new(
+            model,
+            threshold,
+            classifier.frame_samples,
+        ));
+        Ok(classifier)
+    }
+
+    /// Classify the complete frames of `audio` (after any pending samples)
+    pub fn push(&mut self, audio: &[f32]) {
+        self.pushed += audio.len();
+        if let Some(silero) = &mut self.silero {
+            silero.push(audio, &mut self.frames);
+            return;
+        }
 
-        let frame_samples = (sample_rate * self.config.frame_duration_ms / 1000) as usize;
+        for &sample in audio {
+            // Convert f32 to i16 for WebRTC VAD
+            self.pending.push((sample * 32767.0).clamp(-32768.0, 32767.0) as i16);
+            if self.pending.len() == self.frame_samples {
+                let is_speech = self.vad.is_voice_segment(&self.pending).unwrap_or(false);
+                self.frames.push(is_speech);
+                self.pending.clear();
+            }
+        }
+    }
 
-        let audio_i16: Vec<i16> = audio
-            .iter()
-            .map(|&s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16)
-            .collect();
+    /// Speech decision of every frame
+    ///
+    /// A last partial frame counts as speech to avoid cutting it off.
+    pub fn finish(mut self) -> Vec<bool> {
+        if let Some(silero) = self.silero.take() {
+            silero.finish(self.pushed, &mut self.frames);
+            if !self.pushed.is_multiple_of(self.frame_samples) {
+                self.frames.push(true);
+            }
+            return self.frames;
+        }
 
-        // Check first few frames only for quick detection
-        let frames_to_check = 10.min(audio_i16.len() / frame_samples);
-        let mut speech_count = 0;
-
-        for i in 0..frames_to_check {
-            let start = i * frame_samples;
-            let end = start + frame_samples;
-            if end <= audio_i16.len() {
-                if vad.is_voice_segment(&audio_i16[start..end]).unwrap_or(false) {
-                    speech_count += 1;
+        if !self.pending.is_empty() {
+            self.frames.push(true);
+        }
+        self.frames
+    }
+}
+
+/// Incremental speech detector for live audio
+///
+/// Fed with capture chunks as they arrive; reports speech on/off transitions.
+/// Speech starts after `min_speech_duration_ms` of voiced frames and ends after
+/// `padding_ms` of silence, so short clicks and pauses between words don't flicker.
+pub struct StreamingVad {
+    vad: Vad,
+    /// Input rate of the pushed samples
+    input_rate: u32,
+    /// Rate the VAD runs at (input rate if supported, 16kHz otherwise)
+    vad_rate: u32,
+    /// Fractional read position for resampling unsupported rates
+    resample_pos: f64,
+    /// Last input sample of the previous chunk (for interpolation)
+    last_sample: f32,
+    frame_samples: usize,
+    pending: Vec<i16>,
+    onset_frames: usize,
+    release_frames: usize,
+    voiced_run: usize,
+    silent_run: usize,
+    speaking: bool,
+    /// VAD-rate samples analyzed so far
+    analyzed: u64,
+    /// End of the last confirmed speech frame (VAD-rate samples)
+    speech_end: Option<u64>,
+}
+
+impl StreamingVad {
+    /// Create a streaming detector for samples at `sample_rate`
+    pub fn new(config: &VadConfig, sample_rate: u32) -> Result<Self, VadError> {
+        if sample_rate == 0 {
+            return Err(VadError::UnsupportedSampleRate(sample_rate));
+        }
+
+        let (vad_rate, vad_sample_rate) = match sample_rate {
+            8000 => (8000, SampleRate::Rate8kHz),
+            32000 => (32000, SampleRate::Rate32kHz),
+            48000 => (48000, SampleRate::Rate48kHz),
+            _ => (16000, SampleRate::Rate16kHz),
+        };
+
+        let frame_duration_ms = config.frame_duration_ms.max(10);
+        Ok(Self {
+            vad: Vad::new_with_rate_and_mode(vad_sample_rate, config.mode.to_vad_mode()),
+            input_rate: sample_rate,
+            vad_rate,
+            resample_pos: 0.0,
+            last_sample: 0.0,
+            frame_samples: (vad_rate * frame_duration_ms / 1000) as usize,
+            pending: Vec::new(),
+            onset_frames: (config.min_speech_duration_ms / frame_duration_ms).max(1) as usize,
+            release_frames: (config.padding_ms / frame_duration_ms).max(1) as usize,
+            voiced_run: 0,
+            silent_run: 0,
+            speaking: false,
+            analyzed: 0,
+            speech_end: None,
+        })
+    }
+
+    /// Whether speech is currently detected
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// Feed captured samples, returns the new state if it changed
+    pub fn push(&mut self, samples: &[f32]) -> Option<bool> {
+        let was_speaking = self.speaking;
+
+        let converted = self.resample_chunk(samples);
+        self.pending.extend(
+            converted
+                .iter()
+                .map(|&s| (s * 32767.0).clamp(-32768.0, 32767.0) as i16),
+        );
+
+        let mut consumed = 0;
+        while self.pending.len() - consumed >= self.frame_samples {
+            let frame = &self.pending[consumed..consumed + self.frame_samples];
+            let is_voice = self.vad.is_voice_segment(frame).unwrap_or(false);
+            consumed += self.frame_samples;
+            self.analyzed += self.frame_samples as u64;
+
+            if is_voice {
+                self.voiced_run += 1;
+                self.silent_run = 0;
+                if self.voiced_run >= self.onset_frames {
+                    self.speaking = true;
+                    self.speech_end = Some(self.analyzed);
+                }
+            } else {
+                self.silent_run += 1;
+                self.voiced_run = 0;
+                if self.silent_run >= self.release_frames {
+                    self.speaking = false;
                 }
             }
         }
+        self.pending.drain(..consumed);
 
-        // Consider it has speech if at least 20% of checked frames have speech
-        Ok(speech_count > frames_to_check / 5)
+        (self.speaking != was_speaking).then_some(self.speaking)
+    }
+
+    /// Input samples analyzed so far
+    pub fn analyzed_samples(&self) -> u64 {
+        self.to_input_samples(self.analyzed)
+    }
+
+    /// Input sample where confirmed speech last ended (None = no speech yet)
+    pub fn speech_end(&self) -> Option<u64> {
+        self.speech_end.map(|end| self.to_input_samples(end))
+    }
+
+    fn to_input_samples(&self, vad_samples: u64) -> u64 {
+        vad_samples * self.input_rate as u64 / self.vad_rate as u64
+    }
+
+    /// Linear-interpolate into the VAD rate when the input rate is unsupported
+    fn resample_chunk(&mut self, samples: &[f32]) -> Vec<f32> {
+        if self.input_rate == self.vad_rate || samples.is_empty() {
+            return samples.to_vec();
+        }
+
+        let step = self.input_rate as f64 / self.vad_rate as f64;
+        let mut output = Vec::with_capacity((samples.len() as f64 / step) as usize + 1);
+
+        // Positions are relative to the previous chunk's last sample (index -1)
+        while self.resample_pos < samples.len() as f64 {
+            let index = self.resample_pos.floor() as isize;
+            let frac = (self.resample_pos - index as f64) as f32;
+            let a = if index < 0 { self.last_sample } else { samples[index as usize] };
+            let b = samples.get((index + 1) as usize).copied().unwrap_or(a);
+            output.push(a + (b - a) * frac);
+            self.resample_pos += step;
+        }
+
+        self.resample_pos -= samples.len() as f64;
+        self.last_sample = samples[samples.len() - 1];
+        output
     }
 }
 
-impl Default for VoiceActivityDetector {
-    fn default() -> Self {
-        Self::new()
+/// Level below which unanalyzed trailing audio counts as silence (dBFS)
+pub const DEFAULT_TAIL_SILENCE_DB: f32 = -40.0;
+
+/// Live speech detection progress, shared with whoever stops the recording
+///
+/// Updated by the speech monitor thread so trailing silence can be cut as
+/// soon as the recording stops, instead of being sent through inference.
+#[derive(Debug, Clone, Default)]
+pub struct SpeechProgress {
+    inner: Arc<SpeechProgressInner>,
+}
+
+#[derive(Debug, Default)]
+struct SpeechProgressInner {
+    analyzed: AtomicU64,
+    /// End of the last speech + 1 (0 = no speech yet)
+    speech_end: AtomicU64,
+}
+
+impl SpeechProgress {
+    /// Record the state of a streaming detector
+    pub fn update(&self, vad: &StreamingVad) {
+        self.inner
+            .analyzed
+            .store(vad.analyzed_samples(), Ordering::Relaxed);
+        if let Some(end) = vad.speech_end() {
+            self.inner.speech_end.store(end + 1, Ordering::Relaxed);
+        }
+    }
+
+    /// Input samples analyzed so far
+    pub fn analyzed_samples(&self) -> u64 {
+        self.inner.analyzed.load(Ordering::Relaxed)
+    }
+
+    /// Input sample where speech last ended (None = no speech yet)
+    pub fn speech_end(&self) -> Option<u64> {
+        self.inner.speech_end.load(Ordering::Relaxed).checked_sub(1)
+    }
+
+    /// Number of samples to keep from a stopped recording
+    ///
+    /// Cuts silence after the last speech, keeping `padding_ms` after it.
+    /// Audio the detector has not analyzed yet is only dropped if its
+    /// energy stays under `tail_silence_db`; recordings without detected
+    /// speech are left to the pipeline's VAD.
+    pub fn keep_len(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+        padding_ms: u32,
+        tail_silence_db: f32,
+    ) -> usize {
+        let Some(speech_end) = self.speech_end() else {
+            return samples.len();
+        };
+        let padding = sample_rate as u64 * padding_ms as u64 / 1000;
+        let keep = (speech_end + padding) as usize;
+        let analyzed = (self.analyzed_samples() as usize).min(samples.len());
+        if keep >= analyzed {
+            return samples.len();
+        }
+
+        let unanalyzed = &samples[analyzed..];
+        if !unanalyzed.is_empty() && is_above_threshold(unanalyzed, tail_silence_db) {
+            return samples.len();
+        }
+        keep
     }
 }
 
@@ -237,6 +650,9 @@ pub enum VadError {
 
     #[error("VAD processing failed: {0}")]
     ProcessingError(String),
+
+    #[error("Failed to load VAD model: {0}")]
+    ModelError(String),
 }
 
 /// Filter out speech segments shorter than min_frames
@@ -321,6 +737,25 @@ pub fn is_above_threshold(audio: &[f32], threshold_db: f32) -> bool {
     db > threshold_db
 }
 
+/// Cut leading and trailing silence, keeping `padding_ms` around the sound
+///
+/// Silence is 20ms frames at or below `threshold_db`. Audio that is silent
+/// throughout comes back empty.
+pub fn trim_silence(audio: &[f32], sample_rate: u32, threshold_db: f32, padding_ms: u32) -> &[f32] {
+    let frame_len = (sample_rate as usize / 50).max(1);
+    let padding = sample_rate as usize * padding_ms as usize / 1000;
+    let loud = |frame: &[f32]| is_above_threshold(frame, threshold_db);
+
+    let Some(first) = audio.chunks(frame_len).position(loud) else {
+        return &audio[..0];
+    };
+    let last = audio.chunks(frame_len).rposition(loud).unwrap_or(first);
+
+    let start = (first * frame_len).saturating_sub(padding);
+    let end = ((last + 1) * frame_len + padding).min(audio.len());
+    &audio[start..end]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -540,6 +975,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_trim_silence_keeps_padding() {
+        let sample_rate = 16000;
+        let audio = concatenate_audio(vec![
+            generate_silence(sample_rate, 1000),
+            generate_sine_wave(sample_rate, 440.0, 500, 0.5),
+            generate_silence(sample_rate, 2000),
+        ]);
+
+        let trimmed = trim_silence(&audio, sample_rate, -40.0, 100);
+        // 500ms of sound plus 100ms of padding on each side
+        assert_eq!(trimmed.len(), 700 * 16);
+        let silence = generate_silence(sample_rate, 500);
+        assert!(trim_silence(&silence, sample_rate, -40.0, 100).is_empty());
+    }
+
     #[test]
     fn test_vad_trims_trailing_silence() {
         let vad = VoiceActivityDetector::with_config(VadConfig {
@@ -849,6 +1300,21 @@ mod tests {
         assert_eq!(config.frame_duration_ms, 30);
     }
 
+    #[test]
+    fn test_vad_config_from_settings() {
+        let settings = VadSettings {
+            aggressiveness: 7,
+            min_speech_duration_ms: 250,
+            padding_ms: 150,
+            ..VadSettings::default()
+        };
+        let config = VadConfig::from_settings(&settings);
+        assert_eq!(config.mode, VadAggressiveness::VeryAggressive);
+        assert_eq!(config.min_speech_duration_ms, 250);
+        assert_eq!(config.padding_ms, 150);
+        assert_eq!(config.frame_duration_ms, 30);
+    }
+
     // ========================================================================
     // 5. Performance and long recording tests
     // ========================================================================
@@ -1025,6 +1491,96 @@ mod tests {
         assert!(result.speech_percentage >= 0.0 && result.speech_percentage <= 100.0);
     }
 
+    #[test]
+    fn test_streaming_vad_detects_speech_onset_and_release() {
+        let config = VadConfig::default();
+        let mut vad = StreamingVad::new(&config, 16000).unwrap();
+
+        let mut transitions = Vec::new();
+        let mut audio = generate_silence(16000, 300);
+        audio.extend(generate_speech_like_signal(16000, 600, 0.5));
+        audio.extend(generate_silence(16000, 600));
+
+        // Feed in capture-sized chunks
+        for chunk in audio.chunks(480) {
+            if let Some(state) = vad.push(chunk) {
+                transitions.push(state);
+            }
+        }
+
+        assert_eq!(transitions, vec![true, false]);
+        assert!(!vad.is_speaking());
+    }
+
+    #[test]
+    fn test_speech_progress_trims_trailing_silence() {
+        let mut vad = StreamingVad::new(&VadConfig::default(), 16000).unwrap();
+        let progress = SpeechProgress::default();
+
+        let mut audio = generate_speech_like_signal(16000, 600, 0.5);
+        audio.extend(generate_silence(16000, 1000));
+        for chunk in audio.chunks(480) {
+            vad.push(chunk);
+            progress.update(&vad);
+        }
+
+        let speech_end = progress.speech_end().unwrap() as usize;
+        // WebRTC VAD keeps reporting voice for a few frames after speech stops
+        assert!((9600..=12800).contains(&speech_end), "speech ended at {}", speech_end);
+        let keep = progress.keep_len(&audio, 16000, 300, DEFAULT_TAIL_SILENCE_DB);
+        assert_eq!(keep, speech_end + 4800);
+
+        // Speech the detector has not seen yet is never cut
+        let mut late = audio.clone();
+        late.extend(generate_speech_like_signal(16000, 200, 0.5));
+        assert_eq!(
+            progress.keep_len(&late, 16000, 300, DEFAULT_TAIL_SILENCE_DB),
+            late.len()
+        );
+    }
+
+    #[test]
+    fn test_speech_progress_without_speech_keeps_everything() {
+        let mut vad = StreamingVad::new(&VadConfig::default(), 16000).unwrap();
+        let progress = SpeechProgress::default();
+        let silence = generate_silence(16000, 1000);
+        for chunk in silence.chunks(480) {
+            vad.push(chunk);
+            progress.update(&vad);
+        }
+
+        assert_eq!(progress.speech_end(), None);
+        assert_eq!(
+            progress.keep_len(&silence, 16000, 300, DEFAULT_TAIL_SILENCE_DB),
+            silence.len()
+        );
+    }
+
+    #[test]
+    fn test_streaming_vad_silence_never_triggers() {
+        let mut vad = StreamingVad::new(&VadConfig::default(), 48000).unwrap();
+        let silence = generate_silence(48000, 1000);
+        for chunk in silence.chunks(1024) {
+            assert_eq!(vad.push(chunk), None);
+        }
+    }
+
+    #[test]
+    fn test_streaming_vad_resamples_unsupported_rate() {
+        let mut vad = StreamingVad::new(&VadConfig::default(), 44100).unwrap();
+        let speech = generate_speech_like_signal(44100, 800, 0.5);
+        let detected = speech
+            .chunks(441)
+            .filter_map(|chunk| vad.push(chunk))
+            .any(|state| state);
+        assert!(detected, "Should detect speech after resampling 44.1kHz input");
+    }
+
+    #[test]
+    fn test_streaming_vad_rejects_zero_rate() {
+        assert!(StreamingVad::new(&VadConfig::default(), 0).is_err());
+    }
+
     #[test]
     fn test_vad_default_impl() {
         let vad1 = VoiceActivityDetector::new();
@@ -1190,4 +1746,48 @@ mod tests {
         assert!(result_10ms.original_duration_ms > 0);
         assert!(result_30ms.original_duration_ms > 0);
     }
+
+    #[test]
+    fn test_extract_speech_maps_kept_frames() {
+        let vad = VoiceActivityDetector::with_config(VadConfig {
+            mode: VadAggressiveness::Aggressive,
+            min_speech_duration_ms: 30,
+            padding_ms: 0,
+            frame_duration_ms: 30,
+        });
+        let audio = vec![0.1; 480 * 6];
+        let frames = [false, true, true, false, false, true];
+
+        let result = vad.extract_speech(&audio, 16000, &frames);
+        assert_eq!(result.kept, SpeechMap::new([480..1440, 2400..2880]));
+        assert_eq!(result.kept.to_input(0), 480);
+        assert_eq!(result.kept.to_input(960), 2400);
+    }
+
+    #[test]
+    fn test_speech_map_to_input_ms() {
+        // 1s of speech, 2s cut, 1s of speech at 16 kHz
+        let map = SpeechMap::new([0..16000, 48000..64000]);
+
+        assert_eq!(map.to_input_ms(0, 1000, 16000), (0, 1000));
+        assert_eq!(map.to_input_ms(1000, 1500, 16000), (3000, 3500));
+        assert_eq!(map.to_input_ms(500, 1500, 16000), (500, 3500));
+        // Past the kept audio
+        assert_eq!(map.to_input_ms(2500, 2600, 16000), (4500, 4600));
+        assert_eq!(
+            SpeechMap::default().to_input_ms(100, 200, 16000),
+            (100, 200)
+        );
+    }
+
+    #[test]
+    fn test_speech_map_then() {
+        let vad = SpeechMap::new([100..200, 300..400]);
+        // A later filter keeping the end of the first range and the start of the second
+        let mut later = SpeechMap::default();
+        later.push(50..150);
+
+        assert_eq!(vad.then(&later), SpeechMap::new([150..200, 300..350]));
+        assert_eq!(vad.then(&SpeechMap::identity(200)), vad);
+    }
 }
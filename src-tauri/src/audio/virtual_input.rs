@@ -0,0 +1,183 @@
+//! Virtual Audio Input
+//!
+//! Stand-in for a microphone that plays a WAV file or generates a tone in
+//! real time, for end-to-end tests and demo recordings on machines without
+//! audio hardware.
+//!
+//! Selected with the `GIGAWHISPER_VIRTUAL_INPUT` environment variable or by
+//! setting the input device to a `virtual:` id (not listed in the device
+//! picker). Specs:
+//! - `tone` or `tone:<hz>`: sine tone (440 Hz by default)
+//! - `silence`
+//! - `wav:<path>`: plays the file once, then silence
+
+use super::{read_wav_mono, AudioError};
+use std::path::{Path, PathBuf};
+
+/// Environment variable selecting a virtual input
+pub const VIRTUAL_INPUT_ENV: &str = "GIGAWHISPER_VIRTUAL_INPUT";
+
+/// Device id prefix selecting a virtual input from settings
+pub const VIRTUAL_DEVICE_PREFIX: &str = "virtual:";
+
+/// Sample rate of generated signals
+const GENERATED_SAMPLE_RATE: u32 = 48000;
+
+/// Amplitude of the generated tone
+const TONE_AMPLITUDE: f32 = 0.3;
+
+/// Audio source replacing the microphone
+#[derive(Debug, Clone, PartialEq)]
+pub enum VirtualSource {
+    Tone { frequency: f32 },
+    Silence,
+    Wav { samples: Vec<f32>, sample_rate: u32 },
+}
+
+impl VirtualSource {
+    /// Virtual source to use instead of `device_id`, if any
+    ///
+    /// The environment variable takes precedence over the configured device.
+    pub fn select(device_id: Option<&str>) -> Option<Result<Self, AudioError>> {
+        let env = std::env::var(VIRTUAL_INPUT_ENV).ok().filter(|v| !v.trim().is_empty());
+        let spec = env.as_deref().or_else(|| device_id?.strip_prefix(VIRTUAL_DEVICE_PREFIX))?;
+        Some(Self::parse(spec))
+    }
+
+    /// Parse a source spec (`tone`, `tone:<hz>`, `silence`, `wav:<path>`)
+    pub fn parse(spec: &str) -> Result<Self, AudioError> {
+        let spec = spec.trim();
+        let (kind, arg) = match spec.split_once(':') {
+            Some((kind, arg)) => (kind, Some(arg)),
+            None => (spec, None),
+        };
+
+        match (kind.to_ascii_lowercase().as_str(), arg) {
+            ("tone", None) => Ok(Self::Tone { frequency: 440.0 }),
+            ("tone", Some(hz)) => {
+                let nyquist = GENERATED_SAMPLE_RATE as f32 / 2.0;
+                match hz.parse::<f32>() {
+                    Ok(frequency) if frequency > 0.0 && frequency < nyquist => {
+                        Ok(Self::Tone { frequency })
+                    }
+                    _ => Err(AudioError::DeviceNotFound(format!(
+                        "invalid tone frequency '{}'",
+                        hz
+                    ))),
+                }
+            }
+            ("silence", None) => Ok(Self::Silence),
+            ("wav", Some(path)) => Self::from_wav(&PathBuf::from(path)),
+            _ => Err(AudioError::DeviceNotFound(format!("unknown virtual input '{}'", spec))),
+        }
+    }
+
+    /// Load a WAV file, downmixed to mono
+    pub fn from_wav(path: &Path) -> Result<Self, AudioError> {
+        let (samples, sample_rate) = read_wav_mono(path)?;
+        Ok(Self::Wav {
+            samples,
+            sample_rate,
+        })
+    }
+
+    /// Sample rate the source produces
+    pub fn sample_rate(&self) -> u32 {
+        match self {
+            Self::Wav { sample_rate, .. } => *sample_rate,
+            Self::Tone { .. } | Self::Silence => GENERATED_SAMPLE_RATE,
+        }
+    }
+
+    /// Start a playback from the beginning
+    pub fn stream(&self) -> VirtualStream<'_> {
+        VirtualStream {
+            source: self,
+            position: 0,
+        }
+    }
+}
+
+/// Playback position in a virtual source
+pub struct VirtualStream<'a> {
+    source: &'a VirtualSource,
+    position: usize,
+}
+
+impl VirtualStream<'_> {
+    /// Next `len` samples
+    pub fn next_chunk(&mut self, len: usize) -> Vec<f32> {
+        let start = self.position;
+        self.position += len;
+
+        match self.source {
+            VirtualSource::Tone { frequency } => {
+                let step =
+                    2.0 * std::f64::consts::PI * *frequency as f64 / GENERATED_SAMPLE_RATE as f64;
+                (start..start + len)
+                    .map(|i| TONE_AMPLITUDE * (step * i as f64).sin() as f32)
+                    .collect()
+            }
+            VirtualSource::Silence => vec![0.0; len],
+            VirtualSource::Wav { samples, .. } => {
+                let mut chunk: Vec<f32> = samples.iter().skip(start).take(len).copied().collect();
+                chunk.resize(len, 0.0);
+                chunk
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_specs() {
+        assert_eq!(VirtualSource::parse("tone").unwrap(), VirtualSource::Tone { frequency: 440.0 });
+        assert_eq!(
+            VirtualSource::parse("tone:1000").unwrap(),
+            VirtualSource::Tone { frequency: 1000.0 }
+        );
+        assert_eq!(VirtualSource::parse("Silence").unwrap(), VirtualSource::Silence);
+        assert!(VirtualSource::parse("tone:-5").is_err());
+        assert!(VirtualSource::parse("microphone").is_err());
+        assert!(VirtualSource::parse("wav:/nonexistent/file.wav").is_err());
+    }
+
+    #[test]
+    fn test_tone_is_continuous_across_chunks() {
+        let source = VirtualSource::Tone { frequency: 440.0 };
+        let mut stream = source.stream();
+        let mut joined = stream.next_chunk(100);
+        joined.extend(stream.next_chunk(100));
+
+        let whole = source.stream().next_chunk(200);
+        assert_eq!(joined, whole);
+        assert!(whole.iter().all(|s| s.abs() <= TONE_AMPLITUDE));
+    }
+
+    #[test]
+    fn test_wav_round_trip_then_silence() {
+        let path = std::env::temp_dir().join("gigawhisper_virtual_input_test.wav");
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..4 {
+            writer.write_sample(16384i16).unwrap();
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let source = VirtualSource::parse(&format!("wav:{}", path.display())).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(source.sample_rate(), 16000);
+
+        let chunk = source.stream().next_chunk(6);
+        assert_eq!(chunk, vec![0.25, 0.25, 0.25, 0.25, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,690 @@
+//! TypeScript Bindings
+//!
+//! Generates `src/bindings.ts` from the Rust types of the commands and
+//! events below, so a changed payload breaks the frontend build instead of
+//! failing at runtime. A test fails when the file is stale; regenerate it
+//! with `UPDATE_BINDINGS=1 cargo test bindings`. Other tests check that every
+//! registered command is listed with the arguments and success type of its
+//! Rust signature, and that every emitted event is listed.
+
+mod typescript;
+
+pub use typescript::*;
+
+use crate::api::{ApiClientInfo, ApiScope, AuditEntry, IssuedToken};
+use crate::audio::{
+    AudioFormat, AudioLevel, CalibrationReport, DeviceEvent, DeviceTestReport, OrphanedRecording,
+};
+use crate::commands::clipboard::ClipboardHistoryEntry;
+use crate::commands::models::{
+    ModelDownloadCancelled, ModelDownloadComplete, ModelDownloadError, ModelDownloadProgress,
+    PresetModel, QuantizationFallback, PRUNE_SUGGESTED_EVENT, QUANTIZATION_FALLBACK_EVENT,
+};
+use crate::commands::recording::{
+    RecordingStateDto, AUDIO_DEVICE_EVENT, AUDIO_LEVEL_EVENT, MAX_DURATION_EVENT,
+};
+use crate::commands::settings::AudioDeviceDto;
+use crate::commands::transcription::{GpuInfo, PipelineStageInfo};
+use crate::config::{
+    AudioSource, QualityPreset, Settings, VoiceCommand, VoiceProfile, Workspace,
+    WorkspaceSettings,
+};
+use crate::history::{
+    HistoryEntry, HistoryExportFormat, HistoryQuery, HistorySearchResult, IncognitoEntry,
+    IntegrityReport, MinutesFormat, RecentText, TagCount,
+};
+use crate::indicator::{IndicatorSnapshot, INDICATOR_STATE_EVENT};
+use crate::models::{CustomModel, ModelInfo, PruneReport};
+use crate::output::{
+    DictationBox, InjectionMethod, InjectionRecord, SecureInputHolder, SubtitleFormat,
+    TranscriptViewer, ViewerUpdate, VIEWER_EVENT,
+};
+use crate::shortcuts::ShortcutConflict;
+use crate::transcription::{
+    ActivitySignal, BatchProgress, BatchRun, BatchSummary, CaptureInfo, FileJob, KeywordAlert,
+    PartialTranscript, PipelineTrace, ProviderCapabilities, ProviderUsed, ReplayReport,
+    TranscriptionComplete, TranscriptionStatus, BATCH_COMPLETED_EVENT, BATCH_PROGRESS_EVENT,
+    FILE_QUEUE_EVENT, PARTIAL_EVENT, PROVIDER_USED_EVENT,
+};
+use crate::updater::{BuildVariantInfo, DownloadProgress, UpdateInfo};
+use crate::utils::{
+    BackgroundTask, CpuInfo, ErrorCode, ErrorGuidance, HealthReport, LatencyReport, MetricsSummary,
+    ResourceUsage, TranscriptionRecord, ERROR_GUIDANCE_EVENT, SLO_VIOLATED_EVENT,
+};
+use crate::vocabulary::{CorrectionOutcome, ImportMode, ImportSummary, LearnedTerm, Vocabulary};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+
+/// Comment at the top of the generated file
+const HEADER: &str = "\
+// Generated from the Rust command and event types by
+// `UPDATE_BINDINGS=1 cargo test bindings`. Do not edit by hand.
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+";
+
+/// A command callable from the UI
+pub struct CommandBinding {
+    pub name: &'static str,
+    pub args: Vec<(&'static str, Schema)>,
+    /// Success type (the error of a `Result` is always a string)
+    pub returns: Schema,
+    /// Declaration as listed, `name(arg: Type, ...) -> Success`
+    pub signature: &'static str,
+}
+
+/// An event sent to the UI
+pub struct EventBinding {
+    pub name: &'static str,
+    pub payload: Schema,
+}
+
+/// List commands as `module::name(arg: Type, ...) -> Success`
+///
+/// Referencing each command makes a renamed or removed one fail to compile.
+macro_rules! command_bindings {
+    ($gen:ident; $( $module:ident :: $name:ident ( $( $arg:ident : $ty:ty ),* ) -> $ret:ty; )*) => {
+        vec![$({
+            let _ = crate::commands::$module::$name;
+            CommandBinding {
+                name: stringify!($name),
+                args: vec![$( (stringify!($arg), $gen.subschema_for::<$ty>()) ),*],
+                returns: $gen.subschema_for::<$ret>(),
+                signature: concat!(
+                    stringify!($name),
+                    "(",
+                    $( stringify!($arg: $ty), ", ", )*
+                    ") -> ",
+                    stringify!($ret)
+                ),
+            }
+        }),*]
+    };
+}
+
+/// List events as `name => Payload`
+macro_rules! event_bindings {
+    ($gen:ident; $( $name:expr => $ty:ty, )*) => {
+        vec![$(EventBinding {
+            name: $name,
+            payload: $gen.subschema_for::<$ty>(),
+        }),*]
+    };
+}
+
+/// Commands with generated bindings
+pub fn commands(gen: &mut SchemaGenerator) -> Vec<CommandBinding> {
+    command_bindings! { gen;
+        settings::get_settings() -> Settings;
+        settings::save_settings(settings: Settings) -> ();
+        settings::check_shortcuts(settings: Settings) -> Vec<ShortcutConflict>;
+        settings::set_quality_preset(preset: QualityPreset, download: Option<bool>) -> Settings;
+        settings::preset_missing_model(preset: QualityPreset) -> Option<PresetModel>;
+        settings::get_audio_devices() -> Vec<AudioDeviceDto>;
+        settings::calibrate_vad(device_id: Option<String>, apply: bool) -> CalibrationReport;
+        settings::test_audio_device(device_id: Option<String>, playback: bool) -> DeviceTestReport;
+        settings::enroll_voice_profile(device_id: Option<String>) -> VoiceProfile;
+        settings::clear_voice_profile() -> ();
+        settings::get_alert_keywords() -> Vec<String>;
+        settings::add_alert_keyword(keyword: String) -> Vec<String>;
+        settings::remove_alert_keyword(keyword: String) -> Vec<String>;
+        settings::get_builtin_voice_commands(language: String) -> Vec<VoiceCommand>;
+        settings::set_groq_api_key(api_key: String) -> ();
+        settings::has_groq_api_key() -> bool;
+        settings::clear_groq_api_key() -> ();
+        settings::validate_groq_api_key(api_key: String) -> ();
+        settings::set_deepgram_api_key(api_key: String) -> ();
+        settings::has_deepgram_api_key() -> bool;
+        settings::clear_deepgram_api_key() -> ();
+        settings::validate_deepgram_api_key(api_key: String) -> ();
+        settings::set_custom_endpoint_token(token: String) -> ();
+        settings::has_custom_endpoint_token() -> bool;
+        settings::clear_custom_endpoint_token() -> ();
+        settings::set_openai_api_key(api_key: String) -> ();
+        settings::has_openai_api_key() -> bool;
+        settings::clear_openai_api_key() -> ();
+        settings::set_cleanup_endpoint_token(token: String) -> ();
+        settings::has_cleanup_endpoint_token() -> bool;
+        settings::clear_cleanup_endpoint_token() -> ();
+        settings::set_webhook_auth(url: String, value: String) -> ();
+        settings::has_webhook_auth(url: String) -> bool;
+        settings::clear_webhook_auth(url: String) -> ();
+        settings::set_mqtt_password(password: String) -> ();
+        settings::has_mqtt_password() -> bool;
+        settings::clear_mqtt_password() -> ();
+
+        recording::start_recording(
+            profile: Option<String>,
+            source: Option<AudioSource>,
+            translate: Option<bool>
+        ) -> ();
+        recording::stop_recording() -> String;
+        recording::cancel_recording() -> ();
+        recording::get_recording_state() -> RecordingStateDto;
+        recording::get_indicator_state() -> IndicatorSnapshot;
+        recording::get_current_level() -> Option<AudioLevel>;
+        recording::get_orphaned_recording() -> Option<OrphanedRecording>;
+        recording::transcribe_orphaned_recording() -> String;
+        recording::discard_orphaned_recording() -> ();
+
+        transcription::get_transcription_status() -> TranscriptionStatus;
+        transcription::get_provider_capabilities() -> Vec<ProviderCapabilities>;
+        transcription::transcribe_file(path: String) -> String;
+        transcription::enqueue_audio_files(paths: Vec<String>) -> Vec<FileJob>;
+        transcription::start_batch(folder: String, recursive: Option<bool>) -> BatchRun;
+        transcription::pause_batch() -> ();
+        transcription::resume_batch() -> ();
+        transcription::cancel_batch() -> ();
+        transcription::get_batch() -> Option<BatchRun>;
+        transcription::get_file_queue() -> Vec<FileJob>;
+        transcription::clear_file_queue() -> ();
+        transcription::preload_model() -> ();
+        transcription::unload_model() -> ();
+        transcription::signal_activity(signal: ActivitySignal) -> ();
+        transcription::get_gpu_info() -> GpuInfo;
+        transcription::get_cpu_info() -> CpuInfo;
+        transcription::get_metrics_summary() -> MetricsSummary;
+        transcription::get_recent_metrics(count: Option<usize>) -> Vec<TranscriptionRecord>;
+        transcription::reset_metrics() -> ();
+        transcription::get_latency_report() -> LatencyReport;
+        transcription::get_pipeline_config() -> Vec<PipelineStageInfo>;
+        transcription::get_pipeline_traces(count: Option<usize>) -> Vec<PipelineTrace>;
+        transcription::list_debug_captures() -> Vec<CaptureInfo>;
+        transcription::replay_recording(path: String) -> ReplayReport;
+
+        system::get_health_report() -> HealthReport;
+        system::get_background_tasks() -> Vec<BackgroundTask>;
+        system::get_resource_usage() -> ResourceUsage;
+        system::get_error_guidance(code: ErrorCode) -> ErrorGuidance;
+
+        dictation_box::open_dictation_box() -> ();
+        dictation_box::get_dictation_box() -> DictationBox;
+        dictation_box::update_dictation_box(text: String) -> ();
+        dictation_box::send_dictation_box(text: Option<String>) -> InjectionMethod;
+        dictation_box::close_dictation_box() -> ();
+
+        viewer::open_viewer() -> ();
+        viewer::close_viewer() -> ();
+        viewer::get_viewer() -> TranscriptViewer;
+        viewer::set_viewer_paused(paused: bool) -> ();
+        viewer::clear_viewer() -> ();
+
+        clipboard::paste_text(text: String) -> ();
+        clipboard::get_history() -> Vec<ClipboardHistoryEntry>;
+        clipboard::get_injection_audit(limit: Option<usize>) -> Vec<InjectionRecord>;
+        clipboard::export_injection_audit(path: String) -> usize;
+
+        models::list_models() -> Vec<ModelInfo>;
+        models::is_model_downloaded(model: String) -> bool;
+        models::download_model(model: String) -> String;
+        models::delete_model(model: String) -> ();
+        models::import_model(path: String, name: String) -> CustomModel;
+        models::list_custom_models() -> Vec<CustomModel>;
+        models::delete_custom_model(id: String) -> ();
+        models::cancel_model_download(model: String) -> bool;
+        models::is_model_downloading(model: String) -> bool;
+        models::prune_unused_models(days: Option<u32>, confirm: bool) -> PruneReport;
+        models::get_recommended_model() -> String;
+
+        history::get_transcription_history() -> Vec<HistoryEntry>;
+        history::get_history_entry(id: String) -> Option<HistoryEntry>;
+        history::delete_history_entry(id: String) -> bool;
+        history::clear_history() -> ();
+        history::get_history_count() -> usize;
+        history::get_incognito_entries() -> Vec<IncognitoEntry>;
+        history::clear_incognito_entries() -> usize;
+        history::get_audio_data(
+            id: String,
+            format: Option<AudioFormat>,
+            bitrate_kbps: Option<u32>,
+            sample_rate: Option<u32>
+        ) -> String;
+        history::get_screenshot_data(id: String) -> String;
+        history::export_meeting_minutes(
+            id: String,
+            format: MinutesFormat,
+            path: String,
+            template: Option<String>
+        ) -> ();
+        history::export_subtitles(id: String, format: SubtitleFormat, path: String) -> ();
+        history::export_history(
+            format: HistoryExportFormat,
+            path: String,
+            query: Option<HistoryQuery>
+        ) -> usize;
+        history::set_entry_title(id: String, title: String) -> String;
+        history::set_entry_note(id: String, note: String) -> ();
+        history::add_entry_tag(id: String, tag: String) -> Vec<String>;
+        history::remove_entry_tag(id: String, tag: String) -> Vec<String>;
+        history::get_history_tags() -> Vec<TagCount>;
+        history::submit_correction(entry_id: String, corrected_text: String) -> CorrectionOutcome;
+        history::get_recent_texts(limit: Option<usize>) -> Vec<RecentText>;
+        history::fuzzy_search_history(query: String, limit: Option<usize>) -> Vec<RecentText>;
+        history::search_history(query: HistoryQuery) -> HistorySearchResult;
+        history::verify_history_integrity(repair: bool) -> IntegrityReport;
+        history::paste_history_entry(id: String) -> ();
+
+        api::list_api_clients() -> Vec<ApiClientInfo>;
+        api::create_api_client(name: String, scopes: Vec<ApiScope>) -> IssuedToken;
+        api::update_api_client_scopes(id: String, scopes: Vec<ApiScope>) -> ApiClientInfo;
+        api::rotate_api_client_token(id: String) -> IssuedToken;
+        api::delete_api_client(id: String) -> ();
+        api::get_api_audit_log(limit: Option<usize>) -> Vec<AuditEntry>;
+
+        vocabulary::get_vocabulary() -> Vocabulary;
+        vocabulary::save_vocabulary(vocabulary: Vocabulary) -> ();
+        vocabulary::export_vocabulary(
+            path: String,
+            name: Option<String>,
+            description: Option<String>
+        ) -> ();
+        vocabulary::import_vocabulary(path: String, mode: Option<ImportMode>) -> ImportSummary;
+        vocabulary::apply_rules_pack(url: String, mode: Option<ImportMode>) -> ImportSummary;
+        vocabulary::get_learned_terms() -> Vec<LearnedTerm>;
+        vocabulary::remove_learned_term(term: String) -> bool;
+        vocabulary::prune_learned_terms(min_score: Option<f32>) -> usize;
+
+        workspace::list_workspaces() -> WorkspaceSettings;
+        workspace::create_workspace(name: String) -> Workspace;
+        workspace::switch_workspace(id: String) -> ();
+        workspace::delete_workspace(id: String) -> ();
+
+        updater::install_update() -> ();
+        updater::restart_app() -> ();
+        updater::get_build_variant() -> BuildVariantInfo;
+    }
+}
+
+/// Events with generated payload types
+pub fn events(gen: &mut SchemaGenerator) -> Vec<EventBinding> {
+    event_bindings! { gen;
+        "history:updated" => (),
+        "settings:preset-changed" => QualityPreset,
+        "incognito:updated" => (),
+        "calibration:phase" => String,
+        "enrollment:phase" => String,
+        "model-download-progress" => ModelDownloadProgress,
+        "model-download-complete" => ModelDownloadComplete,
+        "model-download-error" => ModelDownloadError,
+        "model-download-cancelled" => ModelDownloadCancelled,
+        PRUNE_SUGGESTED_EVENT => PruneReport,
+        QUANTIZATION_FALLBACK_EVENT => QuantizationFallback,
+        ERROR_GUIDANCE_EVENT => ErrorGuidance,
+        MAX_DURATION_EVENT => u32,
+        AUDIO_LEVEL_EVENT => AudioLevel,
+        AUDIO_DEVICE_EVENT => DeviceEvent,
+        "audio:speech-active" => bool,
+        "audio:device-changed" => Option<String>,
+        "recording:state-changed" => String,
+        "recording:processing" => (),
+        "recording:recovered" => OrphanedRecording,
+        PARTIAL_EVENT => PartialTranscript,
+        PROVIDER_USED_EVENT => ProviderUsed,
+        "transcription:complete" => TranscriptionComplete,
+        "transcription:alert" => KeywordAlert,
+        "transcription:error" => String,
+        SLO_VIOLATED_EVENT => LatencyReport,
+        INDICATOR_STATE_EVENT => IndicatorSnapshot,
+        VIEWER_EVENT => ViewerUpdate,
+        FILE_QUEUE_EVENT => Vec<FileJob>,
+        BATCH_PROGRESS_EVENT => BatchProgress,
+        BATCH_COMPLETED_EVENT => BatchSummary,
+        "dictation-box:opened" => (),
+        "dictation-box:closed" => (),
+        "dictation-box:updated" => String,
+        "output:secure-input" => Option<SecureInputHolder>,
+        "show:popup" => String,
+        "workspace:changed" => String,
+        "shortcuts:profile-switched" => Option<String>,
+        "palette:open" => (),
+        "navigate:settings" => (),
+        "update-available" => UpdateInfo,
+        "update-download-progress" => DownloadProgress,
+        "update-installed" => (),
+    }
+}
+
+/// Contents of `src/bindings.ts`
+pub fn generate() -> String {
+    let mut gen = SchemaGenerator::default();
+    let commands = commands(&mut gen);
+    let events = events(&mut gen);
+
+    let mut out = HEADER.to_string();
+
+    let mut definitions: Vec<_> = gen.definitions().iter().collect();
+    definitions.sort_by_key(|(name, _)| name.as_str());
+    for (name, schema) in definitions {
+        out.push('\n');
+        out.push_str(&render_declaration(name, schema));
+    }
+
+    out.push_str("\nexport const commands = {\n");
+    for command in &commands {
+        out.push_str(&render_command(command));
+    }
+    out.push_str("};\n");
+
+    out.push_str("\n/** Payload of each event sent to the UI */\nexport type EventPayloads = {\n");
+    for event in &events {
+        out.push_str(&format!("  {}: {};\n", quote(event.name), render_type(&event.payload)));
+    }
+    out.push_str(
+        "};\n\
+         \n\
+         /** Listen to an event with its typed payload */\n\
+         export function listenTo<E extends keyof EventPayloads>(\n  \
+           event: E,\n  \
+           handler: (payload: EventPayloads[E]) => void,\n\
+         ): Promise<UnlistenFn> {\n  \
+           return listen<EventPayloads[E]>(event, (e) => handler(e.payload));\n\
+         }\n",
+    );
+    out
+}
+
+/// `name: (args) => invoke<Returns>('name', { args })` entry of `commands`
+///
+/// Trailing nullable arguments may be left out.
+fn render_command(command: &CommandBinding) -> String {
+    let types: Vec<String> = command.args.iter().map(|(_, schema)| render_type(schema)).collect();
+    let optional_from = types
+        .iter()
+        .rposition(|ty| !ty.split(" | ").any(|member| member == "null"))
+        .map_or(0, |i| i + 1);
+
+    let params: Vec<String> = command
+        .args
+        .iter()
+        .zip(&types)
+        .enumerate()
+        .map(|(i, ((name, _), ty))| {
+            let optional = if i >= optional_from { "?" } else { "" };
+            format!("{}{}: {}", camel_case(name), optional, ty)
+        })
+        .collect();
+    let names: Vec<String> = command.args.iter().map(|(name, _)| camel_case(name)).collect();
+    let args = if names.is_empty() {
+        String::new()
+    } else {
+        format!(", {{ {} }}", names.join(", "))
+    };
+
+    format!(
+        "  {}: ({}) =>\n    invoke<{}>({}{}),\n",
+        camel_case(command.name),
+        params.join(", "),
+        render_type(&command.returns),
+        quote(command.name),
+        args
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_bindings_up_to_date() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../src/bindings.ts");
+        let generated = generate();
+        if std::env::var_os("UPDATE_BINDINGS").is_some() {
+            std::fs::write(&path, &generated).unwrap();
+            return;
+        }
+
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            current.replace("\r\n", "\n") == generated,
+            "src/bindings.ts is stale, run `UPDATE_BINDINGS=1 cargo test bindings`"
+        );
+    }
+
+    #[test]
+    fn test_render_command() {
+        let mut gen = SchemaGenerator::default();
+        let commands = commands(&mut gen);
+        let search = commands.iter().find(|c| c.name == "get_recent_texts").unwrap();
+        assert_eq!(
+            render_command(search),
+            "  getRecentTexts: (limit?: number | null) =>\n    \
+             invoke<RecentText[]>('get_recent_texts', { limit }),\n"
+        );
+        let submit = commands.iter().find(|c| c.name == "submit_correction").unwrap();
+        assert!(render_command(submit).contains("{ entryId, correctedText }"));
+    }
+
+    /// Arguments the frontend doesn't pass, filled in by Tauri
+    const INJECTED_ARGS: &[&str] = &["AppHandle", "State", "Window", "WebviewWindow", "Webview"];
+
+    /// Commands registered in `lib.rs`, as `(module path, name)`
+    fn registered_commands() -> Vec<(String, String)> {
+        let lib = include_str!("../lib.rs");
+        let start = lib.find("generate_handler![").unwrap() + "generate_handler![".len();
+        let end = start + lib[start..].find(']').unwrap();
+        lib[start..end]
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(|path| {
+                let (module, name) = path.rsplit_once("::").unwrap();
+                (module.to_string(), name.to_string())
+            })
+            .collect()
+    }
+
+    /// The command's function in the source of `module`
+    fn command_fn(module: &str, name: &str) -> syn::ItemFn {
+        let src = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+        let relative = module.replace("::", "/");
+        let file = [format!("{}.rs", relative), format!("{}/mod.rs", relative)]
+            .iter()
+            .map(|path| src.join(path))
+            .find(|path| path.exists())
+            .unwrap_or_else(|| panic!("no source for {}", module));
+        let source = syn::parse_file(&std::fs::read_to_string(&file).unwrap()).unwrap();
+        source
+            .items
+            .into_iter()
+            .find_map(|item| match item {
+                syn::Item::Fn(item) if item.sig.ident == name => Some(item),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("{} not found in {}", name, file.display()))
+    }
+
+    /// `ty` without module paths and spaces, e.g. `Vec<InjectionRecord>`
+    fn type_name(ty: &syn::Type) -> String {
+        use quote::ToTokens;
+
+        let text: String = ty.to_token_stream().to_string().split_whitespace().collect();
+        let mut parts: Vec<&str> = text.split("::").collect();
+        let last = parts.pop().unwrap_or_default();
+        let mut name: String = parts
+            .iter()
+            .map(|part| part.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_'))
+            .collect();
+        name.push_str(last);
+        name
+    }
+
+    fn last_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+        match ty {
+            syn::Type::Path(path) => path.path.segments.last(),
+            _ => None,
+        }
+    }
+
+    /// Arguments passed by the frontend and the success type of `sig`
+    fn frontend_signature(sig: &syn::Signature) -> (Vec<(String, String)>, String) {
+        let args = sig
+            .inputs
+            .iter()
+            .filter_map(|input| match input {
+                syn::FnArg::Typed(arg) => Some(arg),
+                syn::FnArg::Receiver(_) => None,
+            })
+            .filter(|arg| {
+                last_segment(&arg.ty)
+                    .is_none_or(|segment| !INJECTED_ARGS.contains(&segment.ident.to_string().as_str()))
+            })
+            .map(|arg| {
+                let name = match arg.pat.as_ref() {
+                    syn::Pat::Ident(pat) => pat.ident.to_string(),
+                    pat => panic!("unexpected argument pattern in {}: {:?}", sig.ident, pat),
+                };
+                (name, type_name(&arg.ty))
+            })
+            .collect();
+
+        let returns = match &sig.output {
+            syn::ReturnType::Default => "()".to_string(),
+            syn::ReturnType::Type(_, ty) => {
+                let ok = last_segment(ty)
+                    .filter(|segment| segment.ident == "Result")
+                    .and_then(|segment| match &segment.arguments {
+                        syn::PathArguments::AngleBracketed(generics) => generics.args.first(),
+                        _ => None,
+                    });
+                match ok {
+                    Some(syn::GenericArgument::Type(ok)) => type_name(ok),
+                    _ => type_name(ty),
+                }
+            }
+        };
+        (args, returns)
+    }
+
+    #[test]
+    fn test_every_command_has_bindings() {
+        let mut gen = SchemaGenerator::default();
+        let listed: Vec<&str> = commands(&mut gen).iter().map(|c| c.name).collect();
+        let missing: Vec<String> = registered_commands()
+            .into_iter()
+            .filter(|(_, name)| !listed.contains(&name.as_str()))
+            .map(|(module, name)| format!("{}::{}", module, name))
+            .collect();
+        assert!(missing.is_empty(), "commands without bindings: {:?}", missing);
+    }
+
+    #[test]
+    fn test_bindings_match_command_signatures() {
+        let mut gen = SchemaGenerator::default();
+        let commands = commands(&mut gen);
+        for (module, name) in registered_commands() {
+            let Some(binding) = commands.iter().find(|c| c.name == name) else {
+                continue;
+            };
+            let listed: syn::Signature =
+                syn::parse_str(&format!("fn {}", binding.signature)).unwrap();
+            let actual = command_fn(&module, &name).sig;
+            assert_eq!(
+                frontend_signature(&listed),
+                frontend_signature(&actual),
+                "bindings of {}::{} don't match its signature",
+                module,
+                name
+            );
+        }
+    }
+
+    /// Calls sending an event, with the position of the event name among
+    /// their arguments
+    const EMIT_CALLS: &[(&str, usize)] = &[
+        (".emit(", 0),
+        ("emit_throttled(", 1),
+        ("emit_to_window(", 2),
+    ];
+
+    /// Rust sources under `dir`, except these bindings
+    fn source_files(dir: &Path, files: &mut Vec<std::path::PathBuf>) {
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if path.file_name().is_some_and(|name| name != "bindings") {
+                    source_files(&path, files);
+                }
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    /// Names of the events emitted in the sources
+    ///
+    /// Names are string literals or `&str` constants; calls forwarding a
+    /// variable (the emit helpers themselves) are skipped.
+    fn emitted_events() -> Vec<String> {
+        let mut files = Vec::new();
+        source_files(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut files);
+        let sources: Vec<String> = files
+            .iter()
+            .map(|path| std::fs::read_to_string(path).unwrap())
+            .collect();
+        let constants: std::collections::HashMap<&str, &str> = sources
+            .iter()
+            .flat_map(|source| source.lines())
+            .filter_map(|line| {
+                let (name, value) = line.trim().strip_prefix("pub const ")?.split_once(": &str = ")?;
+                Some((name, value.strip_prefix('"')?.strip_suffix("\";")?))
+            })
+            .collect();
+
+        let mut names = Vec::new();
+        for source in &sources {
+            for (call, position) in EMIT_CALLS {
+                for (start, _) in source.match_indices(call) {
+                    let Some(arg) = source[start + call.len()..].split(',').nth(*position) else {
+                        continue;
+                    };
+                    let arg = arg.trim();
+                    let name = match arg.strip_prefix('"') {
+                        Some(literal) => literal.split('"').next(),
+                        None => constants.get(arg).copied(),
+                    };
+                    names.extend(name.map(str::to_string));
+                }
+            }
+        }
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    #[test]
+    fn test_every_event_has_bindings() {
+        let mut gen = SchemaGenerator::default();
+        let listed: Vec<&str> = events(&mut gen).iter().map(|e| e.name).collect();
+        let emitted = emitted_events();
+        assert!(emitted.iter().any(|name| name == "batch:progress"));
+
+        let missing: Vec<&String> = emitted
+            .iter()
+            .filter(|name| !listed.contains(&name.as_str()))
+            .collect();
+        assert!(missing.is_empty(), "events without bindings: {:?}", missing);
+        let stale: Vec<&&str> = listed
+            .iter()
+            .filter(|name| !emitted.iter().any(|emitted| emitted == *name))
+            .collect();
+        assert!(stale.is_empty(), "bindings of events never emitted: {:?}", stale);
+    }
+
+    #[test]
+    fn test_frontend_signature() {
+        let sig: syn::Signature = syn::parse_str(
+            "fn f(app: tauri::AppHandle, state: State<'_, AppState>, limit: Option<usize>) \
+             -> Result<Vec<output::InjectionRecord>, String>",
+        )
+        .unwrap();
+        assert_eq!(
+            frontend_signature(&sig),
+            (
+                vec![("limit".to_string(), "Option<usize>".to_string())],
+                "Vec<InjectionRecord>".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,340 @@
+//! TypeScript Rendering
+//!
+//! Turns the JSON schemas of the IPC types into TypeScript types. Only the
+//! shapes serde produces are covered: objects, arrays, tuples, string
+//! enums, tagged unions and nullable values.
+
+use schemars::schema::{InstanceType, Schema, SchemaObject, SingleOrVec};
+use serde_json::Value;
+
+/// Lines longer than this split unions one member per line
+const MAX_LINE_CHARS: usize = 100;
+
+/// `export type` declaration of a named schema
+pub fn render_declaration(name: &str, schema: &Schema) -> String {
+    let mut out = String::new();
+    if let Some(description) = description(schema) {
+        out.push_str(&doc_comment(description, ""));
+    }
+
+    let object = match schema {
+        Schema::Object(object) if is_plain_object(object) => Some(object),
+        _ => None,
+    };
+    match object {
+        Some(object) => {
+            out.push_str(&format!("export type {} = {{\n", name));
+            out.push_str(&render_fields(object, "  "));
+            out.push_str("};\n");
+        }
+        None => {
+            let ty = render_type(schema);
+            let members = split_union(&ty);
+            let line = format!("export type {} = {};", name, ty);
+            if members.len() > 1 && line.len() > MAX_LINE_CHARS {
+                out.push_str(&format!("export type {} =\n", name));
+                for member in members {
+                    out.push_str(&format!("  | {}\n", member));
+                }
+                out.pop();
+                out.push_str(";\n");
+            } else {
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
+
+/// Inline TypeScript type of a schema
+pub fn render_type(schema: &Schema) -> String {
+    match schema {
+        Schema::Bool(true) => "unknown".to_string(),
+        Schema::Bool(false) => "never".to_string(),
+        Schema::Object(object) => render_object(object),
+    }
+}
+
+/// `camelCase` name of a command argument, as Tauri expects it
+pub fn camel_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Single-quoted string literal
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// JSDoc comment, indented by `indent`
+pub fn doc_comment(text: &str, indent: &str) -> String {
+    let lines: Vec<&str> = text.trim().lines().map(str::trim_end).collect();
+    if let [line] = lines[..] {
+        return format!("{}/** {} */\n", indent, line);
+    }
+    let mut out = format!("{}/**\n", indent);
+    for line in lines {
+        if line.is_empty() {
+            out.push_str(&format!("{} *\n", indent));
+        } else {
+            out.push_str(&format!("{} * {}\n", indent, line));
+        }
+    }
+    out.push_str(&format!("{} */\n", indent));
+    out
+}
+
+fn description(schema: &Schema) -> Option<&str> {
+    match schema {
+        Schema::Object(object) => object.metadata.as_ref()?.description.as_deref(),
+        Schema::Bool(_) => None,
+    }
+}
+
+fn render_object(object: &SchemaObject) -> String {
+    if let Some(reference) = &object.reference {
+        return reference.rsplit('/').next().unwrap_or(reference).to_string();
+    }
+    if let Some(value) = &object.const_value {
+        return render_literal(value);
+    }
+    if let Some(values) = &object.enum_values {
+        return join_union(values.iter().map(render_literal).collect());
+    }
+    if let Some(subschemas) = &object.subschemas {
+        if let Some(all_of) = &subschemas.all_of {
+            let parts: Vec<String> = all_of.iter().map(render_type).collect();
+            return parts.join(" & ");
+        }
+        if let Some(any_of) = subschemas.any_of.as_ref().or(subschemas.one_of.as_ref()) {
+            return join_union(any_of.iter().map(render_type).collect());
+        }
+    }
+
+    match &object.instance_type {
+        Some(SingleOrVec::Single(instance_type)) => render_instance(object, instance_type),
+        Some(SingleOrVec::Vec(instance_types)) => join_union(
+            instance_types
+                .iter()
+                .map(|instance_type| render_instance(object, instance_type))
+                .collect(),
+        ),
+        None => "unknown".to_string(),
+    }
+}
+
+fn render_instance(object: &SchemaObject, instance_type: &InstanceType) -> String {
+    match instance_type {
+        InstanceType::Null => "null".to_string(),
+        InstanceType::Boolean => "boolean".to_string(),
+        InstanceType::Number | InstanceType::Integer => "number".to_string(),
+        InstanceType::String => "string".to_string(),
+        InstanceType::Array => render_array(object),
+        InstanceType::Object => {
+            if is_plain_object(object) {
+                let fields = render_fields(object, "");
+                let fields = fields.trim_end().trim_end_matches(';');
+                format!("{{ {} }}", fields.replace(";\n", "; "))
+            } else {
+                let values = object
+                    .object
+                    .as_ref()
+                    .and_then(|o| o.additional_properties.as_deref())
+                    .map(render_type)
+                    .unwrap_or_else(|| "unknown".to_string());
+                format!("Record<string, {}>", values)
+            }
+        }
+    }
+}
+
+fn render_array(object: &SchemaObject) -> String {
+    match object.array.as_ref().and_then(|a| a.items.as_ref()) {
+        Some(SingleOrVec::Single(item)) => {
+            let item = render_type(item);
+            if split_union(&item).len() > 1 || item.contains(" & ") {
+                format!("({})[]", item)
+            } else {
+                format!("{}[]", item)
+            }
+        }
+        Some(SingleOrVec::Vec(items)) => {
+            let items: Vec<String> = items.iter().map(render_type).collect();
+            format!("[{}]", items.join(", "))
+        }
+        None => "unknown[]".to_string(),
+    }
+}
+
+/// Object with named properties (rather than a map)
+fn is_plain_object(object: &SchemaObject) -> bool {
+    object.reference.is_none()
+        && object.subschemas.is_none()
+        && object
+            .object
+            .as_ref()
+            .is_some_and(|o| !o.properties.is_empty())
+}
+
+/// Field lines of an object, each prefixed with `indent` (no doc comments
+/// when inline)
+fn render_fields(object: &SchemaObject, indent: &str) -> String {
+    let Some(validation) = &object.object else {
+        return String::new();
+    };
+    let mut out = String::new();
+    for (name, schema) in &validation.properties {
+        if !indent.is_empty() {
+            if let Some(description) = description(schema) {
+                out.push_str(&doc_comment(description, indent));
+            }
+        }
+        let optional = if validation.required.contains(name) { "" } else { "?" };
+        out.push_str(&format!(
+            "{}{}{}: {};\n",
+            indent,
+            property_name(name),
+            optional,
+            render_type(schema)
+        ));
+    }
+    out
+}
+
+fn property_name(name: &str) -> String {
+    let identifier = name
+        .chars()
+        .enumerate()
+        .all(|(i, c)| c == '_' || c.is_ascii_alphabetic() || (i > 0 && c.is_ascii_digit()));
+    if identifier {
+        name.to_string()
+    } else {
+        quote(name)
+    }
+}
+
+fn render_literal(value: &Value) -> String {
+    match value {
+        Value::String(text) => quote(text),
+        other => other.to_string(),
+    }
+}
+
+fn join_union(members: Vec<String>) -> String {
+    let mut unique: Vec<String> = Vec::with_capacity(members.len());
+    for member in members {
+        if !unique.contains(&member) {
+            unique.push(member);
+        }
+    }
+    unique.join(" | ")
+}
+
+/// Top-level members of a union type
+fn split_union(ty: &str) -> Vec<&str> {
+    let mut members = Vec::new();
+    let mut depth = 0i32;
+    let mut start = 0;
+    let bytes = ty.as_bytes();
+    for (i, &b) in bytes.iter().enumerate() {
+        match b {
+            b'{' | b'(' | b'[' | b'<' => depth += 1,
+            b'}' | b')' | b']' | b'>' => depth -= 1,
+            b'|' if depth == 0 && i > 0 && bytes[i - 1] == b' ' => {
+                members.push(ty[start..i - 1].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(ty[start..].trim());
+    members
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use schemars::JsonSchema;
+    use serde::Serialize;
+
+    /// A test payload
+    #[derive(Serialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Payload {
+        /// Identifier
+        id: String,
+        count: Option<u32>,
+        #[serde(default)]
+        tags: Vec<String>,
+        kind: Kind,
+    }
+
+    #[derive(Serialize, JsonSchema)]
+    #[serde(rename_all = "kebab-case")]
+    #[allow(dead_code)]
+    enum Kind {
+        Plain,
+        WithDash,
+    }
+
+    #[derive(Serialize, JsonSchema)]
+    #[serde(tag = "kind", rename_all = "kebab-case")]
+    #[allow(dead_code)]
+    enum Tagged {
+        Empty,
+        Point { x: f32, y: f32 },
+    }
+
+    fn definitions_of<T: JsonSchema>() -> schemars::Map<String, Schema> {
+        let mut gen = schemars::gen::SchemaGenerator::default();
+        gen.subschema_for::<T>();
+        gen.take_definitions()
+    }
+
+    #[test]
+    fn test_render_declarations() {
+        let definitions = definitions_of::<Payload>();
+        assert_eq!(
+            render_declaration("Payload", &definitions["Payload"]),
+            "/** A test payload */\n\
+             export type Payload = {\n  \
+               /** Identifier */\n  \
+               id: string;\n  \
+               count?: number | null;\n  \
+               tags?: string[];\n  \
+               kind: Kind;\n\
+             };\n"
+        );
+        assert_eq!(
+            render_declaration("Kind", &definitions["Kind"]),
+            "export type Kind = 'plain' | 'with-dash';\n"
+        );
+
+        let definitions = definitions_of::<Tagged>();
+        assert_eq!(
+            render_type(&definitions["Tagged"]),
+            "{ kind: 'empty' } | { kind: 'point'; x: number; y: number }"
+        );
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(camel_case("bitrate_kbps"), "bitrateKbps");
+        assert_eq!(camel_case("id"), "id");
+        assert_eq!(quote("it's"), "'it\\'s'");
+        assert_eq!(property_name("model-download"), "'model-download'");
+        assert_eq!(split_union("{ a: 'x' | 'y' } | null"), ["{ a: 'x' | 'y' }", "null"]);
+    }
+}
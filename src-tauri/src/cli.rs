@@ -0,0 +1,229 @@
+//! Command Line Mode
+//!
+//! Headless transcription of an audio file, for scripts and batch jobs:
+//!
+//! ```text
+//! gigawhisper --transcribe meeting.wav --model small --output meeting.txt
+//! ```
+//!
+//! Runs the engine pipeline with the saved settings (vocabulary, language,
+//! provider) without starting the webview, tray or shortcuts. Nothing is
+//! added to the history. On Windows release builds the app has no console,
+//! so use `--output` or redirect stdout to collect the text.
+
+use crate::config::{Settings, TranscriptionProvider, WhisperModel};
+use crate::engine::Engine;
+use std::path::PathBuf;
+
+/// Usage shown on argument errors
+pub const USAGE: &str = "\
+Usage: gigawhisper --transcribe <FILE> [options]
+
+Options:
+  --model <tiny|base|small|medium|large>  Transcribe locally with this model
+  --language <CODE>                       Language code, or \"auto\"
+  --output <FILE>                         Write the text to a file instead of stdout
+  --json                                  Print the full transcript as JSON
+  --download                              Download the model first if it is missing";
+
+/// Options of a command line transcription
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CliArgs {
+    pub input: PathBuf,
+    /// Local model overriding the saved provider
+    pub model: Option<WhisperModel>,
+    pub language: Option<String>,
+    pub output: Option<PathBuf>,
+    pub json: bool,
+    pub download: bool,
+}
+
+/// Parse the arguments after the program name
+///
+/// Returns `Ok(None)` when `--transcribe` is absent, so the desktop app
+/// starts as usual (with any audio files passed to open).
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Option<CliArgs>, String> {
+    let args: Vec<String> = args.into_iter().collect();
+    if !args.iter().any(|arg| arg == "--transcribe") {
+        return Ok(None);
+    }
+
+    let mut parsed = CliArgs::default();
+    let mut input = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| {
+            args.next()
+                .filter(|value| !value.starts_with("--"))
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+        match arg.as_str() {
+            "--transcribe" => input = Some(PathBuf::from(value("--transcribe")?)),
+            "--model" => parsed.model = Some(value("--model")?.parse()?),
+            "--language" => parsed.language = Some(value("--language")?),
+            "--output" => parsed.output = Some(PathBuf::from(value("--output")?)),
+            "--json" => parsed.json = true,
+            "--download" => parsed.download = true,
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    parsed.input = input.ok_or_else(|| "--transcribe needs a value".to_string())?;
+    Ok(Some(parsed))
+}
+
+/// Saved settings with the command line overrides applied
+pub fn apply_overrides(mut settings: Settings, args: &CliArgs) -> Settings {
+    if let Some(model) = &args.model {
+        settings.transcription.provider = TranscriptionProvider::Local;
+        settings.transcription.local.model = model.clone();
+        settings.transcription.local.custom_model = None;
+    }
+    if let Some(language) = &args.language {
+        settings.transcription.language = language.clone();
+    }
+    settings
+}
+
+/// Transcribe the file described by `args`, returning what to print
+pub fn transcribe(args: &CliArgs) -> Result<String, String> {
+    let settings = crate::config::load_settings().map_err(|e| e.to_string())?;
+    crate::config::set_active_workspace(&settings.workspaces.active);
+    crate::config::set_shared_models_dir(settings.model_storage.shared_dir.as_deref());
+    let settings = apply_overrides(settings, args);
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| format!("Failed to start async runtime: {}", e))?;
+
+    let local = &settings.transcription.local;
+    if settings.transcription.provider == TranscriptionProvider::Local
+        && local.custom_model.is_none()
+    {
+        let (path, resolution) = crate::models::resolve_model(&local.model, &local.quantization);
+        if resolution.used.is_none() {
+            if !args.download {
+                return Err(format!(
+                    "Model {:?} is not downloaded ({}); pass --download to fetch it",
+                    local.model,
+                    path.display()
+                ));
+            }
+            eprintln!("Downloading {:?} model...", local.model);
+            runtime
+                .block_on(crate::models::download_model_with_quantization(
+                    &local.model,
+                    &local.quantization,
+                    crate::config::models_dir(),
+                    None,
+                ))
+                .map_err(|e| format!("Model download failed: {}", e))?;
+        }
+    }
+
+    let (samples, sample_rate) = crate::audio::decode_audio_file(&args.input)
+        .map_err(|e| format!("Failed to read {}: {}", args.input.display(), e))?;
+
+    let engine = Engine::new(settings).save_history(false);
+    let transcript = runtime
+        .block_on(engine.transcribe(samples, sample_rate))
+        .map_err(|e| e.to_string())?;
+
+    if args.json {
+        serde_json::to_string_pretty(&transcript).map_err(|e| e.to_string())
+    } else {
+        Ok(transcript.text)
+    }
+}
+
+/// Run command line mode if the arguments ask for it
+///
+/// Returns the process exit code, or None to start the desktop app.
+pub fn run_from_args<I: IntoIterator<Item = String>>(args: I) -> Option<i32> {
+    let args = match parse_args(args) {
+        Ok(Some(args)) => args,
+        Ok(None) => return None,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return Some(2);
+        }
+    };
+
+    let result = transcribe(&args).and_then(|text| match &args.output {
+        Some(path) => std::fs::write(path, format!("{}\n", text))
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e)),
+        None => {
+            println!("{}", text);
+            Ok(())
+        }
+    });
+
+    match result {
+        Ok(()) => Some(0),
+        Err(e) => {
+            eprintln!("{}", e);
+            Some(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        assert_eq!(parse_args(args("notes.wav")), Ok(None));
+        assert_eq!(parse_args(Vec::new()), Ok(None));
+
+        let parsed = parse_args(args(
+            "--transcribe a.wav --model Small --language fr --output out.txt --json",
+        ))
+        .unwrap()
+        .unwrap();
+        assert_eq!(parsed.input, PathBuf::from("a.wav"));
+        assert_eq!(parsed.model, Some(WhisperModel::Small));
+        assert_eq!(parsed.language.as_deref(), Some("fr"));
+        assert_eq!(parsed.output, Some(PathBuf::from("out.txt")));
+        assert!(parsed.json);
+        assert!(!parsed.download);
+    }
+
+    #[test]
+    fn test_parse_args_errors() {
+        assert!(parse_args(args("--transcribe")).is_err());
+        assert!(parse_args(args("--transcribe a.wav --model huge")).is_err());
+        assert!(parse_args(args("--transcribe a.wav --output --json")).is_err());
+        assert!(parse_args(args("--transcribe a.wav b.wav")).is_err());
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let mut settings = Settings::default();
+        settings.transcription.provider = TranscriptionProvider::Groq;
+
+        let cli = CliArgs {
+            model: Some(WhisperModel::Tiny),
+            language: Some("de".to_string()),
+            ..Default::default()
+        };
+        let settings = apply_overrides(settings, &cli);
+        assert_eq!(
+            settings.transcription.provider,
+            TranscriptionProvider::Local
+        );
+        assert_eq!(settings.transcription.local.model, WhisperModel::Tiny);
+        assert_eq!(settings.transcription.language, "de");
+
+        let unchanged = apply_overrides(Settings::default(), &CliArgs::default());
+        assert_eq!(
+            unchanged.transcription.language,
+            Settings::default().transcription.language
+        );
+    }
+}
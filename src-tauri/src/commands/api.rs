@@ -0,0 +1,97 @@
+//! API Client Commands
+//!
+//! Manage the clients allowed to use the local API and plugins, and read
+//! the audit log of their calls. Changes are audited too.
+
+use crate::api::{self, ApiClientInfo, ApiScope, AuditEntry, IssuedToken, PermissionError};
+
+/// Default number of audit entries returned
+const DEFAULT_AUDIT_LIMIT: usize = 200;
+
+/// Save the registry after a change and audit it
+fn commit_change(
+    action: &str,
+    client: &ApiClientInfo,
+    detail: Option<String>,
+) -> Result<(), String> {
+    api::get_client_registry()
+        .read()
+        .save()
+        .map_err(|e| e.to_string())?;
+    api::audit_log().append(&AuditEntry::new(action, Some(client), None, true, detail));
+    Ok(())
+}
+
+fn scopes_detail(scopes: &[ApiScope]) -> Option<String> {
+    Some(
+        scopes
+            .iter()
+            .map(ApiScope::to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+/// List registered API clients
+#[tauri::command]
+pub fn list_api_clients() -> Vec<ApiClientInfo> {
+    api::get_client_registry().read().clients()
+}
+
+/// Register an API client, returning its token (shown only once)
+#[tauri::command]
+pub fn create_api_client(name: String, scopes: Vec<ApiScope>) -> Result<IssuedToken, String> {
+    let issued = api::get_client_registry()
+        .write()
+        .register(&name, scopes)
+        .map_err(|e| e.to_string())?;
+    commit_change("client.create", &issued.client, scopes_detail(&issued.client.scopes))?;
+    Ok(issued)
+}
+
+/// Replace the scopes granted to an API client
+#[tauri::command]
+pub fn update_api_client_scopes(
+    id: String,
+    scopes: Vec<ApiScope>,
+) -> Result<ApiClientInfo, String> {
+    let client = api::get_client_registry()
+        .write()
+        .set_scopes(&id, scopes)
+        .map_err(|e| e.to_string())?;
+    commit_change("client.update-scopes", &client, scopes_detail(&client.scopes))?;
+    Ok(client)
+}
+
+/// Issue a new token for an API client, revoking the previous one
+#[tauri::command]
+pub fn rotate_api_client_token(id: String) -> Result<IssuedToken, String> {
+    let issued = api::get_client_registry()
+        .write()
+        .rotate_token(&id)
+        .map_err(|e| e.to_string())?;
+    commit_change("client.rotate-token", &issued.client, None)?;
+    Ok(issued)
+}
+
+/// Remove an API client, revoking its token
+#[tauri::command]
+pub fn delete_api_client(id: String) -> Result<(), String> {
+    let client = {
+        let mut registry = api::get_client_registry().write();
+        let client = registry
+            .clients()
+            .into_iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| PermissionError::UnknownClient(id.clone()).to_string())?;
+        registry.remove(&id);
+        client
+    };
+    commit_change("client.delete", &client, None)
+}
+
+/// Most recent audit log entries, newest first
+#[tauri::command]
+pub fn get_api_audit_log(limit: Option<usize>) -> Vec<AuditEntry> {
+    api::audit_log().entries(limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+}
@@ -3,41 +3,79 @@
 //! Handle text output and history operations.
 
 use tauri::State;
+use crate::output;
 use crate::AppState;
 
 /// Paste text to the active application
+///
+/// Uses the selection configured in output settings. Recorded in the
+/// injection audit like transcriptions.
 #[tauri::command]
-pub async fn paste_text(_state: State<'_, AppState>, text: String) -> Result<(), String> {
+pub async fn paste_text(state: State<'_, AppState>, text: String) -> Result<(), String> {
     tracing::info!("Pasting text: {} chars", text.len());
 
-    // TODO: Implement actual paste logic
-    // 1. Save current clipboard
-    // 2. Set text to clipboard
-    // 3. Simulate Ctrl+V
-    // 4. Restore clipboard
+    let (selection, format, audit) = {
+        let config = state.config.read();
+        (
+            config.output.selection,
+            config.output.clipboard_format,
+            config.output.injection_audit.clone(),
+        )
+    };
+    let audited = audit.enabled && !text.is_empty();
 
-    // For now, just set clipboard
-    let mut clipboard = arboard::Clipboard::new()
-        .map_err(|e| format!("Failed to access clipboard: {}", e))?;
+    // Capture the target before the paste can move focus
+    let target = audited
+        .then(|| output::get_active_window().map(|w| w.process_name))
+        .flatten();
+    let result = output::paste_text(&text, selection, format)
+        .await
+        .map_err(|e| format!("Failed to paste: {}", e));
+    if audited {
+        let method = if output::effective_selection(selection).uses_clipboard() {
+            output::InjectionMethod::Paste
+        } else {
+            output::InjectionMethod::PrimarySelection
+        };
+        let delivered = result.clone().map(|()| (method, None));
+        output::injection_audit().append(
+            &output::InjectionRecord::new(target, text.chars().count(), &delivered),
+            audit.retention_days,
+        );
+    }
+    result?;
 
-    clipboard
-        .set_text(&text)
-        .map_err(|e| format!("Failed to set clipboard: {}", e))?;
-
-    tracing::info!("Text copied to clipboard");
+    tracing::info!("Text pasted ({:?})", selection);
     Ok(())
 }
 
+/// Default number of injection audit records returned
+const DEFAULT_AUDIT_LIMIT: usize = 200;
+
+/// Most recent injection audit records, newest first
+#[tauri::command]
+pub fn get_injection_audit(limit: Option<usize>) -> Vec<output::InjectionRecord> {
+    output::injection_audit().records(limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+}
+
+/// Export the injection audit log as CSV, returning the number of records
+#[tauri::command]
+pub fn export_injection_audit(path: String) -> Result<usize, String> {
+    output::injection_audit()
+        .export_csv(std::path::Path::new(&path))
+        .map_err(|e| format!("Failed to export injection audit: {}", e))
+}
+
 /// Get transcription history
 #[tauri::command]
-pub fn get_history(_state: State<'_, AppState>) -> Vec<HistoryEntry> {
+pub fn get_history(_state: State<'_, AppState>) -> Vec<ClipboardHistoryEntry> {
     // TODO: Load from persistent storage
     Vec::new()
 }
 
 /// DTO for history entry
-#[derive(serde::Serialize)]
-pub struct HistoryEntry {
+#[derive(serde::Serialize, schemars::JsonSchema)]
+pub struct ClipboardHistoryEntry {
     pub id: String,
     pub text: String,
     pub timestamp: u64,
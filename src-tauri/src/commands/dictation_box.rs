@@ -0,0 +1,98 @@
+//! Dictation Box Commands
+//!
+//! Open, edit and send the floating dictation box.
+
+use crate::output::{self, DictationBox, InjectionMethod, DICTATION_BOX_LABEL};
+use crate::AppState;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Delay after hiding the box so focus returns to the target window
+const SEND_FOCUS_DELAY_MS: u64 = 150;
+
+/// The focused window, unless it is one of GigaWhisper's own
+pub(crate) fn focused_target() -> Option<isize> {
+    output::get_active_window()
+        .filter(|window| !window.is_gigawhisper())
+        .map(|window| window.id)
+}
+
+/// Open the dictation box, sending its text to `target` later
+///
+/// Reopening while already open starts a fresh session.
+pub(crate) fn show_dictation_box(app: &AppHandle, target: Option<isize>) -> Result<(), String> {
+    app.state::<AppState>().dictation_box.lock().open(target);
+
+    let window = app
+        .get_webview_window(DICTATION_BOX_LABEL)
+        .ok_or_else(|| "Dictation box window not found".to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    let _ = window.set_focus();
+
+    let _ = app.emit("dictation-box:opened", ());
+    tracing::info!("Dictation box opened");
+    Ok(())
+}
+
+fn hide_dictation_box(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(DICTATION_BOX_LABEL) {
+        let _ = window.hide();
+    }
+    let _ = app.emit("dictation-box:closed", ());
+}
+
+/// Open the dictation box, targeting the currently focused window
+#[tauri::command]
+pub fn open_dictation_box(app: AppHandle) -> Result<(), String> {
+    show_dictation_box(&app, focused_target())
+}
+
+/// Get the dictation box state
+#[tauri::command]
+pub fn get_dictation_box(state: State<'_, AppState>) -> DictationBox {
+    state.dictation_box.lock().clone()
+}
+
+/// Store the user's edits
+#[tauri::command]
+pub fn update_dictation_box(state: State<'_, AppState>, text: String) {
+    state.dictation_box.lock().update(text);
+}
+
+/// Close the box and inject its text into the previously focused app
+///
+/// `text` carries the latest edits; when omitted the stored text is sent.
+#[tauri::command]
+pub async fn send_dictation_box(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    text: Option<String>,
+) -> Result<InjectionMethod, String> {
+    let (stored, target) = state
+        .dictation_box
+        .lock()
+        .take()
+        .ok_or_else(|| "Dictation box is not open".to_string())?;
+    let text = text.unwrap_or(stored);
+
+    hide_dictation_box(&app);
+    if let Some(id) = target {
+        output::focus_window(id);
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(SEND_FOCUS_DELAY_MS)).await;
+
+    let service = state.transcription_service.clone();
+    let output_settings = state.config.read().output.clone();
+    let method = service
+        .output_text(&text, &app, &output_settings, false)
+        .await?;
+
+    tracing::info!("Dictation box sent ({} chars, {:?})", text.len(), method);
+    Ok(method)
+}
+
+/// Close the box, discarding its text
+#[tauri::command]
+pub fn close_dictation_box(app: AppHandle, state: State<'_, AppState>) {
+    state.dictation_box.lock().take();
+    hide_dictation_box(&app);
+}
@@ -2,44 +2,72 @@
 //!
 //! Tauri commands for managing transcription history.
 
-use crate::history::{self, HistoryEntry};
+use crate::audio::{self, AudioFormat, TranscodeOptions};
+use crate::history::{
+    self, HistoryEntry, HistoryExportFormat, HistoryQuery, HistorySearchResult, IncognitoEntry,
+    IntegrityReport, MinutesFormat, MinutesTemplate, RecentText, TagCount,
+};
+use crate::output::{self, SubtitleFormat};
+use crate::vocabulary::{self, CorrectionOutcome};
+use crate::AppState;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use std::path::Path;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Default number of entries returned to the quick-paste palette
+const DEFAULT_PALETTE_LIMIT: usize = 10;
+
+/// Default page size of history searches
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Largest page of history searches
+const MAX_SEARCH_LIMIT: usize = 500;
+
+/// Delay after hiding the palette so focus returns to the target window
+const PALETTE_FOCUS_DELAY_MS: u64 = 150;
 
 /// Validate that a file path is safely within the audio directory.
 /// Returns the canonicalized path if valid, or None if the path is outside
 /// the expected audio directory (potential path traversal attack).
 fn validate_audio_path(audio_path: &str) -> Option<std::path::PathBuf> {
-    let audio_dir = crate::history::audio_dir();
+    validate_path_within(audio_path, &crate::history::audio_dir())
+}
 
-    // Canonicalize the audio directory (create it if needed for canonicalization)
-    let _ = std::fs::create_dir_all(&audio_dir);
-    let canonical_audio_dir = match std::fs::canonicalize(&audio_dir) {
+/// Validate that a file path is safely within the screenshots directory.
+fn validate_screenshot_path(screenshot_path: &str) -> Option<std::path::PathBuf> {
+    validate_path_within(screenshot_path, &crate::history::screenshots_dir())
+}
+
+fn validate_path_within(file_path: &str, dir: &Path) -> Option<std::path::PathBuf> {
+    // Canonicalize the directory (create it if needed for canonicalization)
+    let _ = std::fs::create_dir_all(dir);
+    let canonical_dir = match std::fs::canonicalize(dir) {
         Ok(p) => p,
         Err(e) => {
-            tracing::warn!("Failed to canonicalize audio directory: {}", e);
+            tracing::warn!("Failed to canonicalize directory '{}': {}", dir.display(), e);
             return None;
         }
     };
 
     // Canonicalize the provided path
-    let path = Path::new(audio_path);
+    let path = Path::new(file_path);
     let canonical_path = match std::fs::canonicalize(path) {
         Ok(p) => p,
         Err(e) => {
-            tracing::warn!("Failed to canonicalize audio path '{}': {}", audio_path, e);
+            tracing::warn!("Failed to canonicalize path '{}': {}", file_path, e);
             return None;
         }
     };
 
-    // Verify the path is within the audio directory
-    if canonical_path.starts_with(&canonical_audio_dir) {
+    // Verify the path is within the directory
+    if canonical_path.starts_with(&canonical_dir) {
         Some(canonical_path)
     } else {
         tracing::warn!(
-            "Path traversal attempt detected: '{}' is not within audio directory '{}'",
-            audio_path,
-            audio_dir.display()
+            "Path traversal attempt detected: '{}' is not within directory '{}'",
+            file_path,
+            dir.display()
         );
         None
     }
@@ -64,10 +92,12 @@ pub fn get_history_entry(id: String) -> Option<HistoryEntry> {
 pub fn delete_history_entry(id: String) -> bool {
     let history = history::get_history();
 
-    // Get entry to find audio path before deletion
-    let audio_path = {
+    // Get entry to find audio and screenshot paths before deletion
+    let (audio_path, screenshot_path) = {
         let h = history.read();
-        h.get(&id).and_then(|e| e.audio_path.clone())
+        h.get(&id)
+            .map(|e| (e.audio_path.clone(), e.screenshot_path.clone()))
+            .unwrap_or_default()
     };
 
     let mut history = history.write();
@@ -82,6 +112,10 @@ pub fn delete_history_entry(id: String) -> bool {
                 tracing::debug!("Deleted audio file: {}", validated_path.display());
             }
         }
+        if let Some(validated_path) = screenshot_path.as_deref().and_then(validate_screenshot_path) {
+            let _ = std::fs::remove_file(&validated_path);
+        }
+        audio::remove_cached_transcodes(&id);
     }
     deleted
 }
@@ -91,13 +125,14 @@ pub fn delete_history_entry(id: String) -> bool {
 pub fn clear_history() {
     let history = history::get_history();
 
-    // Collect all audio paths before clearing
-    let audio_paths: Vec<String> = {
+    // Collect all audio and screenshot paths before clearing
+    let (audio_paths, screenshot_paths): (Vec<String>, Vec<String>) = {
         let h = history.read();
-        h.entries()
-            .iter()
-            .filter_map(|e| e.audio_path.clone())
-            .collect()
+        let entries = h.entries();
+        (
+            entries.iter().filter_map(|e| e.audio_path.clone()).collect(),
+            entries.iter().filter_map(|e| e.screenshot_path.clone()).collect(),
+        )
     };
 
     let mut history = history.write();
@@ -111,8 +146,17 @@ pub fn clear_history() {
         }
     }
 
-    // Try to remove the audio directory if empty
+    for path in screenshot_paths {
+        if let Some(validated_path) = validate_screenshot_path(&path) {
+            let _ = std::fs::remove_file(&validated_path);
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(audio::transcode_cache_dir());
+
+    // Try to remove the audio and screenshot directories if empty
     let _ = std::fs::remove_dir(crate::history::audio_dir());
+    let _ = std::fs::remove_dir(crate::history::screenshots_dir());
 }
 
 /// Get history count
@@ -122,13 +166,28 @@ pub fn get_history_count() -> usize {
     history.read().len()
 }
 
-/// Get audio data as base64 for a history entry
+/// Get incognito dictations still held in memory, newest first
 #[tauri::command]
-pub fn get_audio_data(id: String) -> Result<String, String> {
-    let history = history::get_history();
-    let entry = history
+pub fn get_incognito_entries() -> Vec<IncognitoEntry> {
+    history::incognito_store()
+        .lock()
+        .entries(std::time::Instant::now())
+}
+
+/// Wipe all incognito dictations now
+#[tauri::command]
+pub fn clear_incognito_entries(app: AppHandle) -> usize {
+    let wiped = history::incognito_store().lock().clear();
+    let _ = app.emit("incognito:updated", ());
+    crate::tray::refresh_tray_menu(&app);
+    wiped
+}
+
+/// Resolve the validated audio file of a history entry
+fn entry_audio_path(id: &str) -> Result<std::path::PathBuf, String> {
+    let entry = history::get_history()
         .read()
-        .get(&id)
+        .get(id)
         .ok_or_else(|| "Entry not found".to_string())?;
 
     let audio_path = entry
@@ -137,15 +196,386 @@ pub fn get_audio_data(id: String) -> Result<String, String> {
         .ok_or_else(|| "No audio file for this entry".to_string())?;
 
     // Validate the path is within the audio directory (prevent path traversal)
-    let validated_path = validate_audio_path(audio_path)
-        .ok_or_else(|| "Invalid audio file path".to_string())?;
+    validate_audio_path(audio_path).ok_or_else(|| "Invalid audio file path".to_string())
+}
+
+/// Get audio data as a base64 data URL for a history entry
+///
+/// Without options the recording is returned as saved (WAV, FLAC or Opus).
+/// For large recordings prefer the `gwaudio://` protocol, which serves byte
+/// ranges.
+#[tauri::command]
+pub async fn get_audio_data(
+    id: String,
+    format: Option<AudioFormat>,
+    bitrate_kbps: Option<u32>,
+    sample_rate: Option<u32>,
+) -> Result<String, String> {
+    let validated_path = entry_audio_path(&id)?;
+    let options = TranscodeOptions {
+        format: format.unwrap_or_default(),
+        bitrate_kbps,
+        sample_rate,
+    };
 
-    let audio_bytes = std::fs::read(&validated_path)
-        .map_err(|e| format!("Failed to read audio file: {}", e))?;
+    let mime_type = if options == TranscodeOptions::default() {
+        audio::recording_mime_type(&validated_path)
+    } else {
+        options.format.mime_type()
+    };
+    let audio_bytes = tauri::async_runtime::spawn_blocking(move || {
+        if options == TranscodeOptions::default() {
+            std::fs::read(&validated_path).map_err(|e| format!("Failed to read audio file: {}", e))
+        } else {
+            audio::transcode(&validated_path, &options).map_err(|e| e.to_string())
+        }
+    })
+    .await
+    .map_err(|e| e.to_string())??;
 
     let base64_data = STANDARD.encode(&audio_bytes);
 
-    Ok(format!("data:audio/wav;base64,{}", base64_data))
+    Ok(format!("data:{};base64,{}", mime_type, base64_data))
+}
+
+/// Serve a history recording over the `gwaudio` protocol
+///
+/// The URL path is the entry ID and the query selects the transcoding
+/// (`format`, `bitrate`, `rate`). Single `Range` requests are answered with
+/// partial content so the player can stream and seek.
+pub fn audio_protocol_response(request: &Request<Vec<u8>>) -> Response<Vec<u8>> {
+    let error = |status: StatusCode, message: String| {
+        Response::builder()
+            .status(status)
+            .header(header::CONTENT_TYPE, "text/plain")
+            .body(message.into_bytes())
+            .unwrap_or_default()
+    };
+
+    let id = request.uri().path().trim_start_matches('/');
+    let source = match entry_audio_path(id) {
+        Ok(path) => path,
+        Err(e) => return error(StatusCode::NOT_FOUND, e),
+    };
+    let options = match TranscodeOptions::from_query(request.uri().query().unwrap_or_default()) {
+        Ok(options) => options,
+        Err(e) => return error(StatusCode::BAD_REQUEST, e.to_string()),
+    };
+    let (path, mime_type) = if options == TranscodeOptions::default() {
+        let mime_type = audio::recording_mime_type(&source);
+        (source, mime_type)
+    } else {
+        match audio::cached_transcode(&source, &options) {
+            Ok(path) => (path, options.format.mime_type()),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        }
+    };
+    let len = match std::fs::metadata(&path) {
+        Ok(metadata) => metadata.len(),
+        Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+    };
+
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, mime_type)
+        .header(header::ACCEPT_RANGES, "bytes");
+    let range = request
+        .headers()
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok());
+    let response = match range {
+        None => match std::fs::read(&path) {
+            Ok(bytes) => response.status(StatusCode::OK).body(bytes),
+            Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        },
+        Some(range) => match audio::parse_byte_range(range, len) {
+            Some((start, end)) => match audio::read_byte_range(&path, start, end) {
+                Ok(bytes) => response
+                    .status(StatusCode::PARTIAL_CONTENT)
+                    .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+                    .body(bytes),
+                Err(e) => return error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+            },
+            None => response
+                .status(StatusCode::RANGE_NOT_SATISFIABLE)
+                .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+                .body(Vec::new()),
+        },
+    };
+    response.unwrap_or_default()
+}
+
+/// Get the window screenshot of a history entry as a data URL
+#[tauri::command]
+pub fn get_screenshot_data(id: String) -> Result<String, String> {
+    let history = history::get_history();
+    let entry = history
+        .read()
+        .get(&id)
+        .ok_or_else(|| "Entry not found".to_string())?;
+
+    let screenshot_path = entry
+        .screenshot_path
+        .as_ref()
+        .ok_or_else(|| "No screenshot for this entry".to_string())?;
+
+    let validated_path = validate_screenshot_path(screenshot_path)
+        .ok_or_else(|| "Invalid screenshot path".to_string())?;
+
+    let bytes = std::fs::read(&validated_path)
+        .map_err(|e| format!("Failed to read screenshot: {}", e))?;
+
+    Ok(format!("data:image/bmp;base64,{}", STANDARD.encode(&bytes)))
+}
+
+/// Export a history entry as meeting minutes (DOCX or PDF)
+///
+/// `template` names a template in the minutes templates directory; the
+/// built-in layout is used when omitted.
+#[tauri::command]
+pub fn export_meeting_minutes(
+    id: String,
+    format: MinutesFormat,
+    path: String,
+    template: Option<String>,
+) -> Result<(), String> {
+    let entry = history::get_history()
+        .read()
+        .get(&id)
+        .ok_or_else(|| "Entry not found".to_string())?;
+    let template = MinutesTemplate::load(template.as_deref()).map_err(|e| e.to_string())?;
+
+    history::export_minutes(&entry, format, &template, Path::new(&path))
+        .map_err(|e| format!("Failed to export minutes: {}", e))
+}
+
+/// Export a history entry as subtitles (SRT or WebVTT)
+///
+/// Entries without segment timestamps are timed by spreading the text
+/// over the recording.
+#[tauri::command]
+pub fn export_subtitles(id: String, format: SubtitleFormat, path: String) -> Result<(), String> {
+    let entry = history::get_history()
+        .read()
+        .get(&id)
+        .ok_or_else(|| "Entry not found".to_string())?;
+
+    let cues = if entry.segments.is_empty() {
+        let duration_ms = entry
+            .recording_ms
+            .or_else(|| {
+                let audio_path = validate_audio_path(entry.audio_path.as_deref()?)?;
+                history::audio_duration_ms(&audio_path)
+            })
+            .ok_or_else(|| "No timing information for this entry".to_string())?;
+        output::estimate_cues(&entry.text, duration_ms)
+    } else {
+        output::segment_cues(&entry.segments)
+    };
+
+    std::fs::write(&path, output::render_subtitles(&cues, format))
+        .map_err(|e| format!("Failed to export subtitles: {}", e))
+}
+
+/// Export the history, or the entries matching `query`, to a file
+///
+/// `path` comes from the UI's save dialog. The query's paging is ignored so
+/// every match is exported, newest first. Returns the number of entries
+/// written.
+#[tauri::command]
+pub fn export_history(
+    format: HistoryExportFormat,
+    path: String,
+    query: Option<HistoryQuery>,
+) -> Result<usize, String> {
+    let query = HistoryQuery {
+        offset: 0,
+        limit: None,
+        ..query.unwrap_or_default()
+    };
+    let entries = history::get_history().read().search(&query).entries;
+
+    std::fs::write(&path, history::render_history(&entries, format))
+        .map_err(|e| format!("Failed to export history: {}", e))?;
+    tracing::info!("Exported {} history entries to {}", entries.len(), path);
+    Ok(entries.len())
+}
+
+/// Set the title of a history entry, returning the title applied
+///
+/// A blank title restores the one generated from the first sentence.
+#[tauri::command]
+pub fn set_entry_title(app: AppHandle, id: String, title: String) -> Result<String, String> {
+    if title.trim().chars().count() > history::MAX_TITLE_CHARS {
+        return Err(format!("Title is longer than {} characters", history::MAX_TITLE_CHARS));
+    }
+    let title = {
+        let mut history = history::get_history().write();
+        if !history.set_title(&id, &title) {
+            return Err("Entry not found".to_string());
+        }
+        history.save().map_err(|e| e.to_string())?;
+        history.get(&id).map(|e| e.title).unwrap_or_default()
+    };
+    let _ = app.emit("history:updated", ());
+    Ok(title)
+}
+
+/// Attach a note to a history entry (blank removes it)
+#[tauri::command]
+pub fn set_entry_note(app: AppHandle, id: String, note: String) -> Result<(), String> {
+    if note.trim().chars().count() > history::MAX_NOTE_CHARS {
+        return Err(format!("Note is longer than {} characters", history::MAX_NOTE_CHARS));
+    }
+    {
+        let mut history = history::get_history().write();
+        if !history.set_note(&id, &note) {
+            return Err("Entry not found".to_string());
+        }
+        history.save().map_err(|e| e.to_string())?;
+    }
+    let _ = app.emit("history:updated", ());
+    Ok(())
+}
+
+/// Tag a history entry, returning its tags
+#[tauri::command]
+pub fn add_entry_tag(app: AppHandle, id: String, tag: String) -> Result<Vec<String>, String> {
+    let tag = history::normalize_tag(&tag);
+    if tag.is_empty() {
+        return Err("Tag is empty".to_string());
+    }
+    if tag.chars().count() > history::MAX_TAG_CHARS {
+        return Err(format!("Tag is longer than {} characters", history::MAX_TAG_CHARS));
+    }
+    update_entry_tags(&app, &id, |history| history.add_tag(&id, &tag))
+}
+
+/// Remove a tag from a history entry, returning its remaining tags
+#[tauri::command]
+pub fn remove_entry_tag(app: AppHandle, id: String, tag: String) -> Result<Vec<String>, String> {
+    update_entry_tags(&app, &id, |history| history.remove_tag(&id, &tag))
+}
+
+/// Apply a tag change to an entry, save and notify the history view
+fn update_entry_tags(
+    app: &AppHandle,
+    id: &str,
+    change: impl FnOnce(&mut history::TranscriptionHistory) -> bool,
+) -> Result<Vec<String>, String> {
+    let tags = {
+        let mut history = history::get_history().write();
+        if !change(&mut history) {
+            return Err("Entry not found".to_string());
+        }
+        history.save().map_err(|e| e.to_string())?;
+        history.get(id).map(|e| e.tags).unwrap_or_default()
+    };
+    let _ = app.emit("history:updated", ());
+    Ok(tags)
+}
+
+/// Every tag used in the history with its number of entries
+#[tauri::command]
+pub fn get_history_tags() -> Vec<TagCount> {
+    history::get_history().read().tags()
+}
+
+/// Correct the text of a history entry
+///
+/// The original and corrected texts are kept locally; fixes repeated across
+/// entries become replacement rules in the vocabulary, and names typed in
+/// become learned terms.
+#[tauri::command]
+pub fn submit_correction(
+    entry_id: String,
+    corrected_text: String,
+) -> Result<CorrectionOutcome, String> {
+    let corrected = corrected_text.trim();
+    if corrected.is_empty() {
+        return Err("Corrected text is empty".to_string());
+    }
+
+    let original = {
+        let mut history = history::get_history().write();
+        let entry = history
+            .get(&entry_id)
+            .ok_or_else(|| "Entry not found".to_string())?;
+        if entry.text == corrected {
+            return Ok(CorrectionOutcome::default());
+        }
+        history.set_text(&entry_id, corrected);
+        history.save().map_err(|e| e.to_string())?;
+        entry.text
+    };
+
+    vocabulary::learn_from_edit(&original, corrected);
+    vocabulary::learn_from_correction(&entry_id, &original, corrected).map_err(|e| e.to_string())
+}
+
+/// Get the most recent transcription texts for the quick-paste palette
+#[tauri::command]
+pub fn get_recent_texts(limit: Option<usize>) -> Vec<RecentText> {
+    let history = history::get_history();
+    history
+        .read()
+        .recent_texts(limit.unwrap_or(DEFAULT_PALETTE_LIMIT))
+}
+
+/// Fuzzy search recent transcriptions for the quick-paste palette
+#[tauri::command]
+pub fn fuzzy_search_history(query: String, limit: Option<usize>) -> Vec<RecentText> {
+    let history = history::get_history();
+    history
+        .read()
+        .fuzzy_search(&query, limit.unwrap_or(DEFAULT_PALETTE_LIMIT))
+}
+
+/// Search the history by text, date range, provider, language and tags
+///
+/// Returns one page of matching entries, newest first.
+#[tauri::command]
+pub fn search_history(mut query: HistoryQuery) -> HistorySearchResult {
+    query.limit = Some(query.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT));
+    history::get_history().read().search(&query)
+}
+
+/// Cross-check history entries against their audio and screenshot files
+///
+/// With `repair`, clears references to missing or corrupted files and
+/// deletes files no entry refers to.
+#[tauri::command]
+pub fn verify_history_integrity(repair: bool) -> Result<IntegrityReport, String> {
+    history::verify_integrity(repair).map_err(|e| e.to_string())
+}
+
+/// Paste a history entry into the previously focused application
+///
+/// Hides the main window first so focus returns to the target, then routes
+/// the text through the regular transcription output pipeline.
+#[tauri::command]
+pub async fn paste_history_entry(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let entry = history::get_history()
+        .read()
+        .get(&id)
+        .ok_or_else(|| "Entry not found".to_string())?;
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(PALETTE_FOCUS_DELAY_MS)).await;
+
+    let service = state.transcription_service.clone();
+    let output_settings = state.config.read().output.clone();
+    service
+        .output_text(&entry.text, &app, &output_settings, false)
+        .await?;
+
+    tracing::info!("History entry {} pasted ({} chars)", id, entry.text.len());
+    Ok(())
 }
 
 #[cfg(test)]
@@ -240,6 +670,12 @@ mod tests {
         assert!(!result);
     }
 
+    #[test]
+    fn test_submit_correction_validation() {
+        assert!(submit_correction("nonexistent-id-67890".to_string(), "text".to_string()).is_err());
+        assert!(submit_correction("nonexistent-id-67890".to_string(), "  ".to_string()).is_err());
+    }
+
     #[test]
     fn test_delete_history_entry_empty_id() {
         let result = delete_history_entry("".to_string());
@@ -262,19 +698,29 @@ mod tests {
     // get_audio_data Tests
     // =========================================================================
 
-    #[test]
-    fn test_get_audio_data_nonexistent_entry() {
-        let result = get_audio_data("nonexistent-id-audio".to_string());
+    #[tokio::test]
+    async fn test_get_audio_data_nonexistent_entry() {
+        let result = get_audio_data("nonexistent-id-audio".to_string(), None, None, None).await;
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("not found"));
     }
 
-    #[test]
-    fn test_get_audio_data_empty_id() {
-        let result = get_audio_data("".to_string());
+    #[tokio::test]
+    async fn test_get_audio_data_empty_id() {
+        let result = get_audio_data("".to_string(), None, None, None).await;
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_audio_protocol_unknown_entry() {
+        let request = Request::builder()
+            .uri("gwaudio://localhost/nonexistent-id-audio?format=opus")
+            .body(Vec::new())
+            .unwrap();
+        let response = audio_protocol_response(&request);
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
     // =========================================================================
     // clear_history Tests
     // =========================================================================
@@ -289,10 +735,36 @@ mod tests {
         assert_eq!(get_history_count(), 0);
     }
 
+    // =========================================================================
+    // Quick-paste palette Tests
+    // =========================================================================
+
+    #[test]
+    fn test_get_recent_texts_respects_limit() {
+        let recent = get_recent_texts(Some(3));
+        assert!(recent.len() <= 3);
+    }
+
+    #[test]
+    fn test_fuzzy_search_history_no_match() {
+        let results = fuzzy_search_history("\u{1F984}qzxv-no-such-text".to_string(), None);
+        assert!(results.is_empty());
+    }
+
     // =========================================================================
     // Integration-style Tests
     // =========================================================================
 
+    #[test]
+    fn test_export_history_invalid_path() {
+        let result = export_history(
+            HistoryExportFormat::Csv,
+            "/nonexistent/dir/history.csv".to_string(),
+            None,
+        );
+        assert!(result.unwrap_err().contains("Failed to export history"));
+    }
+
     #[test]
     fn test_history_count_matches_entries() {
         let entries = get_transcription_history();
@@ -2,9 +2,18 @@
 //!
 //! IPC commands exposed to the frontend.
 
+pub mod api;
 pub mod clipboard;
+pub mod dictation_box;
 pub mod history;
 pub mod models;
 pub mod recording;
 pub mod settings;
+pub mod system;
 pub mod transcription;
+pub mod viewer;
+pub mod vocabulary;
+pub mod workspace;
+
+// Update commands live with the updater
+pub use crate::updater;
@@ -1,10 +1,72 @@
 //! Model Commands
 //!
-//! Tauri commands for model management (list, download, delete).
+//! Tauri commands for model management (list, download, import, delete).
 
-use crate::config::{models_dir, WhisperModel};
-use crate::models::{self, DownloadProgress, ModelInfo};
-use tauri::{AppHandle, Emitter};
+use crate::config::{
+    models_dir, ModelPruningSettings, ModelQuantization, QualityPreset, Settings,
+    TranscriptionProvider, VadEngine, WhisperModel,
+};
+use crate::models::{self, CustomModel, DownloadProgress, ModelInfo, ModelResolution, PruneReport};
+use crate::AppState;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Event carrying a `PruneReport` of models the pruning policy suggests removing
+pub const PRUNE_SUGGESTED_EVENT: &str = "models:prune-suggested";
+
+/// Event carrying a `QuantizationFallback` when the requested quantization
+/// of the local model is missing and another one is used
+pub const QUANTIZATION_FALLBACK_EVENT: &str = "models:quantization-fallback";
+
+/// How often the pruning policy looks for unused models
+pub const PRUNE_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(24 * 3600);
+
+/// Payload of the `model-download-progress` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModelDownloadProgress {
+    pub model: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: u64,
+    pub percentage: f32,
+    pub speed_bps: u64,
+}
+
+/// Payload of the `model-download-complete` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModelDownloadComplete {
+    pub model: String,
+    /// Path of the downloaded model file
+    pub path: String,
+}
+
+/// Payload of the `model-download-error` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModelDownloadError {
+    pub model: String,
+    pub error: String,
+}
+
+/// Payload of the `model-download-cancelled` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ModelDownloadCancelled {
+    pub model: String,
+}
+
+/// Payload of the `models:quantization-fallback` event
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct QuantizationFallback {
+    pub model: WhisperModel,
+    /// Quantization set in the settings
+    pub requested: ModelQuantization,
+    /// Downloaded quantization used instead
+    pub used: ModelQuantization,
+    /// Whether the requested quantization is being downloaded
+    pub downloading: bool,
+}
 
 /// List all models with download status
 #[tauri::command]
@@ -23,35 +85,53 @@ pub fn is_model_downloaded(model: String) -> Result<bool, String> {
 #[tauri::command]
 pub async fn download_model(app: AppHandle, model: String) -> Result<String, String> {
     let whisper_model = parse_model(&model)?;
+    download_with_events(&app, model, &whisper_model, &ModelQuantization::F16).await
+}
+
+/// Download a model, reporting progress and the outcome as events
+async fn download_with_events(
+    app: &AppHandle,
+    model: String,
+    whisper_model: &WhisperModel,
+    quantization: &ModelQuantization,
+) -> Result<String, String> {
     let dest_dir = models_dir();
 
-    tracing::info!("Starting download for model: {}", model);
+    tracing::info!("Starting download for model: {} ({:?})", model, quantization);
 
     // Create progress callback that emits events to frontend
     let app_clone = app.clone();
     let model_clone = model.clone();
     let progress_callback = Box::new(move |progress: DownloadProgress| {
-        let _ = app_clone.emit(
+        crate::utils::emit_throttled(
+            &app_clone,
             "model-download-progress",
-            serde_json::json!({
-                "model": model_clone,
-                "downloaded_bytes": progress.downloaded_bytes,
-                "total_bytes": progress.total_bytes,
-                "percentage": progress.percentage,
-                "speed_bps": progress.speed_bps
-            }),
+            ModelDownloadProgress {
+                model: model_clone.clone(),
+                downloaded_bytes: progress.downloaded_bytes,
+                total_bytes: progress.total_bytes,
+                percentage: progress.percentage,
+                speed_bps: progress.speed_bps,
+            },
         );
     });
 
     // Perform download
-    match models::download_model(&whisper_model, dest_dir, Some(progress_callback)).await {
+    let download = models::download_model_with_quantization(
+        whisper_model,
+        quantization,
+        dest_dir,
+        Some(progress_callback),
+    );
+    match download.await {
         Ok(path) => {
+            crate::utils::event_throttler().flush(app, "model-download-progress");
             let _ = app.emit(
                 "model-download-complete",
-                serde_json::json!({
-                    "model": model,
-                    "path": path.display().to_string()
-                }),
+                ModelDownloadComplete {
+                    model,
+                    path: path.display().to_string(),
+                },
             );
             Ok(path.display().to_string())
         }
@@ -59,10 +139,10 @@ pub async fn download_model(app: AppHandle, model: String) -> Result<String, Str
             let error_msg = e.to_string();
             let _ = app.emit(
                 "model-download-error",
-                serde_json::json!({
-                    "model": model,
-                    "error": error_msg
-                }),
+                ModelDownloadError {
+                    model,
+                    error: error_msg.clone(),
+                },
             );
             Err(error_msg)
         }
@@ -76,6 +156,39 @@ pub fn delete_model(model: String) -> Result<(), String> {
     models::delete_model(&whisper_model).map_err(|e| e.to_string())
 }
 
+/// Import a whisper.cpp model file (e.g. a fine-tuned one) under a display name
+///
+/// The file is copied into the models directory; select the returned id in
+/// `transcription.local.custom_model` to use it.
+#[tauri::command]
+pub async fn import_model(path: String, name: String) -> Result<CustomModel, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        models::import_model(std::path::Path::new(&path), &name)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// List the imported models
+///
+/// Async so the first call, which loads the list from disk, stays off the
+/// main thread.
+#[tauri::command]
+pub async fn list_custom_models() -> Vec<CustomModel> {
+    models::custom_models().read().models.clone()
+}
+
+/// Delete an imported model that is not selected
+#[tauri::command]
+pub fn delete_custom_model(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    let selected = state.config.read().transcription.local.custom_model.clone();
+    if selected.as_deref() == Some(id.as_str()) {
+        return Err("This model is in use, select another model first".to_string());
+    }
+    models::delete_custom_model(&id).map_err(|e| e.to_string())
+}
+
 /// Cancel an ongoing model download
 #[tauri::command]
 pub fn cancel_model_download(app: AppHandle, model: String) -> Result<bool, String> {
@@ -85,9 +198,7 @@ pub fn cancel_model_download(app: AppHandle, model: String) -> Result<bool, Stri
     if cancelled {
         let _ = app.emit(
             "model-download-cancelled",
-            serde_json::json!({
-                "model": model
-            }),
+            ModelDownloadCancelled { model },
         );
     }
 
@@ -101,6 +212,210 @@ pub fn is_model_downloading(model: String) -> Result<bool, String> {
     Ok(models::is_downloading(&whisper_model))
 }
 
+/// List or delete downloaded models unused for `days` (default: the pruning setting)
+///
+/// Only lists them unless `confirm` is set, so the user can review what
+/// would be removed. The configured and loaded models are always kept.
+#[tauri::command]
+pub fn prune_unused_models(
+    state: State<'_, AppState>,
+    days: Option<u32>,
+    confirm: bool,
+) -> Result<PruneReport, String> {
+    let config = state.config.read().clone();
+    let days = days.unwrap_or(config.model_pruning.unused_days);
+    if !ModelPruningSettings::UNUSED_DAYS_RANGE.contains(&days) {
+        return Err(format!(
+            "Days must be between {} and {}",
+            ModelPruningSettings::UNUSED_DAYS_RANGE.start(),
+            ModelPruningSettings::UNUSED_DAYS_RANGE.end()
+        ));
+    }
+
+    let loaded = state.transcription_service.loaded_model().map(|(path, _)| path);
+    let unused = models::unused_models(&config, loaded, days);
+    if !confirm {
+        return Ok(PruneReport {
+            models: unused,
+            ..PruneReport::default()
+        });
+    }
+    Ok(models::prune_models(unused))
+}
+
+/// Tell the user about unused models when the pruning policy is enabled
+pub(crate) fn suggest_model_pruning(app: &AppHandle) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let state = app.state::<AppState>();
+    let config = state.config.read().clone();
+    if !config.model_pruning.enabled {
+        return;
+    }
+    let loaded = state.transcription_service.loaded_model().map(|(path, _)| path);
+    let unused = models::unused_models(&config, loaded, config.model_pruning.unused_days);
+    if unused.is_empty() {
+        return;
+    }
+
+    let total_bytes: u64 = unused.iter().map(|m| m.size_bytes).sum();
+    tracing::info!(
+        "{} model(s) unused for {} days ({} MB)",
+        unused.len(),
+        config.model_pruning.unused_days,
+        total_bytes / (1024 * 1024)
+    );
+    let _ = app
+        .notification()
+        .builder()
+        .title("Unused Models")
+        .body(format!(
+            "{} model(s) unused for {} days take {:.1} GB. Open GigaWhisper to remove them.",
+            unused.len(),
+            config.model_pruning.unused_days,
+            total_bytes as f64 / 1e9
+        ))
+        .show();
+    let _ = app.emit(
+        PRUNE_SUGGESTED_EVENT,
+        PruneReport {
+            models: unused,
+            ..PruneReport::default()
+        },
+    );
+}
+
+/// Tell the user the first time the local model falls back to another
+/// quantization, downloading the requested one when enabled
+///
+/// The fallback keeps being used until the requested file is on disk.
+pub(crate) fn report_quantization_fallback(
+    app: &AppHandle,
+    resolution: &ModelResolution,
+    config: &Settings,
+) {
+    use tauri_plugin_notification::NotificationExt;
+
+    static REPORTED: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+    let Some(used) = resolution.used else {
+        return;
+    };
+    let requested_file = resolution.model.filename_with_quantization(&resolution.requested);
+    if !REPORTED.get_or_init(Default::default).lock().insert(requested_file) {
+        return;
+    }
+
+    let downloading = config.transcription.local.download_missing_quantization
+        && !models::is_downloading(&resolution.model);
+    if downloading {
+        spawn_model_download(app, resolution.model.clone(), resolution.requested);
+    }
+
+    let action = if downloading {
+        "Downloading it now."
+    } else {
+        "Download it in Settings to use it."
+    };
+    let _ = app
+        .notification()
+        .builder()
+        .title("Model Quantization Not Downloaded")
+        .body(format!(
+            "{:?} {} is not downloaded, {} is used instead. {}",
+            resolution.model,
+            resolution.requested.display_name(),
+            used.display_name(),
+            action
+        ))
+        .show();
+    let _ = app.emit(
+        QUANTIZATION_FALLBACK_EVENT,
+        QuantizationFallback {
+            model: resolution.model.clone(),
+            requested: resolution.requested,
+            used,
+            downloading,
+        },
+    );
+}
+
+/// Download a model in the background, reporting progress as events
+pub(crate) fn spawn_model_download(
+    app: &AppHandle,
+    model: WhisperModel,
+    quantization: ModelQuantization,
+) {
+    let tasks = app.state::<AppState>().tasks.clone();
+    let app = app.clone();
+    tasks.spawn("model-download", async move {
+        let name = format!("{:?}", model).to_lowercase();
+        if let Err(e) = download_with_events(&app, name, &model, &quantization).await {
+            tracing::warn!("Failed to download {:?} {:?}: {}", model, quantization, e);
+        }
+    });
+}
+
+/// Model a quality preset selects that is not downloaded
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PresetModel {
+    pub model: WhisperModel,
+    pub quantization: ModelQuantization,
+    /// Download size in bytes
+    pub size_bytes: u64,
+}
+
+/// The model `preset` would switch local transcription to, when it isn't
+/// downloaded
+///
+/// None when the preset needs no download or transcription isn't local.
+pub(crate) fn missing_preset_model(
+    config: &Settings,
+    preset: QualityPreset,
+) -> Option<PresetModel> {
+    if config.transcription.provider != TranscriptionProvider::Local {
+        return None;
+    }
+    let (model, quantization) = preset.model()?;
+    if models::is_model_downloaded_with_quantization(&model, &quantization) {
+        return None;
+    }
+    Some(PresetModel {
+        size_bytes: model.size_bytes_with_quantization(&quantization),
+        model,
+        quantization,
+    })
+}
+
+/// Download the Silero VAD model in the background when that engine is
+/// selected and the model is missing
+///
+/// Recordings use WebRTC VAD until the download completes.
+pub(crate) fn ensure_vad_model(app: &AppHandle, config: &Settings) {
+    use tauri_plugin_notification::NotificationExt;
+
+    if config.audio.vad.engine != VadEngine::Silero || models::is_silero_vad_downloaded() {
+        return;
+    }
+
+    let app = app.clone();
+    let tasks = app.state::<AppState>().tasks.clone();
+    tasks.spawn("vad-model-download", async move {
+        if let Err(e) = models::ensure_silero_vad().await {
+            tracing::warn!("Failed to download the Silero VAD model: {}", e);
+            let _ = app
+                .notification()
+                .builder()
+                .title("Silero VAD Unavailable")
+                .body(format!(
+                    "The Silero VAD model could not be downloaded ({}). WebRTC VAD is used instead.",
+                    e
+                ))
+                .show();
+        }
+    });
+}
+
 /// Get the recommended model based on system resources
 #[tauri::command]
 pub fn get_recommended_model() -> String {
@@ -109,12 +424,5 @@ pub fn get_recommended_model() -> String {
 
 /// Parse model string to WhisperModel enum
 fn parse_model(model: &str) -> Result<WhisperModel, String> {
-    match model.to_lowercase().as_str() {
-        "tiny" => Ok(WhisperModel::Tiny),
-        "base" => Ok(WhisperModel::Base),
-        "small" => Ok(WhisperModel::Small),
-        "medium" => Ok(WhisperModel::Medium),
-        "large" => Ok(WhisperModel::Large),
-        _ => Err(format!("Unknown model: {}", model)),
-    }
+    model.parse()
 }
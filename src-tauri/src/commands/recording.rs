@@ -2,16 +2,48 @@
 //!
 //! Handle audio recording start/stop operations.
 
-use crate::audio::{AudioCapture, AudioConfig};
+use crate::audio::{
+    self, AudioCapture, AudioConfig, OrphanedRecording, RecordingCheckpoint, SpeechProgress,
+    StreamingVad, VadConfig,
+};
+use crate::config::{AudioSource, HookEvent, Settings, TranscriptionProvider};
+use crate::transcription::{
+    post_process, recognition_terms, transcript_language, AdaptiveChunking, DeepgramStream,
+    LivePartials, PartialTranscript, RecordingStop, SentenceStabilizer, TranscriptionService,
+    PARTIAL_EVENT, WHISPER_SAMPLE_RATE,
+};
+use crate::utils::HookPayload;
 use crate::{AppState, RecordingState};
-use tauri::{Emitter, State};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_notification::NotificationExt;
 
+/// Event sent with the limit in seconds when a recording is stopped at `max_duration`
+pub const MAX_DURATION_EVENT: &str = "recording:max-duration-reached";
+
+/// Event sent with the `AudioLevel` of each metering window while recording
+pub const AUDIO_LEVEL_EVENT: &str = "audio:level";
+
+/// Event sent with a `DeviceEvent` when the input device is lost or reconnected
+pub const AUDIO_DEVICE_EVENT: &str = "audio:device";
+
+/// How long the Deepgram partials loop waits for audio before checking for results
+const DEEPGRAM_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
 /// Start recording audio from the microphone
+///
+/// `profile` records with a recording profile's overrides (None = global settings).
+/// `source` overrides the audio source of the settings or profile, and
+/// `translate` whether this recording is translated to English.
 #[tauri::command]
 pub async fn start_recording(
     app: tauri::AppHandle,
     state: State<'_, AppState>,
+    profile: Option<String>,
+    source: Option<AudioSource>,
+    translate: Option<bool>,
 ) -> Result<(), String> {
     tracing::info!("Starting recording");
 
@@ -29,18 +61,34 @@ pub async fn start_recording(
         }
     }
 
-    // Get max_duration from config to size the buffer appropriately
-    let max_duration = {
+    // Get max_duration (to size the buffer), input device, buffer mode and source from config
+    let (max_duration, input_device, capture_mode, source) = {
         let config = state.config.read();
-        config.recording.max_duration
+        if let Some(id) = profile.as_deref() {
+            if config.profile(id).is_none() {
+                return Err(format!("Unknown recording profile: {}", id));
+            }
+        }
+        (
+            config.recording.max_duration,
+            config.audio.input_device.clone(),
+            config.audio.capture_mode,
+            source.unwrap_or(
+                config
+                    .effective_for_profile(profile.as_deref())
+                    .audio
+                    .source,
+            ),
+        )
     };
 
     // Initialize audio capture with appropriate buffer size
     let audio_config = AudioConfig {
         buffer_duration_ms: max_duration * 1000, // Convert to ms
+        capture_mode,
         ..AudioConfig::default()
     };
-    let audio_capture = AudioCapture::new(audio_config)
+    let audio_capture = AudioCapture::with_source(source, input_device.as_deref(), audio_config)
         .map_err(|e| format!("Failed to initialize audio: {}", e))?;
 
     // Start capture
@@ -48,13 +96,38 @@ pub async fn start_recording(
         .start()
         .map_err(|e| format!("Failed to start audio capture: {}", e))?;
 
+    spawn_speech_monitor(&app, &audio_capture);
+    spawn_level_meter(&app, &audio_capture);
+    spawn_device_watcher(&app, &audio_capture);
+    *state.translate_override.write() = translate;
+    spawn_live_partials(&app, &audio_capture, profile.as_deref(), false);
+    start_recording_checkpoint(&state, &audio_capture);
+
     // Store capture handle
     *state.audio_capture.lock() = Some(audio_capture);
 
     // Update state
+    let hooks = state.config.read().hooks.clone();
+    crate::utils::fire_hooks(
+        &state.tasks,
+        &hooks,
+        HookEvent::RecordingStarted,
+        HookPayload {
+            profile: profile.clone(),
+            ..Default::default()
+        },
+    );
+    crate::api::publish_event(crate::api::ApiEvent::RecordingStarted {
+        profile: profile.clone(),
+    });
+    *state.active_profile.write() = profile;
+    *state.incognito.write() = false;
+    *state.audio_source.write() = source;
     *state.recording_state.write() = RecordingState::Recording {
         started_at: std::time::Instant::now(),
     };
+    crate::shortcuts::set_cancel_shortcut(&app, true);
+    spawn_max_duration_timer(&app);
 
     // Notify user
     let _ = app
@@ -75,8 +148,10 @@ pub async fn stop_recording(
     state: State<'_, AppState>,
 ) -> Result<String, String> {
     tracing::info!("Stopping recording");
+    crate::shortcuts::set_cancel_shortcut(&app, false);
 
     // Get audio samples with device sample rate
+    let drain_started = std::time::Instant::now();
     let (raw_samples, device_sample_rate) = {
         let mut capture_guard = state.audio_capture.lock();
         match capture_guard.as_ref() {
@@ -93,6 +168,13 @@ pub async fn stop_recording(
         }
     };
 
+    let stop = RecordingStop::since(drain_started);
+    let raw_samples = trim_trailing_silence(&state, raw_samples, device_sample_rate);
+    let incognito = std::mem::take(&mut *state.incognito.write());
+    if incognito {
+        crate::tray::set_incognito_recording(&app, false);
+    }
+
     // Check duration
     let duration = {
         let recording_state = state.recording_state.read();
@@ -111,13 +193,24 @@ pub async fn stop_recording(
     // Update state to processing
     *state.recording_state.write() = RecordingState::Processing;
     let _ = app.emit("recording:processing", ());
+    crate::api::publish_event(crate::api::ApiEvent::RecordingStopped);
 
     // Use transcription service
     let service = state.transcription_service.clone();
+    let profile = state.active_profile.write().take();
     let result = service
-        .process_recording(&app, raw_samples, device_sample_rate)
+        .process_recording(
+            &app,
+            raw_samples,
+            device_sample_rate,
+            stop,
+            profile.as_deref(),
+            incognito,
+        )
         .await;
 
+    finish_recording_checkpoint(&state);
+
     // Update state based on result
     match &result {
         Ok(_) => {
@@ -138,6 +231,7 @@ pub async fn cancel_recording(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     tracing::info!("Cancelling recording");
+    crate::shortcuts::set_cancel_shortcut(&app, false);
 
     // Stop and discard audio
     {
@@ -146,6 +240,17 @@ pub async fn cancel_recording(
             let _ = capture.stop();
         }
     }
+    finish_recording_checkpoint(&state);
+    state.speech_progress.lock().take();
+    if let Some(sentences) = state.live_sentences.lock().take() {
+        sentences.lock().finish();
+    }
+    state.live_transcript.lock().take();
+    state.active_profile.write().take();
+    *state.incognito.write() = false;
+    *state.audio_source.write() = AudioSource::default();
+    state.translate_override.write().take();
+    crate::tray::set_incognito_recording(&app, false);
 
     // Reset state
     *state.recording_state.write() = RecordingState::Idle;
@@ -161,6 +266,533 @@ pub async fn cancel_recording(
     Ok(())
 }
 
+/// Recording length after which the recording is stopped (None = unlimited)
+pub fn max_duration_limit(max_duration: u32) -> Option<std::time::Duration> {
+    (max_duration > 0).then(|| std::time::Duration::from_secs(u64::from(max_duration)))
+}
+
+/// Recording limit for notifications, e.g. "5 minutes" or "90 seconds"
+fn format_limit(secs: u32) -> String {
+    match secs {
+        60 => "1 minute".to_string(),
+        secs if secs % 60 == 0 => format!("{} minutes", secs / 60),
+        1 => "1 second".to_string(),
+        secs => format!("{} seconds", secs),
+    }
+}
+
+/// Stop and transcribe the recording in progress once `max_duration` elapses
+///
+/// The timer gives up if that recording ended in the meantime, so it never
+/// cuts a later one short.
+pub(crate) fn spawn_max_duration_timer(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let max_duration = state.config.read().recording.max_duration;
+    let Some(limit) = max_duration_limit(max_duration) else {
+        return;
+    };
+    let RecordingState::Recording { started_at } = *state.recording_state.read() else {
+        return;
+    };
+
+    let app = app.clone();
+    state.tasks.spawn("max-duration", async move {
+        tokio::time::sleep_until((started_at + limit).into()).await;
+
+        let state = app.state::<AppState>();
+        let same_recording = matches!(
+            *state.recording_state.read(),
+            RecordingState::Recording { started_at: current } if current == started_at
+        );
+        if !same_recording {
+            return;
+        }
+
+        tracing::info!("Maximum recording duration of {}s reached", max_duration);
+        let _ = app.emit(MAX_DURATION_EVENT, max_duration);
+        let _ = app
+            .notification()
+            .builder()
+            .title("Recording Stopped")
+            .body(format!(
+                "Reached the maximum of {}, transcribing now.",
+                format_limit(max_duration)
+            ))
+            .show();
+
+        if let Err(e) = crate::shortcuts::stop_recording_internal(&app).await {
+            tracing::error!("Failed to stop recording at maximum duration: {}", e);
+        }
+    });
+}
+
+/// Audio level of the recording in progress (None when not recording)
+#[tauri::command]
+pub fn get_current_level(state: State<'_, AppState>) -> Option<audio::AudioLevel> {
+    *state.audio_level.lock()
+}
+
+/// Meter live capture frames for the indicator's level meter
+///
+/// Emits `audio:level` (throttled) and keeps the latest level for
+/// `get_current_level`, until the capture is stopped.
+pub(crate) fn spawn_level_meter(app: &AppHandle, capture: &AudioCapture) {
+    let frames = capture.subscribe_frames();
+    let sample_rate = capture.device_sample_rate();
+    let tasks = app.state::<AppState>().tasks.clone();
+    let app = app.clone();
+
+    tasks.spawn_thread("level-meter", move || {
+        let state = app.state::<AppState>();
+        audio::run_level_meter(frames, sample_rate, |level| {
+            *state.audio_level.lock() = Some(level);
+            crate::utils::emit_throttled(&app, AUDIO_LEVEL_EVENT, level);
+        });
+        state.audio_level.lock().take();
+    });
+}
+
+/// Forward input device changes of the capture to the UI until it is stopped
+///
+/// Capture reconnects by itself; this only tells the user what happened.
+pub(crate) fn spawn_device_watcher(app: &AppHandle, capture: &AudioCapture) {
+    let events = capture.subscribe_device_events();
+    let tasks = app.state::<AppState>().tasks.clone();
+    let app = app.clone();
+
+    tasks.spawn_thread("device-watcher", move || {
+        while let Ok(event) = events.recv() {
+            let _ = app.emit(AUDIO_DEVICE_EVENT, &event);
+            let (title, body) = match &event {
+                audio::DeviceEvent::Disconnected { device } => (
+                    "Microphone Disconnected",
+                    format!(
+                        "Lost '{}', recording resumes once a microphone is back.",
+                        device
+                    ),
+                ),
+                audio::DeviceEvent::Reconnected { device } => (
+                    "Microphone Reconnected",
+                    format!("Recording continues on '{}'.", device),
+                ),
+            };
+            let _ = app.notification().builder().title(title).body(body).show();
+        }
+    });
+}
+
+/// Run VAD on live capture frames and emit `audio:speech-active` on changes
+///
+/// Lets the indicator show whether speech is actually being heard (e.g. a
+/// muted headset still captures, but only silence). The monitor thread ends
+/// when the capture is stopped.
+pub(crate) fn spawn_speech_monitor(app: &AppHandle, capture: &AudioCapture) {
+    let vad_config = {
+        let state = app.state::<AppState>();
+        let config = state.config.read();
+        let vad = config.audio.vad.effective_for(config.audio.input_device.as_deref());
+        VadConfig::from_settings(&vad)
+    };
+
+    let frames = capture.subscribe_frames();
+    let sample_rate = capture.device_sample_rate();
+    let progress = SpeechProgress::default();
+    *app.state::<AppState>().speech_progress.lock() = Some(progress.clone());
+    let tasks = app.state::<AppState>().tasks.clone();
+    let app = app.clone();
+
+    tasks.spawn_thread("speech-monitor", move || {
+        // WebRTC VAD is not Send, so it lives on this thread only
+        let mut vad = match StreamingVad::new(&vad_config, sample_rate) {
+            Ok(vad) => vad,
+            Err(e) => {
+                tracing::warn!("Live speech detection unavailable: {}", e);
+                return;
+            }
+        };
+
+        let _ = app.emit("audio:speech-active", false);
+
+        while let Ok(chunk) = frames.recv() {
+            if let Some(speaking) = vad.push(&chunk) {
+                tracing::debug!("Speech active: {}", speaking);
+                crate::utils::emit_throttled(&app, "audio:speech-active", speaking);
+            }
+            progress.update(&vad);
+        }
+
+        if vad.is_speaking() {
+            crate::utils::emit_throttled(&app, "audio:speech-active", false);
+        }
+    });
+}
+
+/// Transcribe the recording while it is captured and emit `transcription:partial`
+///
+/// Uses the recording's profile (or incognito) settings. Runs only when live
+/// partials or type-as-you-speak are enabled, with the local or Deepgram
+/// provider. Locally, audio captured during a partial transcription waits in
+/// the frame channel, so each pass covers everything heard so far; Deepgram
+/// receives the audio as it is captured. The thread ends when the capture is
+/// stopped.
+pub(crate) fn spawn_live_partials(
+    app: &AppHandle,
+    capture: &AudioCapture,
+    profile: Option<&str>,
+    incognito: bool,
+) {
+    let state = app.state::<AppState>();
+    let config = {
+        let config = state.config.read();
+        if incognito {
+            config.effective_for_incognito()
+        } else {
+            config.effective_for_profile(profile)
+        }
+    }
+    .with_translate(*state.translate_override.read());
+    let type_as_you_speak = config.output.type_as_you_speak
+        && config.output.sinks.contains(&crate::config::OutputSink::Paste);
+
+    // A fresh stabilizer per recording, so a late pass of the previous
+    // recording's thread can't paste into this one
+    let sentences = type_as_you_speak.then(|| Arc::new(Mutex::new(SentenceStabilizer::new())));
+    *state.live_sentences.lock() = sentences.clone();
+    state.live_transcript.lock().take();
+
+    if !config.transcription.live_partials && !type_as_you_speak {
+        return;
+    }
+    if matches!(
+        config.transcription.provider,
+        TranscriptionProvider::Groq | TranscriptionProvider::Custom
+    ) {
+        tracing::debug!("Live partials are not available with this provider");
+        return;
+    }
+
+    let frames = capture.subscribe_frames();
+    let sample_rate = capture.device_sample_rate();
+    let tasks = state.tasks.clone();
+    let service = state.transcription_service.clone();
+    let app = app.clone();
+
+    if config.transcription.provider == TranscriptionProvider::Deepgram {
+        // The session's final text becomes the transcript (Deepgram can't translate)
+        let (final_text, receiver) = tokio::sync::oneshot::channel();
+        if !config.transcription.translate {
+            *state.live_transcript.lock() = Some(receiver);
+        }
+        tasks.spawn_thread("live-partials", move || {
+            let sentences = sentences.as_ref();
+            let text = stream_deepgram_partials(&app, frames, sample_rate, &config, sentences);
+            if let Some(text) = text {
+                let _ = final_text.send(text);
+            }
+        });
+        return;
+    }
+
+    tasks.spawn_thread("live-partials", move || {
+        let transcription = &config.transcription;
+        let mut chunking = AdaptiveChunking::new(
+            transcription.partial_interval_ms,
+            transcription.partial_interval_min_ms,
+            transcription.partial_interval_max_ms,
+            transcription.adaptive_partials,
+        );
+        let mut live = LivePartials::new(sample_rate, chunking.interval_ms());
+
+        while let Ok(chunk) = frames.recv() {
+            live.push(&chunk);
+            while let Ok(chunk) = frames.try_recv() {
+                live.push(&chunk);
+            }
+            if !live.is_due() {
+                continue;
+            }
+
+            let new_audio_ms = live.pending_ms();
+            let window = live.take_window();
+            let started = std::time::Instant::now();
+            let result = tauri::async_runtime::block_on(service.transcribe_partial(
+                &window,
+                sample_rate,
+                &config,
+            ));
+            live.set_interval_ms(
+                chunking.record(started.elapsed().as_millis() as u64, new_audio_ms),
+            );
+            crate::utils::metrics().write().record_chunking(chunking.stats());
+            let partial = match result {
+                Ok(text) => live.apply(&text, window.len()),
+                Err(e) => {
+                    tracing::warn!("Live partial transcription stopped: {}", e);
+                    return;
+                }
+            };
+            deliver_partial(&app, &service, &config, sentences.as_ref(), &partial);
+        }
+    });
+}
+
+/// Emit a partial and paste the sentences that have become stable
+fn deliver_partial(
+    app: &AppHandle,
+    service: &TranscriptionService,
+    config: &Settings,
+    sentences: Option<&Arc<Mutex<SentenceStabilizer>>>,
+    partial: &PartialTranscript,
+) {
+    let _ = app.emit(PARTIAL_EVENT, partial);
+    let incognito = *app.state::<AppState>().incognito.read();
+    if !incognito {
+        super::viewer::viewer_partial(app, &partial.text);
+    }
+
+    let Some(sentences) = sentences else {
+        return;
+    };
+    // Held while pasting, so stopping waits for a paste in progress
+    let mut stabilizer = sentences.lock();
+    let mut pasted_before = stabilizer.has_pasted();
+    let language = transcript_language(&config.transcription, None);
+    for sentence in stabilizer.update(&partial.text) {
+        // Typed as the final text would be
+        let sentence = post_process(&sentence, &config.output, language);
+        let text = if pasted_before && !config.output.smart_spacing {
+            format!(" {}", sentence)
+        } else {
+            sentence
+        };
+        pasted_before = true;
+        let pasted = tauri::async_runtime::block_on(service.output_text(
+            &text,
+            app,
+            &config.output,
+            incognito,
+        ));
+        if let Err(e) = pasted {
+            tracing::warn!("Failed to paste sentence while recording: {}", e);
+        }
+        stabilizer.typed(&text);
+    }
+}
+
+/// Stream the recording to Deepgram as it is captured, emitting its results
+///
+/// Audio is sent in 100 ms chunks. Returns the session's final text once the
+/// recording stops, or None when the session failed and the recording has
+/// to be transcribed again.
+fn stream_deepgram_partials(
+    app: &AppHandle,
+    frames: std::sync::mpsc::Receiver<Vec<f32>>,
+    sample_rate: u32,
+    config: &Settings,
+    sentences: Option<&Arc<Mutex<SentenceStabilizer>>>,
+) -> Option<String> {
+    use std::sync::mpsc::RecvTimeoutError;
+
+    let deepgram = &config.transcription.deepgram;
+    let keywords = recognition_terms(config);
+    let connected = deepgram
+        .get_api_key()
+        .ok_or_else(|| "no API key is configured".to_string())
+        .and_then(|key| {
+            DeepgramStream::connect(
+                &key,
+                &deepgram.model,
+                &config.transcription.language,
+                &keywords,
+            )
+            .map_err(|e| e.to_string())
+        });
+    let mut stream = match connected {
+        Ok(stream) => stream,
+        Err(e) => {
+            tracing::warn!("Deepgram live partials unavailable: {}", e);
+            return None;
+        }
+    };
+
+    let service = app.state::<AppState>().transcription_service.clone();
+    let chunk_len = (sample_rate as usize / 10).max(1);
+    let mut pending: Vec<f32> = Vec::with_capacity(chunk_len * 2);
+    let mut sent_samples = 0u64;
+    let mut sequence = 0;
+    loop {
+        let open = match frames.recv_timeout(DEEPGRAM_POLL_INTERVAL) {
+            Ok(chunk) => {
+                pending.extend_from_slice(&chunk);
+                true
+            }
+            Err(RecvTimeoutError::Timeout) => true,
+            Err(RecvTimeoutError::Disconnected) => false,
+        };
+
+        if pending.len() >= chunk_len || (!open && !pending.is_empty()) {
+            let sent = audio::resample(&pending, sample_rate, WHISPER_SAMPLE_RATE).and_then(|s| {
+                sent_samples += s.len() as u64;
+                stream.send_audio(&s).map_err(|e| e.to_string())
+            });
+            pending.clear();
+            if let Err(e) = sent {
+                tracing::warn!("Live partial transcription stopped: {}", e);
+                return None;
+            }
+        }
+
+        match stream.poll() {
+            Ok(true) => {
+                sequence += 1;
+                let partial = PartialTranscript {
+                    text: stream.transcript().text(),
+                    audio_ms: sent_samples * 1000 / WHISPER_SAMPLE_RATE as u64,
+                    sequence,
+                };
+                deliver_partial(app, &service, config, sentences, &partial);
+            }
+            Ok(false) => {}
+            Err(e) => {
+                tracing::warn!("Live partial transcription stopped: {}", e);
+                return None;
+            }
+        }
+
+        if !open {
+            break;
+        }
+    }
+
+    let timeout = std::time::Duration::from_secs(deepgram.timeout_seconds as u64);
+    match stream.finish(timeout) {
+        Ok(text) => Some(text),
+        Err(e) => {
+            tracing::warn!("Live Deepgram session did not finish: {}", e);
+            None
+        }
+    }
+}
+
+/// Cut the silence after the last detected speech from a stopped recording
+///
+/// Uses the live speech monitor's VAD frames so inference starts on a
+/// smaller buffer; the pipeline's VAD would drop this audio anyway.
+pub(crate) fn trim_trailing_silence(
+    state: &AppState,
+    mut samples: Vec<f32>,
+    sample_rate: u32,
+) -> Vec<f32> {
+    let Some(progress) = state.speech_progress.lock().take() else {
+        return samples;
+    };
+    let (vad, tail_silence_db) = {
+        let config = state.config.read();
+        let device = config.audio.input_device.as_deref();
+        let tail_silence_db = config
+            .audio
+            .vad
+            .calibration_for(device)
+            .map_or(audio::DEFAULT_TAIL_SILENCE_DB, |c| c.energy_threshold_db);
+        (config.audio.vad.effective_for(device), tail_silence_db)
+    };
+    if !vad.enabled || !vad.trim_on_stop {
+        return samples;
+    }
+
+    let keep = progress.keep_len(&samples, sample_rate, vad.padding_ms, tail_silence_db);
+    if keep < samples.len() {
+        tracing::info!(
+            "Trimmed {}ms of trailing silence",
+            (samples.len() - keep) as u64 * 1000 / sample_rate.max(1) as u64
+        );
+        samples.truncate(keep);
+    }
+    samples
+}
+
+/// Start checkpointing the recording to disk for crash recovery
+///
+/// Skipped in no-disk-audio mode, where a crashed recording is simply lost.
+pub(crate) fn start_recording_checkpoint(state: &AppState, capture: &AudioCapture) {
+    if state.config.read().privacy.no_disk_audio {
+        return;
+    }
+    let checkpoint =
+        RecordingCheckpoint::spawn(capture.subscribe_frames(), capture.device_sample_rate());
+    *state.recording_checkpoint.lock() = Some(checkpoint);
+}
+
+/// Remove the checkpoint once the recording has been handled
+pub(crate) fn finish_recording_checkpoint(state: &AppState) {
+    let checkpoint = state.recording_checkpoint.lock().take();
+    if let Some(checkpoint) = checkpoint {
+        checkpoint.finish();
+    }
+}
+
+/// Get the recording recovered from a crashed session, if any
+#[tauri::command]
+pub fn get_orphaned_recording() -> Option<OrphanedRecording> {
+    audio::inspect_checkpoint(&audio::recovered_checkpoint_path()).ok()
+}
+
+/// Transcribe the recording recovered from a crashed session
+#[tauri::command]
+pub async fn transcribe_orphaned_recording(
+    app: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    if !matches!(
+        *state.recording_state.read(),
+        RecordingState::Idle | RecordingState::Error(_)
+    ) {
+        return Err("Cannot transcribe while recording or processing".to_string());
+    }
+
+    let (samples, sample_rate) = audio::read_checkpoint(&audio::recovered_checkpoint_path())
+        .map_err(|e| format!("Failed to read recovered recording: {}", e))?;
+
+    tracing::info!(
+        "Transcribing recovered recording: {} samples at {}Hz",
+        samples.len(),
+        sample_rate
+    );
+
+    *state.recording_state.write() = RecordingState::Processing;
+    let _ = app.emit("recording:processing", ());
+
+    let service = state.transcription_service.clone();
+    let result = service
+        .process_recording(
+            &app,
+            samples,
+            sample_rate,
+            RecordingStop::since(std::time::Instant::now()),
+            None,
+            false,
+        )
+        .await;
+
+    match &result {
+        Ok(_) => {
+            *state.recording_state.write() = RecordingState::Idle;
+            audio::discard_recovered_checkpoint();
+        }
+        Err(e) => {
+            *state.recording_state.write() = RecordingState::Error(e.clone());
+        }
+    }
+
+    result
+}
+
+/// Delete the recording recovered from a crashed session
+#[tauri::command]
+pub fn discard_orphaned_recording() {
+    audio::discard_recovered_checkpoint();
+}
+
 /// Get current recording state for UI
 #[tauri::command]
 pub fn get_recording_state(state: State<'_, AppState>) -> RecordingStateDto {
@@ -190,10 +822,38 @@ pub fn get_recording_state(state: State<'_, AppState>) -> RecordingStateDto {
     }
 }
 
+/// Get the indicator state, queried by the indicator window when it loads
+#[tauri::command]
+pub fn get_indicator_state() -> crate::indicator::IndicatorSnapshot {
+    crate::indicator::snapshot()
+}
+
 /// DTO for recording state
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, JsonSchema)]
 pub struct RecordingStateDto {
     pub state: String,
     pub duration_ms: Option<u64>,
     pub error: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_duration_limit() {
+        assert_eq!(max_duration_limit(0), None);
+        assert_eq!(
+            max_duration_limit(300),
+            Some(std::time::Duration::from_secs(300))
+        );
+    }
+
+    #[test]
+    fn test_format_limit() {
+        assert_eq!(format_limit(300), "5 minutes");
+        assert_eq!(format_limit(60), "1 minute");
+        assert_eq!(format_limit(90), "90 seconds");
+        assert_eq!(format_limit(1), "1 second");
+    }
+}
@@ -2,10 +2,27 @@
 //!
 //! Handle configuration read/write operations.
 
-use crate::config::{SecretsManager, Settings};
+use crate::audio::{CalibrationReport, DeviceTestReport};
+use crate::commands::models::{missing_preset_model, spawn_model_download, PresetModel};
+use crate::config::{
+    QualityPreset, SecretsManager, Settings, VadCalibration, VadSettings, VoiceCommand,
+    VoiceProfile,
+};
 use crate::shortcuts;
-use crate::AppState;
-use tauri::{AppHandle, State};
+use crate::{AppState, RecordingState};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Ambient noise capture length for VAD calibration
+const CALIBRATION_SILENCE_SECS: u64 = 10;
+
+/// Sample sentence capture length for VAD calibration
+const CALIBRATION_SPEECH_SECS: u64 = 6;
+
+/// Speech capture length for voice profile enrollment
+const ENROLLMENT_SECS: u64 = 10;
+
+/// Capture length for an input device test
+const DEVICE_TEST_SECS: u64 = 2;
 
 /// Get current settings
 #[tauri::command]
@@ -18,19 +35,54 @@ pub fn get_settings(state: State<'_, AppState>) -> Settings {
 pub async fn save_settings(
     app: AppHandle,
     state: State<'_, AppState>,
-    settings: Settings,
+    mut settings: Settings,
 ) -> Result<(), String> {
     tracing::info!("Saving settings");
 
+    // Workspaces change through their own commands; a window opened before
+    // one was created or switched must not revert it
+    settings.workspaces = state.config.read().workspaces.clone();
+
+    // Refuse shortcuts that would fail to register later
+    let conflicts = check_shortcuts(app.clone(), settings.clone());
+    if !conflicts.is_empty() {
+        let messages: Vec<String> = conflicts.iter().map(ToString::to_string).collect();
+        return Err(messages.join("; "));
+    }
+
     // Validate settings
     settings.validate().map_err(|e| e.to_string())?;
 
+    // Apply a newly picked quality preset, or drop one edited by hand
+    let old_preset = state.config.read().transcription.quality_preset;
+    settings.sync_quality_preset(old_preset);
+    let preset_changed = old_preset != settings.transcription.quality_preset;
+    if preset_changed {
+        let preset = settings.transcription.quality_preset;
+        if let Some(missing) = missing_preset_model(&settings, preset) {
+            return Err(not_downloaded_message(preset, &missing));
+        }
+    }
+
     // Check if shortcuts have changed
-    let old_shortcut = {
+    let (old_bindings, old_cancel) = {
         let config = state.config.read();
-        config.shortcuts.record.clone()
+        (
+            shortcuts::shortcut_bindings(&config),
+            config.shortcuts.cancel.clone(),
+        )
     };
-    let shortcuts_changed = old_shortcut != settings.shortcuts.record;
+    let shortcuts_changed = old_bindings != shortcuts::shortcut_bindings(&settings)
+        || old_cancel != settings.shortcuts.cancel;
+    let device_changed = state.config.read().audio.input_device != settings.audio.input_device;
+    let model_storage_changed = state.config.read().model_storage != settings.model_storage;
+    let encryption_changed =
+        state.config.read().privacy.encrypt_history != settings.privacy.encrypt_history;
+
+    // Refuse to turn on history encryption without a usable key
+    if encryption_changed && settings.privacy.encrypt_history {
+        crate::history::history_key().map_err(|e| format!("Cannot encrypt history: {}", e))?;
+    }
 
     // Update in-memory state
     {
@@ -41,6 +93,29 @@ pub async fn save_settings(
     // Persist to disk
     settings.save().map_err(|e| e.to_string())?;
 
+    if model_storage_changed {
+        crate::config::set_shared_models_dir(settings.model_storage.shared_dir.as_deref());
+    }
+
+    // Rewrite the history file encrypted or in the clear
+    if encryption_changed {
+        crate::history::set_history_encryption(settings.privacy.encrypt_history);
+        if let Err(e) = crate::history::get_history().read().save() {
+            tracing::error!("Failed to save history: {}", e);
+        }
+    }
+
+    // Start, stop or move the local API server
+    crate::api::apply_api_server_settings(&app, &settings.api_server);
+    super::models::ensure_vad_model(&app, &settings);
+    shortcuts::apply_trigger_settings(&app, &settings.shortcuts.triggers);
+    crate::transcription::apply_prefetch_settings(&app, settings.transcription.prefetch);
+
+    // Keep the tray device and preset switchers in sync
+    if device_changed || preset_changed {
+        crate::tray::refresh_tray_menu(&app);
+    }
+
     // Re-register shortcuts if they changed
     if shortcuts_changed {
         tracing::info!("Shortcut changed, re-registering...");
@@ -55,6 +130,86 @@ pub async fn save_settings(
     Ok(())
 }
 
+/// Shortcuts of `settings` that can't be registered
+///
+/// Reports invalid and duplicate shortcuts first; only once there are none
+/// is each new shortcut tried against the OS and other applications.
+#[tauri::command]
+pub fn check_shortcuts(app: AppHandle, settings: Settings) -> Vec<shortcuts::ShortcutConflict> {
+    let conflicts = shortcuts::find_shortcut_conflicts(&settings);
+    if !conflicts.is_empty() {
+        return conflicts;
+    }
+    shortcuts::unavailable_shortcuts(&app, &settings)
+}
+
+/// Switch to a quality preset, returning the updated settings
+///
+/// A preset whose model is not downloaded is refused unless `download` is
+/// set, which starts the download.
+#[tauri::command]
+pub fn set_quality_preset(
+    app: AppHandle,
+    preset: QualityPreset,
+    download: Option<bool>,
+) -> Result<Settings, String> {
+    apply_quality_preset(&app, preset, download.unwrap_or(false))
+}
+
+/// Model a quality preset would switch to that still has to be downloaded
+#[tauri::command]
+pub fn preset_missing_model(
+    state: State<'_, AppState>,
+    preset: QualityPreset,
+) -> Option<PresetModel> {
+    missing_preset_model(&state.config.read(), preset)
+}
+
+/// Set a quality preset's values and save them
+///
+/// `Custom` keeps the current values and only stops following a preset.
+/// A preset whose model is missing is refused, or with `download` applied
+/// while the model downloads.
+pub(crate) fn apply_quality_preset(
+    app: &AppHandle,
+    preset: QualityPreset,
+    download: bool,
+) -> Result<Settings, String> {
+    let state = app.state::<AppState>();
+    let (settings, missing) = {
+        let mut config = state.config.write();
+        if config.transcription.quality_preset == preset {
+            return Ok(config.clone());
+        }
+        let missing = missing_preset_model(&config, preset);
+        if let Some(missing) = missing.as_ref().filter(|_| !download) {
+            return Err(not_downloaded_message(preset, missing));
+        }
+        preset.apply(&mut config);
+        config.transcription.quality_preset = preset;
+        (config.clone(), missing)
+    };
+    settings.save().map_err(|e| e.to_string())?;
+    if let Some(missing) = missing {
+        spawn_model_download(app, missing.model, missing.quantization);
+    }
+
+    tracing::info!("Quality preset set to {}", preset.display_name());
+    let _ = app.emit("settings:preset-changed", preset);
+    crate::tray::refresh_tray_menu(app);
+    Ok(settings)
+}
+
+/// Why a preset can't be used before its model is downloaded
+fn not_downloaded_message(preset: QualityPreset, missing: &PresetModel) -> String {
+    format!(
+        "The {} preset uses the {:?} {} model, which is not downloaded",
+        preset.display_name(),
+        missing.model,
+        missing.quantization.display_name()
+    )
+}
+
 /// Get available audio input devices
 #[tauri::command]
 pub fn get_audio_devices() -> Result<Vec<AudioDeviceDto>, String> {
@@ -81,8 +236,257 @@ pub fn get_audio_devices() -> Result<Vec<AudioDeviceDto>, String> {
     }
 }
 
+/// Run the VAD calibration wizard on an input device
+///
+/// Records ambient silence, then a sample sentence, emitting
+/// `calibration:phase` ("silence", "speech", "analyzing") so the UI can
+/// prompt the user. With `apply`, the measured values are stored for the
+/// device and used by later recordings on it.
+#[tauri::command]
+pub async fn calibrate_vad(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+    apply: bool,
+) -> Result<CalibrationReport, String> {
+    use crate::audio::{analyze_calibration, AudioCapture, AudioConfig};
+
+    if !matches!(
+        *state.recording_state.read(),
+        RecordingState::Idle | RecordingState::Error(_)
+    ) {
+        return Err("Cannot calibrate while recording".to_string());
+    }
+
+    let capture = AudioCapture::with_device_id(device_id.as_deref(), AudioConfig::default())
+        .map_err(|e| format!("Failed to initialize audio: {}", e))?;
+
+    let mut phases = Vec::new();
+    for (phase, seconds) in [
+        ("silence", CALIBRATION_SILENCE_SECS),
+        ("speech", CALIBRATION_SPEECH_SECS),
+    ] {
+        let _ = app.emit("calibration:phase", phase);
+        capture
+            .start()
+            .map_err(|e| format!("Failed to start audio capture: {}", e))?;
+        tokio::time::sleep(std::time::Duration::from_secs(seconds)).await;
+        let (samples, sample_rate) = capture
+            .stop()
+            .map_err(|e| format!("Failed to stop audio: {}", e))?;
+        phases.push((samples, sample_rate));
+    }
+
+    let _ = app.emit("calibration:phase", "analyzing");
+
+    let sample_rate = phases[0].1;
+    let report = analyze_calibration(&phases[0].0, &phases[1].0, sample_rate)
+        .map_err(|e| e.to_string())?;
+
+    tracing::info!(
+        "VAD calibration: noise {:.1} dB, speech {:.1} dB, SNR {:.1} dB -> aggressiveness {}",
+        report.noise_floor_db,
+        report.speech_level_db,
+        report.snr_db,
+        report.aggressiveness
+    );
+
+    if apply {
+        let config = {
+            let mut config = state.config.write();
+            config.audio.vad.calibrations.insert(
+                VadSettings::device_key(device_id.as_deref()),
+                VadCalibration {
+                    aggressiveness: report.aggressiveness,
+                    min_speech_duration_ms: report.min_speech_duration_ms,
+                    energy_threshold_db: report.energy_threshold_db,
+                    noise_floor_db: report.noise_floor_db,
+                },
+            );
+            config.clone()
+        };
+        config.save().map_err(|e| e.to_string())?;
+    }
+
+    Ok(report)
+}
+
+/// Record a few seconds from an input device and report its levels
+///
+/// Lets the user check a microphone before selecting it. With `playback`,
+/// the recording is played back on the default output device.
+#[tauri::command]
+pub async fn test_audio_device(
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+    playback: bool,
+) -> Result<DeviceTestReport, String> {
+    use crate::audio::{analyze_device_test, play_samples, AudioCapture, AudioConfig};
+
+    if !matches!(
+        *state.recording_state.read(),
+        RecordingState::Idle | RecordingState::Error(_)
+    ) {
+        return Err("Cannot test a device while recording".to_string());
+    }
+
+    let capture = AudioCapture::with_device_id(device_id.as_deref(), AudioConfig::default())
+        .map_err(|e| format!("Failed to initialize audio: {}", e))?;
+    capture
+        .start()
+        .map_err(|e| format!("Failed to start audio capture: {}", e))?;
+    tokio::time::sleep(std::time::Duration::from_secs(DEVICE_TEST_SECS)).await;
+    if let Some(error) = capture.get_error() {
+        let _ = capture.stop();
+        return Err(format!("Audio device error: {}", error.message));
+    }
+    let (samples, sample_rate) = capture
+        .stop()
+        .map_err(|e| format!("Failed to stop audio: {}", e))?;
+
+    let mut report = analyze_device_test(&samples, sample_rate);
+    tracing::info!(
+        "Device test: peak {:.1} dB, RMS {:.1} dB",
+        report.peak_db,
+        report.rms_db
+    );
+
+    if playback {
+        tokio::task::spawn_blocking(move || play_samples(&samples, sample_rate))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| format!("Failed to play back the recording: {}", e))?;
+        report.played_back = true;
+    }
+
+    Ok(report)
+}
+
+/// Record the user's voice and store it as the speaker filter profile
+///
+/// Emits `enrollment:phase` ("downloading", "recording", "analyzing") so the
+/// UI can prompt the user to read a few sentences. The embedding goes to the
+/// credential store. Enables the speaker filter on success.
+#[tauri::command]
+pub async fn enroll_voice_profile(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+) -> Result<VoiceProfile, String> {
+    use crate::audio::{resample, AudioCapture, AudioConfig, SpeakerModel, SPEAKER_SAMPLE_RATE};
+
+    if !matches!(
+        *state.recording_state.read(),
+        RecordingState::Idle | RecordingState::Error(_)
+    ) {
+        return Err("Cannot record a voice profile while recording".to_string());
+    }
+
+    if !crate::models::speaker_model_path().exists() {
+        let _ = app.emit("enrollment:phase", "downloading");
+    }
+    let model_path = crate::models::ensure_speaker_model()
+        .await
+        .map_err(|e| format!("Failed to download the speaker model: {}", e))?;
+
+    let capture = AudioCapture::with_device_id(device_id.as_deref(), AudioConfig::default())
+        .map_err(|e| format!("Failed to initialize audio: {}", e))?;
+
+    let _ = app.emit("enrollment:phase", "recording");
+    capture
+        .start()
+        .map_err(|e| format!("Failed to start audio capture: {}", e))?;
+    tokio::time::sleep(std::time::Duration::from_secs(ENROLLMENT_SECS)).await;
+    let (samples, sample_rate) = capture
+        .stop()
+        .map_err(|e| format!("Failed to stop audio: {}", e))?;
+
+    let _ = app.emit("enrollment:phase", "analyzing");
+
+    let samples = resample(&samples, sample_rate, SPEAKER_SAMPLE_RATE)?;
+    let embedding = tauri::async_runtime::spawn_blocking(move || {
+        SpeakerModel::load_cached(&model_path)?.embed(&samples)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())?
+    .ok_or_else(|| "Not enough speech recorded, please try again".to_string())?;
+    SecretsManager::set_voice_profile(&embedding.0).map_err(|e| e.to_string())?;
+
+    let profile = VoiceProfile {
+        enrolled_at: crate::history::chrono_timestamp(),
+    };
+    let config = {
+        let mut config = state.config.write();
+        config.audio.speaker_filter.profile = Some(profile.clone());
+        config.audio.speaker_filter.enabled = true;
+        config.clone()
+    };
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Voice profile enrolled");
+    Ok(profile)
+}
+
+/// Delete the voice profile and turn the speaker filter off
+#[tauri::command]
+pub fn clear_voice_profile(state: State<'_, AppState>) -> Result<(), String> {
+    SecretsManager::delete_voice_profile().map_err(|e| e.to_string())?;
+    let config = {
+        let mut config = state.config.write();
+        config.audio.speaker_filter.profile = None;
+        config.audio.speaker_filter.enabled = false;
+        config.clone()
+    };
+    config.save().map_err(|e| e.to_string())
+}
+
+/// Built-in spoken commands of a language, for editing custom ones
+#[tauri::command]
+pub fn get_builtin_voice_commands(language: String) -> Vec<VoiceCommand> {
+    crate::output::builtin_voice_commands(&language)
+}
+
+/// Get the alert keywords
+#[tauri::command]
+pub fn get_alert_keywords(state: State<'_, AppState>) -> Vec<String> {
+    state.config.read().alerts.keywords.clone()
+}
+
+/// Add an alert keyword, returning the updated list
+#[tauri::command]
+pub fn add_alert_keyword(
+    state: State<'_, AppState>,
+    keyword: String,
+) -> Result<Vec<String>, String> {
+    let config = {
+        let mut config = state.config.write();
+        config.alerts.add_keyword(&keyword).map_err(|e| e.to_string())?;
+        config.clone()
+    };
+    config.save().map_err(|e| e.to_string())?;
+    Ok(config.alerts.keywords)
+}
+
+/// Remove an alert keyword, returning the updated list
+#[tauri::command]
+pub fn remove_alert_keyword(
+    state: State<'_, AppState>,
+    keyword: String,
+) -> Result<Vec<String>, String> {
+    let config = {
+        let mut config = state.config.write();
+        if !config.alerts.remove_keyword(&keyword) {
+            return Err(format!("Unknown alert keyword: {}", keyword));
+        }
+        config.clone()
+    };
+    config.save().map_err(|e| e.to_string())?;
+    Ok(config.alerts.keywords)
+}
+
 /// DTO for audio device
-#[derive(serde::Serialize)]
+#[derive(serde::Serialize, schemars::JsonSchema)]
 pub struct AudioDeviceDto {
     pub id: String,
     pub name: String,
@@ -144,6 +548,219 @@ pub fn validate_groq_api_key(api_key: String) -> Result<(), String> {
     SecretsManager::validate_groq_api_key(&api_key).map_err(|e| e.to_string())
 }
 
+/// Set Deepgram API key (stored securely in the credential manager)
+#[tauri::command]
+pub async fn set_deepgram_api_key(
+    state: State<'_, AppState>,
+    api_key: String,
+) -> Result<(), String> {
+    SecretsManager::set_deepgram_api_key(&api_key).map_err(|e| e.to_string())?;
+
+    {
+        let mut config = state.config.write();
+        config.transcription.deepgram.api_key_configured = true;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Deepgram API key saved securely");
+    Ok(())
+}
+
+/// Check if Deepgram API key is configured
+#[tauri::command]
+pub fn has_deepgram_api_key() -> bool {
+    SecretsManager::has_deepgram_api_key()
+}
+
+/// Remove Deepgram API key
+#[tauri::command]
+pub async fn clear_deepgram_api_key(state: State<'_, AppState>) -> Result<(), String> {
+    let _ = SecretsManager::delete_deepgram_api_key();
+
+    {
+        let mut config = state.config.write();
+        config.transcription.deepgram.api_key_configured = false;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Deepgram API key removed");
+    Ok(())
+}
+
+/// Validate Deepgram API key format (without storing)
+#[tauri::command]
+pub fn validate_deepgram_api_key(api_key: String) -> Result<(), String> {
+    SecretsManager::validate_deepgram_api_key(&api_key).map_err(|e| e.to_string())
+}
+
+/// Set the custom endpoint bearer token (stored securely in the credential manager)
+#[tauri::command]
+pub async fn set_custom_endpoint_token(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<(), String> {
+    SecretsManager::set_custom_endpoint_token(&token).map_err(|e| e.to_string())?;
+
+    {
+        let mut config = state.config.write();
+        config.transcription.custom.token_configured = true;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Custom endpoint token saved securely");
+    Ok(())
+}
+
+/// Check if a custom endpoint bearer token is configured
+#[tauri::command]
+pub fn has_custom_endpoint_token() -> bool {
+    SecretsManager::has_custom_endpoint_token()
+}
+
+/// Remove the custom endpoint bearer token
+#[tauri::command]
+pub async fn clear_custom_endpoint_token(state: State<'_, AppState>) -> Result<(), String> {
+    let _ = SecretsManager::delete_custom_endpoint_token();
+
+    {
+        let mut config = state.config.write();
+        config.transcription.custom.token_configured = false;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Custom endpoint token removed");
+    Ok(())
+}
+
+/// Set the OpenAI API key used by transcript cleanup (stored securely in the credential manager)
+#[tauri::command]
+pub async fn set_openai_api_key(state: State<'_, AppState>, api_key: String) -> Result<(), String> {
+    SecretsManager::set_openai_api_key(&api_key).map_err(|e| e.to_string())?;
+
+    {
+        let mut config = state.config.write();
+        config.output.cleanup.openai_key_configured = true;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("OpenAI API key saved securely");
+    Ok(())
+}
+
+/// Check if an OpenAI API key is configured
+#[tauri::command]
+pub fn has_openai_api_key() -> bool {
+    SecretsManager::has_openai_api_key()
+}
+
+/// Remove the OpenAI API key
+#[tauri::command]
+pub async fn clear_openai_api_key(state: State<'_, AppState>) -> Result<(), String> {
+    let _ = SecretsManager::delete_openai_api_key();
+
+    {
+        let mut config = state.config.write();
+        config.output.cleanup.openai_key_configured = false;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("OpenAI API key removed");
+    Ok(())
+}
+
+/// Set the token sent to a custom cleanup server (stored securely in the credential manager)
+#[tauri::command]
+pub async fn set_cleanup_endpoint_token(
+    state: State<'_, AppState>,
+    token: String,
+) -> Result<(), String> {
+    SecretsManager::set_cleanup_endpoint_token(&token).map_err(|e| e.to_string())?;
+
+    {
+        let mut config = state.config.write();
+        config.output.cleanup.endpoint_token_configured = true;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Cleanup endpoint token saved securely");
+    Ok(())
+}
+
+/// Check if a custom cleanup server token is configured
+#[tauri::command]
+pub fn has_cleanup_endpoint_token() -> bool {
+    SecretsManager::has_cleanup_endpoint_token()
+}
+
+/// Remove the custom cleanup server token
+#[tauri::command]
+pub async fn clear_cleanup_endpoint_token(state: State<'_, AppState>) -> Result<(), String> {
+    let _ = SecretsManager::delete_cleanup_endpoint_token();
+
+    {
+        let mut config = state.config.write();
+        config.output.cleanup.endpoint_token_configured = false;
+    }
+
+    let config = state.config.read().clone();
+    config.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Cleanup endpoint token removed");
+    Ok(())
+}
+
+/// Set the header value sent to the webhook sink at `url` (stored securely)
+#[tauri::command]
+pub fn set_webhook_auth(url: String, value: String) -> Result<(), String> {
+    SecretsManager::set_webhook_auth(&url, &value).map_err(|e| e.to_string())
+}
+
+/// Check if the webhook sink at `url` has an authorization value
+#[tauri::command]
+pub fn has_webhook_auth(url: String) -> bool {
+    SecretsManager::has_webhook_auth(&url)
+}
+
+/// Remove the authorization value of the webhook sink at `url`
+#[tauri::command]
+pub fn clear_webhook_auth(url: String) -> Result<(), String> {
+    let _ = SecretsManager::delete_webhook_auth(&url);
+    Ok(())
+}
+
+/// Set the MQTT broker password (stored securely)
+#[tauri::command]
+pub fn set_mqtt_password(password: String) -> Result<(), String> {
+    SecretsManager::set_mqtt_password(&password).map_err(|e| e.to_string())
+}
+
+/// Check if an MQTT broker password is configured
+#[tauri::command]
+pub fn has_mqtt_password() -> bool {
+    SecretsManager::has_mqtt_password()
+}
+
+/// Remove the MQTT broker password
+#[tauri::command]
+pub fn clear_mqtt_password() -> Result<(), String> {
+    let _ = SecretsManager::delete_mqtt_password();
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,32 @@
+//! System Commands
+//!
+//! Environment diagnostics.
+
+use crate::utils::{self, BackgroundTask, ErrorCode, ErrorGuidance, HealthReport, ResourceUsage};
+use crate::AppState;
+use tauri::State;
+
+/// Get the environment health report (platform capabilities and setup hints)
+#[tauri::command]
+pub fn get_health_report() -> HealthReport {
+    utils::health_report()
+}
+
+/// List background tasks that are still running
+#[tauri::command]
+pub fn get_background_tasks(state: State<'_, AppState>) -> Vec<BackgroundTask> {
+    state.tasks.list()
+}
+
+/// Get memory use of models and caches against the configured budget
+#[tauri::command]
+pub fn get_resource_usage(state: State<'_, AppState>) -> ResourceUsage {
+    let settings = state.config.read().clone();
+    utils::resource_usage(&settings, &state.transcription_service)
+}
+
+/// Get the explanation and fix for a known error
+#[tauri::command]
+pub fn get_error_guidance(code: ErrorCode) -> ErrorGuidance {
+    utils::error_guidance(code)
+}
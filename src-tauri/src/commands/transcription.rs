@@ -2,14 +2,21 @@
 //!
 //! Handle transcription status and results.
 
-use crate::transcription::{TranscriptionStatus, WhisperProvider};
-use crate::utils::{metrics, CpuInfo, MetricsSummary, TranscriptionRecord};
+use crate::config::PipelineStage;
+use crate::transcription::{
+    self, ActivitySignal, BatchRun, CaptureInfo, FileJob, PipelineTrace, ProviderCapabilities,
+    ReplayReport, TranscriptionStatus, WhisperProvider,
+};
+use crate::utils::{
+    latency_tracker, metrics, CpuInfo, LatencyReport, MetricsSummary, TranscriptionRecord,
+};
 use crate::AppState;
+use schemars::JsonSchema;
 use serde::Serialize;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 /// GPU acceleration information
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct GpuInfo {
     /// Whether GPU acceleration is available in this build
     pub available: bool,
@@ -30,6 +37,97 @@ pub fn get_transcription_status(state: State<'_, AppState>) -> TranscriptionStat
     state.transcription_service.get_status()
 }
 
+/// Queue audio files for transcription into the history
+///
+/// Unsupported files are skipped; returns the jobs added.
+#[tauri::command]
+pub fn enqueue_audio_files(app: AppHandle, paths: Vec<String>) -> Result<Vec<FileJob>, String> {
+    let paths: Vec<std::path::PathBuf> = paths.into_iter().map(Into::into).collect();
+    if let Some(missing) = paths.iter().find(|p| !p.is_file()) {
+        return Err(format!("File not found: {}", missing.display()));
+    }
+    Ok(transcription::enqueue_audio_files(&app, &paths))
+}
+
+/// Transcribe an audio file into the history and return the text
+///
+/// WAV files are read directly and MP3, FLAC and Ogg Vorbis are decoded in
+/// process; the other import formats are decoded with ffmpeg when installed.
+#[tauri::command]
+pub async fn transcribe_file(app: AppHandle, path: String) -> Result<String, String> {
+    let path = std::path::PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    if !crate::audio::is_importable_audio(&path) {
+        return Err(format!("Unsupported audio format: {}", path.display()));
+    }
+    tracing::info!("Transcribing file: {}", path.display());
+    transcription::transcribe_audio_file(&app, &path).await
+}
+
+/// Transcribe every audio file in a folder into the history
+///
+/// `recursive` includes subfolders. Progress is reported with
+/// `batch:progress` events and the summary with `batch:completed`.
+#[tauri::command]
+pub fn start_batch(
+    app: AppHandle,
+    folder: String,
+    recursive: Option<bool>,
+) -> Result<BatchRun, String> {
+    transcription::start_batch(&app, std::path::Path::new(&folder), recursive.unwrap_or(false))
+}
+
+/// Pause the batch after the file in progress
+#[tauri::command]
+pub fn pause_batch() -> Result<(), String> {
+    with_batch(BatchRun::pause)
+}
+
+/// Resume a paused batch
+#[tauri::command]
+pub fn resume_batch() -> Result<(), String> {
+    with_batch(BatchRun::resume)
+}
+
+/// Cancel the batch after the file in progress
+#[tauri::command]
+pub fn cancel_batch() -> Result<(), String> {
+    with_batch(BatchRun::cancel)
+}
+
+/// Get the current or last batch
+#[tauri::command]
+pub fn get_batch() -> Option<BatchRun> {
+    transcription::batch().lock().clone()
+}
+
+fn with_batch(f: impl FnOnce(&mut BatchRun) -> Result<(), String>) -> Result<(), String> {
+    match transcription::batch().lock().as_mut() {
+        Some(run) => f(run),
+        None => Err("No batch is running".to_string()),
+    }
+}
+
+/// Get what each transcription provider supports with the current settings
+#[tauri::command]
+pub fn get_provider_capabilities(state: State<'_, AppState>) -> Vec<ProviderCapabilities> {
+    transcription::provider_capabilities(&state.config.read())
+}
+
+/// Get queued, running and recently finished file transcriptions
+#[tauri::command]
+pub fn get_file_queue() -> Vec<FileJob> {
+    transcription::file_queue().lock().jobs()
+}
+
+/// Remove finished jobs from the file queue
+#[tauri::command]
+pub fn clear_file_queue() {
+    transcription::file_queue().lock().clear_finished();
+}
+
 /// Preload the transcription model (for faster first transcription)
 #[tauri::command]
 pub async fn preload_model(state: State<'_, AppState>) -> Result<(), String> {
@@ -37,6 +135,12 @@ pub async fn preload_model(state: State<'_, AppState>) -> Result<(), String> {
     state.transcription_service.preload_model(&config)
 }
 
+/// Report a pre-dictation activity signal so the provider can warm up
+#[tauri::command]
+pub fn signal_activity(app: AppHandle, signal: ActivitySignal) {
+    transcription::on_activity_signal(&app, signal);
+}
+
 /// Unload the transcription model to free memory
 #[tauri::command]
 pub fn unload_model(state: State<'_, AppState>) {
@@ -76,4 +180,68 @@ pub fn get_recent_metrics(count: Option<usize>) -> Vec<TranscriptionRecord> {
 #[tauri::command]
 pub fn reset_metrics() {
     metrics().write().reset();
+    latency_tracker().lock().reset();
+}
+
+/// Get stop-to-paste latency of recent dictations against the target
+#[tauri::command]
+pub fn get_latency_report(state: State<'_, AppState>) -> LatencyReport {
+    let settings = state.config.read().clone();
+    latency_tracker().lock().report(&settings)
+}
+
+/// Pipeline stage as shown in the pipeline editor
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PipelineStageInfo {
+    pub stage: PipelineStage,
+    pub enabled: bool,
+    /// Required stages can be moved but not disabled
+    pub required: bool,
+}
+
+/// Get the configured processing pipeline, in order
+#[tauri::command]
+pub fn get_pipeline_config(state: State<'_, AppState>) -> Vec<PipelineStageInfo> {
+    state
+        .config
+        .read()
+        .audio
+        .pipeline
+        .iter()
+        .map(|s| PipelineStageInfo {
+            stage: s.stage,
+            enabled: s.enabled,
+            required: s.stage.is_required(),
+        })
+        .collect()
+}
+
+/// Get per-stage timings of the most recent recordings, newest first
+#[tauri::command]
+pub fn get_pipeline_traces(count: Option<usize>) -> Vec<PipelineTrace> {
+    transcription::pipeline_traces()
+        .lock()
+        .iter()
+        .rev()
+        .take(count.unwrap_or(10))
+        .cloned()
+        .collect()
+}
+
+/// List saved debug captures, newest first
+#[tauri::command]
+pub fn list_debug_captures() -> Vec<CaptureInfo> {
+    transcription::list_captures(&transcription::captures_dir())
+}
+
+/// Re-run a debug capture and report any divergence from the original result
+#[tauri::command]
+pub async fn replay_recording(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<ReplayReport, String> {
+    state
+        .transcription_service
+        .replay_capture(std::path::Path::new(&path))
+        .await
 }
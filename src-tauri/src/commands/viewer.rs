@@ -0,0 +1,69 @@
+//! Transcript Viewer Commands
+//!
+//! Open the viewer window and control what it shows.
+
+use crate::output::{TranscriptViewer, ViewerUpdate, VIEWER_EVENT, VIEWER_LABEL};
+use crate::AppState;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// Show the viewer window
+pub(crate) fn show_viewer(app: &AppHandle) -> Result<(), String> {
+    let window = app
+        .get_webview_window(VIEWER_LABEL)
+        .ok_or_else(|| "Transcript viewer window not found".to_string())?;
+    window.show().map_err(|e| e.to_string())?;
+    let _ = window.set_focus();
+    tracing::info!("Transcript viewer opened");
+    Ok(())
+}
+
+/// Apply a change to the viewer and send it to the window
+fn update_viewer(app: &AppHandle, f: impl FnOnce(&mut TranscriptViewer) -> Option<ViewerUpdate>) {
+    let update = f(&mut app.state::<AppState>().transcript_viewer.lock());
+    if let Some(update) = update {
+        let _ = app.emit(VIEWER_EVENT, update);
+    }
+}
+
+/// Show the partial transcript of the recording in progress
+pub(crate) fn viewer_partial(app: &AppHandle, text: &str) {
+    update_viewer(app, |viewer| viewer.partial(text));
+}
+
+/// Show a finished transcription
+pub(crate) fn viewer_line(app: &AppHandle, text: &str) {
+    let timestamp = crate::history::chrono_timestamp();
+    update_viewer(app, |viewer| viewer.line(text, timestamp));
+}
+
+/// Open the transcript viewer
+#[tauri::command]
+pub fn open_viewer(app: AppHandle) -> Result<(), String> {
+    show_viewer(&app)
+}
+
+/// Hide the transcript viewer (its text is kept)
+#[tauri::command]
+pub fn close_viewer(app: AppHandle) {
+    if let Some(window) = app.get_webview_window(VIEWER_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+/// Get the viewer state
+#[tauri::command]
+pub fn get_viewer(state: State<'_, AppState>) -> TranscriptViewer {
+    state.transcript_viewer.lock().clone()
+}
+
+/// Freeze or resume the view
+#[tauri::command]
+pub fn set_viewer_paused(app: AppHandle, paused: bool) {
+    update_viewer(&app, |viewer| Some(viewer.set_paused(paused)));
+}
+
+/// Remove all text from the view
+#[tauri::command]
+pub fn clear_viewer(app: AppHandle) {
+    update_viewer(&app, |viewer| Some(viewer.clear()));
+}
@@ -0,0 +1,192 @@
+//! Vocabulary Commands
+//!
+//! Manage custom vocabulary, replacement rules and shareable rules packs.
+
+use crate::vocabulary::{
+    self, ImportMode, ImportSummary, LearnedTerm, Vocabulary, MAX_PACK_SIZE,
+};
+use std::path::PathBuf;
+
+/// Get the current vocabulary and replacement rules
+#[tauri::command]
+pub fn get_vocabulary() -> Vocabulary {
+    vocabulary::get_vocabulary().read().clone()
+}
+
+/// Replace the vocabulary and replacement rules
+#[tauri::command]
+pub fn save_vocabulary(vocabulary: Vocabulary) -> Result<(), String> {
+    let mut current = vocabulary::get_vocabulary().write();
+    *current = vocabulary;
+    current.save().map_err(|e| e.to_string())
+}
+
+/// Export the vocabulary as a shareable rules pack (JSON)
+#[tauri::command]
+pub fn export_vocabulary(
+    path: String,
+    name: Option<String>,
+    description: Option<String>,
+) -> Result<(), String> {
+    vocabulary::export_to_file(
+        &PathBuf::from(path),
+        name.as_deref().unwrap_or("GigaWhisper vocabulary"),
+        description.as_deref().unwrap_or_default(),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// Import a rules pack file
+#[tauri::command]
+pub fn import_vocabulary(path: String, mode: Option<ImportMode>) -> Result<ImportSummary, String> {
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read rules pack: {}", e))?;
+
+    vocabulary::import_pack_json(&content, mode.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Download a rules pack from a URL and apply it
+#[tauri::command]
+pub async fn apply_rules_pack(url: String, mode: Option<ImportMode>) -> Result<ImportSummary, String> {
+    if !url.starts_with("https://") {
+        return Err("Rules packs must be downloaded over HTTPS".to_string());
+    }
+
+    tracing::info!("Downloading rules pack from {}", url);
+
+    let response = reqwest::get(&url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| vocabulary::VocabularyError::Download(e.to_string()).to_string())?;
+
+    let bytes = read_pack_body(response.content_length(), response.bytes_stream()).await?;
+    let content =
+        String::from_utf8(bytes).map_err(|_| "Rules pack is not valid UTF-8".to_string())?;
+
+    vocabulary::import_pack_json(&content, mode.unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// Body of a rules pack download, rejected once it passes `MAX_PACK_SIZE`
+///
+/// The declared length is checked first, then the body is read in chunks so
+/// a server lying about (or omitting) it can't make us buffer more.
+async fn read_pack_body<B, E>(
+    declared_len: Option<u64>,
+    chunks: impl futures_util::Stream<Item = Result<B, E>>,
+) -> Result<Vec<u8>, String>
+where
+    B: AsRef<[u8]>,
+    E: std::fmt::Display,
+{
+    use futures_util::StreamExt;
+
+    if let Some(len) = declared_len {
+        if len > MAX_PACK_SIZE as u64 {
+            return Err(format!(
+                "Rules pack is too large ({} bytes, max {})",
+                len, MAX_PACK_SIZE
+            ));
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut chunks = std::pin::pin!(chunks);
+    while let Some(chunk) = chunks.next().await {
+        let chunk =
+            chunk.map_err(|e| vocabulary::VocabularyError::Download(e.to_string()).to_string())?;
+        let chunk = chunk.as_ref();
+        if bytes.len() + chunk.len() > MAX_PACK_SIZE {
+            return Err(format!(
+                "Rules pack is too large (more than {} bytes)",
+                MAX_PACK_SIZE
+            ));
+        }
+        bytes.extend_from_slice(chunk);
+    }
+    Ok(bytes)
+}
+
+/// Get learned proper nouns, highest score first
+#[tauri::command]
+pub fn get_learned_terms() -> Vec<LearnedTerm> {
+    vocabulary::get_learned_terms()
+        .read()
+        .terms(vocabulary::unix_now())
+}
+
+/// Forget a single learned term
+#[tauri::command]
+pub fn remove_learned_term(term: String) -> Result<bool, String> {
+    let mut learned = vocabulary::get_learned_terms().write();
+    let removed = learned.remove(&term);
+    if removed {
+        learned.save().map_err(|e| e.to_string())?;
+    }
+    Ok(removed)
+}
+
+/// Drop learned terms whose decayed score is below `min_score`
+#[tauri::command]
+pub fn prune_learned_terms(min_score: Option<f32>) -> Result<usize, String> {
+    let mut learned = vocabulary::get_learned_terms().write();
+    let removed = learned.prune(
+        min_score.unwrap_or(vocabulary::APPLY_THRESHOLD),
+        vocabulary::unix_now(),
+    );
+    learned.save().map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_vocabulary_missing_file() {
+        let result = import_vocabulary("/nonexistent/rules.json".to_string(), None);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Failed to read"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_rules_pack_requires_https() {
+        let result = apply_rules_pack("http://example.com/pack.json".to_string(), None).await;
+        assert!(result.unwrap_err().contains("HTTPS"));
+    }
+
+    #[tokio::test]
+    async fn test_read_pack_body_limits_size() {
+        use futures_util::stream;
+
+        let chunks = |sizes: Vec<usize>| {
+            stream::iter(sizes.into_iter().map(|n| Ok::<_, String>(vec![b' '; n])))
+        };
+
+        let body = read_pack_body(Some(2), stream::iter([Ok::<_, String>(b"{}")]))
+            .await
+            .unwrap();
+        assert_eq!(body, b"{}");
+
+        // The declared length is rejected before reading anything
+        let error = read_pack_body(Some(MAX_PACK_SIZE as u64 + 1), chunks(vec![]))
+            .await
+            .unwrap_err();
+        assert!(error.contains("bytes, max"), "{}", error);
+
+        // A missing or false length is caught while reading
+        let half = MAX_PACK_SIZE / 2;
+        let error = read_pack_body(None, chunks(vec![half, half, 1]))
+            .await
+            .unwrap_err();
+        assert!(error.contains("more than"), "{}", error);
+        let error = read_pack_body(Some(10), chunks(vec![MAX_PACK_SIZE + 1]))
+            .await
+            .unwrap_err();
+        assert!(error.contains("more than"), "{}", error);
+
+        let body = read_pack_body(None, chunks(vec![half, MAX_PACK_SIZE - half]))
+            .await
+            .unwrap();
+        assert_eq!(body.len(), MAX_PACK_SIZE);
+    }
+}
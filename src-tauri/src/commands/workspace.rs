@@ -0,0 +1,104 @@
+//! Workspace Commands
+//!
+//! Create, switch and remove workspaces, each with its own history,
+//! vocabulary and output settings.
+
+use crate::config::{Workspace, WorkspaceSettings};
+use crate::{AppState, RecordingState};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+/// List workspaces and the active one
+#[tauri::command]
+pub fn list_workspaces(state: State<'_, AppState>) -> WorkspaceSettings {
+    state.config.read().workspaces.clone()
+}
+
+/// Create a workspace (it starts empty and is not activated)
+#[tauri::command]
+pub fn create_workspace(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Workspace, String> {
+    let (workspace, settings) = {
+        let mut config = state.config.write();
+        let workspace = config.workspaces.add(&name).map_err(|e| e.to_string())?;
+        (workspace, config.clone())
+    };
+    settings.save().map_err(|e| e.to_string())?;
+
+    tracing::info!("Workspace '{}' created", workspace.id);
+    crate::tray::refresh_tray_menu(&app);
+    Ok(workspace)
+}
+
+/// Switch to another workspace
+#[tauri::command]
+pub fn switch_workspace(app: AppHandle, id: String) -> Result<(), String> {
+    activate_workspace(&app, &id)
+}
+
+/// Remove an inactive workspace and its data
+#[tauri::command]
+pub fn delete_workspace(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<(), String> {
+    let settings = {
+        let mut config = state.config.write();
+        config.workspaces.remove(&id).map_err(|e| e.to_string())?;
+        config.clone()
+    };
+    settings.save().map_err(|e| e.to_string())?;
+
+    let dir = crate::config::workspace_dir_for(&id);
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            tracing::warn!("Failed to remove workspace data {:?}: {}", dir, e);
+        }
+    }
+
+    tracing::info!("Workspace '{}' removed", id);
+    crate::tray::refresh_tray_menu(&app);
+    Ok(())
+}
+
+/// Make a workspace active: swap its output settings in, point storage at
+/// its directory and reload history and vocabulary from there
+///
+/// Refused while recording, so a transcription in flight lands in the
+/// workspace it was started in.
+pub(crate) fn activate_workspace(app: &AppHandle, id: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    if !matches!(
+        *state.recording_state.read(),
+        RecordingState::Idle | RecordingState::Error(_)
+    ) {
+        return Err("Cannot switch workspace while recording".to_string());
+    }
+
+    let settings = {
+        let mut config = state.config.write();
+        if config.workspaces.active == id {
+            return Ok(());
+        }
+        config.switch_workspace(id).map_err(|e| e.to_string())?;
+        config.clone()
+    };
+    settings.save().map_err(|e| e.to_string())?;
+
+    // Learned terms waiting to be saved belong to the workspace being left
+    crate::vocabulary::save_learned_terms();
+    crate::config::set_active_workspace(id);
+    crate::history::reload_history();
+    state.tasks.spawn("waveform-backfill", async move {
+        let _ = tokio::task::spawn_blocking(crate::history::backfill_waveforms).await;
+    });
+    crate::vocabulary::reload_vocabulary();
+
+    tracing::info!("Switched to workspace '{}'", id);
+    let _ = app.emit("workspace:changed", id);
+    crate::tray::refresh_tray_menu(app);
+    Ok(())
+}
@@ -5,9 +5,11 @@
 mod migration;
 mod secrets;
 mod settings;
+mod storage;
 mod store;
 
 pub use migration::*;
 pub use secrets::*;
 pub use settings::*;
+pub use storage::*;
 pub use store::*;
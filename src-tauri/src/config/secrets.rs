@@ -7,6 +7,19 @@ use thiserror::Error;
 
 const SERVICE_NAME: &str = "gigawhisper";
 const GROQ_API_KEY_NAME: &str = "groq_api_key";
+const DEEPGRAM_API_KEY_NAME: &str = "deepgram_api_key";
+const CUSTOM_ENDPOINT_TOKEN_NAME: &str = "custom_endpoint_token";
+const OPENAI_API_KEY_NAME: &str = "openai_api_key";
+const CLEANUP_ENDPOINT_TOKEN_NAME: &str = "cleanup_endpoint_token";
+const WEBHOOK_AUTH_NAME: &str = "webhook_auth";
+const MQTT_PASSWORD_NAME: &str = "mqtt_password";
+const HISTORY_KEY_NAME: &str = "history_key";
+const VOICE_PROFILE_NAME: &str = "voice_profile";
+
+/// Credential name of the authorization value of the webhook at `url`
+fn webhook_auth_name(url: &str) -> String {
+    format!("{}:{}", WEBHOOK_AUTH_NAME, url.trim())
+}
 
 /// Errors related to secret storage
 #[derive(Debug, Error)]
@@ -115,6 +128,372 @@ impl SecretsManager {
 
         Ok(())
     }
+
+    /// Store the Deepgram API key securely
+    pub fn set_deepgram_api_key(api_key: &str) -> Result<(), SecretsError> {
+        Self::validate_deepgram_api_key(api_key)?;
+
+        let entry = Entry::new(SERVICE_NAME, DEEPGRAM_API_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(api_key.trim())?;
+        tracing::info!("Deepgram API key stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the Deepgram API key
+    pub fn get_deepgram_api_key() -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, DEEPGRAM_API_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Delete the Deepgram API key
+    pub fn delete_deepgram_api_key() -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, DEEPGRAM_API_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.delete_credential()?;
+        tracing::info!("Deepgram API key removed from credential manager");
+        Ok(())
+    }
+
+    /// Check if Deepgram API key exists
+    pub fn has_deepgram_api_key() -> bool {
+        Self::get_deepgram_api_key().is_ok()
+    }
+
+    /// Validate Deepgram API key format
+    /// Deepgram API keys are 40 hexadecimal characters
+    pub fn validate_deepgram_api_key(api_key: &str) -> Result<(), SecretsError> {
+        let api_key = api_key.trim();
+
+        if api_key.is_empty() {
+            return Err(SecretsError::InvalidFormat(
+                "API key cannot be empty".to_string(),
+            ));
+        }
+        if api_key.len() != 40 {
+            return Err(SecretsError::InvalidFormat(
+                "Deepgram API key must be 40 characters".to_string(),
+            ));
+        }
+        if !api_key.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(SecretsError::InvalidFormat(
+                "API key contains invalid characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Store the custom endpoint bearer token securely
+    pub fn set_custom_endpoint_token(token: &str) -> Result<(), SecretsError> {
+        Self::validate_custom_endpoint_token(token)?;
+
+        let entry = Entry::new(SERVICE_NAME, CUSTOM_ENDPOINT_TOKEN_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(token.trim())?;
+        tracing::info!("Custom endpoint token stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the custom endpoint bearer token
+    pub fn get_custom_endpoint_token() -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, CUSTOM_ENDPOINT_TOKEN_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Delete the custom endpoint bearer token
+    pub fn delete_custom_endpoint_token() -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, CUSTOM_ENDPOINT_TOKEN_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.delete_credential()?;
+        tracing::info!("Custom endpoint token removed from credential manager");
+        Ok(())
+    }
+
+    /// Check if a custom endpoint bearer token exists
+    pub fn has_custom_endpoint_token() -> bool {
+        Self::get_custom_endpoint_token().is_ok()
+    }
+
+    /// Validate a custom endpoint bearer token
+    /// Tokens are server-specific, so only what an HTTP header can carry is checked
+    pub fn validate_custom_endpoint_token(token: &str) -> Result<(), SecretsError> {
+        let token = token.trim();
+
+        if token.is_empty() {
+            return Err(SecretsError::InvalidFormat(
+                "Token cannot be empty".to_string(),
+            ));
+        }
+        if token.chars().any(|c| !c.is_ascii_graphic()) {
+            return Err(SecretsError::InvalidFormat(
+                "Token contains invalid characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Store the OpenAI API key (used by transcript cleanup) securely
+    pub fn set_openai_api_key(api_key: &str) -> Result<(), SecretsError> {
+        Self::validate_openai_api_key(api_key)?;
+
+        let entry = Entry::new(SERVICE_NAME, OPENAI_API_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(api_key.trim())?;
+        tracing::info!("OpenAI API key stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the OpenAI API key
+    pub fn get_openai_api_key() -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, OPENAI_API_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Delete the OpenAI API key
+    pub fn delete_openai_api_key() -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, OPENAI_API_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.delete_credential()?;
+        tracing::info!("OpenAI API key removed from credential manager");
+        Ok(())
+    }
+
+    /// Check if an OpenAI API key exists
+    pub fn has_openai_api_key() -> bool {
+        Self::get_openai_api_key().is_ok()
+    }
+
+    /// Validate OpenAI API key format
+    /// OpenAI keys start with "sk-" and hold no whitespace
+    pub fn validate_openai_api_key(api_key: &str) -> Result<(), SecretsError> {
+        let api_key = api_key.trim();
+
+        if api_key.is_empty() {
+            return Err(SecretsError::InvalidFormat(
+                "API key cannot be empty".to_string(),
+            ));
+        }
+        if !api_key.starts_with("sk-") {
+            return Err(SecretsError::InvalidFormat(
+                "API key must start with 'sk-'".to_string(),
+            ));
+        }
+        if api_key.chars().any(|c| !c.is_ascii_graphic()) {
+            return Err(SecretsError::InvalidFormat(
+                "API key contains invalid characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Store the bearer token of a custom cleanup server securely
+    pub fn set_cleanup_endpoint_token(token: &str) -> Result<(), SecretsError> {
+        Self::validate_custom_endpoint_token(token)?;
+
+        let entry = Entry::new(SERVICE_NAME, CLEANUP_ENDPOINT_TOKEN_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(token.trim())?;
+        tracing::info!("Cleanup endpoint token stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the custom cleanup server's bearer token
+    pub fn get_cleanup_endpoint_token() -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, CLEANUP_ENDPOINT_TOKEN_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Delete the custom cleanup server's bearer token
+    pub fn delete_cleanup_endpoint_token() -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, CLEANUP_ENDPOINT_TOKEN_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.delete_credential()?;
+        tracing::info!("Cleanup endpoint token removed from credential manager");
+        Ok(())
+    }
+
+    /// Check if a custom cleanup server token exists
+    pub fn has_cleanup_endpoint_token() -> bool {
+        Self::get_cleanup_endpoint_token().is_ok()
+    }
+
+    /// Store the authorization header value of the webhook at `url` securely
+    ///
+    /// Each webhook URL has its own value, so a credential is never sent to
+    /// another endpoint.
+    pub fn set_webhook_auth(url: &str, value: &str) -> Result<(), SecretsError> {
+        Self::validate_webhook_auth(value)?;
+        if url.trim().is_empty() {
+            return Err(SecretsError::InvalidFormat(
+                "Webhook URL cannot be empty".to_string(),
+            ));
+        }
+
+        let entry = Entry::new(SERVICE_NAME, &webhook_auth_name(url))
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(value.trim())?;
+        tracing::info!("Webhook authorization stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the authorization header value of the webhook at `url`
+    pub fn get_webhook_auth(url: &str) -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, &webhook_auth_name(url))
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Delete the authorization header value of the webhook at `url`
+    pub fn delete_webhook_auth(url: &str) -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, &webhook_auth_name(url))
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.delete_credential()?;
+        tracing::info!("Webhook authorization removed from credential manager");
+        Ok(())
+    }
+
+    /// Check if the webhook at `url` has an authorization header value
+    pub fn has_webhook_auth(url: &str) -> bool {
+        Self::get_webhook_auth(url).is_ok()
+    }
+
+    /// Validate a webhook authorization header value
+    /// Values such as "Bearer <token>" may contain spaces but no control characters
+    pub fn validate_webhook_auth(value: &str) -> Result<(), SecretsError> {
+        let value = value.trim();
+
+        if value.is_empty() {
+            return Err(SecretsError::InvalidFormat(
+                "Header value cannot be empty".to_string(),
+            ));
+        }
+        if value.chars().any(|c| !(c.is_ascii_graphic() || c == ' ')) {
+            return Err(SecretsError::InvalidFormat(
+                "Header value contains invalid characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Store the MQTT broker password securely
+    pub fn set_mqtt_password(password: &str) -> Result<(), SecretsError> {
+        if password.is_empty() {
+            return Err(SecretsError::InvalidFormat(
+                "Password cannot be empty".to_string(),
+            ));
+        }
+
+        let entry = Entry::new(SERVICE_NAME, MQTT_PASSWORD_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(password)?;
+        tracing::info!("MQTT password stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the MQTT broker password
+    pub fn get_mqtt_password() -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, MQTT_PASSWORD_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Delete the MQTT broker password
+    pub fn delete_mqtt_password() -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, MQTT_PASSWORD_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.delete_credential()?;
+        tracing::info!("MQTT password removed from credential manager");
+        Ok(())
+    }
+
+    /// Check if an MQTT broker password exists
+    pub fn has_mqtt_password() -> bool {
+        Self::get_mqtt_password().is_ok()
+    }
+
+    /// Store the history encryption key (base64)
+    pub fn set_history_key(key: &str) -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, HISTORY_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        entry.set_password(key)?;
+        tracing::info!("History encryption key stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the history encryption key (base64)
+    pub fn get_history_key() -> Result<String, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, HISTORY_KEY_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        Ok(entry.get_password()?)
+    }
+
+    /// Store the enrolled speaker embedding
+    ///
+    /// Kept as raw little-endian floats, which fits the credential size
+    /// limits where the base64 text would not.
+    pub fn set_voice_profile(embedding: &[f32]) -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, VOICE_PROFILE_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        let bytes: Vec<u8> = embedding.iter().flat_map(|v| v.to_le_bytes()).collect();
+        entry.set_secret(&bytes)?;
+        tracing::info!("Voice profile stored securely in credential manager");
+        Ok(())
+    }
+
+    /// Retrieve the enrolled speaker embedding
+    pub fn get_voice_profile() -> Result<Vec<f32>, SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, VOICE_PROFILE_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        let bytes = entry.get_secret()?;
+        if bytes.is_empty() || bytes.len() % 4 != 0 {
+            return Err(SecretsError::InvalidFormat(
+                "Voice profile has an invalid length".to_string(),
+            ));
+        }
+        Ok(bytes
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect())
+    }
+
+    /// Delete the enrolled speaker embedding
+    pub fn delete_voice_profile() -> Result<(), SecretsError> {
+        let entry = Entry::new(SERVICE_NAME, VOICE_PROFILE_NAME)
+            .map_err(|e| SecretsError::CredentialStoreError(e.to_string()))?;
+
+        match entry.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => {}
+            Err(e) => return Err(e.into()),
+        }
+        tracing::info!("Voice profile removed from credential manager");
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -706,6 +1085,57 @@ mod tests {
         assert!(SecretsManager::validate_groq_api_key(key).is_err());
     }
 
+    #[test]
+    fn test_validate_deepgram_api_key() {
+        let key = "0123456789abcdef0123456789abcdef01234567";
+        assert!(SecretsManager::validate_deepgram_api_key(key).is_ok());
+        assert!(SecretsManager::validate_deepgram_api_key(&format!(" {} ", key)).is_ok());
+        assert!(SecretsManager::validate_deepgram_api_key("").is_err());
+        assert!(SecretsManager::validate_deepgram_api_key(&key[..39]).is_err());
+        assert!(SecretsManager::validate_deepgram_api_key(&key.replace('a', "g")).is_err());
+        // Groq keys are not accepted
+        assert!(SecretsManager::validate_deepgram_api_key("gsk_1234567890123456").is_err());
+    }
+
+    #[test]
+    fn test_validate_custom_endpoint_token() {
+        assert!(SecretsManager::validate_custom_endpoint_token("sk-local-123").is_ok());
+        assert!(SecretsManager::validate_custom_endpoint_token(" token ").is_ok());
+        assert!(SecretsManager::validate_custom_endpoint_token("").is_err());
+        assert!(SecretsManager::validate_custom_endpoint_token("two words").is_err());
+        assert!(SecretsManager::validate_custom_endpoint_token("tok\nen").is_err());
+    }
+
+    #[test]
+    fn test_validate_openai_api_key() {
+        assert!(SecretsManager::validate_openai_api_key("sk-proj-abc123").is_ok());
+        assert!(SecretsManager::validate_openai_api_key(" sk-abc123\n").is_ok());
+        assert!(SecretsManager::validate_openai_api_key("").is_err());
+        assert!(SecretsManager::validate_openai_api_key("gsk_1234567890123456").is_err());
+        assert!(SecretsManager::validate_openai_api_key("sk-two words").is_err());
+    }
+
+    #[test]
+    fn test_webhook_auth_is_per_url() {
+        assert_eq!(
+            webhook_auth_name(" https://example.com/hook "),
+            "webhook_auth:https://example.com/hook"
+        );
+        assert_ne!(
+            webhook_auth_name("https://example.com/hook"),
+            webhook_auth_name("https://example.org/hook")
+        );
+        assert!(SecretsManager::set_webhook_auth(" ", "Bearer abc").is_err());
+    }
+
+    #[test]
+    fn test_validate_webhook_auth() {
+        assert!(SecretsManager::validate_webhook_auth("Bearer abc.def").is_ok());
+        assert!(SecretsManager::validate_webhook_auth(" secret\n").is_ok());
+        assert!(SecretsManager::validate_webhook_auth("  ").is_err());
+        assert!(SecretsManager::validate_webhook_auth("a\r\nX-Injected: 1").is_err());
+    }
+
     #[test]
     fn test_validate_realistic_groq_key_format() {
         // Realistic Groq API key format (typically looks like this)
@@ -2,7 +2,9 @@
 //!
 //! Application configuration schema.
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Current schema version for migration support
 /// Increment this when making breaking changes to the settings structure
@@ -14,7 +16,7 @@ fn default_schema_version() -> u32 {
 }
 
 /// Main settings structure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct Settings {
     /// Schema version for migration support
@@ -28,6 +30,28 @@ pub struct Settings {
     pub audio: AudioSettings,
     pub output: OutputSettings,
     pub ui: UiSettings,
+    /// Keywords raising an alert when transcribed
+    pub alerts: AlertSettings,
+    /// Scripts run at recording and transcription lifecycle points
+    pub hooks: HookSettings,
+    /// Recording profiles, each bound to its own shortcut
+    pub profiles: Vec<RecordingProfile>,
+    /// Separate history, vocabulary and output contexts
+    pub workspaces: WorkspaceSettings,
+    /// Memory caps for models and caches
+    pub resources: ResourceSettings,
+    /// Stop-to-paste latency target
+    pub latency: LatencySettings,
+    /// Removal of downloaded models that are no longer used
+    pub model_pruning: ModelPruningSettings,
+    /// Model directory shared by the users of the machine
+    pub model_storage: ModelStorageSettings,
+    /// Local HTTP and websocket API for external tools
+    pub api_server: ApiServerSettings,
+    /// Publishing of recording state and transcripts to an MQTT broker
+    pub mqtt: MqttSettings,
+    /// Keeping audio and transcripts off the disk
+    pub privacy: PrivacySettings,
 }
 
 impl Default for Settings {
@@ -40,6 +64,17 @@ impl Default for Settings {
             audio: AudioSettings::default(),
             output: OutputSettings::default(),
             ui: UiSettings::default(),
+            alerts: AlertSettings::default(),
+            hooks: HookSettings::default(),
+            profiles: Vec::new(),
+            workspaces: WorkspaceSettings::default(),
+            resources: ResourceSettings::default(),
+            latency: LatencySettings::default(),
+            model_pruning: ModelPruningSettings::default(),
+            model_storage: ModelStorageSettings::default(),
+            api_server: ApiServerSettings::default(),
+            mqtt: MqttSettings::default(),
+            privacy: PrivacySettings::default(),
         }
     }
 }
@@ -51,11 +86,42 @@ impl Settings {
         if self.shortcuts.record.is_empty() {
             return Err(SettingsError::InvalidShortcut("record shortcut is empty".to_string()));
         }
-
-        // Validate Groq API key if cloud provider selected
-        if self.transcription.provider == TranscriptionProvider::Groq
-            && !self.transcription.groq.has_api_key()
+        if self.shortcuts.palette.eq_ignore_ascii_case(&self.shortcuts.record) {
+            return Err(SettingsError::InvalidShortcut(
+                "palette shortcut must differ from record shortcut".to_string(),
+            ));
+        }
+        if !self.shortcuts.incognito.is_empty()
+            && (self.shortcuts.incognito.eq_ignore_ascii_case(&self.shortcuts.record)
+                || self.shortcuts.incognito.eq_ignore_ascii_case(&self.shortcuts.palette))
         {
+            return Err(SettingsError::InvalidShortcut(
+                "incognito shortcut must differ from record and palette shortcuts".to_string(),
+            ));
+        }
+        self.shortcuts.gestures.validate()?;
+        let assigned = self.shortcuts.assigned();
+        for (i, (name, shortcut)) in assigned.iter().enumerate() {
+            if let Some((other, _)) = assigned[..i]
+                .iter()
+                .find(|(_, s)| s.eq_ignore_ascii_case(shortcut))
+            {
+                return Err(SettingsError::InvalidShortcut(format!(
+                    "{} shortcut {} is already used by the {} shortcut",
+                    name, shortcut, other
+                )));
+            }
+        }
+
+        // Validate the API key if a cloud provider is selected
+        let has_api_key = match self.transcription.provider {
+            TranscriptionProvider::Local => true,
+            TranscriptionProvider::Groq => self.transcription.groq.has_api_key(),
+            TranscriptionProvider::Deepgram => self.transcription.deepgram.has_api_key(),
+            // The bearer token is optional
+            TranscriptionProvider::Custom => true,
+        };
+        if !has_api_key {
             return Err(SettingsError::MissingApiKey);
         }
 
@@ -71,6 +137,28 @@ impl Settings {
         // Validate output settings
         self.output.validate()?;
 
+        // Validate alert keywords
+        self.alerts.validate()?;
+
+        // Validate automation hooks
+        self.hooks.validate()?;
+
+        // Validate recording profiles
+        self.validate_profiles()?;
+
+        // Validate workspaces
+        self.workspaces.validate()?;
+
+        // Validate memory caps
+        self.resources.validate()?;
+
+        // Validate latency target
+        self.latency.validate()?;
+        self.model_pruning.validate()?;
+        self.model_storage.validate()?;
+        self.api_server.validate()?;
+        self.mqtt.validate()?;
+
         Ok(())
     }
 
@@ -84,9 +172,150 @@ impl Settings {
         sanitized.audio.sanitize();
         sanitized.transcription.sanitize();
         sanitized.output.sanitize();
+        sanitized.alerts.sanitize();
+        sanitized.hooks.sanitize();
+        sanitized.workspaces.sanitize();
+        sanitized.resources.sanitize();
+        sanitized.latency.sanitize();
+        sanitized.model_pruning.sanitize();
+        sanitized.shortcuts.gestures.sanitize();
+        sanitized.model_storage.sanitize();
+        for profile in &mut sanitized.profiles {
+            if let Some(sinks) = profile.sinks.as_mut() {
+                sinks.retain(|sink| sink.validate().is_ok());
+                sinks.truncate(OutputSettings::MAX_SINKS);
+            }
+        }
+        sanitized.profiles.truncate(Self::MAX_PROFILES);
         sanitized
     }
 
+    /// Maximum number of recording profiles
+    pub const MAX_PROFILES: usize = 16;
+
+    /// Find a recording profile by id
+    pub fn profile(&self, id: &str) -> Option<&RecordingProfile> {
+        self.profiles.iter().find(|p| p.id == id)
+    }
+
+    /// Settings with a profile's overrides applied (unknown or no profile = unchanged)
+    pub fn effective_for_profile(&self, profile_id: Option<&str>) -> Settings {
+        match profile_id.and_then(|id| self.profile(id)) {
+            Some(profile) => profile.apply(self),
+            None => self.clone(),
+        }
+    }
+
+    /// Settings with a per-recording translation override applied
+    pub fn with_translate(mut self, translate: Option<bool>) -> Settings {
+        if let Some(translate) = translate {
+            self.transcription.translate = translate;
+        }
+        self
+    }
+
+    /// Settings for an incognito recording: the text is only pasted, and
+    /// nothing that would write it or its audio to disk is enabled
+    pub fn effective_for_incognito(&self) -> Settings {
+        let mut settings = self.clone();
+        settings.recording.save_debug_captures = false;
+        settings.output.capture_screenshots = false;
+        settings.output.sinks = vec![OutputSink::Paste];
+        settings.hooks.enabled = false;
+        settings.privacy.no_disk_audio = true;
+        settings
+    }
+
+    /// Make another workspace active, swapping in its output settings
+    ///
+    /// The current output settings are kept with the workspace being left;
+    /// a workspace that never had its own inherits them.
+    pub fn switch_workspace(&mut self, id: &str) -> Result<(), SettingsError> {
+        if self.workspaces.get(id).is_none() {
+            return Err(SettingsError::InvalidValue(format!("unknown workspace '{}'", id)));
+        }
+        if self.workspaces.active == id {
+            return Ok(());
+        }
+
+        let current = self.output.clone();
+        let active = self.workspaces.active.clone();
+        for workspace in &mut self.workspaces.list {
+            if workspace.id == active {
+                workspace.output = Some(current.clone());
+            } else if workspace.id == id {
+                self.output = workspace.output.take().unwrap_or_else(|| current.clone());
+            }
+        }
+        self.workspaces.active = id.to_string();
+        Ok(())
+    }
+
+    /// Check profile ids and shortcuts are set and unique
+    fn validate_profiles(&self) -> Result<(), SettingsError> {
+        if self.profiles.len() > Self::MAX_PROFILES {
+            return Err(SettingsError::InvalidValue(format!(
+                "{} recording profiles exceeds limit of {}",
+                self.profiles.len(),
+                Self::MAX_PROFILES
+            )));
+        }
+
+        let mut ids: Vec<&str> = Vec::new();
+        let mut shortcuts: Vec<&str> = self
+            .shortcuts
+            .assigned()
+            .into_iter()
+            .map(|(_, shortcut)| shortcut)
+            .collect();
+
+        for profile in &self.profiles {
+            if profile.id.trim().is_empty() {
+                return Err(SettingsError::InvalidValue("profile id is empty".to_string()));
+            }
+            if ids.contains(&profile.id.as_str()) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "duplicate profile id '{}'",
+                    profile.id
+                )));
+            }
+            if profile.shortcut.is_empty() {
+                return Err(SettingsError::InvalidShortcut(format!(
+                    "profile '{}' has no shortcut",
+                    profile.name
+                )));
+            }
+            if shortcuts.iter().any(|s| s.eq_ignore_ascii_case(&profile.shortcut)) {
+                return Err(SettingsError::InvalidShortcut(format!(
+                    "profile '{}' shortcut {} is already in use",
+                    profile.name, profile.shortcut
+                )));
+            }
+            if let Some(sinks) = &profile.sinks {
+                for sink in sinks {
+                    sink.validate()?;
+                }
+            }
+            ids.push(&profile.id);
+            shortcuts.push(&profile.shortcut);
+        }
+
+        Ok(())
+    }
+
+    /// Bring the quality preset in line with an edit of these settings
+    ///
+    /// A newly picked preset sets its values; otherwise a preset whose
+    /// values were changed by hand becomes `Custom`.
+    pub fn sync_quality_preset(&mut self, previous: QualityPreset) {
+        let preset = self.transcription.quality_preset;
+        if preset != previous {
+            preset.apply(self);
+        } else if !preset.matches(self) {
+            self.transcription.quality_preset = QualityPreset::Custom;
+        }
+    }
+
     /// Load settings from disk
     pub fn load() -> Result<Self, SettingsError> {
         super::store::load_settings()
@@ -99,7 +328,7 @@ impl Settings {
 }
 
 /// Recording behavior settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct RecordingSettings {
     /// Recording mode: push-to-talk or toggle
@@ -108,6 +337,14 @@ pub struct RecordingSettings {
     pub max_duration: u32,
     /// Auto-stop after silence (milliseconds, 0 = disabled)
     pub silence_timeout: u32,
+    /// Keep the raw audio and settings of each recording for replay
+    pub save_debug_captures: bool,
+    /// Minutes an incognito dictation stays in memory before it is wiped
+    pub incognito_minutes: u32,
+    /// Audio kept with each history entry
+    pub history_audio: HistoryAudio,
+    /// Encoding of the audio kept with each history entry
+    pub history_audio_codec: HistoryAudioCodec,
 }
 
 /// Recording settings constraints
@@ -116,6 +353,8 @@ impl RecordingSettings {
     pub const MAX_DURATION_LIMIT: u32 = 1800;
     /// Maximum silence timeout (60 seconds)
     pub const MAX_SILENCE_TIMEOUT: u32 = 60000;
+    /// Longest an incognito dictation can be kept (1 hour)
+    pub const MAX_INCOGNITO_MINUTES: u32 = 60;
 
     /// Validate recording settings
     pub fn validate(&self) -> Result<(), SettingsError> {
@@ -133,6 +372,12 @@ impl RecordingSettings {
                 Self::MAX_SILENCE_TIMEOUT
             )));
         }
+        if !(1..=Self::MAX_INCOGNITO_MINUTES).contains(&self.incognito_minutes) {
+            return Err(SettingsError::InvalidValue(format!(
+                "incognito_minutes must be between 1 and {}",
+                Self::MAX_INCOGNITO_MINUTES
+            )));
+        }
         Ok(())
     }
 
@@ -140,6 +385,7 @@ impl RecordingSettings {
     pub fn sanitize(&mut self) {
         self.max_duration = self.max_duration.min(Self::MAX_DURATION_LIMIT);
         self.silence_timeout = self.silence_timeout.min(Self::MAX_SILENCE_TIMEOUT);
+        self.incognito_minutes = self.incognito_minutes.clamp(1, Self::MAX_INCOGNITO_MINUTES);
     }
 }
 
@@ -149,12 +395,57 @@ impl Default for RecordingSettings {
             mode: RecordingMode::PushToTalk,
             max_duration: 300, // 5 minutes
             silence_timeout: 0,
+            save_debug_captures: false,
+            incognito_minutes: 5,
+            history_audio: HistoryAudio::default(),
+            history_audio_codec: HistoryAudioCodec::default(),
+        }
+    }
+}
+
+/// Audio saved with history entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HistoryAudio {
+    /// The whole recording
+    #[default]
+    Full,
+    /// The recording without leading and trailing silence
+    Trimmed,
+    /// Only the speech kept by voice activity detection
+    Speech,
+}
+
+/// Encoding of audio saved with history entries
+///
+/// FLAC and Opus are encoded with `ffmpeg` in the background after the text
+/// is delivered; recordings stay WAV, with a notification, when it is not
+/// installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryAudioCodec {
+    /// Uncompressed 16-bit PCM
+    #[default]
+    Wav,
+    /// Lossless, about half the size of WAV
+    Flac,
+    /// Lossy speech codec, about a tenth of the size of WAV
+    Opus,
+}
+
+impl HistoryAudioCodec {
+    /// Extension of saved files, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Wav => "wav",
+            Self::Flac => "flac",
+            Self::Opus => "opus",
         }
     }
 }
 
 /// Recording mode
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum RecordingMode {
     PushToTalk,
@@ -162,15 +453,31 @@ pub enum RecordingMode {
 }
 
 /// Keyboard shortcut settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct ShortcutSettings {
     /// Main recording shortcut
     pub record: String,
-    /// Cancel recording shortcut
+    /// Cancel recording shortcut, active only while recording (empty = disabled)
     pub cancel: String,
-    /// Open settings shortcut
+    /// Open settings shortcut (empty = disabled)
     pub settings: String,
+    /// Open the history quick-paste palette (empty = disabled)
+    pub palette: String,
+    /// Record an incognito dictation, kept in memory only (empty = disabled)
+    pub incognito: String,
+    /// Show or hide the main window (empty = disabled)
+    pub toggle_window: String,
+    /// Paste the last transcription again (empty = disabled)
+    pub repaste_last: String,
+    /// Cycle the recording profile used by the record shortcut (empty = disabled)
+    pub switch_profile: String,
+    /// Record the system audio output instead of the microphone (empty = disabled)
+    pub system_audio: String,
+    /// Tap, double-tap and long-press gestures on record shortcuts
+    pub gestures: GestureSettings,
+    /// Mouse and gamepad buttons acting as the record shortcut
+    pub triggers: TriggerSettings,
 }
 
 impl Default for ShortcutSettings {
@@ -178,32 +485,301 @@ impl Default for ShortcutSettings {
         Self {
             record: "Ctrl+Space".to_string(),
             cancel: "Escape".to_string(),
-            settings: "Ctrl+Shift+W".to_string(),
+            settings: String::new(),
+            palette: "Ctrl+Shift+H".to_string(),
+            incognito: String::new(),
+            toggle_window: String::new(),
+            repaste_last: String::new(),
+            switch_profile: String::new(),
+            system_audio: String::new(),
+            gestures: GestureSettings::default(),
+            triggers: TriggerSettings::default(),
         }
     }
 }
 
+impl ShortcutSettings {
+    /// Shortcuts in use, by setting name (disabled ones left out)
+    pub fn assigned(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("record", self.record.as_str()),
+            ("cancel", &self.cancel),
+            ("settings", &self.settings),
+            ("palette", &self.palette),
+            ("incognito", &self.incognito),
+            ("toggle window", &self.toggle_window),
+            ("re-paste last", &self.repaste_last),
+            ("switch profile", &self.switch_profile),
+            ("system audio", &self.system_audio),
+        ]
+        .into_iter()
+        .filter(|(_, shortcut)| !shortcut.is_empty())
+        .collect()
+    }
+}
+
+/// Record triggers besides the keyboard
+///
+/// Each trigger acts like the record shortcut, following the recording mode
+/// and gestures. Foot pedals usually present themselves as gamepads.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TriggerSettings {
+    /// Mouse button (None = disabled)
+    pub mouse_button: Option<MouseTrigger>,
+    /// Gamepad or pedal button (None = disabled)
+    pub gamepad_button: Option<GamepadTrigger>,
+}
+
+/// Mouse button usable as a record trigger
+///
+/// macOS only reports the left and right buttons to the listener, so mouse
+/// triggers work on Windows and X11 only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MouseTrigger {
+    Middle,
+    /// Back side button; the focused app still sees it and navigates back
+    Back,
+    /// Forward side button; the focused app still sees it and navigates forward
+    Forward,
+}
+
+/// Gamepad button usable as a record trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GamepadTrigger {
+    /// Any button, for single-button devices such as foot pedals
+    Any,
+    South,
+    East,
+    North,
+    West,
+    LeftBumper,
+    RightBumper,
+    LeftTrigger,
+    RightTrigger,
+    Select,
+    Start,
+}
+
+/// Record shortcut gestures
+///
+/// When enabled, a tap toggles recording, a double-tap pastes the last
+/// transcription again and a long press records push-to-talk style,
+/// whatever the recording mode. A tap only acts once the double-tap window
+/// has passed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GestureSettings {
+    pub enabled: bool,
+    /// Longest gap between the taps of a double-tap, in milliseconds
+    pub double_tap_ms: u32,
+    /// Shortest hold that counts as a long press, in milliseconds
+    pub long_press_ms: u32,
+}
+
+impl Default for GestureSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            double_tap_ms: 300,
+            long_press_ms: 400,
+        }
+    }
+}
+
+impl GestureSettings {
+    /// Valid double-tap window
+    pub const DOUBLE_TAP_RANGE: std::ops::RangeInclusive<u32> = 100..=1000;
+    /// Valid long-press threshold
+    pub const LONG_PRESS_RANGE: std::ops::RangeInclusive<u32> = 200..=3000;
+
+    /// Validate gesture thresholds
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !Self::DOUBLE_TAP_RANGE.contains(&self.double_tap_ms) {
+            return Err(SettingsError::InvalidValue(format!(
+                "double-tap window must be between {} and {} ms",
+                Self::DOUBLE_TAP_RANGE.start(),
+                Self::DOUBLE_TAP_RANGE.end()
+            )));
+        }
+        if !Self::LONG_PRESS_RANGE.contains(&self.long_press_ms) {
+            return Err(SettingsError::InvalidValue(format!(
+                "long-press threshold must be between {} and {} ms",
+                Self::LONG_PRESS_RANGE.start(),
+                Self::LONG_PRESS_RANGE.end()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sanitize gesture thresholds by clamping to valid ranges
+    pub fn sanitize(&mut self) {
+        self.double_tap_ms = self.double_tap_ms.clamp(
+            *Self::DOUBLE_TAP_RANGE.start(),
+            *Self::DOUBLE_TAP_RANGE.end(),
+        );
+        self.long_press_ms = self.long_press_ms.clamp(
+            *Self::LONG_PRESS_RANGE.start(),
+            *Self::LONG_PRESS_RANGE.end(),
+        );
+    }
+}
+
+/// Recording profile bound to its own shortcut
+///
+/// Unset fields fall back to the global settings.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct RecordingProfile {
+    /// Unique identifier
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Global shortcut that records with this profile
+    pub shortcut: String,
+    /// Language code override (ISO 639-1 or "auto")
+    pub language: Option<String>,
+    /// Provider override
+    pub provider: Option<TranscriptionProvider>,
+    /// Local model override
+    pub local_model: Option<WhisperModel>,
+    /// Groq model override
+    pub groq_model: Option<String>,
+    /// Translation to English override
+    pub translate: Option<bool>,
+    /// Output sinks override
+    pub sinks: Option<Vec<OutputSink>>,
+    /// Learned-term capitalization override
+    pub capitalize_learned_terms: Option<bool>,
+    /// Smart spacing override
+    pub smart_spacing: Option<bool>,
+    /// Typography normalization override
+    pub typography: Option<bool>,
+    /// Text casing override
+    pub casing: Option<TextCasing>,
+    /// Audio source override
+    pub source: Option<AudioSource>,
+}
+
+impl RecordingProfile {
+    /// Apply this profile's overrides on top of `base`
+    pub fn apply(&self, base: &Settings) -> Settings {
+        let mut settings = base.clone();
+        let transcription = &mut settings.transcription;
+
+        if let Some(language) = &self.language {
+            transcription.language = language.clone();
+        }
+        if let Some(provider) = &self.provider {
+            transcription.provider = provider.clone();
+        }
+        if let Some(model) = &self.local_model {
+            transcription.local.model = model.clone();
+            transcription.local.custom_model = None;
+        }
+        if let Some(model) = &self.groq_model {
+            transcription.groq.model = model.clone();
+        }
+        if let Some(translate) = self.translate {
+            transcription.translate = translate;
+        }
+
+        if let Some(sinks) = &self.sinks {
+            settings.output.sinks = sinks.clone();
+        }
+        if let Some(capitalize) = self.capitalize_learned_terms {
+            settings.output.capitalize_learned_terms = capitalize;
+        }
+        if let Some(smart_spacing) = self.smart_spacing {
+            settings.output.smart_spacing = smart_spacing;
+        }
+        if let Some(typography) = self.typography {
+            settings.output.typography.enabled = typography;
+        }
+        if let Some(casing) = self.casing {
+            settings.output.casing = casing;
+        }
+        if let Some(source) = self.source {
+            settings.audio.source = source;
+        }
+
+        settings
+    }
+}
+
 /// Transcription settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct TranscriptionSettings {
     /// Active provider
     pub provider: TranscriptionProvider,
+    /// Providers tried in order when the active one fails
+    pub fallback_providers: Vec<TranscriptionProvider>,
     /// Language code (ISO 639-1) or "auto"
     pub language: String,
     /// Local whisper.cpp settings
     pub local: LocalTranscriptionSettings,
     /// Groq API settings
     pub groq: GroqSettings,
+    /// Deepgram streaming API settings
+    pub deepgram: DeepgramSettings,
+    /// Self-hosted OpenAI-compatible server settings
+    pub custom: CustomEndpointSettings,
+    /// Retries of failed cloud requests
+    pub retry: RetrySettings,
+    /// Warm up the provider on pre-dictation signals
+    pub prefetch: PrefetchMode,
+    /// Translate transcriptions to English
+    pub translate: bool,
+    /// Text given to the model as what came before the recording, to steer
+    /// its spelling and style (e.g. "Cardiology consult notes.")
+    pub initial_prompt: String,
+    /// Words the model should recognize: names, jargon, code identifiers
+    pub vocabulary: Vec<String>,
+    /// Named bundle of model, decoding, VAD and GPU settings
+    pub quality_preset: QualityPreset,
+    /// Keep an English translation next to each transcript, for language
+    /// practice (transcribes each recording a second time, after delivery;
+    /// moot when translating)
+    pub language_learning: bool,
+    /// Emit partial text while recording (local and Deepgram providers;
+    /// Deepgram's streaming session then also gives the final text)
+    pub live_partials: bool,
+    /// Audio between partial transcriptions, in milliseconds
+    pub partial_interval_ms: u32,
+    /// Adjust the partial interval to how fast partials transcribe, starting
+    /// from `partial_interval_ms`
+    pub adaptive_partials: bool,
+    /// Shortest adaptive partial interval, in milliseconds
+    pub partial_interval_min_ms: u32,
+    /// Longest adaptive partial interval, in milliseconds
+    pub partial_interval_max_ms: u32,
 }
 
 impl Default for TranscriptionSettings {
     fn default() -> Self {
         Self {
             provider: TranscriptionProvider::Local,
+            fallback_providers: Vec::new(),
             language: "auto".to_string(),
             local: LocalTranscriptionSettings::default(),
             groq: GroqSettings::default(),
+            deepgram: DeepgramSettings::default(),
+            custom: CustomEndpointSettings::default(),
+            retry: RetrySettings::default(),
+            prefetch: PrefetchMode::default(),
+            translate: false,
+            initial_prompt: String::new(),
+            vocabulary: Vec::new(),
+            quality_preset: QualityPreset::Custom,
+            language_learning: false,
+            live_partials: false,
+            partial_interval_ms: 1500,
+            adaptive_partials: true,
+            partial_interval_min_ms: 1000,
+            partial_interval_max_ms: 5000,
         }
     }
 }
@@ -213,9 +789,41 @@ impl TranscriptionSettings {
     pub const MAX_THREADS: usize = 64;
     /// Maximum Groq timeout (5 minutes)
     pub const MAX_GROQ_TIMEOUT: u32 = 300;
+    /// Shortest and longest audio between partial transcriptions (ms)
+    pub const PARTIAL_INTERVAL_RANGE: std::ops::RangeInclusive<u32> = 500..=10_000;
+    /// Longest prompt sent to the models (Whisper reads about 224 tokens)
+    pub const MAX_PROMPT_CHARS: usize = 800;
+    /// Longest vocabulary word or phrase
+    pub const MAX_VOCABULARY_WORD_CHARS: usize = 50;
 
     /// Validate transcription settings
     pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(1..=LocalTranscriptionSettings::MAX_BEAM_SIZE).contains(&self.local.beam_size) {
+            return Err(SettingsError::InvalidValue(format!(
+                "beam_size must be between 1 and {}",
+                LocalTranscriptionSettings::MAX_BEAM_SIZE
+            )));
+        }
+        if let Some(id) = &self.local.custom_model {
+            if !LocalTranscriptionSettings::is_valid_custom_id(id) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "invalid custom model id '{}'",
+                    id
+                )));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.local.temperature) {
+            return Err(SettingsError::InvalidValue(format!(
+                "temperature {} must be between 0.0 and 1.0",
+                self.local.temperature
+            )));
+        }
+        if !(0.0..=1.0).contains(&self.local.no_speech_threshold) {
+            return Err(SettingsError::InvalidValue(format!(
+                "no_speech_threshold {} must be between 0.0 and 1.0",
+                self.local.no_speech_threshold
+            )));
+        }
         if self.local.threads > Self::MAX_THREADS {
             return Err(SettingsError::InvalidValue(format!(
                 "threads {} exceeds limit of {}",
@@ -235,29 +843,250 @@ impl TranscriptionSettings {
                 Self::MAX_GROQ_TIMEOUT
             )));
         }
+        if !(1..=Self::MAX_GROQ_TIMEOUT).contains(&self.deepgram.timeout_seconds) {
+            return Err(SettingsError::InvalidValue(format!(
+                "deepgram timeout_seconds must be between 1 and {} seconds",
+                Self::MAX_GROQ_TIMEOUT
+            )));
+        }
+        if self.provider == TranscriptionProvider::Deepgram && self.translate {
+            return Err(SettingsError::InvalidValue(
+                "translation is not available with Deepgram".to_string(),
+            ));
+        }
+        if self.language_learning && self.translation_chain().is_empty() {
+            return Err(SettingsError::InvalidValue(
+                "language learning needs a provider that can translate".to_string(),
+            ));
+        }
+        if !(1..=Self::MAX_GROQ_TIMEOUT).contains(&self.custom.timeout_seconds) {
+            return Err(SettingsError::InvalidValue(format!(
+                "custom endpoint timeout_seconds must be between 1 and {} seconds",
+                Self::MAX_GROQ_TIMEOUT
+            )));
+        }
+        if !self.custom.base_url.is_empty() || self.provider == TranscriptionProvider::Custom {
+            self.custom.validate_base_url()?;
+        }
+        self.retry.validate()?;
+        for (i, provider) in self.fallback_providers.iter().enumerate() {
+            if self.fallback_providers[..i].contains(provider) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "fallback provider {:?} is listed twice",
+                    provider
+                )));
+            }
+        }
+        if !Self::PARTIAL_INTERVAL_RANGE.contains(&self.partial_interval_ms) {
+            return Err(SettingsError::InvalidValue(format!(
+                "partial_interval_ms {} must be between {} and {}",
+                self.partial_interval_ms,
+                Self::PARTIAL_INTERVAL_RANGE.start(),
+                Self::PARTIAL_INTERVAL_RANGE.end()
+            )));
+        }
+        let bounds = [self.partial_interval_min_ms, self.partial_interval_max_ms];
+        if !bounds.iter().all(|ms| Self::PARTIAL_INTERVAL_RANGE.contains(ms)) {
+            return Err(SettingsError::InvalidValue(format!(
+                "adaptive partial interval bounds must be between {} and {}",
+                Self::PARTIAL_INTERVAL_RANGE.start(),
+                Self::PARTIAL_INTERVAL_RANGE.end()
+            )));
+        }
+        if self.partial_interval_min_ms > self.partial_interval_max_ms {
+            return Err(SettingsError::InvalidValue(
+                "partial_interval_min_ms cannot exceed partial_interval_max_ms".to_string(),
+            ));
+        }
+        if self.initial_prompt.chars().count() > Self::MAX_PROMPT_CHARS {
+            return Err(SettingsError::InvalidValue(format!(
+                "initial prompt is longer than {} characters",
+                Self::MAX_PROMPT_CHARS
+            )));
+        }
+        if let Some(word) = self
+            .vocabulary
+            .iter()
+            .find(|word| word.chars().count() > Self::MAX_VOCABULARY_WORD_CHARS)
+        {
+            return Err(SettingsError::InvalidValue(format!(
+                "vocabulary entry '{}' is longer than {} characters",
+                word,
+                Self::MAX_VOCABULARY_WORD_CHARS
+            )));
+        }
         Ok(())
     }
 
     /// Sanitize transcription settings
     pub fn sanitize(&mut self) {
         self.local.threads = self.local.threads.min(Self::MAX_THREADS);
+        self.local.beam_size = self.local.beam_size.clamp(1, LocalTranscriptionSettings::MAX_BEAM_SIZE);
+        if !self
+            .local
+            .custom_model
+            .as_deref()
+            .is_none_or(LocalTranscriptionSettings::is_valid_custom_id)
+        {
+            self.local.custom_model = None;
+        }
+        self.local.temperature = if self.local.temperature.is_finite() {
+            self.local.temperature.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.local.no_speech_threshold = if self.local.no_speech_threshold.is_finite() {
+            self.local.no_speech_threshold.clamp(0.0, 1.0)
+        } else {
+            0.6
+        };
         if self.groq.timeout_seconds == 0 {
             self.groq.timeout_seconds = 30; // Reset to default
         }
         self.groq.timeout_seconds = self.groq.timeout_seconds.min(Self::MAX_GROQ_TIMEOUT);
+        self.deepgram.timeout_seconds = self.deepgram.timeout_seconds.clamp(1, Self::MAX_GROQ_TIMEOUT);
+        if self.provider == TranscriptionProvider::Deepgram {
+            self.translate = false;
+        }
+        self.custom.timeout_seconds = self.custom.timeout_seconds.clamp(1, Self::MAX_GROQ_TIMEOUT);
+        self.custom.base_url = self.custom.base_url.trim().trim_end_matches('/').to_string();
+        self.retry.sanitize();
+        let mut fallbacks = Vec::new();
+        for provider in self.fallback_providers.drain(..) {
+            if !fallbacks.contains(&provider) {
+                fallbacks.push(provider);
+            }
+        }
+        self.fallback_providers = fallbacks;
+        if self.translation_chain().is_empty() {
+            self.language_learning = false;
+        }
+        let (min, max) = (
+            *Self::PARTIAL_INTERVAL_RANGE.start(),
+            *Self::PARTIAL_INTERVAL_RANGE.end(),
+        );
+        self.partial_interval_ms = self.partial_interval_ms.clamp(min, max);
+        self.partial_interval_min_ms = self.partial_interval_min_ms.clamp(min, max);
+        self.partial_interval_max_ms = self.partial_interval_max_ms.clamp(min, max);
+        if self.partial_interval_min_ms > self.partial_interval_max_ms {
+            std::mem::swap(&mut self.partial_interval_min_ms, &mut self.partial_interval_max_ms);
+        }
+        self.initial_prompt = self
+            .initial_prompt
+            .trim()
+            .chars()
+            .filter(|c| !c.is_control() || *c == '\n')
+            .take(Self::MAX_PROMPT_CHARS)
+            .collect();
+        let mut vocabulary: Vec<String> = Vec::new();
+        for word in self.vocabulary.drain(..) {
+            let word: String = word
+                .trim()
+                .chars()
+                .filter(|c| !c.is_control())
+                .take(Self::MAX_VOCABULARY_WORD_CHARS)
+                .collect();
+            if !word.is_empty() && !vocabulary.iter().any(|w| w.eq_ignore_ascii_case(&word)) {
+                vocabulary.push(word);
+            }
+        }
+        self.vocabulary = vocabulary;
+    }
+
+    /// Providers to try in order: the active one, then the fallbacks
+    ///
+    /// Duplicates of the active provider are skipped, as is Deepgram when
+    /// translating (it cannot translate).
+    pub fn provider_chain(&self) -> Vec<TranscriptionProvider> {
+        let mut chain = vec![self.provider.clone()];
+        for provider in &self.fallback_providers {
+            let unsupported = self.translate && *provider == TranscriptionProvider::Deepgram;
+            if !chain.contains(provider) && !unsupported {
+                chain.push(provider.clone());
+            }
+        }
+        chain
+    }
+
+    /// Providers to try for an English translation: `provider_chain` without
+    /// Deepgram
+    pub fn translation_chain(&self) -> Vec<TranscriptionProvider> {
+        self.provider_chain()
+            .into_iter()
+            .filter(|provider| *provider != TranscriptionProvider::Deepgram)
+            .collect()
+    }
+
+    /// The vocabulary words, trimmed and without duplicates
+    pub fn vocabulary_terms(&self) -> Vec<String> {
+        let mut terms: Vec<String> = Vec::new();
+        for term in &self.vocabulary {
+            let term = term.trim();
+            if !term.is_empty() && !terms.iter().any(|t| t.eq_ignore_ascii_case(term)) {
+                terms.push(term.to_string());
+            }
+        }
+        terms
+    }
+
+    /// Prompt biasing recognition: the initial prompt, then as many of
+    /// `terms` as fit in `MAX_PROMPT_CHARS` (None when both are empty)
+    pub fn recognition_prompt(&self, terms: &[String]) -> Option<String> {
+        let mut prompt: String = self
+            .initial_prompt
+            .trim()
+            .chars()
+            .take(Self::MAX_PROMPT_CHARS)
+            .collect();
+        let mut length = prompt.chars().count();
+        let mut words = Vec::new();
+        for term in terms {
+            // Separator and closing period included
+            let needed = term.chars().count() + 2;
+            if length + needed > Self::MAX_PROMPT_CHARS {
+                break;
+            }
+            length += needed;
+            words.push(term.as_str());
+        }
+
+        if !words.is_empty() {
+            if !prompt.is_empty() {
+                prompt.push(' ');
+            }
+            prompt.push_str(&words.join(", "));
+            prompt.push('.');
+        }
+        (!prompt.is_empty()).then_some(prompt)
     }
 }
 
 /// Transcription provider selection
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum TranscriptionProvider {
     Local,
     Groq,
+    Deepgram,
+    /// Self-hosted server with an OpenAI-compatible transcription endpoint
+    Custom,
+}
+
+/// How eagerly to warm up the provider before dictation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PrefetchMode {
+    /// Never warm up ahead of a recording
+    Off,
+    /// Only on strong signals (recording shortcut modifier held)
+    #[default]
+    Conservative,
+    /// On any signal, including focus moving to a text field (Windows)
+    Aggressive,
 }
 
 /// GPU backend selection for whisper acceleration
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum GpuBackend {
     /// CPU only (no GPU acceleration)
@@ -295,13 +1124,26 @@ impl GpuBackend {
 }
 
 /// Local whisper.cpp settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct LocalTranscriptionSettings {
     /// Whisper model size
     pub model: WhisperModel,
     /// Model quantization level (F16, Q8_0, Q5_1)
     pub quantization: ModelQuantization,
+    /// Download the requested quantization when only another one is on
+    /// disk (that one is used meanwhile)
+    pub download_missing_quantization: bool,
+    /// Imported model used instead of `model` (id from the custom model list)
+    pub custom_model: Option<String>,
+    /// Candidates kept while decoding (1 = greedy, faster; more = beam
+    /// search, more accurate)
+    pub beam_size: u8,
+    /// Sampling temperature of the first decoding pass (0 = always the most
+    /// likely token; whisper.cpp raises it on its own when a pass fails)
+    pub temperature: f32,
+    /// No-speech probability above which a segment is dropped as silence
+    pub no_speech_threshold: f32,
     /// Number of CPU threads (0 = auto-detect optimal)
     pub threads: usize,
     /// Enable GPU acceleration
@@ -315,6 +1157,11 @@ impl Default for LocalTranscriptionSettings {
         Self {
             model: WhisperModel::Small,
             quantization: ModelQuantization::F16,
+            download_missing_quantization: false,
+            custom_model: None,
+            beam_size: 1,
+            temperature: 0.0,
+            no_speech_threshold: 0.6,
             threads: 0, // Auto-detect
             gpu_enabled: false,
             gpu_backend: GpuBackend::Cpu,
@@ -323,9 +1170,39 @@ impl Default for LocalTranscriptionSettings {
 }
 
 impl LocalTranscriptionSettings {
-    /// Get the full model filename including quantization
+    /// Largest beam search width
+    pub const MAX_BEAM_SIZE: u8 = 8;
+
+    /// Get the full model filename including quantization, or the file of
+    /// the imported model
     pub fn model_filename(&self) -> String {
-        self.model.filename_with_quantization(&self.quantization)
+        match &self.custom_model {
+            Some(id) => Self::custom_model_filename(id),
+            None => self.model.filename_with_quantization(&self.quantization),
+        }
+    }
+
+    /// Whether `id` can name an imported model: lowercase letters, digits
+    /// and dashes, so it is safe in a file name
+    pub fn is_valid_custom_id(id: &str) -> bool {
+        !id.is_empty()
+            && id.len() <= 64
+            && id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    }
+
+    /// File name of the imported model `id`
+    pub fn custom_model_filename(id: &str) -> String {
+        format!("custom-{}.bin", id)
+    }
+
+    /// Name of the model in status and metrics
+    pub fn model_name(&self) -> String {
+        match &self.custom_model {
+            Some(id) => format!("custom-{}", id),
+            None => format!("{:?}", self.model).to_lowercase(),
+        }
     }
 
     /// Get estimated model size in bytes
@@ -335,7 +1212,7 @@ impl LocalTranscriptionSettings {
 }
 
 /// Quantization type for Whisper models
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum ModelQuantization {
     /// Full precision (f16) - highest quality, largest size
@@ -381,8 +1258,106 @@ impl ModelQuantization {
     }
 }
 
+/// Speed vs accuracy setting picked with one switch
+///
+/// A preset sets the model size, quantization, VAD aggressiveness, beam size
+/// and GPU use; changing any of those by hand makes it `Custom`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityPreset {
+    Fast,
+    Balanced,
+    Accurate,
+    /// Every setting chosen by hand
+    #[default]
+    Custom,
+}
+
+/// Values a quality preset sets
+struct PresetValues {
+    model: WhisperModel,
+    quantization: ModelQuantization,
+    vad_aggressiveness: u8,
+    beam_size: u8,
+}
+
+impl QualityPreset {
+    /// Presets with their own values, in menu order
+    pub const NAMED: [QualityPreset; 3] = [Self::Fast, Self::Balanced, Self::Accurate];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Fast => "Fast",
+            Self::Balanced => "Balanced",
+            Self::Accurate => "Accurate",
+            Self::Custom => "Custom",
+        }
+    }
+
+    fn values(&self) -> Option<PresetValues> {
+        let (model, quantization, vad_aggressiveness, beam_size) = match self {
+            Self::Fast => (WhisperModel::Base, ModelQuantization::Q5_1, 3, 1),
+            Self::Balanced => (WhisperModel::Small, ModelQuantization::Q8_0, 2, 1),
+            Self::Accurate => (WhisperModel::Medium, ModelQuantization::F16, 1, 5),
+            Self::Custom => return None,
+        };
+        Some(PresetValues {
+            model,
+            quantization,
+            vad_aggressiveness,
+            beam_size,
+        })
+    }
+
+    /// GPU backend presets use: the best one in this build, if any
+    fn gpu_backend() -> GpuBackend {
+        [GpuBackend::Cuda, GpuBackend::Vulkan]
+            .into_iter()
+            .find(GpuBackend::is_available)
+            .unwrap_or(GpuBackend::Cpu)
+    }
+
+    /// Model and quantization this preset selects (None for `Custom`)
+    pub fn model(&self) -> Option<(WhisperModel, ModelQuantization)> {
+        let values = self.values()?;
+        Some((values.model, values.quantization))
+    }
+
+    /// Set this preset's values in `settings` (`Custom` changes nothing)
+    pub fn apply(&self, settings: &mut Settings) {
+        let Some(values) = self.values() else {
+            return;
+        };
+        let local = &mut settings.transcription.local;
+        local.model = values.model;
+        local.custom_model = None;
+        local.quantization = values.quantization;
+        local.beam_size = values.beam_size;
+        local.gpu_backend = Self::gpu_backend();
+        local.gpu_enabled = local.gpu_backend != GpuBackend::Cpu;
+        settings.audio.vad.aggressiveness = values.vad_aggressiveness;
+        settings.transcription.quality_preset = *self;
+    }
+
+    /// Whether `settings` still hold this preset's values
+    pub fn matches(&self, settings: &Settings) -> bool {
+        let Some(values) = self.values() else {
+            return true;
+        };
+        let local = &settings.transcription.local;
+        let backend = Self::gpu_backend();
+        local.model == values.model
+            && local.custom_model.is_none()
+            && local.quantization == values.quantization
+            && local.beam_size == values.beam_size
+            && settings.audio.vad.aggressiveness == values.vad_aggressiveness
+            && local.gpu_enabled == (backend != GpuBackend::Cpu)
+            && (!local.gpu_enabled || local.gpu_backend == backend)
+    }
+}
+
 /// Whisper model sizes
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum WhisperModel {
     Tiny,
@@ -392,6 +1367,21 @@ pub enum WhisperModel {
     Large,
 }
 
+impl std::str::FromStr for WhisperModel {
+    type Err = String;
+
+    fn from_str(model: &str) -> Result<Self, Self::Err> {
+        match model.to_lowercase().as_str() {
+            "tiny" => Ok(Self::Tiny),
+            "base" => Ok(Self::Base),
+            "small" => Ok(Self::Small),
+            "medium" => Ok(Self::Medium),
+            "large" => Ok(Self::Large),
+            _ => Err(format!("Unknown model: {}", model)),
+        }
+    }
+}
+
 impl WhisperModel {
     /// Get model filename (for F16/standard model)
     pub fn filename(&self) -> &'static str {
@@ -443,44 +1433,129 @@ impl WhisperModel {
         }
     }
 
+    /// Next smaller (and faster) model, if any
+    pub fn smaller(&self) -> Option<WhisperModel> {
+        match self {
+            Self::Tiny => None,
+            Self::Base => Some(Self::Tiny),
+            Self::Small => Some(Self::Base),
+            Self::Medium => Some(Self::Small),
+            Self::Large => Some(Self::Medium),
+        }
+    }
+
     /// Get all model sizes
     pub fn all() -> &'static [WhisperModel] {
         &[Self::Tiny, Self::Base, Self::Small, Self::Medium, Self::Large]
     }
 }
 
-/// Groq API settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Retry policy of cloud providers (exponential backoff with jitter)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
-pub struct GroqSettings {
-    /// Whether an API key is configured (actual key stored in Windows Credential Manager)
-    #[serde(default)]
-    pub api_key_configured: bool,
-    /// Model identifier
-    pub model: String,
-    /// Request timeout in seconds (default: 30)
-    pub timeout_seconds: u32,
+pub struct RetrySettings {
+    /// Retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further one
+    pub base_delay_ms: u32,
+    /// Longest delay between attempts; rate limits asking for longer fail
+    pub max_delay_ms: u32,
+    /// Share of each delay that is randomized, in percent
+    pub jitter_percent: u32,
 }
 
-impl Default for GroqSettings {
+impl Default for RetrySettings {
     fn default() -> Self {
         Self {
-            api_key_configured: false,
-            model: "whisper-large-v3".to_string(),
-            timeout_seconds: 30,
+            max_retries: 3,
+            base_delay_ms: 1000,
+            max_delay_ms: 30_000,
+            jitter_percent: 20,
         }
     }
 }
 
-impl GroqSettings {
-    /// Get the API key from secure storage
-    pub fn get_api_key(&self) -> Option<String> {
-        super::SecretsManager::get_groq_api_key().ok()
-    }
+impl RetrySettings {
+    /// Most retries allowed
+    pub const MAX_RETRIES: u32 = 10;
+    /// Shortest and longest delays allowed (ms)
+    pub const DELAY_RANGE: std::ops::RangeInclusive<u32> = 100..=300_000;
 
-    /// Set the API key in secure storage
-    pub fn set_api_key(&mut self, api_key: &str) -> Result<(), super::SecretsError> {
-        super::SecretsManager::set_groq_api_key(api_key)?;
+    /// Validate retry settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.max_retries > Self::MAX_RETRIES {
+            return Err(SettingsError::InvalidValue(format!(
+                "retry max_retries {} exceeds limit of {}",
+                self.max_retries,
+                Self::MAX_RETRIES
+            )));
+        }
+        for delay in [self.base_delay_ms, self.max_delay_ms] {
+            if !Self::DELAY_RANGE.contains(&delay) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "retry delay {}ms must be between {} and {}",
+                    delay,
+                    Self::DELAY_RANGE.start(),
+                    Self::DELAY_RANGE.end()
+                )));
+            }
+        }
+        if self.max_delay_ms < self.base_delay_ms {
+            return Err(SettingsError::InvalidValue(
+                "retry max_delay_ms cannot be below base_delay_ms".to_string(),
+            ));
+        }
+        if self.jitter_percent > 100 {
+            return Err(SettingsError::InvalidValue(format!(
+                "retry jitter_percent {} exceeds 100",
+                self.jitter_percent
+            )));
+        }
+        Ok(())
+    }
+
+    /// Clamp retry settings to their limits
+    pub fn sanitize(&mut self) {
+        let (min, max) = (*Self::DELAY_RANGE.start(), *Self::DELAY_RANGE.end());
+        self.max_retries = self.max_retries.min(Self::MAX_RETRIES);
+        self.base_delay_ms = self.base_delay_ms.clamp(min, max);
+        self.max_delay_ms = self.max_delay_ms.clamp(self.base_delay_ms, max);
+        self.jitter_percent = self.jitter_percent.min(100);
+    }
+}
+
+/// Groq API settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct GroqSettings {
+    /// Whether an API key is configured (actual key stored in Windows Credential Manager)
+    #[serde(default)]
+    pub api_key_configured: bool,
+    /// Model identifier
+    pub model: String,
+    /// Request timeout in seconds (default: 30)
+    pub timeout_seconds: u32,
+}
+
+impl Default for GroqSettings {
+    fn default() -> Self {
+        Self {
+            api_key_configured: false,
+            model: "whisper-large-v3".to_string(),
+            timeout_seconds: 30,
+        }
+    }
+}
+
+impl GroqSettings {
+    /// Get the API key from secure storage
+    pub fn get_api_key(&self) -> Option<String> {
+        super::SecretsManager::get_groq_api_key().ok()
+    }
+
+    /// Set the API key in secure storage
+    pub fn set_api_key(&mut self, api_key: &str) -> Result<(), super::SecretsError> {
+        super::SecretsManager::set_groq_api_key(api_key)?;
         self.api_key_configured = true;
         Ok(())
     }
@@ -498,14 +1573,123 @@ impl GroqSettings {
     }
 }
 
+/// Deepgram streaming API settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct DeepgramSettings {
+    /// Whether an API key is configured (actual key stored in the credential manager)
+    pub api_key_configured: bool,
+    /// Model identifier
+    pub model: String,
+    /// Seconds to wait for the final result (default: 30)
+    pub timeout_seconds: u32,
+}
+
+impl Default for DeepgramSettings {
+    fn default() -> Self {
+        Self {
+            api_key_configured: false,
+            model: "nova-2".to_string(),
+            timeout_seconds: 30,
+        }
+    }
+}
+
+impl DeepgramSettings {
+    /// Get the API key from secure storage
+    pub fn get_api_key(&self) -> Option<String> {
+        super::SecretsManager::get_deepgram_api_key().ok()
+    }
+
+    /// Set the API key in secure storage
+    pub fn set_api_key(&mut self, api_key: &str) -> Result<(), super::SecretsError> {
+        super::SecretsManager::set_deepgram_api_key(api_key)?;
+        self.api_key_configured = true;
+        Ok(())
+    }
+
+    /// Remove the API key from secure storage
+    pub fn clear_api_key(&mut self) -> Result<(), super::SecretsError> {
+        let _ = super::SecretsManager::delete_deepgram_api_key();
+        self.api_key_configured = false;
+        Ok(())
+    }
+
+    /// Check if API key is available
+    pub fn has_api_key(&self) -> bool {
+        self.api_key_configured && super::SecretsManager::has_deepgram_api_key()
+    }
+}
+
+/// Self-hosted OpenAI-compatible server settings
+///
+/// For servers such as faster-whisper-server, LocalAI or Speaches that
+/// expose `/v1/audio/transcriptions`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CustomEndpointSettings {
+    /// Server URL, e.g. "http://localhost:8000" (with or without "/v1")
+    pub base_url: String,
+    /// Whether a bearer token is configured (actual token stored in the credential manager)
+    pub token_configured: bool,
+    /// Model name sent with each request
+    pub model: String,
+    /// Request timeout in seconds (default: 60)
+    pub timeout_seconds: u32,
+}
+
+impl Default for CustomEndpointSettings {
+    fn default() -> Self {
+        Self {
+            base_url: String::new(),
+            token_configured: false,
+            model: "whisper-1".to_string(),
+            timeout_seconds: 60,
+        }
+    }
+}
+
+impl CustomEndpointSettings {
+    /// Get the bearer token from secure storage (None when not configured)
+    pub fn get_token(&self) -> Option<String> {
+        if !self.token_configured {
+            return None;
+        }
+        super::SecretsManager::get_custom_endpoint_token().ok()
+    }
+
+    /// Check that the base URL is an http(s) URL
+    pub fn validate_base_url(&self) -> Result<(), SettingsError> {
+        let url = reqwest::Url::parse(self.base_url.trim()).map_err(|e| {
+            SettingsError::InvalidValue(format!("custom endpoint URL is invalid: {}", e))
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(SettingsError::InvalidValue(
+                "custom endpoint URL must start with http:// or https://".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
 /// Audio input settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct AudioSettings {
     /// Input device ID (None = default)
     pub input_device: Option<String>,
     /// Voice Activity Detection settings
     pub vad: VadSettings,
+    /// Processing stages between capture and text, in order
+    pub pipeline: Vec<PipelineStageSettings>,
+    /// Drop speech from voices other than the enrolled one
+    pub speaker_filter: SpeakerFilterSettings,
+    /// Device buffer mode (low latency helps streaming)
+    pub capture_mode: CaptureMode,
+    /// What is recorded: the input device or the system audio output
+    pub source: AudioSource,
+    /// Algorithm used by the noise suppression stage
+    pub noise_suppressor: NoiseSuppressor,
 }
 
 impl Default for AudioSettings {
@@ -513,47 +1697,344 @@ impl Default for AudioSettings {
         Self {
             input_device: None,
             vad: VadSettings::default(),
+            pipeline: PipelineStage::default_pipeline(),
+            speaker_filter: SpeakerFilterSettings::default(),
+            capture_mode: CaptureMode::default(),
+            source: AudioSource::default(),
+            noise_suppressor: NoiseSuppressor::default(),
         }
     }
 }
 
+/// How the input device buffers audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum CaptureMode {
+    /// The device's default buffer
+    #[default]
+    Shared,
+    /// A ~10ms device buffer, falling back to shared mode when refused
+    LowLatency,
+}
+
+/// What a recording captures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum AudioSource {
+    /// The configured input device
+    #[default]
+    Microphone,
+    /// What the computer plays, e.g. the other side of a call (WASAPI
+    /// loopback on Windows, a monitor or loopback input device elsewhere)
+    SystemAudio,
+}
+
+/// Algorithm of the noise suppression stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoiseSuppressor {
+    /// Rumble filter and attenuation of frames at the noise floor
+    #[default]
+    Filter,
+    /// RNNoise neural network, better at steady noise such as fans and
+    /// keyboards but slower
+    Rnnoise,
+}
+
 impl AudioSettings {
     /// Validate audio settings
     pub fn validate(&self) -> Result<(), SettingsError> {
-        self.vad.validate()
+        self.vad.validate()?;
+        self.speaker_filter.validate()?;
+        PipelineStage::validate_pipeline(&self.pipeline)
     }
 
     /// Sanitize audio settings
+    ///
+    /// Pipelines saved before a stage existed get it added with its default
+    /// state; pipelines that are still invalid are reset.
     pub fn sanitize(&mut self) {
         self.vad.sanitize();
+        self.speaker_filter.sanitize();
+        PipelineStage::add_missing_stages(&mut self.pipeline);
+        if PipelineStage::validate_pipeline(&self.pipeline).is_err() {
+            self.pipeline = PipelineStage::default_pipeline();
+        }
+    }
+
+    /// Enabled pipeline stages, in order
+    pub fn enabled_stages(&self) -> Vec<PipelineStage> {
+        self.pipeline
+            .iter()
+            .filter(|s| s.enabled)
+            .map(|s| s.stage)
+            .collect()
+    }
+}
+
+/// Stage of the audio to text pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    /// Peak normalization
+    Gain,
+    /// Noise removal with the configured `NoiseSuppressor`
+    NoiseSuppression,
+    /// Calibrated noise gate and removal of non-speech segments
+    Vad,
+    /// Removal of segments spoken by someone other than the enrolled voice
+    SpeakerFilter,
+    /// Conversion to the 16 kHz sample rate Whisper expects
+    Resample,
+    /// Speech to text
+    Transcribe,
+    /// Vocabulary rules and learned capitalization
+    PostProcess,
+}
+
+/// One configured pipeline stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct PipelineStageSettings {
+    pub stage: PipelineStage,
+    pub enabled: bool,
+}
+
+impl PipelineStage {
+    /// Every stage, in default order
+    pub const ALL: [PipelineStage; 7] = [
+        Self::Resample,
+        Self::Gain,
+        Self::NoiseSuppression,
+        Self::Vad,
+        Self::SpeakerFilter,
+        Self::Transcribe,
+        Self::PostProcess,
+    ];
+
+    /// Stages that can't be disabled
+    pub fn is_required(self) -> bool {
+        matches!(self, Self::Resample | Self::Transcribe | Self::PostProcess)
+    }
+
+    /// Stages working on audio (as opposed to text)
+    pub fn is_audio(self) -> bool {
+        !matches!(self, Self::Transcribe | Self::PostProcess)
+    }
+
+    /// Whether the stage is enabled in the default pipeline
+    ///
+    /// The speaker filter stage is enabled but only acts once turned on in
+    /// the speaker filter settings, like VAD.
+    pub fn enabled_by_default(self) -> bool {
+        !matches!(self, Self::Gain | Self::NoiseSuppression)
+    }
+
+    /// Default pipeline: gain and noise suppression off
+    pub fn default_pipeline() -> Vec<PipelineStageSettings> {
+        Self::ALL
+            .iter()
+            .map(|&stage| PipelineStageSettings {
+                stage,
+                enabled: stage.enabled_by_default(),
+            })
+            .collect()
+    }
+
+    /// Insert stages missing from a saved pipeline just before transcription
+    pub fn add_missing_stages(pipeline: &mut Vec<PipelineStageSettings>) {
+        for stage in Self::ALL {
+            if pipeline.iter().any(|s| s.stage == stage) {
+                continue;
+            }
+            let position = match stage.is_audio() {
+                true => pipeline
+                    .iter()
+                    .position(|s| s.stage == Self::Transcribe)
+                    .unwrap_or(pipeline.len()),
+                false => pipeline.len(),
+            };
+            pipeline.insert(
+                position,
+                PipelineStageSettings {
+                    stage,
+                    enabled: stage.enabled_by_default(),
+                },
+            );
+        }
+    }
+
+    /// Check a pipeline lists every stage once, in a workable order
+    ///
+    /// Audio stages may be reordered among themselves, except that VAD only
+    /// supports 16 kHz input and must follow resampling. Transcription comes
+    /// after every audio stage and post-processing comes last.
+    pub fn validate_pipeline(pipeline: &[PipelineStageSettings]) -> Result<(), SettingsError> {
+        let position = |stage: PipelineStage| pipeline.iter().position(|s| s.stage == stage);
+
+        for stage in Self::ALL {
+            let count = pipeline.iter().filter(|s| s.stage == stage).count();
+            if count != 1 {
+                return Err(SettingsError::InvalidValue(format!(
+                    "pipeline must list stage {:?} exactly once",
+                    stage
+                )));
+            }
+        }
+        if let Some(disabled) = pipeline.iter().find(|s| s.stage.is_required() && !s.enabled) {
+            return Err(SettingsError::InvalidValue(format!(
+                "pipeline stage {:?} cannot be disabled",
+                disabled.stage
+            )));
+        }
+        if position(Self::Vad) < position(Self::Resample) {
+            return Err(SettingsError::InvalidValue(
+                "VAD must run after resampling".to_string(),
+            ));
+        }
+        if position(Self::SpeakerFilter) < position(Self::Resample) {
+            return Err(SettingsError::InvalidValue(
+                "speaker filter must run after resampling".to_string(),
+            ));
+        }
+        let transcribe = position(Self::Transcribe);
+        if pipeline.iter().any(|s| s.stage.is_audio() && position(s.stage) > transcribe) {
+            return Err(SettingsError::InvalidValue(
+                "audio stages must run before transcription".to_string(),
+            ));
+        }
+        if position(Self::PostProcess) < transcribe {
+            return Err(SettingsError::InvalidValue(
+                "post-processing must run after transcription".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Speaker verification settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SpeakerFilterSettings {
+    /// Drop segments that don't match the voice profile
+    pub enabled: bool,
+    /// How closely a segment must match the profile (0.0 = lenient, 1.0 = strict)
+    pub sensitivity: f32,
+    /// Enrolled voice, recorded during onboarding or from the settings
+    pub profile: Option<VoiceProfile>,
+}
+
+impl Default for SpeakerFilterSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sensitivity: 0.5,
+            profile: None,
+        }
+    }
+}
+
+/// Enrolled voice of the user
+///
+/// The speaker embedding itself is kept in the credential store (see
+/// `SecretsManager::set_voice_profile`), not in the settings file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VoiceProfile {
+    /// When the profile was recorded (ISO 8601)
+    pub enrolled_at: String,
+}
+
+impl SpeakerFilterSettings {
+    /// Similarity threshold at sensitivity 0.0
+    const MIN_THRESHOLD: f32 = 0.3;
+    /// Similarity threshold at sensitivity 1.0
+    const MAX_THRESHOLD: f32 = 0.8;
+
+    /// Validate speaker filter settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(0.0..=1.0).contains(&self.sensitivity) {
+            return Err(SettingsError::InvalidValue(format!(
+                "speaker filter sensitivity {} must be between 0.0 and 1.0",
+                self.sensitivity
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sanitize speaker filter settings
+    pub fn sanitize(&mut self) {
+        self.sensitivity = if self.sensitivity.is_finite() {
+            self.sensitivity.clamp(0.0, 1.0)
+        } else {
+            0.5
+        };
+    }
+
+    /// Minimum profile similarity for a segment to be kept
+    pub fn similarity_threshold(&self) -> f32 {
+        Self::MIN_THRESHOLD + (Self::MAX_THRESHOLD - Self::MIN_THRESHOLD) * self.sensitivity
     }
 }
 
 /// Voice Activity Detection settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct VadSettings {
     /// Enable VAD filtering before transcription
     pub enabled: bool,
+    /// Detector classifying speech in recordings
+    pub engine: VadEngine,
     /// VAD aggressiveness (0-3, higher = more aggressive)
     pub aggressiveness: u8,
     /// Minimum speech segment duration in ms
     pub min_speech_duration_ms: u32,
     /// Padding around speech segments in ms
     pub padding_ms: u32,
+    /// Cut trailing silence as soon as recording stops, using the live VAD
+    pub trim_on_stop: bool,
+    /// Per-device calibration results, keyed by input device ID ("default" for the system default)
+    pub calibrations: BTreeMap<String, VadCalibration>,
 }
 
 impl Default for VadSettings {
     fn default() -> Self {
         Self {
             enabled: true,  // Enable by default for performance
+            engine: VadEngine::default(),
             aggressiveness: 2, // Aggressive mode
             min_speech_duration_ms: 100,
             padding_ms: 300,
+            trim_on_stop: true,
+            calibrations: BTreeMap::new(),
         }
     }
 }
 
+/// Voice activity detector implementation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum VadEngine {
+    /// WebRTC VAD, fast and built in
+    #[default]
+    #[serde(rename = "webrtc")]
+    WebRtc,
+    /// Silero VAD neural network, better with music and soft speech (the
+    /// model is downloaded when selected)
+    Silero,
+}
+
+/// VAD values measured by the calibration wizard for one input device
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VadCalibration {
+    /// VAD aggressiveness (0-3)
+    pub aggressiveness: u8,
+    /// Minimum speech segment duration in ms
+    pub min_speech_duration_ms: u32,
+    /// Recordings whose loudest window stays below this level (dBFS) are treated as silence
+    pub energy_threshold_db: f32,
+    /// Measured ambient noise floor (dBFS)
+    pub noise_floor_db: f32,
+}
+
 impl VadSettings {
     /// Maximum VAD aggressiveness (webrtc-vad limit)
     pub const MAX_AGGRESSIVENESS: u8 = 3;
@@ -593,282 +2074,2609 @@ impl VadSettings {
         self.aggressiveness = self.aggressiveness.min(Self::MAX_AGGRESSIVENESS);
         self.min_speech_duration_ms = self.min_speech_duration_ms.min(Self::MAX_MIN_SPEECH_DURATION_MS);
         self.padding_ms = self.padding_ms.min(Self::MAX_PADDING_MS);
+        for calibration in self.calibrations.values_mut() {
+            calibration.aggressiveness = calibration.aggressiveness.min(Self::MAX_AGGRESSIVENESS);
+            calibration.min_speech_duration_ms = calibration
+                .min_speech_duration_ms
+                .min(Self::MAX_MIN_SPEECH_DURATION_MS);
+        }
+    }
+
+    /// Calibration key for an input device (None = system default)
+    pub fn device_key(device: Option<&str>) -> String {
+        device.unwrap_or("default").to_string()
+    }
+
+    /// Get the calibration stored for an input device
+    pub fn calibration_for(&self, device: Option<&str>) -> Option<&VadCalibration> {
+        self.calibrations.get(&Self::device_key(device))
+    }
+
+    /// Effective VAD settings for a device, with calibrated values applied
+    pub fn effective_for(&self, device: Option<&str>) -> VadSettings {
+        let mut effective = self.clone();
+        if let Some(calibration) = self.calibration_for(device) {
+            effective.aggressiveness = calibration.aggressiveness;
+            effective.min_speech_duration_ms = calibration.min_speech_duration_ms;
+        }
+        effective
     }
 }
 
-/// Output behavior settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Id of the workspace using the top-level data directory
+pub const DEFAULT_WORKSPACE_ID: &str = "default";
+
+/// A separate context with its own history, vocabulary and output settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Workspace {
+    /// Stable identifier, also the name of the workspace's data directory
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Output settings of the workspace while it is inactive (the active
+    /// workspace uses `Settings::output`; None = inherit on first switch)
+    #[serde(default)]
+    pub output: Option<OutputSettings>,
+}
+
+/// Workspace list and the active workspace
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
-pub struct OutputSettings {
-    /// Auto-capitalize first letter
-    pub auto_capitalize: bool,
-    /// Add punctuation automatically
-    pub auto_punctuation: bool,
-    /// Delay before paste (milliseconds)
-    pub paste_delay: u32,
+pub struct WorkspaceSettings {
+    /// Id of the active workspace
+    pub active: String,
+    pub list: Vec<Workspace>,
 }
 
-impl Default for OutputSettings {
+impl Default for WorkspaceSettings {
     fn default() -> Self {
         Self {
-            auto_capitalize: true,
-            auto_punctuation: true,
-            paste_delay: 50,
+            active: DEFAULT_WORKSPACE_ID.to_string(),
+            list: vec![Workspace {
+                id: DEFAULT_WORKSPACE_ID.to_string(),
+                name: "Default".to_string(),
+                output: None,
+            }],
         }
     }
 }
 
-impl OutputSettings {
-    /// Maximum paste delay (2 seconds)
-    pub const MAX_PASTE_DELAY: u32 = 2000;
+impl WorkspaceSettings {
+    /// Maximum number of workspaces
+    pub const MAX_WORKSPACES: usize = 16;
 
-    /// Validate output settings
+    /// Maximum length of a workspace name
+    pub const MAX_NAME_LEN: usize = 40;
+
+    /// Find a workspace by id
+    pub fn get(&self, id: &str) -> Option<&Workspace> {
+        self.list.iter().find(|w| w.id == id)
+    }
+
+    /// Validate workspace settings
     pub fn validate(&self) -> Result<(), SettingsError> {
-        if self.paste_delay > Self::MAX_PASTE_DELAY {
+        if self.get(DEFAULT_WORKSPACE_ID).is_none() {
+            return Err(SettingsError::InvalidValue(
+                "the default workspace is missing".to_string(),
+            ));
+        }
+        if self.get(&self.active).is_none() {
             return Err(SettingsError::InvalidValue(format!(
-                "paste_delay {} exceeds limit of {} ms",
-                self.paste_delay,
-                Self::MAX_PASTE_DELAY
+                "active workspace '{}' does not exist",
+                self.active
+            )));
+        }
+        if self.list.len() > Self::MAX_WORKSPACES {
+            return Err(SettingsError::InvalidValue(format!(
+                "at most {} workspaces are allowed",
+                Self::MAX_WORKSPACES
+            )));
+        }
+        for (i, workspace) in self.list.iter().enumerate() {
+            if !Self::is_valid_id(&workspace.id) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "invalid workspace id '{}'",
+                    workspace.id
+                )));
+            }
+            Self::validate_name(&workspace.name)?;
+            if self.list[..i].iter().any(|w| w.id == workspace.id) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "duplicate workspace id '{}'",
+                    workspace.id
+                )));
+            }
+            if let Some(output) = &workspace.output {
+                output.validate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Ids are lowercase ASCII letters, digits and dashes (used as directory names)
+    fn is_valid_id(id: &str) -> bool {
+        !id.is_empty()
+            && id.len() <= Self::MAX_NAME_LEN
+            && id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    }
+
+    fn validate_name(name: &str) -> Result<(), SettingsError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > Self::MAX_NAME_LEN {
+            return Err(SettingsError::InvalidValue(format!(
+                "workspace name must be 1 to {} characters",
+                Self::MAX_NAME_LEN
             )));
         }
         Ok(())
     }
 
-    /// Sanitize output settings
+    /// Sanitize workspace settings: drop invalid and duplicate workspaces,
+    /// restore the default one and fall back to it if the active one is gone
     pub fn sanitize(&mut self) {
-        self.paste_delay = self.paste_delay.min(Self::MAX_PASTE_DELAY);
+        let mut list: Vec<Workspace> = Vec::new();
+        for workspace in &self.list {
+            if Self::is_valid_id(&workspace.id)
+                && Self::validate_name(&workspace.name).is_ok()
+                && !list.iter().any(|w| w.id == workspace.id)
+            {
+                let mut workspace = workspace.clone();
+                if let Some(output) = workspace.output.as_mut() {
+                    output.sanitize();
+                }
+                list.push(workspace);
+            }
+        }
+        if !list.iter().any(|w| w.id == DEFAULT_WORKSPACE_ID) {
+            list.insert(0, Self::default().list.remove(0));
+        }
+        list.truncate(Self::MAX_WORKSPACES);
+        if !list.iter().any(|w| w.id == self.active) {
+            self.active = DEFAULT_WORKSPACE_ID.to_string();
+        }
+        self.list = list;
     }
-}
 
-/// UI settings
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(default)]
-pub struct UiSettings {
-    /// Show recording indicator
-    pub show_indicator: bool,
-    /// Indicator position
-    pub indicator_position: IndicatorPosition,
-    /// Application theme
-    pub theme: Theme,
-    /// Start minimized to tray
-    pub start_minimized: bool,
-    /// Minimize to tray instead of taskbar
-    pub minimize_to_tray: bool,
+    /// Add a workspace, deriving its id from the name
+    pub fn add(&mut self, name: &str) -> Result<Workspace, SettingsError> {
+        let name = name.trim();
+        Self::validate_name(name)?;
+        if self.list.iter().any(|w| w.name.eq_ignore_ascii_case(name)) {
+            return Err(SettingsError::InvalidValue(format!(
+                "a workspace named '{}' already exists",
+                name
+            )));
+        }
+        if self.list.len() >= Self::MAX_WORKSPACES {
+            return Err(SettingsError::InvalidValue(format!(
+                "at most {} workspaces are allowed",
+                Self::MAX_WORKSPACES
+            )));
+        }
+
+        let mut base: String = name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        base = base.split('-').filter(|part| !part.is_empty()).collect::<Vec<_>>().join("-");
+        if base.is_empty() {
+            base = "workspace".to_string();
+        }
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while self.get(&id).is_some() {
+            id = format!("{}-{}", base, suffix);
+            suffix += 1;
+        }
+
+        let workspace = Workspace {
+            id,
+            name: name.to_string(),
+            output: None,
+        };
+        self.list.push(workspace.clone());
+        Ok(workspace)
+    }
+
+    /// Remove an inactive workspace (the default one can't be removed)
+    pub fn remove(&mut self, id: &str) -> Result<Workspace, SettingsError> {
+        if id == DEFAULT_WORKSPACE_ID {
+            return Err(SettingsError::InvalidValue(
+                "the default workspace can't be removed".to_string(),
+            ));
+        }
+        if id == self.active {
+            return Err(SettingsError::InvalidValue(
+                "switch to another workspace before removing this one".to_string(),
+            ));
+        }
+        let index = self
+            .list
+            .iter()
+            .position(|w| w.id == id)
+            .ok_or_else(|| SettingsError::InvalidValue(format!("unknown workspace '{}'", id)))?;
+        Ok(self.list.remove(index))
+    }
 }
 
-impl Default for UiSettings {
+/// Keyword alert settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct AlertSettings {
+    /// Words or phrases to watch for (case-insensitive, whole words)
+    pub keywords: Vec<String>,
+    /// Show a notification when a keyword is transcribed
+    pub notify: bool,
+}
+
+impl Default for AlertSettings {
     fn default() -> Self {
         Self {
-            show_indicator: true,
-            indicator_position: IndicatorPosition::Cursor,
-            theme: Theme::System,
-            start_minimized: false,
-            minimize_to_tray: true,
+            keywords: Vec::new(),
+            notify: true,
         }
     }
 }
 
-/// Recording indicator position
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum IndicatorPosition {
-    Cursor,
-    Center,
-    Corner,
+impl AlertSettings {
+    /// Maximum number of alert keywords
+    pub const MAX_KEYWORDS: usize = 100;
+
+    /// Maximum length of one keyword
+    pub const MAX_KEYWORD_LEN: usize = 100;
+
+    /// Validate alert settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.keywords.len() > Self::MAX_KEYWORDS {
+            return Err(SettingsError::InvalidValue(format!(
+                "at most {} alert keywords are allowed",
+                Self::MAX_KEYWORDS
+            )));
+        }
+        for (i, keyword) in self.keywords.iter().enumerate() {
+            Self::validate_keyword(keyword)?;
+            if self.keywords[..i]
+                .iter()
+                .any(|other| Self::same_keyword(other, keyword))
+            {
+                return Err(SettingsError::InvalidValue(format!(
+                    "duplicate alert keyword '{}'",
+                    keyword.trim()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keywords are compared trimmed and case-insensitively
+    fn same_keyword(a: &str, b: &str) -> bool {
+        a.trim().to_lowercase() == b.trim().to_lowercase()
+    }
+
+    fn validate_keyword(keyword: &str) -> Result<(), SettingsError> {
+        let keyword = keyword.trim();
+        if keyword.is_empty() {
+            return Err(SettingsError::InvalidValue("alert keyword is empty".to_string()));
+        }
+        if keyword.chars().count() > Self::MAX_KEYWORD_LEN {
+            return Err(SettingsError::InvalidValue(format!(
+                "alert keyword is longer than {} characters",
+                Self::MAX_KEYWORD_LEN
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sanitize alert settings: trim keywords, drop invalid ones and duplicates
+    pub fn sanitize(&mut self) {
+        let mut keywords: Vec<String> = Vec::new();
+        for keyword in &self.keywords {
+            let keyword = keyword.trim();
+            if Self::validate_keyword(keyword).is_ok()
+                && !keywords.iter().any(|k| Self::same_keyword(k, keyword))
+            {
+                keywords.push(keyword.to_string());
+            }
+        }
+        keywords.truncate(Self::MAX_KEYWORDS);
+        self.keywords = keywords;
+    }
+
+    /// Add a keyword
+    pub fn add_keyword(&mut self, keyword: &str) -> Result<(), SettingsError> {
+        let keyword = keyword.trim();
+        Self::validate_keyword(keyword)?;
+        if self.keywords.iter().any(|k| Self::same_keyword(k, keyword)) {
+            return Err(SettingsError::InvalidValue(format!(
+                "alert keyword '{}' already exists",
+                keyword
+            )));
+        }
+        if self.keywords.len() >= Self::MAX_KEYWORDS {
+            return Err(SettingsError::InvalidValue(format!(
+                "at most {} alert keywords are allowed",
+                Self::MAX_KEYWORDS
+            )));
+        }
+        self.keywords.push(keyword.to_string());
+        Ok(())
+    }
+
+    /// Remove a keyword (case-insensitive), returning whether it existed
+    pub fn remove_keyword(&mut self, keyword: &str) -> bool {
+        let before = self.keywords.len();
+        self.keywords.retain(|k| !Self::same_keyword(k, keyword));
+        self.keywords.len() != before
+    }
+}
+
+/// Lifecycle point a hook runs at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum HookEvent {
+    RecordingStarted,
+    TranscriptionCompleted,
+    OutputInjected,
+}
+
+impl HookEvent {
+    /// Name passed to hooks in `GIGAWHISPER_EVENT`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::RecordingStarted => "recording-started",
+            Self::TranscriptionCompleted => "transcription-completed",
+            Self::OutputInjected => "output-injected",
+        }
+    }
+}
+
+/// An executable run at a lifecycle point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Hook {
+    pub event: HookEvent,
+    /// Executable to run (not interpreted by a shell)
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Automation hook settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HookSettings {
+    /// Run the configured hooks
+    pub enabled: bool,
+    /// Seconds a hook may run before it is killed
+    pub timeout_secs: u32,
+    pub hooks: Vec<Hook>,
+}
+
+impl Default for HookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            timeout_secs: 10,
+            hooks: Vec::new(),
+        }
+    }
+}
+
+impl HookSettings {
+    /// Maximum number of hooks
+    pub const MAX_HOOKS: usize = 20;
+
+    /// Longest time a hook may run (5 minutes)
+    pub const MAX_TIMEOUT_SECS: u32 = 300;
+
+    /// Validate hook settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(1..=Self::MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Err(SettingsError::InvalidValue(format!(
+                "hook timeout must be between 1 and {} seconds",
+                Self::MAX_TIMEOUT_SECS
+            )));
+        }
+        if self.hooks.len() > Self::MAX_HOOKS {
+            return Err(SettingsError::InvalidValue(format!(
+                "at most {} hooks are allowed",
+                Self::MAX_HOOKS
+            )));
+        }
+        if self.hooks.iter().any(|hook| hook.command.trim().is_empty()) {
+            return Err(SettingsError::InvalidValue("hook command is empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Sanitize hook settings: clamp the timeout and drop hooks without a command
+    pub fn sanitize(&mut self) {
+        self.timeout_secs = self.timeout_secs.clamp(1, Self::MAX_TIMEOUT_SECS);
+        self.hooks.retain(|hook| !hook.command.trim().is_empty());
+        self.hooks.truncate(Self::MAX_HOOKS);
+    }
+
+    /// Hooks to run for an event (none while disabled)
+    pub fn for_event(&self, event: HookEvent) -> impl Iterator<Item = &Hook> {
+        self.hooks
+            .iter()
+            .filter(move |hook| self.enabled && hook.event == event)
+    }
+}
+
+/// Memory budget settings
+///
+/// Caps apply while idle; 0 means no cap.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ResourceSettings {
+    /// Most RAM the app should keep in use (MB)
+    pub max_ram_mb: u32,
+    /// Most VRAM the Whisper model should keep in use (MB)
+    pub max_vram_mb: u32,
+    /// Unload the Whisper model after this many idle minutes (0 = keep it loaded)
+    pub idle_unload_minutes: u32,
+}
+
+impl ResourceSettings {
+    /// Largest memory cap (1 TB)
+    pub const MAX_CAP_MB: u32 = 1024 * 1024;
+
+    /// Longest idle time before unloading (1 day)
+    pub const MAX_IDLE_UNLOAD_MINUTES: u32 = 24 * 60;
+
+    /// Validate resource settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.max_ram_mb > Self::MAX_CAP_MB || self.max_vram_mb > Self::MAX_CAP_MB {
+            return Err(SettingsError::InvalidValue(format!(
+                "memory caps must be at most {} MB",
+                Self::MAX_CAP_MB
+            )));
+        }
+        if self.idle_unload_minutes > Self::MAX_IDLE_UNLOAD_MINUTES {
+            return Err(SettingsError::InvalidValue(format!(
+                "idle unload time must be at most {} minutes",
+                Self::MAX_IDLE_UNLOAD_MINUTES
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sanitize resource settings by clamping to valid ranges
+    pub fn sanitize(&mut self) {
+        self.max_ram_mb = self.max_ram_mb.min(Self::MAX_CAP_MB);
+        self.max_vram_mb = self.max_vram_mb.min(Self::MAX_CAP_MB);
+        self.idle_unload_minutes = self.idle_unload_minutes.min(Self::MAX_IDLE_UNLOAD_MINUTES);
+    }
+
+    /// RAM cap in bytes (None = unlimited)
+    pub fn max_ram_bytes(&self) -> Option<u64> {
+        (self.max_ram_mb > 0).then(|| self.max_ram_mb as u64 * 1024 * 1024)
+    }
+
+    /// VRAM cap in bytes (None = unlimited)
+    pub fn max_vram_bytes(&self) -> Option<u64> {
+        (self.max_vram_mb > 0).then(|| self.max_vram_mb as u64 * 1024 * 1024)
+    }
+}
+
+/// Latency objective for dictations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LatencySettings {
+    /// Target time from stopping a recording to delivering its text (ms, 0 = no target)
+    pub target_ms: u32,
+    /// Consecutive dictations over the target before raising an alert
+    pub alert_after: u32,
+    /// Show a notification when the target is repeatedly missed
+    pub notify: bool,
+}
+
+impl Default for LatencySettings {
+    fn default() -> Self {
+        Self {
+            target_ms: 2000,
+            alert_after: 3,
+            notify: true,
+        }
+    }
+}
+
+impl LatencySettings {
+    /// Valid latency targets (ms)
+    pub const TARGET_RANGE: std::ops::RangeInclusive<u32> = 200..=60_000;
+
+    /// Valid number of misses before alerting
+    pub const ALERT_AFTER_RANGE: std::ops::RangeInclusive<u32> = 1..=20;
+
+    /// Validate latency settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.target_ms != 0 && !Self::TARGET_RANGE.contains(&self.target_ms) {
+            return Err(SettingsError::InvalidValue(format!(
+                "latency target must be between {} and {} ms",
+                Self::TARGET_RANGE.start(),
+                Self::TARGET_RANGE.end()
+            )));
+        }
+        if !Self::ALERT_AFTER_RANGE.contains(&self.alert_after) {
+            return Err(SettingsError::InvalidValue(format!(
+                "latency alert threshold must be between {} and {} dictations",
+                Self::ALERT_AFTER_RANGE.start(),
+                Self::ALERT_AFTER_RANGE.end()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sanitize latency settings by clamping to valid ranges
+    pub fn sanitize(&mut self) {
+        if self.target_ms != 0 {
+            self.target_ms = self
+                .target_ms
+                .clamp(*Self::TARGET_RANGE.start(), *Self::TARGET_RANGE.end());
+        }
+        self.alert_after = self
+            .alert_after
+            .clamp(*Self::ALERT_AFTER_RANGE.start(), *Self::ALERT_AFTER_RANGE.end());
+    }
+}
+
+/// Pruning of unused model downloads
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ModelPruningSettings {
+    /// Suggest removing models unused for `unused_days` (nothing is deleted without confirmation)
+    pub enabled: bool,
+    /// Days without use before a model is suggested for removal
+    pub unused_days: u32,
+}
+
+impl Default for ModelPruningSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            unused_days: 30,
+        }
+    }
+}
+
+impl ModelPruningSettings {
+    /// Valid number of unused days
+    pub const UNUSED_DAYS_RANGE: std::ops::RangeInclusive<u32> = 1..=3650;
+
+    /// Validate pruning settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !Self::UNUSED_DAYS_RANGE.contains(&self.unused_days) {
+            return Err(SettingsError::InvalidValue(format!(
+                "unused days must be between {} and {}",
+                Self::UNUSED_DAYS_RANGE.start(),
+                Self::UNUSED_DAYS_RANGE.end()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Sanitize pruning settings by clamping to valid ranges
+    pub fn sanitize(&mut self) {
+        self.unused_days = self
+            .unused_days
+            .clamp(*Self::UNUSED_DAYS_RANGE.start(), *Self::UNUSED_DAYS_RANGE.end());
+    }
+}
+
+/// Where models are stored
+///
+/// Settings and history always stay in the OS user's own directories; only
+/// the large model files can be shared between users.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ModelStorageSettings {
+    /// Absolute path of a model directory shared by all users (None = per-user).
+    /// When it is read-only, its models are used and downloads go to the
+    /// user's own directory.
+    pub shared_dir: Option<String>,
+}
+
+impl ModelStorageSettings {
+    /// Validate model storage settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if let Some(dir) = &self.shared_dir {
+            if !std::path::Path::new(dir).is_absolute() {
+                return Err(SettingsError::InvalidValue(
+                    "shared model directory must be an absolute path".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sanitize model storage settings, dropping a blank or relative shared path
+    pub fn sanitize(&mut self) {
+        self.shared_dir = self
+            .shared_dir
+            .take()
+            .map(|dir| dir.trim().to_string())
+            .filter(|dir| std::path::Path::new(dir).is_absolute());
+    }
+}
+
+/// Local API server
+///
+/// Listens on 127.0.0.1 only; every call needs the token of a registered
+/// API client with the matching scope.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ApiServerSettings {
+    /// Run the server
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ApiServerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 47811,
+        }
+    }
+}
+
+impl ApiServerSettings {
+    /// Validate API server settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.port < 1024 {
+            return Err(SettingsError::InvalidValue(format!(
+                "API server port {} is reserved, use 1024 or above",
+                self.port
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// MQTT publishing, for home automation
+///
+/// Recording state goes to `<topic>/state` ("recording", "processing",
+/// "idle" or "error") and final transcripts to `<topic>/transcript`.
+/// The broker password is kept in the credential store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct MqttSettings {
+    /// Publish to the broker
+    pub enabled: bool,
+    /// Broker host name or IP address
+    pub host: String,
+    pub port: u16,
+    /// Topic prefix
+    pub topic: String,
+    pub client_id: String,
+    /// User name, if the broker requires authentication
+    pub username: Option<String>,
+    /// Ask the broker to keep the last state for new subscribers
+    pub retain: bool,
 }
 
-/// Application theme
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-pub enum Theme {
-    System,
-    Light,
-    Dark,
-}
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            topic: "gigawhisper".to_string(),
+            client_id: "gigawhisper".to_string(),
+            username: None,
+            retain: false,
+        }
+    }
+}
+
+impl MqttSettings {
+    /// Validate MQTT settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !self.enabled {
+            return Ok(());
+        }
+        if self.host.trim().is_empty() {
+            return Err(SettingsError::InvalidValue(
+                "MQTT broker host cannot be empty".to_string(),
+            ));
+        }
+        let topic = self.topic.trim();
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            return Err(SettingsError::InvalidValue(format!(
+                "Invalid MQTT topic '{}': it must be non-empty without wildcards",
+                self.topic
+            )));
+        }
+        if self.client_id.trim().is_empty() || self.client_id.len() > 23 {
+            return Err(SettingsError::InvalidValue(
+                "MQTT client ID must be 1 to 23 characters".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Privacy settings
+///
+/// With `no_disk_audio`, recordings only live in memory: history entries are
+/// saved without audio, no crash-recovery checkpoint or debug capture is
+/// written, and the recording's audio buffers are zeroed once the text is
+/// delivered. Copies made while transcribing are freed without being
+/// overwritten. The history encryption key is kept in the credential store.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct PrivacySettings {
+    /// Never write recorded audio to disk
+    pub no_disk_audio: bool,
+    /// Encrypt the history file at rest
+    pub encrypt_history: bool,
+}
+
+/// Output behavior settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct OutputSettings {
+    /// Auto-capitalize first letter
+    pub auto_capitalize: bool,
+    /// Add punctuation automatically
+    pub auto_punctuation: bool,
+    /// Delay before paste (milliseconds)
+    pub paste_delay: u32,
+    /// How text is inserted at the cursor
+    pub mode: OutputMode,
+    /// Characters per second in typing mode
+    pub typing_rate: u32,
+    /// Re-apply the capitalization of proper nouns learned from corrections
+    pub capitalize_learned_terms: bool,
+    /// Destinations for each transcription, in order (empty = history only)
+    pub sinks: Vec<OutputSink>,
+    /// Selection receiving pasted text (Linux; other platforms always use the clipboard)
+    pub selection: PasteSelection,
+    /// Formats placed on the clipboard
+    pub clipboard_format: ClipboardFormat,
+    /// Add or drop the leading space depending on the text before the caret
+    pub smart_spacing: bool,
+    /// Attach a thumbnail of the target window to each history entry
+    pub capture_screenshots: bool,
+    /// Apps never captured (matched against the process name, case-insensitive)
+    pub screenshot_excluded_apps: Vec<String>,
+    /// Compliance log of text injections
+    pub injection_audit: InjectionAuditSettings,
+    /// Paste each sentence while still recording, once it stops changing
+    /// (local provider only)
+    pub type_as_you_speak: bool,
+    /// Locale conventions for quotes, apostrophes, spacing and decimals
+    pub typography: TypographySettings,
+    /// Case transform applied last
+    pub casing: TextCasing,
+    /// Spoken punctuation and formatting ("comma", "new line")
+    pub voice_commands: VoiceCommandSettings,
+    /// Rewriting of transcripts by a language model before output
+    pub cleanup: CleanupSettings,
+}
+
+/// How transcriptions are inserted at the cursor
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputMode {
+    /// Through the clipboard and Ctrl+V, falling back to typing
+    #[default]
+    Paste,
+    /// Typed with synthetic key events; the clipboard is left untouched
+    /// unless typing fails
+    Type,
+}
+
+/// Case transform of transcriptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum TextCasing {
+    /// Leave the casing as transcribed
+    #[default]
+    Keep,
+    /// First letter of each sentence uppercase, the rest as transcribed
+    Sentence,
+    /// all lowercase
+    Lower,
+    /// ALL UPPERCASE
+    Upper,
+    /// First Letter Of Each Word Uppercase, the rest as transcribed
+    Title,
+}
+
+/// Transcript cleanup by a language model
+///
+/// The transcript is sent with the prompt to an OpenAI-compatible chat
+/// completions endpoint; the raw transcript is used when the request fails
+/// or times out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct CleanupSettings {
+    /// Rewrite each transcript before output
+    pub enabled: bool,
+    pub provider: CleanupProvider,
+    /// Server URL (empty = the provider's default). A custom URL must use
+    /// https unless it is on this computer, and is sent its own token rather
+    /// than the provider's API key
+    pub base_url: String,
+    /// Model name (empty = the provider's default)
+    pub model: String,
+    /// Instructions sent with each transcript
+    pub prompt: String,
+    /// Seconds to wait for the rewrite before using the raw transcript
+    pub timeout_seconds: u32,
+    /// Whether an OpenAI API key is configured (actual key stored in the credential manager)
+    pub openai_key_configured: bool,
+    /// Whether a token for the custom `base_url` is configured (actual token
+    /// stored in the credential manager)
+    pub endpoint_token_configured: bool,
+}
+
+impl Default for CleanupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            provider: CleanupProvider::Groq,
+            base_url: String::new(),
+            model: String::new(),
+            prompt: CleanupSettings::DEFAULT_PROMPT.to_string(),
+            timeout_seconds: 10,
+            openai_key_configured: false,
+            endpoint_token_configured: false,
+        }
+    }
+}
+
+impl CleanupSettings {
+    /// Prompt of new installs
+    pub const DEFAULT_PROMPT: &'static str = "Fix the grammar, spelling and punctuation of \
+        this dictated text. Keep its wording and language. Reply with the corrected text only.";
+    /// Longest prompt
+    pub const MAX_PROMPT_CHARS: usize = 4000;
+    /// Longest request timeout (2 minutes)
+    pub const MAX_TIMEOUT_SECONDS: u32 = 120;
+
+    /// Validate cleanup settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if !(1..=Self::MAX_TIMEOUT_SECONDS).contains(&self.timeout_seconds) {
+            return Err(SettingsError::InvalidValue(format!(
+                "cleanup timeout must be between 1 and {} seconds",
+                Self::MAX_TIMEOUT_SECONDS
+            )));
+        }
+        if self.prompt.chars().count() > Self::MAX_PROMPT_CHARS {
+            return Err(SettingsError::InvalidValue(format!(
+                "cleanup prompt is longer than {} characters",
+                Self::MAX_PROMPT_CHARS
+            )));
+        }
+        if self.enabled && self.prompt.trim().is_empty() {
+            return Err(SettingsError::InvalidValue(
+                "cleanup prompt is empty".to_string(),
+            ));
+        }
+        if !self.base_url.trim().is_empty() {
+            let url = reqwest::Url::parse(self.base_url.trim()).map_err(|e| {
+                SettingsError::InvalidValue(format!("cleanup URL is invalid: {}", e))
+            })?;
+            if !matches!(url.scheme(), "http" | "https") {
+                return Err(SettingsError::InvalidValue(
+                    "cleanup URL must start with http:// or https://".to_string(),
+                ));
+            }
+            if !self.endpoint_is_secure() {
+                return Err(SettingsError::InvalidValue(
+                    "cleanup URL must use https:// unless it is on this computer".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the custom server URL uses https or stays on this computer
+    ///
+    /// True when no custom URL is set.
+    pub fn endpoint_is_secure(&self) -> bool {
+        if self.base_url.trim().is_empty() {
+            return true;
+        }
+        let Ok(url) = reqwest::Url::parse(self.base_url.trim()) else {
+            return false;
+        };
+        let loopback = url.host_str().is_some_and(|host| {
+            host.eq_ignore_ascii_case("localhost")
+                || host
+                    .trim_matches(['[', ']'])
+                    .parse::<std::net::IpAddr>()
+                    .is_ok_and(|ip| ip.is_loopback())
+        });
+        url.scheme() == "https" || (url.scheme() == "http" && loopback)
+    }
+
+    /// Token sent to the custom server URL
+    pub fn get_endpoint_token(&self) -> Option<String> {
+        if !self.endpoint_token_configured {
+            return None;
+        }
+        super::SecretsManager::get_cleanup_endpoint_token().ok()
+    }
+
+    /// Sanitize cleanup settings
+    pub fn sanitize(&mut self) {
+        self.timeout_seconds = self.timeout_seconds.clamp(1, Self::MAX_TIMEOUT_SECONDS);
+        if self.prompt.trim().is_empty() {
+            self.prompt = Self::DEFAULT_PROMPT.to_string();
+        }
+        if self.prompt.chars().count() > Self::MAX_PROMPT_CHARS {
+            self.prompt = self.prompt.chars().take(Self::MAX_PROMPT_CHARS).collect();
+        }
+    }
+
+    /// Server URL, the provider's default when unset
+    pub fn base_url(&self) -> &str {
+        match self.base_url.trim() {
+            "" => self.provider.default_base_url(),
+            url => url,
+        }
+    }
+
+    /// Model name, the provider's default when unset
+    pub fn model(&self) -> &str {
+        match self.model.trim() {
+            "" => self.provider.default_model(),
+            model => model,
+        }
+    }
+}
+
+/// Language model service used for transcript cleanup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CleanupProvider {
+    /// Groq chat models, with the Groq API key
+    #[default]
+    Groq,
+    /// OpenAI chat models
+    OpenAi,
+    /// A local Ollama server (no key)
+    Ollama,
+}
+
+impl CleanupProvider {
+    pub fn default_base_url(self) -> &'static str {
+        match self {
+            Self::Groq => "https://api.groq.com/openai/v1",
+            Self::OpenAi => "https://api.openai.com/v1",
+            Self::Ollama => "http://localhost:11434/v1",
+        }
+    }
+
+    pub fn default_model(self) -> &'static str {
+        match self {
+            Self::Groq => "llama-3.1-8b-instant",
+            Self::OpenAi => "gpt-4o-mini",
+            Self::Ollama => "llama3.2",
+        }
+    }
+}
+
+/// Spoken punctuation and formatting commands
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct VoiceCommandSettings {
+    /// Replace spoken commands with the characters they name
+    pub enabled: bool,
+    /// Commands added to, or replacing, the built-in ones
+    pub commands: Vec<VoiceCommand>,
+}
+
+impl VoiceCommandSettings {
+    /// Maximum number of custom commands
+    pub const MAX_COMMANDS: usize = 200;
+
+    /// Validate voice command settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.commands.len() > Self::MAX_COMMANDS {
+            return Err(SettingsError::InvalidValue(format!(
+                "{} voice commands exceeds limit of {}",
+                self.commands.len(),
+                Self::MAX_COMMANDS
+            )));
+        }
+        if self.commands.iter().any(|c| c.phrase.trim().is_empty()) {
+            return Err(SettingsError::InvalidValue(
+                "voice commands need a phrase".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A spoken phrase and the text replacing it
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct VoiceCommand {
+    /// Words to say, matched in any case
+    pub phrase: String,
+    /// Text inserted instead (punctuation attaches to the previous word)
+    pub text: String,
+    /// Language the command applies to (ISO 639-1, None = every language)
+    pub language: Option<String>,
+}
+
+/// Typography normalization settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TypographySettings {
+    /// Apply the conventions of the transcription's language before injection
+    pub enabled: bool,
+    /// Conventions replacing the built-in ones of a language
+    pub languages: Vec<TypographyConventions>,
+}
+
+impl TypographySettings {
+    /// Maximum number of per-language conventions
+    pub const MAX_LANGUAGES: usize = 100;
+
+    /// Validate typography settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.languages.len() > Self::MAX_LANGUAGES {
+            return Err(SettingsError::InvalidValue(format!(
+                "{} typography languages exceeds limit of {}",
+                self.languages.len(),
+                Self::MAX_LANGUAGES
+            )));
+        }
+        for (i, conventions) in self.languages.iter().enumerate() {
+            let language = conventions.language.as_str();
+            if language.is_empty() || language == "auto" {
+                return Err(SettingsError::InvalidValue(
+                    "typography conventions need a language code".to_string(),
+                ));
+            }
+            if self.languages[..i].iter().any(|c| c.language == language) {
+                return Err(SettingsError::InvalidValue(format!(
+                    "typography conventions for \"{}\" are defined twice",
+                    language
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Conventions configured for `language`, if any
+    pub fn conventions(&self, language: &str) -> Option<&TypographyConventions> {
+        self.languages.iter().find(|c| c.language == language)
+    }
+}
+
+/// Typographic conventions of a language
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct TypographyConventions {
+    /// Language code (ISO 639-1)
+    pub language: String,
+    pub quotes: QuoteStyle,
+    pub decimal_separator: DecimalSeparator,
+    /// Replace straight apostrophes with typographic ones (’)
+    pub typographic_apostrophes: bool,
+    /// Non-breaking spaces before ; : ! ? and inside « » (French)
+    pub space_before_punctuation: bool,
+}
+
+impl Default for TypographyConventions {
+    fn default() -> Self {
+        Self {
+            language: String::new(),
+            quotes: QuoteStyle::Curly,
+            decimal_separator: DecimalSeparator::Point,
+            typographic_apostrophes: true,
+            space_before_punctuation: false,
+        }
+    }
+}
+
+/// Quotation marks used for double quotes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum QuoteStyle {
+    /// Leave quotes as transcribed
+    Keep,
+    /// "…"
+    Straight,
+    /// “…”
+    #[default]
+    Curly,
+    /// „…“
+    LowHigh,
+    /// „…”
+    LowRight,
+    /// «…»
+    Guillemets,
+    /// »…«
+    ReversedGuillemets,
+    /// 「…」
+    Corner,
+}
+
+/// Separator between the integer and fractional parts of numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum DecimalSeparator {
+    /// Leave numbers as transcribed
+    Keep,
+    #[default]
+    Point,
+    Comma,
+}
+
+/// Injection audit log settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct InjectionAuditSettings {
+    /// Record each injection (time, target app, method, character count)
+    pub enabled: bool,
+    /// Days records are kept
+    pub retention_days: u32,
+}
+
+impl Default for InjectionAuditSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 90,
+        }
+    }
+}
+
+impl InjectionAuditSettings {
+    /// Maximum retention (10 years)
+    pub const MAX_RETENTION_DAYS: u32 = 3650;
+}
+
+/// Clipboard payload format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardFormat {
+    /// Plain text only
+    #[default]
+    PlainText,
+    /// Plain text plus an HTML rendering of its markdown, for rich text editors
+    Html,
+}
+
+/// X11/Wayland selection used for pasting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PasteSelection {
+    /// Regular clipboard, pasted with Ctrl+V
+    #[default]
+    Clipboard,
+    /// Primary selection only, pasted by the user with a middle click
+    Primary,
+    /// Both: Ctrl+V pastes now, middle click pastes again later
+    Both,
+}
+
+impl PasteSelection {
+    /// Whether the regular clipboard receives the text
+    pub fn uses_clipboard(self) -> bool {
+        !matches!(self, Self::Primary)
+    }
+
+    /// Whether the primary selection receives the text
+    pub fn uses_primary(self) -> bool {
+        !matches!(self, Self::Clipboard)
+    }
+}
+
+/// Transcription output destination
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum OutputSink {
+    /// Paste at cursor (or show the popup when GigaWhisper is focused)
+    Paste,
+    /// Copy to clipboard only
+    Clipboard,
+    /// Append to a text file; `{date}` in the path expands to YYYY-MM-DD
+    AppendToFile {
+        path: String,
+        /// Write a timestamp line before each transcription
+        #[serde(default)]
+        header: bool,
+    },
+    /// Append to the day's Markdown note (`YYYY-MM-DD.md`) in a folder,
+    /// e.g. an Obsidian vault
+    MarkdownJournal {
+        folder: String,
+        /// Entry layout; `{text}`, `{date}`, `{time}` and `{tags}` are replaced
+        #[serde(default = "default_journal_template")]
+        template: String,
+        /// Tags written as `#tag` in place of `{tags}`
+        #[serde(default)]
+        tags: Vec<String>,
+    },
+    /// POST the transcription as JSON
+    Webhook {
+        url: String,
+        /// Header carrying the stored webhook authorization (e.g. "Authorization"),
+        /// none to send no credentials
+        #[serde(default)]
+        auth_header: Option<String>,
+        /// Retries after a failed delivery (network errors, 429 and 5xx responses)
+        #[serde(default = "default_webhook_retries")]
+        max_retries: u32,
+    },
+}
+
+fn default_webhook_retries() -> u32 {
+    2
+}
+
+fn default_journal_template() -> String {
+    OutputSink::DEFAULT_JOURNAL_TEMPLATE.to_string()
+}
+
+impl OutputSink {
+    /// Journal entry layout of new markdown journal sinks
+    pub const DEFAULT_JOURNAL_TEMPLATE: &'static str = "- {time} {text} {tags}";
+
+    /// Short label used in logs and results
+    pub fn label(&self) -> String {
+        match self {
+            Self::Paste => "paste".to_string(),
+            Self::Clipboard => "clipboard".to_string(),
+            Self::AppendToFile { path, .. } => format!("file:{}", path),
+            Self::MarkdownJournal { folder, .. } => format!("journal:{}", folder),
+            Self::Webhook { url, .. } => format!("webhook:{}", url),
+        }
+    }
+
+    /// Check the sink configuration
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        match self {
+            Self::AppendToFile { path, .. } if path.trim().is_empty() => Err(
+                SettingsError::InvalidValue("append-to-file sink has an empty path".to_string()),
+            ),
+            Self::MarkdownJournal { folder, .. } if folder.trim().is_empty() => Err(
+                SettingsError::InvalidValue("markdown journal sink has no folder".to_string()),
+            ),
+            Self::MarkdownJournal { template, .. } if !template.contains("{text}") => {
+                Err(SettingsError::InvalidValue(
+                    "markdown journal template must contain {text}".to_string(),
+                ))
+            }
+            Self::Webhook { url, .. } if !(url.starts_with("https://") || url.starts_with("http://")) => {
+                Err(SettingsError::InvalidValue(format!(
+                    "webhook sink URL must start with http:// or https://: {}",
+                    url
+                )))
+            }
+            Self::Webhook {
+                auth_header: Some(name),
+                ..
+            } if reqwest::header::HeaderName::from_bytes(name.trim().as_bytes()).is_err() => {
+                Err(SettingsError::InvalidValue(format!(
+                    "webhook auth header name is invalid: {}",
+                    name
+                )))
+            }
+            Self::Webhook { max_retries, .. } if *max_retries > RetrySettings::MAX_RETRIES => {
+                Err(SettingsError::InvalidValue(format!(
+                    "webhook retries must be at most {}",
+                    RetrySettings::MAX_RETRIES
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        Self {
+            auto_capitalize: true,
+            auto_punctuation: true,
+            paste_delay: 50,
+            mode: OutputMode::Paste,
+            typing_rate: 100,
+            capitalize_learned_terms: true,
+            sinks: vec![OutputSink::Paste],
+            selection: PasteSelection::Clipboard,
+            clipboard_format: ClipboardFormat::PlainText,
+            smart_spacing: false,
+            capture_screenshots: false,
+            screenshot_excluded_apps: ["1password", "bitwarden", "dashlane", "keepass", "lastpass"]
+                .iter()
+                .map(|app| app.to_string())
+                .collect(),
+            injection_audit: InjectionAuditSettings::default(),
+            type_as_you_speak: false,
+            typography: TypographySettings::default(),
+            casing: TextCasing::Keep,
+            voice_commands: VoiceCommandSettings::default(),
+            cleanup: CleanupSettings::default(),
+        }
+    }
+}
+
+impl OutputSettings {
+    /// Maximum paste delay (2 seconds)
+    pub const MAX_PASTE_DELAY: u32 = 2000;
+    /// Maximum number of output sinks
+    pub const MAX_SINKS: usize = 8;
+    /// Slowest typing rate (characters per second)
+    pub const MIN_TYPING_RATE: u32 = 5;
+    /// Fastest typing rate (characters per second)
+    pub const MAX_TYPING_RATE: u32 = 1000;
+
+    /// Validate output settings
+    pub fn validate(&self) -> Result<(), SettingsError> {
+        if self.paste_delay > Self::MAX_PASTE_DELAY {
+            return Err(SettingsError::InvalidValue(format!(
+                "paste_delay {} exceeds limit of {} ms",
+                self.paste_delay,
+                Self::MAX_PASTE_DELAY
+            )));
+        }
+        if !(Self::MIN_TYPING_RATE..=Self::MAX_TYPING_RATE).contains(&self.typing_rate) {
+            return Err(SettingsError::InvalidValue(format!(
+                "typing_rate {} must be between {} and {} characters per second",
+                self.typing_rate,
+                Self::MIN_TYPING_RATE,
+                Self::MAX_TYPING_RATE
+            )));
+        }
+        if self.sinks.len() > Self::MAX_SINKS {
+            return Err(SettingsError::InvalidValue(format!(
+                "{} output sinks exceeds limit of {}",
+                self.sinks.len(),
+                Self::MAX_SINKS
+            )));
+        }
+        for sink in &self.sinks {
+            sink.validate()?;
+        }
+        let retention_days = self.injection_audit.retention_days;
+        if !(1..=InjectionAuditSettings::MAX_RETENTION_DAYS).contains(&retention_days) {
+            return Err(SettingsError::InvalidValue(format!(
+                "injection audit retention of {} days must be between 1 and {}",
+                retention_days,
+                InjectionAuditSettings::MAX_RETENTION_DAYS
+            )));
+        }
+        self.typography.validate()?;
+        self.voice_commands.validate()?;
+        self.cleanup.validate()?;
+        Ok(())
+    }
+
+    /// Sanitize output settings
+    pub fn sanitize(&mut self) {
+        self.paste_delay = self.paste_delay.min(Self::MAX_PASTE_DELAY);
+        self.typing_rate = self
+            .typing_rate
+            .clamp(Self::MIN_TYPING_RATE, Self::MAX_TYPING_RATE);
+        self.sinks.retain(|sink| sink.validate().is_ok());
+        self.sinks.truncate(Self::MAX_SINKS);
+        self.injection_audit.retention_days = self
+            .injection_audit
+            .retention_days
+            .clamp(1, InjectionAuditSettings::MAX_RETENTION_DAYS);
+        let mut seen = std::collections::HashSet::new();
+        self.typography.languages.retain(|c| {
+            !c.language.is_empty() && c.language != "auto" && seen.insert(c.language.clone())
+        });
+        self.typography.languages.truncate(TypographySettings::MAX_LANGUAGES);
+        self.voice_commands.commands.retain(|c| !c.phrase.trim().is_empty());
+        self.voice_commands.commands.truncate(VoiceCommandSettings::MAX_COMMANDS);
+        self.cleanup.sanitize();
+    }
+}
+
+/// UI settings
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct UiSettings {
+    /// Show recording indicator
+    pub show_indicator: bool,
+    /// Indicator position
+    pub indicator_position: IndicatorPosition,
+    /// Application theme
+    pub theme: Theme,
+    /// Start minimized to tray
+    pub start_minimized: bool,
+    /// Minimize to tray instead of taskbar
+    pub minimize_to_tray: bool,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            show_indicator: true,
+            indicator_position: IndicatorPosition::Cursor,
+            theme: Theme::System,
+            start_minimized: false,
+            minimize_to_tray: true,
+        }
+    }
+}
+
+/// Recording indicator position
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorPosition {
+    Cursor,
+    Center,
+    Corner,
+}
+
+/// Application theme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    System,
+    Light,
+    Dark,
+}
+
+/// Settings errors
+#[derive(Debug, thiserror::Error)]
+pub enum SettingsError {
+    #[error("Invalid shortcut: {0}")]
+    InvalidShortcut(String),
+
+    #[error("Missing API key for cloud provider")]
+    MissingApiKey,
+
+    #[error("Invalid value: {0}")]
+    InvalidValue(String),
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] toml::ser::Error),
+
+    #[error("Deserialization error: {0}")]
+    Deserialization(#[from] toml::de::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_settings() {
+        let settings = Settings::default();
+
+        assert_eq!(settings.recording.mode, RecordingMode::PushToTalk);
+        assert_eq!(settings.recording.max_duration, 300);
+        assert_eq!(settings.shortcuts.record, "Ctrl+Space");
+        assert_eq!(settings.transcription.provider, TranscriptionProvider::Local);
+        assert_eq!(settings.transcription.language, "auto");
+        assert!(settings.ui.show_indicator);
+        assert_eq!(settings.shortcuts.palette, "Ctrl+Shift+H");
+    }
+
+    #[test]
+    fn test_palette_shortcut_must_differ_from_record() {
+        let mut settings = Settings::default();
+        settings.shortcuts.palette = settings.shortcuts.record.to_lowercase();
+        assert!(matches!(
+            settings.validate(),
+            Err(SettingsError::InvalidShortcut(_))
+        ));
+    }
+
+    #[test]
+    fn test_recording_settings_validation_valid() {
+        let settings = RecordingSettings {
+            mode: RecordingMode::Toggle,
+            max_duration: 600,
+            silence_timeout: 5000,
+            save_debug_captures: false,
+            incognito_minutes: 5,
+            history_audio: HistoryAudio::Full,
+            history_audio_codec: HistoryAudioCodec::Wav,
+        };
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_recording_settings_validation_invalid_max_duration() {
+        let settings = RecordingSettings {
+            mode: RecordingMode::PushToTalk,
+            max_duration: 10000, // Exceeds limit
+            silence_timeout: 0,
+            save_debug_captures: false,
+            incognito_minutes: 5,
+            history_audio: HistoryAudio::Full,
+            history_audio_codec: HistoryAudioCodec::Wav,
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_recording_settings_sanitize() {
+        let mut settings = RecordingSettings {
+            mode: RecordingMode::Toggle,
+            max_duration: 10000, // Should be clamped
+            silence_timeout: 100000, // Should be clamped
+            save_debug_captures: false,
+            incognito_minutes: 5,
+            history_audio: HistoryAudio::Full,
+            history_audio_codec: HistoryAudioCodec::Wav,
+        };
+        settings.sanitize();
+
+        assert_eq!(settings.max_duration, RecordingSettings::MAX_DURATION_LIMIT);
+        assert_eq!(settings.silence_timeout, RecordingSettings::MAX_SILENCE_TIMEOUT);
+    }
+
+    #[test]
+    fn test_hook_settings() {
+        let mut hooks = HookSettings {
+            enabled: true,
+            timeout_secs: 0,
+            hooks: vec![
+                Hook {
+                    event: HookEvent::TranscriptionCompleted,
+                    command: "/usr/local/bin/notify".to_string(),
+                    args: vec!["--quiet".to_string()],
+                },
+                Hook {
+                    event: HookEvent::OutputInjected,
+                    command: "  ".to_string(),
+                    args: Vec::new(),
+                },
+            ],
+        };
+        assert!(hooks.validate().is_err());
+
+        hooks.sanitize();
+        assert_eq!(hooks.timeout_secs, 1);
+        assert_eq!(hooks.hooks.len(), 1);
+        assert!(hooks.validate().is_ok());
+        assert_eq!(hooks.for_event(HookEvent::TranscriptionCompleted).count(), 1);
+        assert_eq!(hooks.for_event(HookEvent::RecordingStarted).count(), 0);
+
+        hooks.enabled = false;
+        assert_eq!(hooks.for_event(HookEvent::TranscriptionCompleted).count(), 0);
+    }
+
+    #[test]
+    fn test_resource_settings() {
+        let mut resources = ResourceSettings::default();
+        assert!(resources.validate().is_ok());
+        assert_eq!(resources.max_ram_bytes(), None);
+
+        resources.max_ram_mb = 512;
+        assert_eq!(resources.max_ram_bytes(), Some(512 * 1024 * 1024));
+
+        resources.max_vram_mb = ResourceSettings::MAX_CAP_MB + 1;
+        resources.idle_unload_minutes = 100_000;
+        assert!(resources.validate().is_err());
+        resources.sanitize();
+        assert!(resources.validate().is_ok());
+        assert_eq!(resources.idle_unload_minutes, ResourceSettings::MAX_IDLE_UNLOAD_MINUTES);
+    }
+
+    #[test]
+    fn test_latency_settings() {
+        let mut latency = LatencySettings::default();
+        assert!(latency.validate().is_ok());
+
+        latency.target_ms = 0;
+        assert!(latency.validate().is_ok());
+
+        latency.target_ms = 50;
+        latency.alert_after = 0;
+        assert!(latency.validate().is_err());
+        latency.sanitize();
+        assert_eq!(latency.target_ms, 200);
+        assert_eq!(latency.alert_after, 1);
+        assert!(latency.validate().is_ok());
+    }
+
+    #[test]
+    fn test_model_pruning_settings() {
+        let mut pruning = ModelPruningSettings::default();
+        assert!(!pruning.enabled);
+        assert!(pruning.validate().is_ok());
+
+        pruning.unused_days = 0;
+        assert!(pruning.validate().is_err());
+        pruning.sanitize();
+        assert_eq!(pruning.unused_days, 1);
+    }
+
+    #[test]
+    fn test_api_server_settings() {
+        let mut settings = Settings::default();
+        assert!(!settings.api_server.enabled);
+        assert!(settings.validate().is_ok());
+
+        settings.api_server.port = 80;
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_mqtt_settings() {
+        let mut mqtt = MqttSettings {
+            topic: String::new(),
+            ..Default::default()
+        };
+        // Not checked while disabled
+        assert!(mqtt.validate().is_ok());
+
+        mqtt.enabled = true;
+        assert!(mqtt.validate().is_err());
+        mqtt.topic = "home/office/#".to_string();
+        assert!(mqtt.validate().is_err());
+        mqtt.topic = "home/office/dictation".to_string();
+        assert!(mqtt.validate().is_ok());
+        mqtt.client_id = "a-client-id-longer-than-23".to_string();
+        assert!(mqtt.validate().is_err());
+    }
+
+    #[test]
+    fn test_privacy_settings_serialization() {
+        // Older settings files have no privacy section
+        let settings: Settings = serde_json::from_str("{}").unwrap();
+        assert_eq!(settings.privacy, PrivacySettings::default());
+        assert!(!settings.privacy.no_disk_audio);
+
+        let json = r#"{"privacy":{"no_disk_audio":true}}"#;
+        let settings: Settings = serde_json::from_str(json).unwrap();
+        assert!(settings.privacy.no_disk_audio);
+        assert!(!settings.privacy.encrypt_history);
+    }
+
+    #[test]
+    fn test_model_storage_settings() {
+        let mut storage = ModelStorageSettings::default();
+        assert!(storage.validate().is_ok());
+
+        storage.shared_dir = Some("models".to_string());
+        assert!(storage.validate().is_err());
+        storage.sanitize();
+        assert_eq!(storage.shared_dir, None);
+
+        let shared = std::env::temp_dir().join("gigawhisper-models");
+        storage.shared_dir = Some(format!(" {} ", shared.display()));
+        storage.sanitize();
+        assert_eq!(storage.shared_dir, Some(shared.display().to_string()));
+        assert!(storage.validate().is_ok());
+    }
+
+    #[test]
+    fn test_gesture_settings() {
+        let mut settings = Settings::default();
+        assert!(!settings.shortcuts.gestures.enabled);
+
+        settings.shortcuts.gestures.double_tap_ms = 50;
+        assert!(settings.validate().is_err());
+        assert_eq!(settings.sanitize().shortcuts.gestures.double_tap_ms, 100);
+
+        settings.shortcuts.gestures.double_tap_ms = 300;
+        settings.shortcuts.gestures.long_press_ms = 10_000;
+        assert!(settings.validate().is_err());
+        assert_eq!(settings.sanitize().shortcuts.gestures.long_press_ms, 3000);
+    }
+
+    #[test]
+    fn test_action_shortcuts_unique() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.shortcuts.assigned().len(), 3);
+
+        settings.shortcuts.repaste_last = "ctrl+shift+h".to_string();
+        assert!(matches!(
+            settings.validate(),
+            Err(SettingsError::InvalidShortcut(_))
+        ));
+
+        settings.shortcuts.repaste_last = "Ctrl+Alt+V".to_string();
+        assert!(settings.validate().is_ok());
+        settings.profiles.push(RecordingProfile {
+            id: "meeting".to_string(),
+            name: "Meeting".to_string(),
+            shortcut: "Ctrl+Alt+V".to_string(),
+            ..Default::default()
+        });
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_incognito_settings() {
+        let mut settings = Settings::default();
+        settings.recording.incognito_minutes = 0;
+        assert!(settings.validate().is_err());
+        assert_eq!(settings.sanitize().recording.incognito_minutes, 1);
+
+        settings.recording.incognito_minutes = 5;
+        settings.shortcuts.incognito = settings.shortcuts.record.clone();
+        assert!(matches!(
+            settings.validate(),
+            Err(SettingsError::InvalidShortcut(_))
+        ));
+
+        settings.shortcuts.incognito = "Ctrl+Alt+Space".to_string();
+        settings.recording.save_debug_captures = true;
+        settings.output.sinks.push(OutputSink::Webhook {
+            url: "https://example.com/hook".to_string(),
+            auth_header: None,
+            max_retries: 2,
+        });
+        let incognito = settings.effective_for_incognito();
+        assert!(!incognito.recording.save_debug_captures);
+        assert!(!incognito.output.capture_screenshots);
+        assert_eq!(incognito.output.sinks, vec![OutputSink::Paste]);
+        assert!(!incognito.hooks.enabled);
+        assert!(incognito.privacy.no_disk_audio);
+    }
+
+    #[test]
+    fn test_whisper_model_filename() {
+        assert_eq!(WhisperModel::Tiny.filename(), "ggml-tiny.bin");
+        assert_eq!(WhisperModel::Base.filename(), "ggml-base.bin");
+        assert_eq!(WhisperModel::Small.filename(), "ggml-small.bin");
+        assert_eq!(WhisperModel::Medium.filename(), "ggml-medium.bin");
+        assert_eq!(WhisperModel::Large.filename(), "ggml-large.bin");
+    }
+
+    #[test]
+    fn test_whisper_model_filename_with_quantization() {
+        assert_eq!(
+            WhisperModel::Small.filename_with_quantization(&ModelQuantization::F16),
+            "ggml-small.bin"
+        );
+        assert_eq!(
+            WhisperModel::Small.filename_with_quantization(&ModelQuantization::Q8_0),
+            "ggml-small-q8_0.bin"
+        );
+        assert_eq!(
+            WhisperModel::Small.filename_with_quantization(&ModelQuantization::Q5_1),
+            "ggml-small-q5_1.bin"
+        );
+    }
+
+    #[test]
+    fn test_whisper_model_size_bytes() {
+        assert!(WhisperModel::Tiny.size_bytes() < WhisperModel::Base.size_bytes());
+        assert!(WhisperModel::Base.size_bytes() < WhisperModel::Small.size_bytes());
+        assert!(WhisperModel::Small.size_bytes() < WhisperModel::Medium.size_bytes());
+        assert!(WhisperModel::Medium.size_bytes() < WhisperModel::Large.size_bytes());
+    }
+
+    #[test]
+    fn test_quantization_memory_factor() {
+        assert_eq!(ModelQuantization::F16.memory_factor(), 1.0);
+        assert!(ModelQuantization::Q8_0.memory_factor() < 1.0);
+        assert!(ModelQuantization::Q5_1.memory_factor() < ModelQuantization::Q8_0.memory_factor());
+    }
+
+    #[test]
+    fn test_vad_settings_validation_valid() {
+        let settings = VadSettings {
+            enabled: true,
+            engine: VadEngine::WebRtc,
+            aggressiveness: 2,
+            min_speech_duration_ms: 100,
+            padding_ms: 300,
+            trim_on_stop: true,
+            calibrations: BTreeMap::new(),
+        };
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_vad_settings_validation_invalid_aggressiveness() {
+        let settings = VadSettings {
+            enabled: true,
+            engine: VadEngine::WebRtc,
+            aggressiveness: 10, // Exceeds limit
+            min_speech_duration_ms: 100,
+            padding_ms: 300,
+            trim_on_stop: true,
+            calibrations: BTreeMap::new(),
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_vad_settings_sanitize() {
+        let mut settings = VadSettings {
+            enabled: true,
+            engine: VadEngine::Silero,
+            aggressiveness: 10,
+            min_speech_duration_ms: 100000,
+            padding_ms: 100000,
+            trim_on_stop: true,
+            calibrations: BTreeMap::new(),
+        };
+        settings.sanitize();
+
+        assert_eq!(settings.aggressiveness, VadSettings::MAX_AGGRESSIVENESS);
+        assert_eq!(settings.min_speech_duration_ms, VadSettings::MAX_MIN_SPEECH_DURATION_MS);
+        assert_eq!(settings.padding_ms, VadSettings::MAX_PADDING_MS);
+    }
+
+    #[test]
+    fn test_vad_settings_effective_for_device() {
+        let mut settings = VadSettings::default();
+        settings.calibrations.insert(
+            "USB Mic".to_string(),
+            VadCalibration {
+                aggressiveness: 0,
+                min_speech_duration_ms: 150,
+                energy_threshold_db: -45.0,
+                noise_floor_db: -60.0,
+            },
+        );
+
+        let usb = settings.effective_for(Some("USB Mic"));
+        assert_eq!(usb.aggressiveness, 0);
+        assert_eq!(usb.min_speech_duration_ms, 150);
+
+        // Uncalibrated devices keep the global values
+        let default = settings.effective_for(None);
+        assert_eq!(default.aggressiveness, settings.aggressiveness);
+        assert!(settings.calibration_for(None).is_none());
+    }
+
+    #[test]
+    fn test_vad_settings_calibrations_toml_roundtrip() {
+        let mut settings = Settings::default();
+        settings.audio.vad.calibrations.insert(
+            "Headset (USB Audio)".to_string(),
+            VadCalibration {
+                aggressiveness: 3,
+                min_speech_duration_ms: 250,
+                energy_threshold_db: -38.5,
+                noise_floor_db: -48.0,
+            },
+        );
+        let serialized = toml::to_string_pretty(&settings).unwrap();
+        let deserialized: Settings = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.audio.vad.calibrations, settings.audio.vad.calibrations);
+    }
+
+    #[test]
+    fn test_vad_engine_serialization() {
+        let settings: VadSettings = toml::from_str("engine = \"silero\"").unwrap();
+        assert_eq!(settings.engine, VadEngine::Silero);
+        assert_eq!(VadSettings::default().engine, VadEngine::WebRtc);
+        assert_eq!(serde_json::to_string(&VadEngine::WebRtc).unwrap(), "\"webrtc\"");
+    }
+
+    #[test]
+    fn test_output_settings_validation_valid() {
+        let settings = OutputSettings {
+            auto_capitalize: true,
+            auto_punctuation: true,
+            paste_delay: 100,
+            mode: OutputMode::Paste,
+            typing_rate: 100,
+            capitalize_learned_terms: true,
+            sinks: vec![OutputSink::Paste],
+            selection: PasteSelection::Clipboard,
+            clipboard_format: ClipboardFormat::PlainText,
+            smart_spacing: true,
+            capture_screenshots: false,
+            screenshot_excluded_apps: Vec::new(),
+            injection_audit: InjectionAuditSettings::default(),
+            type_as_you_speak: false,
+            typography: TypographySettings::default(),
+            casing: TextCasing::Keep,
+            voice_commands: VoiceCommandSettings::default(),
+            cleanup: CleanupSettings::default(),
+        };
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_output_settings_validation_invalid_paste_delay() {
+        let settings = OutputSettings {
+            auto_capitalize: true,
+            auto_punctuation: true,
+            paste_delay: 10000, // Exceeds limit
+            mode: OutputMode::Paste,
+            typing_rate: 100,
+            capitalize_learned_terms: true,
+            sinks: vec![OutputSink::Paste],
+            selection: PasteSelection::Clipboard,
+            clipboard_format: ClipboardFormat::PlainText,
+            smart_spacing: true,
+            capture_screenshots: false,
+            screenshot_excluded_apps: Vec::new(),
+            injection_audit: InjectionAuditSettings::default(),
+            type_as_you_speak: false,
+            typography: TypographySettings::default(),
+            casing: TextCasing::Keep,
+            voice_commands: VoiceCommandSettings::default(),
+            cleanup: CleanupSettings::default(),
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_typing_mode_settings() {
+        let mut settings: OutputSettings =
+            toml::from_str("mode = \"type\"\ntyping_rate = 2").unwrap();
+        assert_eq!(settings.mode, OutputMode::Type);
+        assert!(settings.validate().is_err());
+
+        settings.sanitize();
+        assert_eq!(settings.typing_rate, OutputSettings::MIN_TYPING_RATE);
+        assert!(settings.validate().is_ok());
+        assert_eq!(OutputSettings::default().mode, OutputMode::Paste);
+    }
+
+    #[test]
+    fn test_output_sinks_validation() {
+        let mut settings = OutputSettings {
+            sinks: vec![
+                OutputSink::Paste,
+                OutputSink::Webhook {
+                    url: "ftp://example.com".to_string(),
+                    auth_header: None,
+                    max_retries: 2,
+                },
+                OutputSink::AppendToFile {
+                    path: " ".to_string(),
+                    header: false,
+                },
+            ],
+            ..OutputSettings::default()
+        };
+        assert!(settings.validate().is_err());
+
+        settings.sanitize();
+        assert_eq!(settings.sinks, vec![OutputSink::Paste]);
+        assert!(settings.validate().is_ok());
+
+        let journal: OutputSink =
+            toml::from_str("type = \"markdown-journal\"\nfolder = \"Daily\"").unwrap();
+        assert_eq!(
+            journal,
+            OutputSink::MarkdownJournal {
+                folder: "Daily".to_string(),
+                template: OutputSink::DEFAULT_JOURNAL_TEMPLATE.to_string(),
+                tags: Vec::new(),
+            }
+        );
+        assert!(OutputSink::MarkdownJournal {
+            folder: "Daily".to_string(),
+            template: "- {time}".to_string(),
+            tags: Vec::new(),
+        }
+        .validate()
+        .is_err());
+
+        let webhook: OutputSink =
+            toml::from_str("type = \"webhook\"\nurl = \"https://example.com/hook\"").unwrap();
+        assert_eq!(
+            webhook,
+            OutputSink::Webhook {
+                url: "https://example.com/hook".to_string(),
+                auth_header: None,
+                max_retries: 2,
+            }
+        );
+        assert!(OutputSink::Webhook {
+            url: "https://example.com/hook".to_string(),
+            auth_header: Some("Bad Header:".to_string()),
+            max_retries: 2,
+        }
+        .validate()
+        .is_err());
+
+        // No sinks: transcriptions are only kept in history
+        settings.sinks.clear();
+        assert!(settings.validate().is_ok());
+        let settings: OutputSettings =
+            toml::from_str("[[sinks]]\ntype = \"append-to-file\"\npath = \"notes.md\"").unwrap();
+        assert_eq!(
+            settings.sinks,
+            vec![OutputSink::AppendToFile {
+                path: "notes.md".to_string(),
+                header: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_typography_settings() {
+        let fr = TypographyConventions {
+            language: "fr".to_string(),
+            quotes: QuoteStyle::Guillemets,
+            space_before_punctuation: true,
+            ..TypographyConventions::default()
+        };
+        let mut settings = OutputSettings::default();
+        settings.typography.languages =
+            vec![fr.clone(), fr.clone(), TypographyConventions::default()];
+        assert!(settings.validate().is_err());
+
+        settings.sanitize();
+        assert_eq!(settings.typography.languages, vec![fr]);
+        assert!(settings.validate().is_ok());
+        assert!(settings.typography.conventions("fr").is_some());
+        assert!(settings.typography.conventions("de").is_none());
+    }
+
+    #[test]
+    fn test_cleanup_settings() {
+        let mut settings = OutputSettings::default();
+        assert!(!settings.cleanup.enabled);
+        assert_eq!(
+            settings.cleanup.base_url(),
+            "https://api.groq.com/openai/v1"
+        );
+        assert_eq!(
+            serde_json::to_string(&CleanupProvider::OpenAi).unwrap(),
+            "\"openai\""
+        );
+
+        settings.cleanup.provider = CleanupProvider::Ollama;
+        settings.cleanup.model = " mistral ".to_string();
+        assert_eq!(settings.cleanup.base_url(), "http://localhost:11434/v1");
+        assert_eq!(settings.cleanup.model(), "mistral");
+
+        settings.cleanup.enabled = true;
+        settings.cleanup.prompt = " ".to_string();
+        settings.cleanup.timeout_seconds = 0;
+        settings.cleanup.base_url = "ftp://llm.local".to_string();
+        assert!(settings.validate().is_err());
+
+        settings.sanitize();
+        settings.cleanup.base_url = "http://llm.local:8080/v1".to_string();
+        assert!(settings.validate().is_err());
+        for url in [
+            "https://llm.local:8080/v1",
+            "http://localhost:8080",
+            "http://127.0.0.1:8080",
+            "http://[::1]:8080",
+        ] {
+            settings.cleanup.base_url = url.to_string();
+            assert!(settings.validate().is_ok(), "{}", url);
+        }
+        assert_eq!(settings.cleanup.prompt, CleanupSettings::DEFAULT_PROMPT);
+        assert_eq!(settings.cleanup.timeout_seconds, 1);
+    }
+
+    #[test]
+    fn test_voice_command_settings() {
+        let mut settings = OutputSettings::default();
+        assert!(!settings.voice_commands.enabled);
+        settings.voice_commands.commands = vec![
+            VoiceCommand {
+                phrase: "smiley".to_string(),
+                text: ":)".to_string(),
+                language: None,
+            },
+            VoiceCommand::default(),
+        ];
+        assert!(settings.validate().is_err());
+
+        settings.sanitize();
+        assert_eq!(settings.voice_commands.commands.len(), 1);
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_injection_audit_retention() {
+        let mut settings = OutputSettings::default();
+        settings.injection_audit.retention_days = 0;
+        assert!(settings.validate().is_err());
+        settings.sanitize();
+        assert_eq!(settings.injection_audit.retention_days, 1);
+
+        settings.injection_audit.retention_days = 100_000;
+        settings.sanitize();
+        assert_eq!(
+            settings.injection_audit.retention_days,
+            InjectionAuditSettings::MAX_RETENTION_DAYS
+        );
+    }
+
+    #[test]
+    fn test_paste_selection() {
+        assert!(PasteSelection::Clipboard.uses_clipboard());
+        assert!(!PasteSelection::Clipboard.uses_primary());
+        assert!(!PasteSelection::Primary.uses_clipboard());
+        assert!(PasteSelection::Both.uses_clipboard() && PasteSelection::Both.uses_primary());
+
+        let settings: OutputSettings = toml::from_str("selection = \"primary\"").unwrap();
+        assert_eq!(settings.selection, PasteSelection::Primary);
+        assert_eq!(OutputSettings::default().selection, PasteSelection::Clipboard);
+    }
+
+    #[test]
+    fn test_clipboard_format_default_and_parse() {
+        assert_eq!(OutputSettings::default().clipboard_format, ClipboardFormat::PlainText);
+
+        let settings: OutputSettings = toml::from_str("clipboard_format = \"html\"").unwrap();
+        assert_eq!(settings.clipboard_format, ClipboardFormat::Html);
+    }
+
+    #[test]
+    fn test_output_sinks_toml_roundtrip() {
+        let mut settings = Settings::default();
+        settings.output.sinks = vec![
+            OutputSink::Paste,
+            OutputSink::AppendToFile {
+                path: "notes/{date}.md".to_string(),
+                header: true,
+            },
+            OutputSink::MarkdownJournal {
+                folder: "vault/Daily".to_string(),
+                template: OutputSink::DEFAULT_JOURNAL_TEMPLATE.to_string(),
+                tags: vec!["dictation".to_string()],
+            },
+            OutputSink::Webhook {
+                url: "https://example.com/hook".to_string(),
+                auth_header: Some("Authorization".to_string()),
+                max_retries: 3,
+            },
+        ];
+        let serialized = toml::to_string_pretty(&settings).unwrap();
+        let deserialized: Settings = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.output.sinks, settings.output.sinks);
+    }
+
+    #[test]
+    fn test_transcription_settings_validation_invalid_threads() {
+        let mut settings = TranscriptionSettings::default();
+        settings.local.threads = 100; // Exceeds limit
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_transcription_settings_validation_invalid_groq_timeout() {
+        let mut settings = TranscriptionSettings::default();
+        settings.groq.timeout_seconds = 0;
+        assert!(settings.validate().is_err());
+
+        settings.groq.timeout_seconds = 1000; // Exceeds limit
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_transcription_settings_partial_interval() {
+        let mut settings = TranscriptionSettings::default();
+        assert!(!settings.live_partials);
+        assert!(settings.validate().is_ok());
 
-/// Settings errors
-#[derive(Debug, thiserror::Error)]
-pub enum SettingsError {
-    #[error("Invalid shortcut: {0}")]
-    InvalidShortcut(String),
+        settings.partial_interval_ms = 100;
+        assert!(settings.validate().is_err());
+        settings.sanitize();
+        assert_eq!(settings.partial_interval_ms, 500);
 
-    #[error("Missing API key for cloud provider")]
-    MissingApiKey,
+        assert!(settings.adaptive_partials);
+        settings.partial_interval_min_ms = 6000;
+        settings.partial_interval_max_ms = 2000;
+        assert!(settings.validate().is_err());
+        settings.sanitize();
+        assert_eq!(
+            (settings.partial_interval_min_ms, settings.partial_interval_max_ms),
+            (2000, 6000)
+        );
+        assert!(settings.validate().is_ok());
+    }
 
-    #[error("Invalid value: {0}")]
-    InvalidValue(String),
+    #[test]
+    fn test_quality_presets() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.transcription.quality_preset, QualityPreset::Custom);
 
-    #[error("IO error: {0}")]
-    Io(#[from] std::io::Error),
+        settings.transcription.quality_preset = QualityPreset::Fast;
+        settings.sync_quality_preset(QualityPreset::Custom);
+        assert_eq!(settings.transcription.local.model, WhisperModel::Base);
+        assert_eq!(settings.transcription.local.quantization, ModelQuantization::Q5_1);
+        assert_eq!(settings.audio.vad.aggressiveness, 3);
+        assert!(settings.validate().is_ok());
 
-    #[error("Serialization error: {0}")]
-    Serialization(#[from] toml::ser::Error),
+        settings.transcription.quality_preset = QualityPreset::Accurate;
+        settings.sync_quality_preset(QualityPreset::Fast);
+        assert_eq!(settings.transcription.local.model, WhisperModel::Medium);
+        assert_eq!(settings.transcription.local.beam_size, 5);
+        assert!(QualityPreset::Accurate.matches(&settings));
+        assert!(!QualityPreset::Fast.matches(&settings));
 
-    #[error("Deserialization error: {0}")]
-    Deserialization(#[from] toml::de::Error),
-}
+        // Saving unchanged keeps the preset, a manual change makes it Custom
+        settings.sync_quality_preset(QualityPreset::Accurate);
+        assert_eq!(settings.transcription.quality_preset, QualityPreset::Accurate);
+        settings.transcription.local.beam_size = 2;
+        settings.sync_quality_preset(QualityPreset::Accurate);
+        assert_eq!(settings.transcription.quality_preset, QualityPreset::Custom);
+        assert_eq!(settings.transcription.local.beam_size, 2);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        settings.transcription.local.beam_size = 0;
+        assert!(settings.validate().is_err());
+        settings.transcription.sanitize();
+        assert_eq!(settings.transcription.local.beam_size, 1);
+    }
 
     #[test]
-    fn test_default_settings() {
-        let settings = Settings::default();
+    fn test_custom_model_selection() {
+        let mut settings = Settings::default();
+        settings.transcription.local.custom_model = Some("medical".to_string());
+        let local = &settings.transcription.local;
+        assert_eq!(local.model_filename(), "custom-medical.bin");
+        assert_eq!(local.model_name(), "custom-medical");
+        assert!(settings.validate().is_ok());
 
-        assert_eq!(settings.recording.mode, RecordingMode::PushToTalk);
-        assert_eq!(settings.recording.max_duration, 300);
-        assert_eq!(settings.shortcuts.record, "Ctrl+Space");
-        assert_eq!(settings.transcription.provider, TranscriptionProvider::Local);
-        assert_eq!(settings.transcription.language, "auto");
-        assert!(settings.ui.show_indicator);
+        // A profile or preset picking a model replaces the imported one
+        settings.profiles.push(RecordingProfile {
+            local_model: Some(WhisperModel::Tiny),
+            ..test_profile("quick", "F9")
+        });
+        let quick = settings.effective_for_profile(Some("quick"));
+        assert_eq!(quick.transcription.local.model_filename(), "ggml-tiny.bin");
+        assert!(!QualityPreset::Fast.matches(&settings));
+        QualityPreset::Fast.apply(&mut settings);
+        assert_eq!(settings.transcription.local.custom_model, None);
+
+        settings.transcription.local.custom_model = Some("../ggml-large".to_string());
+        assert!(settings.validate().is_err());
+        settings.transcription.sanitize();
+        assert_eq!(settings.transcription.local.custom_model, None);
     }
 
     #[test]
-    fn test_recording_settings_validation_valid() {
-        let settings = RecordingSettings {
-            mode: RecordingMode::Toggle,
-            max_duration: 600,
-            silence_timeout: 5000,
-        };
-        assert!(settings.validate().is_ok());
+    fn test_decoding_settings() {
+        let mut transcription = TranscriptionSettings::default();
+        assert_eq!(transcription.local.temperature, 0.0);
+        assert_eq!(transcription.local.no_speech_threshold, 0.6);
+        assert!(transcription.validate().is_ok());
+
+        transcription.local.temperature = 1.5;
+        transcription.local.no_speech_threshold = f32::NAN;
+        assert!(transcription.validate().is_err());
+        transcription.sanitize();
+        assert_eq!(transcription.local.temperature, 1.0);
+        assert_eq!(transcription.local.no_speech_threshold, 0.6);
+        assert!(transcription.validate().is_ok());
     }
 
     #[test]
-    fn test_recording_settings_validation_invalid_max_duration() {
-        let settings = RecordingSettings {
-            mode: RecordingMode::PushToTalk,
-            max_duration: 10000, // Exceeds limit
-            silence_timeout: 0,
-        };
+    fn test_transcription_settings_deepgram() {
+        let mut settings = TranscriptionSettings::default();
+        assert_eq!(settings.deepgram.model, "nova-2");
+
+        settings.provider = TranscriptionProvider::Deepgram;
+        settings.translate = true;
+        assert!(settings.validate().is_err());
+        settings.sanitize();
+        assert!(!settings.translate);
+
+        settings.language_learning = true;
+        assert!(settings.validate().is_err());
+        settings.fallback_providers = vec![TranscriptionProvider::Local];
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.translation_chain(), vec![TranscriptionProvider::Local]);
+        settings.fallback_providers.clear();
+        settings.sanitize();
+        assert!(!settings.language_learning);
+        assert!(settings.validate().is_ok());
+
+        settings.deepgram.timeout_seconds = 0;
         assert!(settings.validate().is_err());
     }
 
     #[test]
-    fn test_recording_settings_sanitize() {
-        let mut settings = RecordingSettings {
-            mode: RecordingMode::Toggle,
-            max_duration: 10000, // Should be clamped
-            silence_timeout: 100000, // Should be clamped
-        };
-        settings.sanitize();
+    fn test_transcription_settings_custom_endpoint() {
+        let mut settings = TranscriptionSettings::default();
+        assert!(settings.validate().is_ok());
 
-        assert_eq!(settings.max_duration, RecordingSettings::MAX_DURATION_LIMIT);
-        assert_eq!(settings.silence_timeout, RecordingSettings::MAX_SILENCE_TIMEOUT);
+        settings.provider = TranscriptionProvider::Custom;
+        assert!(settings.validate().is_err());
+
+        settings.custom.base_url = "ftp://localhost:8000".to_string();
+        assert!(settings.validate().is_err());
+
+        settings.custom.base_url = " http://localhost:8000/v1/ ".to_string();
+        settings.custom.timeout_seconds = 0;
+        settings.sanitize();
+        assert_eq!(settings.custom.base_url, "http://localhost:8000/v1");
+        assert_eq!(settings.custom.timeout_seconds, 1);
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.custom.get_token(), None);
     }
 
     #[test]
-    fn test_whisper_model_filename() {
-        assert_eq!(WhisperModel::Tiny.filename(), "ggml-tiny.bin");
-        assert_eq!(WhisperModel::Base.filename(), "ggml-base.bin");
-        assert_eq!(WhisperModel::Small.filename(), "ggml-small.bin");
-        assert_eq!(WhisperModel::Medium.filename(), "ggml-medium.bin");
-        assert_eq!(WhisperModel::Large.filename(), "ggml-large.bin");
+    fn test_retry_settings() {
+        let mut settings = TranscriptionSettings::default();
+        assert!(settings.validate().is_ok());
+
+        settings.retry.max_retries = 50;
+        settings.retry.base_delay_ms = 5000;
+        settings.retry.max_delay_ms = 1000;
+        settings.retry.jitter_percent = 150;
+        assert!(settings.validate().is_err());
+
+        settings.sanitize();
+        assert_eq!(settings.retry.max_retries, RetrySettings::MAX_RETRIES);
+        assert_eq!(settings.retry.max_delay_ms, 5000);
+        assert_eq!(settings.retry.jitter_percent, 100);
+        assert!(settings.validate().is_ok());
     }
 
     #[test]
-    fn test_whisper_model_filename_with_quantization() {
+    fn test_transcription_settings_provider_chain() {
+        let mut settings = TranscriptionSettings::default();
+        assert_eq!(settings.provider_chain(), vec![TranscriptionProvider::Local]);
+
+        settings.provider = TranscriptionProvider::Groq;
+        settings.fallback_providers = vec![
+            TranscriptionProvider::Deepgram,
+            TranscriptionProvider::Groq,
+            TranscriptionProvider::Local,
+            TranscriptionProvider::Deepgram,
+        ];
+        assert!(settings.validate().is_err());
+        settings.sanitize();
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.fallback_providers.len(), 3);
         assert_eq!(
-            WhisperModel::Small.filename_with_quantization(&ModelQuantization::F16),
-            "ggml-small.bin"
+            settings.provider_chain(),
+            vec![
+                TranscriptionProvider::Groq,
+                TranscriptionProvider::Deepgram,
+                TranscriptionProvider::Local,
+            ]
         );
+
+        settings.translate = true;
         assert_eq!(
-            WhisperModel::Small.filename_with_quantization(&ModelQuantization::Q8_0),
-            "ggml-small-q8_0.bin"
+            settings.provider_chain(),
+            vec![TranscriptionProvider::Groq, TranscriptionProvider::Local]
         );
+    }
+
+    fn test_profile(id: &str, shortcut: &str) -> RecordingProfile {
+        RecordingProfile {
+            id: id.to_string(),
+            name: id.to_string(),
+            shortcut: shortcut.to_string(),
+            ..RecordingProfile::default()
+        }
+    }
+
+    #[test]
+    fn test_profile_apply_overrides() {
+        let base = Settings::default();
+        let profile = RecordingProfile {
+            language: Some("pl".to_string()),
+            translate: Some(true),
+            sinks: Some(vec![OutputSink::Clipboard]),
+            smart_spacing: Some(false),
+            typography: Some(true),
+            casing: Some(TextCasing::Lower),
+            source: Some(AudioSource::SystemAudio),
+            ..test_profile("polish", "F10")
+        };
+
+        let settings = profile.apply(&base);
+        assert_eq!(settings.transcription.language, "pl");
+        assert!(settings.transcription.translate);
+        assert_eq!(settings.output.sinks, vec![OutputSink::Clipboard]);
+        assert!(!settings.output.smart_spacing);
+        assert!(settings.output.typography.enabled);
+        assert_eq!(settings.output.casing, TextCasing::Lower);
+        assert_eq!(settings.audio.source, AudioSource::SystemAudio);
+        // Unset fields keep the global values
+        assert_eq!(settings.transcription.provider, base.transcription.provider);
         assert_eq!(
-            WhisperModel::Small.filename_with_quantization(&ModelQuantization::Q5_1),
-            "ggml-small-q5_1.bin"
+            settings.output.capitalize_learned_terms,
+            base.output.capitalize_learned_terms
         );
     }
 
     #[test]
-    fn test_whisper_model_size_bytes() {
-        assert!(WhisperModel::Tiny.size_bytes() < WhisperModel::Base.size_bytes());
-        assert!(WhisperModel::Base.size_bytes() < WhisperModel::Small.size_bytes());
-        assert!(WhisperModel::Small.size_bytes() < WhisperModel::Medium.size_bytes());
-        assert!(WhisperModel::Medium.size_bytes() < WhisperModel::Large.size_bytes());
+    fn test_effective_for_profile() {
+        let mut settings = Settings::default();
+        settings.profiles.push(RecordingProfile {
+            language: Some("en".to_string()),
+            ..test_profile("english", "F9")
+        });
+
+        assert_eq!(settings.effective_for_profile(Some("english")).transcription.language, "en");
+        assert_eq!(settings.effective_for_profile(Some("missing")).transcription.language, "auto");
+        assert_eq!(settings.effective_for_profile(None).transcription.language, "auto");
     }
 
     #[test]
-    fn test_quantization_memory_factor() {
-        assert_eq!(ModelQuantization::F16.memory_factor(), 1.0);
-        assert!(ModelQuantization::Q8_0.memory_factor() < 1.0);
-        assert!(ModelQuantization::Q5_1.memory_factor() < ModelQuantization::Q8_0.memory_factor());
+    fn test_translate_overrides() {
+        let mut settings = Settings::default();
+        settings.transcription.translate = true;
+        settings.profiles.push(test_profile("inherit", "F9"));
+        settings.profiles.push(RecordingProfile {
+            translate: Some(false),
+            ..test_profile("original", "F10")
+        });
+
+        assert!(settings.effective_for_profile(Some("inherit")).transcription.translate);
+        assert!(!settings.effective_for_profile(Some("original")).transcription.translate);
+
+        // A per-recording override wins over the profile
+        let recording = settings
+            .effective_for_profile(Some("original"))
+            .with_translate(Some(true));
+        assert!(recording.transcription.translate);
+        assert!(settings.clone().with_translate(None).transcription.translate);
     }
 
     #[test]
-    fn test_vad_settings_validation_valid() {
-        let settings = VadSettings {
-            enabled: true,
-            aggressiveness: 2,
-            min_speech_duration_ms: 100,
-            padding_ms: 300,
-        };
-        assert!(settings.validate().is_ok());
+    fn test_recognition_prompt() {
+        let mut transcription = TranscriptionSettings::default();
+        assert_eq!(transcription.recognition_prompt(&[]), None);
+
+        transcription.initial_prompt = "  Cardiology consult. ".to_string();
+        transcription.vocabulary = ["Kubernetes", " kubectl ", "KUBERNETES", "", "etcd"]
+            .iter()
+            .map(|term| term.to_string())
+            .collect();
+        let terms = transcription.vocabulary_terms();
+        assert_eq!(terms, ["Kubernetes", "kubectl", "etcd"]);
+        assert_eq!(
+            transcription.recognition_prompt(&terms).as_deref(),
+            Some("Cardiology consult. Kubernetes, kubectl, etcd.")
+        );
+
+        // Words that do not fit are left out
+        let many: Vec<String> = (0..200).map(|i| format!("term{}", i)).collect();
+        let prompt = transcription.recognition_prompt(&many).unwrap();
+        assert!(prompt.chars().count() <= TranscriptionSettings::MAX_PROMPT_CHARS);
+        assert!(prompt.ends_with('.'));
     }
 
     #[test]
-    fn test_vad_settings_validation_invalid_aggressiveness() {
-        let settings = VadSettings {
-            enabled: true,
-            aggressiveness: 10, // Exceeds limit
-            min_speech_duration_ms: 100,
-            padding_ms: 300,
+    fn test_prompt_and_vocabulary_sanitize() {
+        let mut transcription = TranscriptionSettings {
+            initial_prompt: "x".repeat(TranscriptionSettings::MAX_PROMPT_CHARS + 1),
+            vocabulary: vec![
+                " GraphQL ".to_string(),
+                "graphql".to_string(),
+                "".to_string(),
+                "Dr.\0Müller".to_string(),
+            ],
+            ..Default::default()
         };
-        assert!(settings.validate().is_err());
+        assert!(transcription.validate().is_err());
+
+        transcription.sanitize();
+        assert!(transcription.validate().is_ok());
+        assert_eq!(
+            transcription.initial_prompt.len(),
+            TranscriptionSettings::MAX_PROMPT_CHARS
+        );
+        assert_eq!(transcription.vocabulary, ["GraphQL", "Dr.Müller"]);
     }
 
     #[test]
-    fn test_vad_settings_sanitize() {
-        let mut settings = VadSettings {
-            enabled: true,
-            aggressiveness: 10,
-            min_speech_duration_ms: 100000,
-            padding_ms: 100000,
-        };
-        settings.sanitize();
+    fn test_alert_keywords() {
+        let mut alerts = AlertSettings::default();
+        assert!(alerts.add_keyword("  action item ").is_ok());
+        assert!(alerts.add_keyword("Alice").is_ok());
+        assert!(alerts.add_keyword("ACTION ITEM").is_err());
+        assert!(alerts.add_keyword("   ").is_err());
+        assert_eq!(alerts.keywords, vec!["action item", "Alice"]);
+        assert!(alerts.validate().is_ok());
 
-        assert_eq!(settings.aggressiveness, VadSettings::MAX_AGGRESSIVENESS);
-        assert_eq!(settings.min_speech_duration_ms, VadSettings::MAX_MIN_SPEECH_DURATION_MS);
-        assert_eq!(settings.padding_ms, VadSettings::MAX_PADDING_MS);
+        assert!(alerts.remove_keyword("alice"));
+        assert!(!alerts.remove_keyword("bob"));
+        assert_eq!(alerts.keywords, vec!["action item"]);
+
+        alerts.keywords = vec![" deadline ".into(), "".into(), "Deadline".into()];
+        assert!(alerts.validate().is_err());
+        alerts.sanitize();
+        assert_eq!(alerts.keywords, vec!["deadline"]);
     }
 
     #[test]
-    fn test_output_settings_validation_valid() {
-        let settings = OutputSettings {
-            auto_capitalize: true,
-            auto_punctuation: true,
-            paste_delay: 100,
-        };
-        assert!(settings.validate().is_ok());
+    fn test_workspace_add_and_remove() {
+        let mut workspaces = WorkspaceSettings::default();
+        let work = workspaces.add(" Work & Clients ").unwrap();
+        assert_eq!(work.id, "work-clients");
+        assert_eq!(work.name, "Work & Clients");
+        assert!(workspaces.add("work & clients").is_err());
+        assert_eq!(workspaces.add("Work, Clients").unwrap().id, "work-clients-2");
+        assert_eq!(workspaces.add("Émile").unwrap().id, "mile");
+        assert!(workspaces.validate().is_ok());
+
+        assert!(workspaces.remove(DEFAULT_WORKSPACE_ID).is_err());
+        workspaces.active = "work-clients".to_string();
+        assert!(workspaces.remove("work-clients").is_err());
+        assert!(workspaces.remove("work-clients-2").is_ok());
+        assert!(workspaces.remove("work-clients-2").is_err());
     }
 
     #[test]
-    fn test_output_settings_validation_invalid_paste_delay() {
-        let settings = OutputSettings {
-            auto_capitalize: true,
-            auto_punctuation: true,
-            paste_delay: 10000, // Exceeds limit
+    fn test_workspace_sanitize() {
+        let mut workspaces = WorkspaceSettings {
+            active: "gone".to_string(),
+            list: vec![
+                Workspace { id: "../evil".into(), name: "Evil".into(), output: None },
+                Workspace { id: "work".into(), name: "Work".into(), output: None },
+                Workspace { id: "work".into(), name: "Work 2".into(), output: None },
+            ],
         };
-        assert!(settings.validate().is_err());
+        assert!(workspaces.validate().is_err());
+        workspaces.sanitize();
+
+        let ids: Vec<&str> = workspaces.list.iter().map(|w| w.id.as_str()).collect();
+        assert_eq!(ids, vec![DEFAULT_WORKSPACE_ID, "work"]);
+        assert_eq!(workspaces.active, DEFAULT_WORKSPACE_ID);
+        assert!(workspaces.validate().is_ok());
     }
 
     #[test]
-    fn test_transcription_settings_validation_invalid_threads() {
-        let mut settings = TranscriptionSettings::default();
-        settings.local.threads = 100; // Exceeds limit
-        assert!(settings.validate().is_err());
+    fn test_switch_workspace_swaps_output() {
+        let mut settings = Settings::default();
+        settings.workspaces.add("Work").unwrap();
+        settings.output.paste_delay = 120;
+
+        // A new workspace inherits the current output settings
+        settings.switch_workspace("work").unwrap();
+        assert_eq!(settings.workspaces.active, "work");
+        assert_eq!(settings.output.paste_delay, 120);
+
+        settings.output.paste_delay = 40;
+        settings.switch_workspace(DEFAULT_WORKSPACE_ID).unwrap();
+        assert_eq!(settings.output.paste_delay, 120);
+        settings.switch_workspace("work").unwrap();
+        assert_eq!(settings.output.paste_delay, 40);
+
+        assert!(settings.switch_workspace("missing").is_err());
+        assert!(settings.workspaces.validate().is_ok());
     }
 
     #[test]
-    fn test_transcription_settings_validation_invalid_groq_timeout() {
-        let mut settings = TranscriptionSettings::default();
-        settings.groq.timeout_seconds = 0;
-        assert!(settings.validate().is_err());
+    fn test_profile_validation() {
+        let mut settings = Settings {
+            profiles: vec![test_profile("a", "F9"), test_profile("b", "F10")],
+            ..Settings::default()
+        };
+        assert!(settings.validate_profiles().is_ok());
 
-        settings.groq.timeout_seconds = 1000; // Exceeds limit
-        assert!(settings.validate().is_err());
+        settings.profiles[1].id = "a".to_string();
+        assert!(settings.validate_profiles().is_err());
+
+        settings.profiles[1] = test_profile("b", "f9");
+        assert!(settings.validate_profiles().is_err());
+
+        settings.profiles[1] = test_profile("b", "Ctrl+Space");
+        assert!(settings.validate_profiles().is_err());
+
+        settings.profiles[1] = test_profile("b", "");
+        assert!(settings.validate_profiles().is_err());
+    }
+
+    #[test]
+    fn test_profiles_toml_roundtrip() {
+        let mut settings = Settings::default();
+        settings.profiles.push(RecordingProfile {
+            provider: Some(TranscriptionProvider::Groq),
+            sinks: Some(vec![OutputSink::Paste, OutputSink::Clipboard]),
+            ..test_profile("work", "F9")
+        });
+
+        let serialized = toml::to_string_pretty(&settings).unwrap();
+        let deserialized: Settings = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.profiles, settings.profiles);
+    }
+
+    #[test]
+    fn test_prefetch_mode_serialization() {
+        let settings: TranscriptionSettings = toml::from_str("prefetch = \"aggressive\"").unwrap();
+        assert_eq!(settings.prefetch, PrefetchMode::Aggressive);
+        assert_eq!(TranscriptionSettings::default().prefetch, PrefetchMode::Conservative);
     }
 
     #[test]
@@ -922,6 +4730,15 @@ mod tests {
         assert_eq!(toggle_json, "\"toggle\"");
     }
 
+    #[test]
+    fn test_history_audio_serialization() {
+        assert_eq!(serde_json::to_string(&HistoryAudio::Trimmed).unwrap(), "\"trimmed\"");
+        let recording: RecordingSettings = serde_json::from_str(r#"{"mode":"toggle"}"#).unwrap();
+        assert_eq!(recording.history_audio, HistoryAudio::Full);
+        assert_eq!(recording.history_audio_codec, HistoryAudioCodec::Wav);
+        assert_eq!(serde_json::to_string(&HistoryAudioCodec::Opus).unwrap(), "\"opus\"");
+    }
+
     #[test]
     fn test_theme_serialization() {
         let system = Theme::System;
@@ -933,6 +4750,107 @@ mod tests {
         assert_eq!(serde_json::to_string(&dark).unwrap(), "\"dark\"");
     }
 
+    #[test]
+    fn test_default_pipeline_is_valid() {
+        let settings = AudioSettings::default();
+        assert!(settings.validate().is_ok());
+        assert_eq!(
+            settings.enabled_stages(),
+            vec![
+                PipelineStage::Resample,
+                PipelineStage::Vad,
+                PipelineStage::SpeakerFilter,
+                PipelineStage::Transcribe,
+                PipelineStage::PostProcess
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pipeline_reorder_audio_stages() {
+        let mut pipeline = PipelineStage::default_pipeline();
+        // Gain before resampling is allowed
+        pipeline.swap(0, 1);
+        assert!(PipelineStage::validate_pipeline(&pipeline).is_ok());
+    }
+
+    #[test]
+    fn test_pipeline_invalid_orders() {
+        let stage = |stage, enabled| PipelineStageSettings { stage, enabled };
+        use PipelineStage::*;
+
+        let vad_first = vec![
+            stage(Vad, true),
+            stage(Resample, true),
+            stage(Gain, false),
+            stage(NoiseSuppression, false),
+            stage(Transcribe, true),
+            stage(PostProcess, true),
+        ];
+        assert!(PipelineStage::validate_pipeline(&vad_first).is_err());
+
+        let gain_after_transcribe = vec![
+            stage(Resample, true),
+            stage(NoiseSuppression, false),
+            stage(Vad, true),
+            stage(Transcribe, true),
+            stage(Gain, true),
+            stage(PostProcess, true),
+        ];
+        assert!(PipelineStage::validate_pipeline(&gain_after_transcribe).is_err());
+
+        let mut missing = PipelineStage::default_pipeline();
+        missing.retain(|s| s.stage != Gain);
+        assert!(PipelineStage::validate_pipeline(&missing).is_err());
+
+        let mut required_off = PipelineStage::default_pipeline();
+        required_off[0].enabled = false;
+        assert!(PipelineStage::validate_pipeline(&required_off).is_err());
+    }
+
+    #[test]
+    fn test_sanitize_resets_invalid_pipeline() {
+        let mut settings = AudioSettings::default();
+        settings.pipeline.reverse();
+        settings.sanitize();
+        assert_eq!(settings.pipeline, PipelineStage::default_pipeline());
+    }
+
+    #[test]
+    fn test_sanitize_adds_missing_stages() {
+        use PipelineStage::*;
+        let mut settings = AudioSettings::default();
+        // Pipeline saved before the speaker filter existed, with gain moved first
+        settings.pipeline.retain(|s| s.stage != SpeakerFilter);
+        settings.pipeline.swap(0, 1);
+        settings.pipeline[0].enabled = true;
+        settings.sanitize();
+
+        assert!(settings.validate().is_ok());
+        assert_eq!(settings.pipeline[0].stage, Gain);
+        assert!(settings.pipeline[0].enabled);
+        assert_eq!(settings.pipeline[5].stage, Transcribe);
+        assert_eq!(
+            settings.pipeline[4],
+            PipelineStageSettings {
+                stage: SpeakerFilter,
+                enabled: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_speaker_filter_sensitivity() {
+        let mut settings = SpeakerFilterSettings::default();
+        assert!((settings.similarity_threshold() - 0.55).abs() < 1e-6);
+
+        settings.sensitivity = 1.5;
+        assert!(settings.validate().is_err());
+        settings.sanitize();
+        assert_eq!(settings.sensitivity, 1.0);
+        assert!((settings.similarity_threshold() - 0.8).abs() < 1e-6);
+    }
+
     #[test]
     fn test_indicator_position_serialization() {
         assert_eq!(serde_json::to_string(&IndicatorPosition::Cursor).unwrap(), "\"cursor\"");
@@ -0,0 +1,213 @@
+//! Storage Ownership
+//!
+//! Settings, history and models live in the OS user's own directories,
+//! kept private to that user on Unix. Model files may instead come from a
+//! directory shared by every user of the machine; when the user cannot
+//! write to it, its models are still used and downloads go to the user's
+//! own directory.
+
+use super::{config_dir, data_dir, user_models_dir};
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Shared model directory from the settings, with whether it is writable
+static SHARED_MODELS: OnceLock<RwLock<Option<SharedModelsDir>>> = OnceLock::new();
+
+/// A model directory shared by the users of the machine
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct SharedModelsDir {
+    pub path: PathBuf,
+    /// Whether this user can download models into it
+    pub writable: bool,
+}
+
+/// Where each kind of data is stored, for the health report
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StorageReport {
+    pub config_dir: PathBuf,
+    pub data_dir: PathBuf,
+    /// Directory new models are downloaded to
+    pub models_dir: PathBuf,
+    pub shared_models: Option<SharedModelsDir>,
+}
+
+fn shared_models_lock() -> &'static RwLock<Option<SharedModelsDir>> {
+    SHARED_MODELS.get_or_init(|| RwLock::new(None))
+}
+
+/// Use `path` as the shared model directory (None = per-user only)
+///
+/// Called when settings are loaded or saved; writability is checked once
+/// here rather than on every model lookup.
+pub fn set_shared_models_dir(path: Option<&str>) {
+    let shared = path.map(|path| {
+        let path = PathBuf::from(path);
+        let writable = is_writable(&path);
+        if !writable {
+            tracing::info!(
+                "Shared model directory {:?} is read-only, downloads go to {:?}",
+                path,
+                user_models_dir()
+            );
+        }
+        SharedModelsDir { path, writable }
+    });
+    *shared_models_lock().write() = shared;
+}
+
+/// Get the shared model directory, if configured
+pub fn shared_models_dir() -> Option<SharedModelsDir> {
+    shared_models_lock().read().clone()
+}
+
+/// Directories searched for model files, shared first
+pub fn model_search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = shared_models_dir().into_iter().map(|s| s.path).collect();
+    dirs.push(user_models_dir());
+    dirs
+}
+
+/// Create `dir` (and its parents) readable only by the current user
+///
+/// Existing directories are tightened too. Permissions are left to the OS
+/// on Windows, where per-user profile directories are already private.
+pub fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(dir)?.permissions().mode();
+        if mode & 0o077 != 0 {
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    Ok(())
+}
+
+/// Create the configuration and data directories private to the user
+pub fn secure_storage_dirs() {
+    for dir in [config_dir(), data_dir()] {
+        if let Err(e) = create_private_dir(&dir) {
+            tracing::warn!("Failed to secure {:?}: {}", dir, e);
+        }
+    }
+}
+
+/// Current storage locations
+pub fn storage_report() -> StorageReport {
+    StorageReport {
+        config_dir: config_dir(),
+        data_dir: data_dir(),
+        models_dir: super::models_dir(),
+        shared_models: shared_models_dir(),
+    }
+}
+
+/// Ownership and permission problems of the storage directories
+pub fn storage_warnings() -> Vec<String> {
+    let mut warnings = Vec::new();
+    for (name, dir) in [("Settings", config_dir()), ("Data", data_dir())] {
+        if let Some(problem) = dir_problem(&dir) {
+            warnings.push(format!("{} directory {}: {}", name, dir.display(), problem));
+        }
+    }
+
+    if let Some(shared) = shared_models_dir() {
+        if !shared.path.is_dir() {
+            warnings.push(format!(
+                "Shared model directory {} does not exist, only your own models are used",
+                shared.path.display()
+            ));
+        } else if !is_writable(&shared.path) {
+            warnings.push(format!(
+                "Shared model directory {} is read-only: its models are used, new downloads go to {}",
+                shared.path.display(),
+                user_models_dir().display()
+            ));
+        }
+    }
+    warnings
+}
+
+/// Why a private directory is unsafe or unusable, if it is
+fn dir_problem(dir: &Path) -> Option<String> {
+    if !dir.exists() {
+        return None;
+    }
+    if !is_writable(dir) {
+        return Some("not writable by the current user".to_string());
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{MetadataExt, PermissionsExt};
+        let metadata = std::fs::metadata(dir).ok()?;
+        if owner_of_new_file(dir).is_some_and(|uid| uid != metadata.uid()) {
+            return Some("owned by another user".to_string());
+        }
+        if metadata.permissions().mode() & 0o077 != 0 {
+            return Some("readable by other users".to_string());
+        }
+    }
+    None
+}
+
+/// Whether the current user can create files in `dir`
+fn is_writable(dir: &Path) -> bool {
+    probe_new_file(dir).is_some()
+}
+
+/// User id of files the current user creates in `dir`
+#[cfg(unix)]
+fn owner_of_new_file(dir: &Path) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+    probe_new_file(dir).map(|m| m.uid())
+}
+
+/// Metadata of a file created in `dir` and removed again
+fn probe_new_file(dir: &Path) -> Option<std::fs::Metadata> {
+    let path = dir.join(format!(".gigawhisper-probe-{}", std::process::id()));
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .ok()?;
+    let metadata = file.metadata().ok();
+    drop(file);
+    let _ = std::fs::remove_file(&path);
+    metadata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_private_dir() {
+        let root = TempDir::new().unwrap();
+        let dir = root.path().join("a").join("b");
+        create_private_dir(&dir).unwrap();
+        assert!(dir.is_dir());
+        assert_eq!(dir_problem(&dir), None);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o755)).unwrap();
+            assert_eq!(dir_problem(&dir).as_deref(), Some("readable by other users"));
+            create_private_dir(&dir).unwrap();
+            assert_eq!(std::fs::metadata(&dir).unwrap().permissions().mode() & 0o777, 0o700);
+        }
+    }
+
+    #[test]
+    fn test_missing_dir_has_no_problem() {
+        let root = TempDir::new().unwrap();
+        assert_eq!(dir_problem(&root.path().join("missing")), None);
+        assert!(!is_writable(&root.path().join("missing")));
+        assert!(is_writable(root.path()));
+    }
+}
@@ -2,8 +2,16 @@
 //!
 //! Persist settings to disk.
 
-use super::{Settings, SettingsError};
+use super::{
+    create_private_dir, model_search_dirs, shared_models_dir, Settings, SettingsError,
+    DEFAULT_WORKSPACE_ID,
+};
+use parking_lot::RwLock;
 use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Id of the active workspace, scoping history and vocabulary paths
+static ACTIVE_WORKSPACE: OnceLock<RwLock<String>> = OnceLock::new();
 
 /// Get the configuration directory path
 pub fn config_dir() -> PathBuf {
@@ -20,14 +28,79 @@ pub fn config_file() -> PathBuf {
     config_dir().join("settings.toml")
 }
 
-/// Get the models directory path
-pub fn models_dir() -> PathBuf {
+/// Get the application data directory (shared by all workspaces)
+pub fn data_dir() -> PathBuf {
     directories::ProjectDirs::from("com", "gigawhisper", "GigaWhisper")
         .map(|dirs| dirs.data_dir().to_path_buf())
-        .unwrap_or_else(|| {
-            std::env::current_dir().unwrap_or_default().join("models")
-        })
-        .join("models")
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
+}
+
+/// Get the current user's cache directory
+pub fn cache_dir() -> PathBuf {
+    directories::ProjectDirs::from("com", "gigawhisper", "GigaWhisper")
+        .map(|dirs| dirs.cache_dir().to_path_buf())
+        .unwrap_or_else(|| data_dir().join("cache"))
+}
+
+/// Get the current user's own models directory
+pub fn user_models_dir() -> PathBuf {
+    data_dir().join("models")
+}
+
+/// Get the directory models are downloaded to
+///
+/// The shared model directory when one is configured and writable,
+/// otherwise the user's own.
+pub fn models_dir() -> PathBuf {
+    match shared_models_dir() {
+        Some(shared) if shared.writable => shared.path,
+        _ => user_models_dir(),
+    }
+}
+
+/// Get the path of a model file
+///
+/// The shared directory is searched before the user's own; a model found in
+/// neither is placed in the download directory.
+pub fn find_model(filename: &str) -> PathBuf {
+    model_search_dirs()
+        .into_iter()
+        .map(|dir| dir.join(filename))
+        .find(|path| path.exists())
+        .unwrap_or_else(|| models_dir().join(filename))
+}
+
+fn active_workspace_lock() -> &'static RwLock<String> {
+    ACTIVE_WORKSPACE.get_or_init(|| RwLock::new(DEFAULT_WORKSPACE_ID.to_string()))
+}
+
+/// Get the id of the active workspace
+pub fn active_workspace() -> String {
+    active_workspace_lock().read().clone()
+}
+
+/// Point workspace-scoped paths at another workspace
+///
+/// Stores already loaded from the previous workspace must be reloaded.
+pub fn set_active_workspace(id: &str) {
+    *active_workspace_lock().write() = id.to_string();
+}
+
+/// Get the data directory of a workspace
+///
+/// The default workspace uses the data directory itself, so data from
+/// before workspaces existed stays in place.
+pub fn workspace_dir_for(id: &str) -> PathBuf {
+    if id == DEFAULT_WORKSPACE_ID {
+        data_dir()
+    } else {
+        data_dir().join("workspaces").join(id)
+    }
+}
+
+/// Get the data directory of the active workspace
+pub fn workspace_dir() -> PathBuf {
+    workspace_dir_for(&active_workspace())
 }
 
 /// Load settings from disk
@@ -47,7 +120,7 @@ pub fn load_settings() -> Result<Settings, SettingsError> {
 
     // Sanitize settings to ensure values are within valid ranges
     // This prevents crashes or unexpected behavior from manually edited config files
-    let sanitized = settings.sanitize();
+    let mut sanitized = settings.sanitize();
 
     // Log if any values were sanitized
     if settings.recording.max_duration != sanitized.recording.max_duration
@@ -60,17 +133,41 @@ pub fn load_settings() -> Result<Settings, SettingsError> {
         tracing::warn!("Some settings values were out of range and have been sanitized");
     }
 
+    // Voice profiles used to be written here in plain text, from an older
+    // analysis the speaker model can't compare against
+    if has_plaintext_voice_profile(&content) {
+        tracing::info!("Removing the plain-text voice profile, it must be recorded again");
+        sanitized.audio.speaker_filter.profile = None;
+        sanitized.audio.speaker_filter.enabled = false;
+        save_settings(&sanitized)?;
+    }
+
     tracing::info!("Settings loaded from {:?}", path);
     Ok(sanitized)
 }
 
+/// Whether a settings file still holds a speaker embedding
+fn has_plaintext_voice_profile(content: &str) -> bool {
+    toml::from_str::<toml::Value>(content)
+        .ok()
+        .and_then(|config| {
+            config
+                .get("audio")?
+                .get("speaker_filter")?
+                .get("profile")?
+                .get("embedding")
+                .cloned()
+        })
+        .is_some()
+}
+
 /// Save settings to disk
 pub fn save_settings(settings: &Settings) -> Result<(), SettingsError> {
     let path = config_file();
 
-    // Ensure config directory exists
+    // Ensure config directory exists, private to the user
     if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)?;
+        create_private_dir(parent)?;
     }
 
     let content = toml::to_string_pretty(settings)?;
@@ -84,6 +181,25 @@ pub fn save_settings(settings: &Settings) -> Result<(), SettingsError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_plaintext_voice_profile_detected() {
+        let legacy = r#"
+[audio.speaker_filter]
+enabled = true
+
+[audio.speaker_filter.profile]
+embedding = [0.5, 0.25]
+enrolled_at = "2026-01-01T00:00:00Z"
+"#;
+        assert!(has_plaintext_voice_profile(legacy));
+
+        let settings: Settings = toml::from_str(legacy).unwrap();
+        let profile = settings.audio.speaker_filter.profile.as_ref().unwrap();
+        assert_eq!(profile.enrolled_at, "2026-01-01T00:00:00Z");
+        let saved = toml::to_string_pretty(&settings).unwrap();
+        assert!(!has_plaintext_voice_profile(&saved));
+    }
+
     #[test]
     fn test_default_settings_roundtrip() {
         let settings = Settings::default();
@@ -95,4 +211,22 @@ mod tests {
             deserialized.shortcuts.record
         );
     }
+
+    #[test]
+    fn test_find_model_defaults_to_user_dir() {
+        assert_eq!(models_dir(), user_models_dir());
+        assert_eq!(
+            find_model("ggml-missing-model.bin"),
+            user_models_dir().join("ggml-missing-model.bin")
+        );
+    }
+
+    #[test]
+    fn test_workspace_dirs() {
+        assert_eq!(workspace_dir_for(DEFAULT_WORKSPACE_ID), data_dir());
+        assert_eq!(
+            workspace_dir_for("work"),
+            data_dir().join("workspaces").join("work")
+        );
+    }
 }
@@ -0,0 +1,310 @@
+//! Embeddable Engine
+//!
+//! Tauri-free facade over capture, transcription and history, for Rust
+//! applications embedding `gigawhisper_lib`. Progress is reported through
+//! callbacks instead of app events. The desktop app is an adapter over the
+//! same pipeline (`TranscriptionService::process_recording`), adding output
+//! sinks, notifications and the recording indicator.
+//!
+//! ```no_run
+//! # async fn example() -> Result<(), gigawhisper_lib::engine::EngineError> {
+//! use gigawhisper_lib::config::Settings;
+//! use gigawhisper_lib::engine::{Engine, EngineEvent};
+//!
+//! let engine = Engine::new(Settings::default()).on_event(|event| {
+//!     if let EngineEvent::Transcribed { text } = event {
+//!         println!("{}", text);
+//!     }
+//! });
+//!
+//! engine.record()?;
+//! std::thread::sleep(std::time::Duration::from_secs(3));
+//! let transcript = engine.stop().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::audio::{AudioCapture, AudioConfig, AudioError};
+use crate::config::{Settings, SettingsError};
+use crate::history::HistoryEntry;
+use crate::transcription::{PipelineError, TranscriptionService};
+use crate::utils::StageTimings;
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+use thiserror::Error;
+
+/// Engine errors
+#[derive(Error, Debug)]
+pub enum EngineError {
+    #[error("Audio error: {0}")]
+    Audio(#[from] AudioError),
+
+    #[error("Already recording")]
+    AlreadyRecording,
+
+    #[error("Not recording")]
+    NotRecording,
+
+    #[error(transparent)]
+    Pipeline(#[from] PipelineError),
+}
+
+/// Progress reported to callbacks
+#[derive(Debug, Clone, PartialEq)]
+pub enum EngineEvent {
+    RecordingStarted,
+    RecordingStopped { duration_ms: u64 },
+    Processing,
+    Transcribed { text: String },
+    Failed { error: String },
+}
+
+/// Callback receiving engine events
+pub type EventCallback = Box<dyn Fn(&EngineEvent) + Send + Sync>;
+
+/// Result of a transcription
+#[derive(Debug, Clone, Serialize)]
+pub struct Transcript {
+    /// Final text after vocabulary rules and learned capitalization
+    pub text: String,
+    /// Transcriber output before post-processing
+    pub raw_text: String,
+    pub provider: String,
+    pub language: Option<String>,
+    pub audio_duration_ms: u64,
+    pub processing_time_ms: u64,
+    pub timings: StageTimings,
+    /// Alert keywords found in the text
+    pub alerts: Vec<String>,
+}
+
+/// Recording in progress
+struct ActiveRecording {
+    capture: AudioCapture,
+    started_at: Instant,
+}
+
+/// Capture and transcription engine
+pub struct Engine {
+    settings: RwLock<Settings>,
+    service: Arc<TranscriptionService>,
+    recording: Mutex<Option<ActiveRecording>>,
+    callbacks: Vec<EventCallback>,
+    save_history: bool,
+}
+
+impl Engine {
+    /// Create an engine with the given settings
+    pub fn new(settings: Settings) -> Self {
+        Self {
+            settings: RwLock::new(settings),
+            service: Arc::new(TranscriptionService::new()),
+            recording: Mutex::new(None),
+            callbacks: Vec::new(),
+            save_history: true,
+        }
+    }
+
+    /// Create an engine with the settings saved by the desktop app
+    pub fn with_saved_settings() -> Result<Self, SettingsError> {
+        Ok(Self::new(crate::config::load_settings()?))
+    }
+
+    /// Register a callback for engine events
+    pub fn on_event(mut self, callback: impl Fn(&EngineEvent) + Send + Sync + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Whether transcriptions are added to the shared history (default: true)
+    pub fn save_history(mut self, enabled: bool) -> Self {
+        self.save_history = enabled;
+        self
+    }
+
+    /// Current settings
+    pub fn settings(&self) -> Settings {
+        self.settings.read().clone()
+    }
+
+    /// Replace the settings, used from the next recording on
+    pub fn set_settings(&self, settings: Settings) {
+        *self.settings.write() = settings;
+    }
+
+    /// Underlying transcription service (model preloading, status)
+    pub fn service(&self) -> &Arc<TranscriptionService> {
+        &self.service
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording.lock().is_some()
+    }
+
+    /// Start recording from the configured input device
+    pub fn record(&self) -> Result<(), EngineError> {
+        let mut recording = self.recording.lock();
+        if recording.is_some() {
+            return Err(EngineError::AlreadyRecording);
+        }
+
+        let audio_config = {
+            let settings = self.settings.read();
+            AudioConfig {
+                buffer_duration_ms: settings.recording.max_duration * 1000,
+                capture_mode: settings.audio.capture_mode,
+                ..AudioConfig::default()
+            }
+        };
+        let input_device = self.settings.read().audio.input_device.clone();
+        let capture = AudioCapture::with_device_id(input_device.as_deref(), audio_config)?;
+        capture.start()?;
+
+        *recording = Some(ActiveRecording {
+            capture,
+            started_at: Instant::now(),
+        });
+        drop(recording);
+
+        self.emit(EngineEvent::RecordingStarted);
+        Ok(())
+    }
+
+    /// Stop recording and transcribe what was captured
+    pub async fn stop(&self) -> Result<Transcript, EngineError> {
+        let drain_started = Instant::now();
+        let (samples, sample_rate) = self.stop_capture()?;
+        let capture_drain_ms = drain_started.elapsed().as_millis() as u64;
+
+        let mut transcript = self.transcribe(samples, sample_rate).await?;
+        transcript.timings.capture_drain_ms = capture_drain_ms;
+        Ok(transcript)
+    }
+
+    /// Stop recording and discard the audio
+    pub fn cancel(&self) -> Result<(), EngineError> {
+        self.stop_capture().map(|_| ())
+    }
+
+    /// Transcribe mono samples recorded at `sample_rate`
+    ///
+    /// Runs the same pipeline stages as a recording made in the desktop app.
+    pub async fn transcribe(
+        &self,
+        samples: Vec<f32>,
+        sample_rate: u32,
+    ) -> Result<Transcript, EngineError> {
+        self.emit(EngineEvent::Processing);
+
+        let settings = self.settings();
+        let mut processed = match self
+            .service
+            .run_pipeline(samples, sample_rate, &settings, None)
+            .await
+        {
+            Ok(processed) => processed,
+            Err(e) => {
+                self.emit(EngineEvent::Failed {
+                    error: e.to_string(),
+                });
+                return Err(e.into());
+            }
+        };
+
+        if self.save_history {
+            if processed.wants_translation {
+                processed.translation = self
+                    .service
+                    .learning_translation(&processed.speech, &settings)
+                    .await;
+            }
+            if let Some(id) = processed.save_to_history(None, None) {
+                let codec = processed.history_audio_codec;
+                if let Err(e) = crate::history::compress_entry_audio(&id, codec) {
+                    tracing::warn!("Keeping WAV audio for {}: {}", id, e);
+                }
+            }
+        }
+        processed.record_metrics(&settings, processed.timings, false);
+        self.emit(EngineEvent::Transcribed {
+            text: processed.text.clone(),
+        });
+
+        Ok(Transcript {
+            audio_duration_ms: processed.audio_duration_ms(),
+            text: processed.text,
+            raw_text: processed.transcription.text,
+            provider: processed.transcription.provider,
+            language: processed.transcription.language,
+            processing_time_ms: processed.transcription.duration_ms,
+            timings: processed.timings,
+            alerts: processed.alerts,
+        })
+    }
+
+    /// Transcribe a WAV file
+    pub async fn transcribe_file(&self, path: &Path) -> Result<Transcript, EngineError> {
+        let (samples, sample_rate) = crate::audio::read_wav_mono(path)?;
+        self.transcribe(samples, sample_rate).await
+    }
+
+    /// Transcription history shared with the desktop app, newest first
+    pub fn history(&self) -> Vec<HistoryEntry> {
+        crate::history::get_history().read().entries()
+    }
+
+    fn stop_capture(&self) -> Result<(Vec<f32>, u32), EngineError> {
+        let recording = self.recording.lock().take().ok_or(EngineError::NotRecording)?;
+        let result = recording.capture.stop()?;
+        self.emit(EngineEvent::RecordingStopped {
+            duration_ms: recording.started_at.elapsed().as_millis() as u64,
+        });
+        Ok(result)
+    }
+
+    fn emit(&self, event: EngineEvent) {
+        for callback in &self.callbacks {
+            callback(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_record_silence_reports_no_speech() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut settings = Settings::default();
+        settings.audio.input_device = Some("virtual:silence".to_string());
+        settings.audio.vad.enabled = true;
+
+        let sink = events.clone();
+        let engine = Engine::new(settings)
+            .save_history(false)
+            .on_event(move |event| sink.lock().push(event.clone()));
+
+        assert!(matches!(engine.stop().await, Err(EngineError::NotRecording)));
+        engine.record().unwrap();
+        assert!(matches!(engine.record(), Err(EngineError::AlreadyRecording)));
+        tokio::time::sleep(Duration::from_millis(300)).await;
+
+        let result = engine.stop().await;
+        assert!(matches!(result, Err(EngineError::Pipeline(_))));
+        assert!(!engine.is_recording());
+
+        let events = events.lock();
+        assert_eq!(events[0], EngineEvent::RecordingStarted);
+        assert!(matches!(
+            events[1],
+            EngineEvent::RecordingStopped { duration_ms } if duration_ms >= 300
+        ));
+        assert_eq!(events[2], EngineEvent::Processing);
+        assert!(matches!(events[3], EngineEvent::Failed { .. }));
+    }
+}
@@ -0,0 +1,417 @@
+//! C API
+//!
+//! C ABI over the embeddable engine, exported by the `cdylib` and
+//! `staticlib` targets so non-Rust applications (Python through ctypes, C#
+//! through P/Invoke) can transcribe locally. The matching header is
+//! `include/gigawhisper.h`.
+//!
+//! Conventions:
+//! - functions returning `int32_t` return 0 on success and -1 on failure
+//! - functions returning a pointer return NULL on failure
+//! - after a failure, `gw_last_error` describes it (per thread)
+//! - a panic is caught at the boundary and reported as a failure
+//! - results are UTF-8 JSON strings, freed with `gw_string_free`
+//!
+//! Transcriptions made through this API are not added to the app history.
+
+use crate::config::{models_dir, Settings, WhisperModel};
+use crate::engine::Engine;
+use crate::models::DownloadProgress;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Opaque engine handle
+pub struct GwEngine {
+    engine: Engine,
+}
+
+/// Download progress callback: percentage (0-100) and the caller's user data
+pub type GwProgressCallback = extern "C" fn(percentage: f32, user_data: *mut c_void);
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Run `future` to completion on the library's runtime
+///
+/// Fails instead of panicking when called from a thread already driving a
+/// tokio runtime, where blocking on another one isn't allowed.
+fn block_on<F: Future>(future: F) -> Result<F::Output, String> {
+    static RUNTIME: OnceLock<Result<tokio::runtime::Runtime, String>> = OnceLock::new();
+    if tokio::runtime::Handle::try_current().is_ok() {
+        return Err("cannot be called from inside an async runtime".to_string());
+    }
+    let runtime = RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .map_err(|e| format!("Failed to start async runtime: {}", e))
+        })
+        .as_ref()
+        .map_err(Clone::clone)?;
+    Ok(runtime.block_on(future))
+}
+
+/// Run an exported function's body, returning `failed` if it panics
+///
+/// Unwinding into a C caller aborts the host process, so the panic is
+/// recorded as the last error instead.
+fn guard<T>(failed: T, body: impl FnOnce() -> T) -> T {
+    match std::panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(value) => value,
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_error(format!("internal error: {}", message));
+            failed
+        }
+    }
+}
+
+fn set_error(message: impl std::fmt::Display) {
+    let message = CString::new(message.to_string().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|error| *error.borrow_mut() = Some(message));
+}
+
+fn clear_error() {
+    LAST_ERROR.with(|error| *error.borrow_mut() = None);
+}
+
+/// Borrow a C string argument
+///
+/// # Safety
+/// `ptr` must be NULL or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> Result<&'a str, String> {
+    if ptr.is_null() {
+        return Err(format!("{} is NULL", name));
+    }
+    CStr::from_ptr(ptr)
+        .to_str()
+        .map_err(|_| format!("{} is not valid UTF-8", name))
+}
+
+/// JSON of `result` as an owned C string, or NULL with the error recorded
+fn json_result<T: Serialize>(result: Result<T, String>) -> *mut c_char {
+    let json = result.and_then(|value| serde_json::to_string(&value).map_err(|e| e.to_string()));
+    match json.map(|json| CString::new(json).map_err(|e| e.to_string())) {
+        Ok(Ok(json)) => {
+            clear_error();
+            json.into_raw()
+        }
+        Ok(Err(e)) | Err(e) => {
+            set_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+fn status(result: Result<(), String>) -> i32 {
+    match result {
+        Ok(()) => {
+            clear_error();
+            0
+        }
+        Err(e) => {
+            set_error(e);
+            -1
+        }
+    }
+}
+
+/// Create an engine
+///
+/// `settings_toml` uses the format of the app's `settings.toml`; NULL loads
+/// the settings saved by the app. Returns NULL on failure.
+///
+/// # Safety
+/// `settings_toml` must be NULL or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gw_engine_new(settings_toml: *const c_char) -> *mut GwEngine {
+    guard(std::ptr::null_mut(), || engine_new(settings_toml))
+}
+
+unsafe fn engine_new(settings_toml: *const c_char) -> *mut GwEngine {
+    let settings = if settings_toml.is_null() {
+        crate::config::load_settings().map_err(|e| e.to_string())
+    } else {
+        str_arg(settings_toml, "settings_toml").and_then(|toml| {
+            toml::from_str::<Settings>(toml)
+                .map(|settings| settings.sanitize())
+                .map_err(|e| e.to_string())
+        })
+    };
+
+    match settings {
+        Ok(settings) => {
+            clear_error();
+            Box::into_raw(Box::new(GwEngine {
+                engine: Engine::new(settings).save_history(false),
+            }))
+        }
+        Err(e) => {
+            set_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// Destroy an engine
+///
+/// # Safety
+/// `engine` must be NULL or a handle from `gw_engine_new` not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn gw_engine_free(engine: *mut GwEngine) {
+    guard((), || {
+        if !engine.is_null() {
+            drop(Box::from_raw(engine));
+        }
+    })
+}
+
+/// Load the configured model ahead of the first transcription
+///
+/// # Safety
+/// `engine` must be a live handle from `gw_engine_new`.
+#[no_mangle]
+pub unsafe extern "C" fn gw_engine_preload(engine: *const GwEngine) -> i32 {
+    guard(-1, || {
+        let Some(engine) = engine.as_ref() else {
+            return status(Err("engine is NULL".to_string()));
+        };
+        let settings = engine.engine.settings();
+        status(engine.engine.service().preload_model(&settings))
+    })
+}
+
+/// Transcribe a WAV file, returning the transcript as JSON
+///
+/// # Safety
+/// `engine` must be a live handle and `path` a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gw_transcribe_file(
+    engine: *const GwEngine,
+    path: *const c_char,
+) -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        let Some(engine) = engine.as_ref() else {
+            return json_result::<()>(Err("engine is NULL".to_string()));
+        };
+        json_result(str_arg(path, "path").and_then(|path| {
+            block_on(engine.engine.transcribe_file(Path::new(path)))?.map_err(|e| e.to_string())
+        }))
+    })
+}
+
+/// Transcribe mono float samples, returning the transcript as JSON
+///
+/// # Safety
+/// `engine` must be a live handle and `samples` point to `len` floats.
+#[no_mangle]
+pub unsafe extern "C" fn gw_transcribe_buffer(
+    engine: *const GwEngine,
+    samples: *const f32,
+    len: usize,
+    sample_rate: u32,
+) -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        let Some(engine) = engine.as_ref() else {
+            return json_result::<()>(Err("engine is NULL".to_string()));
+        };
+        if samples.is_null() || sample_rate == 0 {
+            return json_result::<()>(Err("samples is NULL or sample_rate is 0".to_string()));
+        }
+
+        let samples = std::slice::from_raw_parts(samples, len).to_vec();
+        json_result(
+            block_on(engine.engine.transcribe(samples, sample_rate))
+                .and_then(|result| result.map_err(|e| e.to_string())),
+        )
+    })
+}
+
+/// List Whisper models and whether they are downloaded, as JSON
+#[no_mangle]
+pub extern "C" fn gw_models_list() -> *mut c_char {
+    guard(std::ptr::null_mut(), || {
+        json_result(Ok(crate::models::list_models()))
+    })
+}
+
+/// Download a model (`tiny`, `base`, `small`, `medium`, `large`)
+///
+/// Blocks until the download finishes. `progress` may be NULL.
+///
+/// # Safety
+/// `model` must be a NUL-terminated string. `user_data` is passed back to
+/// `progress` as is, possibly from another thread.
+#[no_mangle]
+pub unsafe extern "C" fn gw_model_download(
+    model: *const c_char,
+    progress: Option<GwProgressCallback>,
+    user_data: *mut c_void,
+) -> i32 {
+    guard(-1, || model_download(model, progress, user_data))
+}
+
+unsafe fn model_download(
+    model: *const c_char,
+    progress: Option<GwProgressCallback>,
+    user_data: *mut c_void,
+) -> i32 {
+    /// Caller-owned pointer handed back to the callback
+    struct UserData(*mut c_void);
+    // SAFETY: the caller guarantees `user_data` may be used from the download thread
+    unsafe impl Send for UserData {}
+
+    let model = match str_arg(model, "model").and_then(str::parse::<WhisperModel>) {
+        Ok(model) => model,
+        Err(e) => return status(Err(e)),
+    };
+
+    let callback = progress.map(|progress| {
+        let user_data = UserData(user_data);
+        Box::new(move |p: DownloadProgress| {
+            let user_data = &user_data;
+            progress(p.percentage, user_data.0)
+        }) as crate::models::ProgressCallback
+    });
+
+    let download = crate::models::download_model(&model, models_dir(), callback);
+    status(block_on(download).and_then(|result| result.map(|_| ()).map_err(|e| e.to_string())))
+}
+
+/// Delete a downloaded model
+///
+/// # Safety
+/// `model` must be a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn gw_model_delete(model: *const c_char) -> i32 {
+    guard(-1, || {
+        status(
+            str_arg(model, "model")
+                .and_then(str::parse::<WhisperModel>)
+                .and_then(|model| crate::models::delete_model(&model).map_err(|e| e.to_string())),
+        )
+    })
+}
+
+/// Description of the last failure on this thread, or NULL
+///
+/// The pointer is valid until the next call into the library on this thread.
+#[no_mangle]
+pub extern "C" fn gw_last_error() -> *const c_char {
+    guard(std::ptr::null(), || {
+        LAST_ERROR.with(|error| {
+            error
+                .borrow()
+                .as_ref()
+                .map_or(std::ptr::null(), |message| message.as_ptr())
+        })
+    })
+}
+
+/// Free a string returned by the library
+///
+/// # Safety
+/// `string` must be NULL or a string returned by this library, not freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn gw_string_free(string: *mut c_char) {
+    guard((), || {
+        if !string.is_null() {
+            drop(CString::from_raw(string));
+        }
+    })
+}
+
+/// Library version
+#[no_mangle]
+pub extern "C" fn gw_version() -> *const c_char {
+    static VERSION: OnceLock<CString> = OnceLock::new();
+    guard(std::ptr::null(), || {
+        VERSION
+            .get_or_init(|| CString::new(env!("CARGO_PKG_VERSION")).unwrap_or_default())
+            .as_ptr()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> Option<String> {
+        let error = gw_last_error();
+        (!error.is_null()).then(|| unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned())
+    }
+
+    #[test]
+    fn test_invalid_arguments_set_last_error() {
+        unsafe {
+            assert!(gw_transcribe_file(std::ptr::null(), std::ptr::null()).is_null());
+            assert_eq!(last_error().as_deref(), Some("engine is NULL"));
+
+            let model = CString::new("huge").unwrap();
+            assert_eq!(gw_model_delete(model.as_ptr()), -1);
+            assert_eq!(last_error().as_deref(), Some("Unknown model: huge"));
+
+            let toml = CString::new("recording = 5").unwrap();
+            assert!(gw_engine_new(toml.as_ptr()).is_null());
+            assert!(last_error().is_some());
+        }
+    }
+
+    #[test]
+    fn test_panics_become_errors() {
+        assert_eq!(guard(-1, || panic!("boom")), -1);
+        assert_eq!(last_error().as_deref(), Some("internal error: boom"));
+
+        let result = guard(std::ptr::null_mut::<c_char>(), || panic!("index {}", 3));
+        assert!(result.is_null());
+        assert_eq!(last_error().as_deref(), Some("internal error: index 3"));
+    }
+
+    #[test]
+    fn test_block_on_inside_runtime_fails() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let result = runtime.block_on(async { block_on(async { 1 }) });
+        assert!(result.is_err());
+        assert_eq!(block_on(async { 1 }), Ok(1));
+    }
+
+    #[test]
+    fn test_models_list_is_json() {
+        let json = gw_models_list();
+        assert!(!json.is_null());
+        assert_eq!(last_error(), None);
+
+        let text = unsafe { CStr::from_ptr(json) }.to_str().unwrap().to_string();
+        unsafe { gw_string_free(json) };
+        let models: Vec<serde_json::Value> = serde_json::from_str(&text).unwrap();
+        assert!(models.iter().any(|m| m["model"] == "tiny"));
+    }
+
+    #[test]
+    fn test_transcribe_buffer_reports_pipeline_error() {
+        let toml = CString::new("").unwrap();
+        unsafe {
+            let engine = gw_engine_new(toml.as_ptr());
+            assert!(!engine.is_null());
+
+            // Too short to transcribe
+            let samples = [0.0f32; 160];
+            let result = gw_transcribe_buffer(engine, samples.as_ptr(), samples.len(), 16000);
+            assert!(result.is_null());
+            assert_eq!(last_error().as_deref(), Some("Recording too short"));
+
+            gw_engine_free(engine);
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! History Encryption
+//!
+//! Encryption of the history file at rest with ChaCha20-Poly1305. The key is
+//! generated on first use and kept in the credential store, so a copy of the
+//! file cannot be read outside the user's account. No new key is generated
+//! while an encrypted history file exists, since it could never be opened
+//! again. An encrypted file is decrypted on load whatever the setting, which
+//! only decides how the next save is written.
+
+use crate::config::{SecretsError, SecretsManager};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+/// Cipher named in encrypted history files
+const CIPHER: &str = "chacha20poly1305";
+
+/// Bytes of the nonce stored before the ciphertext
+const NONCE_LEN: usize = 12;
+
+/// Whether saves encrypt the history file
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Key read from the credential store, kept for later saves
+static KEY: Mutex<Option<HistoryKey>> = Mutex::new(None);
+
+/// Key encrypting the history file
+pub type HistoryKey = Zeroizing<[u8; 32]>;
+
+/// Errors reading or writing an encrypted history file
+#[derive(Debug, Error)]
+pub enum HistoryCryptoError {
+    #[error("History key unavailable: {0}")]
+    Key(#[from] SecretsError),
+
+    #[error("Invalid history key in the credential store")]
+    InvalidKey,
+
+    #[error("The history is encrypted but its key is missing from the credential store")]
+    MissingKey,
+
+    #[error("Failed to encrypt history")]
+    Encrypt,
+
+    #[error("Failed to decrypt history: wrong key or damaged file")]
+    Decrypt,
+
+    #[error("Invalid encrypted history file: {0}")]
+    Format(String),
+}
+
+/// On-disk form of an encrypted history
+#[derive(Serialize, Deserialize)]
+struct EncryptedFile {
+    cipher: String,
+    /// Base64 of the nonce followed by the ciphertext
+    data: String,
+}
+
+/// Encrypt the history file from the next save on, or stop encrypting it
+pub fn set_history_encryption(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+/// Whether saves encrypt the history file
+pub fn history_encryption_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Key of the history file, created in the credential store if missing
+///
+/// Fails instead of creating a key when the history file is already
+/// encrypted.
+pub fn history_key() -> Result<HistoryKey, HistoryCryptoError> {
+    let mut cached = KEY.lock();
+    if let Some(key) = cached.as_ref() {
+        return Ok(key.clone());
+    }
+
+    let stored = SecretsManager::get_history_key();
+    let key = match stored_key(stored, history_file_encrypted)? {
+        Some(key) => key,
+        None => {
+            let mut key = Zeroizing::new([0u8; 32]);
+            key.copy_from_slice(&ChaCha20Poly1305::generate_key(&mut OsRng));
+            SecretsManager::set_history_key(&BASE64.encode(*key))?;
+            key
+        }
+    };
+    *cached = Some(key.clone());
+    Ok(key)
+}
+
+/// Key from the credential store lookup, or None when a new one may be created
+fn stored_key(
+    stored: Result<String, SecretsError>,
+    file_encrypted: impl FnOnce() -> bool,
+) -> Result<Option<HistoryKey>, HistoryCryptoError> {
+    match stored {
+        Ok(encoded) => decode_key(&encoded).map(Some),
+        Err(SecretsError::NotFound(_)) if file_encrypted() => Err(HistoryCryptoError::MissingKey),
+        Err(SecretsError::NotFound(_)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Whether the history file on disk is encrypted
+fn history_file_encrypted() -> bool {
+    std::fs::read_to_string(super::history_file_path())
+        .is_ok_and(|content| is_encrypted_history(&content))
+}
+
+fn decode_key(encoded: &str) -> Result<HistoryKey, HistoryCryptoError> {
+    let bytes = Zeroizing::new(
+        BASE64
+            .decode(encoded.trim())
+            .map_err(|_| HistoryCryptoError::InvalidKey)?,
+    );
+    let mut key = Zeroizing::new([0u8; 32]);
+    if bytes.len() != key.len() {
+        return Err(HistoryCryptoError::InvalidKey);
+    }
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
+
+/// Whether `content` is an encrypted history file
+pub fn is_encrypted_history(content: &str) -> bool {
+    serde_json::from_str::<EncryptedFile>(content).is_ok()
+}
+
+/// Encrypt serialized history for writing to disk
+pub fn encrypt_history(plain: &str, key: &[u8; 32]) -> Result<String, HistoryCryptoError> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plain.as_bytes())
+        .map_err(|_| HistoryCryptoError::Encrypt)?;
+
+    let mut data = nonce.to_vec();
+    data.extend_from_slice(&ciphertext);
+    let file = EncryptedFile {
+        cipher: CIPHER.to_string(),
+        data: BASE64.encode(data),
+    };
+    serde_json::to_string_pretty(&file).map_err(|e| HistoryCryptoError::Format(e.to_string()))
+}
+
+/// Decrypt an encrypted history file back to serialized history
+pub fn decrypt_history(content: &str, key: &[u8; 32]) -> Result<String, HistoryCryptoError> {
+    let file: EncryptedFile =
+        serde_json::from_str(content).map_err(|e| HistoryCryptoError::Format(e.to_string()))?;
+    if file.cipher != CIPHER {
+        return Err(HistoryCryptoError::Format(format!(
+            "unsupported cipher '{}'",
+            file.cipher
+        )));
+    }
+    let data = BASE64
+        .decode(&file.data)
+        .map_err(|e| HistoryCryptoError::Format(e.to_string()))?;
+    if data.len() < NONCE_LEN {
+        return Err(HistoryCryptoError::Format("truncated data".to_string()));
+    }
+
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let plain = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| HistoryCryptoError::Decrypt)?;
+    String::from_utf8(plain).map_err(|e| HistoryCryptoError::Format(e.to_string()))
+}
+
+/// Serialized history as written to disk, encrypted when enabled
+pub(super) fn seal_history(plain: String) -> Result<String, HistoryCryptoError> {
+    if !history_encryption_enabled() {
+        return Ok(plain);
+    }
+    let plain = Zeroizing::new(plain);
+    let key = history_key()?;
+    encrypt_history(&plain, &key)
+}
+
+/// Serialized history from the file content, decrypted if needed
+pub(super) fn open_history(content: String) -> Result<String, HistoryCryptoError> {
+    if !is_encrypted_history(&content) {
+        return Ok(content);
+    }
+    let key = history_key()?;
+    decrypt_history(&content, &key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: [u8; 32] = [7; 32];
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let plain = r#"{"entries":[{"text":"secret meeting notes"}]}"#;
+        let sealed = encrypt_history(plain, &KEY).unwrap();
+
+        assert!(is_encrypted_history(&sealed));
+        assert!(!sealed.contains("secret"));
+        assert_eq!(decrypt_history(&sealed, &KEY).unwrap(), plain);
+        // A fresh nonce for every save
+        assert_ne!(encrypt_history(plain, &KEY).unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_decrypt_rejects_wrong_key_and_tampering() {
+        let sealed = encrypt_history(r#"{"entries":[]}"#, &KEY).unwrap();
+        assert!(matches!(
+            decrypt_history(&sealed, &[8; 32]),
+            Err(HistoryCryptoError::Decrypt)
+        ));
+
+        let mut file: EncryptedFile = serde_json::from_str(&sealed).unwrap();
+        let mut data = BASE64.decode(&file.data).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        file.data = BASE64.encode(data);
+        let tampered = serde_json::to_string(&file).unwrap();
+        assert!(matches!(
+            decrypt_history(&tampered, &KEY),
+            Err(HistoryCryptoError::Decrypt)
+        ));
+    }
+
+    #[test]
+    fn test_plain_history_is_not_encrypted() {
+        assert!(!is_encrypted_history(r#"{"entries":[]}"#));
+        assert!(!is_encrypted_history("not json"));
+        assert_eq!(
+            open_history(r#"{"entries":[]}"#.to_string()).unwrap(),
+            r#"{"entries":[]}"#
+        );
+    }
+
+    #[test]
+    fn test_no_new_key_for_encrypted_file() {
+        let missing = || Err(SecretsError::NotFound("history_key".to_string()));
+        assert!(matches!(
+            stored_key(missing(), || true),
+            Err(HistoryCryptoError::MissingKey)
+        ));
+        assert!(stored_key(missing(), || false).unwrap().is_none());
+
+        let stored = stored_key(Ok(BASE64.encode(KEY)), || true).unwrap();
+        assert_eq!(*stored.unwrap(), KEY);
+    }
+
+    #[test]
+    fn test_decode_key() {
+        assert_eq!(*decode_key(&BASE64.encode(KEY)).unwrap(), KEY);
+        assert!(decode_key("c2hvcnQ=").is_err());
+        assert!(decode_key("not base64!").is_err());
+    }
+}
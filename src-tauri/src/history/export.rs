@@ -0,0 +1,287 @@
+//! History Export
+//!
+//! Renders history entries as JSON, CSV or Markdown for archiving or for
+//! analysis in other tools. Exports carry the text, tags and metadata; audio,
+//! screenshots and waveforms stay in the app. Translations kept in language
+//! learning mode are exported next to the original text.
+
+use super::HistoryEntry;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Columns of CSV exports
+const CSV_COLUMNS: [&str; 10] = [
+    "id",
+    "timestamp",
+    "title",
+    "text",
+    "translation",
+    "note",
+    "tags",
+    "provider",
+    "language",
+    "duration_ms",
+];
+
+/// Export file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoryExportFormat {
+    Json,
+    Csv,
+    Markdown,
+}
+
+impl HistoryExportFormat {
+    /// File extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+/// An entry as written to JSON exports
+#[derive(Serialize)]
+struct ExportedEntry<'a> {
+    id: &'a str,
+    timestamp: &'a str,
+    title: &'a str,
+    text: &'a str,
+    translation: Option<&'a str>,
+    note: Option<&'a str>,
+    tags: &'a [String],
+    provider: &'a str,
+    language: Option<&'a str>,
+    duration_ms: u64,
+}
+
+impl<'a> From<&'a HistoryEntry> for ExportedEntry<'a> {
+    fn from(entry: &'a HistoryEntry) -> Self {
+        Self {
+            id: &entry.id,
+            timestamp: &entry.timestamp,
+            title: &entry.title,
+            text: &entry.text,
+            translation: entry.translation.as_deref(),
+            note: entry.note.as_deref(),
+            tags: &entry.tags,
+            provider: &entry.provider,
+            language: entry.language.as_deref(),
+            duration_ms: entry.duration_ms,
+        }
+    }
+}
+
+/// Render `entries` in `format`, keeping their order
+pub fn render_history(entries: &[HistoryEntry], format: HistoryExportFormat) -> String {
+    match format {
+        HistoryExportFormat::Json => render_json(entries),
+        HistoryExportFormat::Csv => render_csv(entries),
+        HistoryExportFormat::Markdown => render_markdown(entries),
+    }
+}
+
+fn render_json(entries: &[HistoryEntry]) -> String {
+    let rows: Vec<ExportedEntry> = entries.iter().map(ExportedEntry::from).collect();
+    // Borrowed strings and integers always serialize
+    let mut out = serde_json::to_string_pretty(&rows).unwrap_or_default();
+    out.push('\n');
+    out
+}
+
+/// RFC 4180 CSV with a header row and CRLF line endings
+fn render_csv(entries: &[HistoryEntry]) -> String {
+    let mut out = CSV_COLUMNS.join(",");
+    out.push_str("\r\n");
+    for entry in entries {
+        let duration = entry.duration_ms.to_string();
+        let tags = entry.tags.join("; ");
+        let fields = [
+            entry.id.as_str(),
+            &entry.timestamp,
+            &entry.title,
+            &entry.text,
+            entry.translation.as_deref().unwrap_or_default(),
+            entry.note.as_deref().unwrap_or_default(),
+            &tags,
+            &entry.provider,
+            entry.language.as_deref().unwrap_or_default(),
+            &duration,
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        out.push_str(&row.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Quote a field holding a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// One section per entry: title, metadata line, text and note
+///
+/// A translated entry shows its text and translation side by side.
+fn render_markdown(entries: &[HistoryEntry]) -> String {
+    let mut out = String::from("# Transcription History\n");
+    for entry in entries {
+        let title = if entry.title.trim().is_empty() {
+            entry.timestamp.as_str()
+        } else {
+            entry.title.trim()
+        };
+        out.push_str(&format!("\n## {}\n\n", title.replace('\n', " ")));
+
+        let mut details = vec![entry.timestamp.clone(), entry.provider.clone()];
+        details.extend(entry.language.clone());
+        details.push(format_duration(entry.duration_ms));
+        out.push_str(&format!("*{}*\n\n", details.join(" · ")));
+
+        match entry.translation.as_deref() {
+            Some(translation) => {
+                out.push_str("| Original | English |\n| --- | --- |\n");
+                out.push_str(&format!(
+                    "| {} | {} |\n",
+                    table_cell(&entry.text),
+                    table_cell(translation)
+                ));
+            }
+            None => {
+                out.push_str(entry.text.trim());
+                out.push('\n');
+            }
+        }
+
+        if let Some(note) = entry.note.as_deref().filter(|n| !n.trim().is_empty()) {
+            out.push('\n');
+            for line in note.trim().lines().map(str::trim_end) {
+                if line.is_empty() {
+                    out.push_str(">\n");
+                } else {
+                    out.push_str(&format!("> {}\n", line));
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Text as a single Markdown table cell
+fn table_cell(text: &str) -> String {
+    text.trim()
+        .replace('|', "\\|")
+        .lines()
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("<br>")
+}
+
+/// Duration as "1:05" or "1:02:05"
+fn format_duration(duration_ms: u64) -> String {
+    let seconds = duration_ms / 1000;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: &str, text: &str, note: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            text: text.to_string(),
+            timestamp: "2026-03-02T09:15:00Z".to_string(),
+            duration_ms: 65_400,
+            provider: "whisper.cpp".to_string(),
+            language: Some("en".to_string()),
+            audio_path: Some("/tmp/audio.wav".to_string()),
+            waveform: vec![1, 2, 3],
+            screenshot_path: None,
+            alerts: Vec::new(),
+            title: "Standup".to_string(),
+            note: note.map(str::to_string),
+            recording_ms: None,
+            segments: Vec::new(),
+            translation: None,
+            tags: Vec::new(),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_render_csv_escapes_fields() {
+        let entries = [entry("a", "Hello, \"world\"\nbye", None)];
+        assert_eq!(
+            render_history(&entries, HistoryExportFormat::Csv),
+            "id,timestamp,title,text,translation,note,tags,provider,language,duration_ms\r\n\
+             a,2026-03-02T09:15:00Z,Standup,\"Hello, \"\"world\"\"\nbye\",,,,whisper.cpp,en,65400\r\n"
+        );
+    }
+
+    #[test]
+    fn test_render_json_leaves_out_media() {
+        let mut tagged = entry("a", "Hello", Some("Follow up"));
+        tagged.tags = vec!["work".to_string(), "q3 roadmap".to_string()];
+        let json = render_history(&[tagged], HistoryExportFormat::Json);
+        let rows: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(rows[0]["text"], "Hello");
+        assert_eq!(rows[0]["note"], "Follow up");
+        assert_eq!(rows[0]["tags"], serde_json::json!(["work", "q3 roadmap"]));
+        assert!(rows[0].get("audio_path").is_none());
+        assert!(rows[0].get("waveform").is_none());
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let entries = [entry("a", "Hello there.", Some("First line\n\nSecond line"))];
+        assert_eq!(
+            render_history(&entries, HistoryExportFormat::Markdown),
+            "# Transcription History\n\
+             \n\
+             ## Standup\n\
+             \n\
+             *2026-03-02T09:15:00Z · whisper.cpp · en · 1:05*\n\
+             \n\
+             Hello there.\n\
+             \n\
+             > First line\n\
+             >\n\
+             > Second line\n"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_side_by_side() {
+        let mut translated = entry("a", "Hola. ¿Qué tal?", None);
+        translated.translation = Some("Hello.\nHow | are you?".to_string());
+        let markdown = render_history(&[translated], HistoryExportFormat::Markdown);
+        assert!(markdown.contains(
+            "| Original | English |\n\
+             | --- | --- |\n\
+             | Hola. ¿Qué tal? | Hello.<br>How \\| are you? |\n"
+        ));
+
+        let json = render_history(&[entry("b", "Hi", None)], HistoryExportFormat::Json);
+        let rows: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(rows[0]["translation"].is_null());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(5_000), "0:05");
+        assert_eq!(format_duration(3_725_000), "1:02:05");
+    }
+}
@@ -0,0 +1,509 @@
+//! PDF Fonts
+//!
+//! TrueType fonts embedded in exported PDFs, so text in any script prints
+//! instead of falling outside the Latin-only standard fonts. DejaVu Sans is
+//! bundled; a document with characters it lacks (CJK, for example) uses a
+//! system font covering more of them. Only the glyphs a document uses are
+//! kept in the embedded copy.
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+/// Bundled font, covering Latin, Greek and Cyrillic among others
+const BUNDLED_FONT: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+
+/// Composite glyph flags
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// Tables copied unchanged into a subset font
+const SUBSET_TABLES: [&[u8; 4]; 8] = [
+    b"OS/2", b"cmap", b"cvt ", b"fpgm", b"hhea", b"hmtx", b"maxp", b"prep",
+];
+
+/// TrueType font parsed for PDF embedding
+pub(super) struct TrueTypeFont {
+    data: Cow<'static, [u8]>,
+    /// Offset and length of each table
+    tables: HashMap<[u8; 4], (usize, usize)>,
+    /// Name used in the PDF font dictionaries
+    name: String,
+    units_per_em: u16,
+    num_glyphs: u16,
+    long_loca: bool,
+    cmap: HashMap<char, u16>,
+    /// Advance width of each glyph (font units)
+    advances: Vec<u16>,
+    ascent: i16,
+    descent: i16,
+    bbox: [i16; 4],
+}
+
+impl TrueTypeFont {
+    /// The bundled DejaVu Sans
+    pub(super) fn bundled() -> Self {
+        Self::parse(Cow::Borrowed(BUNDLED_FONT), "DejaVuSans").expect("bundled font is valid")
+    }
+
+    /// Font file at `path`, None if missing or not a TrueType font
+    ///
+    /// The first font of a collection (`.ttc`) is used.
+    fn load(path: &Path) -> Option<Self> {
+        let data = std::fs::read(path).ok()?;
+        let name: String = path
+            .file_stem()?
+            .to_string_lossy()
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .collect();
+        Self::parse(Cow::Owned(data), &name)
+    }
+
+    fn parse(data: Cow<'static, [u8]>, name: &str) -> Option<Self> {
+        let start = if data.get(..4)? == b"ttcf" {
+            u32_at(&data, 12)? as usize
+        } else {
+            0
+        };
+        let mut tables = HashMap::new();
+        for i in 0..u16_at(&data, start + 4)? as usize {
+            let record = start + 12 + 16 * i;
+            let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+            let offset = u32_at(&data, record + 8)? as usize;
+            let len = u32_at(&data, record + 12)? as usize;
+            data.get(offset..offset.checked_add(len)?)?;
+            tables.insert(tag, (offset, len));
+        }
+        // Glyph outlines must be TrueType, not CFF
+        if !tables.contains_key(b"glyf") || !tables.contains_key(b"loca") {
+            return None;
+        }
+
+        let table = |tag: &[u8; 4]| {
+            tables
+                .get(tag)
+                .map(|&(offset, len)| &data[offset..offset + len])
+        };
+        let (head, hhea, maxp) = (table(b"head")?, table(b"hhea")?, table(b"maxp")?);
+        let units_per_em = u16_at(head, 18)?.max(1);
+        let bbox = [
+            i16_at(head, 36)?,
+            i16_at(head, 38)?,
+            i16_at(head, 40)?,
+            i16_at(head, 42)?,
+        ];
+        let long_loca = i16_at(head, 50)? == 1;
+        let num_glyphs = u16_at(maxp, 4)?;
+        let (ascent, descent) = (i16_at(hhea, 4)?, i16_at(hhea, 6)?);
+
+        let hmtx = table(b"hmtx")?;
+        let metrics = u16_at(hhea, 34)?.clamp(1, num_glyphs.max(1)) as usize;
+        let advances = (0..num_glyphs as usize)
+            .map(|glyph| u16_at(hmtx, 4 * glyph.min(metrics - 1)))
+            .collect::<Option<Vec<u16>>>()?;
+        let cmap = parse_cmap(table(b"cmap")?)?;
+
+        Some(Self {
+            data,
+            tables,
+            name: name.to_string(),
+            units_per_em,
+            num_glyphs,
+            long_loca,
+            cmap,
+            advances,
+            ascent,
+            descent,
+            bbox,
+        })
+    }
+
+    fn table(&self, tag: &[u8; 4]) -> Option<&[u8]> {
+        self.tables
+            .get(tag)
+            .map(|&(offset, len)| &self.data[offset..offset + len])
+    }
+
+    /// Name of the font for the PDF font dictionaries
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Glyph of `c`, None when the font lacks it
+    pub(super) fn glyph(&self, c: char) -> Option<u16> {
+        self.cmap.get(&c).copied()
+    }
+
+    /// Advance width of a glyph in thousandths of an em
+    pub(super) fn advance(&self, glyph: u16) -> f32 {
+        let units = self.advances.get(glyph as usize).copied().unwrap_or(0);
+        units as f32 * 1000.0 / self.units_per_em as f32
+    }
+
+    /// Width of `text` set at `size` points
+    pub(super) fn text_width(&self, text: &str, size: f32) -> f32 {
+        text.chars()
+            .map(|c| self.advance(self.glyph(c).unwrap_or(0)))
+            .sum::<f32>()
+            * size
+            / 1000.0
+    }
+
+    /// Font metric scaled to thousandths of an em
+    pub(super) fn scaled(&self, units: i16) -> i32 {
+        (units as f32 * 1000.0 / self.units_per_em as f32).round() as i32
+    }
+
+    /// Ascent and descent (font units)
+    pub(super) fn vertical_metrics(&self) -> (i16, i16) {
+        (self.ascent, self.descent)
+    }
+
+    /// Bounding box of all glyphs (font units)
+    pub(super) fn bbox(&self) -> [i16; 4] {
+        self.bbox
+    }
+
+    /// Outline data of a glyph (empty for blank glyphs)
+    fn glyph_data(&self, glyph: u16) -> &[u8] {
+        let range = || {
+            let loca = self.table(b"loca")?;
+            let g = glyph as usize;
+            let (start, end) = if self.long_loca {
+                (
+                    u32_at(loca, 4 * g)? as usize,
+                    u32_at(loca, 4 * g + 4)? as usize,
+                )
+            } else {
+                (
+                    2 * u16_at(loca, 2 * g)? as usize,
+                    2 * u16_at(loca, 2 * g + 2)? as usize,
+                )
+            };
+            let glyf = self.table(b"glyf")?;
+            glyf.get(start..end)
+        };
+        range().unwrap_or(&[])
+    }
+
+    /// Standalone font keeping only the outlines of `glyphs`
+    ///
+    /// Glyph ids are unchanged, so text encoded for this font shows the
+    /// same with the subset. Components of composite glyphs and the
+    /// `.notdef` glyph are always kept.
+    pub(super) fn subset(&self, glyphs: &BTreeSet<u16>) -> Vec<u8> {
+        let mut kept: BTreeSet<u16> = glyphs.iter().copied().chain([0]).collect();
+        let mut pending: Vec<u16> = kept.iter().copied().collect();
+        while let Some(glyph) = pending.pop() {
+            for component in components(self.glyph_data(glyph)) {
+                if component < self.num_glyphs && kept.insert(component) {
+                    pending.push(component);
+                }
+            }
+        }
+
+        let mut glyf = Vec::new();
+        let mut loca = Vec::with_capacity(4 * (self.num_glyphs as usize + 1));
+        for glyph in 0..self.num_glyphs {
+            loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+            if kept.contains(&glyph) {
+                glyf.extend_from_slice(self.glyph_data(glyph));
+                glyf.resize(glyf.len().next_multiple_of(4), 0);
+            }
+        }
+        loca.extend_from_slice(&(glyf.len() as u32).to_be_bytes());
+
+        // Long loca offsets, and a checksum adjustment left for readers to ignore
+        let mut head = self.table(b"head").unwrap_or_default().to_vec();
+        head[8..12].fill(0);
+        head[50..52].copy_from_slice(&1i16.to_be_bytes());
+
+        let mut tables: Vec<([u8; 4], &[u8])> =
+            vec![(*b"glyf", &glyf), (*b"head", &head), (*b"loca", &loca)];
+        for tag in SUBSET_TABLES {
+            if let Some(table) = self.table(tag) {
+                tables.push((*tag, table));
+            }
+        }
+        tables.sort_by_key(|(tag, _)| *tag);
+        write_sfnt(&tables)
+    }
+}
+
+/// Font for a document: the bundled font, or a system font showing more of `text`
+pub(super) fn font_for(text: &str) -> TrueTypeFont {
+    let chars: BTreeSet<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    let coverage = |font: &TrueTypeFont| chars.iter().filter(|&&c| font.glyph(c).is_some()).count();
+
+    let mut best = TrueTypeFont::bundled();
+    let mut best_coverage = coverage(&best);
+    for path in system_font_paths() {
+        if best_coverage == chars.len() {
+            break;
+        }
+        let Some(font) = TrueTypeFont::load(&path) else {
+            continue;
+        };
+        let font_coverage = coverage(&font);
+        if font_coverage > best_coverage {
+            tracing::debug!(
+                "Using {:?} for characters missing from the bundled font",
+                path
+            );
+            best = font;
+            best_coverage = font_coverage;
+        }
+    }
+    best
+}
+
+/// System fonts tried for characters the bundled font lacks
+fn system_font_paths() -> Vec<PathBuf> {
+    #[cfg(target_os = "windows")]
+    {
+        let windows = std::env::var_os("WINDIR").unwrap_or_else(|| "C:\\Windows".into());
+        let fonts = PathBuf::from(windows).join("Fonts");
+        [
+            "msyh.ttc",
+            "simsun.ttc",
+            "YuGothR.ttc",
+            "msgothic.ttc",
+            "malgun.ttf",
+            "Nirmala.ttf",
+            "arialuni.ttf",
+        ]
+        .iter()
+        .map(|name| fonts.join(name))
+        .collect()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        [
+            "/Library/Fonts/Arial Unicode.ttf",
+            "/System/Library/Fonts/Supplemental/Arial Unicode.ttf",
+            "/System/Library/Fonts/PingFang.ttc",
+            "/System/Library/Fonts/AppleSDGothicNeo.ttc",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        [
+            "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+            "/usr/share/fonts/truetype/wqy/wqy-microhei.ttc",
+            "/usr/share/fonts/truetype/wqy/wqy-zenhei.ttc",
+            "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+            "/usr/share/fonts/truetype/unifont/unifont.ttf",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect()
+    }
+}
+
+/// Character to glyph map from the best Unicode subtable of `cmap`
+fn parse_cmap(cmap: &[u8]) -> Option<HashMap<char, u16>> {
+    let mut best: Option<(u8, usize)> = None;
+    for i in 0..u16_at(cmap, 2)? as usize {
+        let record = 4 + 8 * i;
+        let platform = u16_at(cmap, record)?;
+        let encoding = u16_at(cmap, record + 2)?;
+        let offset = u32_at(cmap, record + 4)? as usize;
+        let unicode = platform == 0 || (platform == 3 && matches!(encoding, 1 | 10));
+        let rank = match u16_at(cmap, offset) {
+            Some(12) if unicode => 2,
+            Some(4) if unicode => 1,
+            _ => continue,
+        };
+        if best.is_none_or(|(best_rank, _)| rank > best_rank) {
+            best = Some((rank, offset));
+        }
+    }
+    let table = cmap.get(best?.1..)?;
+
+    let mut map = HashMap::new();
+    if u16_at(table, 0)? == 12 {
+        for group in 0..u32_at(table, 12)? as usize {
+            let record = 16 + 12 * group;
+            let start = u32_at(table, record)?;
+            let end = u32_at(table, record + 4)?.min(0x10FFFF);
+            let first_glyph = u32_at(table, record + 8)?;
+            for code in start..=end {
+                let glyph = u16::try_from(first_glyph + (code - start)).ok();
+                if let (Some(c), Some(glyph)) = (char::from_u32(code), glyph) {
+                    map.insert(c, glyph);
+                }
+            }
+        }
+    } else {
+        let segments = u16_at(table, 6)? as usize / 2;
+        for segment in 0..segments {
+            let end = u16_at(table, 14 + 2 * segment)?;
+            let start = u16_at(table, 16 + 2 * segments + 2 * segment)?;
+            let delta = u16_at(table, 16 + 4 * segments + 2 * segment)?;
+            let range_position = 16 + 6 * segments + 2 * segment;
+            let range_offset = u16_at(table, range_position)? as usize;
+            for code in start..=end {
+                let glyph = if range_offset == 0 {
+                    code.wrapping_add(delta)
+                } else {
+                    let position = range_position + range_offset + 2 * (code - start) as usize;
+                    match u16_at(table, position)? {
+                        0 => 0,
+                        glyph => glyph.wrapping_add(delta),
+                    }
+                };
+                if let Some(c) = char::from_u32(code as u32) {
+                    map.insert(c, glyph);
+                }
+            }
+        }
+    }
+    map.retain(|_, glyph| *glyph != 0);
+    Some(map)
+}
+
+/// Glyphs a composite glyph is built from
+fn components(glyph: &[u8]) -> Vec<u16> {
+    let mut components = Vec::new();
+    if i16_at(glyph, 0).is_none_or(|contours| contours >= 0) {
+        return components;
+    }
+
+    let mut position = 10;
+    while let (Some(flags), Some(component)) =
+        (u16_at(glyph, position), u16_at(glyph, position + 2))
+    {
+        components.push(component);
+        position += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+            8
+        } else {
+            6
+        };
+        position += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+/// TrueType file of `tables`, sorted by tag
+fn write_sfnt(tables: &[([u8; 4], &[u8])]) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = 16u16 << entry_selector;
+
+    let mut font = Vec::new();
+    font.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for value in [
+        count,
+        search_range,
+        entry_selector,
+        count * 16 - search_range,
+    ] {
+        font.extend_from_slice(&value.to_be_bytes());
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in tables {
+        font.extend_from_slice(tag);
+        font.extend_from_slice(&checksum(data).to_be_bytes());
+        font.extend_from_slice(&(offset as u32).to_be_bytes());
+        font.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        font.extend_from_slice(data);
+        font.resize(font.len().next_multiple_of(4), 0);
+    }
+    font
+}
+
+/// Table checksum: sum of its big-endian 32-bit words
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+fn u16_at(data: &[u8], position: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(position..position + 2)?.try_into().ok()?,
+    ))
+}
+
+fn i16_at(data: &[u8], position: usize) -> Option<i16> {
+    u16_at(data, position).map(|value| value as i16)
+}
+
+fn u32_at(data: &[u8], position: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(position..position + 4)?.try_into().ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_font_covers_scripts() {
+        let font = TrueTypeFont::bundled();
+        for c in ['A', '\u{e9}', '\u{416}', '\u{3a9}', '\u{2022}'] {
+            assert!(font.glyph(c).is_some(), "{}", c);
+        }
+        assert_eq!(font.glyph('\u{4e2d}'), None);
+        assert!(font.text_width("W", 10.0) > font.text_width("i", 10.0));
+        assert_eq!(font.text_width("", 10.0), 0.0);
+    }
+
+    #[test]
+    fn test_subset_keeps_used_glyphs() {
+        let font = TrueTypeFont::bundled();
+        let a = font.glyph('A').unwrap();
+        let e_acute = font.glyph('\u{e9}').unwrap();
+        let subset = font.subset(&BTreeSet::from([a, e_acute]));
+        assert!(subset.len() < BUNDLED_FONT.len() / 4);
+
+        let parsed = TrueTypeFont::parse(Cow::Owned(subset), "Subset").unwrap();
+        assert_eq!(parsed.num_glyphs, font.num_glyphs);
+        assert_eq!(parsed.glyph_data(a), font.glyph_data(a));
+        assert_eq!(parsed.glyph_data(e_acute), font.glyph_data(e_acute));
+        for component in components(font.glyph_data(e_acute)) {
+            assert!(!parsed.glyph_data(component).is_empty());
+        }
+        assert!(parsed.glyph_data(font.glyph('B').unwrap()).is_empty());
+    }
+
+    #[test]
+    fn test_latin_text_uses_bundled_font() {
+        assert_eq!(
+            font_for("Weekly sync \u{2013} caf\u{e9}").name(),
+            "DejaVuSans"
+        );
+    }
+
+    #[test]
+    fn test_checksum_pads_last_word() {
+        assert_eq!(checksum(&[0, 0, 0, 1, 2]), 1 + 0x0200_0000);
+    }
+}
@@ -0,0 +1,139 @@
+//! Incognito Dictations
+//!
+//! Results of incognito recordings. They live in memory only, never in the
+//! history file, and are wiped once their time is up. The audio is not kept
+//! at all.
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Global incognito store
+static INCOGNITO: Mutex<IncognitoStore> = Mutex::new(IncognitoStore::new());
+
+/// An incognito dictation as shown to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct IncognitoEntry {
+    pub id: String,
+    pub text: String,
+    /// When the dictation was made (ISO 8601)
+    pub timestamp: String,
+    /// Seconds until the dictation is wiped
+    pub expires_in_secs: u64,
+}
+
+struct StoredEntry {
+    id: String,
+    text: String,
+    timestamp: String,
+    expires_at: Instant,
+}
+
+impl Drop for StoredEntry {
+    fn drop(&mut self) {
+        // Overwrite the text rather than leave it in freed memory
+        let mut bytes = std::mem::take(&mut self.text).into_bytes();
+        bytes.fill(0);
+        std::hint::black_box(&bytes);
+    }
+}
+
+/// Incognito dictations not yet wiped
+pub struct IncognitoStore {
+    entries: Vec<StoredEntry>,
+}
+
+impl IncognitoStore {
+    pub const fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Keep a dictation for `ttl`, returning its id
+    pub fn add(&mut self, text: String, ttl: Duration, now: Instant) -> String {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.entries.push(StoredEntry {
+            id: id.clone(),
+            text,
+            timestamp: super::chrono_timestamp(),
+            expires_at: now + ttl,
+        });
+        id
+    }
+
+    /// Wipe dictations whose time is up, returning how many were wiped
+    pub fn purge_expired(&mut self, now: Instant) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.expires_at > now);
+        before - self.entries.len()
+    }
+
+    /// Wipe all dictations, returning how many were wiped
+    pub fn clear(&mut self) -> usize {
+        let count = self.entries.len();
+        self.entries.clear();
+        count
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Dictations still kept, newest first
+    pub fn entries(&self, now: Instant) -> Vec<IncognitoEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| IncognitoEntry {
+                id: entry.id.clone(),
+                text: entry.text.clone(),
+                timestamp: entry.timestamp.clone(),
+                expires_in_secs: entry.expires_at.duration_since(now).as_secs(),
+            })
+            .collect()
+    }
+}
+
+impl Default for IncognitoStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the global incognito store
+pub fn incognito_store() -> &'static Mutex<IncognitoStore> {
+    &INCOGNITO
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entries_expire() {
+        let now = Instant::now();
+        let mut store = IncognitoStore::new();
+        store.add("first".to_string(), Duration::from_secs(60), now);
+        store.add("second".to_string(), Duration::from_secs(300), now);
+
+        let entries = store.entries(now);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].text, "second");
+        assert_eq!(entries[0].expires_in_secs, 300);
+
+        let later = now + Duration::from_secs(61);
+        assert_eq!(store.entries(later).len(), 1);
+        assert_eq!(store.purge_expired(later), 1);
+        assert_eq!(store.len(), 1);
+
+        assert_eq!(store.clear(), 1);
+        assert!(store.is_empty());
+    }
+}
@@ -0,0 +1,396 @@
+//! History Integrity
+//!
+//! Cross-checks history entries against the audio and screenshot files on
+//! disk, and reports rows of the history file that could not be read.
+//! Repairs only touch file references and unreferenced files, never the
+//! transcriptions themselves.
+
+use super::{audio_dir, get_history, screenshots_dir, HistoryEntry, TranscriptionHistory};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+/// How often scheduled maintenance checks the history
+pub const INTEGRITY_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 3600);
+
+/// Files younger than this are never orphans: a recording saves its audio
+/// and screenshot before its entry
+const ORPHAN_GRACE: Duration = Duration::from_secs(60);
+
+/// A row of the history file dropped at load
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct CorruptedRow {
+    /// Position in the file, unset when the file itself is unreadable
+    pub index: Option<usize>,
+    pub error: String,
+}
+
+/// A problem found by the integrity check
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum IntegrityIssue {
+    /// An entry's audio file no longer exists
+    MissingAudio { id: String, path: String },
+    /// An entry's audio file is not a readable WAV file
+    CorruptedAudio { id: String, path: String },
+    /// An entry's screenshot no longer exists
+    MissingScreenshot { id: String, path: String },
+    /// A file of the audio or screenshots directory no entry refers to
+    OrphanedFile { path: String, size_bytes: u64 },
+    /// A history row that could not be parsed (a copy of the file is kept)
+    CorruptedRow(CorruptedRow),
+}
+
+/// Result of an integrity check
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct IntegrityReport {
+    pub entries_checked: usize,
+    pub files_checked: usize,
+    pub issues: Vec<IntegrityIssue>,
+    /// Whether the issues were repaired
+    pub repaired: bool,
+    /// Space reclaimed by deleting orphaned files
+    pub freed_bytes: u64,
+}
+
+impl TranscriptionHistory {
+    /// Check entries against the files in `audio_dir` and `screenshots_dir`
+    pub fn check_integrity(&self, audio_dir: &Path, screenshots_dir: &Path) -> IntegrityReport {
+        let mut issues: Vec<IntegrityIssue> = self
+            .corrupted_rows
+            .iter()
+            .cloned()
+            .map(IntegrityIssue::CorruptedRow)
+            .collect();
+
+        let mut referenced = HashSet::new();
+        for entry in &self.entries {
+            if let Some(path) = &entry.audio_path {
+                let file = Path::new(path);
+                if !file.exists() {
+                    issues.push(IntegrityIssue::MissingAudio {
+                        id: entry.id.clone(),
+                        path: path.clone(),
+                    });
+                } else {
+                    // Still referenced: it only becomes an orphan once repaired
+                    referenced.extend(file.file_name().map(OsString::from));
+                    if !is_readable_audio(file) {
+                        issues.push(IntegrityIssue::CorruptedAudio {
+                            id: entry.id.clone(),
+                            path: path.clone(),
+                        });
+                    }
+                }
+            }
+            if let Some(path) = &entry.screenshot_path {
+                let file = Path::new(path);
+                if file.exists() {
+                    referenced.extend(file.file_name().map(OsString::from));
+                } else {
+                    issues.push(IntegrityIssue::MissingScreenshot {
+                        id: entry.id.clone(),
+                        path: path.clone(),
+                    });
+                }
+            }
+        }
+
+        let mut files_checked = 0;
+        for dir in [audio_dir, screenshots_dir] {
+            files_checked += find_orphans(dir, &referenced, &mut issues);
+        }
+
+        IntegrityReport {
+            entries_checked: self.entries.len(),
+            files_checked,
+            issues,
+            repaired: false,
+            freed_bytes: 0,
+        }
+    }
+
+    /// Repair the issues of `report`
+    ///
+    /// Dangling and corrupted references are cleared, orphaned files
+    /// deleted and dropped rows forgotten. Returns whether entries changed.
+    pub fn repair_integrity(&mut self, report: &mut IntegrityReport) -> bool {
+        let mut changed = false;
+        for issue in &report.issues {
+            match issue {
+                IntegrityIssue::MissingAudio { id, .. } | IntegrityIssue::CorruptedAudio { id, .. } => {
+                    if let Some(entry) = self.entry_mut(id) {
+                        entry.audio_path = None;
+                        changed = true;
+                    }
+                }
+                IntegrityIssue::MissingScreenshot { id, .. } => {
+                    if let Some(entry) = self.entry_mut(id) {
+                        entry.screenshot_path = None;
+                        changed = true;
+                    }
+                }
+                IntegrityIssue::OrphanedFile { path, size_bytes } => {
+                    match std::fs::remove_file(path) {
+                        Ok(()) => report.freed_bytes += size_bytes,
+                        Err(e) => tracing::warn!("Failed to delete orphaned file {}: {}", path, e),
+                    }
+                }
+                IntegrityIssue::CorruptedRow(_) => {
+                    // Saving rewrites the file without the dropped rows
+                    self.corrupted_rows.clear();
+                    changed = true;
+                }
+            }
+        }
+        report.repaired = true;
+        changed
+    }
+
+    fn entry_mut(&mut self, id: &str) -> Option<&mut HistoryEntry> {
+        self.entries.iter_mut().find(|e| e.id == id)
+    }
+}
+
+/// Whether a saved recording opens: WAV headers are parsed, compressed
+/// recordings only checked for their container signature
+fn is_readable_audio(file: &Path) -> bool {
+    let signature = match file.extension().and_then(|e| e.to_str()) {
+        Some("flac") => b"fLaC",
+        Some("opus") => b"OggS",
+        _ => return hound::WavReader::open(file).is_ok(),
+    };
+    let mut header = [0u8; 4];
+    std::fs::File::open(file)
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut header))
+        .is_ok_and(|()| &header == signature)
+}
+
+/// Add the files of `dir` not in `referenced` to `issues`; returns the
+/// number of files checked
+fn find_orphans(dir: &Path, referenced: &HashSet<OsString>, issues: &mut Vec<IntegrityIssue>) -> usize {
+    let Ok(read_dir) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+
+    let mut checked = 0;
+    for dir_entry in read_dir.flatten() {
+        let Ok(metadata) = dir_entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        checked += 1;
+
+        let recent = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_none_or(|age| age < ORPHAN_GRACE);
+        if recent || referenced.contains(&dir_entry.file_name()) {
+            continue;
+        }
+        issues.push(IntegrityIssue::OrphanedFile {
+            path: dir_entry.path().to_string_lossy().to_string(),
+            size_bytes: metadata.len(),
+        });
+    }
+    checked
+}
+
+/// Parse a history file that failed to load, keeping the rows that parse
+pub(super) fn salvage_history(content: &str) -> TranscriptionHistory {
+    let mut history = TranscriptionHistory::new();
+
+    let rows = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(serde_json::Value::Object(mut root)) => match root.remove("entries") {
+            Some(serde_json::Value::Array(rows)) => rows,
+            _ => {
+                history.corrupted_rows.push(CorruptedRow {
+                    index: None,
+                    error: "missing entries list".to_string(),
+                });
+                return history;
+            }
+        },
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            history.corrupted_rows.push(CorruptedRow {
+                index: None,
+                error: e.to_string(),
+            });
+            return history;
+        }
+    };
+
+    for (index, row) in rows.into_iter().enumerate() {
+        match serde_json::from_value::<HistoryEntry>(row) {
+            Ok(entry) => history.entries.push_back(entry),
+            Err(e) => history.corrupted_rows.push(CorruptedRow {
+                index: Some(index),
+                error: e.to_string(),
+            }),
+        }
+    }
+    history
+}
+
+/// Check the global history, repairing the issues found when `repair` is set
+pub fn verify_integrity(repair: bool) -> Result<IntegrityReport, std::io::Error> {
+    let (audio_dir, screenshots_dir) = (audio_dir(), screenshots_dir());
+
+    if !repair {
+        return Ok(get_history().read().check_integrity(&audio_dir, &screenshots_dir));
+    }
+
+    let mut history = get_history().write();
+    let mut report = history.check_integrity(&audio_dir, &screenshots_dir);
+    if history.repair_integrity(&mut report) {
+        history.save()?;
+    }
+    if !report.issues.is_empty() {
+        tracing::info!(
+            "Repaired {} history issues, freed {} bytes",
+            report.issues.len(),
+            report.freed_bytes
+        );
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn entry(id: &str, audio_path: Option<String>) -> HistoryEntry {
+        HistoryEntry {
+            id: id.to_string(),
+            text: "Hello".to_string(),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            duration_ms: 1000,
+            provider: "whisper.cpp".to_string(),
+            language: None,
+            audio_path,
+            waveform: Vec::new(),
+            screenshot_path: None,
+            alerts: Vec::new(),
+            title: "Hello".to_string(),
+            note: None,
+            recording_ms: None,
+            segments: Vec::new(),
+            translation: None,
+            tags: Vec::new(),
+            source: None,
+        }
+    }
+
+    fn write_wav(path: &Path) {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(path, spec).unwrap();
+        writer.write_sample(0i16).unwrap();
+        writer.finalize().unwrap();
+    }
+
+    fn set_old(path: &Path) {
+        let old = SystemTime::now() - 2 * ORPHAN_GRACE;
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_check_and_repair() {
+        let dir = TempDir::new().unwrap();
+        let audio = dir.path().join("audio");
+        let screenshots = dir.path().join("screenshots");
+        std::fs::create_dir_all(&audio).unwrap();
+
+        let kept = audio.join("kept.wav");
+        write_wav(&kept);
+        let corrupted = audio.join("corrupted.wav");
+        std::fs::write(&corrupted, b"not a wav").unwrap();
+        let orphan = audio.join("orphan.wav");
+        std::fs::write(&orphan, b"orphan").unwrap();
+        let fresh = audio.join("fresh.wav");
+        std::fs::write(&fresh, b"still recording").unwrap();
+        for path in [&kept, &corrupted, &orphan] {
+            set_old(path);
+        }
+
+        let path = |p: &Path| Some(p.to_string_lossy().to_string());
+        let mut history = TranscriptionHistory::new();
+        history.entries.push_back(entry("kept", path(&kept)));
+        history.entries.push_back(entry("corrupted", path(&corrupted)));
+        history.entries.push_back(entry("missing", path(&audio.join("missing.wav"))));
+
+        let mut report = history.check_integrity(&audio, &screenshots);
+        assert_eq!(report.entries_checked, 3);
+        assert_eq!(report.files_checked, 4);
+        assert_eq!(report.issues.len(), 3);
+        assert!(report.issues.contains(&IntegrityIssue::OrphanedFile {
+            path: orphan.to_string_lossy().to_string(),
+            size_bytes: 6,
+        }));
+
+        assert!(history.repair_integrity(&mut report));
+        assert_eq!(report.freed_bytes, 6);
+        assert!(!orphan.exists());
+        assert!(fresh.exists());
+        assert!(history.get("kept").unwrap().audio_path.is_some());
+        assert!(history.get("corrupted").unwrap().audio_path.is_none());
+        assert!(history.get("missing").unwrap().audio_path.is_none());
+
+        // The corrupted file is now an orphan
+        let report = history.check_integrity(&audio, &screenshots);
+        assert_eq!(report.issues.len(), 1);
+    }
+
+    #[test]
+    fn test_compressed_audio_is_readable() {
+        let dir = TempDir::new().unwrap();
+        let flac = dir.path().join("a.flac");
+        std::fs::write(&flac, b"fLaC\0\0\0\x22").unwrap();
+        let opus = dir.path().join("b.opus");
+        std::fs::write(&opus, b"OggS\0\x02").unwrap();
+        let mislabeled = dir.path().join("c.opus");
+        std::fs::write(&mislabeled, b"fLaC\0\0\0\x22").unwrap();
+
+        assert!(is_readable_audio(&flac));
+        assert!(is_readable_audio(&opus));
+        assert!(!is_readable_audio(&mislabeled));
+        assert!(!is_readable_audio(&dir.path().join("missing.flac")));
+    }
+
+    #[test]
+    fn test_salvage_history() {
+        let good = serde_json::to_value(entry("good", None)).unwrap();
+        let content = serde_json::json!({ "entries": [good, { "id": 5 }] }).to_string();
+
+        let mut history = salvage_history(&content);
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.corrupted_rows.len(), 1);
+        assert_eq!(history.corrupted_rows[0].index, Some(1));
+
+        let mut report = history.check_integrity(Path::new("/nonexistent"), Path::new("/nonexistent"));
+        assert!(matches!(report.issues[..], [IntegrityIssue::CorruptedRow(_)]));
+        assert!(history.repair_integrity(&mut report));
+        assert!(history.corrupted_rows.is_empty());
+
+        let history = salvage_history("{\"entries\": [");
+        assert!(history.entries.is_empty());
+        assert_eq!(history.corrupted_rows[0].index, None);
+    }
+}
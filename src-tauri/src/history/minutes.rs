@@ -0,0 +1,817 @@
+//! Meeting Minutes Export
+//!
+//! Renders a history entry as meeting minutes (notes, action items,
+//! chapters) in DOCX or PDF, laid out by a template. Both formats are
+//! written directly: DOCX as an uncompressed ZIP of WordprocessingML parts,
+//! PDF with an embedded TrueType font subset, so no office suite is needed.
+
+use super::font::{font_for, TrueTypeFont};
+use super::HistoryEntry;
+use crate::vocabulary::KeywordIndex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Phrases marking a sentence as an action item
+const ACTION_MARKERS: &[&str] = &[
+    "action item",
+    "todo",
+    "to do",
+    "follow up",
+    "need to",
+    "needs to",
+];
+
+/// A4 page size in points
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+
+/// Page margin in points
+const PAGE_MARGIN: f32 = 56.0;
+
+/// Minutes export errors
+#[derive(Debug, Error)]
+pub enum MinutesError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid minutes template: {0}")]
+    Template(String),
+}
+
+/// Output document format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MinutesFormat {
+    Docx,
+    Pdf,
+}
+
+/// Section of the minutes, in template order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MinutesSection {
+    Notes,
+    ActionItems,
+    Chapters,
+}
+
+impl MinutesSection {
+    fn heading(self) -> &'static str {
+        match self {
+            Self::Notes => "Notes",
+            Self::ActionItems => "Action Items",
+            Self::Chapters => "Discussion",
+        }
+    }
+}
+
+/// Layout of exported minutes
+///
+/// Loaded from `<name>.toml` in the minutes templates directory. `{title}`,
+/// `{date}` and `{duration}` in the title and footer are replaced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MinutesTemplate {
+    pub title: String,
+    /// Sections in order; sections without content are left out
+    pub sections: Vec<MinutesSection>,
+    pub footer: Option<String>,
+}
+
+impl Default for MinutesTemplate {
+    fn default() -> Self {
+        Self {
+            title: "Meeting Minutes - {date}".to_string(),
+            sections: vec![
+                MinutesSection::Notes,
+                MinutesSection::ActionItems,
+                MinutesSection::Chapters,
+            ],
+            footer: Some("Transcribed with GigaWhisper ({duration})".to_string()),
+        }
+    }
+}
+
+impl MinutesTemplate {
+    /// Load a template by name, None = built-in template
+    pub fn load(name: Option<&str>) -> Result<Self, MinutesError> {
+        let Some(name) = name else {
+            return Ok(Self::default());
+        };
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(MinutesError::Template(format!("invalid template name '{}'", name)));
+        }
+
+        let path = minutes_templates_dir().join(format!("{}.toml", name));
+        let content = std::fs::read_to_string(path)?;
+        let template: Self =
+            toml::from_str(&content).map_err(|e| MinutesError::Template(e.to_string()))?;
+        if template.sections.is_empty() {
+            return Err(MinutesError::Template("template has no sections".to_string()));
+        }
+        Ok(template)
+    }
+}
+
+/// One part of the discussion
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chapter {
+    pub title: String,
+    pub text: String,
+}
+
+/// Content of the minutes
+#[derive(Debug, Clone, Default)]
+pub struct MeetingMinutes {
+    /// Title of the history entry
+    pub title: String,
+    /// Date of the meeting (YYYY-MM-DD)
+    pub date: String,
+    pub duration_ms: u64,
+    /// Note attached to the history entry
+    pub notes: Option<String>,
+    pub chapters: Vec<Chapter>,
+    pub action_items: Vec<String>,
+}
+
+impl MeetingMinutes {
+    /// Minutes of a history entry
+    ///
+    /// Paragraphs become chapters, and sentences with an alert keyword or an
+    /// action phrase ("action item", "todo", ...) become action items.
+    pub fn from_entry(entry: &HistoryEntry) -> Self {
+        let paragraphs: Vec<&str> = entry
+            .text
+            .split("\n\n")
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .collect();
+        let chapters = match paragraphs.len() {
+            0 => Vec::new(),
+            1 => vec![Chapter {
+                title: "Transcript".to_string(),
+                text: paragraphs[0].to_string(),
+            }],
+            _ => paragraphs
+                .iter()
+                .enumerate()
+                .map(|(i, text)| Chapter {
+                    title: format!("Part {}", i + 1),
+                    text: text.to_string(),
+                })
+                .collect(),
+        };
+
+        let markers: Vec<String> = ACTION_MARKERS
+            .iter()
+            .map(|m| m.to_string())
+            .chain(entry.alerts.iter().cloned())
+            .collect();
+        let index = KeywordIndex::new(&markers);
+        let action_items = sentences(&entry.text)
+            .into_iter()
+            .filter(|sentence| !index.find(sentence).is_empty())
+            .collect();
+
+        Self {
+            title: entry.title.clone(),
+            date: entry.timestamp.chars().take(10).collect(),
+            duration_ms: entry.recording_ms.unwrap_or(entry.duration_ms),
+            notes: entry.note.clone(),
+            chapters,
+            action_items,
+        }
+    }
+}
+
+/// Laid-out element of the document
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Title(String),
+    Heading(String),
+    Paragraph(String),
+    Bullet(String),
+    Footer(String),
+}
+
+/// Lay out `minutes` following `template`
+pub fn layout(minutes: &MeetingMinutes, template: &MinutesTemplate) -> Vec<Block> {
+    let fill = |text: &str| {
+        text.replace("{title}", &minutes.title)
+            .replace("{date}", &minutes.date)
+            .replace("{duration}", &format_duration(minutes.duration_ms))
+    };
+
+    let mut blocks = vec![Block::Title(fill(&template.title))];
+    for &section in &template.sections {
+        let content: Vec<Block> = match section {
+            MinutesSection::Notes => minutes
+                .notes
+                .iter()
+                .map(|s| Block::Paragraph(s.clone()))
+                .collect(),
+            MinutesSection::ActionItems => minutes
+                .action_items
+                .iter()
+                .map(|s| Block::Bullet(s.clone()))
+                .collect(),
+            MinutesSection::Chapters => minutes
+                .chapters
+                .iter()
+                .flat_map(|c| [Block::Heading(c.title.clone()), Block::Paragraph(c.text.clone())])
+                .collect(),
+        };
+        if !content.is_empty() {
+            blocks.push(Block::Heading(section.heading().to_string()));
+            blocks.extend(content);
+        }
+    }
+    if let Some(footer) = &template.footer {
+        blocks.push(Block::Footer(fill(footer)));
+    }
+    blocks
+}
+
+/// Export a history entry as meeting minutes to `path`
+pub fn export_minutes(
+    entry: &HistoryEntry,
+    format: MinutesFormat,
+    template: &MinutesTemplate,
+    path: &Path,
+) -> Result<(), MinutesError> {
+    let blocks = layout(&MeetingMinutes::from_entry(entry), template);
+    let bytes = match format {
+        MinutesFormat::Docx => render_docx(&blocks),
+        MinutesFormat::Pdf => render_pdf(&blocks),
+    };
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Get the minutes templates directory
+pub fn minutes_templates_dir() -> PathBuf {
+    crate::config::data_dir().join("templates")
+}
+
+fn format_duration(ms: u64) -> String {
+    let seconds = ms / 1000;
+    if seconds >= 60 {
+        format!("{} min {} s", seconds / 60, seconds % 60)
+    } else {
+        format!("{} s", seconds)
+    }
+}
+
+/// Sentences of `text`, split after `.`, `!`, `?` and line breaks
+pub(crate) fn sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    for c in text.chars() {
+        if c != '\n' {
+            current.push(c);
+        }
+        if matches!(c, '.' | '!' | '?' | '\n') {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            current.clear();
+        }
+    }
+    let rest = current.trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+// DOCX
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// WordprocessingML paragraph with direct formatting
+fn docx_paragraph(text: &str, size_half_points: u32, bold: bool, indent: bool) -> String {
+    let mut properties = String::new();
+    if indent {
+        properties.push_str(r#"<w:pPr><w:ind w:left="360" w:hanging="360"/></w:pPr>"#);
+    }
+    let bold = if bold { "<w:b/>" } else { "" };
+    let text = if indent {
+        format!("\u{2022}\t{}", text)
+    } else {
+        text.to_string()
+    };
+    format!(
+        r#"<w:p>{}<w:r><w:rPr>{}<w:sz w:val="{}"/></w:rPr><w:t xml:space="preserve">{}</w:t></w:r></w:p>"#,
+        properties,
+        bold,
+        size_half_points,
+        xml_escape(&text)
+    )
+}
+
+/// Render blocks as a DOCX document
+pub fn render_docx(blocks: &[Block]) -> Vec<u8> {
+    let body: String = blocks
+        .iter()
+        .map(|block| match block {
+            Block::Title(text) => docx_paragraph(text, 40, true, false),
+            Block::Heading(text) => docx_paragraph(text, 28, true, false),
+            Block::Paragraph(text) => docx_paragraph(text, 22, false, false),
+            Block::Bullet(text) => docx_paragraph(text, 22, false, true),
+            Block::Footer(text) => docx_paragraph(text, 18, false, false),
+        })
+        .collect();
+
+    let document = format!(
+        concat!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">"#,
+            r#"<w:body>{}</w:body></w:document>"#
+        ),
+        body
+    );
+    let content_types = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">"#,
+        r#"<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>"#,
+        r#"<Default Extension="xml" ContentType="application/xml"/>"#,
+        r#"<Override PartName="/word/document.xml" "#,
+        r#"ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>"#,
+        r#"</Types>"#
+    );
+    let relationships = concat!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        r#"<Relationship Id="rId1" "#,
+        r#"Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" "#,
+        r#"Target="word/document.xml"/></Relationships>"#
+    );
+
+    write_stored_zip(&[
+        ("[Content_Types].xml", content_types.as_bytes()),
+        ("_rels/.rels", relationships.as_bytes()),
+        ("word/document.xml", document.as_bytes()),
+    ])
+}
+
+/// CRC-32 (IEEE) of `data`
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// ZIP archive of uncompressed files
+fn write_stored_zip(files: &[(&str, &[u8])]) -> Vec<u8> {
+    // 1980-01-01, the earliest DOS date
+    const DOS_DATE: u16 = 0x21;
+
+    let mut zip = Vec::new();
+    let mut central = Vec::new();
+    for (name, data) in files {
+        let offset = zip.len() as u32;
+        let crc = crc32(data);
+
+        let mut common = Vec::new();
+        common.extend_from_slice(&20u16.to_le_bytes()); // version needed
+        common.extend_from_slice(&0u16.to_le_bytes()); // flags
+        common.extend_from_slice(&0u16.to_le_bytes()); // stored
+        common.extend_from_slice(&0u16.to_le_bytes()); // time
+        common.extend_from_slice(&DOS_DATE.to_le_bytes());
+        common.extend_from_slice(&crc.to_le_bytes());
+        common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        common.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes()); // extra field length
+
+        zip.extend_from_slice(&0x0403_4b50u32.to_le_bytes());
+        zip.extend_from_slice(&common);
+        zip.extend_from_slice(name.as_bytes());
+        zip.extend_from_slice(data);
+
+        central.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        central.extend_from_slice(&20u16.to_le_bytes()); // version made by
+        central.extend_from_slice(&common);
+        central.extend_from_slice(&0u16.to_le_bytes()); // comment length
+        central.extend_from_slice(&0u16.to_le_bytes()); // disk number
+        central.extend_from_slice(&0u16.to_le_bytes()); // internal attributes
+        central.extend_from_slice(&0u32.to_le_bytes()); // external attributes
+        central.extend_from_slice(&offset.to_le_bytes());
+        central.extend_from_slice(name.as_bytes());
+    }
+
+    let central_offset = zip.len() as u32;
+    zip.extend_from_slice(&central);
+    zip.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(files.len() as u16).to_le_bytes());
+    zip.extend_from_slice(&(central.len() as u32).to_le_bytes());
+    zip.extend_from_slice(&central_offset.to_le_bytes());
+    zip.extend_from_slice(&0u16.to_le_bytes());
+    zip
+}
+
+// PDF
+
+/// Line of text placed on a page
+struct PdfLine {
+    text: String,
+    size: f32,
+    bold: bool,
+    indent: f32,
+    /// Vertical space taken, including spacing before the next line
+    advance: f32,
+}
+
+/// Split text into lines no wider than `max_width`
+///
+/// Words wider than a line are broken between characters.
+fn wrap(text: &str, max_width: f32, width: impl Fn(&str) -> f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() {
+            word.to_string()
+        } else {
+            format!("{} {}", line, word)
+        };
+        if width(&candidate) <= max_width {
+            line = candidate;
+            continue;
+        }
+
+        if !line.is_empty() {
+            lines.push(std::mem::take(&mut line));
+        }
+        for c in word.chars() {
+            line.push(c);
+            if line.chars().count() > 1 && width(&line) > max_width {
+                line.pop();
+                lines.push(std::mem::replace(&mut line, c.to_string()));
+            }
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Text as a hex string of glyph ids, recording the glyphs used
+fn encode_glyphs(text: &str, font: &TrueTypeFont, used: &mut BTreeMap<u16, char>) -> String {
+    text.chars()
+        .map(|c| {
+            // Characters the font lacks show as its missing-glyph box
+            let glyph = font.glyph(c).unwrap_or(0);
+            used.entry(glyph).or_insert(c);
+            format!("{:04X}", glyph)
+        })
+        .collect()
+}
+
+/// CMap mapping glyph ids back to text, for copying and searching
+fn to_unicode_cmap(used: &BTreeMap<u16, char>) -> String {
+    let mut cmap = String::from(concat!(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n",
+        "/CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n",
+        "/CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n",
+        "1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n"
+    ));
+    let mapped: Vec<(&u16, &char)> = used.iter().filter(|(&glyph, _)| glyph != 0).collect();
+    // At most 100 mappings per block
+    for block in mapped.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", block.len()));
+        for (glyph, c) in block {
+            let utf16: String = c
+                .encode_utf16(&mut [0; 2])
+                .iter()
+                .map(|unit| format!("{:04X}", unit))
+                .collect();
+            cmap.push_str(&format!("<{:04X}> <{}>\n", glyph, utf16));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMapResource defineresource pop\nend\nend");
+    cmap
+}
+
+/// PDF stream object holding `data`
+fn pdf_stream(dictionary: &str, data: &[u8]) -> Vec<u8> {
+    let mut stream = format!("<< {}/Length {} >>\nstream\n", dictionary, data.len()).into_bytes();
+    stream.extend_from_slice(data);
+    stream.extend_from_slice(b"\nendstream");
+    stream
+}
+
+/// Render blocks as a PDF document (A4)
+pub fn render_pdf(blocks: &[Block]) -> Vec<u8> {
+    let width = PAGE_WIDTH - 2.0 * PAGE_MARGIN;
+
+    let styled: Vec<(String, f32, bool, f32, f32)> = blocks
+        .iter()
+        .map(|block| match block {
+            Block::Title(t) => (t.clone(), 20.0, true, 0.0, 0.0),
+            Block::Heading(t) => (t.clone(), 14.0, true, 0.0, 10.0),
+            Block::Paragraph(t) => (t.clone(), 11.0, false, 0.0, 4.0),
+            Block::Bullet(t) => (format!("\u{2022} {}", t), 11.0, false, 12.0, 2.0),
+            Block::Footer(t) => (t.clone(), 9.0, false, 0.0, 16.0),
+        })
+        .collect();
+    let all_text: Vec<&str> = styled.iter().map(|(text, ..)| text.as_str()).collect();
+    let font = font_for(&all_text.join(" "));
+
+    let mut lines = Vec::new();
+    for (text, size, bold, indent, space_before) in styled {
+        let fits = |line: &str| font.text_width(line, size);
+        for (i, text) in wrap(&text, width - indent, fits).into_iter().enumerate() {
+            lines.push(PdfLine {
+                text,
+                size,
+                bold,
+                indent,
+                advance: size * 1.3 + if i == 0 { space_before } else { 0.0 },
+            });
+        }
+    }
+
+    // Paginate
+    let mut used = BTreeMap::new();
+    let mut pages: Vec<Vec<u8>> = Vec::new();
+    let mut content = Vec::new();
+    let mut y = PAGE_HEIGHT - PAGE_MARGIN;
+    for line in &lines {
+        if y - line.advance < PAGE_MARGIN && !content.is_empty() {
+            pages.push(std::mem::take(&mut content));
+            y = PAGE_HEIGHT - PAGE_MARGIN;
+        }
+        y -= line.advance;
+        // The font has no bold face, so bold text is also stroked
+        let style = if line.bold {
+            format!("2 Tr {:.2} w", line.size * 0.03)
+        } else {
+            "0 Tr".to_string()
+        };
+        content.extend_from_slice(
+            format!(
+                "BT /F1 {} Tf {} {:.1} {:.1} Td <{}> Tj ET\n",
+                line.size,
+                style,
+                PAGE_MARGIN + line.indent,
+                y,
+                encode_glyphs(&line.text, &font, &mut used)
+            )
+            .as_bytes(),
+        );
+    }
+    if !content.is_empty() || pages.is_empty() {
+        pages.push(content);
+    }
+
+    // Objects: 1 catalog, 2 page tree, 3-7 font, then a page and its content per page
+    let base_font = format!("GIGAWH+{}", font.name());
+    let widths: Vec<String> = used
+        .keys()
+        .map(|&glyph| format!("{} [{}]", glyph, font.advance(glyph).round()))
+        .collect();
+    let (ascent, descent) = font.vertical_metrics();
+    let bbox = font.bbox().map(|v| font.scaled(v).to_string());
+    let glyphs: BTreeSet<u16> = used.keys().copied().collect();
+    let font_file = font.subset(&glyphs);
+
+    let mut objects: Vec<Vec<u8>> = Vec::new();
+    let kids: Vec<String> = (0..pages.len()).map(|i| format!("{} 0 R", 8 + 2 * i)).collect();
+    objects.push(b"<< /Type /Catalog /Pages 2 0 R >>".to_vec());
+    objects.push(
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+    );
+    objects.push(
+        format!(
+            concat!(
+                "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H ",
+                "/DescendantFonts [4 0 R] /ToUnicode 7 0 R >>"
+            ),
+            base_font
+        )
+        .into_bytes(),
+    );
+    objects.push(
+        format!(
+            concat!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} ",
+                "/CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> ",
+                "/FontDescriptor 5 0 R /CIDToGIDMap /Identity /W [{}] >>"
+            ),
+            base_font,
+            widths.join(" ")
+        )
+        .into_bytes(),
+    );
+    objects.push(
+        format!(
+            concat!(
+                "<< /Type /FontDescriptor /FontName /{} /Flags 32 /FontBBox [{}] ",
+                "/ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 6 0 R >>"
+            ),
+            base_font,
+            bbox.join(" "),
+            font.scaled(ascent),
+            font.scaled(descent),
+            font.scaled(ascent)
+        )
+        .into_bytes(),
+    );
+    let length1 = format!("/Length1 {} ", font_file.len());
+    objects.push(pdf_stream(&length1, &font_file));
+    objects.push(pdf_stream("", to_unicode_cmap(&used).as_bytes()));
+    for (i, content) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                concat!(
+                    "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] ",
+                    "/Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>"
+                ),
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                9 + 2 * i
+            )
+            .into_bytes(),
+        );
+        objects.push(pdf_stream("", content));
+    }
+
+    // Binary marker so transfers keep the embedded font intact
+    let mut pdf = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (i, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+        pdf.extend_from_slice(object);
+        pdf.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend_from_slice(
+        format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).as_bytes(),
+    );
+    for offset in offsets {
+        pdf.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+    }
+    pdf.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .as_bytes(),
+    );
+    pdf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(text: &str, alerts: &[&str]) -> HistoryEntry {
+        HistoryEntry {
+            id: "1".to_string(),
+            text: text.to_string(),
+            timestamp: "2024-03-05T14:00:00Z".to_string(),
+            duration_ms: 125_000,
+            provider: "test".to_string(),
+            language: Some("en".to_string()),
+            audio_path: None,
+            waveform: Vec::new(),
+            screenshot_path: None,
+            alerts: alerts.iter().map(|a| a.to_string()).collect(),
+            title: "Weekly sync".to_string(),
+            note: None,
+            recording_ms: None,
+            segments: Vec::new(),
+            translation: None,
+            tags: Vec::new(),
+            source: None,
+        }
+    }
+
+    #[test]
+    fn test_minutes_from_entry() {
+        let minutes = MeetingMinutes::from_entry(&entry(
+            "We reviewed the roadmap. Alice will send the budget!\n\nTodo: book the room. Done.",
+            &["alice"],
+        ));
+        assert_eq!(minutes.date, "2024-03-05");
+        assert_eq!(minutes.chapters.len(), 2);
+        assert_eq!(minutes.chapters[1].title, "Part 2");
+        assert_eq!(
+            minutes.action_items,
+            vec!["Alice will send the budget!", "Todo: book the room."]
+        );
+    }
+
+    #[test]
+    fn test_layout_skips_empty_sections() {
+        let minutes = MeetingMinutes::from_entry(&entry("Short update.", &[]));
+        let blocks = layout(&minutes, &MinutesTemplate::default());
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Title("Meeting Minutes - 2024-03-05".to_string()),
+                Block::Heading("Discussion".to_string()),
+                Block::Heading("Transcript".to_string()),
+                Block::Paragraph("Short update.".to_string()),
+                Block::Footer("Transcribed with GigaWhisper (2 min 5 s)".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_layout_includes_title_and_notes() {
+        let mut entry = entry("Short update.", &[]);
+        entry.note = Some("Follow up next week".to_string());
+        let template = MinutesTemplate {
+            title: "{title} ({date})".to_string(),
+            ..MinutesTemplate::default()
+        };
+        let blocks = layout(&MeetingMinutes::from_entry(&entry), &template);
+        assert_eq!(blocks[0], Block::Title("Weekly sync (2024-03-05)".to_string()));
+        assert_eq!(blocks[1], Block::Heading("Notes".to_string()));
+        assert_eq!(blocks[2], Block::Paragraph("Follow up next week".to_string()));
+    }
+
+    #[test]
+    fn test_template_name_is_validated() {
+        assert!(MinutesTemplate::load(None).is_ok());
+        assert!(matches!(
+            MinutesTemplate::load(Some("../secrets")),
+            Err(MinutesError::Template(_))
+        ));
+    }
+
+    #[test]
+    fn test_docx_is_stored_zip() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+
+        let docx = render_docx(&[Block::Title("R&D <sync>".to_string())]);
+        assert_eq!(&docx[..4], b"PK\x03\x04");
+        let text = String::from_utf8_lossy(&docx);
+        assert!(text.contains("word/document.xml"));
+        assert!(text.contains("R&amp;D &lt;sync&gt;"));
+        // End of central directory lists the three parts
+        let eocd = docx.len() - 22;
+        assert_eq!(&docx[eocd..eocd + 4], b"PK\x05\x06");
+        assert_eq!(u16::from_le_bytes([docx[eocd + 10], docx[eocd + 11]]), 3);
+    }
+
+    #[test]
+    fn test_pdf_embeds_font_and_paginates() {
+        let mut blocks = vec![Block::Title("Notes (draft)".to_string())];
+        blocks.extend((0..60).map(|i| Block::Paragraph(format!("Line {} caf\u{e9}", i))));
+        let cyrillic = "\u{41f}\u{440}\u{438}\u{432}\u{435}\u{442}";
+        blocks.push(Block::Paragraph(cyrillic.to_string()));
+        let pdf = render_pdf(&blocks);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("/Encoding /Identity-H"));
+        assert!(text.contains("/FontFile2 6 0 R"));
+        assert!(text.trim_end().ends_with("%%EOF"));
+
+        // Cyrillic is drawn with real glyphs and maps back to its text
+        let font = TrueTypeFont::bundled();
+        let glyph = font.glyph('\u{41f}').unwrap();
+        assert!(text.contains(&format!("<{:04X}", glyph)));
+        assert!(text.contains(&format!("<{:04X}> <041F>", glyph)));
+    }
+
+    #[test]
+    fn test_wrap_long_words() {
+        let chars = |line: &str| line.chars().count() as f32;
+        let lines = wrap("aaaa bb cccccccccc", 5.0, chars);
+        assert_eq!(lines, vec!["aaaa", "bb", "ccccc", "ccccc"]);
+    }
+}
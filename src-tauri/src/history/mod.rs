@@ -2,7 +2,24 @@
 //!
 //! Store and retrieve transcription history.
 
+mod encryption;
+mod export;
+mod font;
+mod incognito;
+mod integrity;
+mod minutes;
+
+pub use encryption::*;
+pub use export::*;
+pub use incognito::*;
+pub use integrity::*;
+pub use minutes::*;
+
+use crate::config::{AudioSource, HistoryAudioCodec};
+use crate::transcription::TranscriptSegment;
+use crate::utils::primary_language;
 use parking_lot::RwLock;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::path::PathBuf;
@@ -11,11 +28,26 @@ use std::sync::OnceLock;
 /// Maximum number of history entries to keep
 const MAX_HISTORY_ENTRIES: usize = 100;
 
+/// Number of points in a waveform thumbnail
+pub const WAVEFORM_POINTS: usize = 200;
+
+/// Longest title generated from an entry's first sentence
+const AUTO_TITLE_CHARS: usize = 60;
+
+/// Longest title a user can set
+pub const MAX_TITLE_CHARS: usize = 200;
+
+/// Longest note a user can attach
+pub const MAX_NOTE_CHARS: usize = 10_000;
+
+/// Longest tag a user can attach
+pub const MAX_TAG_CHARS: usize = 40;
+
 /// Global history instance
 static HISTORY: OnceLock<RwLock<TranscriptionHistory>> = OnceLock::new();
 
 /// A single transcription entry in history
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct HistoryEntry {
     /// Unique identifier
     pub id: String,
@@ -32,12 +64,191 @@ pub struct HistoryEntry {
     /// Path to the audio file (optional, for playback)
     #[serde(default)]
     pub audio_path: Option<String>,
+    /// Peak amplitude per segment of the audio (0-255), for waveform thumbnails
+    #[serde(default)]
+    pub waveform: Vec<u8>,
+    /// Thumbnail of the window the text was sent to (optional, opt-in)
+    #[serde(default)]
+    pub screenshot_path: Option<String>,
+    /// Alert keywords found in the text
+    #[serde(default)]
+    pub alerts: Vec<String>,
+    /// Title, generated from the first sentence unless set by the user
+    #[serde(default)]
+    pub title: String,
+    /// Free-form note attached by the user
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Length of the recording before silence was trimmed from the saved audio
+    #[serde(default)]
+    pub recording_ms: Option<u64>,
+    /// Timed segments of the transcription, for subtitles
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    /// English translation of the text, kept in language learning mode
+    #[serde(default)]
+    pub translation: Option<String>,
+    /// User-defined tags, in the order they were added
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// What was recorded (None for transcribed files and older entries)
+    #[serde(default)]
+    pub source: Option<AudioSource>,
+}
+
+/// Optional details attached to a new history entry
+#[derive(Debug, Clone, Default)]
+pub struct EntryDetails {
+    /// Thumbnail of the target window
+    pub screenshot_path: Option<String>,
+    /// Alert keywords found in the text
+    pub alerts: Vec<String>,
+    /// Length of the recording when the saved audio is trimmed
+    pub recording_ms: Option<u64>,
+    /// Timed segments of the transcription
+    pub segments: Vec<TranscriptSegment>,
+    /// English translation of the text
+    pub translation: Option<String>,
+    /// What was recorded
+    pub source: Option<AudioSource>,
+    /// Keep the audio out of the audio directory (the waveform is still kept)
+    pub discard_audio: bool,
+}
+
+/// Lightweight view of a history entry for quick-paste lookups
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct RecentText {
+    /// Entry identifier (pass to `paste_history_entry`)
+    pub id: String,
+    /// The transcribed text
+    pub text: String,
+    /// Timestamp when transcription was created (ISO 8601)
+    pub timestamp: String,
+    pub title: String,
+}
+
+impl From<&HistoryEntry> for RecentText {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            id: entry.id.clone(),
+            text: entry.text.clone(),
+            timestamp: entry.timestamp.clone(),
+            title: entry.title.clone(),
+        }
+    }
+}
+
+/// Filters of a history search; unset filters match every entry
+#[derive(Debug, Clone, Default, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct HistoryQuery {
+    /// Words that must all appear in the text, title, note or translation
+    /// (any case)
+    pub text: Option<String>,
+    /// Earliest timestamp (ISO 8601, inclusive)
+    pub from: Option<String>,
+    /// Latest timestamp (ISO 8601, inclusive; a date alone covers the whole day)
+    pub to: Option<String>,
+    /// Provider name ("whisper.cpp", "groq", ...)
+    pub provider: Option<String>,
+    /// Language code, region subtags ignored
+    pub language: Option<String>,
+    /// Tags that must all be on the entry (any case)
+    pub tags: Vec<String>,
+    /// Matching entries to skip, for paging
+    pub offset: usize,
+    /// Maximum number of entries returned
+    pub limit: Option<usize>,
+}
+
+/// A page of history search results, newest first
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HistorySearchResult {
+    pub entries: Vec<HistoryEntry>,
+    /// Number of matching entries, including those outside the page
+    pub total: usize,
+}
+
+/// Query with its words lowercased once for all entries
+struct PreparedQuery<'a> {
+    query: &'a HistoryQuery,
+    words: Vec<String>,
+}
+
+impl PreparedQuery<'_> {
+    fn matches(&self, entry: &HistoryEntry) -> bool {
+        let query = self.query;
+        let stamp = entry.timestamp.as_str();
+        if query.from.as_deref().is_some_and(|from| timestamp_prefix(stamp, from) < from)
+            || query.to.as_deref().is_some_and(|to| timestamp_prefix(stamp, to) > to)
+        {
+            return false;
+        }
+        if let Some(provider) = &query.provider {
+            if !entry.provider.eq_ignore_ascii_case(provider) {
+                return false;
+            }
+        }
+        if let Some(language) = &query.language {
+            let entry_language = entry.language.as_deref().unwrap_or_default();
+            if !primary_language(entry_language).eq_ignore_ascii_case(primary_language(language)) {
+                return false;
+            }
+        }
+        if !query
+            .tags
+            .iter()
+            .all(|tag| entry.tags.iter().any(|t| t.eq_ignore_ascii_case(tag.trim())))
+        {
+            return false;
+        }
+        if self.words.is_empty() {
+            return true;
+        }
+
+        let fields = [
+            Some(&entry.text),
+            Some(&entry.title),
+            entry.note.as_ref(),
+            entry.translation.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|field| field.to_lowercase())
+        .collect::<Vec<_>>();
+        self.words
+            .iter()
+            .all(|word| fields.iter().any(|field| field.contains(word.as_str())))
+    }
+}
+
+/// A tag in use and the number of entries carrying it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct TagCount {
+    pub tag: String,
+    pub entries: usize,
+}
+
+/// Tag with surrounding whitespace trimmed and inner runs collapsed
+pub fn normalize_tag(tag: &str) -> String {
+    tag.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The part of `timestamp` as long as `bound`, so a date bound covers its whole day
+fn timestamp_prefix<'a>(timestamp: &'a str, bound: &str) -> &'a str {
+    timestamp.get(..bound.len()).unwrap_or(timestamp)
 }
 
 /// Transcription history storage
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct TranscriptionHistory {
     entries: VecDeque<HistoryEntry>,
+    /// Rows of the history file that failed to parse at load
+    #[serde(skip)]
+    corrupted_rows: Vec<CorruptedRow>,
+    /// The history file could not be decrypted; saving would overwrite it
+    #[serde(skip)]
+    locked: bool,
 }
 
 impl TranscriptionHistory {
@@ -45,23 +256,62 @@ impl TranscriptionHistory {
     pub fn new() -> Self {
         Self {
             entries: VecDeque::new(),
+            corrupted_rows: Vec::new(),
+            locked: false,
         }
     }
 
+    /// Whether the history file could not be decrypted at load
+    ///
+    /// A locked history starts empty and refuses to save, so the encrypted
+    /// file survives until its key is available again.
+    pub fn is_locked(&self) -> bool {
+        self.locked
+    }
+
     /// Load history from disk
+    ///
+    /// A file that fails to parse is copied aside before its readable rows
+    /// are loaded, so the next save does not lose the rest.
     pub fn load() -> Self {
         let path = history_file_path();
         if path.exists() {
             match std::fs::read_to_string(&path) {
                 Ok(content) => {
-                    match serde_json::from_str(&content) {
+                    let content = match encryption::open_history(content) {
+                        Ok(content) => content,
+                        Err(e) => {
+                            // Keep the file for when the key is available again
+                            tracing::error!("Failed to open encrypted history file: {}", e);
+                            if let Err(e) = std::fs::copy(&path, path.with_extension("json.corrupt")) {
+                                tracing::warn!("Failed to back up history file: {}", e);
+                            }
+                            let mut history = Self::new();
+                            history.locked = true;
+                            history.corrupted_rows.push(CorruptedRow {
+                                index: None,
+                                error: e.to_string(),
+                            });
+                            return history;
+                        }
+                    };
+                    match serde_json::from_str::<Self>(&content) {
                         Ok(history) => {
-                            tracing::info!("Loaded {} history entries",
-                                match &history { TranscriptionHistory { entries } => entries.len() });
+                            tracing::info!("Loaded {} history entries", history.entries.len());
                             return history;
                         }
                         Err(e) => {
                             tracing::warn!("Failed to parse history file: {}", e);
+                            if let Err(e) = std::fs::copy(&path, path.with_extension("json.corrupt")) {
+                                tracing::warn!("Failed to back up history file: {}", e);
+                            }
+                            let history = integrity::salvage_history(&content);
+                            tracing::warn!(
+                                "Recovered {} history entries, dropped {} rows",
+                                history.entries.len(),
+                                history.corrupted_rows.len()
+                            );
+                            return history;
                         }
                     }
                 }
@@ -75,6 +325,11 @@ impl TranscriptionHistory {
 
     /// Save history to disk
     pub fn save(&self) -> Result<(), std::io::Error> {
+        if self.locked {
+            return Err(std::io::Error::other(
+                "history is locked: the encrypted history file could not be opened",
+            ));
+        }
         let path = history_file_path();
 
         // Ensure directory exists
@@ -84,6 +339,7 @@ impl TranscriptionHistory {
 
         let content = serde_json::to_string_pretty(self)
             .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+        let content = encryption::seal_history(content).map_err(std::io::Error::other)?;
 
         std::fs::write(&path, content)?;
         tracing::debug!("History saved to {:?}", path);
@@ -91,10 +347,20 @@ impl TranscriptionHistory {
     }
 
     /// Add a new entry to history
-    pub fn add(&mut self, entry: HistoryEntry) {
+    ///
+    /// An entry without a title gets one from its first sentence.
+    /// Screenshots of entries dropped to make room are deleted with them.
+    pub fn add(&mut self, mut entry: HistoryEntry) {
+        if entry.title.trim().is_empty() {
+            entry.title = auto_title(&entry.text);
+        }
+
+
         // Remove oldest if at capacity
         while self.entries.len() >= MAX_HISTORY_ENTRIES {
-            self.entries.pop_back();
+            if let Some(path) = self.entries.pop_back().and_then(|e| e.screenshot_path) {
+                let _ = std::fs::remove_file(path);
+            }
         }
 
         // Add new entry at front
@@ -111,6 +377,120 @@ impl TranscriptionHistory {
         self.entries.iter().find(|e| e.id == id).cloned()
     }
 
+    /// Replace the text of an entry, returns true if found
+    pub fn set_text(&mut self, id: &str, text: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.text = text.to_string();
+                // Segment timings no longer match the edited text
+                entry.segments.clear();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the title of an entry, returns true if found
+    ///
+    /// A blank title is replaced by one generated from the text.
+    pub fn set_title(&mut self, id: &str, title: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                let title = title.trim();
+                entry.title = if title.is_empty() {
+                    auto_title(&entry.text)
+                } else {
+                    title.to_string()
+                };
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the note of an entry (blank removes it), returns true if found
+    pub fn set_note(&mut self, id: &str, note: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.note = Some(note.trim())
+                    .filter(|n| !n.is_empty())
+                    .map(str::to_string);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Point an entry at its re-encoded audio file, returns true if found
+    pub fn set_audio_path(&mut self, id: &str, audio_path: String) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.audio_path = Some(audio_path);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Set the English translation of an entry, returns true if found
+    pub fn set_translation(&mut self, id: &str, translation: String) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                entry.translation = Some(translation);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Add a tag to an entry, returns true if found
+    ///
+    /// Whitespace in the tag is collapsed; a tag already on the entry in
+    /// any case is not added again.
+    pub fn add_tag(&mut self, id: &str, tag: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                let tag = normalize_tag(tag);
+                if !tag.is_empty() && !entry.tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                    entry.tags.push(tag);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove a tag (any case) from an entry, returns true if found
+    pub fn remove_tag(&mut self, id: &str, tag: &str) -> bool {
+        match self.entries.iter_mut().find(|e| e.id == id) {
+            Some(entry) => {
+                let tag = normalize_tag(tag);
+                entry.tags.retain(|t| !t.eq_ignore_ascii_case(&tag));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Every tag in use with its number of entries, sorted by name
+    ///
+    /// Tags differing only in case are counted together under their most
+    /// recent spelling.
+    pub fn tags(&self) -> Vec<TagCount> {
+        let mut counts: Vec<TagCount> = Vec::new();
+        for tag in self.entries.iter().flat_map(|e| &e.tags) {
+            match counts.iter_mut().find(|c| c.tag.eq_ignore_ascii_case(tag)) {
+                Some(count) => count.entries += 1,
+                None => counts.push(TagCount {
+                    tag: tag.clone(),
+                    entries: 1,
+                }),
+            }
+        }
+        counts.sort_by_key(|c| c.tag.to_lowercase());
+        counts
+    }
+
     /// Delete entry by ID
     pub fn delete(&mut self, id: &str) -> bool {
         let len_before = self.entries.len();
@@ -132,30 +512,209 @@ impl TranscriptionHistory {
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
     }
+
+    /// Entries with saved audio but no waveform yet, as (id, audio path)
+    pub fn missing_waveforms(&self) -> Vec<(String, String)> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.waveform.is_empty())
+            .filter_map(|entry| Some((entry.id.clone(), entry.audio_path.clone()?)))
+            .collect()
+    }
+
+    /// Set the waveform of an entry that has none yet, returns whether it was set
+    pub fn set_missing_waveform(&mut self, id: &str, waveform: Vec<u8>) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) if entry.waveform.is_empty() => {
+                entry.waveform = waveform;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Generate titles for entries saved before titles existed, returns the count
+    pub fn backfill_titles(&mut self) -> usize {
+        let mut updated = 0;
+        for entry in self.entries.iter_mut() {
+            if entry.title.trim().is_empty() && !entry.text.trim().is_empty() {
+                entry.title = auto_title(&entry.text);
+                updated += 1;
+            }
+        }
+        updated
+    }
+
+    /// Get the most recent non-empty texts (newest first)
+    ///
+    /// Only the fields needed by the quick-paste palette are cloned.
+    pub fn recent_texts(&self, limit: usize) -> Vec<RecentText> {
+        self.entries
+            .iter()
+            .filter(|e| !e.text.trim().is_empty())
+            .take(limit)
+            .map(RecentText::from)
+            .collect()
+    }
+
+    /// Entries matching all filters of `query`, newest first
+    ///
+    /// Only the entries of the requested page are cloned.
+    pub fn search(&self, query: &HistoryQuery) -> HistorySearchResult {
+        let prepared = PreparedQuery {
+            query,
+            words: query
+                .text
+                .as_deref()
+                .unwrap_or_default()
+                .split_whitespace()
+                .map(str::to_lowercase)
+                .collect(),
+        };
+        let limit = query.limit.unwrap_or(usize::MAX);
+
+        let mut total = 0;
+        let mut entries = Vec::new();
+        for entry in self.entries.iter().filter(|e| prepared.matches(e)) {
+            if total >= query.offset && entries.len() < limit {
+                entries.push(entry.clone());
+            }
+            total += 1;
+        }
+        HistorySearchResult { entries, total }
+    }
+
+    /// Fuzzy search entry texts, titles, notes and translations, best matches
+    /// first (ties keep newest first)
+    pub fn fuzzy_search(&self, query: &str, limit: usize) -> Vec<RecentText> {
+        if query.trim().is_empty() {
+            return self.recent_texts(limit);
+        }
+
+        let mut scored: Vec<(u32, usize, &HistoryEntry)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, e)| {
+                [Some(&e.text), Some(&e.title), e.note.as_ref(), e.translation.as_ref()]
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|field| fuzzy_score(query, field))
+                    .max()
+                    .map(|score| (score, index, e))
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, _, e)| RecentText::from(e))
+            .collect()
+    }
+}
+
+/// Title from the first sentence of `text`, shortened at a word boundary
+pub fn auto_title(text: &str) -> String {
+    let first = text
+        .split_inclusive(['.', '!', '?', '\n'])
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .unwrap_or_default();
+    let first = first.trim_end_matches(['.', '\n']);
+    if first.chars().count() <= AUTO_TITLE_CHARS {
+        return first.to_string();
+    }
+
+    let cut: String = first.chars().take(AUTO_TITLE_CHARS).collect();
+    let cut = match cut.rfind(char::is_whitespace) {
+        Some(space) if space > 0 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end_matches([',', ';', ':', ' ']))
+}
+
+/// Score how well `query` fuzzily matches `text`
+///
+/// Every non-whitespace query character must appear in the text in order
+/// (case-insensitive). Consecutive matches, matches at word starts and plain
+/// substring matches score higher. Returns None if the query does not match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let needle: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    if needle.is_empty() {
+        return Some(0);
+    }
+
+    let mut score = 0u32;
+    let mut matched = 0;
+    let mut prev_matched = false;
+    let mut prev_char: Option<char> = None;
+
+    for c in text.chars() {
+        if matched == needle.len() {
+            break;
+        }
+
+        let lower = c.to_lowercase().next().unwrap_or(c);
+        if lower == needle[matched] {
+            score += 1;
+            if prev_matched {
+                score += 5;
+            }
+            if prev_char.is_none_or(|p| !p.is_alphanumeric()) {
+                score += 10;
+            }
+            matched += 1;
+            prev_matched = true;
+        } else {
+            prev_matched = false;
+        }
+        prev_char = Some(c);
+    }
+
+    if matched < needle.len() {
+        return None;
+    }
+
+    if text.to_lowercase().contains(&query.trim().to_lowercase()) {
+        score += 50;
+    }
+
+    Some(score)
 }
 
 /// Get the history file path
 fn history_file_path() -> PathBuf {
-    crate::config::models_dir()
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
-        .join("history.json")
+    crate::config::workspace_dir().join("history.json")
 }
 
 /// Get the audio files directory
 pub fn audio_dir() -> PathBuf {
-    crate::config::models_dir()
-        .parent()
-        .map(|p| p.to_path_buf())
-        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default())
-        .join("audio")
+    crate::config::workspace_dir().join("audio")
 }
 
-/// Save audio samples to a WAV file and return the path
-pub fn save_audio_file(samples: &[f32], sample_rate: u32, id: &str) -> Result<PathBuf, std::io::Error> {
-    let audio_path = audio_dir();
-    std::fs::create_dir_all(&audio_path)?;
+/// Get the window screenshots directory
+pub fn screenshots_dir() -> PathBuf {
+    crate::config::workspace_dir().join("screenshots")
+}
+
+/// Save audio samples to a WAV file in `audio_path` and return the path
+///
+/// Compression to the configured codec happens later, with
+/// `compress_entry_audio`, so saving doesn't wait for `ffmpeg`.
+pub fn save_audio_file(
+    audio_path: &std::path::Path,
+    samples: &[f32],
+    sample_rate: u32,
+    id: &str,
+) -> Result<PathBuf, std::io::Error> {
+    std::fs::create_dir_all(audio_path)?;
 
     let file_path = audio_path.join(format!("{}.wav", id));
 
@@ -184,9 +743,130 @@ pub fn save_audio_file(samples: &[f32], sample_rate: u32, id: &str) -> Result<Pa
     Ok(file_path)
 }
 
+/// Re-encode the WAV audio of an entry with `codec` and point the entry at
+/// the new file
+///
+/// Blocks while `ffmpeg` runs. Returns whether the entry changed; on error
+/// the WAV is kept.
+pub fn compress_entry_audio(
+    id: &str,
+    codec: HistoryAudioCodec,
+) -> Result<bool, crate::audio::TranscodeError> {
+    if codec == HistoryAudioCodec::Wav {
+        return Ok(false);
+    }
+    let Some(source) = get_history().read().get(id).and_then(|e| e.audio_path) else {
+        return Ok(false);
+    };
+
+    let compressed = crate::audio::compress_recording(std::path::Path::new(&source), codec)?;
+    let mut history = get_history().write();
+    if !history.set_audio_path(id, compressed.to_string_lossy().to_string()) {
+        // Deleted while it was being encoded
+        let _ = std::fs::remove_file(&compressed);
+        return Ok(false);
+    }
+    if let Err(e) = history.save() {
+        tracing::error!("Failed to save history: {}", e);
+    }
+    Ok(true)
+}
+
+/// Peak amplitude of each of `points` equal segments, scaled to 0-255
+///
+/// Returns fewer points when there are fewer samples than `points`.
+pub fn waveform_peaks(samples: &[f32], points: usize) -> Vec<u8> {
+    if samples.is_empty() || points == 0 {
+        return Vec::new();
+    }
+
+    let segment = samples.len().div_ceil(points);
+    samples
+        .chunks(segment)
+        .map(|chunk| {
+            let peak = chunk.iter().fold(0.0f32, |max, s| max.max(s.abs()));
+            (peak.min(1.0) * 255.0).round() as u8
+        })
+        .collect()
+}
+
+/// Read a saved recording (WAV, FLAC or Opus) as mono f32 samples
+fn read_audio_samples(path: &std::path::Path) -> Result<Vec<f32>, std::io::Error> {
+    crate::audio::decode_audio_file(path)
+        .map(|(samples, _)| samples)
+        .map_err(std::io::Error::other)
+}
+
+/// Length of a saved audio file (WAV, FLAC or Opus)
+///
+/// WAV is measured from its header; compressed files are decoded.
+pub fn audio_duration_ms(path: &std::path::Path) -> Option<u64> {
+    if let Ok(reader) = hound::WavReader::open(path) {
+        let sample_rate = reader.spec().sample_rate.max(1) as u64;
+        return Some(reader.duration() as u64 * 1000 / sample_rate);
+    }
+    let (samples, sample_rate) = crate::audio::decode_audio_file(path).ok()?;
+    Some(samples.len() as u64 * 1000 / sample_rate.max(1) as u64)
+}
+
 /// Get or initialize the global history instance
 pub fn get_history() -> &'static RwLock<TranscriptionHistory> {
-    HISTORY.get_or_init(|| RwLock::new(TranscriptionHistory::load()))
+    HISTORY.get_or_init(|| RwLock::new(load_history()))
+}
+
+/// Reload the global history from the active workspace
+pub fn reload_history() {
+    let history = load_history();
+    *get_history().write() = history;
+}
+
+fn load_history() -> TranscriptionHistory {
+    let mut history = TranscriptionHistory::load();
+
+    // Entries saved before titles existed get them once, waveforms are
+    // computed in the background by `backfill_waveforms`
+    if history.backfill_titles() > 0 {
+        if let Err(e) = history.save() {
+            tracing::error!("Failed to save history: {}", e);
+        }
+    }
+
+    history
+}
+
+/// Compute missing waveforms of the global history from saved audio files,
+/// returns the count updated
+///
+/// Audio is decoded without holding the history lock, so a long history
+/// doesn't stall startup or workspace switches.
+pub fn backfill_waveforms() -> usize {
+    let pending = get_history().read().missing_waveforms();
+    let computed: Vec<(String, Vec<u8>)> = pending
+        .into_iter()
+        .filter_map(|(id, path)| match read_audio_samples(path.as_ref()) {
+            Ok(samples) => Some((id, waveform_peaks(&samples, WAVEFORM_POINTS))),
+            Err(e) => {
+                tracing::debug!("No waveform for entry {}: {}", id, e);
+                None
+            }
+        })
+        .collect();
+    if computed.is_empty() {
+        return 0;
+    }
+
+    let mut history = get_history().write();
+    let updated = computed
+        .into_iter()
+        .map(|(id, waveform)| history.set_missing_waveform(&id, waveform))
+        .filter(|&set| set)
+        .count();
+    if updated > 0 {
+        if let Err(e) = history.save() {
+            tracing::error!("Failed to save history: {}", e);
+        }
+    }
+    updated
 }
 
 /// Add a transcription to history
@@ -205,6 +885,16 @@ pub fn add_transcription(
         provider,
         language,
         audio_path,
+        waveform: Vec::new(),
+        screenshot_path: None,
+        alerts: Vec::new(),
+        title: String::new(),
+        note: None,
+        recording_ms: None,
+        segments: Vec::new(),
+        translation: None,
+        tags: Vec::new(),
+        source: None,
     };
 
     let history = get_history();
@@ -225,26 +915,29 @@ pub fn add_transcription_with_audio(
     language: Option<String>,
     samples: &[f32],
     sample_rate: u32,
-) {
+    details: EntryDetails,
+) -> String {
     let id = uuid::Uuid::new_v4().to_string();
-
-    // Save audio file
-    let audio_path = match save_audio_file(samples, sample_rate, &id) {
-        Ok(path) => Some(path.to_string_lossy().to_string()),
-        Err(e) => {
-            tracing::error!("Failed to save audio file: {}", e);
-            None
-        }
-    };
+    let audio_path = save_entry_audio(&audio_dir(), samples, sample_rate, &id, &details);
 
     let entry = HistoryEntry {
-        id,
+        id: id.clone(),
         text,
         timestamp: chrono_timestamp(),
         duration_ms,
         provider,
         language,
         audio_path,
+        waveform: waveform_peaks(samples, WAVEFORM_POINTS),
+        screenshot_path: details.screenshot_path,
+        alerts: details.alerts,
+        title: String::new(),
+        note: None,
+        recording_ms: details.recording_ms,
+        segments: details.segments,
+        translation: details.translation,
+        tags: Vec::new(),
+        source: details.source,
     };
 
     let history = get_history();
@@ -255,18 +948,42 @@ pub fn add_transcription_with_audio(
             tracing::error!("Failed to save history: {}", e);
         }
     }
+    id
+}
+
+/// Save the audio of a new entry in `dir`, unless `details` discards it
+fn save_entry_audio(
+    dir: &std::path::Path,
+    samples: &[f32],
+    sample_rate: u32,
+    id: &str,
+    details: &EntryDetails,
+) -> Option<String> {
+    if details.discard_audio {
+        return None;
+    }
+    match save_audio_file(dir, samples, sample_rate, id) {
+        Ok(path) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            tracing::error!("Failed to save audio file: {}", e);
+            None
+        }
+    }
 }
 
 /// Get current timestamp in ISO 8601 format
-fn chrono_timestamp() -> String {
+pub fn chrono_timestamp() -> String {
     use std::time::{SystemTime, UNIX_EPOCH};
 
     let duration = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap_or_default();
 
-    let secs = duration.as_secs();
+    timestamp_from_unix(duration.as_secs())
+}
 
+/// Format unix seconds as an ISO 8601 timestamp
+pub fn timestamp_from_unix(secs: u64) -> String {
     // Simple ISO 8601 format without chrono dependency
     // Format: 2024-01-15T10:30:00Z
     let days = secs / 86400;
@@ -332,6 +1049,16 @@ mod tests {
             provider: "test-provider".to_string(),
             language: Some("en".to_string()),
             audio_path,
+            waveform: Vec::new(),
+            screenshot_path: None,
+            alerts: Vec::new(),
+            title: String::new(),
+            note: None,
+            recording_ms: None,
+            segments: Vec::new(),
+            translation: None,
+            tags: Vec::new(),
+            source: None,
         }
     }
 
@@ -860,6 +1587,333 @@ mod tests {
         assert!(test_history.history.get("does-not-exist").is_none());
     }
 
+    #[test]
+    fn test_recent_texts_limit_and_order() {
+        let mut history = TranscriptionHistory::new();
+        for i in 1..=5 {
+            history.add(create_test_entry(&format!("entry-{}", i), &format!("Text {}", i), None));
+        }
+        history.add(create_test_entry("blank", "   ", None));
+
+        let recent = history.recent_texts(3);
+
+        // Empty transcriptions are skipped, newest first
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].id, "entry-5");
+        assert_eq!(recent[1].id, "entry-4");
+        assert_eq!(recent[2].id, "entry-3");
+    }
+
+    #[test]
+    fn test_fuzzy_score_matching() {
+        assert!(fuzzy_score("hlo", "hello").is_some());
+        assert!(fuzzy_score("HELLO", "hello world").is_some());
+        assert!(fuzzy_score("hw", "hello world").is_some());
+        assert!(fuzzy_score("xyz", "hello world").is_none());
+        assert!(fuzzy_score("olleh", "hello").is_none());
+        assert_eq!(fuzzy_score("   ", "anything"), Some(0));
+    }
+
+    #[test]
+    fn test_fuzzy_score_prefers_substring_and_word_starts() {
+        let substring = fuzzy_score("meet", "schedule a meeting").unwrap();
+        let scattered = fuzzy_score("meet", "my embedded test").unwrap();
+        assert!(substring > scattered);
+    }
+
+    #[test]
+    fn test_fuzzy_search_ranking() {
+        let mut history = TranscriptionHistory::new();
+        history.add(create_test_entry("a", "Send the invoice tomorrow", None));
+        history.add(create_test_entry("b", "Meeting notes for Monday", None));
+        history.add(create_test_entry("c", "Remember to email the team", None));
+
+        let results = history.fuzzy_search("meeting", 10);
+        assert_eq!(results[0].id, "b");
+
+        let results = history.fuzzy_search("zzz", 10);
+        assert!(results.is_empty());
+
+        // Empty query falls back to recent texts
+        let results = history.fuzzy_search("", 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, "c");
+    }
+
+    #[test]
+    fn test_search_filters() {
+        let mut history = TranscriptionHistory::new();
+        let mut entry = |id: &str, text: &str, timestamp: &str, provider: &str, language: &str| {
+            let mut entry = create_test_entry(id, text, None);
+            entry.timestamp = timestamp.to_string();
+            entry.provider = provider.to_string();
+            entry.language = Some(language.to_string());
+            history.add(entry);
+        };
+        entry("a", "Budget review with Anna", "2024-01-10T09:00:00Z", "groq", "en");
+        entry("b", "Réunion budget", "2024-01-15T23:59:00Z", "whisper.cpp", "fr");
+        entry("c", "Call the BUDGET office", "2024-02-01T08:00:00Z", "whisper.cpp", "en-US");
+
+        let search = |query: HistoryQuery| -> Vec<String> {
+            history.search(&query).entries.into_iter().map(|e| e.id).collect()
+        };
+        let text = |text: &str| HistoryQuery {
+            text: Some(text.to_string()),
+            ..HistoryQuery::default()
+        };
+
+        assert_eq!(search(text("budget")), vec!["c", "b", "a"]);
+        assert_eq!(search(text("budget anna")), vec!["a"]);
+        assert_eq!(
+            search(HistoryQuery {
+                from: Some("2024-01-11".to_string()),
+                to: Some("2024-01-15".to_string()),
+                ..HistoryQuery::default()
+            }),
+            vec!["b"]
+        );
+        assert_eq!(
+            search(HistoryQuery {
+                provider: Some("whisper.cpp".to_string()),
+                language: Some("en".to_string()),
+                ..text("budget")
+            }),
+            vec!["c"]
+        );
+    }
+
+    #[test]
+    fn test_search_paging() {
+        let mut history = TranscriptionHistory::new();
+        for i in 0..5 {
+            history.add(create_test_entry(&i.to_string(), "note", None));
+        }
+        history.add(create_test_entry("other", "unrelated", None));
+
+        let result = history.search(&HistoryQuery {
+            text: Some("NOTE".to_string()),
+            offset: 1,
+            limit: Some(2),
+            ..HistoryQuery::default()
+        });
+        assert_eq!(result.total, 5);
+        let ids: Vec<&str> = result.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "2"]);
+    }
+
+    #[test]
+    fn test_auto_title() {
+        assert_eq!(auto_title("Call the bank. Then lunch."), "Call the bank");
+        assert_eq!(auto_title("\n  Are you there? Yes"), "Are you there?");
+        assert_eq!(auto_title(""), "");
+        let long = auto_title(&"word ".repeat(30));
+        assert!(long.ends_with("word…"));
+        assert!(long.chars().count() <= AUTO_TITLE_CHARS + 1);
+    }
+
+    #[test]
+    fn test_titles_and_notes() {
+        let mut history = TranscriptionHistory::new();
+        history.add(create_test_entry("a", "Budget review. Numbers look fine.", None));
+        assert_eq!(history.get("a").unwrap().title, "Budget review");
+
+        assert!(history.set_title("a", "  Q3 budget  "));
+        assert!(history.set_note("a", "Ask finance about travel costs"));
+        let entry = history.get("a").unwrap();
+        assert_eq!(entry.title, "Q3 budget");
+        assert_eq!(entry.note.as_deref(), Some("Ask finance about travel costs"));
+
+        // Titles and notes are searched too
+        assert_eq!(history.fuzzy_search("q3", 10)[0].id, "a");
+        assert_eq!(history.fuzzy_search("travel", 10)[0].id, "a");
+
+        assert!(history.set_title("a", " "));
+        assert!(history.set_note("a", ""));
+        let entry = history.get("a").unwrap();
+        assert_eq!(entry.title, "Budget review");
+        assert_eq!(entry.note, None);
+        assert!(!history.set_note("missing", "x"));
+    }
+
+    #[test]
+    fn test_translation_is_searched() {
+        let mut history = TranscriptionHistory::new();
+        let mut entry = create_test_entry("a", "Je voudrais un café", None);
+        entry.translation = Some("I would like a coffee".to_string());
+        history.add(entry);
+
+        let query = HistoryQuery {
+            text: Some("coffee".to_string()),
+            ..HistoryQuery::default()
+        };
+        assert_eq!(history.search(&query).total, 1);
+        assert_eq!(history.fuzzy_search("coffee", 10)[0].id, "a");
+
+        // Entries saved before translations existed still load
+        let json = serde_json::to_value(history.get("a").unwrap()).unwrap();
+        let mut json = json.as_object().unwrap().clone();
+        json.remove("translation");
+        let old: HistoryEntry = serde_json::from_value(json.into()).unwrap();
+        assert_eq!(old.translation, None);
+    }
+
+    #[test]
+    fn test_locked_history_refuses_to_save() {
+        let mut history = TranscriptionHistory::new();
+        history.locked = true;
+        history.add(create_test_entry("a", "kept in memory only", None));
+        assert!(history.save().is_err());
+    }
+
+    #[test]
+    fn test_discarded_audio_is_not_saved() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let samples = vec![0.25; 1600];
+        let mut details = EntryDetails {
+            discard_audio: true,
+            ..EntryDetails::default()
+        };
+
+        assert!(save_entry_audio(dir.path(), &samples, 16000, "a", &details).is_none());
+        assert!(!dir.path().join("a.wav").exists());
+
+        details.discard_audio = false;
+        let path = save_entry_audio(dir.path(), &samples, 16000, "b", &details).unwrap();
+        assert!(std::path::Path::new(&path).exists());
+    }
+
+    #[test]
+    fn test_saved_audio_is_wav_until_compressed() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let samples = vec![0.25; 8000];
+        let path = save_audio_file(dir.path(), &samples, 16000, "a").unwrap();
+        assert_eq!(path, dir.path().join("a.wav"));
+        assert_eq!(audio_duration_ms(&path), Some(500));
+
+        let mut history = TranscriptionHistory::new();
+        history.add(create_test_entry("a", "Hello", None));
+        assert!(history.set_audio_path("a", "a.opus".to_string()));
+        assert_eq!(history.entries[0].audio_path.as_deref(), Some("a.opus"));
+        assert!(!history.set_audio_path("b", "b.opus".to_string()));
+    }
+
+    #[test]
+    fn test_set_translation() {
+        let mut history = TranscriptionHistory::new();
+        history.add(create_test_entry("a", "Je voudrais un café", None));
+
+        assert!(history.set_translation("a", "I would like a coffee".to_string()));
+        assert_eq!(
+            history.get("a").unwrap().translation.as_deref(),
+            Some("I would like a coffee")
+        );
+        assert!(!history.set_translation("missing", "x".to_string()));
+    }
+
+    #[test]
+    fn test_tags() {
+        let mut history = TranscriptionHistory::new();
+        history.add(create_test_entry("a", "Sprint planning", None));
+        history.add(create_test_entry("b", "Buy groceries", None));
+        history.add(create_test_entry("c", "Call the dentist", None));
+
+        assert!(history.add_tag("a", "  work  "));
+        assert!(history.add_tag("a", "WORK"));
+        assert!(history.add_tag("a", "q3   roadmap"));
+        assert!(history.add_tag("b", "personal"));
+        assert!(history.add_tag("c", "Personal"));
+        assert!(!history.add_tag("missing", "work"));
+        assert_eq!(history.get("a").unwrap().tags, vec!["work", "q3 roadmap"]);
+
+        let tagged = |tags: &[&str]| {
+            let query = HistoryQuery {
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                ..HistoryQuery::default()
+            };
+            let result = history.search(&query);
+            result.entries.into_iter().map(|e| e.id).collect::<Vec<_>>()
+        };
+        assert_eq!(tagged(&["personal"]), vec!["c", "b"]);
+        assert_eq!(tagged(&["Work", "q3 roadmap"]), vec!["a"]);
+        assert!(tagged(&["work", "personal"]).is_empty());
+
+        let counts: Vec<(String, usize)> =
+            history.tags().into_iter().map(|c| (c.tag, c.entries)).collect();
+        assert_eq!(
+            counts,
+            vec![
+                ("Personal".to_string(), 2),
+                ("q3 roadmap".to_string(), 1),
+                ("work".to_string(), 1),
+            ]
+        );
+
+        assert!(history.remove_tag("a", "Work"));
+        assert_eq!(history.get("a").unwrap().tags, vec!["q3 roadmap"]);
+        assert!(!history.remove_tag("missing", "work"));
+    }
+
+    #[test]
+    fn test_waveform_peaks() {
+        let mut samples = vec![0.0; 1000];
+        samples[10] = -0.5;
+        samples[999] = 1.5;
+
+        let peaks = waveform_peaks(&samples, 10);
+        assert_eq!(peaks.len(), 10);
+        assert_eq!(peaks[0], 128);
+        assert_eq!(peaks[5], 0);
+        assert_eq!(peaks[9], 255);
+
+        assert_eq!(waveform_peaks(&[0.1, 0.2], 200).len(), 2);
+        assert!(waveform_peaks(&[], 200).is_empty());
+    }
+
+    #[test]
+    fn test_backfill_waveforms() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let wav_path = temp_dir.path().join("old.wav");
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&wav_path, spec).unwrap();
+        for i in 0..16000 {
+            writer.write_sample(if i % 2 == 0 { 16384i16 } else { -16384 }).unwrap();
+        }
+        writer.finalize().unwrap();
+
+        let mut history = TranscriptionHistory::new();
+        history.add(create_test_entry(
+            "old",
+            "before waveforms",
+            Some(wav_path.to_string_lossy().to_string()),
+        ));
+        history.add(create_test_entry("missing", "no audio", Some("/nonexistent.wav".to_string())));
+        history.add(create_test_entry("none", "no audio path", None));
+
+        let pending = history.missing_waveforms();
+        assert_eq!(pending.len(), 2);
+        assert!(read_audio_samples(std::path::Path::new("/nonexistent.wav")).is_err());
+
+        let samples = read_audio_samples(&wav_path).unwrap();
+        let waveform = waveform_peaks(&samples, WAVEFORM_POINTS);
+        assert!(history.set_missing_waveform("old", waveform.clone()));
+        let entry = history.get("old").unwrap();
+        assert_eq!(entry.waveform.len(), WAVEFORM_POINTS);
+        assert!(entry.waveform.iter().all(|&p| p == 128));
+
+        // Already computed entries are skipped
+        assert!(!history.set_missing_waveform("old", waveform));
+        assert_eq!(
+            history.missing_waveforms(),
+            vec![("missing".to_string(), "/nonexistent.wav".to_string())]
+        );
+    }
+
     #[test]
     fn test_special_characters_in_text() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -876,4 +1930,24 @@ mod tests {
         let retrieved = test_history.history.get("special").unwrap();
         assert_eq!(retrieved.text, special_text);
     }
+
+    #[test]
+    fn test_evicted_entry_screenshot_is_deleted() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let screenshot = temp_dir.path().join("oldest.bmp");
+        fs::write(&screenshot, b"BM").unwrap();
+
+        let mut history = TranscriptionHistory::new();
+        let mut oldest = create_test_entry("oldest", "first", None);
+        oldest.screenshot_path = Some(screenshot.to_string_lossy().to_string());
+        history.add(oldest);
+        for i in 1..MAX_HISTORY_ENTRIES {
+            history.add(create_test_entry(&format!("entry-{}", i), "text", None));
+        }
+        assert!(screenshot.exists());
+
+        history.add(create_test_entry("newest", "last", None));
+        assert!(history.get("oldest").is_none());
+        assert!(!screenshot.exists());
+    }
 }
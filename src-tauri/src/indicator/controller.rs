@@ -0,0 +1,198 @@
+//! Indicator Controller
+//!
+//! Single entry point for driving the indicator window. State lives here
+//! rather than in the webview: every change is pushed to the indicator
+//! window only, and the window queries the current snapshot when it loads,
+//! so a state change that happens before its listeners are registered is
+//! not lost.
+
+use super::{indicator_origin, ScreenRect};
+use crate::utils::emit_to_window;
+use crate::AppState;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::Instant;
+use tauri::{AppHandle, Manager, PhysicalPosition, WebviewWindow};
+
+/// Window label of the indicator overlay
+pub const INDICATOR_LABEL: &str = "recording-indicator";
+
+/// Event carrying an `IndicatorSnapshot` to the indicator window
+pub const INDICATOR_STATE_EVENT: &str = "indicator:state";
+
+/// What the indicator displays
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IndicatorState {
+    Recording,
+    Processing,
+}
+
+/// Indicator state as seen by the window
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct IndicatorSnapshot {
+    pub visible: bool,
+    pub state: Option<IndicatorState>,
+    /// Time spent in the current state
+    pub elapsed_ms: u64,
+    /// The recording is incognito (shown distinctly)
+    pub incognito: bool,
+}
+
+struct IndicatorStatus {
+    current: Option<(IndicatorState, Instant)>,
+    incognito: bool,
+}
+
+impl IndicatorStatus {
+    fn snapshot(&self, now: Instant) -> IndicatorSnapshot {
+        IndicatorSnapshot {
+            visible: self.current.is_some(),
+            state: self.current.map(|(state, _)| state),
+            elapsed_ms: self
+                .current
+                .map(|(_, since)| now.duration_since(since).as_millis() as u64)
+                .unwrap_or(0),
+            incognito: self.incognito,
+        }
+    }
+}
+
+static STATUS: Mutex<IndicatorStatus> = Mutex::new(IndicatorStatus {
+    current: None,
+    incognito: false,
+});
+
+/// Current indicator state
+pub fn snapshot() -> IndicatorSnapshot {
+    STATUS.lock().snapshot(Instant::now())
+}
+
+/// Show the indicator in `state`
+///
+/// Positions the window when it becomes visible. Does nothing but record
+/// the state when the indicator is disabled in settings.
+pub fn show(app: &AppHandle, state: IndicatorState) {
+    let was_visible = {
+        let mut status = STATUS.lock();
+        let was_visible = status.current.is_some();
+        if status.current.map(|(current, _)| current) != Some(state) {
+            status.current = Some((state, Instant::now()));
+        }
+        was_visible
+    };
+
+    let app_state = app.state::<AppState>();
+    let (enabled, position) = {
+        let config = app_state.config.read();
+        (config.ui.show_indicator, config.ui.indicator_position.clone())
+    };
+    if !enabled {
+        tracing::debug!("Recording indicator disabled in settings");
+        return;
+    }
+
+    let Some(window) = app.get_webview_window(INDICATOR_LABEL) else {
+        tracing::warn!("Recording indicator window not found");
+        return;
+    };
+
+    if !was_visible {
+        place(app, &window, &position);
+        let _ = window.show();
+        tracing::debug!("Recording indicator shown");
+    }
+    publish(app);
+}
+
+/// Mark the shown recording as incognito, until the indicator is hidden
+pub fn set_incognito(app: &AppHandle) {
+    {
+        let mut status = STATUS.lock();
+        if status.current.is_none() {
+            return;
+        }
+        status.incognito = true;
+    }
+    publish(app);
+}
+
+/// Hide the indicator
+pub fn hide(app: &AppHandle) {
+    {
+        let mut status = STATUS.lock();
+        if status.current.is_none() {
+            return;
+        }
+        status.current = None;
+        status.incognito = false;
+    }
+
+    if let Some(window) = app.get_webview_window(INDICATOR_LABEL) {
+        let _ = window.hide();
+        tracing::debug!("Recording indicator hidden");
+    }
+    publish(app);
+}
+
+/// Push the current snapshot to the indicator window
+fn publish(app: &AppHandle) {
+    emit_to_window(app, INDICATOR_LABEL, INDICATOR_STATE_EVENT, snapshot());
+}
+
+/// Move the window to its configured position
+fn place(app: &AppHandle, window: &WebviewWindow, position: &crate::config::IndicatorPosition) {
+    let cursor = app.cursor_position().ok();
+    let monitor = cursor
+        .and_then(|c| app.monitor_from_point(c.x, c.y).ok().flatten())
+        .or_else(|| window.current_monitor().ok().flatten())
+        .or_else(|| window.primary_monitor().ok().flatten());
+    let (Some(monitor), Ok(size)) = (monitor, window.outer_size()) else {
+        return;
+    };
+
+    let area = monitor.work_area();
+    let work_area = ScreenRect {
+        x: area.position.x,
+        y: area.position.y,
+        width: area.size.width as i32,
+        height: area.size.height as i32,
+    };
+    let (x, y) = indicator_origin(
+        position,
+        cursor.map(|c| (c.x as i32, c.y as i32)),
+        work_area,
+        (size.width as i32, size.height as i32),
+    );
+
+    if let Err(e) = window.set_position(PhysicalPosition::new(x, y)) {
+        tracing::warn!("Failed to position recording indicator: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_snapshot() {
+        let start = Instant::now();
+        let status = IndicatorStatus {
+            current: Some((IndicatorState::Processing, start)),
+            incognito: true,
+        };
+        let snapshot = status.snapshot(start + Duration::from_millis(1500));
+        assert!(snapshot.visible);
+        assert_eq!(snapshot.state, Some(IndicatorState::Processing));
+        assert_eq!(snapshot.elapsed_ms, 1500);
+        assert!(snapshot.incognito);
+
+        let hidden = IndicatorStatus {
+            current: None,
+            incognito: false,
+        };
+        assert!(!hidden.snapshot(start).visible);
+    }
+}
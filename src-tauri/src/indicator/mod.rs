@@ -0,0 +1,9 @@
+//! Recording Indicator Module
+//!
+//! Floating overlay showing recording and processing state.
+
+mod controller;
+mod position;
+
+pub use controller::*;
+pub use position::*;
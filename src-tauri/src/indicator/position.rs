@@ -0,0 +1,117 @@
+//! Indicator Placement
+//!
+//! Where the indicator window goes on screen for each `IndicatorPosition`,
+//! in physical pixels.
+
+use crate::config::IndicatorPosition;
+
+/// Gap between the indicator and the cursor or screen edges
+const MARGIN: i32 = 24;
+
+/// Screen rectangle in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// Top-left corner of the indicator window
+///
+/// `work_area` is the usable area of the monitor (taskbar excluded),
+/// `size` the window size. The result always keeps the window inside the
+/// work area.
+pub fn indicator_origin(
+    position: &IndicatorPosition,
+    cursor: Option<(i32, i32)>,
+    work_area: ScreenRect,
+    size: (i32, i32),
+) -> (i32, i32) {
+    let (width, height) = size;
+    let top_center = (work_area.x + (work_area.width - width) / 2, work_area.y + MARGIN);
+
+    let (x, y) = match position {
+        IndicatorPosition::Cursor => match cursor {
+            // Below the cursor, flipped above it near the bottom edge
+            Some((cx, cy)) => {
+                let below = cy + MARGIN;
+                let y = if below + height > work_area.y + work_area.height {
+                    cy - MARGIN - height
+                } else {
+                    below
+                };
+                (cx - width / 2, y)
+            }
+            None => top_center,
+        },
+        IndicatorPosition::Center => top_center,
+        IndicatorPosition::Corner => (
+            work_area.x + work_area.width - width - MARGIN,
+            work_area.y + work_area.height - height - MARGIN,
+        ),
+    };
+
+    (
+        clamp(x, work_area.x, work_area.x + work_area.width - width),
+        clamp(y, work_area.y, work_area.y + work_area.height - height),
+    )
+}
+
+/// `value.clamp(min, max)` that tolerates a window larger than the area
+fn clamp(value: i32, min: i32, max: i32) -> i32 {
+    value.min(max).max(min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREEN: ScreenRect = ScreenRect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1040,
+    };
+    const SIZE: (i32, i32) = (280, 70);
+
+    #[test]
+    fn test_center_is_top_center() {
+        assert_eq!(
+            indicator_origin(&IndicatorPosition::Center, None, SCREEN, SIZE),
+            (820, 24)
+        );
+    }
+
+    #[test]
+    fn test_corner_is_bottom_right() {
+        assert_eq!(
+            indicator_origin(&IndicatorPosition::Corner, None, SCREEN, SIZE),
+            (1616, 946)
+        );
+    }
+
+    #[test]
+    fn test_cursor_follows_and_stays_on_screen() {
+        let cursor = IndicatorPosition::Cursor;
+        assert_eq!(indicator_origin(&cursor, Some((500, 300)), SCREEN, SIZE), (360, 324));
+        // Flipped above the cursor near the bottom, clamped at the left edge
+        assert_eq!(indicator_origin(&cursor, Some((10, 1000)), SCREEN, SIZE), (0, 906));
+        // Unknown cursor falls back to top center
+        assert_eq!(indicator_origin(&cursor, None, SCREEN, SIZE), (820, 24));
+    }
+
+    #[test]
+    fn test_secondary_monitor_offset() {
+        let screen = ScreenRect {
+            x: -1280,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        assert_eq!(
+            indicator_origin(&IndicatorPosition::Center, None, screen, SIZE),
+            (-780, 24)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Single Instance
+//!
+//! Launching the app while it is already running ("Open with", file
+//! associations, a second click on the shortcut) hands the audio files to
+//! the running instance and exits instead of starting a second app.
+//!
+//! The running instance listens on a loopback port. The port and a random
+//! token go in a file in the per-user data directory, so only the same OS
+//! user can reach it.
+
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// File holding the running instance's port and token
+const INSTANCE_FILE: &str = "instance";
+
+/// How long a launch waits for the running instance
+const HANDOFF_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Reply of the running instance once it took the launch
+const ACCEPTED: &str = "ok";
+
+/// A later launch, as sent to the running instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Launch {
+    token: String,
+    /// Absolute paths of the audio files to transcribe
+    pub files: Vec<PathBuf>,
+}
+
+/// Address and token of the running instance
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct InstanceInfo {
+    port: u16,
+    token: String,
+}
+
+fn instance_file() -> PathBuf {
+    crate::config::data_dir().join(INSTANCE_FILE)
+}
+
+/// Hand `files` to the running instance
+///
+/// Returns true when an instance took them and this process should exit.
+pub fn forward_to_running(files: &[PathBuf]) -> bool {
+    match forward(&instance_file(), files) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::debug!("No running instance to hand the launch to: {}", e);
+            false
+        }
+    }
+}
+
+fn forward(path: &Path, files: &[PathBuf]) -> std::io::Result<()> {
+    let info: InstanceInfo = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, info.port));
+    let mut stream = TcpStream::connect_timeout(&address, HANDOFF_TIMEOUT)?;
+    stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+    stream.set_write_timeout(Some(HANDOFF_TIMEOUT))?;
+
+    let launch = Launch {
+        token: info.token,
+        files: files
+            .iter()
+            .map(|file| std::path::absolute(file).unwrap_or_else(|_| file.clone()))
+            .collect(),
+    };
+    let mut message = serde_json::to_string(&launch)?;
+    message.push('\n');
+    stream.write_all(message.as_bytes())?;
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.trim() == ACCEPTED {
+        Ok(())
+    } else {
+        Err(std::io::Error::other("launch not accepted"))
+    }
+}
+
+/// Listener receiving the launches of later instances
+pub struct InstanceListener {
+    listener: TcpListener,
+    token: String,
+    path: PathBuf,
+}
+
+impl InstanceListener {
+    /// Listen for later launches and advertise this instance
+    pub fn bind() -> std::io::Result<Self> {
+        Self::bind_at(instance_file())
+    }
+
+    fn bind_at(path: PathBuf) -> std::io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let info = InstanceInfo {
+            port: listener.local_addr()?.port(),
+            token: format!(
+                "{}{}",
+                uuid::Uuid::new_v4().simple(),
+                uuid::Uuid::new_v4().simple()
+            ),
+        };
+        write_private(&path, &serde_json::to_string(&info)?)?;
+        Ok(Self {
+            listener,
+            token: info.token,
+            path,
+        })
+    }
+
+    /// Wait for the next launch with the right token
+    ///
+    /// Connections without the token are dropped unanswered.
+    pub fn accept(&self) -> std::io::Result<Launch> {
+        loop {
+            let (stream, _) = self.listener.accept()?;
+            match self.read_launch(stream) {
+                Ok(Some(launch)) => return Ok(launch),
+                Ok(None) => tracing::warn!("Ignored a launch with a wrong token"),
+                Err(e) => tracing::debug!("Dropped an incomplete launch: {}", e),
+            }
+        }
+    }
+
+    fn read_launch(&self, stream: TcpStream) -> std::io::Result<Option<Launch>> {
+        stream.set_read_timeout(Some(HANDOFF_TIMEOUT))?;
+        let mut line = String::new();
+        let mut reader = BufReader::new(stream);
+        reader.read_line(&mut line)?;
+        let launch: Launch = serde_json::from_str(&line)?;
+        if launch.token != self.token {
+            return Ok(None);
+        }
+        reader
+            .get_mut()
+            .write_all(format!("{}\n", ACCEPTED).as_bytes())?;
+        Ok(Some(launch))
+    }
+
+    /// Stop advertising this instance
+    pub fn release(&self) {
+        let ours = std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<InstanceInfo>(&text).ok())
+            .is_some_and(|info| info.token == self.token);
+        if ours {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Write `contents` to a file only the current user can read
+fn write_private(path: &Path, contents: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents.as_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("gigawhisper-instance-{}", uuid::Uuid::new_v4()));
+        dir.join(name)
+    }
+
+    #[test]
+    fn test_launch_reaches_running_instance() {
+        let path = temp_path(INSTANCE_FILE);
+        let listener = InstanceListener::bind_at(path.clone()).unwrap();
+        let receiver = std::thread::spawn(move || {
+            let launch = listener.accept().unwrap();
+            listener.release();
+            launch
+        });
+
+        forward(&path, &[PathBuf::from("/tmp/meeting.mp3")]).unwrap();
+        let launch = receiver.join().unwrap();
+        assert_eq!(launch.files, vec![PathBuf::from("/tmp/meeting.mp3")]);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_wrong_token_is_not_accepted() {
+        let path = temp_path(INSTANCE_FILE);
+        let listener = InstanceListener::bind_at(path.clone()).unwrap();
+        let port = listener.listener.local_addr().unwrap().port();
+        let forged = InstanceInfo {
+            port,
+            token: "guess".to_string(),
+        };
+        let forged_path = temp_path(INSTANCE_FILE);
+        write_private(&forged_path, &serde_json::to_string(&forged).unwrap()).unwrap();
+
+        std::thread::spawn(move || {
+            let _ = listener.accept();
+        });
+        assert!(forward(&forged_path, &[]).is_err());
+    }
+
+    #[test]
+    fn test_no_running_instance() {
+        assert!(forward(&temp_path(INSTANCE_FILE), &[]).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_instance_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path(INSTANCE_FILE);
+        let _listener = InstanceListener::bind_at(path.clone()).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
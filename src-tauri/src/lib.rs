@@ -2,11 +2,18 @@
 //!
 //! Core functionality for voice transcription.
 
+pub mod api;
 pub mod audio;
+pub mod bindings;
 pub mod build_info;
+pub mod cli;
 pub mod commands;
 pub mod config;
+pub mod engine;
+pub mod ffi;
 pub mod history;
+pub mod indicator;
+pub mod instance;
 pub mod models;
 pub mod output;
 pub mod shortcuts;
@@ -14,6 +21,7 @@ pub mod transcription;
 pub mod tray;
 pub mod updater;
 pub mod utils;
+pub mod vocabulary;
 
 use parking_lot::Mutex;
 use std::sync::Arc;
@@ -26,6 +34,33 @@ pub struct AppState {
     pub config: parking_lot::RwLock<config::Settings>,
     pub recording_state: parking_lot::RwLock<RecordingState>,
     pub audio_capture: Mutex<Option<audio::AudioCapture>>,
+    pub recording_checkpoint: Mutex<Option<audio::RecordingCheckpoint>>,
+    /// Live speech detection of the recording in progress
+    pub speech_progress: Mutex<Option<audio::SpeechProgress>>,
+    /// Latest audio level of the recording in progress
+    pub audio_level: Mutex<Option<audio::AudioLevel>>,
+    /// Sentences pasted while the recording is in progress (type as you speak)
+    pub live_sentences: Mutex<Option<Arc<Mutex<transcription::SentenceStabilizer>>>>,
+    /// Final text of the Deepgram session streaming the recording in progress
+    pub live_transcript: Mutex<Option<tokio::sync::oneshot::Receiver<String>>>,
+    /// Profile of the recording in progress (None = global settings)
+    pub active_profile: parking_lot::RwLock<Option<String>>,
+    /// Whether the recording in progress is incognito (kept in memory only)
+    pub incognito: parking_lot::RwLock<bool>,
+    /// Source of the recording in progress, until its transcription is saved
+    pub audio_source: parking_lot::RwLock<config::AudioSource>,
+    /// Translation override of the recording in progress (None = settings)
+    pub translate_override: parking_lot::RwLock<Option<bool>>,
+    /// Profile the record shortcut uses, picked with the switch profile shortcut
+    pub selected_profile: parking_lot::RwLock<Option<String>>,
+    /// Gesture recognition state of the record shortcuts
+    pub shortcut_gestures: Mutex<shortcuts::GestureRecognizer>,
+    /// Floating mini editor collecting transcriptions
+    pub dictation_box: Mutex<output::DictationBox>,
+    /// Text shown in the transcript viewer window
+    pub transcript_viewer: Mutex<output::TranscriptViewer>,
+    /// Spawned background work, aborted on shutdown
+    pub tasks: utils::TaskRegistry,
     pub transcription_service: Arc<transcription::TranscriptionService>,
 }
 
@@ -113,8 +148,30 @@ pub fn run() {
         tracing::info!("First launch detected - will show onboarding");
     }
 
+    // Audio files passed on the command line ("Open with", file associations)
+    let opened_files = transcription::audio_files_from_args(std::env::args().skip(1));
+
+    // Hand them to an instance that is already running rather than starting a second one
+    if instance::forward_to_running(&opened_files) {
+        tracing::info!("GigaWhisper is already running; handed the launch to it");
+        return;
+    }
+    let instance_listener = match instance::InstanceListener::bind() {
+        Ok(listener) => Some(Arc::new(listener)),
+        Err(e) => {
+            tracing::warn!("Failed to listen for later launches: {}", e);
+            None
+        }
+    };
+    let exit_listener = instance_listener.clone();
+
     // Load configuration
     let config = config::Settings::load().unwrap_or_default();
+    config::set_active_workspace(&config.workspaces.active);
+    config::set_shared_models_dir(config.model_storage.shared_dir.as_deref());
+    history::set_history_encryption(config.privacy.encrypt_history);
+    config::secure_storage_dirs();
+    audio::prune_transcode_cache(audio::TRANSCODE_CACHE_MAX_AGE);
 
     // Create transcription service
     let transcription_service = Arc::new(transcription::TranscriptionService::new());
@@ -124,6 +181,20 @@ pub fn run() {
         config: parking_lot::RwLock::new(config.clone()),
         recording_state: parking_lot::RwLock::new(RecordingState::default()),
         audio_capture: Mutex::new(None),
+        recording_checkpoint: Mutex::new(None),
+        speech_progress: Mutex::new(None),
+        audio_level: Mutex::new(None),
+        live_sentences: Mutex::new(None),
+        live_transcript: Mutex::new(None),
+        active_profile: parking_lot::RwLock::new(None),
+        incognito: parking_lot::RwLock::new(false),
+        audio_source: parking_lot::RwLock::new(config::AudioSource::default()),
+        translate_override: parking_lot::RwLock::new(None),
+        selected_profile: parking_lot::RwLock::new(None),
+        shortcut_gestures: Mutex::new(shortcuts::GestureRecognizer::default()),
+        dictation_box: Mutex::new(output::DictationBox::default()),
+        transcript_viewer: Mutex::new(output::TranscriptViewer::default()),
+        tasks: utils::TaskRegistry::new(),
         transcription_service: transcription_service.clone(),
     };
 
@@ -137,12 +208,71 @@ pub fn run() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(app_state)
+        .register_asynchronous_uri_scheme_protocol("gwaudio", |_ctx, request, responder| {
+            // Transcoding can take a moment; keep it off the webview thread
+            tauri::async_runtime::spawn_blocking(move || {
+                responder.respond(commands::history::audio_protocol_response(&request));
+            });
+        })
         .setup(move |app| {
+            // Report what paste and shortcut support this session offers
+            let platform = utils::platform_capabilities();
+            tracing::info!(
+                "Display server: {:?}, clipboard: {:?}, paste: {:?}",
+                platform.display_server,
+                platform.clipboard,
+                platform.paste
+            );
+            for hint in &platform.guidance {
+                tracing::warn!("{}", hint);
+            }
+
             // Setup system tray
             tray::setup_tray(app)?;
 
             // Register global shortcuts
             shortcuts::register_shortcuts(app)?;
+            let triggers = app.state::<AppState>().config.read().shortcuts.triggers.clone();
+            shortcuts::apply_trigger_settings(app.handle(), &triggers);
+            let prefetch = app.state::<AppState>().config.read().transcription.prefetch;
+            transcription::apply_prefetch_settings(app.handle(), prefetch);
+
+            // Offer to recover a recording interrupted by a crash
+            if let Some(orphan) = audio::recover_orphaned_checkpoint() {
+                let app_handle = app.handle().clone();
+                app.state::<AppState>().tasks.spawn("recovery-notice", async move {
+                    use tauri::Emitter;
+                    use tauri_plugin_notification::NotificationExt;
+
+                    // Give the frontend time to register its listeners
+                    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+                    let _ = app_handle.emit("recording:recovered", &orphan);
+                    let _ = app_handle
+                        .notification()
+                        .builder()
+                        .title("Unfinished Recording Found")
+                        .body(format!(
+                            "A {:.0}s recording was interrupted. Open GigaWhisper to transcribe or delete it.",
+                            orphan.duration_secs
+                        ))
+                        .show();
+                });
+            }
+
+            // Transcribe audio files the app was opened with
+            if !opened_files.is_empty() {
+                transcription::enqueue_audio_files(app.handle(), &opened_files);
+            }
+
+            // Later launches show the app and queue their files here
+            if let Some(listener) = instance_listener {
+                let handle = app.handle().clone();
+                app.state::<AppState>().tasks.spawn_thread("instance-listener", move || {
+                    while let Ok(launch) = listener.accept() {
+                        open_launch(&handle, &launch.files);
+                    }
+                });
+            }
 
             // Show or hide main window on startup based on configuration
             // On first launch, always show the window for onboarding
@@ -158,68 +288,262 @@ pub fn run() {
                 }
             }
 
-            // Check for updates in the background
+            // Keep idle memory use within the configured budget
+            let app_handle = app.handle().clone();
+            state.tasks.spawn("resource-budget", async move {
+                let mut interval = tokio::time::interval(utils::BUDGET_CHECK_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    utils::enforce_budget(&app_handle);
+                }
+            });
+
+            // Suggest removing models that are no longer used
             let app_handle = app.handle().clone();
-            tauri::async_runtime::spawn(async move {
+            state.tasks.spawn("model-pruning", async move {
+                // Leave startup to the model preload and update check
+                tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+                let mut interval = tokio::time::interval(commands::models::PRUNE_CHECK_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    commands::models::suggest_model_pruning(&app_handle);
+                }
+            });
+
+            // Waveforms of entries saved before waveforms existed
+            state.tasks.spawn("waveform-backfill", async move {
+                let _ = tokio::task::spawn_blocking(history::backfill_waveforms).await;
+            });
+
+            // Repair dangling history references and orphaned files
+            state.tasks.spawn("history-maintenance", async move {
+                tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+                let mut interval = tokio::time::interval(history::INTEGRITY_CHECK_INTERVAL);
+                loop {
+                    interval.tick().await;
+                    let result = tokio::task::spawn_blocking(|| history::verify_integrity(true)).await;
+                    if let Ok(Err(e)) = result {
+                        tracing::error!("History maintenance failed: {}", e);
+                    }
+                }
+            });
+
+            let app_handle = app.handle().clone();
+            state.tasks.spawn("update-check", async move {
                 // Small delay to let the app fully initialize
                 tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
                 updater::check_for_updates(app_handle).await;
             });
 
+            // Local API server, when enabled
+            let api_server = state.config.read().api_server.clone();
+            api::apply_api_server_settings(app.handle(), &api_server);
+
+            // Fetch the Silero VAD model if selected but missing
+            let config = state.config.read().clone();
+            commands::models::ensure_vad_model(app.handle(), &config);
+            api::spawn_mqtt_publisher(app.handle());
+
             tracing::info!("GigaWhisper setup complete");
             Ok(())
         })
         .on_window_event(|window, event| {
-            // Hide main window instead of closing it (keep app in tray)
-            if window.label() == "main" {
-                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+            if window.label() != "main" {
+                return;
+            }
+            match event {
+                // Hide main window instead of closing it (keep app in tray)
+                tauri::WindowEvent::CloseRequested { api, .. } => {
                     // Prevent the window from being destroyed
                     api.prevent_close();
                     // Hide the window instead
                     let _ = window.hide();
                     tracing::debug!("Main window hidden (not closed)");
                 }
+                // Transcribe audio files dropped on the window
+                tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                    transcription::enqueue_audio_files(window.app_handle(), paths);
+                }
+                _ => {}
             }
         })
         .invoke_handler(tauri::generate_handler![
             commands::recording::start_recording,
             commands::recording::stop_recording,
             commands::recording::cancel_recording,
+            commands::recording::get_current_level,
             commands::recording::get_recording_state,
+            commands::recording::get_indicator_state,
+            commands::recording::get_orphaned_recording,
+            commands::recording::transcribe_orphaned_recording,
+            commands::recording::discard_orphaned_recording,
             commands::transcription::get_transcription_status,
+            commands::transcription::get_provider_capabilities,
+            commands::transcription::transcribe_file,
+            commands::transcription::enqueue_audio_files,
+            commands::transcription::start_batch,
+            commands::transcription::pause_batch,
+            commands::transcription::resume_batch,
+            commands::transcription::cancel_batch,
+            commands::transcription::get_batch,
+            commands::transcription::get_file_queue,
+            commands::transcription::clear_file_queue,
             commands::transcription::preload_model,
             commands::transcription::unload_model,
+            commands::transcription::signal_activity,
             commands::transcription::get_gpu_info,
             commands::transcription::get_cpu_info,
             commands::transcription::get_metrics_summary,
             commands::transcription::get_recent_metrics,
             commands::transcription::reset_metrics,
+            commands::transcription::get_latency_report,
+            commands::transcription::get_pipeline_config,
+            commands::transcription::get_pipeline_traces,
+            commands::transcription::list_debug_captures,
+            commands::transcription::replay_recording,
             commands::settings::get_settings,
             commands::settings::save_settings,
+            commands::settings::check_shortcuts,
+            commands::settings::set_quality_preset,
+            commands::settings::preset_missing_model,
             commands::settings::get_audio_devices,
+            commands::settings::calibrate_vad,
+            commands::settings::test_audio_device,
+            commands::settings::enroll_voice_profile,
+            commands::settings::clear_voice_profile,
+            commands::settings::get_alert_keywords,
+            commands::settings::add_alert_keyword,
+            commands::settings::remove_alert_keyword,
+            commands::settings::get_builtin_voice_commands,
             commands::settings::set_groq_api_key,
             commands::settings::has_groq_api_key,
             commands::settings::clear_groq_api_key,
             commands::settings::validate_groq_api_key,
+            commands::settings::set_deepgram_api_key,
+            commands::settings::has_deepgram_api_key,
+            commands::settings::clear_deepgram_api_key,
+            commands::settings::validate_deepgram_api_key,
+            commands::settings::set_custom_endpoint_token,
+            commands::settings::has_custom_endpoint_token,
+            commands::settings::clear_custom_endpoint_token,
+            commands::settings::set_openai_api_key,
+            commands::settings::has_openai_api_key,
+            commands::settings::clear_openai_api_key,
+            commands::settings::set_cleanup_endpoint_token,
+            commands::settings::has_cleanup_endpoint_token,
+            commands::settings::clear_cleanup_endpoint_token,
+            commands::settings::set_webhook_auth,
+            commands::settings::has_webhook_auth,
+            commands::settings::clear_webhook_auth,
+            commands::settings::set_mqtt_password,
+            commands::settings::has_mqtt_password,
+            commands::settings::clear_mqtt_password,
+            commands::system::get_health_report,
+            commands::system::get_background_tasks,
+            commands::system::get_resource_usage,
+            commands::system::get_error_guidance,
+            commands::dictation_box::open_dictation_box,
+            commands::dictation_box::get_dictation_box,
+            commands::dictation_box::update_dictation_box,
+            commands::dictation_box::send_dictation_box,
+            commands::dictation_box::close_dictation_box,
+            commands::viewer::open_viewer,
+            commands::viewer::close_viewer,
+            commands::viewer::get_viewer,
+            commands::viewer::set_viewer_paused,
+            commands::viewer::clear_viewer,
             commands::clipboard::paste_text,
             commands::clipboard::get_history,
+            commands::clipboard::get_injection_audit,
+            commands::clipboard::export_injection_audit,
             commands::models::list_models,
             commands::models::is_model_downloaded,
             commands::models::is_model_downloading,
             commands::models::download_model,
             commands::models::cancel_model_download,
             commands::models::delete_model,
+            commands::models::import_model,
+            commands::models::list_custom_models,
+            commands::models::delete_custom_model,
             commands::models::get_recommended_model,
+            commands::models::prune_unused_models,
             commands::history::get_transcription_history,
             commands::history::get_history_entry,
             commands::history::delete_history_entry,
             commands::history::clear_history,
             commands::history::get_history_count,
+            commands::history::get_incognito_entries,
+            commands::history::clear_incognito_entries,
             commands::history::get_audio_data,
+            commands::history::get_screenshot_data,
+            commands::history::export_meeting_minutes,
+            commands::history::export_subtitles,
+            commands::history::export_history,
+            commands::history::set_entry_title,
+            commands::history::set_entry_note,
+            commands::history::add_entry_tag,
+            commands::history::remove_entry_tag,
+            commands::history::get_history_tags,
+            commands::history::submit_correction,
+            commands::api::list_api_clients,
+            commands::api::create_api_client,
+            commands::api::update_api_client_scopes,
+            commands::api::rotate_api_client_token,
+            commands::api::delete_api_client,
+            commands::api::get_api_audit_log,
+            commands::history::get_recent_texts,
+            commands::history::fuzzy_search_history,
+            commands::history::search_history,
+            commands::history::verify_history_integrity,
+            commands::history::paste_history_entry,
+            commands::vocabulary::get_vocabulary,
+            commands::vocabulary::save_vocabulary,
+            commands::vocabulary::export_vocabulary,
+            commands::vocabulary::import_vocabulary,
+            commands::vocabulary::apply_rules_pack,
+            commands::vocabulary::get_learned_terms,
+            commands::vocabulary::remove_learned_term,
+            commands::vocabulary::prune_learned_terms,
+            commands::workspace::list_workspaces,
+            commands::workspace::create_workspace,
+            commands::workspace::switch_workspace,
+            commands::workspace::delete_workspace,
             updater::install_update,
             updater::restart_app,
             updater::get_build_variant,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(move |app, event| match event {
+            tauri::RunEvent::Exit => {
+                app.state::<AppState>().tasks.shutdown();
+                vocabulary::save_learned_terms();
+                if let Some(listener) = &exit_listener {
+                    listener.release();
+                }
+            }
+            // Files opened with the app while it runs on macOS
+            #[cfg(target_os = "macos")]
+            tauri::RunEvent::Opened { urls } => {
+                let files = urls
+                    .iter()
+                    .filter_map(|url| url.to_file_path().ok())
+                    .filter(|path| path.is_file() && audio::is_importable_audio(path))
+                    .collect::<Vec<_>>();
+                open_launch(app, &files);
+            }
+            _ => {}
+        });
+}
+
+/// Bring the app forward for a later launch and queue the files it opened
+fn open_launch(app: &tauri::AppHandle, files: &[std::path::PathBuf]) {
+    if files.is_empty() {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+    } else {
+        transcription::enqueue_audio_files(app, files);
+    }
 }
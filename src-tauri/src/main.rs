@@ -5,5 +5,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // `--transcribe` runs headless and exits without starting the app
+    if let Some(code) = gigawhisper_lib::cli::run_from_args(std::env::args().skip(1)) {
+        std::process::exit(code);
+    }
     gigawhisper_lib::run()
 }
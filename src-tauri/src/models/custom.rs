@@ -0,0 +1,459 @@
+//! Custom Models
+//!
+//! Whisper models imported by the user, such as fine-tuned ones. An imported
+//! file is checked to be a whisper.cpp model, copied into the user's models
+//! directory and listed with its display name in `custom_models.json` next
+//! to it. Settings refer to an imported model by its id.
+
+use super::get_available_space;
+use crate::config::{user_models_dir, LocalTranscriptionSettings};
+use crate::vocabulary::unix_now;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// Magic number of whisper.cpp model files ("ggml", little-endian)
+const GGML_MAGIC: u32 = 0x6767_6d6c;
+
+/// Magic bytes of GGUF files
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+
+/// Smallest file accepted as a model (quantized tiny models are ~30 MB)
+const MIN_MODEL_SIZE: u64 = 1_000_000;
+
+/// Longest display name
+pub const MAX_MODEL_NAME_CHARS: usize = 64;
+
+/// Global custom model list
+static CUSTOM_MODELS: OnceLock<RwLock<CustomModelList>> = OnceLock::new();
+
+/// Errors importing or removing a custom model
+#[derive(Debug, Error)]
+pub enum CustomModelError {
+    #[error("Model file not found: {0}")]
+    NotFound(String),
+
+    #[error("Not a whisper.cpp model: {0}")]
+    InvalidModel(String),
+
+    #[error("Invalid model name: {0}")]
+    InvalidName(String),
+
+    #[error("A model named '{0}' is already imported")]
+    DuplicateName(String),
+
+    #[error("No imported model '{0}'")]
+    UnknownModel(String),
+
+    #[error("Insufficient disk space: need {needed} bytes, have {available} bytes")]
+    InsufficientSpace { needed: u64, available: u64 },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Hyperparameters from the header of a whisper.cpp model file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModelHeader {
+    pub n_vocab: i32,
+    pub n_audio_layer: i32,
+    pub n_text_layer: i32,
+    pub n_mels: i32,
+    pub ftype: i32,
+}
+
+impl ModelHeader {
+    /// Whether the model has the multilingual vocabulary (English-only
+    /// models have 51864 tokens)
+    pub fn is_multilingual(&self) -> bool {
+        self.n_vocab >= 51865
+    }
+}
+
+/// A model imported by the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CustomModel {
+    /// Identifier settings refer to, also part of the file name
+    pub id: String,
+    /// Name shown in the model list
+    pub name: String,
+    /// File name in the user's models directory
+    pub filename: String,
+    pub size_bytes: u64,
+    /// Whether the model transcribes other languages than English
+    pub multilingual: bool,
+    /// Unix timestamp of the import (seconds)
+    pub imported_at: u64,
+}
+
+/// Imported models, in import order
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CustomModelList {
+    pub models: Vec<CustomModel>,
+}
+
+impl CustomModelList {
+    /// Load the custom model list from disk
+    pub fn load() -> Self {
+        let path = list_file_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(list) => return list,
+                    Err(e) => tracing::warn!("Failed to parse custom model list: {}", e),
+                },
+                Err(e) => tracing::warn!("Failed to read custom model list: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    /// Save the custom model list to disk
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let path = list_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, content)
+    }
+
+    /// Imported model with this id
+    pub fn get(&self, id: &str) -> Option<&CustomModel> {
+        self.models.iter().find(|model| model.id == id)
+    }
+
+    /// Check `name` and pick an unused id for it
+    fn new_id(&self, name: &str) -> Result<String, CustomModelError> {
+        if name.is_empty() || name.chars().count() > MAX_MODEL_NAME_CHARS {
+            return Err(CustomModelError::InvalidName(format!(
+                "must be 1 to {} characters",
+                MAX_MODEL_NAME_CHARS
+            )));
+        }
+        if self
+            .models
+            .iter()
+            .any(|m| m.name.eq_ignore_ascii_case(name))
+        {
+            return Err(CustomModelError::DuplicateName(name.to_string()));
+        }
+
+        // Room left for a numeric suffix within the id length limit
+        let base: String = slug(name).chars().take(48).collect();
+        let base = match base.trim_end_matches('-') {
+            "" => "model".to_string(),
+            base => base.to_string(),
+        };
+        let mut id = base.clone();
+        let mut n = 2;
+        while self.get(&id).is_some() {
+            id = format!("{}-{}", base, n);
+            n += 1;
+        }
+        Ok(id)
+    }
+}
+
+/// Get or initialize the global custom model list
+pub fn custom_models() -> &'static RwLock<CustomModelList> {
+    CUSTOM_MODELS.get_or_init(|| RwLock::new(CustomModelList::load()))
+}
+
+/// Path of an imported model (None if it is not in the list)
+pub fn custom_model_path(id: &str) -> Option<PathBuf> {
+    let list = custom_models().read();
+    list.get(id)
+        .map(|model| user_models_dir().join(&model.filename))
+}
+
+/// Read the header of a whisper.cpp model file
+///
+/// Rejects files that whisper.cpp cannot load, including GGUF files, which
+/// it only reads for other model types.
+pub fn read_model_header(path: &Path) -> Result<ModelHeader, CustomModelError> {
+    let mut file = std::fs::File::open(path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => CustomModelError::NotFound(path.display().to_string()),
+        _ => e.into(),
+    })?;
+    let mut bytes = [0u8; 48];
+    let read = file.read(&mut bytes)?;
+    parse_header(&bytes[..read])
+}
+
+fn parse_header(bytes: &[u8]) -> Result<ModelHeader, CustomModelError> {
+    if bytes.starts_with(GGUF_MAGIC) {
+        return Err(CustomModelError::InvalidModel(
+            "GGUF files are not supported for Whisper, convert the model to GGML \
+             (ggml-*.bin) with whisper.cpp's conversion scripts"
+                .to_string(),
+        ));
+    }
+    // Magic followed by 11 hyperparameters
+    let values: Vec<i32> = bytes
+        .chunks_exact(4)
+        .take(12)
+        .map(|chunk| i32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    if values.len() < 12 || values[0] as u32 != GGML_MAGIC {
+        return Err(CustomModelError::InvalidModel(
+            "missing GGML header".to_string(),
+        ));
+    }
+
+    let header = ModelHeader {
+        n_vocab: values[1],
+        n_audio_layer: values[5],
+        n_text_layer: values[9],
+        n_mels: values[10],
+        ftype: values[11],
+    };
+    let layers = 1..=64;
+    if header.n_vocab <= 0
+        || !layers.contains(&header.n_audio_layer)
+        || !layers.contains(&header.n_text_layer)
+        || ![80, 128].contains(&header.n_mels)
+    {
+        return Err(CustomModelError::InvalidModel(format!(
+            "unexpected hyperparameters {:?}",
+            header
+        )));
+    }
+    Ok(header)
+}
+
+/// Lowercase letters, digits and single dashes of `name`
+fn slug(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+/// A checked model file copied next to its final location
+struct StagedModel {
+    temp_path: PathBuf,
+    size_bytes: u64,
+    header: ModelHeader,
+}
+
+/// Check the model at `source` and copy it into `dir` under a temporary name
+fn stage_model(dir: &Path, source: &Path) -> Result<StagedModel, CustomModelError> {
+    let header = read_model_header(source)?;
+    let size_bytes = std::fs::metadata(source)?.len();
+    if size_bytes < MIN_MODEL_SIZE {
+        return Err(CustomModelError::InvalidModel(format!(
+            "file is too small ({} bytes)",
+            size_bytes
+        )));
+    }
+
+    std::fs::create_dir_all(dir)?;
+    if let Some(available) = get_available_space(dir) {
+        if available < size_bytes {
+            return Err(CustomModelError::InsufficientSpace {
+                needed: size_bytes,
+                available,
+            });
+        }
+    }
+
+    // Copy next to the destination so the final rename is atomic
+    let temp_path = dir.join(format!("import-{}.tmp", uuid::Uuid::new_v4()));
+    if let Err(e) = std::fs::copy(source, &temp_path) {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e.into());
+    }
+    Ok(StagedModel {
+        temp_path,
+        size_bytes,
+        header,
+    })
+}
+
+/// Move a staged model to its final file and add it to `list` as `name`
+///
+/// The staged file is removed if the model can't be added.
+fn add_staged(
+    dir: &Path,
+    list: &mut CustomModelList,
+    staged: StagedModel,
+    name: &str,
+    now: u64,
+) -> Result<CustomModel, CustomModelError> {
+    let added = list.new_id(name).and_then(|id| {
+        let filename = LocalTranscriptionSettings::custom_model_filename(&id);
+        std::fs::rename(&staged.temp_path, dir.join(&filename))?;
+        Ok((id, filename))
+    });
+    let (id, filename) = match added {
+        Ok(added) => added,
+        Err(e) => {
+            let _ = std::fs::remove_file(&staged.temp_path);
+            return Err(e);
+        }
+    };
+
+    let model = CustomModel {
+        id,
+        name: name.to_string(),
+        filename,
+        size_bytes: staged.size_bytes,
+        multilingual: staged.header.is_multilingual(),
+        imported_at: now,
+    };
+    list.models.push(model.clone());
+    Ok(model)
+}
+
+/// Import the model at `source` under the display name `name`
+///
+/// Copies the file, so it can be large and slow: call off the async runtime.
+/// The model list is only locked once the copy is done.
+pub fn import_model(source: &Path, name: &str) -> Result<CustomModel, CustomModelError> {
+    let name = name.trim();
+    let dir = user_models_dir();
+
+    // Refuse a bad or taken name before copying a large file
+    custom_models().read().new_id(name)?;
+    let staged = stage_model(&dir, source)?;
+
+    // Checked again, another import may have taken the name meanwhile
+    let mut list = custom_models().write();
+    let model = add_staged(&dir, &mut list, staged, name, unix_now())?;
+    list.save()?;
+    tracing::info!("Imported model '{}' from {:?}", model.name, source);
+    Ok(model)
+}
+
+/// Delete an imported model and its file
+pub fn delete_custom_model(id: &str) -> Result<(), CustomModelError> {
+    let mut list = custom_models().write();
+    let index = list
+        .models
+        .iter()
+        .position(|model| model.id == id)
+        .ok_or_else(|| CustomModelError::UnknownModel(id.to_string()))?;
+
+    super::delete_model_file(&user_models_dir().join(&list.models[index].filename))?;
+    list.models.remove(index);
+    list.save()?;
+    Ok(())
+}
+
+/// Get the custom model list file path
+fn list_file_path() -> PathBuf {
+    user_models_dir().join("custom_models.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Header of a multilingual base model
+    fn header_bytes(magic: u32) -> Vec<u8> {
+        [magic as i32, 51865, 1500, 512, 8, 6, 448, 512, 8, 6, 80, 1]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let header = parse_header(&header_bytes(GGML_MAGIC)).unwrap();
+        assert_eq!(header.n_audio_layer, 6);
+        assert_eq!(header.n_mels, 80);
+        assert!(header.is_multilingual());
+
+        assert!(parse_header(&header_bytes(0x1234_5678)).is_err());
+        assert!(parse_header(&header_bytes(GGML_MAGIC)[..20]).is_err());
+        let gguf = parse_header(b"GGUF\x03\x00\x00\x00");
+        assert!(matches!(gguf, Err(CustomModelError::InvalidModel(e)) if e.contains("GGUF")));
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Medical Whisper v2"), "medical-whisper-v2");
+        assert_eq!(slug("  Über--Modell! "), "ber-modell");
+        assert_eq!(slug("日本語"), "");
+    }
+
+    /// Stage `source` and add it to `list`, like `import_model`
+    fn import_into(
+        dir: &Path,
+        list: &mut CustomModelList,
+        source: &Path,
+        name: &str,
+    ) -> Result<CustomModel, CustomModelError> {
+        let name = name.trim();
+        list.new_id(name)?;
+        let staged = stage_model(dir, source)?;
+        add_staged(dir, list, staged, name, 1000)
+    }
+
+    #[test]
+    fn test_import_into() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("fine-tuned.bin");
+        let mut content = header_bytes(GGML_MAGIC);
+        content.resize(MIN_MODEL_SIZE as usize, 0);
+        std::fs::write(&source, &content).unwrap();
+
+        let models = dir.path().join("models");
+        let mut list = CustomModelList::default();
+        let model = import_into(&models, &mut list, &source, " Medical ").unwrap();
+        assert_eq!(model.id, "medical");
+        assert_eq!(model.name, "Medical");
+        assert_eq!(model.size_bytes, MIN_MODEL_SIZE);
+        assert!(models.join(&model.filename).exists());
+        assert_eq!(list.get("medical"), Some(&model));
+
+        // Names are unique, ids are made unique
+        assert!(matches!(
+            import_into(&models, &mut list, &source, "MEDICAL"),
+            Err(CustomModelError::DuplicateName(_))
+        ));
+        let second = import_into(&models, &mut list, &source, "medical!").unwrap();
+        assert_eq!(second.id, "medical-2");
+        assert!(LocalTranscriptionSettings::is_valid_custom_id(&second.id));
+
+        assert!(import_into(&models, &mut list, &source, "").is_err());
+        let missing = dir.path().join("missing.bin");
+        assert!(matches!(
+            import_into(&models, &mut list, &missing, "Missing"),
+            Err(CustomModelError::NotFound(_))
+        ));
+        assert_eq!(list.models.len(), 2);
+    }
+
+    #[test]
+    fn test_staged_file_removed_when_name_is_taken() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("fine-tuned.bin");
+        let mut content = header_bytes(GGML_MAGIC);
+        content.resize(MIN_MODEL_SIZE as usize, 0);
+        std::fs::write(&source, &content).unwrap();
+
+        let models = dir.path().join("models");
+        let mut list = CustomModelList::default();
+        import_into(&models, &mut list, &source, "Medical").unwrap();
+
+        // Another import took the name while this one was copying
+        let staged = stage_model(&models, &source).unwrap();
+        let temp_path = staged.temp_path.clone();
+        assert!(temp_path.exists());
+        assert!(add_staged(&models, &mut list, staged, "Medical", 1000).is_err());
+        assert!(!temp_path.exists());
+        assert_eq!(list.models.len(), 1);
+    }
+}
@@ -4,13 +4,14 @@
 //! Supports both standard (F16) and quantized (Q8_0, Q5_1) models.
 //! Includes SHA256 checksum verification to ensure model integrity.
 
-use crate::config::{ModelQuantization, WhisperModel};
+use crate::config::{models_dir, ModelQuantization, WhisperModel};
 use parking_lot::Mutex;
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 /// Base URL for model downloads (both standard F16 and quantized models)
@@ -264,7 +265,7 @@ pub async fn download_model_with_quantization(
 
 /// Check available disk space at the given path
 #[cfg(windows)]
-fn get_available_space(path: &std::path::Path) -> Option<u64> {
+pub(super) fn get_available_space(path: &std::path::Path) -> Option<u64> {
     use std::os::windows::ffi::OsStrExt;
     use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
     use windows::core::PCWSTR;
@@ -296,7 +297,7 @@ fn get_available_space(path: &std::path::Path) -> Option<u64> {
 }
 
 #[cfg(not(windows))]
-fn get_available_space(_path: &std::path::Path) -> Option<u64> {
+pub(super) fn get_available_space(_path: &std::path::Path) -> Option<u64> {
     None
 }
 
@@ -499,6 +500,75 @@ pub fn is_downloading(model: &WhisperModel) -> bool {
     download_manager().is_downloading(model)
 }
 
+/// Longest wait for the connection when fetching a small model
+const PINNED_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Download a small model file in one request into the models directory
+///
+/// The bytes are checked against `sha256` before anything is written.
+/// `name` only labels the log messages.
+pub(super) async fn download_pinned_model(
+    name: &str,
+    url: &str,
+    filename: &str,
+    sha256: &str,
+    timeout: Duration,
+) -> Result<PathBuf, DownloadError> {
+    let dest_dir = models_dir();
+    tokio::fs::create_dir_all(&dest_dir).await?;
+    let dest_path = dest_dir.join(filename);
+
+    tracing::info!("Downloading {} model from: {}", name, url);
+    let client = reqwest::Client::builder()
+        .connect_timeout(PINNED_CONNECT_TIMEOUT)
+        .timeout(timeout)
+        .build()
+        .map_err(|e| DownloadError::Network(e.to_string()))?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| DownloadError::Network(e.to_string()))?;
+    if !response.status().is_success() {
+        return Err(DownloadError::Network(format!(
+            "HTTP {}: {}",
+            response.status(),
+            response.status().canonical_reason().unwrap_or("Unknown")
+        )));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| DownloadError::Network(e.to_string()))?;
+    verify_pinned_checksum(name, &bytes, sha256)?;
+
+    // Write next to the destination so the rename is atomic
+    let temp_path = dest_path.with_extension("tmp");
+    tokio::fs::write(&temp_path, &bytes).await?;
+    tokio::fs::rename(&temp_path, &dest_path).await?;
+
+    tracing::info!("{} model downloaded: {:?}", name, dest_path);
+    Ok(dest_path)
+}
+
+/// Check downloaded model bytes against a pinned checksum
+fn verify_pinned_checksum(name: &str, bytes: &[u8], expected: &str) -> Result<(), DownloadError> {
+    let actual = format!("{:x}", Sha256::digest(bytes));
+    if actual != expected {
+        tracing::error!(
+            "{} checksum mismatch! Expected: {}, Got: {}",
+            name,
+            expected,
+            actual
+        );
+        return Err(DownloadError::ChecksumMismatch {
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -509,6 +579,17 @@ mod tests {
         assert!(url.contains("ggml-tiny.bin"));
     }
 
+    #[test]
+    fn test_verify_pinned_checksum() {
+        // SHA-256 of the empty input
+        let empty = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        assert!(verify_pinned_checksum("Test", b"", empty).is_ok());
+        assert!(matches!(
+            verify_pinned_checksum("Test", b"not the model", empty),
+            Err(DownloadError::ChecksumMismatch { .. })
+        ));
+    }
+
     #[test]
     fn test_cancellation_token() {
         let token = CancellationToken::new();
@@ -2,11 +2,11 @@
 //!
 //! Manage Whisper model files including quantized variants.
 
-use crate::config::{models_dir, ModelQuantization, WhisperModel};
-use std::path::PathBuf;
+use crate::config::{find_model, ModelQuantization, WhisperModel};
+use std::path::{Path, PathBuf};
 
 /// Model information
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 pub struct ModelInfo {
     pub model: String,
     pub quantization: String,
@@ -16,9 +16,67 @@ pub struct ModelInfo {
     pub is_quantized: bool,
 }
 
-/// Get path for a specific model with quantization
+/// Get path for a specific model with quantization (see `find_model`)
 pub fn model_path_with_quantization(model: &WhisperModel, quant: &ModelQuantization) -> PathBuf {
-    models_dir().join(model.filename_with_quantization(quant))
+    find_model(&model.filename_with_quantization(quant))
+}
+
+/// Which quantization of a model is used for the one requested
+#[derive(Debug, Clone, PartialEq, serde::Serialize, schemars::JsonSchema)]
+pub struct ModelResolution {
+    pub model: WhisperModel,
+    pub requested: ModelQuantization,
+    /// Quantization on disk that is loaded (None when no variant is downloaded)
+    pub used: Option<ModelQuantization>,
+}
+
+impl ModelResolution {
+    /// Whether another quantization than the requested one is loaded
+    pub fn is_fallback(&self) -> bool {
+        self.used.is_some_and(|used| used != self.requested)
+    }
+}
+
+/// Find the file of `model` at `quant`, or else the closest downloaded
+/// quantization
+///
+/// When no variant is downloaded the requested path is returned, so loading
+/// reports the missing file.
+pub fn resolve_model(model: &WhisperModel, quant: &ModelQuantization) -> (PathBuf, ModelResolution) {
+    let found = fallback_order(quant).into_iter().find_map(|candidate| {
+        let path = model_path_with_quantization(model, &candidate);
+        path.exists().then_some((path, candidate))
+    });
+    let (path, used) = match found {
+        Some((path, used)) => (path, Some(used)),
+        None => (model_path_with_quantization(model, quant), None),
+    };
+    if let Some(used) = used.filter(|used| used != quant) {
+        tracing::info!(
+            "{:?} {:?} is not downloaded, using {:?} instead",
+            model,
+            quant,
+            used
+        );
+    }
+    let resolution = ModelResolution {
+        model: model.clone(),
+        requested: *quant,
+        used,
+    };
+    (path, resolution)
+}
+
+/// `requested` first, then the other quantizations nearest in precision
+fn fallback_order(requested: &ModelQuantization) -> Vec<ModelQuantization> {
+    let all = ModelQuantization::all();
+    let index = all.iter().position(|q| q == requested).unwrap_or(0);
+    let mut order = all.to_vec();
+    order.sort_by_key(|q| {
+        let i = all.iter().position(|other| other == q).unwrap_or(0);
+        i.abs_diff(index)
+    });
+    order
 }
 
 /// Get path for a specific model (F16 by default)
@@ -104,11 +162,23 @@ pub fn list_downloaded_models() -> Vec<ModelInfo> {
 
 /// Delete a downloaded model with specific quantization
 pub fn delete_model_with_quantization(model: &WhisperModel, quant: &ModelQuantization) -> Result<(), std::io::Error> {
-    let path = model_path_with_quantization(model, quant);
+    delete_model_file(&model_path_with_quantization(model, quant))
+}
+
+/// Delete a model file and its usage record
+pub fn delete_model_file(path: &Path) -> Result<(), std::io::Error> {
     if path.exists() {
-        std::fs::remove_file(&path)?;
+        std::fs::remove_file(path)?;
         tracing::info!("Deleted model: {:?}", path);
     }
+    if let Some(filename) = path.file_name().and_then(|n| n.to_str()) {
+        let mut usage = super::model_usage().write();
+        if usage.forget(filename) {
+            if let Err(e) = usage.save() {
+                tracing::warn!("Failed to save model usage: {}", e);
+            }
+        }
+    }
     Ok(())
 }
 
@@ -169,6 +239,28 @@ fn get_system_memory_gb() -> Option<u64> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fallback_order_prefers_nearest_precision() {
+        use ModelQuantization::*;
+        assert_eq!(fallback_order(&Q5_1), vec![Q5_1, Q8_0, F16]);
+        assert_eq!(fallback_order(&F16), vec![F16, Q8_0, Q5_1]);
+        assert_eq!(fallback_order(&Q8_0), vec![Q8_0, F16, Q5_1]);
+    }
+
+    #[test]
+    fn test_model_resolution_fallback() {
+        let mut resolution = ModelResolution {
+            model: WhisperModel::Small,
+            requested: ModelQuantization::Q5_1,
+            used: Some(ModelQuantization::F16),
+        };
+        assert!(resolution.is_fallback());
+        resolution.used = None;
+        assert!(!resolution.is_fallback());
+        resolution.used = Some(ModelQuantization::Q5_1);
+        assert!(!resolution.is_fallback());
+    }
+
     // =========================================================================
     // ModelInfo Tests
     // =========================================================================
@@ -1,9 +1,17 @@
 //! Models Module
 //!
-//! Whisper model management and download.
+//! Whisper, VAD and speaker model management and download.
 
+mod custom;
 mod downloader;
 mod manager;
+mod speaker_model;
+mod usage;
+mod vad_model;
 
+pub use custom::*;
 pub use downloader::*;
 pub use manager::*;
+pub use speaker_model::*;
+pub use usage::*;
+pub use vad_model::*;
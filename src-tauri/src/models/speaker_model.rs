@@ -0,0 +1,69 @@
+//! Speaker Model
+//!
+//! Download of the speaker-embedding model used by the speaker filter
+//! (WeSpeaker ResNet34 trained on VoxCeleb, exported to ONNX by
+//! sherpa-onnx). Like the Silero VAD model it is fetched on demand, when a
+//! voice profile is recorded, instead of going through the model manager UI.
+
+use super::{download_pinned_model, DownloadError};
+use crate::config::find_model;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+/// File name of the speaker-embedding model
+pub const SPEAKER_MODEL_FILENAME: &str = "wespeaker_en_voxceleb_resnet34.onnx";
+
+/// Release of the speaker-embedding model (80 fbank bins in, 256-d embedding out)
+const SPEAKER_MODEL_URL: &str = "https://github.com/k2-fsa/sherpa-onnx/releases/download/speaker-recongition-models/wespeaker_en_voxceleb_resnet34.onnx";
+
+/// SHA-256 of the model at `SPEAKER_MODEL_URL`
+const SPEAKER_MODEL_SHA256: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Longest the whole download may take (~27 MB)
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Set while the model is being downloaded
+static DOWNLOADING: AtomicBool = AtomicBool::new(false);
+
+/// Path of the speaker-embedding model (see `find_model`)
+pub fn speaker_model_path() -> PathBuf {
+    find_model(SPEAKER_MODEL_FILENAME)
+}
+
+/// Path of the speaker-embedding model, downloading it first if missing
+pub async fn ensure_speaker_model() -> Result<PathBuf, DownloadError> {
+    let path = speaker_model_path();
+    if path.exists() {
+        return Ok(path);
+    }
+    if DOWNLOADING.swap(true, Ordering::SeqCst) {
+        return Err(DownloadError::Network(
+            "Speaker model download already in progress".to_string(),
+        ));
+    }
+
+    let result = download_pinned_model(
+        "Speaker",
+        SPEAKER_MODEL_URL,
+        SPEAKER_MODEL_FILENAME,
+        SPEAKER_MODEL_SHA256,
+        DOWNLOAD_TIMEOUT,
+    )
+    .await;
+    DOWNLOADING.store(false, Ordering::SeqCst);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_speaker_model_path() {
+        assert!(speaker_model_path().ends_with(SPEAKER_MODEL_FILENAME));
+        assert!(SPEAKER_MODEL_URL.ends_with(SPEAKER_MODEL_FILENAME));
+        assert_eq!(SPEAKER_MODEL_SHA256.len(), 64);
+    }
+}
@@ -0,0 +1,271 @@
+//! Model Usage
+//!
+//! Last-used times of downloaded models, kept in `usage.json` next to the
+//! models, and the pruning of models left unused. Models never used since
+//! they were downloaded count from their download time.
+
+use super::{delete_model_file, list_downloaded_models, ModelInfo};
+use crate::config::{find_model, user_models_dir, Settings};
+use crate::vocabulary::unix_now;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Minimum time between saved updates of a model's last use (1 hour)
+const RECORD_INTERVAL_SECS: u64 = 3600;
+
+const SECS_PER_DAY: u64 = 24 * 3600;
+
+/// Global model usage instance
+static MODEL_USAGE: OnceLock<RwLock<ModelUsage>> = OnceLock::new();
+
+/// Last-used times by model filename
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelUsage {
+    /// Unix timestamps (seconds)
+    last_used: HashMap<String, u64>,
+}
+
+impl ModelUsage {
+    /// Load model usage from disk
+    pub fn load() -> Self {
+        let path = usage_file_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(usage) => return usage,
+                    Err(e) => tracing::warn!("Failed to parse model usage file: {}", e),
+                },
+                Err(e) => tracing::warn!("Failed to read model usage file: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    /// Save model usage to disk
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let path = usage_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, content)
+    }
+
+    /// Record a use of `filename`; returns whether the change is worth saving
+    pub fn record(&mut self, filename: &str, now: u64) -> bool {
+        let previous = self.last_used.insert(filename.to_string(), now);
+        previous.is_none_or(|last| now.saturating_sub(last) >= RECORD_INTERVAL_SECS)
+    }
+
+    /// When `filename` was last used
+    pub fn last_used(&self, filename: &str) -> Option<u64> {
+        self.last_used.get(filename).copied()
+    }
+
+    /// Drop the record of a deleted model, returns true if found
+    pub fn forget(&mut self, filename: &str) -> bool {
+        self.last_used.remove(filename).is_some()
+    }
+}
+
+/// A downloaded model suggested for removal
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct PruneCandidate {
+    pub model: String,
+    pub quantization: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// ISO 8601 time of the last use (or the download)
+    pub last_used: String,
+    pub unused_days: u64,
+}
+
+/// Result of `prune_unused_models`
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct PruneReport {
+    /// Models unused for the requested number of days
+    pub models: Vec<PruneCandidate>,
+    /// Whether the models were deleted
+    pub deleted: bool,
+    /// Disk space freed (bytes)
+    pub freed_bytes: u64,
+    /// Models that could not be deleted, with the reason
+    pub errors: Vec<String>,
+}
+
+/// Get or initialize the global model usage instance
+pub fn model_usage() -> &'static RwLock<ModelUsage> {
+    MODEL_USAGE.get_or_init(|| RwLock::new(ModelUsage::load()))
+}
+
+/// Record that the model at `path` was used
+pub fn record_model_use(path: &Path) {
+    let Some(filename) = path.file_name().and_then(|n| n.to_str()) else {
+        return;
+    };
+    let mut usage = model_usage().write();
+    if usage.record(filename, unix_now()) {
+        if let Err(e) = usage.save() {
+            tracing::warn!("Failed to save model usage: {}", e);
+        }
+    }
+}
+
+/// Pick the models unused for at least `min_days`, least recently used first
+///
+/// `models` pairs each downloaded model with its last use; models in `keep`
+/// are never picked.
+pub fn select_unused(
+    models: Vec<(ModelInfo, u64)>,
+    keep: &[PathBuf],
+    min_days: u32,
+    now: u64,
+) -> Vec<PruneCandidate> {
+    let mut candidates: Vec<(u64, PruneCandidate)> = models
+        .into_iter()
+        .filter(|(info, _)| !keep.contains(&info.path))
+        .filter_map(|(info, last_used)| {
+            let unused_days = now.saturating_sub(last_used) / SECS_PER_DAY;
+            (unused_days >= min_days as u64).then(|| {
+                let candidate = PruneCandidate {
+                    model: info.model,
+                    quantization: info.quantization,
+                    path: info.path,
+                    size_bytes: info.size_bytes,
+                    last_used: crate::history::timestamp_from_unix(last_used),
+                    unused_days,
+                };
+                (last_used, candidate)
+            })
+        })
+        .collect();
+    candidates.sort_by_key(|(last_used, _)| *last_used);
+    candidates.into_iter().map(|(_, c)| c).collect()
+}
+
+/// Models the settings refer to: the local model and profile overrides
+fn models_in_use(settings: &Settings) -> Vec<PathBuf> {
+    let local = &settings.transcription.local;
+    let mut paths = vec![find_model(&local.model_filename())];
+    paths.extend(settings.profiles.iter().filter_map(|profile| {
+        let model = profile.local_model.as_ref()?;
+        Some(find_model(&model.filename_with_quantization(&local.quantization)))
+    }));
+    paths
+}
+
+/// Downloaded models unused for at least `min_days`
+///
+/// Models the settings refer to and `loaded` (the model in memory) are kept,
+/// as are models of the shared directory, which other users may rely on.
+pub fn unused_models(
+    settings: &Settings,
+    loaded: Option<PathBuf>,
+    min_days: u32,
+) -> Vec<PruneCandidate> {
+    let mut keep = models_in_use(settings);
+    keep.extend(loaded);
+
+    let usage = model_usage().read();
+    let own_dir = user_models_dir();
+    let models = list_downloaded_models()
+        .into_iter()
+        .filter(|info| info.path.starts_with(&own_dir))
+        .map(|info| {
+            let filename = info.path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            let last_used = usage
+                .last_used(filename)
+                .unwrap_or_else(|| downloaded_at(&info.path));
+            (info, last_used)
+        })
+        .collect();
+    select_unused(models, &keep, min_days, unix_now())
+}
+
+/// When a model file was written (now if unknown, so it is never pruned early)
+fn downloaded_at(path: &Path) -> u64 {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or_else(unix_now)
+}
+
+/// Delete the picked models
+pub fn prune_models(models: Vec<PruneCandidate>) -> PruneReport {
+    let mut report = PruneReport {
+        deleted: true,
+        ..PruneReport::default()
+    };
+    for candidate in &models {
+        match delete_model_file(&candidate.path) {
+            Ok(()) => report.freed_bytes += candidate.size_bytes,
+            Err(e) => report
+                .errors
+                .push(format!("{} ({}): {}", candidate.model, candidate.quantization, e)),
+        }
+    }
+    tracing::info!(
+        "Pruned {} unused model(s), freed {} MB",
+        models.len() - report.errors.len(),
+        report.freed_bytes / (1024 * 1024)
+    );
+    report.models = models;
+    report
+}
+
+/// Get the model usage file path (per user, even with a shared model directory)
+fn usage_file_path() -> PathBuf {
+    user_models_dir().join("usage.json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(model: &str, path: &str) -> ModelInfo {
+        ModelInfo {
+            model: model.to_string(),
+            quantization: "f16".to_string(),
+            path: PathBuf::from(path),
+            size_bytes: 1000,
+            downloaded: true,
+            is_quantized: false,
+        }
+    }
+
+    #[test]
+    fn test_record_throttles_saves() {
+        let mut usage = ModelUsage::default();
+        assert!(usage.record("ggml-tiny.bin", 1000));
+        assert!(!usage.record("ggml-tiny.bin", 1060));
+        assert_eq!(usage.last_used("ggml-tiny.bin"), Some(1060));
+        assert!(usage.record("ggml-tiny.bin", 1060 + RECORD_INTERVAL_SECS));
+
+        assert!(usage.forget("ggml-tiny.bin"));
+        assert_eq!(usage.last_used("ggml-tiny.bin"), None);
+    }
+
+    #[test]
+    fn test_select_unused() {
+        let now = 100 * SECS_PER_DAY;
+        let models = vec![
+            (info("tiny", "/m/tiny.bin"), now - 40 * SECS_PER_DAY),
+            (info("base", "/m/base.bin"), now - 5 * SECS_PER_DAY),
+            (info("large", "/m/large.bin"), now - 90 * SECS_PER_DAY),
+            (info("small", "/m/small.bin"), now - 60 * SECS_PER_DAY),
+        ];
+        let keep = vec![PathBuf::from("/m/small.bin")];
+
+        let unused = select_unused(models, &keep, 30, now);
+        let names: Vec<&str> = unused.iter().map(|c| c.model.as_str()).collect();
+        assert_eq!(names, vec!["large", "tiny"]);
+        assert_eq!(unused[0].unused_days, 90);
+    }
+}
@@ -0,0 +1,148 @@
+//! Casing
+//!
+//! Explicit case transforms applied as the last post-processing step, for
+//! targets that want all-lowercase commands or Title Case headers. Sentence
+//! and title case only change first letters, so acronyms and names such as
+//! "NASA" or "iPhone" inside the text are kept. Case mapping is
+//! Unicode-aware ("ß" uppercases to "SS", "ǆ" titlecases to "ǅ").
+
+use crate::config::TextCasing;
+
+/// Apply `casing` to a transcription
+pub fn apply_casing(text: &str, casing: TextCasing) -> String {
+    match casing {
+        TextCasing::Keep => text.to_string(),
+        TextCasing::Lower => text.to_lowercase(),
+        TextCasing::Upper => text.to_uppercase(),
+        TextCasing::Sentence => sentence_case(text),
+        TextCasing::Title => title_case(text),
+    }
+}
+
+/// Uppercase the first letter of each sentence
+fn sentence_case(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut sentence_start = true;
+    let mut after_terminator = false;
+    for c in text.chars() {
+        if after_terminator && c.is_whitespace() {
+            sentence_start = true;
+        }
+        after_terminator = matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？')
+            || (after_terminator && is_closing(c));
+
+        if c.is_alphabetic() && sentence_start {
+            push_titlecase(&mut out, c);
+            sentence_start = false;
+        } else {
+            // A number starts the sentence too ("3 apples")
+            if c.is_numeric() {
+                sentence_start = false;
+            }
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Uppercase the first letter of each word
+///
+/// Apostrophes inside a word do not start a new one ("don't" becomes
+/// "Don't"); hyphens do ("jean-luc" becomes "Jean-Luc").
+fn title_case(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut in_word = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_alphanumeric() {
+            if in_word || !c.is_alphabetic() {
+                out.push(c);
+            } else {
+                push_titlecase(&mut out, c);
+            }
+            in_word = true;
+        } else {
+            let apostrophe = matches!(c, '\'' | '’')
+                && in_word
+                && chars.get(i + 1).is_some_and(|n| n.is_alphabetic());
+            in_word = apostrophe;
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Closing quotes and brackets that may follow a sentence terminator
+fn is_closing(c: char) -> bool {
+    matches!(c, '"' | '\'' | '”' | '’' | '»' | '“' | ')' | ']' | '」')
+}
+
+/// Push the titlecase form of `c`
+///
+/// Differs from uppercase for the Latin digraphs ("ǆ" → "ǅ") and for
+/// letters whose uppercase form has several letters ("ß" → "Ss").
+fn push_titlecase(out: &mut String, c: char) {
+    let digraph = match c {
+        'Ǆ' | 'ǅ' | 'ǆ' => Some('ǅ'),
+        'Ǉ' | 'ǈ' | 'ǉ' => Some('ǈ'),
+        'Ǌ' | 'ǋ' | 'ǌ' => Some('ǋ'),
+        'Ǳ' | 'ǲ' | 'ǳ' => Some('ǲ'),
+        _ => None,
+    };
+    if let Some(title) = digraph {
+        out.push(title);
+        return;
+    }
+
+    let mut upper = c.to_uppercase();
+    if let Some(first) = upper.next() {
+        out.push(first);
+    }
+    for rest in upper {
+        out.extend(rest.to_lowercase());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lower_and_upper() {
+        assert_eq!(apply_casing("Git STATUS", TextCasing::Lower), "git status");
+        assert_eq!(apply_casing("straße", TextCasing::Upper), "STRASSE");
+        // Final sigma depends on its position
+        assert_eq!(apply_casing("ΣΟΦΟΣ ΟΔΟΣ", TextCasing::Lower), "σοφος οδος");
+        assert_eq!(apply_casing("hello", TextCasing::Keep), "hello");
+    }
+
+    #[test]
+    fn test_sentence_case() {
+        assert_eq!(
+            apply_casing("hello world. how are you? «fine.» ok", TextCasing::Sentence),
+            "Hello world. How are you? «Fine.» Ok"
+        );
+        assert_eq!(apply_casing("3 apples. été", TextCasing::Sentence), "3 apples. Été");
+        // Only first letters change
+        assert_eq!(
+            apply_casing("ask NASA. iPhone sales", TextCasing::Sentence),
+            "Ask NASA. IPhone sales"
+        );
+        assert_eq!(
+            apply_casing("version 1.5 is out", TextCasing::Sentence),
+            "Version 1.5 is out"
+        );
+    }
+
+    #[test]
+    fn test_title_case() {
+        assert_eq!(
+            apply_casing("the QUICK brown fox's jean-luc", TextCasing::Title),
+            "The QUICK Brown Fox's Jean-Luc"
+        );
+        assert_eq!(apply_casing("meet McDonald", TextCasing::Title), "Meet McDonald");
+        assert_eq!(apply_casing("ßtraße ǆungla", TextCasing::Title), "Sstraße ǅungla");
+        assert_eq!(apply_casing("l’école 2nd", TextCasing::Title), "L’école 2nd");
+        assert_eq!(apply_casing("'quoted' word", TextCasing::Title), "'Quoted' Word");
+    }
+}
@@ -1,8 +1,17 @@
 //! Clipboard Operations
 //!
-//! Read/write clipboard content.
+//! Read/write clipboard content. Native Wayland sessions go through
+//! wl-clipboard when available, since X11 clipboard access only reaches
+//! XWayland apps. On Linux the primary selection (middle-click paste) can
+//! be written as well.
+//!
+//! Concealed writes (incognito dictations) carry each platform's marker for
+//! sensitive data, so clipboard history, cloud sync and clipboard managers
+//! leave them out.
 
 use arboard::Clipboard;
+use crate::config::{ClipboardFormat, PasteSelection};
+use crate::utils::{platform_capabilities, ClipboardBackend};
 
 /// Clipboard errors
 #[derive(Debug, thiserror::Error)]
@@ -15,10 +24,17 @@ pub enum ClipboardError {
 
     #[error("Failed to set clipboard content: {0}")]
     Set(String),
+
+    #[error("Primary selection is not available on this platform")]
+    PrimaryUnsupported,
 }
 
 /// Get current clipboard text
 pub fn get_text() -> Result<String, ClipboardError> {
+    if platform_capabilities().clipboard == ClipboardBackend::WlClipboard {
+        return wl_paste();
+    }
+
     let mut clipboard = Clipboard::new()
         .map_err(|e| ClipboardError::Access(e.to_string()))?;
 
@@ -29,6 +45,10 @@ pub fn get_text() -> Result<String, ClipboardError> {
 
 /// Set clipboard text
 pub fn set_text(text: &str) -> Result<(), ClipboardError> {
+    if platform_capabilities().clipboard == ClipboardBackend::WlClipboard {
+        return wl_copy(text, false, false);
+    }
+
     let mut clipboard = Clipboard::new()
         .map_err(|e| ClipboardError::Access(e.to_string()))?;
 
@@ -37,35 +57,242 @@ pub fn set_text(text: &str) -> Result<(), ClipboardError> {
         .map_err(|e| ClipboardError::Set(e.to_string()))
 }
 
+/// Set clipboard text, adding an HTML rendering when `format` asks for one
+///
+/// wl-copy serves a single type per call, so Wayland sessions using
+/// wl-clipboard keep plain text only. `concealed` keeps the text out of
+/// clipboard history.
+pub fn set_formatted_text(
+    text: &str,
+    format: ClipboardFormat,
+    concealed: bool,
+) -> Result<(), ClipboardError> {
+    if platform_capabilities().clipboard == ClipboardBackend::WlClipboard {
+        return wl_copy(text, false, concealed);
+    }
+    if format == ClipboardFormat::PlainText && !concealed {
+        return set_text(text);
+    }
+
+    let mut clipboard = Clipboard::new()
+        .map_err(|e| ClipboardError::Access(e.to_string()))?;
+    let set = if concealed {
+        conceal(clipboard.set())
+    } else {
+        clipboard.set()
+    };
+
+    let result = match format {
+        ClipboardFormat::PlainText => set.text(text),
+        ClipboardFormat::Html => set.html(super::markdown_to_html(text), Some(text.to_string())),
+    };
+    result.map_err(|e| ClipboardError::Set(e.to_string()))
+}
+
+/// Mark clipboard data as sensitive
+///
+/// Windows leaves it out of clipboard history, cloud sync and monitoring
+/// apps (`ExcludeClipboardContentFromMonitorProcessing`), macOS adds
+/// `org.nspasteboard.ConcealedType` and Linux `x-kde-passwordManagerHint`.
+fn conceal(set: arboard::Set<'_>) -> arboard::Set<'_> {
+    #[cfg(windows)]
+    {
+        use arboard::SetExtWindows;
+        set.exclude_from_monitoring()
+            .exclude_from_history()
+            .exclude_from_cloud()
+    }
+    #[cfg(target_os = "macos")]
+    {
+        use arboard::SetExtApple;
+        set.exclude_from_history()
+    }
+    #[cfg(target_os = "linux")]
+    {
+        use arboard::SetExtLinux;
+        set.exclude_from_history()
+    }
+}
+
+/// Clipboard kept open once the primary selection was set
+///
+/// arboard serves X11 selections from a thread that stops when the last
+/// `Clipboard` is dropped. The primary selection is not handed to clipboard
+/// managers, so it would be empty by the time the user middle-clicks.
+#[cfg(target_os = "linux")]
+static SELECTION_OWNER: parking_lot::Mutex<Option<Clipboard>> = parking_lot::Mutex::new(None);
+
+/// Set the primary selection (middle-click paste)
+#[cfg(target_os = "linux")]
+pub fn set_primary_text(text: &str, concealed: bool) -> Result<(), ClipboardError> {
+    use arboard::{LinuxClipboardKind, SetExtLinux};
+
+    if platform_capabilities().clipboard == ClipboardBackend::WlClipboard {
+        return wl_copy(text, true, concealed);
+    }
+
+    let mut owner = SELECTION_OWNER.lock();
+    let clipboard = match owner.as_mut() {
+        Some(clipboard) => clipboard,
+        None => owner.insert(Clipboard::new().map_err(|e| ClipboardError::Access(e.to_string()))?),
+    };
+
+    let set = clipboard.set().clipboard(LinuxClipboardKind::Primary);
+    let set = if concealed { conceal(set) } else { set };
+    set.text(text)
+        .map_err(|e| ClipboardError::Set(e.to_string()))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_primary_text(_text: &str, _concealed: bool) -> Result<(), ClipboardError> {
+    Err(ClipboardError::PrimaryUnsupported)
+}
+
+/// Selection actually used on this platform (only Linux has a primary selection)
+pub fn effective_selection(selection: PasteSelection) -> PasteSelection {
+    if cfg!(target_os = "linux") {
+        selection
+    } else {
+        PasteSelection::Clipboard
+    }
+}
+
+/// Put text in the configured selection(s)
+pub fn set_selection_text(
+    text: &str,
+    selection: PasteSelection,
+    format: ClipboardFormat,
+    concealed: bool,
+) -> Result<(), ClipboardError> {
+    let selection = effective_selection(selection);
+    if selection.uses_primary() {
+        set_primary_text(text, concealed)?;
+    }
+    if selection.uses_clipboard() {
+        set_formatted_text(text, format, concealed)?;
+    }
+    Ok(())
+}
+
+/// Clipboard sequence number, incremented by the system on every change
+#[cfg(windows)]
+pub fn clipboard_sequence_number() -> Option<u32> {
+    // SAFETY: GetClipboardSequenceNumber takes no arguments and only reads
+    // a counter maintained by the window station.
+    let sequence = unsafe { windows::Win32::System::DataExchange::GetClipboardSequenceNumber() };
+    (sequence != 0).then_some(sequence)
+}
+
+#[cfg(not(windows))]
+pub fn clipboard_sequence_number() -> Option<u32> {
+    None
+}
+
+/// Read the Wayland clipboard with `wl-paste`
+fn wl_paste() -> Result<String, ClipboardError> {
+    let output = std::process::Command::new("wl-paste")
+        .arg("--no-newline")
+        .output()
+        .map_err(|e| ClipboardError::Access(e.to_string()))?;
+
+    if !output.status.success() {
+        return Err(ClipboardError::Get(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|e| ClipboardError::Get(e.to_string()))
+}
+
+/// Write the Wayland clipboard (or primary selection) with `wl-copy`
+///
+/// wl-copy forks to keep serving the selection, so this returns as soon as
+/// the text has been handed over. `--sensitive` (`concealed`) needs
+/// wl-clipboard 2.2 or later.
+fn wl_copy(text: &str, primary: bool, concealed: bool) -> Result<(), ClipboardError> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut command = Command::new("wl-copy");
+    if primary {
+        command.arg("--primary");
+    }
+    if concealed {
+        command.arg("--sensitive");
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| ClipboardError::Access(e.to_string()))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| ClipboardError::Set(e.to_string()))?;
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| ClipboardError::Set(e.to_string()))?;
+    if !status.success() {
+        return Err(ClipboardError::Set(format!("wl-copy exited with {}", status)));
+    }
+    Ok(())
+}
+
 /// Copy text to clipboard (alias for set_text)
 pub fn copy_to_clipboard(text: &str) -> Result<(), ClipboardError> {
     set_text(text)
 }
 
-/// Paste text using clipboard and Ctrl+V
-/// Preserves original clipboard content
-pub async fn paste_text(text: &str) -> Result<(), ClipboardError> {
-    use super::keyboard;
+/// Paste text into the active application
+///
+/// With the clipboard selected, pastes with Ctrl+V and restores the previous
+/// clipboard content once the paste has been read. A paste that can't be
+/// confirmed leaves the text on the clipboard for a manual paste. With only
+/// the primary selection, the text is left there for the user to
+/// middle-click paste.
+pub async fn paste_text(
+    text: &str,
+    selection: PasteSelection,
+    format: ClipboardFormat,
+) -> Result<(), ClipboardError> {
+    use super::{keyboard, wait_for_paste, InjectionProbe, Verification};
+
+    let selection = effective_selection(selection);
+    if selection.uses_primary() {
+        set_primary_text(text, false)?;
+    }
+    if !selection.uses_clipboard() {
+        return Ok(());
+    }
 
     // Save current clipboard
     let previous = get_text().ok();
 
     // Set new text
-    set_text(text)?;
+    set_formatted_text(text, format, false)?;
 
     // Small delay to ensure clipboard is set
     tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
 
     // Simulate paste
+    let before = InjectionProbe::capture();
     keyboard::send_ctrl_v()
         .map_err(|e| ClipboardError::Set(e.to_string()))?;
 
-    // Wait for paste to complete
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-
-    // Restore previous clipboard content
-    if let Some(prev) = previous {
-        let _ = set_text(&prev);
+    // Restoring before the target read the clipboard would paste the old content
+    match wait_for_paste(&before).await {
+        Verification::Confirmed | Verification::Unverified => {
+            if let Some(prev) = previous {
+                let _ = set_text(&prev);
+            }
+        }
+        verification => {
+            tracing::warn!(
+                "Paste not confirmed ({:?}), leaving the text on the clipboard",
+                verification
+            );
+        }
     }
 
     Ok(())
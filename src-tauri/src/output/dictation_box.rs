@@ -0,0 +1,96 @@
+//! Dictation Box
+//!
+//! Pending-output state of the floating mini editor. While the box is open,
+//! transcriptions are appended to it instead of being pasted; sending
+//! injects the edited text into the window that was focused when the box
+//! opened.
+
+use super::apply_smart_spacing;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Window label of the dictation box
+pub const DICTATION_BOX_LABEL: &str = "dictation-box";
+
+/// Dictation box state
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct DictationBox {
+    pub open: bool,
+    /// Current (possibly user-edited) text
+    pub text: String,
+    /// Window to send the text to
+    #[serde(skip)]
+    pub target_window: Option<isize>,
+}
+
+impl DictationBox {
+    /// Start a new session targeting `target_window`
+    pub fn open(&mut self, target_window: Option<isize>) {
+        self.open = true;
+        self.text.clear();
+        self.target_window = target_window;
+    }
+
+    /// Append a transcription, returning the updated text if the box is open
+    pub fn append(&mut self, text: &str) -> Option<&str> {
+        if !self.open {
+            return None;
+        }
+        let preceding = self.text.chars().last();
+        self.text.push_str(&apply_smart_spacing(text, preceding));
+        Some(&self.text)
+    }
+
+    /// Replace the text with the user's edits
+    pub fn update(&mut self, text: String) {
+        if self.open {
+            self.text = text;
+        }
+    }
+
+    /// Close the box, returning its text and target window
+    pub fn take(&mut self) -> Option<(String, Option<isize>)> {
+        if !self.open {
+            return None;
+        }
+        self.open = false;
+        Some((std::mem::take(&mut self.text), self.target_window.take()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_append_only_when_open() {
+        let mut dictation = DictationBox::default();
+        assert!(dictation.append("ignored").is_none());
+
+        dictation.open(Some(1));
+        assert_eq!(dictation.append("Hello"), Some("Hello"));
+        assert_eq!(dictation.append("world."), Some("Hello world."));
+    }
+
+    #[test]
+    fn test_take_closes_and_returns_target() {
+        let mut dictation = DictationBox::default();
+        dictation.open(Some(42));
+        dictation.append("Draft");
+        dictation.update("Edited draft".to_string());
+
+        assert_eq!(dictation.take(), Some(("Edited draft".to_string(), Some(42))));
+        assert!(!dictation.open);
+        assert!(dictation.take().is_none());
+    }
+
+    #[test]
+    fn test_open_resets_text() {
+        let mut dictation = DictationBox::default();
+        dictation.open(None);
+        dictation.append("old");
+        dictation.open(Some(2));
+        assert!(dictation.text.is_empty());
+        assert_eq!(dictation.target_window, Some(2));
+    }
+}
@@ -5,6 +5,8 @@
 /// Information about the active window
 #[derive(Debug, Clone)]
 pub struct ActiveWindow {
+    /// Native window handle, stable while the window exists
+    pub id: isize,
     /// Window title
     pub title: String,
     /// Process name
@@ -13,6 +15,14 @@ pub struct ActiveWindow {
     pub has_text_input: bool,
 }
 
+impl ActiveWindow {
+    /// Whether this is one of GigaWhisper's own windows
+    pub fn is_gigawhisper(&self) -> bool {
+        self.process_name.to_lowercase().contains("gigawhisper")
+            || self.title.to_lowercase().contains("gigawhisper")
+    }
+}
+
 /// Get information about the currently active window
 #[cfg(windows)]
 pub fn get_active_window() -> Option<ActiveWindow> {
@@ -45,6 +55,7 @@ pub fn get_active_window() -> Option<ActiveWindow> {
         let has_text_input = check_text_input(hwnd);
 
         Some(ActiveWindow {
+            id: hwnd.0 as isize,
             title,
             process_name,
             has_text_input,
@@ -125,6 +136,22 @@ fn check_text_input(_hwnd: windows::Win32::Foundation::HWND) -> bool {
     }
 }
 
+/// Bring a window back to the foreground
+#[cfg(windows)]
+pub fn focus_window(id: isize) -> bool {
+    use windows::Win32::Foundation::HWND;
+    use windows::Win32::UI::WindowsAndMessaging::SetForegroundWindow;
+
+    // SAFETY: SetForegroundWindow validates the handle and fails for windows
+    // that no longer exist.
+    unsafe { SetForegroundWindow(HWND(id as *mut std::ffi::c_void)).as_bool() }
+}
+
+#[cfg(not(windows))]
+pub fn focus_window(_id: isize) -> bool {
+    false
+}
+
 /// Check if the active window likely accepts text input
 pub fn has_text_input_focus() -> bool {
     get_active_window()
@@ -135,13 +162,13 @@ pub fn has_text_input_focus() -> bool {
 /// Check if we should auto-paste (any window except GigaWhisper is active)
 pub fn should_auto_paste() -> bool {
     match get_active_window() {
-        Some(window) => {
-            // Don't paste into our own app
-            let is_our_app = window.process_name.to_lowercase().contains("gigawhisper")
-                || window.title.to_lowercase().contains("gigawhisper");
-            !is_our_app
+        // Don't paste into our own app
+        Some(window) => !window.is_gigawhisper(),
+        // Linux can't report the focused window; paste when a key
+        // injection tool is available
+        None => {
+            cfg!(target_os = "linux") && crate::utils::platform_capabilities().can_paste()
         }
-        None => false,
     }
 }
 
@@ -0,0 +1,136 @@
+//! Input Method Detection
+//!
+//! Synthetic typing goes through the active input method, so with a CJK
+//! IME on, injected characters get composed into the wrong text. Detect an
+//! active IME so injection can stick to clipboard paste, and commit a
+//! pending composition before pasting so it isn't mixed with the new text.
+
+/// Check whether an input method editor is active for the focused window
+#[cfg(windows)]
+pub fn is_ime_active() -> bool {
+    windows_ime::open_status().unwrap_or(false)
+}
+
+/// Check whether the current input source is an input method (not a plain layout)
+#[cfg(target_os = "macos")]
+pub fn is_ime_active() -> bool {
+    macos_ime::is_input_method()
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+pub fn is_ime_active() -> bool {
+    false
+}
+
+/// IME switched off for the duration of a paste
+///
+/// Turning the IME off commits any pending composition; dropping the guard
+/// turns it back on.
+pub struct ImeSuspension {
+    #[cfg(windows)]
+    ime_window: windows::Win32::Foundation::HWND,
+}
+
+/// Commit the pending composition and suspend the IME until the guard is dropped
+#[cfg(windows)]
+pub fn suspend_ime() -> Option<ImeSuspension> {
+    let ime_window = windows_ime::default_ime_window()?;
+    windows_ime::set_open_status(ime_window, false);
+    Some(ImeSuspension { ime_window })
+}
+
+#[cfg(not(windows))]
+pub fn suspend_ime() -> Option<ImeSuspension> {
+    None
+}
+
+impl Drop for ImeSuspension {
+    fn drop(&mut self) {
+        #[cfg(windows)]
+        windows_ime::set_open_status(self.ime_window, true);
+    }
+}
+
+#[cfg(windows)]
+mod windows_ime {
+    use windows::Win32::Foundation::{HWND, LPARAM, WPARAM};
+    use windows::Win32::UI::Input::Ime::ImmGetDefaultIMEWnd;
+    use windows::Win32::UI::WindowsAndMessaging::{GetForegroundWindow, SendMessageW, WM_IME_CONTROL};
+
+    const IMC_GETOPENSTATUS: usize = 0x0005;
+    const IMC_SETOPENSTATUS: usize = 0x0006;
+
+    /// Default IME window of the foreground window's thread
+    ///
+    /// Input contexts of other processes can't be opened directly, but their
+    /// default IME window accepts WM_IME_CONTROL requests.
+    pub fn default_ime_window() -> Option<HWND> {
+        // SAFETY: Both calls take/return plain window handles; a null
+        // foreground window or missing IME window is checked before use.
+        unsafe {
+            let foreground = GetForegroundWindow();
+            if foreground.0.is_null() {
+                return None;
+            }
+            let ime_window = ImmGetDefaultIMEWnd(foreground);
+            (!ime_window.0.is_null()).then_some(ime_window)
+        }
+    }
+
+    pub fn open_status() -> Option<bool> {
+        let ime_window = default_ime_window()?;
+        // SAFETY: WM_IME_CONTROL with IMC_GETOPENSTATUS carries no pointers.
+        let status = unsafe {
+            SendMessageW(ime_window, WM_IME_CONTROL, WPARAM(IMC_GETOPENSTATUS), LPARAM(0))
+        };
+        Some(status.0 != 0)
+    }
+
+    pub fn set_open_status(ime_window: HWND, open: bool) {
+        // SAFETY: WM_IME_CONTROL with IMC_SETOPENSTATUS carries no pointers.
+        unsafe {
+            SendMessageW(
+                ime_window,
+                WM_IME_CONTROL,
+                WPARAM(IMC_SETOPENSTATUS),
+                LPARAM(open as isize),
+            );
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos_ime {
+    use std::ffi::c_void;
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        static kTISPropertyInputSourceType: *const c_void;
+        static kTISTypeKeyboardLayout: *const c_void;
+        fn TISCopyCurrentKeyboardInputSource() -> *const c_void;
+        fn TISGetInputSourceProperty(source: *const c_void, key: *const c_void) -> *const c_void;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFEqual(a: *const c_void, b: *const c_void) -> u8;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    pub fn is_input_method() -> bool {
+        // SAFETY: The input source is a +1 reference released below; the
+        // property value follows the Get rule and is not released. The
+        // constants are immutable CFStrings exported by Carbon.
+        unsafe {
+            let source = TISCopyCurrentKeyboardInputSource();
+            if source.is_null() {
+                return false;
+            }
+            let source_type = TISGetInputSourceProperty(source, kTISPropertyInputSourceType);
+            let is_layout =
+                !source_type.is_null() && CFEqual(source_type, kTISTypeKeyboardLayout) != 0;
+            CFRelease(source);
+            !source_type.is_null() && !is_layout
+        }
+    }
+}
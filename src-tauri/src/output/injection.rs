@@ -0,0 +1,427 @@
+//! Text Injection
+//!
+//! Deliver text into the focused application and verify it arrived. Methods
+//! are tried in order (paste, UI Automation, typing) until one can send its
+//! input; when that input can't be confirmed the text stays on the clipboard
+//! for a manual paste.
+
+use super::{
+    clipboard_sequence_number, focused_control_value, get_active_window, insert_text_uia,
+    is_ime_active, send_ctrl_v, suspend_ime, type_text, typing_delay, KeyboardError,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// Time given to the target app to process an injection before verifying
+const VERIFY_DELAY: Duration = Duration::from_millis(150);
+
+/// Longest wait for a paste to show up in the focused control
+const PASTE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Interval between read-backs while waiting for a paste
+const PASTE_POLL: Duration = Duration::from_millis(50);
+
+/// How text reached its destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum InjectionMethod {
+    /// Clipboard + Ctrl+V
+    Paste,
+    /// Written into the focused control through UI Automation (Windows)
+    UiAutomation,
+    /// Typed character by character
+    Typing,
+    /// Left on the clipboard for the user to paste
+    ClipboardOnly,
+    /// Left in the primary selection for a middle-click paste (Linux)
+    PrimarySelection,
+    /// Shown in the GigaWhisper popup
+    Popup,
+    /// Added to the open dictation box
+    DictationBox,
+}
+
+/// Whether an injection could be confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verification {
+    /// The focused control's content changed
+    Confirmed,
+    /// No failure detected, but the target can't be read back
+    Unverified,
+    /// The injection was rejected or didn't change the target
+    Failed,
+    /// Focus moved to another window; injecting again could hit the wrong app
+    FocusChanged,
+}
+
+/// Result of delivering text to the focused application
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct InjectionReport {
+    pub method: InjectionMethod,
+    pub verification: Verification,
+}
+
+/// Observable state around an injection attempt
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InjectionProbe {
+    /// Foreground window handle
+    pub window: Option<isize>,
+    /// Value of the focused control (UI Automation read-back)
+    pub control_value: Option<String>,
+    /// Clipboard sequence number
+    pub clipboard_sequence: Option<u32>,
+}
+
+impl InjectionProbe {
+    /// Capture the current state
+    pub fn capture() -> Self {
+        Self {
+            window: get_active_window().map(|w| w.id),
+            control_value: focused_control_value(),
+            clipboard_sequence: clipboard_sequence_number(),
+        }
+    }
+
+    /// Compare with the state after an injection
+    ///
+    /// `uses_clipboard` enables the clipboard check: if the clipboard changed
+    /// before the paste was read, the target received something else.
+    pub fn verify(&self, after: &Self, uses_clipboard: bool) -> Verification {
+        if let (Some(before), Some(now)) = (self.window, after.window) {
+            if before != now {
+                return Verification::FocusChanged;
+            }
+        }
+
+        if uses_clipboard {
+            if let (Some(before), Some(now)) = (self.clipboard_sequence, after.clipboard_sequence) {
+                if before != now {
+                    return Verification::Failed;
+                }
+            }
+        }
+
+        match (&self.control_value, &after.control_value) {
+            (Some(before), Some(now)) if before == now => Verification::Failed,
+            (Some(_), Some(_)) => Verification::Confirmed,
+            _ => Verification::Unverified,
+        }
+    }
+}
+
+/// How a transcription is delivered to the focused application
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeliveryRoute {
+    /// Secure Input is on: copy the text and say why it wasn't pasted
+    SecureInput,
+    /// GigaWhisper has focus: show the text in the popup
+    Popup,
+    /// Type the text without touching the clipboard
+    Type,
+    /// Leave the text in the primary selection
+    PrimarySelection,
+    /// Copy the text and paste it
+    Paste,
+}
+
+/// Pick the delivery route, checking Secure Input first
+///
+/// Secure Input swallows synthetic keystrokes and hides which window has
+/// focus, so no other check is trusted while it is on. `typing` is the
+/// typing output mode with no input method active.
+pub fn delivery_route(
+    secure_input: bool,
+    should_paste: bool,
+    typing: bool,
+    uses_clipboard: bool,
+) -> DeliveryRoute {
+    if secure_input {
+        DeliveryRoute::SecureInput
+    } else if !should_paste {
+        DeliveryRoute::Popup
+    } else if typing {
+        DeliveryRoute::Type
+    } else if !uses_clipboard {
+        DeliveryRoute::PrimarySelection
+    } else {
+        DeliveryRoute::Paste
+    }
+}
+
+/// Wait for a paste sent after `before` was captured to reach the focused control
+///
+/// Reads the control back until it changes or `PASTE_TIMEOUT` passes, after
+/// at least `VERIFY_DELAY` so targets that can't be read back get time too.
+pub async fn wait_for_paste(before: &InjectionProbe) -> Verification {
+    poll_verification(before, InjectionProbe::capture, PASTE_TIMEOUT).await
+}
+
+async fn poll_verification(
+    before: &InjectionProbe,
+    mut capture: impl FnMut() -> InjectionProbe,
+    timeout: Duration,
+) -> Verification {
+    let started = Instant::now();
+    tokio::time::sleep(VERIFY_DELAY).await;
+    loop {
+        let verification = before.verify(&capture(), true);
+        if verification != Verification::Failed || started.elapsed() >= timeout {
+            return verification;
+        }
+        tokio::time::sleep(PASTE_POLL).await;
+    }
+}
+
+/// Injection methods to try, in order
+pub fn injection_chain() -> &'static [InjectionMethod] {
+    if cfg!(windows) {
+        &[
+            InjectionMethod::Paste,
+            InjectionMethod::UiAutomation,
+            InjectionMethod::Typing,
+        ]
+    } else {
+        &[InjectionMethod::Paste, InjectionMethod::Typing]
+    }
+}
+
+/// Whether a method can be used while an input method editor is active
+///
+/// Typed characters are fed through the IME and get recomposed, so only
+/// methods that deliver finished text are allowed.
+pub fn is_ime_safe(method: InjectionMethod) -> bool {
+    method != InjectionMethod::Typing
+}
+
+/// Inject text into the focused application, falling back until one method works
+///
+/// The text must already be on the clipboard. Returns `ClipboardOnly` when
+/// every method failed, focus moved away mid-way, or a method whose input
+/// went out could not be confirmed.
+pub async fn inject_text(text: &str) -> InjectionReport {
+    let ime_active = is_ime_active();
+    if ime_active {
+        tracing::info!("Input method active, injecting without typing");
+    }
+
+    for &method in injection_chain() {
+        if ime_active && !is_ime_safe(method) {
+            continue;
+        }
+
+        let before = InjectionProbe::capture();
+        let attempt = match method {
+            InjectionMethod::Paste => {
+                // Commit any pending composition so it isn't merged with the paste
+                let _ime = if ime_active { suspend_ime() } else { None };
+                send_ctrl_v()
+            }
+            InjectionMethod::UiAutomation => insert_text_uia(text),
+            InjectionMethod::Typing => type_text(text, None),
+            _ => continue,
+        };
+
+        let sent = attempt.is_ok();
+        let verification = match attempt {
+            Ok(()) => {
+                tokio::time::sleep(VERIFY_DELAY).await;
+                before.verify(&InjectionProbe::capture(), method == InjectionMethod::Paste)
+            }
+            Err(e) => {
+                tracing::warn!("Injection via {:?} failed: {}", method, e);
+                Verification::Failed
+            }
+        };
+
+        if let Some(report) = attempt_outcome(method, sent, verification) {
+            return report;
+        }
+    }
+
+    InjectionReport {
+        method: InjectionMethod::ClipboardOnly,
+        verification: Verification::Failed,
+    }
+}
+
+/// Report to return after one injection attempt, or None to try the next method
+///
+/// Only a method that sent nothing falls back. Once its input went out, a
+/// paste the read-back missed may still land, and another method would
+/// insert the text twice; the text stays on the clipboard instead.
+fn attempt_outcome(
+    method: InjectionMethod,
+    sent: bool,
+    verification: Verification,
+) -> Option<InjectionReport> {
+    match verification {
+        Verification::Confirmed | Verification::Unverified => Some(InjectionReport {
+            method,
+            verification,
+        }),
+        Verification::FocusChanged => {
+            tracing::warn!("Focus changed during {:?}, not retrying", method);
+            Some(InjectionReport {
+                method: InjectionMethod::ClipboardOnly,
+                verification,
+            })
+        }
+        Verification::Failed if sent => {
+            tracing::warn!("{:?} could not be confirmed, not retrying", method);
+            Some(InjectionReport {
+                method: InjectionMethod::ClipboardOnly,
+                verification,
+            })
+        }
+        Verification::Failed => None,
+    }
+}
+
+/// Type text into the focused application without touching the clipboard
+///
+/// Typing runs off the async runtime since slow rates can take several
+/// seconds. The report's method is `Typing` even when verification failed.
+pub async fn type_into_focus(text: &str, chars_per_second: u32) -> InjectionReport {
+    let before = InjectionProbe::capture();
+    let owned = text.to_string();
+    let delay = typing_delay(chars_per_second);
+    let attempt = tokio::task::spawn_blocking(move || type_text(&owned, Some(delay)))
+        .await
+        .unwrap_or(Err(KeyboardError::SendFailed));
+
+    let verification = match attempt {
+        Ok(()) => {
+            tokio::time::sleep(VERIFY_DELAY).await;
+            before.verify(&InjectionProbe::capture(), false)
+        }
+        Err(e) => {
+            tracing::warn!("Typing failed: {}", e);
+            Verification::Failed
+        }
+    };
+
+    InjectionReport {
+        method: InjectionMethod::Typing,
+        verification,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn probe(window: isize, value: Option<&str>, sequence: u32) -> InjectionProbe {
+        InjectionProbe {
+            window: Some(window),
+            control_value: value.map(String::from),
+            clipboard_sequence: Some(sequence),
+        }
+    }
+
+    #[test]
+    fn test_verify_confirmed_when_value_changes() {
+        let before = probe(1, Some("Hello"), 7);
+        let after = probe(1, Some("Hello world"), 7);
+        assert_eq!(before.verify(&after, true), Verification::Confirmed);
+    }
+
+    #[test]
+    fn test_verify_failed_when_value_unchanged() {
+        let before = probe(1, Some("Hello"), 7);
+        assert_eq!(before.verify(&before.clone(), true), Verification::Failed);
+    }
+
+    #[test]
+    fn test_verify_focus_changed() {
+        let before = probe(1, Some("Hello"), 7);
+        let after = probe(2, Some("Other"), 7);
+        assert_eq!(before.verify(&after, true), Verification::FocusChanged);
+    }
+
+    #[test]
+    fn test_verify_clipboard_replaced() {
+        let before = probe(1, None, 7);
+        let after = probe(1, None, 8);
+        assert_eq!(before.verify(&after, true), Verification::Failed);
+        assert_eq!(before.verify(&after, false), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_verify_unverified_without_readback() {
+        let before = InjectionProbe::default();
+        assert_eq!(before.verify(&InjectionProbe::default(), true), Verification::Unverified);
+    }
+
+    #[test]
+    fn test_ime_safe_methods() {
+        assert!(is_ime_safe(InjectionMethod::Paste));
+        assert!(is_ime_safe(InjectionMethod::UiAutomation));
+        assert!(!is_ime_safe(InjectionMethod::Typing));
+    }
+
+    #[test]
+    fn test_verify_cjk_readback() {
+        let before = probe(1, Some("今日は"), 7);
+        let after = probe(1, Some("今日は良い天気です"), 7);
+        assert_eq!(before.verify(&after, true), Verification::Confirmed);
+
+        let unchanged = probe(1, Some("你好世界"), 7);
+        assert_eq!(unchanged.verify(&unchanged.clone(), true), Verification::Failed);
+    }
+
+    #[test]
+    fn test_no_fallback_after_input_was_sent() {
+        let report = attempt_outcome(InjectionMethod::Paste, true, Verification::Failed).unwrap();
+        assert_eq!(report.method, InjectionMethod::ClipboardOnly);
+        assert_eq!(report.verification, Verification::Failed);
+
+        // A method that sent nothing falls back to the next one
+        assert!(attempt_outcome(InjectionMethod::Paste, false, Verification::Failed).is_none());
+
+        let report = attempt_outcome(InjectionMethod::Paste, true, Verification::Unverified);
+        assert_eq!(report.unwrap().method, InjectionMethod::Paste);
+    }
+
+    #[tokio::test]
+    async fn test_poll_verification_waits_for_slow_paste() {
+        let before = probe(1, Some("Hello"), 7);
+        let mut reads = 0;
+        let slow = || {
+            reads += 1;
+            if reads < 3 {
+                probe(1, Some("Hello"), 7)
+            } else {
+                probe(1, Some("Hello world"), 7)
+            }
+        };
+        let verification = poll_verification(&before, slow, Duration::from_secs(5)).await;
+        assert_eq!(verification, Verification::Confirmed);
+
+        let ignored = || probe(1, Some("Hello"), 7);
+        let verification = poll_verification(&before, ignored, Duration::from_millis(300)).await;
+        assert_eq!(verification, Verification::Failed);
+    }
+
+    #[test]
+    fn test_delivery_route_order() {
+        use DeliveryRoute::*;
+
+        // Secure Input wins over every other condition
+        assert_eq!(delivery_route(true, false, true, true), SecureInput);
+        assert_eq!(delivery_route(true, true, false, false), SecureInput);
+
+        assert_eq!(delivery_route(false, false, true, true), Popup);
+        assert_eq!(delivery_route(false, true, true, false), Type);
+        assert_eq!(delivery_route(false, true, false, false), PrimarySelection);
+        assert_eq!(delivery_route(false, true, false, true), Paste);
+    }
+
+    #[test]
+    fn test_chain_ends_with_typing() {
+        let chain = injection_chain();
+        assert_eq!(chain.first(), Some(&InjectionMethod::Paste));
+        assert_eq!(chain.last(), Some(&InjectionMethod::Typing));
+    }
+}
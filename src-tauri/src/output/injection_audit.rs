@@ -0,0 +1,270 @@
+//! Injection Audit Log
+//!
+//! Compliance record of every text injection: when, into which app, how,
+//! and how many characters. The text itself is never stored. Kept apart
+//! from the history as append-only JSON lines; records older than the
+//! retention period are dropped.
+
+use super::{InjectionMethod, Verification};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// One injection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct InjectionRecord {
+    pub timestamp: String,
+    /// Process name of the target window
+    pub app: Option<String>,
+    /// How the text was delivered (None when delivery failed)
+    pub method: Option<InjectionMethod>,
+    pub verification: Option<Verification>,
+    /// Number of characters delivered
+    pub chars: usize,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl InjectionRecord {
+    /// Record of delivering `chars` characters to `app`
+    pub fn new(
+        app: Option<String>,
+        chars: usize,
+        result: &Result<(InjectionMethod, Option<Verification>), String>,
+    ) -> Self {
+        let (method, verification, error) = match result {
+            Ok((method, verification)) => (Some(*method), *verification, None),
+            Err(e) => (None, None, Some(e.clone())),
+        };
+        Self {
+            timestamp: crate::history::chrono_timestamp(),
+            app,
+            method,
+            verification,
+            chars,
+            success: result.is_ok(),
+            error,
+        }
+    }
+}
+
+/// Injection audit log file
+pub struct InjectionAudit {
+    path: PathBuf,
+    /// Serializes appends, pruning and export
+    lock: Mutex<()>,
+}
+
+impl InjectionAudit {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append a record, dropping records older than `retention_days`
+    pub fn append(&self, record: &InjectionRecord, retention_days: u32) {
+        let _guard = self.lock.lock();
+        let result = retention_cutoff(retention_days)
+            .map_or(Ok(0), |cutoff| self.prune_locked(&cutoff))
+            .and_then(|_| self.append_locked(record));
+        if let Err(e) = result {
+            tracing::warn!("Failed to write injection audit log: {}", e);
+        }
+    }
+
+    fn append_locked(&self, record: &InjectionRecord) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// Drop records older than `cutoff` (ISO 8601), returning how many were dropped
+    pub fn prune_before(&self, cutoff: &str) -> std::io::Result<usize> {
+        let _guard = self.lock.lock();
+        self.prune_locked(cutoff)
+    }
+
+    fn prune_locked(&self, cutoff: &str) -> std::io::Result<usize> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e),
+        };
+
+        // Records are in time order: nothing to do while the oldest is recent
+        let is_expired = |line: &str| {
+            !serde_json::from_str::<InjectionRecord>(line)
+                .is_ok_and(|record| record.timestamp.as_str() >= cutoff)
+        };
+        match content.lines().next() {
+            Some(first) if is_expired(first) => {}
+            _ => return Ok(0),
+        }
+
+        let kept: Vec<&str> = content.lines().filter(|line| !is_expired(line)).collect();
+        let dropped = content.lines().count() - kept.len();
+        let mut rewritten = kept.join("\n");
+        if !rewritten.is_empty() {
+            rewritten.push('\n');
+        }
+        std::fs::write(&self.path, rewritten)?;
+        Ok(dropped)
+    }
+
+    /// Most recent records, newest first
+    pub fn records(&self, limit: usize) -> Vec<InjectionRecord> {
+        let _guard = self.lock.lock();
+        let Ok(content) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        content
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect()
+    }
+
+    /// Write all records to `dest` as CSV, returning the number of records
+    pub fn export_csv(&self, dest: &Path) -> std::io::Result<usize> {
+        let mut records = self.records(usize::MAX);
+        records.reverse();
+
+        let mut csv = String::from("timestamp,app,method,verification,chars,success,error\n");
+        for record in &records {
+            let fields = [
+                record.timestamp.clone(),
+                record.app.clone().unwrap_or_default(),
+                record.method.map(label).unwrap_or_default(),
+                record.verification.map(label).unwrap_or_default(),
+                record.chars.to_string(),
+                record.success.to_string(),
+                record.error.clone().unwrap_or_default(),
+            ];
+            let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+            csv.push_str(&line.join(","));
+            csv.push('\n');
+        }
+        std::fs::write(dest, csv)?;
+        Ok(records.len())
+    }
+}
+
+/// Serialized name of a unit enum value
+fn label<T: Serialize>(value: T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Oldest timestamp kept with `retention_days` (None = keep everything)
+fn retention_cutoff(retention_days: u32) -> Option<String> {
+    let now = crate::vocabulary::unix_now();
+    let retention = retention_days as u64 * 86400;
+    (now > retention).then(|| crate::history::timestamp_from_unix(now - retention))
+}
+
+/// Get the global injection audit log
+pub fn injection_audit() -> &'static InjectionAudit {
+    static INJECTION_AUDIT: OnceLock<InjectionAudit> = OnceLock::new();
+    INJECTION_AUDIT.get_or_init(|| {
+        InjectionAudit::new(crate::config::data_dir().join("injection_audit.jsonl"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(timestamp: &str, app: &str) -> InjectionRecord {
+        InjectionRecord {
+            timestamp: timestamp.to_string(),
+            ..InjectionRecord::new(
+                Some(app.to_string()),
+                12,
+                &Ok((InjectionMethod::Paste, Some(Verification::Confirmed))),
+            )
+        }
+    }
+
+    #[test]
+    fn test_record_from_failure() {
+        let failed = InjectionRecord::new(None, 5, &Err("Clipboard error".to_string()));
+        assert!(!failed.success);
+        assert_eq!(failed.method, None);
+        assert_eq!(failed.error.as_deref(), Some("Clipboard error"));
+    }
+
+    #[test]
+    fn test_append_keeps_recent_records() {
+        let dir = TempDir::new().unwrap();
+        let audit = InjectionAudit::new(dir.path().join("audit.jsonl"));
+        audit.append(&record("2000-01-01T00:00:00Z", "old.exe"), 30);
+        // The old record is dropped when the next one is appended
+        audit.append(&record(&crate::history::chrono_timestamp(), "new.exe"), 30);
+
+        let records = audit.records(10);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].app.as_deref(), Some("new.exe"));
+    }
+
+    #[test]
+    fn test_prune_before() {
+        let dir = TempDir::new().unwrap();
+        let audit = InjectionAudit::new(dir.path().join("audit.jsonl"));
+        assert_eq!(audit.prune_before("2024-01-01T00:00:00Z").unwrap(), 0);
+
+        for timestamp in ["2023-12-01T00:00:00Z", "2023-12-31T23:59:59Z", "2024-01-02T00:00:00Z"] {
+            audit.append(&record(timestamp, "app.exe"), u32::MAX);
+        }
+        assert_eq!(audit.prune_before("2024-01-01T00:00:00Z").unwrap(), 2);
+        assert_eq!(audit.records(10).len(), 1);
+    }
+
+    #[test]
+    fn test_export_csv_escapes_fields() {
+        let dir = TempDir::new().unwrap();
+        let audit = InjectionAudit::new(dir.path().join("audit.jsonl"));
+        audit.append(&record("2024-01-02T00:00:00Z", "Word, Office"), u32::MAX);
+        audit.append(
+            &InjectionRecord::new(None, 3, &Err("said \"no\"".to_string())),
+            u32::MAX,
+        );
+
+        let dest = dir.path().join("export.csv");
+        assert_eq!(audit.export_csv(&dest).unwrap(), 2);
+        let csv = std::fs::read_to_string(dest).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "timestamp,app,method,verification,chars,success,error");
+        assert_eq!(
+            lines[1],
+            "2024-01-02T00:00:00Z,\"Word, Office\",paste,confirmed,12,true,"
+        );
+        assert!(lines[2].ends_with(",,,3,false,\"said \"\"no\"\"\""));
+    }
+}
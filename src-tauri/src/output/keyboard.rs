@@ -1,7 +1,11 @@
 //! Keyboard Injection
 //!
-//! Simulate keyboard input using Windows SendInput API.
+//! Simulate keyboard input using Windows SendInput API, or on Linux the
+//! external injection tool detected for the session (xdotool, wtype, ydotool).
+//! On Windows, UI Automation can also write into the focused control for apps
+//! that ignore synthetic input.
 
+use std::time::Duration;
 #[cfg(windows)]
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
 
@@ -13,6 +17,9 @@ pub enum KeyboardError {
 
     #[error("Platform not supported")]
     Unsupported,
+
+    #[error("UI Automation insertion failed: {0}")]
+    Automation(String),
 }
 
 /// Simulate Ctrl+V keypress
@@ -92,20 +99,66 @@ pub fn send_ctrl_v() -> Result<(), KeyboardError> {
     Ok(())
 }
 
-#[cfg(not(windows))]
+#[cfg(target_os = "linux")]
+pub fn send_ctrl_v() -> Result<(), KeyboardError> {
+    run_injector(|backend| {
+        backend.paste_args().iter().map(|s| s.to_string()).collect()
+    })
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
 pub fn send_ctrl_v() -> Result<(), KeyboardError> {
     Err(KeyboardError::Unsupported)
 }
 
+/// Run the first injection tool that succeeds
+///
+/// Several Wayland tools may be installed but only one works with the
+/// running compositor (wtype needs the virtual-keyboard protocol, ydotool
+/// its daemon), so each is tried in order of preference.
+#[cfg(target_os = "linux")]
+fn run_injector(
+    args: impl Fn(crate::utils::PasteBackend) -> Vec<String>,
+) -> Result<(), KeyboardError> {
+    let backends = &crate::utils::platform_capabilities().paste;
+    if backends.is_empty() {
+        return Err(KeyboardError::Unsupported);
+    }
+
+    for &backend in backends {
+        let Some(program) = backend.program() else {
+            continue;
+        };
+        match std::process::Command::new(program).args(args(backend)).status() {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => tracing::warn!("{} exited with {}", program, status),
+            Err(e) => tracing::warn!("Failed to run {}: {}", program, e),
+        }
+    }
+
+    Err(KeyboardError::SendFailed)
+}
+
+/// Pause between typed characters for a rate in characters per second
+pub fn typing_delay(chars_per_second: u32) -> Duration {
+    Duration::from_micros(1_000_000 / chars_per_second.max(1) as u64)
+}
+
 /// Type text character by character using Unicode input
+///
+/// `delay` is the pause between characters; None types as fast as the
+/// target reliably accepts.
 #[cfg(windows)]
-pub fn type_text(text: &str) -> Result<(), KeyboardError> {
+pub fn type_text(text: &str, delay: Option<Duration>) -> Result<(), KeyboardError> {
     use std::mem::size_of;
 
-    for c in text.chars() {
+    let delay = delay.unwrap_or(Duration::from_micros(500));
+
+    // Characters outside the BMP (rare CJK ideographs, emoji) are sent as
+    // their two UTF-16 surrogates, which Windows recombines
+    for unit in text.encode_utf16() {
         // SAFETY: SendInput with Unicode characters is safe because:
-        // - KEYEVENTF_UNICODE flag tells Windows to interpret wScan as a Unicode character
-        // - Rust chars are valid Unicode scalar values, safe to cast to u16 for BMP chars
+        // - KEYEVENTF_UNICODE flag tells Windows to interpret wScan as a UTF-16 code unit
         // - The INPUT array is properly initialized and stack-allocated
         // - We check return value and propagate errors on failure
         // - Small sleep between characters prevents input buffer overflow
@@ -117,7 +170,7 @@ pub fn type_text(text: &str) -> Result<(), KeyboardError> {
                     Anonymous: INPUT_0 {
                         ki: KEYBDINPUT {
                             wVk: VIRTUAL_KEY(0),
-                            wScan: c as u16,
+                            wScan: unit,
                             dwFlags: KEYEVENTF_UNICODE,
                             time: 0,
                             dwExtraInfo: 0,
@@ -130,7 +183,7 @@ pub fn type_text(text: &str) -> Result<(), KeyboardError> {
                     Anonymous: INPUT_0 {
                         ki: KEYBDINPUT {
                             wVk: VIRTUAL_KEY(0),
-                            wScan: c as u16,
+                            wScan: unit,
                             dwFlags: KEYEVENTF_UNICODE | KEYEVENTF_KEYUP,
                             time: 0,
                             dwExtraInfo: 0,
@@ -145,14 +198,229 @@ pub fn type_text(text: &str) -> Result<(), KeyboardError> {
             }
         }
 
-        // Small delay between characters
-        std::thread::sleep(std::time::Duration::from_micros(500));
+        // Delay between characters
+        std::thread::sleep(delay);
     }
 
     Ok(())
 }
 
+#[cfg(target_os = "linux")]
+pub fn type_text(text: &str, delay: Option<Duration>) -> Result<(), KeyboardError> {
+    let delay_ms = delay.map(|d| d.as_millis() as u64);
+    run_injector(|backend| backend.type_args(text, delay_ms))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn type_text(_text: &str, _delay: Option<Duration>) -> Result<(), KeyboardError> {
+    Err(KeyboardError::Unsupported)
+}
+
+/// Run a UI Automation query on a dedicated COM thread
+#[cfg(windows)]
+pub(super) fn with_automation<T: Send + 'static>(
+    f: impl FnOnce(&windows::Win32::UI::Accessibility::IUIAutomation) -> windows::core::Result<T>
+        + Send
+        + 'static,
+) -> Result<T, KeyboardError> {
+    use windows::Win32::System::Com::{
+        CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER,
+        COINIT_MULTITHREADED,
+    };
+    use windows::Win32::UI::Accessibility::{CUIAutomation, IUIAutomation};
+
+    std::thread::spawn(move || {
+        // SAFETY: COM is initialized for this short-lived thread only and
+        // uninitialized after every interface obtained here has been dropped.
+        unsafe {
+            CoInitializeEx(None, COINIT_MULTITHREADED).ok()?;
+            let result = CoCreateInstance::<_, IUIAutomation>(&CUIAutomation, None, CLSCTX_INPROC_SERVER)
+                .and_then(|automation| f(&automation));
+            CoUninitialize();
+            result
+        }
+    })
+    .join()
+    .map_err(|_| KeyboardError::Automation("automation thread panicked".to_string()))?
+    .map_err(|e| KeyboardError::Automation(e.message()))
+}
+
+/// Current value of the focused control, if it exposes one
+///
+/// Used to check whether a synthetic paste actually reached the control.
+#[cfg(windows)]
+pub fn focused_control_value() -> Option<String> {
+    use windows::Win32::UI::Accessibility::{IUIAutomationValuePattern, UIA_ValuePatternId};
+
+    with_automation(|automation| unsafe {
+        let element = automation.GetFocusedElement()?;
+        let pattern: IUIAutomationValuePattern = element.GetCurrentPatternAs(UIA_ValuePatternId)?;
+        Ok(pattern.CurrentValue()?.to_string())
+    })
+    .ok()
+}
+
 #[cfg(not(windows))]
-pub fn type_text(_text: &str) -> Result<(), KeyboardError> {
+pub fn focused_control_value() -> Option<String> {
+    None
+}
+
+/// Whether the focused control is a text field accepting input
+#[cfg(windows)]
+pub fn focused_control_is_editable() -> bool {
+    use windows::Win32::UI::Accessibility::{IUIAutomationValuePattern, UIA_ValuePatternId};
+
+    with_automation(|automation| unsafe {
+        let element = automation.GetFocusedElement()?;
+        let pattern: IUIAutomationValuePattern = element.GetCurrentPatternAs(UIA_ValuePatternId)?;
+        Ok(!pattern.CurrentIsReadOnly()?.as_bool())
+    })
+    .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+pub fn focused_control_is_editable() -> bool {
+    false
+}
+
+/// Insert text at the caret of the focused control through UI Automation
+///
+/// The caret and selection come from the control's TextPattern; the new
+/// content is written with its ValuePattern. Controls without a TextPattern
+/// get the text appended.
+#[cfg(windows)]
+pub fn insert_text_uia(text: &str) -> Result<(), KeyboardError> {
+    use windows::core::BSTR;
+    use windows::Win32::UI::Accessibility::{
+        IUIAutomationTextPattern, IUIAutomationValuePattern, TextPatternRangeEndpoint_End,
+        TextPatternRangeEndpoint_Start, UIA_TextPatternId, UIA_ValuePatternId,
+    };
+
+    let text = text.to_string();
+    with_automation(move |automation| unsafe {
+        let element = automation.GetFocusedElement()?;
+        let value_pattern: IUIAutomationValuePattern =
+            element.GetCurrentPatternAs(UIA_ValuePatternId)?;
+        if value_pattern.CurrentIsReadOnly()?.as_bool() {
+            return Err(windows::core::Error::new(
+                windows::Win32::Foundation::E_ACCESSDENIED,
+                "focused control is read-only",
+            ));
+        }
+
+        let current: Vec<u16> = value_pattern.CurrentValue()?.as_wide().to_vec();
+
+        // Locate the selection: text before it and its length, in UTF-16 units
+        let caret = element
+            .GetCurrentPatternAs::<IUIAutomationTextPattern>(UIA_TextPatternId)
+            .and_then(|text_pattern| {
+                let selection = text_pattern.GetSelection()?;
+                if selection.Length()? == 0 {
+                    return Ok(None);
+                }
+                let selected = selection.GetElement(0)?;
+                let prefix = text_pattern.DocumentRange()?;
+                prefix.MoveEndpointByRange(
+                    TextPatternRangeEndpoint_End,
+                    &selected,
+                    TextPatternRangeEndpoint_Start,
+                )?;
+                Ok(Some((
+                    prefix.GetText(-1)?.as_wide().to_vec(),
+                    selected.GetText(-1)?.len(),
+                )))
+            })
+            .ok()
+            .flatten();
+
+        let updated = match caret {
+            Some((prefix, selected_len)) => splice_utf16(&current, &prefix, selected_len, &text),
+            None => splice_utf16(&current, &current, 0, &text),
+        }
+        .ok_or_else(|| {
+            windows::core::Error::new(
+                windows::Win32::Foundation::E_UNEXPECTED,
+                "selection does not match control value",
+            )
+        })?;
+
+        value_pattern.SetValue(&BSTR::from(updated.as_str()))
+    })
+}
+
+#[cfg(not(windows))]
+pub fn insert_text_uia(_text: &str) -> Result<(), KeyboardError> {
     Err(KeyboardError::Unsupported)
 }
+
+/// Replace the selection in `value` with `insert`
+///
+/// `prefix` is the text before the selection and `selected_len` its length,
+/// both in UTF-16 units as reported by UI Automation. Returns None if the
+/// prefix doesn't match the value, so stale selections never clobber text.
+#[cfg_attr(not(windows), allow(dead_code))]
+pub(crate) fn splice_utf16(
+    value: &[u16],
+    prefix: &[u16],
+    selected_len: usize,
+    insert: &str,
+) -> Option<String> {
+    if !value.starts_with(prefix) || prefix.len() + selected_len > value.len() {
+        return None;
+    }
+
+    let mut updated = prefix.to_vec();
+    updated.extend(insert.encode_utf16());
+    updated.extend_from_slice(&value[prefix.len() + selected_len..]);
+    String::from_utf16(&updated).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    #[test]
+    fn test_typing_delay() {
+        assert_eq!(typing_delay(100), Duration::from_millis(10));
+        assert_eq!(typing_delay(0), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_splice_inserts_at_caret() {
+        let result = splice_utf16(&wide("Hello world"), &wide("Hello "), 0, "big ");
+        assert_eq!(result.as_deref(), Some("Hello big world"));
+    }
+
+    #[test]
+    fn test_splice_replaces_selection() {
+        let result = splice_utf16(&wide("Hello world"), &wide("Hello "), 5, "there");
+        assert_eq!(result.as_deref(), Some("Hello there"));
+    }
+
+    #[test]
+    fn test_splice_appends_without_caret() {
+        let value = wide("Café");
+        let result = splice_utf16(&value, &value, 0, " 👍");
+        assert_eq!(result.as_deref(), Some("Café 👍"));
+    }
+
+    #[test]
+    fn test_splice_cjk_text() {
+        // 𠮷 lies outside the BMP and takes two UTF-16 units
+        let result = splice_utf16(&wide("𠮷野家で"), &wide("𠮷野家"), 0, "牛丼を");
+        assert_eq!(result.as_deref(), Some("𠮷野家牛丼をで"));
+
+        let result = splice_utf16(&wide("我喜欢茶"), &wide("我喜欢"), 1, "咖啡");
+        assert_eq!(result.as_deref(), Some("我喜欢咖啡"));
+    }
+
+    #[test]
+    fn test_splice_rejects_stale_prefix() {
+        assert!(splice_utf16(&wide("Hello"), &wide("Bye"), 0, "x").is_none());
+        assert!(splice_utf16(&wide("Hello"), &wide("Hel"), 5, "x").is_none());
+    }
+}
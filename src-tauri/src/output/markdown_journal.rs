@@ -0,0 +1,206 @@
+//! Markdown Journal
+//!
+//! Appends transcriptions to daily Markdown notes (`YYYY-MM-DD.md`) in a
+//! folder such as an Obsidian vault's daily notes directory. Dates and times
+//! are local, so a late-evening dictation lands in that day's note.
+
+use super::SinkError;
+use chrono::{DateTime, TimeZone};
+use std::fmt::Display;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Local date (`YYYY-MM-DD`) and time (`HH:MM`) of an ISO 8601 timestamp
+pub fn local_date_time(timestamp: &str) -> (String, String) {
+    date_time_in(timestamp, &chrono::Local)
+}
+
+fn date_time_in<Tz: TimeZone>(timestamp: &str, tz: &Tz) -> (String, String)
+where
+    Tz::Offset: Display,
+{
+    match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => {
+            let time = time.with_timezone(tz);
+            (
+                time.format("%Y-%m-%d").to_string(),
+                time.format("%H:%M").to_string(),
+            )
+        }
+        Err(_) => (
+            timestamp.get(..10).unwrap_or(timestamp).to_string(),
+            timestamp.get(11..16).unwrap_or_default().to_string(),
+        ),
+    }
+}
+
+/// Daily note for `date` (`YYYY-MM-DD`)
+pub fn journal_path(folder: &str, date: &str) -> PathBuf {
+    Path::new(folder.trim()).join(format!("{}.md", date))
+}
+
+/// Tags as Markdown hashtags, e.g. `#dictation #work-log`
+pub fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|tag| {
+            tag.trim()
+                .trim_start_matches('#')
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+        })
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| format!("#{}", tag))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Render one journal entry
+///
+/// The template may use `{text}`, `{date}` (YYYY-MM-DD), `{time}` (HH:MM)
+/// and `{tags}`. Trailing spaces left by an empty placeholder are dropped.
+pub fn render_entry(template: &str, text: &str, date: &str, time: &str, tags: &[String]) -> String {
+    template
+        .replace("{date}", date)
+        .replace("{time}", time)
+        .replace("{tags}", &format_tags(tags))
+        .replace("{text}", text.trim())
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Append a transcription made at `timestamp` (ISO 8601) to the day's note,
+/// creating the note if needed
+///
+/// Returns the path of the note written to.
+pub fn append_to_journal(
+    folder: &str,
+    template: &str,
+    tags: &[String],
+    text: &str,
+    timestamp: &str,
+) -> Result<PathBuf, SinkError> {
+    let (date, time) = local_date_time(timestamp);
+    let path = journal_path(folder, &date);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    // Start on a new line when the note was last edited by hand
+    let needs_newline = std::fs::read(&path)
+        .map(|content| content.last().is_some_and(|&b| b != b'\n'))
+        .unwrap_or(false);
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    if needs_newline {
+        writeln!(file)?;
+    }
+    writeln!(file, "{}", render_entry(template, text, &date, &time, tags))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_journal_path() {
+        assert_eq!(
+            journal_path("vault/Daily ", "2024-01-15"),
+            PathBuf::from("vault/Daily/2024-01-15.md")
+        );
+    }
+
+    #[test]
+    fn test_date_time_in_local_zone() {
+        let utc = chrono::Utc;
+        assert_eq!(
+            date_time_in("2024-01-15T23:30:00Z", &utc),
+            ("2024-01-15".to_string(), "23:30".to_string())
+        );
+
+        // Late evening in UTC is already the next day further east
+        let east = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        assert_eq!(
+            date_time_in("2024-01-15T23:30:00Z", &east),
+            ("2024-01-16".to_string(), "01:30".to_string())
+        );
+
+        // Unparsable timestamps are used as written
+        assert_eq!(
+            date_time_in("2024-01-15 10:30", &east),
+            ("2024-01-15".to_string(), "10:30".to_string())
+        );
+    }
+
+    #[test]
+    fn test_render_entry() {
+        let tags = vec![
+            "dictation".to_string(),
+            "#work log".to_string(),
+            " ".to_string(),
+        ];
+        assert_eq!(
+            render_entry(
+                "- {time} {text} {tags}",
+                " Call Anna ",
+                "2024-01-15",
+                "10:30",
+                &tags
+            ),
+            "- 10:30 Call Anna #dictation #work-log"
+        );
+        assert_eq!(
+            render_entry(
+                "## {date} {time} {tags}\n{text}",
+                "Hello",
+                "2024-01-15",
+                "10:30",
+                &[]
+            ),
+            "## 2024-01-15 10:30\nHello"
+        );
+    }
+
+    #[test]
+    fn test_append_to_journal() {
+        let dir = TempDir::new().unwrap();
+        let folder = dir.path().join("Daily");
+        let folder = folder.to_str().unwrap();
+        let (date, first) = local_date_time("2024-01-15T10:30:00Z");
+        let (_, second) = local_date_time("2024-01-15T10:40:00Z");
+        let note = dir.path().join(format!("Daily/{}.md", date));
+
+        append_to_journal(
+            folder,
+            "- {time} {text}",
+            &[],
+            "first",
+            "2024-01-15T10:30:00Z",
+        )
+        .unwrap();
+        std::fs::write(&note, format!("- {} first\nEdited by hand", first)).unwrap();
+        let path = append_to_journal(
+            folder,
+            "- {time} {text}",
+            &[],
+            "second",
+            "2024-01-15T10:40:00Z",
+        )
+        .unwrap();
+
+        assert_eq!(path, note);
+        assert_eq!(
+            std::fs::read_to_string(&note).unwrap(),
+            format!("- {} first\nEdited by hand\n- {} second\n", first, second)
+        );
+    }
+}
@@ -2,10 +2,40 @@
 //!
 //! Text injection and display.
 
+mod casing;
 mod clipboard;
+mod dictation_box;
 mod focus;
+mod ime;
+mod injection;
+mod injection_audit;
 mod keyboard;
+mod markdown_journal;
+mod rich_text;
+mod screenshot;
+mod secure_input;
+mod sinks;
+mod spacing;
+mod subtitles;
+mod typography;
+mod viewer;
+mod voice_commands;
 
+pub use casing::*;
 pub use clipboard::*;
+pub use dictation_box::*;
 pub use focus::*;
+pub use ime::*;
+pub use injection::*;
+pub use injection_audit::*;
 pub use keyboard::*;
+pub use markdown_journal::*;
+pub use rich_text::*;
+pub use screenshot::*;
+pub use secure_input::*;
+pub use sinks::*;
+pub use spacing::*;
+pub use subtitles::*;
+pub use typography::*;
+pub use viewer::*;
+pub use voice_commands::*;
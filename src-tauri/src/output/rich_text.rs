@@ -0,0 +1,177 @@
+//! Rich Text Rendering
+//!
+//! Minimal markdown to HTML conversion for clipboard payloads, so pasting
+//! into word processors keeps headings, lists and emphasis. Covers the
+//! subset dictation produces: headings, bullet and numbered lists,
+//! paragraphs, bold, italic and inline code.
+
+/// Render markdown as an HTML fragment
+pub fn markdown_to_html(markdown: &str) -> String {
+    let mut html = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut list: Option<&'static str> = None;
+
+    for line in markdown.lines() {
+        let trimmed = line.trim();
+
+        let item = trimmed
+            .strip_prefix("- ")
+            .or_else(|| trimmed.strip_prefix("* "))
+            .map(|rest| ("ul", rest))
+            .or_else(|| ordered_item(trimmed).map(|rest| ("ol", rest)));
+
+        if trimmed.is_empty() || item.is_some() || heading(trimmed).is_some() {
+            flush_paragraph(&mut html, &mut paragraph);
+        }
+        if item.map(|(tag, _)| tag) != list {
+            if let Some(tag) = list.take() {
+                html.push_str(&format!("</{}>", tag));
+            }
+        }
+
+        if let Some((tag, content)) = item {
+            if list.is_none() {
+                html.push_str(&format!("<{}>", tag));
+                list = Some(tag);
+            }
+            html.push_str(&format!("<li>{}</li>", render_inline(content)));
+        } else if let Some((level, content)) = heading(trimmed) {
+            html.push_str(&format!("<h{0}>{1}</h{0}>", level, render_inline(content)));
+        } else if !trimmed.is_empty() {
+            paragraph.push(trimmed);
+        }
+    }
+
+    flush_paragraph(&mut html, &mut paragraph);
+    if let Some(tag) = list {
+        html.push_str(&format!("</{}>", tag));
+    }
+    html
+}
+
+fn flush_paragraph(html: &mut String, paragraph: &mut Vec<&str>) {
+    if paragraph.is_empty() {
+        return;
+    }
+    let lines: Vec<String> = paragraph.iter().map(|l| render_inline(l)).collect();
+    html.push_str(&format!("<p>{}</p>", lines.join("<br>")));
+    paragraph.clear();
+}
+
+/// `# Title` -> (1, "Title"), up to level 6
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if (1..=6).contains(&level) {
+        line[level..].strip_prefix(' ').map(|rest| (level, rest.trim()))
+    } else {
+        None
+    }
+}
+
+/// `12. Item` -> "Item"
+fn ordered_item(line: &str) -> Option<&str> {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        return None;
+    }
+    line[digits..].strip_prefix(". ")
+}
+
+/// Escape HTML and render `**bold**`, `*italic*` and `` `code` ``
+///
+/// Underscores are left alone so identifiers like `snake_case_name` survive.
+fn render_inline(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+        let span = if rest.starts_with(&['`']) {
+            delimited(rest, "`").map(|(inner, len)| (format!("<code>{}</code>", escape(&inner)), len))
+        } else if rest.starts_with(&['*', '*']) {
+            delimited(rest, "**")
+                .map(|(inner, len)| (format!("<strong>{}</strong>", render_inline(&inner)), len))
+        } else if rest.starts_with(&['*']) {
+            delimited(rest, "*")
+                .map(|(inner, len)| (format!("<em>{}</em>", render_inline(&inner)), len))
+        } else {
+            None
+        };
+
+        match span {
+            Some((rendered, len)) => {
+                out.push_str(&rendered);
+                i += len;
+            }
+            None => {
+                out.push_str(&escape(&chars[i].to_string()));
+                i += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// Content between a leading delimiter and its closing match, and the total length consumed
+fn delimited(chars: &[char], delimiter: &str) -> Option<(String, usize)> {
+    let delimiter: Vec<char> = delimiter.chars().collect();
+    let start = delimiter.len();
+    (start..chars.len())
+        .find(|&i| i > start && chars[i..].starts_with(&delimiter))
+        .map(|end| (chars[start..end].iter().collect(), end + delimiter.len()))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_paragraphs() {
+        assert_eq!(markdown_to_html("Hello world"), "<p>Hello world</p>");
+        assert_eq!(
+            markdown_to_html("First line\nsecond line\n\nNext"),
+            "<p>First line<br>second line</p><p>Next</p>"
+        );
+    }
+
+    #[test]
+    fn test_headings_and_lists() {
+        let html = markdown_to_html("# Notes\n- one\n- two\n1. first\n2. second");
+        assert_eq!(
+            html,
+            "<h1>Notes</h1><ul><li>one</li><li>two</li></ul><ol><li>first</li><li>second</li></ol>"
+        );
+    }
+
+    #[test]
+    fn test_inline_formatting() {
+        assert_eq!(
+            markdown_to_html("**Bold**, *italic* and `a < b`"),
+            "<p><strong>Bold</strong>, <em>italic</em> and <code>a &lt; b</code></p>"
+        );
+    }
+
+    #[test]
+    fn test_unmatched_markers_are_literal() {
+        assert_eq!(markdown_to_html("5 * 3 = 15"), "<p>5 * 3 = 15</p>");
+        assert_eq!(markdown_to_html("#hashtag"), "<p>#hashtag</p>");
+        assert_eq!(markdown_to_html("snake_case_name"), "<p>snake_case_name</p>");
+    }
+
+    #[test]
+    fn test_html_is_escaped() {
+        assert_eq!(
+            markdown_to_html("<script>\"x\" & y</script>"),
+            "<p>&lt;script&gt;&quot;x&quot; &amp; y&lt;/script&gt;</p>"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! Target Window Screenshots
+//!
+//! Thumbnail of the window receiving a transcription, attached to its
+//! history entry as a reminder of the context. Opt-in, and never taken of
+//! GigaWhisper itself, private browser windows, excluded apps (password
+//! managers by default) or while Secure Input is active.
+
+use super::ActiveWindow;
+use std::path::Path;
+
+/// Maximum thumbnail width in pixels
+pub const THUMBNAIL_MAX_WIDTH: u32 = 480;
+
+/// Maximum thumbnail height in pixels
+pub const THUMBNAIL_MAX_HEIGHT: u32 = 320;
+
+/// Window title fragments marking private browsing
+const PRIVATE_TITLE_MARKERS: &[&str] = &["inprivate", "incognito", "private browsing"];
+
+/// Whether privacy rules forbid a screenshot of `window`
+pub fn is_screenshot_excluded(window: &ActiveWindow, excluded_apps: &[String]) -> bool {
+    let title = window.title.to_lowercase();
+    let process = window.process_name.to_lowercase();
+
+    window.is_gigawhisper()
+        || PRIVATE_TITLE_MARKERS.iter().any(|marker| title.contains(marker))
+        || excluded_apps
+            .iter()
+            .map(|app| app.trim().to_lowercase())
+            .any(|app| !app.is_empty() && process.contains(&app))
+}
+
+/// Size of a thumbnail of a `width` x `height` image, keeping the aspect ratio
+pub fn thumbnail_size(width: u32, height: u32) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+    let scale = (THUMBNAIL_MAX_WIDTH as f64 / width as f64)
+        .min(THUMBNAIL_MAX_HEIGHT as f64 / height as f64)
+        .min(1.0);
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// Encode top-down BGRA pixels as a 24-bit BMP file
+pub fn encode_bmp(bgra: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let row_size = (width as usize * 3).div_ceil(4) * 4;
+    let data_size = row_size * height as usize;
+    let file_size = 54 + data_size;
+
+    let mut bmp = Vec::with_capacity(file_size);
+    // File header
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(file_size as u32).to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes());
+    bmp.extend_from_slice(&54u32.to_le_bytes());
+    // Info header
+    bmp.extend_from_slice(&40u32.to_le_bytes());
+    bmp.extend_from_slice(&(width as i32).to_le_bytes());
+    bmp.extend_from_slice(&(height as i32).to_le_bytes());
+    bmp.extend_from_slice(&1u16.to_le_bytes());
+    bmp.extend_from_slice(&24u16.to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes()); // no compression
+    bmp.extend_from_slice(&(data_size as u32).to_le_bytes());
+    bmp.extend_from_slice(&2835i32.to_le_bytes()); // 72 DPI
+    bmp.extend_from_slice(&2835i32.to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes());
+    bmp.extend_from_slice(&0u32.to_le_bytes());
+
+    // Rows are stored bottom-up
+    let stride = width as usize * 4;
+    for row in bgra.chunks(stride).take(height as usize).rev() {
+        let start = bmp.len();
+        for pixel in row.chunks(4) {
+            bmp.extend_from_slice(&pixel[..3]);
+        }
+        bmp.resize(start + row_size, 0);
+    }
+    bmp
+}
+
+/// Save a thumbnail of `window` to `path` as a BMP file
+#[cfg(windows)]
+pub fn capture_window_thumbnail(window: &ActiveWindow, path: &Path) -> Result<(), String> {
+    use windows::Win32::Foundation::{HWND, RECT};
+    use windows::Win32::Graphics::Gdi::*;
+    use windows::Win32::UI::WindowsAndMessaging::GetWindowRect;
+
+    let hwnd = HWND(window.id as *mut std::ffi::c_void);
+
+    // SAFETY: GetWindowRect fails for windows that no longer exist. Every DC
+    // and bitmap created here is released before returning, and GetDIBits
+    // writes into a buffer sized for the requested 32-bit top-down bitmap.
+    let pixels = unsafe {
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).map_err(|e| e.to_string())?;
+        let (width, height) = (rect.right - rect.left, rect.bottom - rect.top);
+        if width <= 0 || height <= 0 {
+            return Err("Window has no visible area".to_string());
+        }
+        let (thumb_width, thumb_height) = thumbnail_size(width as u32, height as u32);
+
+        let screen = GetDC(HWND::default());
+        let memory = CreateCompatibleDC(screen);
+        let bitmap = CreateCompatibleBitmap(screen, thumb_width as i32, thumb_height as i32);
+        let previous = SelectObject(memory, bitmap);
+
+        SetStretchBltMode(memory, HALFTONE);
+        let copied = StretchBlt(
+            memory,
+            0,
+            0,
+            thumb_width as i32,
+            thumb_height as i32,
+            screen,
+            rect.left,
+            rect.top,
+            width,
+            height,
+            SRCCOPY,
+        )
+        .as_bool();
+
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: thumb_width as i32,
+                biHeight: -(thumb_height as i32), // top-down
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut buffer = vec![0u8; thumb_width as usize * thumb_height as usize * 4];
+        let rows = GetDIBits(
+            memory,
+            bitmap,
+            0,
+            thumb_height,
+            Some(buffer.as_mut_ptr() as *mut std::ffi::c_void),
+            &mut info,
+            DIB_RGB_COLORS,
+        );
+
+        SelectObject(memory, previous);
+        let _ = DeleteObject(bitmap);
+        let _ = DeleteDC(memory);
+        ReleaseDC(HWND::default(), screen);
+
+        if !copied || rows != thumb_height as i32 {
+            return Err("Failed to copy window contents".to_string());
+        }
+        (buffer, thumb_width, thumb_height)
+    };
+
+    let (buffer, width, height) = pixels;
+    std::fs::write(path, encode_bmp(&buffer, width, height)).map_err(|e| e.to_string())
+}
+
+#[cfg(not(windows))]
+pub fn capture_window_thumbnail(_window: &ActiveWindow, _path: &Path) -> Result<(), String> {
+    Err("Window screenshots are not supported on this platform".to_string())
+}
+
+/// Capture the focused window into `dir` if privacy rules allow it
+///
+/// Returns the path of the saved thumbnail.
+pub fn capture_focused_window(dir: &Path, excluded_apps: &[String]) -> Option<String> {
+    if super::is_secure_input_enabled() {
+        tracing::debug!("Secure Input active, skipping screenshot");
+        return None;
+    }
+    let window = super::get_active_window()?;
+    if is_screenshot_excluded(&window, excluded_apps) {
+        tracing::debug!("Screenshot of '{}' excluded", window.process_name);
+        return None;
+    }
+
+    if let Err(e) = std::fs::create_dir_all(dir) {
+        tracing::warn!("Failed to create screenshots directory: {}", e);
+        return None;
+    }
+    let path = dir.join(format!("{}.bmp", uuid::Uuid::new_v4()));
+    match capture_window_thumbnail(&window, &path) {
+        Ok(()) => Some(path.to_string_lossy().to_string()),
+        Err(e) => {
+            tracing::warn!("Failed to capture window screenshot: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(title: &str, process_name: &str) -> ActiveWindow {
+        ActiveWindow {
+            id: 1,
+            title: title.to_string(),
+            process_name: process_name.to_string(),
+            has_text_input: true,
+        }
+    }
+
+    #[test]
+    fn test_privacy_exclusions() {
+        let excluded = vec!["KeePass".to_string(), " ".to_string()];
+        assert!(!is_screenshot_excluded(&window("Notes - Word", "WINWORD.EXE"), &excluded));
+        assert!(is_screenshot_excluded(&window("Vault", "KeePassXC.exe"), &excluded));
+        assert!(is_screenshot_excluded(
+            &window("Mail - InPrivate - Microsoft Edge", "msedge.exe"),
+            &excluded
+        ));
+        assert!(is_screenshot_excluded(&window("Settings", "gigawhisper.exe"), &excluded));
+    }
+
+    #[test]
+    fn test_thumbnail_size_keeps_aspect_ratio() {
+        assert_eq!(thumbnail_size(1920, 1080), (480, 270));
+        assert_eq!(thumbnail_size(800, 1600), (160, 320));
+        // Small windows are not enlarged
+        assert_eq!(thumbnail_size(300, 200), (300, 200));
+        assert_eq!(thumbnail_size(0, 100), (0, 0));
+    }
+
+    #[test]
+    fn test_encode_bmp_layout() {
+        // 2x2 image: blue, green / red, white (BGRA, top-down)
+        let pixels = [
+            255, 0, 0, 0, 0, 255, 0, 0, //
+            0, 0, 255, 0, 255, 255, 255, 0,
+        ];
+        let bmp = encode_bmp(&pixels, 2, 2);
+
+        // 6 bytes of pixels per row, padded to 8
+        assert_eq!(bmp.len(), 54 + 16);
+        assert_eq!(&bmp[..2], b"BM");
+        assert_eq!(u32::from_le_bytes(bmp[2..6].try_into().unwrap()), 70);
+        // Bottom row first
+        assert_eq!(&bmp[54..62], &[0, 0, 255, 255, 255, 255, 0, 0]);
+        assert_eq!(&bmp[62..70], &[255, 0, 0, 0, 255, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,121 @@
+//! Secure Input Detection
+//!
+//! On macOS, an app can enable Secure Input (typically while a password
+//! field has focus), which silently drops synthetic keystrokes and pastes
+//! system-wide. Detect it so injection can be skipped instead of failing
+//! unnoticed.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Process holding Secure Input
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct SecureInputHolder {
+    pub pid: u32,
+    /// Executable name, if it could be resolved
+    pub process_name: Option<String>,
+}
+
+impl SecureInputHolder {
+    /// Name shown to the user
+    pub fn display_name(&self) -> String {
+        self.process_name
+            .clone()
+            .unwrap_or_else(|| format!("process {}", self.pid))
+    }
+}
+
+#[cfg(target_os = "macos")]
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn IsSecureEventInputEnabled() -> u8;
+}
+
+/// Check whether Secure Input is currently enabled
+#[cfg(target_os = "macos")]
+pub fn is_secure_input_enabled() -> bool {
+    // SAFETY: IsSecureEventInputEnabled takes no arguments and only reads
+    // the global event input state; it is safe to call from any thread.
+    unsafe { IsSecureEventInputEnabled() != 0 }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_secure_input_enabled() -> bool {
+    false
+}
+
+/// Find the process holding Secure Input
+///
+/// Read from the console session registry (`ioreg`), which records the PID
+/// of the app that enabled it.
+#[cfg(target_os = "macos")]
+pub fn secure_input_holder() -> Option<SecureInputHolder> {
+    use std::process::Command;
+
+    let output = Command::new("ioreg")
+        .args(["-l", "-w", "0", "-d", "1"])
+        .output()
+        .ok()?;
+    let pid = parse_secure_input_pid(&String::from_utf8_lossy(&output.stdout))?;
+
+    let process_name = Command::new("ps")
+        .args(["-p", &pid.to_string(), "-o", "comm="])
+        .output()
+        .ok()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .and_then(|path| {
+            std::path::Path::new(&path)
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+        })
+        .filter(|name| !name.is_empty());
+
+    Some(SecureInputHolder { pid, process_name })
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn secure_input_holder() -> Option<SecureInputHolder> {
+    None
+}
+
+/// Extract `kCGSSessionSecureInputPID` from `ioreg` output
+pub fn parse_secure_input_pid(ioreg_output: &str) -> Option<u32> {
+    const KEY: &str = "\"kCGSSessionSecureInputPID\"=";
+
+    ioreg_output.match_indices(KEY).find_map(|(index, _)| {
+        let digits: String = ioreg_output[index + KEY.len()..]
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect();
+        digits.parse().ok().filter(|&pid| pid != 0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_secure_input_pid() {
+        let output = r#"  |   "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,"kCGSSessionSecureInputPID"=412,"kCGSSessionUserIDKey"=501})"#;
+        assert_eq!(parse_secure_input_pid(output), Some(412));
+
+        let output = r#"  |   "IOConsoleUsers" = ({"kCGSSessionOnConsoleKey"=Yes,"kCGSSessionUserIDKey"=501})"#;
+        assert_eq!(parse_secure_input_pid(output), None);
+    }
+
+    #[test]
+    fn test_holder_display_name() {
+        let holder = SecureInputHolder {
+            pid: 42,
+            process_name: Some("1Password".to_string()),
+        };
+        assert_eq!(holder.display_name(), "1Password");
+
+        let holder = SecureInputHolder {
+            pid: 42,
+            process_name: None,
+        };
+        assert_eq!(holder.display_name(), "process 42");
+    }
+}
@@ -0,0 +1,242 @@
+//! Output Sinks
+//!
+//! Non-interactive destinations for transcriptions: text files, daily
+//! Markdown notes (see `markdown_journal`) and webhooks. Paste and clipboard sinks reuse the existing output path.
+
+use super::InjectionMethod;
+use crate::transcription::{parse_retry_after, RetryPolicy};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Webhook request timeout
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sink errors
+#[derive(Debug, thiserror::Error)]
+pub enum SinkError {
+    #[error("Failed to write file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Webhook request failed: {0}")]
+    Webhook(String),
+}
+
+/// Outcome of delivering a transcription to one sink
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct SinkResult {
+    /// Sink label (see `OutputSink::label`)
+    pub sink: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// How the text was inserted (paste sink only)
+    pub method: Option<InjectionMethod>,
+}
+
+impl SinkResult {
+    /// Build a result from a sink label and its delivery outcome
+    pub fn new(sink: String, result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self {
+                sink,
+                success: true,
+                error: None,
+                method: None,
+            },
+            Err(e) => Self {
+                sink,
+                success: false,
+                error: Some(e),
+                method: None,
+            },
+        }
+    }
+
+    /// Attach the injection method used by the sink
+    pub fn with_method(mut self, method: Option<InjectionMethod>) -> Self {
+        self.method = method;
+        self
+    }
+}
+
+/// Transcription delivered to the sinks; posted as is to webhooks
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SinkPayload {
+    pub text: String,
+    /// ISO 8601 time of delivery
+    pub timestamp: String,
+    /// Length of the recording in milliseconds
+    pub duration_ms: Option<u64>,
+    /// Detected or configured language code
+    pub language: Option<String>,
+}
+
+/// Expand `{date}` in a path template using an ISO 8601 timestamp
+pub fn expand_path_template(template: &str, timestamp: &str) -> PathBuf {
+    let date = timestamp.get(..10).unwrap_or(timestamp);
+    PathBuf::from(template.replace("{date}", date))
+}
+
+/// Timestamp line written before a transcription, e.g. `[2024-01-15 10:30:00 UTC]`
+pub fn timestamp_header(timestamp: &str) -> String {
+    format!("[{} UTC]", timestamp.trim_end_matches('Z').replacen('T', " ", 1))
+}
+
+/// Append a transcription as a new line to a file, creating it if needed
+///
+/// With `header`, a timestamp line precedes the text.
+pub fn append_to_file(
+    template: &str,
+    text: &str,
+    timestamp: &str,
+    header: bool,
+) -> Result<(), SinkError> {
+    let path = expand_path_template(template, timestamp);
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+    if header {
+        writeln!(file, "{}", timestamp_header(timestamp))?;
+    }
+    writeln!(file, "{}", text)?;
+    Ok(())
+}
+
+/// Whether a webhook response status may succeed when sent again
+pub fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// POST a transcription to a webhook as JSON
+///
+/// `auth` is a header name and value sent with each request. Network
+/// errors, rate limits and server errors are retried following `retry`.
+pub async fn post_webhook(
+    url: &str,
+    payload: &SinkPayload,
+    auth: Option<(&str, &str)>,
+    retry: &RetryPolicy,
+) -> Result<(), SinkError> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| SinkError::Webhook(e.to_string()))?;
+
+    let mut attempt = 0;
+    loop {
+        let mut request = client.post(url).json(payload);
+        if let Some((name, value)) = auth {
+            request = request.header(name, value);
+        }
+
+        let (error, retry_after) = match request.send().await {
+            Ok(response) if response.status().is_success() => return Ok(()),
+            Ok(response) => {
+                let status = response.status();
+                if !is_retryable_status(status) {
+                    return Err(SinkError::Webhook(format!("HTTP {}", status)));
+                }
+                (format!("HTTP {}", status), parse_retry_after(response.headers()))
+            }
+            Err(e) => (e.to_string(), None),
+        };
+
+        let Some(delay) = retry.delay(attempt, retry_after) else {
+            return Err(SinkError::Webhook(error));
+        };
+        tracing::warn!("Webhook delivery failed ({}), retrying in {:?}", error, delay);
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_expand_path_template() {
+        let path = expand_path_template("notes/{date}.md", "2024-01-15T10:30:00Z");
+        assert_eq!(path, PathBuf::from("notes/2024-01-15.md"));
+
+        let path = expand_path_template("notes/inbox.md", "2024-01-15T10:30:00Z");
+        assert_eq!(path, PathBuf::from("notes/inbox.md"));
+    }
+
+    #[test]
+    fn test_append_to_file() {
+        let dir = TempDir::new().unwrap();
+        let template = dir.path().join("daily").join("{date}.md");
+        let template = template.to_str().unwrap();
+
+        append_to_file(template, "first", "2024-01-15T10:30:00Z", false).unwrap();
+        append_to_file(template, "second", "2024-01-15T11:00:00Z", false).unwrap();
+
+        let content = std::fs::read_to_string(dir.path().join("daily/2024-01-15.md")).unwrap();
+        assert_eq!(content, "first\nsecond\n");
+    }
+
+    #[test]
+    fn test_append_to_file_with_header() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        let path = path.to_str().unwrap();
+
+        append_to_file(path, "first", "2024-01-15T10:30:00Z", true).unwrap();
+        append_to_file(path, "second", "2024-01-15T11:00:05Z", true).unwrap();
+
+        let content = std::fs::read_to_string(path).unwrap();
+        assert_eq!(
+            content,
+            "[2024-01-15 10:30:00 UTC]\nfirst\n[2024-01-15 11:00:05 UTC]\nsecond\n"
+        );
+    }
+
+    #[test]
+    fn test_sink_payload_json() {
+        let payload = SinkPayload {
+            text: "Hello".to_string(),
+            timestamp: "2024-01-15T10:30:00Z".to_string(),
+            duration_ms: Some(1500),
+            language: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            serde_json::json!({
+                "text": "Hello",
+                "timestamp": "2024-01-15T10:30:00Z",
+                "duration_ms": 1500,
+                "language": null,
+            })
+        );
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        assert!(is_retryable_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(reqwest::StatusCode::BAD_GATEWAY));
+        assert!(!is_retryable_status(reqwest::StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(reqwest::StatusCode::NOT_FOUND));
+    }
+
+    #[test]
+    fn test_sink_result_new() {
+        let ok = SinkResult::new("paste".to_string(), Ok(()));
+        assert!(ok.success);
+        assert!(ok.error.is_none());
+
+        let err = SinkResult::new("webhook:x".to_string(), Err("timeout".to_string()));
+        assert!(!err.success);
+        assert_eq!(err.error.as_deref(), Some("timeout"));
+    }
+}
@@ -0,0 +1,331 @@
+//! Smart Spacing
+//!
+//! Decide whether injected text needs a leading space based on the
+//! character before the caret: "word" + "next" needs one, "(" + "next" or
+//! "word" + "," doesn't. The character comes from UI Automation (Windows)
+//! or the Accessibility API (macOS); otherwise the end of the previous
+//! injection into the same window is used. When the target window is
+//! unknown, no previous injection is assumed to be in it.
+
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+
+/// How long the previous injection is trusted as the caret context
+const INJECTION_MEMORY: Duration = Duration::from_secs(15);
+
+/// Characters after which no space is inserted
+const OPENING: &[char] = &['(', '[', '{', '«', '“', '‘', '¿', '¡', '/', '@', '#', '$'];
+
+/// Characters that attach to the preceding text
+const ATTACHING: &[char] = &[
+    '.', ',', ';', ':', '!', '?', ')', ']', '}', '»', '”', '’', '%', '…',
+];
+
+/// Stand-in for "caret at the start of the field"
+pub const FIELD_START: char = '\n';
+
+/// End of the last injected text
+struct LastInjection {
+    window: Option<isize>,
+    last_char: char,
+    at: Instant,
+}
+
+static LAST_INJECTION: Mutex<Option<LastInjection>> = Mutex::new(None);
+
+/// Whether a space is needed between `preceding` and `first`
+pub fn needs_leading_space(preceding: char, first: char) -> bool {
+    if preceding.is_whitespace() || OPENING.contains(&preceding) {
+        return false;
+    }
+    if first.is_whitespace() || ATTACHING.contains(&first) {
+        return false;
+    }
+    // CJK scripts don't separate words with spaces
+    !(is_cjk(preceding) || is_cjk(first))
+}
+
+/// Adjust the leading space of `text` for the character before the caret
+///
+/// Unknown context leaves the text untouched.
+pub fn apply_smart_spacing(text: &str, preceding: Option<char>) -> String {
+    let Some(preceding) = preceding else {
+        return text.to_string();
+    };
+
+    let trimmed = text.trim_start();
+    match trimmed.chars().next() {
+        Some(first) if needs_leading_space(preceding, first) => format!(" {}", trimmed),
+        Some(_) => trimmed.to_string(),
+        None => text.to_string(),
+    }
+}
+
+/// Character before the caret in the focused control, if it can be determined
+///
+/// Returns `FIELD_START` when the caret is at the beginning of the field.
+pub fn char_before_caret(window: Option<isize>) -> Option<char> {
+    query_char_before_caret().or_else(|| recent_injection_char(window, Instant::now()))
+}
+
+/// Remember the end of injected text for the next spacing decision
+pub fn remember_injection(window: Option<isize>, text: &str) {
+    if let Some(last_char) = text.chars().last() {
+        *LAST_INJECTION.lock() = Some(LastInjection {
+            window,
+            last_char,
+            at: Instant::now(),
+        });
+    }
+}
+
+fn recent_injection_char(window: Option<isize>, now: Instant) -> Option<char> {
+    LAST_INJECTION
+        .lock()
+        .as_ref()
+        .filter(|last| {
+            window.is_some()
+                && last.window == window
+                && now.duration_since(last.at) < INJECTION_MEMORY
+        })
+        .map(|last| last.last_char)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3000..=0x303F   // CJK punctuation
+        | 0x3040..=0x30FF // Hiragana, Katakana
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul syllables
+        | 0xFF00..=0xFFEF // Full-width forms
+        | 0x20000..=0x2FFFF // CJK Extensions B+
+    )
+}
+
+#[cfg(windows)]
+fn query_char_before_caret() -> Option<char> {
+    use windows::Win32::UI::Accessibility::{
+        IUIAutomationTextPattern, TextPatternRangeEndpoint_End, TextPatternRangeEndpoint_Start,
+        TextUnit_Character, UIA_TextPatternId,
+    };
+
+    super::keyboard::with_automation(|automation| unsafe {
+        let element = automation.GetFocusedElement()?;
+        let pattern: IUIAutomationTextPattern = element.GetCurrentPatternAs(UIA_TextPatternId)?;
+        let selection = pattern.GetSelection()?;
+        if selection.Length()? == 0 {
+            return Ok(None);
+        }
+
+        // Collapse to the selection start, then extend one character back
+        let range = selection.GetElement(0)?;
+        range.MoveEndpointByRange(
+            TextPatternRangeEndpoint_End,
+            &range,
+            TextPatternRangeEndpoint_Start,
+        )?;
+        let moved =
+            range.MoveEndpointByUnit(TextPatternRangeEndpoint_Start, TextUnit_Character, -1)?;
+        if moved == 0 {
+            return Ok(Some(FIELD_START));
+        }
+        Ok(range.GetText(-1)?.to_string().chars().last())
+    })
+    .ok()
+    .flatten()
+}
+
+#[cfg(target_os = "macos")]
+fn query_char_before_caret() -> Option<char> {
+    macos_ax::char_before_caret()
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn query_char_before_caret() -> Option<char> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+mod macos_ax {
+    use super::FIELD_START;
+    use std::ffi::{c_char, c_void};
+
+    type CFTypeRef = *const c_void;
+
+    #[repr(C)]
+    struct CFRange {
+        location: isize,
+        length: isize,
+    }
+
+    const AX_SUCCESS: i32 = 0;
+    const AX_VALUE_CF_RANGE: u32 = 4;
+    const CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+
+    #[link(name = "ApplicationServices", kind = "framework")]
+    extern "C" {
+        fn AXUIElementCreateSystemWide() -> CFTypeRef;
+        fn AXUIElementCopyAttributeValue(
+            element: CFTypeRef,
+            attribute: CFTypeRef,
+            value: *mut CFTypeRef,
+        ) -> i32;
+        fn AXUIElementCopyParameterizedAttributeValue(
+            element: CFTypeRef,
+            attribute: CFTypeRef,
+            parameter: CFTypeRef,
+            result: *mut CFTypeRef,
+        ) -> i32;
+        fn AXValueCreate(value_type: u32, value: *const c_void) -> CFTypeRef;
+        fn AXValueGetValue(value: CFTypeRef, value_type: u32, out: *mut c_void) -> u8;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            allocator: CFTypeRef,
+            string: *const c_char,
+            encoding: u32,
+        ) -> CFTypeRef;
+        fn CFStringGetLength(string: CFTypeRef) -> isize;
+        fn CFStringGetCharacterAtIndex(string: CFTypeRef, index: isize) -> u16;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    /// Owned Core Foundation reference, released on drop
+    struct Owned(CFTypeRef);
+
+    impl Owned {
+        fn new(cf: CFTypeRef) -> Option<Self> {
+            (!cf.is_null()).then_some(Self(cf))
+        }
+    }
+
+    impl Drop for Owned {
+        fn drop(&mut self) {
+            // SAFETY: Only non-null references obtained under the Create/Copy
+            // rule are wrapped, so each is released exactly once.
+            unsafe { CFRelease(self.0) }
+        }
+    }
+
+    fn cf_string(name: &[u8]) -> Option<Owned> {
+        // SAFETY: `name` is a NUL-terminated ASCII literal.
+        Owned::new(unsafe {
+            CFStringCreateWithCString(
+                std::ptr::null(),
+                name.as_ptr() as *const c_char,
+                CF_STRING_ENCODING_UTF8,
+            )
+        })
+    }
+
+    fn copy_attribute(element: &Owned, name: &[u8]) -> Option<Owned> {
+        let attribute = cf_string(name)?;
+        let mut value: CFTypeRef = std::ptr::null();
+        // SAFETY: Valid element and attribute references; the result is a
+        // +1 reference owned by the caller.
+        let status = unsafe { AXUIElementCopyAttributeValue(element.0, attribute.0, &mut value) };
+        (status == AX_SUCCESS).then(|| Owned::new(value)).flatten()
+    }
+
+    pub fn char_before_caret() -> Option<char> {
+        // SAFETY: Every reference is checked for null and owned by `Owned`;
+        // CFRange matches the layout AXValue uses for kAXValueCFRangeType.
+        unsafe {
+            let system = Owned::new(AXUIElementCreateSystemWide())?;
+            let focused = copy_attribute(&system, b"AXFocusedUIElement\0")?;
+            let selected = copy_attribute(&focused, b"AXSelectedTextRange\0")?;
+
+            let mut range = CFRange {
+                location: 0,
+                length: 0,
+            };
+            if AXValueGetValue(
+                selected.0,
+                AX_VALUE_CF_RANGE,
+                &mut range as *mut CFRange as *mut c_void,
+            ) == 0
+            {
+                return None;
+            }
+            if range.location <= 0 {
+                return Some(FIELD_START);
+            }
+
+            let before = CFRange {
+                location: range.location - 1,
+                length: 1,
+            };
+            let parameter = Owned::new(AXValueCreate(
+                AX_VALUE_CF_RANGE,
+                &before as *const CFRange as *const c_void,
+            ))?;
+            let attribute = cf_string(b"AXStringForRange\0")?;
+            let mut text: CFTypeRef = std::ptr::null();
+            if AXUIElementCopyParameterizedAttributeValue(
+                focused.0,
+                attribute.0,
+                parameter.0,
+                &mut text,
+            ) != AX_SUCCESS
+            {
+                return None;
+            }
+            let text = Owned::new(text)?;
+            if CFStringGetLength(text.0) < 1 {
+                return None;
+            }
+            char::from_u32(CFStringGetCharacterAtIndex(text.0, 0) as u32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_space_after_word() {
+        assert_eq!(apply_smart_spacing("next", Some('d')), " next");
+        assert_eq!(apply_smart_spacing("Next", Some('.')), " Next");
+    }
+
+    #[test]
+    fn test_no_space_after_opening_or_whitespace() {
+        assert_eq!(apply_smart_spacing("next", Some('(')), "next");
+        assert_eq!(apply_smart_spacing(" next", Some(' ')), "next");
+        assert_eq!(apply_smart_spacing("Hello", Some(FIELD_START)), "Hello");
+    }
+
+    #[test]
+    fn test_no_space_before_punctuation() {
+        assert_eq!(apply_smart_spacing(", then", Some('d')), ", then");
+        assert_eq!(apply_smart_spacing("?", Some('d')), "?");
+    }
+
+    #[test]
+    fn test_no_space_between_cjk() {
+        assert_eq!(apply_smart_spacing("天気です", Some('は')), "天気です");
+        assert_eq!(apply_smart_spacing("好", Some('你')), "好");
+    }
+
+    #[test]
+    fn test_unknown_context_unchanged() {
+        assert_eq!(apply_smart_spacing(" text", None), " text");
+    }
+
+    #[test]
+    fn test_recent_injection_heuristic() {
+        remember_injection(Some(7), "First sentence.");
+        let now = Instant::now();
+        assert_eq!(recent_injection_char(Some(7), now), Some('.'));
+        assert_eq!(recent_injection_char(Some(8), now), None);
+        assert_eq!(recent_injection_char(Some(7), now + INJECTION_MEMORY), None);
+
+        // Two unknown windows are not the same window
+        remember_injection(None, "Other app.");
+        assert_eq!(recent_injection_char(None, Instant::now()), None);
+    }
+}
@@ -0,0 +1,204 @@
+//! Subtitles
+//!
+//! SRT and WebVTT rendering of transcriptions, for captioning screen
+//! recordings. Cues come from the provider's segment timestamps; text
+//! without them is spread over the recording by sentence length.
+
+use crate::transcription::TranscriptSegment;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Longest caption line (the usual broadcast limit)
+const MAX_LINE_CHARS: usize = 42;
+
+/// Longest cue when timings are estimated (two full lines)
+const MAX_CUE_CHARS: usize = 2 * MAX_LINE_CHARS;
+
+/// Subtitle file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    /// File extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Srt => "srt",
+            Self::Vtt => "vtt",
+        }
+    }
+}
+
+/// A caption shown from `start_ms` to `end_ms`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cue {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+}
+
+/// Cues from timed segments, skipping empty ones
+pub fn segment_cues(segments: &[TranscriptSegment]) -> Vec<Cue> {
+    segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(|segment| Cue {
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms.max(segment.start_ms),
+            text: segment.text.trim().to_string(),
+        })
+        .collect()
+}
+
+/// Cues for untimed text spoken over `duration_ms`
+///
+/// Each sentence (split further when longer than two lines) gets a share of
+/// the duration proportional to its length.
+pub fn estimate_cues(text: &str, duration_ms: u64) -> Vec<Cue> {
+    let chunks: Vec<String> = crate::history::sentences(text)
+        .into_iter()
+        .flat_map(|sentence| split_words(&sentence, MAX_CUE_CHARS))
+        .collect();
+    let total_chars: usize = chunks.iter().map(|c| c.chars().count()).sum();
+    if total_chars == 0 {
+        return Vec::new();
+    }
+
+    let mut cues = Vec::with_capacity(chunks.len());
+    let mut chars_before = 0;
+    for chunk in chunks {
+        let start_ms = duration_ms * chars_before as u64 / total_chars as u64;
+        chars_before += chunk.chars().count();
+        let end_ms = duration_ms * chars_before as u64 / total_chars as u64;
+        cues.push(Cue {
+            start_ms,
+            end_ms,
+            text: chunk,
+        });
+    }
+    cues
+}
+
+/// Render cues as an SRT or WebVTT file
+pub fn render_subtitles(cues: &[Cue], format: SubtitleFormat) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    for (i, cue) in cues.iter().enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", i + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n",
+            format_timestamp(cue.start_ms, format),
+            format_timestamp(cue.end_ms, format)
+        ));
+        for line in split_words(&cue.text, MAX_LINE_CHARS) {
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// `HH:MM:SS,mmm` (SRT) or `HH:MM:SS.mmm` (WebVTT)
+pub fn format_timestamp(ms: u64, format: SubtitleFormat) -> String {
+    let separator = match format {
+        SubtitleFormat::Srt => ',',
+        SubtitleFormat::Vtt => '.',
+    };
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        separator,
+        ms % 1000
+    )
+}
+
+/// Split `text` at spaces into pieces of at most `max_chars`
+///
+/// A single word longer than `max_chars` gets a piece of its own.
+fn split_words(text: &str, max_chars: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        let len = current.chars().count();
+        if len > 0 && len + 1 + word.chars().count() > max_chars {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: u64, end_ms: u64, text: &str) -> TranscriptSegment {
+        TranscriptSegment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0, SubtitleFormat::Srt), "00:00:00,000");
+        assert_eq!(format_timestamp(3_723_045, SubtitleFormat::Srt), "01:02:03,045");
+        assert_eq!(format_timestamp(61_500, SubtitleFormat::Vtt), "00:01:01.500");
+    }
+
+    #[test]
+    fn test_render_from_segments() {
+        let cues = segment_cues(&[
+            segment(0, 1240, " Hello there."),
+            segment(1240, 1300, " "),
+            segment(1300, 2500, "General Kenobi."),
+        ]);
+        assert_eq!(cues.len(), 2);
+
+        assert_eq!(
+            render_subtitles(&cues, SubtitleFormat::Srt),
+            "1\n00:00:00,000 --> 00:00:01,240\nHello there.\n\n\
+             2\n00:00:01,300 --> 00:00:02,500\nGeneral Kenobi.\n\n"
+        );
+        assert!(render_subtitles(&cues, SubtitleFormat::Vtt)
+            .starts_with("WEBVTT\n\n00:00:00.000 --> 00:00:01.240\nHello there.\n"));
+    }
+
+    #[test]
+    fn test_estimate_cues() {
+        let cues = estimate_cues("One two. Three four five six!", 3000);
+        assert_eq!(cues.len(), 2);
+        assert_eq!(cues[0].text, "One two.");
+        assert_eq!(cues[0].start_ms, 0);
+        assert_eq!(cues[0].end_ms, cues[1].start_ms);
+        assert_eq!(cues[1].end_ms, 3000);
+        assert!(estimate_cues("  ", 3000).is_empty());
+    }
+
+    #[test]
+    fn test_long_cues_wrap() {
+        let text = "word ".repeat(30);
+        let cues = estimate_cues(&text, 10_000);
+        assert!(cues.iter().all(|c| c.text.chars().count() <= MAX_CUE_CHARS));
+
+        let rendered = render_subtitles(&cues[..1], SubtitleFormat::Vtt);
+        assert!(rendered.lines().all(|l| l.chars().count() <= MAX_LINE_CHARS));
+    }
+}
@@ -0,0 +1,346 @@
+//! Typography
+//!
+//! Locale conventions applied to transcriptions before injection. Whisper
+//! writes straight quotes, straight apostrophes and decimal points whatever
+//! the language; this rewrites them to the conventions of the transcription's
+//! language ("3.5" becomes "3,5" and "il a dit "oui"" becomes
+//! "il a dit « oui »" in French).
+
+use crate::config::{DecimalSeparator, QuoteStyle, TypographyConventions, TypographySettings};
+
+/// No-break space (before ":" and inside guillemets)
+const NBSP: char = '\u{a0}';
+
+/// Narrow no-break space (before ";", "!" and "?")
+const NARROW_NBSP: char = '\u{202f}';
+
+/// Whether `c` is one of the no-break spaces typography inserts
+///
+/// They bind punctuation to the word before it, so text compared across
+/// typography treats them as part of that word.
+pub fn is_no_break_space(c: char) -> bool {
+    c == NBSP || c == NARROW_NBSP
+}
+
+/// Characters after which a straight double quote opens a quotation
+const OPENING_CONTEXT: &[char] = &['(', '[', '{', '—', '–', '-', '/'];
+
+/// Built-in conventions of a language (ISO 639-1, region subtags ignored)
+pub fn builtin_conventions(language: &str) -> Option<TypographyConventions> {
+    use DecimalSeparator::{Comma, Point};
+    use QuoteStyle::*;
+
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+    let (quotes, decimal_separator) = match primary {
+        "en" => (Curly, Point),
+        "fr" | "es" | "it" | "pt" | "ca" | "ru" | "uk" | "be" | "no" | "el" => {
+            (Guillemets, Comma)
+        }
+        "de" | "cs" | "sk" | "sl" | "lt" | "bg" | "is" => (LowHigh, Comma),
+        "pl" | "hu" | "ro" | "hr" | "nl" => (LowRight, Comma),
+        "da" => (ReversedGuillemets, Comma),
+        "sv" | "fi" | "tr" | "id" | "vi" => (Curly, Comma),
+        "ja" | "zh" | "yue" => (Corner, Point),
+        "ko" | "he" | "hi" | "th" => (Curly, Point),
+        _ => return None,
+    };
+    Some(TypographyConventions {
+        language: primary.to_string(),
+        quotes,
+        decimal_separator,
+        typographic_apostrophes: !matches!(primary, "ja" | "zh" | "yue"),
+        space_before_punctuation: primary == "fr",
+    })
+}
+
+/// Conventions for `language`: the configured ones, else the built-in ones
+pub fn conventions_for(
+    settings: &TypographySettings,
+    language: &str,
+) -> Option<TypographyConventions> {
+    let primary = language.split(['-', '_']).next().unwrap_or(language);
+    settings
+        .conventions(language)
+        .or_else(|| settings.conventions(primary))
+        .cloned()
+        .or_else(|| builtin_conventions(language))
+}
+
+/// Apply the conventions of `language` when typography is enabled
+///
+/// Unknown languages (including undetected "auto") leave the text untouched.
+pub fn apply_typography(
+    text: &str,
+    settings: &TypographySettings,
+    language: Option<&str>,
+) -> String {
+    let conventions = language
+        .filter(|_| settings.enabled)
+        .filter(|l| *l != "auto")
+        .and_then(|l| conventions_for(settings, l));
+    match conventions {
+        Some(conventions) => normalize_typography(text, &conventions),
+        None => text.to_string(),
+    }
+}
+
+/// Rewrite numbers, quotes, apostrophes and punctuation spacing
+pub fn normalize_typography(text: &str, conventions: &TypographyConventions) -> String {
+    let mut text = normalize_decimals(text, conventions.decimal_separator);
+    text = normalize_quotes(&text, conventions.quotes);
+    if conventions.typographic_apostrophes {
+        text = normalize_apostrophes(&text);
+    }
+    if conventions.space_before_punctuation {
+        text = space_punctuation(&text);
+    }
+    text
+}
+
+/// Swap the decimal separator of numbers with a single separator
+///
+/// Fractions of exactly three digits are left alone, since "1.000" may be
+/// a thousands separator. Numbers attached to letters ("v1.5") and with
+/// several separators ("1.2.3", "10.000,5") are kept as they are.
+fn normalize_decimals(text: &str, separator: DecimalSeparator) -> String {
+    let (from, to) = match separator {
+        DecimalSeparator::Keep => return text.to_string(),
+        DecimalSeparator::Point => (',', '.'),
+        DecimalSeparator::Comma => ('.', ','),
+    };
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    while i < chars.len() {
+        let attached_before =
+            i > 0 && (chars[i - 1].is_alphanumeric() || matches!(chars[i - 1], '.' | ','));
+        let starts_number = chars[i].is_ascii_digit() && !attached_before;
+        if !starts_number {
+            out.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        // Digits and separators followed by a digit
+        let start = i;
+        let mut separators = Vec::new();
+        while i < chars.len() {
+            if chars[i].is_ascii_digit() {
+                i += 1;
+            } else if matches!(chars[i], '.' | ',')
+                && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())
+            {
+                separators.push(i);
+                i += 1;
+            } else {
+                break;
+            }
+        }
+        let attached = chars.get(i).is_some_and(|c| c.is_alphabetic());
+
+        let swap = match separators.as_slice() {
+            [at] if chars[*at] == from && !attached => i - at - 1 != 3,
+            _ => false,
+        };
+        for (j, c) in chars[start..i].iter().enumerate() {
+            out.push(if swap && separators[0] == start + j { to } else { *c });
+        }
+    }
+    out
+}
+
+/// Opening and closing double quotation marks of a style
+fn quote_marks(style: QuoteStyle) -> Option<(char, char)> {
+    match style {
+        QuoteStyle::Keep => None,
+        QuoteStyle::Straight => Some(('"', '"')),
+        QuoteStyle::Curly => Some(('“', '”')),
+        QuoteStyle::LowHigh => Some(('„', '“')),
+        QuoteStyle::LowRight => Some(('„', '”')),
+        QuoteStyle::Guillemets => Some(('«', '»')),
+        QuoteStyle::ReversedGuillemets => Some(('»', '«')),
+        QuoteStyle::Corner => Some(('「', '」')),
+    }
+}
+
+/// Replace double quotation marks with those of `style`
+///
+/// Whether a straight quote opens or closes depends on what precedes it.
+/// Applying the same style again leaves the text unchanged.
+fn normalize_quotes(text: &str, style: QuoteStyle) -> String {
+    let Some((open, close)) = quote_marks(style) else {
+        return text.to_string();
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut previous: Option<char> = None;
+    for c in text.chars() {
+        let replacement = match c {
+            '„' => open,
+            '”' => close,
+            // “ closes German quotations, so it is treated like a straight quote
+            '"' | '“' => {
+                let opens = previous
+                    .is_none_or(|p| p.is_whitespace() || OPENING_CONTEXT.contains(&p));
+                if opens {
+                    open
+                } else {
+                    close
+                }
+            }
+            _ => c,
+        };
+        out.push(replacement);
+        previous = Some(c);
+    }
+    out
+}
+
+/// Replace straight apostrophes inside words ("don't", "l'homme")
+fn normalize_apostrophes(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .iter()
+        .enumerate()
+        .map(|(i, &c)| {
+            let in_word = i > 0
+                && chars[i - 1].is_alphanumeric()
+                && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+            if c == '\'' && in_word {
+                '’'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Put no-break spaces before ; : ! ? » and after « (French)
+///
+/// Marks inside a token ("10:30", "https://", "?q=") are left alone.
+fn space_punctuation(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out: Vec<char> = Vec::with_capacity(chars.len() + 8);
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let space = match c {
+            ';' | '!' | '?' => Some(NARROW_NBSP),
+            ':' | '»' => Some(NBSP),
+            _ => None,
+        };
+
+        if let Some(space) = space {
+            let ends_token = next.is_none_or(|n| {
+                n.is_whitespace() || matches!(n, '.' | ',' | ';' | ':' | '!' | '?' | '»' | ')')
+            });
+            match out.last().copied() {
+                Some(' ') => {
+                    out.pop();
+                    out.push(space);
+                }
+                Some(p)
+                    if ends_token
+                        && !p.is_whitespace()
+                        && !matches!(p, ';' | ':' | '!' | '?') =>
+                {
+                    out.push(space);
+                }
+                _ => {}
+            }
+            out.push(c);
+            i += 1;
+            continue;
+        }
+
+        out.push(c);
+        if c == '«' {
+            match next {
+                Some(' ') => {
+                    out.push(NBSP);
+                    i += 1;
+                }
+                Some(n) if n != NBSP && n != NARROW_NBSP => out.push(NBSP),
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+    out.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn french() -> TypographyConventions {
+        builtin_conventions("fr").unwrap()
+    }
+
+    #[test]
+    fn test_decimals() {
+        let comma = |text| normalize_decimals(text, DecimalSeparator::Comma);
+        assert_eq!(comma("Il fait 3.5 degrés"), "Il fait 3,5 degrés");
+        assert_eq!(
+            normalize_decimals("It costs 2,75 dollars.", DecimalSeparator::Point),
+            "It costs 2.75 dollars."
+        );
+        // Ambiguous thousands, versions and attached numbers are kept
+        assert_eq!(comma("1.000 personnes"), "1.000 personnes");
+        assert_eq!(comma("version 1.2.3"), "version 1.2.3");
+        assert_eq!(comma("v1.5 et 2.5x"), "v1.5 et 2.5x");
+        assert_eq!(comma("Fin 3."), "Fin 3.");
+    }
+
+    #[test]
+    fn test_quotes() {
+        assert_eq!(normalize_quotes("He said \"yes\".", QuoteStyle::Curly), "He said “yes”.");
+        assert_eq!(normalize_quotes("Er sagte \"ja\"", QuoteStyle::LowHigh), "Er sagte „ja“");
+        assert_eq!(normalize_quotes("(\"a\") “b”", QuoteStyle::LowRight), "(„a”) „b”");
+        assert_eq!(normalize_quotes("\"x\"", QuoteStyle::Keep), "\"x\"");
+        // Already converted quotes are stable
+        assert_eq!(normalize_quotes("Er sagte „ja“", QuoteStyle::LowHigh), "Er sagte „ja“");
+    }
+
+    #[test]
+    fn test_apostrophes() {
+        assert_eq!(normalize_apostrophes("don't 'quote' l'homme"), "don’t 'quote' l’homme");
+    }
+
+    #[test]
+    fn test_french_spacing() {
+        assert_eq!(
+            normalize_typography("Il a dit \"oui\" : c'est 2.5 fois plus! Vraiment ?", &french()),
+            "Il a dit «\u{a0}oui\u{a0}»\u{a0}: c’est 2,5 fois plus\u{202f}! Vraiment\u{202f}?"
+        );
+        // Times, URLs and repeated marks are left alone
+        assert_eq!(
+            space_punctuation("À 10:30 sur https://exemple.fr?!"),
+            "À 10:30 sur https://exemple.fr\u{202f}?!"
+        );
+        // Already spaced text is stable
+        let once = normalize_typography("Bonjour ! « Salut »", &french());
+        assert_eq!(normalize_typography(&once, &french()), once);
+    }
+
+    #[test]
+    fn test_apply_typography_uses_settings() {
+        let mut settings = TypographySettings::default();
+        assert_eq!(apply_typography("3.5", &settings, Some("de")), "3.5");
+
+        settings.enabled = true;
+        assert_eq!(apply_typography("3.5", &settings, Some("de")), "3,5");
+        assert_eq!(apply_typography("3.5", &settings, Some("auto")), "3.5");
+        assert_eq!(apply_typography("3.5", &settings, Some("xx")), "3.5");
+        assert_eq!(apply_typography("\"a\"", &settings, Some("pt-BR")), "«a»");
+
+        settings.languages.push(TypographyConventions {
+            language: "pt".to_string(),
+            quotes: QuoteStyle::Curly,
+            ..TypographyConventions::default()
+        });
+        assert_eq!(apply_typography("\"a\" 3,5", &settings, Some("pt-BR")), "“a” 3.5");
+    }
+}
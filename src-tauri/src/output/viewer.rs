@@ -0,0 +1,141 @@
+//! Transcript Viewer
+//!
+//! State of the read-only viewer window, a large-font, always-on-top view
+//! that live-tails dictations for presentations and accessibility. Finished
+//! transcriptions become lines; the partial of the recording in progress is
+//! shown after them. While paused, the view stays as it is and updates are
+//! dropped.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Window label of the transcript viewer
+pub const VIEWER_LABEL: &str = "transcript-viewer";
+
+/// Event carrying a `ViewerUpdate`
+pub const VIEWER_EVENT: &str = "viewer:updated";
+
+/// Finished transcriptions kept in the view
+const MAX_VIEWER_LINES: usize = 200;
+
+/// A finished transcription
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ViewerLine {
+    pub text: String,
+    pub timestamp: String,
+}
+
+/// Change to apply to the view
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ViewerUpdate {
+    /// Text of the recording in progress so far
+    Partial { text: String },
+    /// A transcription finished; replaces the partial
+    Line { line: ViewerLine },
+    Cleared,
+    Paused { paused: bool },
+}
+
+/// Transcript viewer state
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct TranscriptViewer {
+    pub paused: bool,
+    pub lines: VecDeque<ViewerLine>,
+    /// Partial of the recording in progress
+    pub partial: Option<String>,
+}
+
+impl TranscriptViewer {
+    /// Show the text of the recording in progress
+    pub fn partial(&mut self, text: &str) -> Option<ViewerUpdate> {
+        let text = text.trim();
+        if self.paused || text.is_empty() || self.partial.as_deref() == Some(text) {
+            return None;
+        }
+        self.partial = Some(text.to_string());
+        Some(ViewerUpdate::Partial {
+            text: text.to_string(),
+        })
+    }
+
+    /// Add a finished transcription
+    pub fn line(&mut self, text: &str, timestamp: String) -> Option<ViewerUpdate> {
+        let text = text.trim();
+        if self.paused || text.is_empty() {
+            return None;
+        }
+        self.partial = None;
+        if self.lines.len() == MAX_VIEWER_LINES {
+            self.lines.pop_front();
+        }
+        let line = ViewerLine {
+            text: text.to_string(),
+            timestamp,
+        };
+        self.lines.push_back(line.clone());
+        Some(ViewerUpdate::Line { line })
+    }
+
+    /// Remove all text
+    pub fn clear(&mut self) -> ViewerUpdate {
+        self.lines.clear();
+        self.partial = None;
+        ViewerUpdate::Cleared
+    }
+
+    /// Freeze or resume the view
+    pub fn set_paused(&mut self, paused: bool) -> ViewerUpdate {
+        self.paused = paused;
+        ViewerUpdate::Paused { paused }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partials_replaced_by_line() {
+        let mut viewer = TranscriptViewer::default();
+        assert!(viewer.partial("Hello").is_some());
+        assert!(viewer.partial("Hello ").is_none());
+        assert_eq!(
+            viewer.partial("Hello world"),
+            Some(ViewerUpdate::Partial {
+                text: "Hello world".to_string()
+            })
+        );
+
+        viewer.line("Hello world.", "t".to_string());
+        assert!(viewer.partial.is_none());
+        assert_eq!(viewer.lines.len(), 1);
+        assert!(viewer.line("  ", "t".to_string()).is_none());
+    }
+
+    #[test]
+    fn test_paused_drops_updates() {
+        let mut viewer = TranscriptViewer::default();
+        viewer.line("Kept", "t".to_string());
+        viewer.set_paused(true);
+        assert!(viewer.partial("Dropped").is_none());
+        assert!(viewer.line("Dropped", "t".to_string()).is_none());
+        assert_eq!(viewer.lines.len(), 1);
+
+        viewer.set_paused(false);
+        assert!(viewer.line("Shown", "t".to_string()).is_some());
+        assert_eq!(viewer.clear(), ViewerUpdate::Cleared);
+        assert!(viewer.lines.is_empty());
+    }
+
+    #[test]
+    fn test_lines_capped() {
+        let mut viewer = TranscriptViewer::default();
+        for i in 0..MAX_VIEWER_LINES + 5 {
+            viewer.line(&format!("Line {}", i), "t".to_string());
+        }
+        assert_eq!(viewer.lines.len(), MAX_VIEWER_LINES);
+        assert_eq!(viewer.lines[0].text, "Line 5");
+    }
+}
@@ -0,0 +1,511 @@
+//! Voice Commands
+//!
+//! Spoken punctuation and formatting. Dictating "see you tomorrow period new
+//! line thanks" gives "see you tomorrow.\nThanks". Each language has a
+//! built-in table of commands; custom commands from the settings are added
+//! to it and win over built-in ones with the same phrase. Punctuation the
+//! transcriber wrote around a command ("comma," or ", comma.") is dropped.
+//!
+//! Built-in commands that are also everyday words ("point", "period") only
+//! count at the end of a clause, so "le point de vue" stays as spoken.
+
+use crate::config::{VoiceCommand, VoiceCommandSettings};
+use crate::utils::primary_language;
+use Spacing::{Break, Left, Right};
+
+/// Punctuation the transcriber may write around a spoken command
+const TRANSCRIBER_PUNCTUATION: &[char] = &['.', ',', ';', ':', '!', '?', '…'];
+
+/// Characters ignored when matching a word against a command
+const MATCH_TRIMMED: &[char] = &[
+    '.', ',', ';', ':', '!', '?', '…', '"', '“', '”', '„', '«', '»', '(', ')',
+];
+
+/// Built-in single-word commands that are also everyday words
+///
+/// They only match at the end of the text, before another command or when
+/// the transcriber punctuated them.
+const COMMON_WORD_COMMANDS: &[&str] = &["period", "colon", "point", "punkt", "punto", "coma"];
+
+/// How the text of a command joins the words around it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spacing {
+    /// Attaches to the previous word (", . ? ! ) :")
+    Left,
+    /// Attaches to the next word ("(" or an opening quote)
+    Right,
+    /// Line break: no space either side, next word capitalized
+    Break,
+    /// Spaced like a word
+    Word,
+}
+
+impl Spacing {
+    /// Spacing of a custom command, guessed from its text
+    fn of(text: &str) -> Self {
+        if text.starts_with(['\n', '\r']) {
+            Break
+        } else if !text.is_empty() && text.chars().all(|c| ".,;:!?)]}…”»%".contains(c)) {
+            Left
+        } else if !text.is_empty() && text.chars().all(|c| "([{“«¿¡".contains(c)) {
+            Right
+        } else {
+            Spacing::Word
+        }
+    }
+}
+
+const ENGLISH: &[(&str, &str, Spacing)] = &[
+    ("new paragraph", "\n\n", Break),
+    ("new line", "\n", Break),
+    ("comma", ",", Left),
+    ("period", ".", Left),
+    ("full stop", ".", Left),
+    ("question mark", "?", Left),
+    ("exclamation mark", "!", Left),
+    ("exclamation point", "!", Left),
+    ("colon", ":", Left),
+    ("semicolon", ";", Left),
+    ("ellipsis", "…", Left),
+    ("open quote", "\"", Right),
+    ("close quote", "\"", Left),
+    ("open parenthesis", "(", Right),
+    ("close parenthesis", ")", Left),
+];
+
+const FRENCH: &[(&str, &str, Spacing)] = &[
+    ("nouveau paragraphe", "\n\n", Break),
+    ("à la ligne", "\n", Break),
+    ("nouvelle ligne", "\n", Break),
+    ("virgule", ",", Left),
+    ("point", ".", Left),
+    ("point d'interrogation", "?", Left),
+    ("point d'exclamation", "!", Left),
+    ("deux points", ":", Left),
+    ("point virgule", ";", Left),
+    ("point-virgule", ";", Left),
+    ("points de suspension", "…", Left),
+    ("ouvrez les guillemets", "\"", Right),
+    ("fermez les guillemets", "\"", Left),
+    ("ouvrez la parenthèse", "(", Right),
+    ("fermez la parenthèse", ")", Left),
+];
+
+const GERMAN: &[(&str, &str, Spacing)] = &[
+    ("neuer absatz", "\n\n", Break),
+    ("neue zeile", "\n", Break),
+    ("komma", ",", Left),
+    ("punkt", ".", Left),
+    ("fragezeichen", "?", Left),
+    ("ausrufezeichen", "!", Left),
+    ("doppelpunkt", ":", Left),
+    ("semikolon", ";", Left),
+    ("anführungszeichen auf", "\"", Right),
+    ("anführungszeichen zu", "\"", Left),
+    ("klammer auf", "(", Right),
+    ("klammer zu", ")", Left),
+];
+
+const SPANISH: &[(&str, &str, Spacing)] = &[
+    ("nuevo párrafo", "\n\n", Break),
+    ("nueva línea", "\n", Break),
+    ("coma", ",", Left),
+    ("punto", ".", Left),
+    ("punto y coma", ";", Left),
+    ("dos puntos", ":", Left),
+    ("signo de interrogación", "?", Left),
+    ("signo de exclamación", "!", Left),
+    ("abrir comillas", "\"", Right),
+    ("cerrar comillas", "\"", Left),
+    ("abrir paréntesis", "(", Right),
+    ("cerrar paréntesis", ")", Left),
+];
+
+/// A command ready for matching
+struct Command {
+    /// Lowercased words of the phrase
+    words: Vec<String>,
+    text: String,
+    spacing: Spacing,
+    /// Only matches at the end of a clause
+    clause_end: bool,
+}
+
+impl Command {
+    fn new(phrase: &str, text: &str, spacing: Spacing) -> Self {
+        Self {
+            words: phrase
+                .split_whitespace()
+                .map(match_key)
+                .filter(|w| !w.is_empty())
+                .collect(),
+            text: text.to_string(),
+            spacing,
+            clause_end: false,
+        }
+    }
+
+    fn builtin(phrase: &str, text: &str, spacing: Spacing) -> Self {
+        Self {
+            clause_end: COMMON_WORD_COMMANDS.contains(&phrase),
+            ..Self::new(phrase, text, spacing)
+        }
+    }
+
+    /// Whether the command's words start at `keys[start]`
+    fn starts_at(&self, keys: &[String], start: usize) -> bool {
+        keys.get(start..start + self.words.len())
+            .is_some_and(|words| words.iter().eq(self.words.iter()))
+    }
+}
+
+/// Part of the rewritten text
+enum Piece<'a> {
+    /// A transcribed word with the whitespace before it
+    Word {
+        separator: &'a str,
+        text: &'a str,
+    },
+    Command {
+        text: &'a str,
+        spacing: Spacing,
+    },
+}
+
+/// Built-in commands of a language (ISO 639-1, region subtags ignored)
+///
+/// Languages without a table get none.
+pub fn builtin_voice_commands(language: &str) -> Vec<VoiceCommand> {
+    builtin_table(primary_language(language))
+        .iter()
+        .map(|(phrase, text, _)| VoiceCommand {
+            phrase: phrase.to_string(),
+            text: text.to_string(),
+            language: Some(primary_language(language).to_string()),
+        })
+        .collect()
+}
+
+/// Replace the spoken commands of `language` in `text` when enabled
+///
+/// An unknown language ("auto" or None) uses the English commands. Text
+/// without commands is returned unchanged.
+pub fn apply_voice_commands(
+    text: &str,
+    settings: &VoiceCommandSettings,
+    language: Option<&str>,
+) -> String {
+    if !settings.enabled {
+        return text.to_string();
+    }
+    let language = language
+        .filter(|l| *l != "auto")
+        .map(primary_language)
+        .unwrap_or("en");
+
+    // Custom commands first so they win over built-in ones of the same length
+    let mut commands: Vec<Command> = settings
+        .commands
+        .iter()
+        .filter(|c| {
+            c.language
+                .as_deref()
+                .is_none_or(|l| primary_language(l) == language)
+        })
+        .map(|c| Command::new(&c.phrase, &c.text, Spacing::of(&c.text)))
+        .chain(
+            builtin_table(language)
+                .iter()
+                .map(|&(phrase, text, spacing)| Command::builtin(phrase, text, spacing)),
+        )
+        // A phrase of punctuation only would match nothing but itself
+        .filter(|c| !c.words.is_empty())
+        .collect();
+    commands.sort_by_key(|c| std::cmp::Reverse(c.words.len()));
+
+    replace_commands(text, &commands).unwrap_or_else(|| text.to_string())
+}
+
+fn builtin_table(language: &str) -> &'static [(&'static str, &'static str, Spacing)] {
+    match language {
+        "en" => ENGLISH,
+        "fr" => FRENCH,
+        "de" => GERMAN,
+        "es" => SPANISH,
+        _ => &[],
+    }
+}
+
+/// A word as compared with command phrases
+fn match_key(word: &str) -> String {
+    word.trim_matches(MATCH_TRIMMED)
+        .to_lowercase()
+        .replace('’', "'")
+}
+
+/// Rewrite `text` with `commands` (longest first); None when none matched
+fn replace_commands(text: &str, commands: &[Command]) -> Option<String> {
+    let tokens = split_words(text);
+    let keys: Vec<String> = tokens.iter().map(|(_, word)| match_key(word)).collect();
+
+    let mut pieces: Vec<Piece> = Vec::with_capacity(tokens.len());
+    let mut matched = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let command = commands.iter().find(|c| {
+            c.starts_at(&keys, i)
+                && (!c.clause_end || ends_clause(&tokens, &keys, commands, i + c.words.len()))
+        });
+        let Some(command) = command else {
+            let (separator, text) = tokens[i];
+            pieces.push(Piece::Word { separator, text });
+            i += 1;
+            continue;
+        };
+
+        // The transcriber's own punctuation before a punctuation command goes
+        if command.spacing == Left {
+            if let Some(Piece::Word { text, .. }) = pieces.last_mut() {
+                *text = text.trim_end_matches(TRANSCRIBER_PUNCTUATION);
+            }
+        }
+        pieces.push(Piece::Command {
+            text: &command.text,
+            spacing: command.spacing,
+        });
+        matched = true;
+        i += command.words.len();
+    }
+
+    matched.then(|| render(&pieces))
+}
+
+/// Whether a clause ends before `tokens[end]`: at the end of the text,
+/// before another command or after punctuation the transcriber wrote
+fn ends_clause(tokens: &[(&str, &str)], keys: &[String], commands: &[Command], end: usize) -> bool {
+    end == tokens.len()
+        || tokens[end - 1].1.ends_with(TRANSCRIBER_PUNCTUATION)
+        || commands.iter().any(|c| c.starts_at(keys, end))
+}
+
+/// Words of `text` with the whitespace before each
+fn split_words(text: &str) -> Vec<(&str, &str)> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let word_start = rest
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len());
+        if word_start == rest.len() {
+            break;
+        }
+        let word_len = rest[word_start..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - word_start);
+        tokens.push((
+            &rest[..word_start],
+            &rest[word_start..word_start + word_len],
+        ));
+        rest = &rest[word_start + word_len..];
+    }
+    tokens
+}
+
+fn render(pieces: &[Piece]) -> String {
+    let mut out = String::new();
+    // No space before the next word (start of text, after "(" or a line break)
+    let mut glued = true;
+    let mut capitalize = false;
+    for piece in pieces {
+        match *piece {
+            Piece::Word { separator, text } => {
+                if text.is_empty() {
+                    continue;
+                }
+                if !glued {
+                    out.push_str(if separator.is_empty() { " " } else { separator });
+                }
+                if capitalize {
+                    let mut chars = text.chars();
+                    out.extend(chars.next().into_iter().flat_map(char::to_uppercase));
+                    out.push_str(chars.as_str());
+                } else {
+                    out.push_str(text);
+                }
+                glued = false;
+                capitalize = false;
+            }
+            Piece::Command { text, spacing } => match spacing {
+                Left => {
+                    trim_spaces(&mut out);
+                    out.push_str(text);
+                    glued = false;
+                    capitalize = text.ends_with(['.', '?', '!']);
+                }
+                Right => {
+                    if !glued {
+                        out.push(' ');
+                    }
+                    out.push_str(text);
+                    glued = true;
+                }
+                Break => {
+                    trim_spaces(&mut out);
+                    out.push_str(text);
+                    glued = true;
+                    capitalize = true;
+                }
+                Spacing::Word => {
+                    if !glued && !text.is_empty() {
+                        out.push(' ');
+                    }
+                    out.push_str(text);
+                    glued = glued && text.is_empty();
+                }
+            },
+        }
+    }
+    trim_spaces(&mut out);
+    out
+}
+
+/// Drop trailing spaces, keeping line breaks
+fn trim_spaces(out: &mut String) {
+    let trimmed = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(trimmed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enabled() -> VoiceCommandSettings {
+        VoiceCommandSettings {
+            enabled: true,
+            commands: Vec::new(),
+        }
+    }
+
+    fn apply(text: &str, language: &str) -> String {
+        apply_voice_commands(text, &enabled(), Some(language))
+    }
+
+    #[test]
+    fn test_english_commands() {
+        assert_eq!(
+            apply("see you tomorrow period new line thanks comma Anna", "en"),
+            "see you tomorrow.\nThanks, Anna"
+        );
+        assert_eq!(
+            apply("he said open quote hello close quote question mark", "en"),
+            "he said \"hello\"?"
+        );
+        assert_eq!(
+            apply(
+                "call me open parenthesis after five close parenthesis",
+                "en-US"
+            ),
+            "call me (after five)"
+        );
+    }
+
+    #[test]
+    fn test_transcriber_punctuation_is_dropped() {
+        assert_eq!(
+            apply("Hello, comma, how are you? Question mark.", "en"),
+            "Hello, how are you?"
+        );
+        assert_eq!(apply("Thanks. New line. Bye.", "en"), "Thanks.\nBye.");
+    }
+
+    #[test]
+    fn test_localized_commands() {
+        assert_eq!(
+            apply("Bonjour virgule ça va point d'interrogation", "fr"),
+            "Bonjour, ça va?"
+        );
+        assert_eq!(
+            apply("Bis morgen Punkt neuer Absatz Gruß", "de"),
+            "Bis morgen.\n\nGruß"
+        );
+        assert_eq!(apply("uno punto y coma dos", "es"), "uno; dos");
+        // English commands when the language is unknown
+        assert_eq!(
+            apply_voice_commands("yes comma no", &enabled(), None),
+            "yes, no"
+        );
+        assert_eq!(apply("yes comma no", "ja"), "yes comma no");
+    }
+
+    #[test]
+    fn test_common_words_only_end_clauses() {
+        assert_eq!(apply("le point de vue", "fr"), "le point de vue");
+        assert_eq!(apply("c'est fini point", "fr"), "c'est fini.");
+        assert_eq!(apply("c'est fini point. Merci", "fr"), "c'est fini. Merci");
+        assert_eq!(apply("el punto de vista", "es"), "el punto de vista");
+        assert_eq!(apply("Punkt drei Uhr", "de"), "Punkt drei Uhr");
+        assert_eq!(
+            apply("the trial period ends colon tomorrow", "en"),
+            "the trial period ends colon tomorrow"
+        );
+        assert_eq!(apply("done period new line next", "en"), "done.\nNext");
+        // Words that are only commands still match anywhere
+        assert_eq!(apply("un virgule deux", "fr"), "un, deux");
+    }
+
+    #[test]
+    fn test_custom_commands() {
+        let settings = VoiceCommandSettings {
+            enabled: true,
+            commands: vec![
+                VoiceCommand {
+                    phrase: "smiley face".to_string(),
+                    text: "🙂".to_string(),
+                    language: None,
+                },
+                VoiceCommand {
+                    phrase: "period".to_string(),
+                    text: "!".to_string(),
+                    language: Some("en".to_string()),
+                },
+                VoiceCommand {
+                    phrase: "virgule".to_string(),
+                    text: ";".to_string(),
+                    language: Some("fr".to_string()),
+                },
+            ],
+        };
+        assert_eq!(
+            apply_voice_commands("great news period smiley face", &settings, Some("en")),
+            "great news! 🙂"
+        );
+        assert_eq!(
+            apply_voice_commands("virgule comma", &settings, Some("en")),
+            "virgule,"
+        );
+    }
+
+    #[test]
+    fn test_unchanged_without_commands() {
+        let text = "  Two lines\nof text, with  spacing ";
+        assert_eq!(apply(text, "en"), text);
+        assert_eq!(
+            apply_voice_commands(
+                "one comma two",
+                &VoiceCommandSettings::default(),
+                Some("en")
+            ),
+            "one comma two"
+        );
+    }
+
+    #[test]
+    fn test_builtin_voice_commands() {
+        let french = builtin_voice_commands("fr-CA");
+        assert!(french
+            .iter()
+            .any(|c| c.phrase == "virgule" && c.text == ","));
+        assert!(french.iter().all(|c| c.language.as_deref() == Some("fr")));
+        assert!(builtin_voice_commands("ja").is_empty());
+    }
+}
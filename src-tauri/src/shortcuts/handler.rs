@@ -1,68 +1,408 @@
 //! Shortcut Handler
 //!
-//! Register and handle global keyboard shortcuts.
+//! Register and handle global keyboard shortcuts. Every shortcut maps to a
+//! `ShortcutAction` through a table built from the settings, and one
+//! dispatcher runs the actions. With gestures enabled, record shortcuts go
+//! through a timing state machine first.
 
 use crate::audio::{AudioCapture, AudioConfig};
+use crate::config::{AudioSource, GestureSettings, Settings};
+use crate::indicator::{self, IndicatorState};
+use crate::transcription::RecordingStop;
 use crate::{AppState, RecordingState};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
 
-/// Register all global shortcuts
-pub fn register_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    let state = app.state::<AppState>();
-    let config = state.config.read();
+/// What a global shortcut does
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutAction {
+    /// Start or stop recording, with a recording profile's overrides (None =
+    /// the profile picked with `SwitchProfile`, if any)
+    Record {
+        profile: Option<String>,
+    },
+    /// Start or stop an incognito recording
+    Incognito,
+    /// Start or stop a recording of the system audio output
+    SystemAudio,
+    /// Discard the recording in progress
+    Cancel,
+    OpenSettings,
+    OpenPalette,
+    ToggleWindow,
+    RepasteLast,
+    SwitchProfile,
+}
+
+/// Shortcut bound to an action
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShortcutBinding {
+    pub shortcut: String,
+    pub action: ShortcutAction,
+}
 
-    // Parse shortcut from config
-    let record_shortcut: Shortcut = config.shortcuts.record.parse().map_err(|e| {
-        tracing::error!("Failed to parse shortcut '{}': {}", config.shortcuts.record, e);
-        e
-    })?;
+/// Shortcuts registered at all times, record shortcut first
+///
+/// Disabled (empty) shortcuts are left out, and so is the cancel shortcut,
+/// which is only registered while recording so Escape keeps working in
+/// other applications.
+pub fn shortcut_bindings(settings: &Settings) -> Vec<ShortcutBinding> {
+    let shortcuts = &settings.shortcuts;
+    let mut bindings = vec![
+        (&shortcuts.record, ShortcutAction::Record { profile: None }),
+        (&shortcuts.settings, ShortcutAction::OpenSettings),
+        (&shortcuts.palette, ShortcutAction::OpenPalette),
+        (&shortcuts.incognito, ShortcutAction::Incognito),
+        (&shortcuts.toggle_window, ShortcutAction::ToggleWindow),
+        (&shortcuts.repaste_last, ShortcutAction::RepasteLast),
+        (&shortcuts.switch_profile, ShortcutAction::SwitchProfile),
+        (&shortcuts.system_audio, ShortcutAction::SystemAudio),
+    ];
+    bindings.extend(settings.profiles.iter().map(|profile| {
+        (
+            &profile.shortcut,
+            ShortcutAction::Record {
+                profile: Some(profile.id.clone()),
+            },
+        )
+    }));
+
+    bindings
+        .into_iter()
+        .filter(|(shortcut, _)| !shortcut.is_empty())
+        .map(|(shortcut, action)| ShortcutBinding {
+            shortcut: shortcut.clone(),
+            action,
+        })
+        .collect()
+}
 
-    // Register the shortcut
+/// Register one shortcut running `action`
+fn register_binding(
+    app: &AppHandle,
+    shortcut: &str,
+    action: ShortcutAction,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let parsed: Shortcut = shortcut.parse()?;
     app.global_shortcut()
-        .on_shortcut(record_shortcut.clone(), move |app, shortcut, event| {
-            handle_record_shortcut(app, shortcut, event.state);
-        })
-        .map_err(|e| {
-            tracing::error!("Failed to register shortcut {:?}: {}", record_shortcut, e);
-            e
+        .on_shortcut(parsed, move |app, _shortcut, event| {
+            dispatch(app, &action, event.state);
         })?;
+    Ok(())
+}
+
+/// Register all global shortcuts
+pub fn register_shortcuts(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    register_all(app.handle())
+}
+
+/// Register the shortcuts of the current settings
+///
+/// Only a failure of the record shortcut is an error; the others are
+/// skipped with a warning.
+fn register_all(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let bindings = shortcut_bindings(&app.state::<AppState>().config.read());
+
+    for binding in bindings {
+        let is_record = binding.action == ShortcutAction::Record { profile: None };
+        match register_binding(app, &binding.shortcut, binding.action.clone()) {
+            Ok(()) => tracing::info!(
+                "Global shortcut registered: {} ({:?})",
+                binding.shortcut,
+                binding.action
+            ),
+            Err(e) if is_record => {
+                tracing::error!("Failed to register shortcut '{}': {}", binding.shortcut, e);
+                return Err(e);
+            }
+            Err(e) => tracing::warn!(
+                "Failed to register shortcut '{}' ({:?}): {}",
+                binding.shortcut,
+                binding.action,
+                e
+            ),
+        }
+    }
 
-    tracing::info!("Global shortcut registered: {:?}", record_shortcut);
+    // Settings changed mid-recording
+    if matches!(
+        *app.state::<AppState>().recording_state.read(),
+        RecordingState::Recording { .. }
+    ) {
+        set_cancel_shortcut(app, true);
+    }
     Ok(())
 }
 
-/// Handle record shortcut event
-fn handle_record_shortcut(app: &AppHandle, _shortcut: &Shortcut, event: ShortcutState) {
+/// Register the cancel shortcut while recording, unregister it otherwise
+pub fn set_cancel_shortcut(app: &AppHandle, active: bool) {
+    let shortcut = app
+        .state::<AppState>()
+        .config
+        .read()
+        .shortcuts
+        .cancel
+        .clone();
+    let Ok(parsed) = shortcut.parse::<Shortcut>() else {
+        return;
+    };
+
+    let global_shortcut = app.global_shortcut();
+    if !active {
+        if global_shortcut.is_registered(parsed) {
+            let _ = global_shortcut.unregister(parsed);
+        }
+        return;
+    }
+    if global_shortcut.is_registered(parsed) {
+        return;
+    }
+    if let Err(e) = register_binding(app, &shortcut, ShortcutAction::Cancel) {
+        tracing::warn!("Failed to register cancel shortcut '{}': {}", shortcut, e);
+    }
+}
+
+/// Run the action of a shortcut event
+pub(crate) fn dispatch(app: &AppHandle, action: &ShortcutAction, event: ShortcutState) {
+    match action {
+        ShortcutAction::Record { profile } => {
+            let state = app.state::<AppState>();
+            let profile = profile.clone().or_else(|| {
+                // The picked profile may have been deleted since
+                let selected = state.selected_profile.read().clone();
+                selected.filter(|id| state.config.read().profile(id).is_some())
+            });
+            if state.config.read().shortcuts.gestures.enabled {
+                handle_record_gesture(app, event, profile);
+            } else {
+                handle_record_shortcut(app, event, profile, false, None);
+            }
+        }
+        ShortcutAction::Incognito => handle_record_shortcut(app, event, None, true, None),
+        ShortcutAction::SystemAudio => {
+            handle_record_shortcut(app, event, None, false, Some(AudioSource::SystemAudio))
+        }
+        // The remaining actions run once per press
+        _ if event != ShortcutState::Pressed => {}
+        ShortcutAction::Cancel => {
+            let app = app.clone();
+            app.state::<AppState>()
+                .tasks
+                .clone()
+                .spawn("cancel-recording", async move {
+                    let state = app.state::<AppState>();
+                    if let Err(e) =
+                        crate::commands::recording::cancel_recording(app.clone(), state).await
+                    {
+                        tracing::error!("Failed to cancel recording: {}", e);
+                    }
+                    indicator::hide(&app);
+                });
+        }
+        ShortcutAction::OpenSettings => {
+            show_main_window(app);
+            let _ = app.emit("navigate:settings", ());
+        }
+        ShortcutAction::OpenPalette => open_palette(app),
+        ShortcutAction::ToggleWindow => {
+            if let Some(window) = app.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    show_main_window(app);
+                }
+            }
+        }
+        ShortcutAction::RepasteLast => {
+            let Some(entry) = crate::history::get_history()
+                .read()
+                .entries()
+                .into_iter()
+                .next()
+            else {
+                tracing::debug!("Re-paste: history is empty");
+                return;
+            };
+            let app = app.clone();
+            app.state::<AppState>()
+                .tasks
+                .clone()
+                .spawn("repaste-last", async move {
+                    let state = app.state::<AppState>();
+                    if let Err(e) =
+                        crate::commands::history::paste_history_entry(app.clone(), state, entry.id)
+                            .await
+                    {
+                        tracing::error!("Failed to re-paste last transcription: {}", e);
+                    }
+                });
+        }
+        ShortcutAction::SwitchProfile => switch_profile(app),
+    }
+}
+
+/// Feed a record shortcut event to the gesture recognizer
+///
+/// Long presses and single taps are only known once no event came in time,
+/// so each event schedules a poll for when its threshold has passed.
+fn handle_record_gesture(app: &AppHandle, event: ShortcutState, profile: Option<String>) {
+    let state = app.state::<AppState>();
+    let settings = state.config.read().shortcuts.gestures.clone();
+    let now = Instant::now();
+
+    let mut gestures = state.shortcut_gestures.lock();
+    // A tap resolved by this event belongs to the previous press
+    let previous = gestures.profile.clone();
+    let (gesture, wait_ms) = match event {
+        ShortcutState::Pressed => {
+            let gesture = gestures.press(now, &settings);
+            gestures.profile = profile;
+            (gesture, settings.long_press_ms)
+        }
+        ShortcutState::Released => (gestures.release(now), settings.double_tap_ms),
+    };
+    drop(gestures);
+
+    if let Some(gesture) = gesture {
+        run_gesture(app, gesture, previous);
+    }
+
+    let app = app.clone();
+    state.tasks.spawn("shortcut-gesture", async move {
+        tokio::time::sleep(Duration::from_millis(u64::from(wait_ms) + 10)).await;
+        let state = app.state::<AppState>();
+        let settings = state.config.read().shortcuts.gestures.clone();
+        let (gesture, profile) = {
+            let mut gestures = state.shortcut_gestures.lock();
+            (
+                gestures.poll(Instant::now(), &settings),
+                gestures.profile.clone(),
+            )
+        };
+        if let Some(gesture) = gesture {
+            run_gesture(&app, gesture, profile);
+        }
+    });
+}
+
+/// Run the action of a record shortcut gesture
+fn run_gesture(app: &AppHandle, gesture: Gesture, profile: Option<String>) {
+    tracing::debug!("Record shortcut gesture: {:?}", gesture);
+    match gesture {
+        Gesture::Tap => handle_toggle(app, ShortcutState::Pressed, profile, false, None),
+        Gesture::DoubleTap => dispatch(app, &ShortcutAction::RepasteLast, ShortcutState::Pressed),
+        Gesture::LongPressStart => {
+            handle_push_to_talk(app, ShortcutState::Pressed, profile, false, None)
+        }
+        Gesture::LongPressEnd => {
+            handle_push_to_talk(app, ShortcutState::Released, profile, false, None)
+        }
+    }
+}
+
+/// Profile following `current` in `ids`, back to None after the last one
+pub fn next_profile(ids: &[String], current: Option<&str>) -> Option<String> {
+    match current.and_then(|id| ids.iter().position(|p| p == id)) {
+        Some(index) => ids.get(index + 1).cloned(),
+        // Nothing picked yet, or the picked profile was deleted
+        None => ids.first().cloned(),
+    }
+}
+
+/// Make the record shortcut use the next recording profile
+fn switch_profile(app: &AppHandle) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let state = app.state::<AppState>();
+    let config = state.config.read();
+    let ids: Vec<String> = config.profiles.iter().map(|p| p.id.clone()).collect();
+    let selected = next_profile(&ids, state.selected_profile.read().as_deref());
+    let name = selected
+        .as_deref()
+        .and_then(|id| config.profile(id))
+        .map_or("Default settings".to_string(), |p| p.name.clone());
+    *state.selected_profile.write() = selected.clone();
+
+    tracing::info!("Record shortcut profile switched to {:?}", selected);
+    let _ = app.emit("shortcuts:profile-switched", &selected);
+    let _ = app
+        .notification()
+        .builder()
+        .title("Recording Profile")
+        .body(&name)
+        .show();
+}
+
+/// Show and focus the main window
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Show the main window and ask the frontend to open the quick-paste palette
+fn open_palette(app: &AppHandle) {
+    show_main_window(app);
+    let _ = app.emit("palette:open", ());
+    tracing::debug!("Quick-paste palette opened");
+}
+
+/// Handle record shortcut event (`profile` is set for profile shortcuts,
+/// `incognito` for the incognito shortcut, `source` for the system audio
+/// shortcut)
+fn handle_record_shortcut(
+    app: &AppHandle,
+    event: ShortcutState,
+    profile: Option<String>,
+    incognito: bool,
+    source: Option<AudioSource>,
+) {
     let state = app.state::<AppState>();
     let config = state.config.read();
 
     match config.recording.mode {
         crate::config::RecordingMode::PushToTalk => {
-            handle_push_to_talk(app, event);
+            handle_push_to_talk(app, event, profile, incognito, source);
         }
         crate::config::RecordingMode::Toggle => {
-            handle_toggle(app, event);
+            handle_toggle(app, event, profile, incognito, source);
         }
     }
 }
 
 /// Handle push-to-talk mode
-fn handle_push_to_talk(app: &AppHandle, event: ShortcutState) {
+fn handle_push_to_talk(
+    app: &AppHandle,
+    event: ShortcutState,
+    profile: Option<String>,
+    incognito: bool,
+    source: Option<AudioSource>,
+) {
+    let tasks = app.state::<AppState>().tasks.clone();
     let app_clone = app.clone();
 
     match event {
         ShortcutState::Pressed => {
             tracing::debug!("PTT: Key pressed, starting recording");
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = start_recording_internal(&app_clone).await {
+            tasks.spawn("start-recording", async move {
+                if let Err(e) =
+                    start_recording_internal(&app_clone, profile, incognito, source, None).await
+                {
                     tracing::error!("Failed to start recording: {}", e);
+                    // Only errors the user can fix are worth a notification
+                    if let Some(guidance) = crate::utils::guidance_for(&e) {
+                        crate::utils::notify_guidance(&app_clone, &guidance);
+                    }
                 }
             });
         }
         ShortcutState::Released => {
             tracing::debug!("PTT: Key released, stopping recording");
-            tauri::async_runtime::spawn(async move {
+            tasks.spawn("stop-recording", async move {
                 if let Err(e) = stop_recording_internal(&app_clone).await {
                     tracing::error!("Failed to stop recording: {}", e);
                 }
@@ -72,7 +412,15 @@ fn handle_push_to_talk(app: &AppHandle, event: ShortcutState) {
 }
 
 /// Handle toggle mode
-fn handle_toggle(app: &AppHandle, event: ShortcutState) {
+///
+/// Any record shortcut stops the current recording, whichever profile started it.
+fn handle_toggle(
+    app: &AppHandle,
+    event: ShortcutState,
+    profile: Option<String>,
+    incognito: bool,
+    source: Option<AudioSource>,
+) {
     if event != ShortcutState::Pressed {
         return;
     }
@@ -87,20 +435,27 @@ fn handle_toggle(app: &AppHandle, event: ShortcutState) {
         }
     };
 
+    let tasks = state.tasks.clone();
     let app_clone = app.clone();
 
     match should_start {
         Some(true) => {
             tracing::debug!("Toggle: Starting recording");
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = start_recording_internal(&app_clone).await {
+            tasks.spawn("start-recording", async move {
+                if let Err(e) =
+                    start_recording_internal(&app_clone, profile, incognito, source, None).await
+                {
                     tracing::error!("Failed to start recording: {}", e);
+                    // Only errors the user can fix are worth a notification
+                    if let Some(guidance) = crate::utils::guidance_for(&e) {
+                        crate::utils::notify_guidance(&app_clone, &guidance);
+                    }
                 }
             });
         }
         Some(false) => {
             tracing::debug!("Toggle: Stopping recording");
-            tauri::async_runtime::spawn(async move {
+            tasks.spawn("stop-recording", async move {
                 if let Err(e) = stop_recording_internal(&app_clone).await {
                     tracing::error!("Failed to stop recording: {}", e);
                 }
@@ -122,24 +477,54 @@ pub fn unregister_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::E
 /// Re-register shortcuts after config change
 pub fn update_shortcuts(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     unregister_shortcuts(app)?;
-
-    let state = app.state::<AppState>();
-    let config = state.config.read();
-
-    let record_shortcut: Shortcut = config.shortcuts.record.parse()?;
-
-    let app_clone = app.clone();
-    app.global_shortcut()
-        .on_shortcut(record_shortcut, move |_app, shortcut, event| {
-            handle_record_shortcut(&app_clone, shortcut, event.state);
-        })?;
+    register_all(app)?;
 
     tracing::info!("Global shortcuts updated");
     Ok(())
 }
 
-/// Internal function to start recording
-async fn start_recording_internal(app: &AppHandle) -> Result<(), String> {
+/// Configured shortcuts the OS or another application already holds
+///
+/// Tries to register each shortcut that is not registered by us yet, and
+/// releases it again. Invalid shortcuts are skipped.
+pub fn unavailable_shortcuts(app: &AppHandle, settings: &Settings) -> Vec<ShortcutConflict> {
+    let global_shortcut = app.global_shortcut();
+    configured_shortcuts(settings)
+        .into_iter()
+        .filter_map(|(name, shortcut)| {
+            let parsed = parse_shortcut(&shortcut).ok()?;
+            if global_shortcut.is_registered(parsed) {
+                return None;
+            }
+            match global_shortcut.register(parsed) {
+                Ok(()) => {
+                    let _ = global_shortcut.unregister(parsed);
+                    None
+                }
+                Err(e) => Some(ShortcutConflict {
+                    name,
+                    shortcut,
+                    reason: ConflictReason::Unavailable {
+                        error: e.to_string(),
+                    },
+                }),
+            }
+        })
+        .collect()
+}
+
+/// Internal function to start recording, optionally with a recording profile
+///
+/// An incognito recording is not checkpointed to disk and is kept in memory
+/// only once transcribed. `source` overrides the profile's audio source and
+/// `translate` its translation setting.
+pub(crate) async fn start_recording_internal(
+    app: &AppHandle,
+    profile: Option<String>,
+    incognito: bool,
+    source: Option<AudioSource>,
+    translate: Option<bool>,
+) -> Result<(), String> {
     let state = app.state::<AppState>();
 
     // Check current state
@@ -156,18 +541,35 @@ async fn start_recording_internal(app: &AppHandle) -> Result<(), String> {
         }
     }
 
-    // Get max_duration from config
-    let max_duration = {
+    // Get max_duration, input device, buffer mode and source from config
+    let (max_duration, input_device, capture_mode, source) = {
         let config = state.config.read();
-        config.recording.max_duration
+        // Profile ids also come from the local API
+        if let Some(id) = profile.as_deref() {
+            if config.profile(id).is_none() {
+                return Err(format!("Unknown recording profile: {}", id));
+            }
+        }
+        (
+            config.recording.max_duration,
+            config.audio.input_device.clone(),
+            config.audio.capture_mode,
+            source.unwrap_or(
+                config
+                    .effective_for_profile(profile.as_deref())
+                    .audio
+                    .source,
+            ),
+        )
     };
 
     // Initialize audio capture with appropriate buffer size
     let audio_config = AudioConfig {
         buffer_duration_ms: max_duration * 1000,
+        capture_mode,
         ..AudioConfig::default()
     };
-    let audio_capture = AudioCapture::new(audio_config)
+    let audio_capture = AudioCapture::with_source(source, input_device.as_deref(), audio_config)
         .map_err(|e| format!("Failed to initialize audio: {}", e))?;
 
     // Start capture
@@ -175,16 +577,51 @@ async fn start_recording_internal(app: &AppHandle) -> Result<(), String> {
         .start()
         .map_err(|e| format!("Failed to start audio capture: {}", e))?;
 
+    crate::commands::recording::spawn_speech_monitor(app, &audio_capture);
+    crate::commands::recording::spawn_level_meter(app, &audio_capture);
+    crate::commands::recording::spawn_device_watcher(app, &audio_capture);
+    *state.translate_override.write() = translate;
+    crate::commands::recording::spawn_live_partials(app, &audio_capture, profile.as_deref(), incognito);
+    if !incognito {
+        crate::commands::recording::start_recording_checkpoint(&state, &audio_capture);
+    }
+
     // Store capture handle
     *state.audio_capture.lock() = Some(audio_capture);
 
+    // Incognito recordings leave no trace, hooks and API events included
+    if !incognito {
+        let hooks = state.config.read().hooks.clone();
+        crate::utils::fire_hooks(
+            &state.tasks,
+            &hooks,
+            crate::config::HookEvent::RecordingStarted,
+            crate::utils::HookPayload {
+                profile: profile.clone(),
+                ..Default::default()
+            },
+        );
+        crate::api::publish_event(crate::api::ApiEvent::RecordingStarted {
+            profile: profile.clone(),
+        });
+    }
+
     // Update state
+    *state.active_profile.write() = profile;
+    *state.incognito.write() = incognito;
+    *state.audio_source.write() = source;
     *state.recording_state.write() = RecordingState::Recording {
         started_at: std::time::Instant::now(),
     };
+    set_cancel_shortcut(app, true);
+    crate::commands::recording::spawn_max_duration_timer(app);
 
     // Show recording indicator
-    show_recording_indicator(app);
+    indicator::show(app, IndicatorState::Recording);
+    if incognito {
+        indicator::set_incognito(app);
+        crate::tray::set_incognito_recording(app, true);
+    }
 
     // Emit event
     let _ = app.emit("recording:state-changed", "recording");
@@ -194,17 +631,25 @@ async fn start_recording_internal(app: &AppHandle) -> Result<(), String> {
 }
 
 /// Internal function to stop recording and transcribe
-async fn stop_recording_internal(app: &AppHandle) -> Result<String, String> {
+pub(crate) async fn stop_recording_internal(app: &AppHandle) -> Result<String, String> {
     use tauri_plugin_notification::NotificationExt;
 
     let state = app.state::<AppState>();
 
     tracing::info!("Stopping recording via shortcut");
+    set_cancel_shortcut(app, false);
 
     // Switch indicator to processing state
-    show_processing_indicator(app);
+    indicator::show(app, IndicatorState::Processing);
+
+    // Taken up front so no early return leaves the next recording incognito
+    let incognito = std::mem::take(&mut *state.incognito.write());
+    if incognito {
+        crate::tray::set_incognito_recording(app, false);
+    }
 
     // Get audio samples and check for errors
+    let drain_started = std::time::Instant::now();
     let (raw_samples, device_sample_rate, stream_error) = {
         let mut capture_guard = state.audio_capture.lock();
         match capture_guard.as_ref() {
@@ -219,33 +664,28 @@ async fn stop_recording_internal(app: &AppHandle) -> Result<String, String> {
                 (result.0, result.1, stream_error)
             }
             None => {
-                hide_recording_indicator(app);
+                indicator::hide(app);
                 return Err("Not recording".to_string());
             }
         }
     };
 
-    // Handle microphone disconnection or other stream errors
+    let stop = RecordingStop::since(drain_started);
+    let raw_samples =
+        crate::commands::recording::trim_trailing_silence(&state, raw_samples, device_sample_rate);
+
+    // Stopped while the microphone is still missing: warn, and transcribe
+    // what was recorded before it went away
     if let Some(error) = stream_error {
         tracing::warn!("Stream error detected during recording: {}", error.message);
 
         if error.is_disconnection {
-            // Notify user about microphone disconnection
             let _ = app
                 .notification()
                 .builder()
                 .title("Microphone Disconnected")
-                .body("The microphone was disconnected during recording. Please reconnect and try again.")
+                .body("The microphone was disconnected during recording. Transcribing what was recorded before.")
                 .show();
-
-            // Emit error event to frontend
-            let _ = app.emit("recording:microphone-error", "Microphone disconnected during recording");
-
-            *state.recording_state.write() = RecordingState::Error("Microphone disconnected".to_string());
-            let _ = app.emit("recording:state-changed", "error");
-            hide_recording_indicator(app);
-
-            return Err("Microphone disconnected during recording".to_string());
         }
     }
 
@@ -255,7 +695,7 @@ async fn stop_recording_internal(app: &AppHandle) -> Result<String, String> {
         match &*recording_state {
             RecordingState::Recording { started_at } => started_at.elapsed(),
             _ => {
-                hide_recording_indicator(app);
+                indicator::hide(app);
                 return Err("Not recording".to_string());
             }
         }
@@ -270,21 +710,35 @@ async fn stop_recording_internal(app: &AppHandle) -> Result<String, String> {
     // Update state to processing
     *state.recording_state.write() = RecordingState::Processing;
     let _ = app.emit("recording:state-changed", "processing");
+    if !incognito {
+        crate::api::publish_event(crate::api::ApiEvent::RecordingStopped);
+    }
 
     // Check for minimum audio
     if raw_samples.len() < 1600 {
+        crate::commands::recording::finish_recording_checkpoint(&state);
         *state.recording_state.write() = RecordingState::Idle;
         let _ = app.emit("recording:state-changed", "idle");
-        hide_recording_indicator(app);
+        indicator::hide(app);
         return Err("Recording too short".to_string());
     }
 
     // Use transcription service
     let service = state.transcription_service.clone();
+    let profile = state.active_profile.write().take();
     let result = service
-        .process_recording(app, raw_samples, device_sample_rate)
+        .process_recording(
+            app,
+            raw_samples,
+            device_sample_rate,
+            stop,
+            profile.as_deref(),
+            incognito,
+        )
         .await;
 
+    crate::commands::recording::finish_recording_checkpoint(&state);
+
     // Update state based on result
     match &result {
         Ok(_) => {
@@ -298,58 +752,11 @@ async fn stop_recording_internal(app: &AppHandle) -> Result<String, String> {
     }
 
     // Hide indicator
-    hide_recording_indicator(app);
+    indicator::hide(app);
 
     result
 }
 
-/// Show the recording indicator overlay window
-fn show_recording_indicator(app: &AppHandle) {
-    let state = app.state::<AppState>();
-    let show_indicator = {
-        let config = state.config.read();
-        config.ui.show_indicator
-    };
-
-    if !show_indicator {
-        tracing::debug!("Recording indicator disabled in settings");
-        return;
-    }
-
-    if let Some(window) = app.get_webview_window("recording-indicator") {
-        let _ = window.show();
-
-        let window_clone = window.clone();
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(50));
-            let _ = window_clone.emit("recording:state-changed", "recording");
-        });
-
-        tracing::debug!("Recording indicator shown");
-    } else {
-        tracing::warn!("Recording indicator window not found");
-    }
-}
-
-/// Switch indicator to processing state
-fn show_processing_indicator(app: &AppHandle) {
-    if let Some(window) = app.get_webview_window("recording-indicator") {
-        let _ = window.emit("indicator:processing", ());
-        let _ = window.emit("recording:state-changed", "processing");
-        tracing::debug!("Recording indicator switched to processing");
-    }
-
-    let _ = app.emit("recording:state-changed", "processing");
-}
-
-/// Hide the recording indicator overlay window
-fn hide_recording_indicator(app: &AppHandle) {
-    if let Some(window) = app.get_webview_window("recording-indicator") {
-        let _ = window.hide();
-        tracing::debug!("Recording indicator hidden");
-    }
-}
-
 // ============================================================================
 // Shortcut Utilities (testable without Tauri runtime)
 // ============================================================================
@@ -381,6 +788,107 @@ pub fn normalize_shortcut(shortcut_str: &str) -> Result<String, ShortcutError> {
     Ok(format!("{:?}", shortcut))
 }
 
+/// Why a configured shortcut can't be used
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum ConflictReason {
+    /// Not a valid key combination
+    Invalid { error: String },
+    /// Same key combination as another configured shortcut
+    Duplicate { other: String },
+    /// Held by the OS or another application
+    Unavailable { error: String },
+}
+
+/// Configured shortcut that can't be registered
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ShortcutConflict {
+    /// Setting name, or the name of a recording profile
+    pub name: String,
+    pub shortcut: String,
+    pub reason: ConflictReason,
+}
+
+impl std::fmt::Display for ShortcutConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.reason {
+            ConflictReason::Invalid { error } => {
+                write!(
+                    f,
+                    "{} shortcut {} is invalid: {}",
+                    self.name, self.shortcut, error
+                )
+            }
+            ConflictReason::Duplicate { other } => write!(
+                f,
+                "{} shortcut {} is already used by the {} shortcut",
+                self.name, self.shortcut, other
+            ),
+            ConflictReason::Unavailable { error } => write!(
+                f,
+                "{} shortcut {} is taken by the system or another application: {}",
+                self.name, self.shortcut, error
+            ),
+        }
+    }
+}
+
+/// Every configured keyboard shortcut, by name, including the cancel
+/// shortcut and recording profiles
+fn configured_shortcuts(settings: &Settings) -> Vec<(String, String)> {
+    let mut shortcuts: Vec<(String, String)> = settings
+        .shortcuts
+        .assigned()
+        .into_iter()
+        .map(|(name, shortcut)| (name.to_string(), shortcut.to_string()))
+        .collect();
+    shortcuts.extend(
+        settings
+            .profiles
+            .iter()
+            .filter(|profile| !profile.shortcut.is_empty())
+            .map(|profile| {
+                (
+                    format!("'{}' profile", profile.name),
+                    profile.shortcut.clone(),
+                )
+            }),
+    );
+    shortcuts
+}
+
+/// Invalid shortcuts and key combinations used by more than one shortcut
+///
+/// Shortcuts are compared once parsed, so `ctrl+space` and `Control+Space`
+/// conflict.
+pub fn find_shortcut_conflicts(settings: &Settings) -> Vec<ShortcutConflict> {
+    let shortcuts = configured_shortcuts(settings);
+    let mut conflicts = Vec::new();
+
+    for (i, (name, shortcut)) in shortcuts.iter().enumerate() {
+        let reason = if let Err(e) = parse_shortcut(shortcut) {
+            ConflictReason::Invalid {
+                error: e.to_string(),
+            }
+        } else if let Some((other, _)) = shortcuts[..i]
+            .iter()
+            .find(|(_, earlier)| shortcuts_conflict(shortcut, earlier).unwrap_or(false))
+        {
+            ConflictReason::Duplicate {
+                other: other.clone(),
+            }
+        } else {
+            continue;
+        };
+        conflicts.push(ShortcutConflict {
+            name: name.clone(),
+            shortcut: shortcut.clone(),
+            reason,
+        });
+    }
+    conflicts
+}
+
 /// Determines the recording action based on the current state and event.
 /// This pure function encapsulates the state machine logic for recording.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -414,6 +922,91 @@ pub fn determine_toggle_action(
     }
 }
 
+/// Gesture recognized on a record shortcut
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gesture {
+    /// Single short press, once the double-tap window has passed
+    Tap,
+    /// Second press within the double-tap window
+    DoubleTap,
+    /// Key held past the long-press threshold
+    LongPressStart,
+    /// Key released after a long press
+    LongPressEnd,
+}
+
+/// Timing state machine turning record shortcut presses into gestures
+///
+/// `press` and `release` take the shortcut events. `poll` must be called
+/// once the long-press threshold or the double-tap window has passed, since
+/// those gestures are decided by an event not coming.
+#[derive(Debug, Default)]
+pub struct GestureRecognizer {
+    /// Recording profile of the shortcut last pressed
+    pub profile: Option<String>,
+    pressed_at: Option<Instant>,
+    /// The key is held as a long press
+    long_press: bool,
+    /// The press completed a double-tap, so its release is ignored
+    double_tapped: bool,
+    /// Release time of a tap waiting out the double-tap window
+    tap_at: Option<Instant>,
+}
+
+impl GestureRecognizer {
+    /// Handle a key press, ignoring key repeat
+    pub fn press(&mut self, now: Instant, settings: &GestureSettings) -> Option<Gesture> {
+        if self.pressed_at.is_some() {
+            return None;
+        }
+        self.pressed_at = Some(now);
+
+        let window = Duration::from_millis(u64::from(settings.double_tap_ms));
+        match self.tap_at.take() {
+            Some(tap_at) if now.duration_since(tap_at) <= window => {
+                self.double_tapped = true;
+                Some(Gesture::DoubleTap)
+            }
+            // The window has passed but the tap was not polled yet
+            Some(_) => Some(Gesture::Tap),
+            None => None,
+        }
+    }
+
+    /// Handle a key release
+    pub fn release(&mut self, now: Instant) -> Option<Gesture> {
+        self.pressed_at.take()?;
+        if std::mem::take(&mut self.long_press) {
+            return Some(Gesture::LongPressEnd);
+        }
+        if !std::mem::take(&mut self.double_tapped) {
+            self.tap_at = Some(now);
+        }
+        None
+    }
+
+    /// Recognize a long press or a single tap whose threshold has passed
+    pub fn poll(&mut self, now: Instant, settings: &GestureSettings) -> Option<Gesture> {
+        let threshold = Duration::from_millis(u64::from(settings.long_press_ms));
+        let window = Duration::from_millis(u64::from(settings.double_tap_ms));
+        match (self.pressed_at, self.tap_at) {
+            (Some(pressed_at), _)
+                if !self.long_press
+                    && !self.double_tapped
+                    && now.duration_since(pressed_at) >= threshold =>
+            {
+                self.long_press = true;
+                Some(Gesture::LongPressStart)
+            }
+            (None, Some(tap_at)) if now.duration_since(tap_at) > window => {
+                self.tap_at = None;
+                Some(Gesture::Tap)
+            }
+            _ => None,
+        }
+    }
+}
+
 /// Custom error type for shortcut operations
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ShortcutError {
@@ -1108,4 +1701,221 @@ mod tests {
             );
         }
     }
+
+    // ========================================================================
+    // Action Table Tests
+    // ========================================================================
+
+    mod action_table {
+        use super::*;
+        use crate::config::RecordingProfile;
+
+        #[test]
+        fn test_default_bindings() {
+            let bindings = shortcut_bindings(&Settings::default());
+            let actions: Vec<_> = bindings.iter().map(|b| b.action.clone()).collect();
+            assert_eq!(
+                actions,
+                vec![
+                    ShortcutAction::Record { profile: None },
+                    ShortcutAction::OpenPalette,
+                ]
+            );
+            // Registered only while recording
+            assert!(!actions.contains(&ShortcutAction::Cancel));
+        }
+
+        #[test]
+        fn test_bindings_include_actions_and_profiles() {
+            let mut settings = Settings::default();
+            settings.shortcuts.repaste_last = "Ctrl+Alt+V".to_string();
+            settings.profiles.push(RecordingProfile {
+                id: "meeting".to_string(),
+                shortcut: "Ctrl+Alt+M".to_string(),
+                ..Default::default()
+            });
+
+            let bindings = shortcut_bindings(&settings);
+            assert!(bindings
+                .iter()
+                .all(|b| b.action != ShortcutAction::OpenSettings));
+            assert!(bindings.contains(&ShortcutBinding {
+                shortcut: "Ctrl+Alt+V".to_string(),
+                action: ShortcutAction::RepasteLast,
+            }));
+            assert_eq!(
+                bindings.last(),
+                Some(&ShortcutBinding {
+                    shortcut: "Ctrl+Alt+M".to_string(),
+                    action: ShortcutAction::Record {
+                        profile: Some("meeting".to_string())
+                    },
+                })
+            );
+        }
+
+        #[test]
+        fn test_next_profile_cycles() {
+            let ids = vec!["a".to_string(), "b".to_string()];
+            assert_eq!(next_profile(&ids, None), Some("a".to_string()));
+            assert_eq!(next_profile(&ids, Some("a")), Some("b".to_string()));
+            assert_eq!(next_profile(&ids, Some("b")), None);
+            assert_eq!(next_profile(&ids, Some("deleted")), Some("a".to_string()));
+            assert_eq!(next_profile(&[], None), None);
+        }
+    }
+
+    // ========================================================================
+    // Configured Shortcut Conflict Tests
+    // ========================================================================
+
+    mod configured_conflicts {
+        use super::*;
+        use crate::config::RecordingProfile;
+
+        #[test]
+        fn test_default_shortcuts_have_no_conflicts() {
+            assert!(find_shortcut_conflicts(&Settings::default()).is_empty());
+        }
+
+        #[test]
+        fn test_detects_duplicates_written_differently() {
+            let mut settings = Settings::default();
+            settings.shortcuts.record = "Ctrl+Space".to_string();
+            settings.shortcuts.repaste_last = "control+space".to_string();
+            settings.profiles.push(RecordingProfile {
+                id: "meeting".to_string(),
+                name: "Meeting".to_string(),
+                shortcut: "Ctrl+Space".to_string(),
+                ..Default::default()
+            });
+
+            let conflicts = find_shortcut_conflicts(&settings);
+            assert_eq!(conflicts.len(), 2);
+            assert_eq!(conflicts[0].name, "re-paste last");
+            assert_eq!(
+                conflicts[0].reason,
+                ConflictReason::Duplicate {
+                    other: "record".to_string()
+                }
+            );
+            assert_eq!(conflicts[1].name, "'Meeting' profile");
+            assert_eq!(
+                conflicts[1].to_string(),
+                "'Meeting' profile shortcut Ctrl+Space is already used by the record shortcut"
+            );
+        }
+
+        #[test]
+        fn test_detects_invalid_shortcuts() {
+            let mut settings = Settings::default();
+            settings.shortcuts.palette = "Ctrl+Nope".to_string();
+
+            let conflicts = find_shortcut_conflicts(&settings);
+            assert_eq!(conflicts.len(), 1);
+            assert_eq!(conflicts[0].name, "palette");
+            assert!(matches!(
+                conflicts[0].reason,
+                ConflictReason::Invalid { .. }
+            ));
+        }
+    }
+
+    // ========================================================================
+    // Gesture Recognizer Tests
+    // ========================================================================
+
+    mod gestures {
+        use super::*;
+
+        fn at(start: Instant, ms: u64) -> Instant {
+            start + Duration::from_millis(ms)
+        }
+
+        #[test]
+        fn test_single_tap_waits_for_double_tap_window() {
+            let settings = GestureSettings::default();
+            let start = Instant::now();
+            let mut recognizer = GestureRecognizer::default();
+
+            assert_eq!(recognizer.press(start, &settings), None);
+            assert_eq!(recognizer.release(at(start, 80)), None);
+            assert_eq!(recognizer.poll(at(start, 200), &settings), None);
+            assert_eq!(
+                recognizer.poll(at(start, 400), &settings),
+                Some(Gesture::Tap)
+            );
+            assert_eq!(recognizer.poll(at(start, 800), &settings), None);
+        }
+
+        #[test]
+        fn test_double_tap() {
+            let settings = GestureSettings::default();
+            let start = Instant::now();
+            let mut recognizer = GestureRecognizer::default();
+
+            recognizer.press(start, &settings);
+            recognizer.release(at(start, 80));
+            assert_eq!(
+                recognizer.press(at(start, 200), &settings),
+                Some(Gesture::DoubleTap)
+            );
+            // Neither the held second press nor its release is a gesture
+            assert_eq!(recognizer.poll(at(start, 700), &settings), None);
+            assert_eq!(recognizer.release(at(start, 750)), None);
+            assert_eq!(recognizer.poll(at(start, 1200), &settings), None);
+        }
+
+        #[test]
+        fn test_long_press() {
+            let settings = GestureSettings::default();
+            let start = Instant::now();
+            let mut recognizer = GestureRecognizer::default();
+
+            recognizer.press(start, &settings);
+            assert_eq!(recognizer.poll(at(start, 300), &settings), None);
+            assert_eq!(
+                recognizer.poll(at(start, 410), &settings),
+                Some(Gesture::LongPressStart)
+            );
+            assert_eq!(recognizer.poll(at(start, 900), &settings), None);
+            // Key repeat while held
+            assert_eq!(recognizer.press(at(start, 950), &settings), None);
+            assert_eq!(
+                recognizer.release(at(start, 2000)),
+                Some(Gesture::LongPressEnd)
+            );
+            assert_eq!(recognizer.poll(at(start, 3000), &settings), None);
+        }
+
+        #[test]
+        fn test_late_press_resolves_pending_tap() {
+            let settings = GestureSettings::default();
+            let start = Instant::now();
+            let mut recognizer = GestureRecognizer::default();
+
+            recognizer.press(start, &settings);
+            recognizer.release(at(start, 50));
+            // Pressed again after the window, before the tap was polled
+            assert_eq!(
+                recognizer.press(at(start, 500), &settings),
+                Some(Gesture::Tap)
+            );
+            assert_eq!(recognizer.release(at(start, 550)), None);
+            assert_eq!(
+                recognizer.poll(at(start, 900), &settings),
+                Some(Gesture::Tap)
+            );
+        }
+
+        #[test]
+        fn test_release_without_press_is_ignored() {
+            let mut recognizer = GestureRecognizer::default();
+            assert_eq!(recognizer.release(Instant::now()), None);
+            assert_eq!(
+                recognizer.poll(Instant::now(), &GestureSettings::default()),
+                None
+            );
+        }
+    }
 }
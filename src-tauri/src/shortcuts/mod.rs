@@ -1,7 +1,9 @@
 //! Shortcuts Module
 //!
-//! Global keyboard shortcut handling.
+//! Global keyboard shortcut handling, plus mouse and gamepad triggers.
 
 mod handler;
+mod triggers;
 
 pub use handler::*;
+pub use triggers::*;
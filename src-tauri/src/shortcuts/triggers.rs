@@ -0,0 +1,285 @@
+//! Mouse and Gamepad Triggers
+//!
+//! Record triggers that work alongside the keyboard shortcuts: a mouse
+//! button through a global input listener (rdev), and a gamepad button
+//! through gilrs, which also covers foot pedals presenting themselves as
+//! game controllers. A trigger dispatches the same action as the record
+//! shortcut, so it follows the recording mode and gestures. The rdev
+//! listener also reports presses of the record shortcut's modifiers, which
+//! warm up the transcription provider.
+//!
+//! The rdev listener only observes input: the trigger button still reaches
+//! the focused app, so a Back or Forward trigger also navigates a browser
+//! or file manager. Swallowing it would need rdev's grab, which requires
+//! evdev on Linux.
+
+use super::handler::{dispatch, ShortcutAction};
+use crate::config::{GamepadTrigger, MouseTrigger, TriggerSettings};
+use crate::transcription::{is_shortcut_modifier, on_activity_signal, ActivitySignal};
+use crate::utils::DisplayServer;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::ShortcutState;
+
+/// How often the gamepad listener checks for input
+const GAMEPAD_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Button codes of the back and forward side buttons (XBUTTON1 and XBUTTON2)
+#[cfg(windows)]
+const SIDE_BUTTONS: (u8, u8) = (1, 2);
+/// Button codes of the back and forward side buttons (X11 buttons 8 and 9)
+#[cfg(not(windows))]
+const SIDE_BUTTONS: (u8, u8) = (8, 9);
+
+/// Whether the input listener was started; it runs until the app exits
+static INPUT_LISTENER: AtomicBool = AtomicBool::new(false);
+
+/// Whether the input listener serves a mouse trigger
+static MOUSE_TRIGGER_ON: AtomicBool = AtomicBool::new(false);
+
+/// Whether the input listener reports modifier presses for warm-up
+static MODIFIER_WARMUP_ON: AtomicBool = AtomicBool::new(false);
+
+/// Stop flag of the running gamepad listener
+static GAMEPAD_LISTENER: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+/// Shortcut state a mouse event gives the trigger, if it is the trigger button
+pub fn mouse_event_state(trigger: MouseTrigger, event: &rdev::EventType) -> Option<ShortcutState> {
+    let (button, state) = match event {
+        rdev::EventType::ButtonPress(button) => (button, ShortcutState::Pressed),
+        rdev::EventType::ButtonRelease(button) => (button, ShortcutState::Released),
+        _ => return None,
+    };
+    let matches = match (trigger, button) {
+        (MouseTrigger::Middle, rdev::Button::Middle) => true,
+        (MouseTrigger::Back, rdev::Button::Unknown(code)) => *code == SIDE_BUTTONS.0,
+        (MouseTrigger::Forward, rdev::Button::Unknown(code)) => *code == SIDE_BUTTONS.1,
+        _ => false,
+    };
+    matches.then_some(state)
+}
+
+/// Whether a gamepad button is the trigger
+///
+/// gilrs calls the bumpers `LeftTrigger`/`RightTrigger` and the analog
+/// triggers `LeftTrigger2`/`RightTrigger2`.
+pub fn gamepad_button_matches(trigger: GamepadTrigger, button: gilrs::Button) -> bool {
+    use gilrs::Button;
+
+    match trigger {
+        GamepadTrigger::Any => true,
+        GamepadTrigger::South => button == Button::South,
+        GamepadTrigger::East => button == Button::East,
+        GamepadTrigger::North => button == Button::North,
+        GamepadTrigger::West => button == Button::West,
+        GamepadTrigger::LeftBumper => button == Button::LeftTrigger,
+        GamepadTrigger::RightBumper => button == Button::RightTrigger,
+        GamepadTrigger::LeftTrigger => button == Button::LeftTrigger2,
+        GamepadTrigger::RightTrigger => button == Button::RightTrigger2,
+        GamepadTrigger::Select => button == Button::Select,
+        GamepadTrigger::Start => button == Button::Start,
+    }
+}
+
+/// Shortcut state a gamepad event gives the trigger, if it is the trigger button
+pub fn gamepad_event_state(
+    trigger: GamepadTrigger,
+    event: &gilrs::EventType,
+) -> Option<ShortcutState> {
+    match event {
+        gilrs::EventType::ButtonPressed(button, _) if gamepad_button_matches(trigger, *button) => {
+            Some(ShortcutState::Pressed)
+        }
+        gilrs::EventType::ButtonReleased(button, _) if gamepad_button_matches(trigger, *button) => {
+            Some(ShortcutState::Released)
+        }
+        _ => None,
+    }
+}
+
+/// Run the record action for a trigger event
+fn fire(app: &AppHandle, state: ShortcutState) {
+    dispatch(app, &ShortcutAction::Record { profile: None }, state);
+}
+
+/// Start or stop the trigger listeners to match the settings
+pub fn apply_trigger_settings(app: &AppHandle, settings: &TriggerSettings) {
+    MOUSE_TRIGGER_ON.store(settings.mouse_button.is_some(), Ordering::SeqCst);
+    if settings.mouse_button.is_some() {
+        start_input_listener(app);
+    }
+
+    let mut gamepad = GAMEPAD_LISTENER.lock();
+    match (settings.gamepad_button.is_some(), gamepad.is_some()) {
+        (true, false) => *gamepad = start_gamepad_listener(app),
+        (false, true) => {
+            if let Some(stop) = gamepad.take() {
+                stop.store(true, Ordering::SeqCst);
+            }
+            tracing::info!("Gamepad trigger stopped");
+        }
+        _ => {}
+    }
+}
+
+/// Report presses of the record shortcut's modifiers to the warm-up
+pub fn set_modifier_warmup(app: &AppHandle, enabled: bool) {
+    MODIFIER_WARMUP_ON.store(enabled, Ordering::SeqCst);
+    if enabled {
+        start_input_listener(app);
+    }
+}
+
+/// Listen to global input until the app exits, once
+///
+/// Serves mouse triggers and the warm-up signal of the recording shortcut's
+/// modifiers. rdev's listener can't be stopped, so it keeps running once
+/// started and drops events without reading the settings while neither is
+/// on.
+fn start_input_listener(app: &AppHandle) {
+    if INPUT_LISTENER.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    match DisplayServer::detect() {
+        DisplayServer::MacOs => {
+            tracing::warn!("Mouse triggers and modifier warm-up are not supported on macOS");
+            return;
+        }
+        DisplayServer::Wayland => {
+            tracing::warn!("The input listener only sees input in X11 windows on Wayland");
+        }
+        _ => {}
+    }
+
+    let app_handle = app.clone();
+    app.state::<crate::AppState>()
+        .tasks
+        .spawn_thread("input-listener", move || {
+            tracing::info!("Input listener started");
+            let result = rdev::listen(move |event| match event.event_type {
+                rdev::EventType::ButtonPress(_) | rdev::EventType::ButtonRelease(_)
+                    if MOUSE_TRIGGER_ON.load(Ordering::SeqCst) =>
+                {
+                    let trigger = app_handle
+                        .state::<crate::AppState>()
+                        .config
+                        .read()
+                        .shortcuts
+                        .triggers
+                        .mouse_button;
+                    if let Some(state) =
+                        trigger.and_then(|t| mouse_event_state(t, &event.event_type))
+                    {
+                        fire(&app_handle, state);
+                    }
+                }
+                rdev::EventType::KeyPress(key) if MODIFIER_WARMUP_ON.load(Ordering::SeqCst) => {
+                    let is_modifier = is_shortcut_modifier(
+                        &app_handle
+                            .state::<crate::AppState>()
+                            .config
+                            .read()
+                            .shortcuts
+                            .record,
+                        key,
+                    );
+                    if is_modifier {
+                        on_activity_signal(&app_handle, ActivitySignal::ModifierHeld);
+                    }
+                }
+                _ => {}
+            });
+            if let Err(e) = result {
+                tracing::error!("Input listener failed: {:?}", e);
+            }
+        });
+}
+
+/// Poll gamepads until the returned flag is set
+fn start_gamepad_listener(app: &AppHandle) -> Option<Arc<AtomicBool>> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = stop.clone();
+    let app_handle = app.clone();
+    app.state::<crate::AppState>()
+        .tasks
+        .spawn_thread("gamepad-trigger", move || {
+            let mut gilrs = match gilrs::Gilrs::new() {
+                Ok(gilrs) => gilrs,
+                Err(e) => {
+                    tracing::error!("Failed to open gamepads: {}", e);
+                    return;
+                }
+            };
+            tracing::info!("Gamepad trigger started");
+
+            while !stopped.load(Ordering::SeqCst) {
+                while let Some(gilrs::Event { event, .. }) = gilrs.next_event() {
+                    let trigger = app_handle
+                        .state::<crate::AppState>()
+                        .config
+                        .read()
+                        .shortcuts
+                        .triggers
+                        .gamepad_button;
+                    if let Some(state) = trigger.and_then(|t| gamepad_event_state(t, &event)) {
+                        fire(&app_handle, state);
+                    }
+                }
+                std::thread::sleep(GAMEPAD_POLL_INTERVAL);
+            }
+        })?;
+    Some(stop)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mouse_event_state() {
+        let press = rdev::EventType::ButtonPress(rdev::Button::Middle);
+        let release = rdev::EventType::ButtonRelease(rdev::Button::Middle);
+        assert_eq!(
+            mouse_event_state(MouseTrigger::Middle, &press),
+            Some(ShortcutState::Pressed)
+        );
+        assert_eq!(
+            mouse_event_state(MouseTrigger::Middle, &release),
+            Some(ShortcutState::Released)
+        );
+        assert_eq!(mouse_event_state(MouseTrigger::Back, &press), None);
+
+        let back = rdev::EventType::ButtonPress(rdev::Button::Unknown(SIDE_BUTTONS.0));
+        assert_eq!(
+            mouse_event_state(MouseTrigger::Back, &back),
+            Some(ShortcutState::Pressed)
+        );
+        assert_eq!(mouse_event_state(MouseTrigger::Forward, &back), None);
+
+        let moved = rdev::EventType::MouseMove { x: 1.0, y: 2.0 };
+        assert_eq!(mouse_event_state(MouseTrigger::Middle, &moved), None);
+    }
+
+    #[test]
+    fn test_gamepad_button_matches() {
+        use gilrs::Button;
+
+        assert!(gamepad_button_matches(GamepadTrigger::Any, Button::Unknown));
+        assert!(gamepad_button_matches(
+            GamepadTrigger::LeftBumper,
+            Button::LeftTrigger
+        ));
+        assert!(gamepad_button_matches(
+            GamepadTrigger::RightTrigger,
+            Button::RightTrigger2
+        ));
+        assert!(!gamepad_button_matches(
+            GamepadTrigger::RightTrigger,
+            Button::RightTrigger
+        ));
+        assert!(!gamepad_button_matches(GamepadTrigger::South, Button::East));
+    }
+}
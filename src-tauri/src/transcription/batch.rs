@@ -0,0 +1,412 @@
+//! Batch Folder Transcription
+//!
+//! Transcribes every audio file in a folder into the history, one at a time,
+//! so a folder of recordings can be left to run overnight. A batch can be
+//! paused between files and cancelled; each finished file is reported with a
+//! `batch:progress` event and the whole run with a `batch:completed` summary.
+//! The batch's files are kept in a `FileQueue` of its own.
+
+use super::{FileJob, FileJobStatus, FileQueue};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event sent after each file
+pub const BATCH_PROGRESS_EVENT: &str = "batch:progress";
+
+/// Event carrying the summary of a finished batch
+pub const BATCH_COMPLETED_EVENT: &str = "batch:completed";
+
+/// How often a paused batch checks whether it was resumed
+const PAUSE_POLL: Duration = Duration::from_millis(250);
+
+/// Current or last batch
+static BATCH: Mutex<Option<BatchRun>> = Mutex::new(None);
+
+/// State of a batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchState {
+    Running,
+    Paused,
+    Cancelled,
+    Completed,
+}
+
+/// A batch of files from one folder
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BatchRun {
+    pub id: String,
+    pub folder: String,
+    pub state: BatchState,
+    pub files: FileQueue,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+}
+
+/// Summary of a finished batch
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct BatchSummary {
+    pub id: String,
+    pub folder: String,
+    pub state: BatchState,
+    pub total: usize,
+    pub done: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Time spent on files, excluding pauses
+    pub elapsed_ms: u64,
+    /// Files that failed, with their errors
+    pub failures: Vec<FileJob>,
+}
+
+/// Progress of a batch after a file finished
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    /// Position of the file in the batch (0-based)
+    pub index: usize,
+    pub total: usize,
+    pub file: FileJob,
+}
+
+/// What the worker does next
+#[derive(Debug, PartialEq)]
+enum BatchStep {
+    /// Transcribe this file
+    File(FileJob),
+    /// Paused; check again later
+    Wait,
+    /// Completed or cancelled
+    Stop,
+}
+
+impl BatchRun {
+    /// Start a batch over `paths`, found in `folder`
+    pub fn new(folder: &Path, paths: &[PathBuf]) -> Self {
+        let mut files = FileQueue::new();
+        files.enqueue_from_folder(folder, paths);
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            folder: folder.to_string_lossy().to_string(),
+            state: BatchState::Running,
+            files,
+            started_at: crate::history::chrono_timestamp(),
+            finished_at: None,
+        }
+    }
+
+    /// Whether the batch still has work (running or paused)
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, BatchState::Running | BatchState::Paused)
+    }
+
+    /// Pause after the file in progress
+    pub fn pause(&mut self) -> Result<(), String> {
+        match self.state {
+            BatchState::Running => {
+                self.state = BatchState::Paused;
+                Ok(())
+            }
+            BatchState::Paused => Ok(()),
+            _ => Err("The batch has finished".to_string()),
+        }
+    }
+
+    /// Continue a paused batch
+    pub fn resume(&mut self) -> Result<(), String> {
+        match self.state {
+            BatchState::Paused | BatchState::Running => {
+                self.state = BatchState::Running;
+                Ok(())
+            }
+            _ => Err("The batch has finished".to_string()),
+        }
+    }
+
+    /// Cancel after the file in progress; remaining files are skipped
+    pub fn cancel(&mut self) -> Result<(), String> {
+        if !self.is_active() {
+            return Err("The batch has finished".to_string());
+        }
+        self.state = BatchState::Cancelled;
+        Ok(())
+    }
+
+    fn next_step(&mut self) -> BatchStep {
+        match self.state {
+            BatchState::Paused => return BatchStep::Wait,
+            BatchState::Cancelled => {
+                self.files.skip_queued();
+                self.finish();
+                return BatchStep::Stop;
+            }
+            BatchState::Completed => return BatchStep::Stop,
+            BatchState::Running => {}
+        }
+
+        match self.files.start_next() {
+            Some(file) => BatchStep::File(file),
+            None => {
+                self.state = BatchState::Completed;
+                self.finish();
+                BatchStep::Stop
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        if self.finished_at.is_none() {
+            self.finished_at = Some(crate::history::chrono_timestamp());
+        }
+    }
+
+    /// Counts and failures of the batch so far
+    pub fn summary(&self) -> BatchSummary {
+        let count = |status| self.files.iter().filter(|f| f.status == status).count();
+        BatchSummary {
+            id: self.id.clone(),
+            folder: self.folder.clone(),
+            state: self.state,
+            total: self.files.iter().len(),
+            done: count(FileJobStatus::Done),
+            failed: count(FileJobStatus::Failed),
+            skipped: count(FileJobStatus::Skipped),
+            elapsed_ms: self.files.iter().map(|f| f.elapsed_ms).sum(),
+            failures: self
+                .files
+                .iter()
+                .filter(|f| f.status == FileJobStatus::Failed)
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Get the current or last batch
+pub fn batch() -> &'static Mutex<Option<BatchRun>> {
+    &BATCH
+}
+
+/// Audio files in a folder, sorted by path
+///
+/// Symlinked directories are not entered, so a link cycle can't make the
+/// scan recurse forever.
+pub fn scan_audio_folder(folder: &Path, recursive: bool) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![folder.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_symlink() && path.is_dir() {
+                continue;
+            }
+            if path.is_dir() {
+                if recursive {
+                    dirs.push(path);
+                }
+            } else if crate::audio::is_importable_audio(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Start transcribing the audio files in `folder`
+pub fn start_batch(app: &AppHandle, folder: &Path, recursive: bool) -> Result<BatchRun, String> {
+    if !folder.is_dir() {
+        return Err(format!("Folder not found: {}", folder.display()));
+    }
+    let paths = scan_audio_folder(folder, recursive)
+        .map_err(|e| format!("Failed to read {}: {}", folder.display(), e))?;
+    if paths.is_empty() {
+        return Err(format!("No audio files in {}", folder.display()));
+    }
+
+    let run = {
+        let mut batch = batch().lock();
+        if batch.as_ref().is_some_and(BatchRun::is_active) {
+            return Err("A batch is already running".to_string());
+        }
+        let run = BatchRun::new(folder, &paths);
+        *batch = Some(run.clone());
+        run
+    };
+    let total = run.files.iter().len();
+    tracing::info!("Starting batch of {} file(s) in {}", total, run.folder);
+
+    let tasks = app.state::<crate::AppState>().tasks.clone();
+    let app = app.clone();
+    tasks.spawn("batch", async move { run_batch(&app).await });
+    Ok(run)
+}
+
+/// Transcribe the batch's files until it completes or is cancelled
+async fn run_batch(app: &AppHandle) {
+    use tauri_plugin_notification::NotificationExt;
+
+    loop {
+        let step = match batch().lock().as_mut() {
+            Some(run) => run.next_step(),
+            None => BatchStep::Stop,
+        };
+        let file = match step {
+            BatchStep::File(file) => file,
+            BatchStep::Wait => {
+                tokio::time::sleep(PAUSE_POLL).await;
+                continue;
+            }
+            BatchStep::Stop => break,
+        };
+
+        let started = Instant::now();
+        let result = super::transcribe_audio_file(app, Path::new(&file.path)).await;
+        if let Err(e) = &result {
+            tracing::warn!("Batch: failed to transcribe {}: {}", file.path, e);
+        }
+
+        let progress = batch().lock().as_mut().and_then(|run| {
+            run.files.finish(&file.id, result, started.elapsed());
+            let (index, file) = run.files.get(&file.id)?;
+            Some(BatchProgress {
+                batch_id: run.id.clone(),
+                index,
+                total: run.files.iter().len(),
+                file: file.clone(),
+            })
+        });
+        if let Some(progress) = progress {
+            let _ = app.emit(BATCH_PROGRESS_EVENT, progress);
+        }
+    }
+
+    let Some(summary) = batch().lock().as_ref().map(BatchRun::summary) else {
+        return;
+    };
+    tracing::info!(
+        "Batch {:?}: {} done, {} failed, {} skipped",
+        summary.state,
+        summary.done,
+        summary.failed,
+        summary.skipped
+    );
+
+    let body = match summary.state {
+        BatchState::Cancelled => format!(
+            "Cancelled after {} of {} file(s)",
+            summary.done + summary.failed,
+            summary.total
+        ),
+        _ if summary.failed > 0 => format!(
+            "{} file(s) transcribed, {} failed",
+            summary.done, summary.failed
+        ),
+        _ => format!("{} file(s) transcribed into the history", summary.done),
+    };
+    let _ = app
+        .notification()
+        .builder()
+        .title("Batch Transcription Finished")
+        .body(&body)
+        .show();
+    let _ = app.emit(BATCH_COMPLETED_EVENT, summary);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_run(names: &[&str]) -> BatchRun {
+        let folder = Path::new("/recordings");
+        let paths: Vec<PathBuf> = names.iter().map(|n| folder.join(n)).collect();
+        BatchRun::new(folder, &paths)
+    }
+
+    #[test]
+    fn test_batch_runs_files_in_order() {
+        let mut run = test_run(&["a.wav", "day2/b.mp3"]);
+        assert_eq!(run.files.jobs()[1].name, "day2/b.mp3");
+
+        let BatchStep::File(first) = run.next_step() else {
+            panic!("expected a file");
+        };
+        assert_eq!(first.path, "/recordings/a.wav");
+        let (hello, elapsed) = (Ok("hello".to_string()), Duration::from_millis(40));
+        run.files.finish(&first.id, hello, elapsed);
+
+        let BatchStep::File(second) = run.next_step() else {
+            panic!("expected a file");
+        };
+        let bad = Err("bad file".to_string());
+        run.files.finish(&second.id, bad, Duration::from_millis(2));
+        assert_eq!(run.next_step(), BatchStep::Stop);
+
+        let summary = run.summary();
+        assert_eq!(summary.state, BatchState::Completed);
+        assert_eq!((summary.done, summary.failed, summary.skipped), (1, 1, 0));
+        assert_eq!(summary.elapsed_ms, 42);
+        assert_eq!(summary.failures[0].error.as_deref(), Some("bad file"));
+        assert!(run.finished_at.is_some());
+    }
+
+    #[test]
+    fn test_batch_pause_and_cancel() {
+        let mut run = test_run(&["a.wav", "b.wav", "c.wav"]);
+        let BatchStep::File(first) = run.next_step() else {
+            panic!("expected a file");
+        };
+
+        run.pause().unwrap();
+        assert_eq!(run.next_step(), BatchStep::Wait);
+        run.resume().unwrap();
+        let empty = Ok(String::new());
+        run.files.finish(&first.id, empty, Duration::ZERO);
+
+        run.cancel().unwrap();
+        assert_eq!(run.next_step(), BatchStep::Stop);
+        assert!(!run.is_active());
+        assert!(run.pause().is_err());
+
+        let summary = run.summary();
+        assert_eq!(summary.state, BatchState::Cancelled);
+        assert_eq!((summary.done, summary.skipped), (1, 2));
+    }
+
+    #[test]
+    fn test_scan_audio_folder() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let nested = dir.path().join("day2");
+        std::fs::create_dir(&nested).unwrap();
+        for path in [
+            dir.path().join("b.mp3"),
+            dir.path().join("a.wav"),
+            dir.path().join("notes.txt"),
+            nested.join("c.flac"),
+        ] {
+            std::fs::write(path, b"").unwrap();
+        }
+
+        let flat = scan_audio_folder(dir.path(), false).unwrap();
+        assert_eq!(flat, vec![dir.path().join("a.wav"), dir.path().join("b.mp3")]);
+        let all = scan_audio_folder(dir.path(), true).unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2], nested.join("c.flac"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_scan_audio_folder_skips_symlinked_dirs() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.wav"), b"").unwrap();
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("loop")).unwrap();
+
+        let all = scan_audio_folder(dir.path(), true).unwrap();
+        assert_eq!(all, vec![dir.path().join("a.wav")]);
+    }
+}
@@ -0,0 +1,237 @@
+//! Provider Capabilities
+//!
+//! What each transcription provider can do with the current settings, so
+//! the settings UI can rule out impossible combinations up front.
+
+use crate::config::{Settings, TranscriptionProvider};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Languages Whisper models recognize (ISO 639-1, plus "haw" and "yue")
+pub const WHISPER_LANGUAGES: &[&str] = &[
+    "af", "am", "ar", "as", "az", "ba", "be", "bg", "bn", "bo", "br", "bs", "ca", "cs", "cy",
+    "da", "de", "el", "en", "es", "et", "eu", "fa", "fi", "fo", "fr", "gl", "gu", "ha", "haw",
+    "he", "hi", "hr", "ht", "hu", "hy", "id", "is", "it", "ja", "jw", "ka", "kk", "km", "kn",
+    "ko", "la", "lb", "ln", "lo", "lt", "lv", "mg", "mi", "mk", "ml", "mn", "mr", "ms", "mt",
+    "my", "ne", "nl", "nn", "no", "oc", "pa", "pl", "ps", "pt", "ro", "ru", "sa", "sd", "si",
+    "sk", "sl", "sn", "so", "sq", "sr", "su", "sv", "sw", "ta", "te", "tg", "th", "tk", "tl",
+    "tr", "tt", "uk", "ur", "uz", "vi", "yi", "yo", "yue", "zh",
+];
+
+/// Languages Deepgram's Nova-2 model streams (ISO 639-1)
+pub const DEEPGRAM_LANGUAGES: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "en", "es", "et", "fi", "fr", "hi", "hu", "id", "it",
+    "ja", "ko", "lt", "lv", "ms", "nl", "no", "pl", "pt", "ro", "ru", "sk", "sv", "th", "tr",
+    "uk", "vi", "zh",
+];
+
+/// Largest upload the Groq API accepts (bytes)
+pub const GROQ_MAX_UPLOAD_BYTES: u64 = 25 * 1024 * 1024;
+
+/// Bytes per second of the 16 kHz, 16-bit mono WAV uploaded to Groq
+const GROQ_UPLOAD_BYTE_RATE: u64 = 16000 * 2;
+
+/// Groq models that only transcribe English
+const GROQ_ENGLISH_MODELS: &[&str] = &["distil-whisper-large-v3-en"];
+
+/// Groq models served by the translations endpoint
+const GROQ_TRANSLATION_MODELS: &[&str] = &["whisper-large-v3"];
+
+/// What a provider supports
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ProviderCapabilities {
+    pub provider: TranscriptionProvider,
+    /// Model the capabilities apply to
+    pub model: String,
+    /// Languages that can be selected (ISO 639-1)
+    pub languages: Vec<String>,
+    /// Whether "auto" language detection is offered
+    pub language_detection: bool,
+    /// Longest recording accepted, in seconds (None = no provider limit)
+    pub max_audio_secs: Option<u64>,
+    /// Partial results while transcribing
+    pub streaming: bool,
+    /// Segment timestamps in results
+    pub timestamps: bool,
+    /// Translation to English
+    pub translation: bool,
+    /// Whether the provider can be used right now
+    pub available: bool,
+    /// Why the provider can't be used, when unavailable
+    pub unavailable_reason: Option<String>,
+}
+
+impl ProviderCapabilities {
+    /// Capabilities of local whisper.cpp with the configured model
+    pub fn local(settings: &Settings, model_downloaded: bool) -> Self {
+        Self {
+            provider: TranscriptionProvider::Local,
+            model: settings.transcription.local.model_filename(),
+            languages: whisper_languages(),
+            language_detection: true,
+            max_audio_secs: None,
+            streaming: true,
+            timestamps: true,
+            translation: true,
+            available: model_downloaded,
+            unavailable_reason: (!model_downloaded)
+                .then(|| "The selected model has not been downloaded".to_string()),
+        }
+    }
+
+    /// Capabilities of the Groq API with the configured model
+    pub fn groq(settings: &Settings, api_key_configured: bool) -> Self {
+        let model = settings.transcription.groq.model.clone();
+        let english_only = GROQ_ENGLISH_MODELS.contains(&model.as_str());
+        Self {
+            provider: TranscriptionProvider::Groq,
+            languages: if english_only {
+                vec!["en".to_string()]
+            } else {
+                whisper_languages()
+            },
+            language_detection: !english_only,
+            max_audio_secs: Some(GROQ_MAX_UPLOAD_BYTES / GROQ_UPLOAD_BYTE_RATE),
+            streaming: false,
+            timestamps: false,
+            translation: GROQ_TRANSLATION_MODELS.contains(&model.as_str()),
+            available: api_key_configured,
+            unavailable_reason: (!api_key_configured)
+                .then(|| "No Groq API key is configured".to_string()),
+            model,
+        }
+    }
+
+    /// Capabilities of the Deepgram streaming API with the configured model
+    pub fn deepgram(settings: &Settings, api_key_configured: bool) -> Self {
+        Self {
+            provider: TranscriptionProvider::Deepgram,
+            model: settings.transcription.deepgram.model.clone(),
+            languages: DEEPGRAM_LANGUAGES.iter().map(|l| l.to_string()).collect(),
+            // Streaming sessions need the language up front
+            language_detection: false,
+            max_audio_secs: None,
+            streaming: true,
+            timestamps: true,
+            translation: false,
+            available: api_key_configured,
+            unavailable_reason: (!api_key_configured)
+                .then(|| "No Deepgram API key is configured".to_string()),
+        }
+    }
+
+    /// Capabilities of a self-hosted OpenAI-compatible server
+    ///
+    /// What the server supports isn't known ahead of time, so the Whisper
+    /// feature set is assumed.
+    pub fn custom(settings: &Settings) -> Self {
+        let custom = &settings.transcription.custom;
+        let configured = custom.validate_base_url().is_ok();
+        Self {
+            provider: TranscriptionProvider::Custom,
+            model: custom.model.clone(),
+            languages: whisper_languages(),
+            language_detection: true,
+            max_audio_secs: None,
+            streaming: false,
+            timestamps: false,
+            translation: true,
+            available: configured,
+            unavailable_reason: (!configured)
+                .then(|| "No custom endpoint URL is configured".to_string()),
+        }
+    }
+
+    /// Whether a language code (or "auto") can be used
+    pub fn supports_language(&self, language: &str) -> bool {
+        if language == "auto" {
+            return self.language_detection;
+        }
+        self.languages.iter().any(|l| l == language)
+    }
+}
+
+fn whisper_languages() -> Vec<String> {
+    WHISPER_LANGUAGES.iter().map(|l| l.to_string()).collect()
+}
+
+/// Capabilities of every provider with the current settings
+pub fn provider_capabilities(settings: &Settings) -> Vec<ProviderCapabilities> {
+    let model_path = crate::config::find_model(&settings.transcription.local.model_filename());
+    vec![
+        ProviderCapabilities::local(settings, model_path.exists()),
+        ProviderCapabilities::groq(settings, settings.transcription.groq.has_api_key()),
+        ProviderCapabilities::deepgram(settings, settings.transcription.deepgram.has_api_key()),
+        ProviderCapabilities::custom(settings),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_capabilities() {
+        let settings = Settings::default();
+        let local = ProviderCapabilities::local(&settings, false);
+        assert!(!local.available);
+        assert!(local.unavailable_reason.is_some());
+        assert!(local.translation);
+        assert!(local.supports_language("auto"));
+        assert!(local.supports_language("pl"));
+        assert!(!local.supports_language("xx"));
+        assert_eq!(local.max_audio_secs, None);
+    }
+
+    #[test]
+    fn test_groq_capabilities_depend_on_model() {
+        let mut settings = Settings::default();
+        let groq = ProviderCapabilities::groq(&settings, true);
+        assert!(groq.available);
+        assert!(groq.translation);
+        // 25 MB of 16 kHz 16-bit mono is about 13 minutes
+        assert_eq!(groq.max_audio_secs, Some(819));
+
+        settings.transcription.groq.model = "distil-whisper-large-v3-en".to_string();
+        let english = ProviderCapabilities::groq(&settings, true);
+        assert_eq!(english.languages, vec!["en"]);
+        assert!(!english.supports_language("auto"));
+        assert!(!english.translation);
+
+        settings.transcription.groq.model = "whisper-large-v3-turbo".to_string();
+        let turbo = ProviderCapabilities::groq(&settings, false);
+        assert!(turbo.supports_language("de"));
+        assert!(!turbo.translation);
+        assert!(!turbo.available);
+    }
+
+    #[test]
+    fn test_deepgram_capabilities() {
+        let deepgram = ProviderCapabilities::deepgram(&Settings::default(), true);
+        assert!(deepgram.streaming);
+        assert!(!deepgram.translation);
+        assert!(!deepgram.supports_language("auto"));
+        assert!(deepgram.supports_language("de"));
+        assert!(!deepgram.supports_language("haw"));
+    }
+
+    #[test]
+    fn test_custom_capabilities_need_url() {
+        let mut settings = Settings::default();
+        let custom = ProviderCapabilities::custom(&settings);
+        assert!(!custom.available);
+        assert!(custom.unavailable_reason.is_some());
+
+        settings.transcription.custom.base_url = "http://localhost:8000".to_string();
+        let custom = ProviderCapabilities::custom(&settings);
+        assert!(custom.available);
+        assert!(custom.supports_language("auto"));
+    }
+
+    #[test]
+    fn test_language_list_is_sorted_and_unique() {
+        assert!(DEEPGRAM_LANGUAGES.windows(2).all(|w| w[0] < w[1]));
+        assert!(WHISPER_LANGUAGES.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(WHISPER_LANGUAGES.len(), 100);
+    }
+}
@@ -0,0 +1,186 @@
+//! Transcript Cleanup
+//!
+//! Optional rewriting of a transcript by a language model (Groq, OpenAI or a
+//! local Ollama server) through the OpenAI-compatible chat completions API.
+
+use crate::config::{CleanupProvider, CleanupSettings, SecretsManager};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// HTTP client shared by cleanup requests
+static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+fn client() -> &'static reqwest::Client {
+    CLIENT.get_or_init(reqwest::Client::new)
+}
+
+/// Error from a cleanup request
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum CleanupError {
+    #[error("No API key configured for {0}")]
+    MissingKey(&'static str),
+
+    #[error("Cleanup URL must use https:// unless it is on this computer")]
+    InsecureUrl,
+
+    #[error("Request failed: {0}")]
+    Network(String),
+
+    #[error("API error: {0}")]
+    Api(String),
+
+    #[error("Unusable reply: {0}")]
+    InvalidReply(String),
+}
+
+/// Chat completions URL under a server's base URL
+///
+/// The base URL may include the "/v1" prefix or not.
+pub fn chat_completions_url(base_url: &str) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    if base.ends_with("/v1") {
+        format!("{}/chat/completions", base)
+    } else {
+        format!("{}/v1/chat/completions", base)
+    }
+}
+
+/// Request body sending `text` with the cleanup prompt
+fn request_body(text: &str, settings: &CleanupSettings) -> serde_json::Value {
+    serde_json::json!({
+        "model": settings.model(),
+        "temperature": 0,
+        "messages": [
+            { "role": "system", "content": settings.prompt.trim() },
+            { "role": "user", "content": text },
+        ],
+    })
+}
+
+/// Rewritten text from a chat completions response
+///
+/// Rejects empty replies and replies far longer than the transcript, which
+/// are answers to the dictated text rather than a corrected copy of it.
+pub fn parse_reply(body: &serde_json::Value, original: &str) -> Result<String, CleanupError> {
+    let reply = body["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| CleanupError::InvalidReply("no message in response".to_string()))?
+        .trim();
+    if reply.is_empty() {
+        return Err(CleanupError::InvalidReply("empty reply".to_string()));
+    }
+    let limit = original.chars().count() * 3 + 200;
+    if reply.chars().count() > limit {
+        return Err(CleanupError::InvalidReply(
+            "reply is much longer than the transcript".to_string(),
+        ));
+    }
+    Ok(reply.to_string())
+}
+
+/// Credential sent to the cleanup server (none for Ollama)
+///
+/// A custom server URL gets its own token, never the Groq or OpenAI key.
+fn api_key(settings: &CleanupSettings) -> Result<Option<String>, CleanupError> {
+    if !settings.base_url.trim().is_empty() {
+        if !settings.endpoint_is_secure() {
+            return Err(CleanupError::InsecureUrl);
+        }
+        return Ok(settings.get_endpoint_token());
+    }
+    match settings.provider {
+        CleanupProvider::Groq => SecretsManager::get_groq_api_key()
+            .map(Some)
+            .map_err(|_| CleanupError::MissingKey("Groq")),
+        CleanupProvider::OpenAi => SecretsManager::get_openai_api_key()
+            .map(Some)
+            .map_err(|_| CleanupError::MissingKey("OpenAI")),
+        CleanupProvider::Ollama => Ok(None),
+    }
+}
+
+/// Rewrite a transcript with the configured language model
+///
+/// Callers fall back to the raw transcript on error.
+pub async fn cleanup_transcript(
+    text: &str,
+    settings: &CleanupSettings,
+) -> Result<String, CleanupError> {
+    let key = api_key(settings)?;
+    let mut request = client()
+        .post(chat_completions_url(settings.base_url()))
+        .timeout(Duration::from_secs(settings.timeout_seconds as u64))
+        .json(&request_body(text, settings));
+    if let Some(key) = key {
+        request = request.bearer_auth(key);
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| CleanupError::Network(e.to_string()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        return Err(CleanupError::Api(format!("HTTP {}: {}", status, body)));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| CleanupError::InvalidReply(e.to_string()))?;
+    parse_reply(&body, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_completions_url() {
+        assert_eq!(
+            chat_completions_url("https://api.groq.com/openai/v1"),
+            "https://api.groq.com/openai/v1/chat/completions"
+        );
+        assert_eq!(
+            chat_completions_url("http://localhost:11434/"),
+            "http://localhost:11434/v1/chat/completions"
+        );
+    }
+
+    #[test]
+    fn test_custom_url_never_gets_provider_key() {
+        let mut settings = CleanupSettings {
+            base_url: "https://llm.example.com/v1".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(api_key(&settings), Ok(None)));
+
+        settings.base_url = "http://llm.example.com/v1".to_string();
+        assert!(matches!(api_key(&settings), Err(CleanupError::InsecureUrl)));
+    }
+
+    #[test]
+    fn test_request_body() {
+        let settings = CleanupSettings {
+            provider: CleanupProvider::OpenAi,
+            ..Default::default()
+        };
+        let body = request_body("hello world", &settings);
+        assert_eq!(body["model"], "gpt-4o-mini");
+        assert_eq!(body["messages"][0]["role"], "system");
+        assert_eq!(body["messages"][1]["content"], "hello world");
+    }
+
+    #[test]
+    fn test_parse_reply() {
+        let reply = |content: &str| serde_json::json!({ "choices": [{ "message": { "content": content } }] });
+
+        assert_eq!(
+            parse_reply(&reply(" Hello, world. \n"), "hello world").unwrap(),
+            "Hello, world."
+        );
+        assert!(parse_reply(&reply("  "), "hello world").is_err());
+        assert!(parse_reply(&serde_json::json!({ "choices": [] }), "hello").is_err());
+        assert!(parse_reply(&reply(&"word ".repeat(100)), "hi").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+//! Custom Endpoint Provider
+//!
+//! Transcription on a self-hosted server exposing the OpenAI-compatible
+//! `/v1/audio/transcriptions` endpoint (faster-whisper-server, LocalAI,
+//! Speaches, ...).
+
+use super::{
+    parse_retry_after, RetryPolicy, TranscriptionConfig, TranscriptionError,
+    TranscriptionProvider, TranscriptionResult,
+};
+use crate::audio::encode_wav;
+use async_trait::async_trait;
+use std::time::{Duration, Instant};
+
+const DEFAULT_MODEL: &str = "whisper-1";
+
+/// Transcription or translation URL under a server's base URL
+///
+/// The base URL may include the "/v1" prefix or not.
+pub fn endpoint_url(base_url: &str, translate: bool) -> String {
+    let base = base_url.trim().trim_end_matches('/');
+    let path = if translate {
+        "audio/translations"
+    } else {
+        "audio/transcriptions"
+    };
+    if base.ends_with("/v1") {
+        format!("{}/{}", base, path)
+    } else {
+        format!("{}/v1/{}", base, path)
+    }
+}
+
+/// OpenAI-compatible endpoint transcription provider
+#[derive(Clone)]
+pub struct CustomEndpointProvider {
+    base_url: String,
+    model: String,
+    token: Option<String>,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+}
+
+impl CustomEndpointProvider {
+    /// Create a provider for the server at `base_url`
+    pub fn new(
+        base_url: String,
+        model: Option<String>,
+        token: Option<String>,
+        timeout_seconds: u64,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(timeout_seconds))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        Self {
+            base_url,
+            model: model
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            token,
+            client,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Set the retry policy
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Send one transcription request
+    ///
+    /// Sets `retry_after` when the server rate limits and says how long to wait.
+    async fn send(
+        &self,
+        wav_data: &[u8],
+        config: &TranscriptionConfig,
+        retry_after: &mut Option<Duration>,
+    ) -> Result<EndpointResponse, TranscriptionError> {
+        let file_part = reqwest::multipart::Part::bytes(wav_data.to_vec())
+            .file_name("audio.wav")
+            .mime_str("audio/wav")
+            .map_err(|e| TranscriptionError::Failed(e.to_string()))?;
+
+        let mut form = reqwest::multipart::Form::new()
+            .part("file", file_part)
+            .text("model", self.model.clone())
+            .text("response_format", "json");
+
+        // Translations always produce English
+        if config.language != "auto" && !config.translate {
+            form = form.text("language", config.language.clone());
+        }
+        if let Some(prompt) = &config.prompt {
+            form = form.text("prompt", prompt.clone());
+        }
+
+        let mut request = self
+            .client
+            .post(endpoint_url(&self.base_url, config.translate))
+            .multipart(form);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| TranscriptionError::NetworkError(e.to_string()))?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            *retry_after = parse_retry_after(response.headers());
+            return Err(TranscriptionError::RateLimited);
+        }
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            let message = format!("Server returned {}: {}", status, error_text);
+            // Server errors are usually transient
+            return Err(if status.is_server_error() {
+                TranscriptionError::NetworkError(message)
+            } else if status == reqwest::StatusCode::UNAUTHORIZED {
+                TranscriptionError::Unauthorized(message)
+            } else {
+                TranscriptionError::ApiError(message)
+            });
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| TranscriptionError::Failed(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for CustomEndpointProvider {
+    async fn transcribe(
+        &self,
+        audio: &[f32],
+        config: &TranscriptionConfig,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        if self.base_url.trim().is_empty() {
+            return Err(TranscriptionError::ApiError(
+                "No server URL configured. Please set the custom endpoint URL in settings."
+                    .to_string(),
+            ));
+        }
+
+        let start = Instant::now();
+        let wav_data = encode_wav(audio, 16000, 1);
+
+        // Retry transient failures with backoff
+        let mut retries = 0;
+        let mut retry_after = None;
+        let result = loop {
+            let error = match self.send(&wav_data, config, &mut retry_after).await {
+                Ok(result) => break result,
+                Err(e) if RetryPolicy::is_retryable(&e) => e,
+                Err(e) => return Err(e),
+            };
+            let Some(delay) = self.retry.delay(retries, retry_after.take()) else {
+                return Err(error);
+            };
+            retries += 1;
+            tracing::warn!(
+                "Custom endpoint request failed ({}), retry {}/{} in {:?}",
+                error,
+                retries,
+                self.retry.max_retries,
+                delay
+            );
+            tokio::time::sleep(delay).await;
+        };
+
+        Ok(TranscriptionResult {
+            text: result.text.trim().to_string(),
+            language: result.language,
+            duration_ms: start.elapsed().as_millis() as u64,
+            provider: "custom".to_string(),
+            segments: Vec::new(),
+            model: None,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn is_available(&self) -> bool {
+        !self.base_url.trim().is_empty()
+    }
+
+    fn cost_per_minute(&self) -> Option<f64> {
+        // Self-hosted
+        Some(0.0)
+    }
+}
+
+/// Transcription response (`language` is only sent by some servers)
+#[derive(serde::Deserialize)]
+struct EndpointResponse {
+    text: String,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_url() {
+        assert_eq!(
+            endpoint_url("http://localhost:8000", false),
+            "http://localhost:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            endpoint_url("http://localhost:8000/v1/", false),
+            "http://localhost:8000/v1/audio/transcriptions"
+        );
+        assert_eq!(
+            endpoint_url("https://stt.example.com/api/v1", true),
+            "https://stt.example.com/api/v1/audio/translations"
+        );
+    }
+
+    #[test]
+    fn test_provider_defaults() {
+        let provider = CustomEndpointProvider::new(String::new(), Some(" ".to_string()), None, 60);
+        assert_eq!(provider.model, "whisper-1");
+        assert!(!provider.is_available());
+        assert_eq!(provider.name(), "custom");
+
+        let provider = CustomEndpointProvider::new(
+            "http://localhost:8000".to_string(),
+            Some("Systran/faster-whisper-small".to_string()),
+            Some("token".to_string()),
+            60,
+        );
+        assert!(provider.is_available());
+        assert_eq!(provider.model, "Systran/faster-whisper-small");
+    }
+
+    #[test]
+    fn test_response_parsing() {
+        let response: EndpointResponse = serde_json::from_str(r#"{"text":" Hello "}"#).unwrap();
+        assert_eq!(response.text, " Hello ");
+        assert_eq!(response.language, None);
+
+        let response: EndpointResponse =
+            serde_json::from_str(r#"{"text":"Bonjour","language":"fr"}"#).unwrap();
+        assert_eq!(response.language.as_deref(), Some("fr"));
+    }
+}
@@ -0,0 +1,510 @@
+//! Deepgram Streaming Provider
+//!
+//! Cloud transcription over Deepgram's websocket streaming API. Audio is
+//! sent as 16 kHz 16-bit PCM while results come back, so the same session
+//! serves live partials during a recording and the final transcription.
+//! Without live partials, the processed recording is streamed faster than
+//! real time once it stops.
+
+use super::{
+    TranscriptionConfig, TranscriptionError, TranscriptionProvider, TranscriptionResult,
+    WHISPER_SAMPLE_RATE,
+};
+use crate::config::SecretsManager;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+use tungstenite::client::IntoClientRequest;
+use tungstenite::handshake::HandshakeError;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message, WebSocket};
+
+const DEEPGRAM_LISTEN_URL: &str = "wss://api.deepgram.com/v1/listen";
+const DEFAULT_MODEL: &str = "nova-2";
+const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
+
+/// Audio per message when streaming a finished recording (100 ms)
+const CHUNK_SAMPLES: usize = WHISPER_SAMPLE_RATE as usize / 10;
+
+/// How long a poll waits for results before returning
+const POLL_TIMEOUT: Duration = Duration::from_millis(10);
+
+/// Longest wait for the connection, the TLS and websocket handshakes, and
+/// each send
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Most keywords sent with a session
+const MAX_KEYWORDS: usize = 100;
+
+/// Message asking the server to flush its results and close the stream
+const CLOSE_STREAM: &str = r#"{"type":"CloseStream"}"#;
+
+/// A transcript received from the stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeepgramResult {
+    pub transcript: String,
+    /// Final results won't be revised; interim ones are replaced by the next result
+    pub is_final: bool,
+}
+
+#[derive(Deserialize)]
+struct ServerMessage {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    is_final: bool,
+    channel: Option<Channel>,
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Channel {
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Deserialize)]
+struct Alternative {
+    transcript: String,
+}
+
+/// Parse a message from the server (None for metadata and other non-results)
+pub fn parse_message(json: &str) -> Result<Option<DeepgramResult>, TranscriptionError> {
+    let message: ServerMessage = serde_json::from_str(json)
+        .map_err(|e| TranscriptionError::ApiError(format!("Unexpected Deepgram message: {}", e)))?;
+    match message.kind.as_str() {
+        "Results" => Ok(message
+            .channel
+            .and_then(|c| c.alternatives.into_iter().next())
+            .map(|alternative| DeepgramResult {
+                transcript: alternative.transcript,
+                is_final: message.is_final,
+            })),
+        "Error" => Err(TranscriptionError::ApiError(
+            message
+                .description
+                .unwrap_or_else(|| "Deepgram reported an error".to_string()),
+        )),
+        _ => Ok(None),
+    }
+}
+
+/// Text of a stream: finalized segments followed by the latest interim result
+#[derive(Debug, Default, Clone)]
+pub struct DeepgramTranscript {
+    finals: Vec<String>,
+    interim: String,
+}
+
+impl DeepgramTranscript {
+    pub fn apply(&mut self, result: DeepgramResult) {
+        if result.is_final {
+            self.interim.clear();
+            if !result.transcript.trim().is_empty() {
+                self.finals.push(result.transcript.trim().to_string());
+            }
+        } else {
+            self.interim = result.transcript.trim().to_string();
+        }
+    }
+
+    /// Finalized text plus the interim result
+    pub fn text(&self) -> String {
+        let mut parts: Vec<&str> = self.finals.iter().map(String::as_str).collect();
+        if !self.interim.is_empty() {
+            parts.push(&self.interim);
+        }
+        parts.join(" ")
+    }
+
+    /// Finalized text only
+    pub fn final_text(&self) -> String {
+        self.finals.join(" ")
+    }
+}
+
+/// Streaming endpoint URL for a model and language
+///
+/// `keywords` are boosted with the model's keyword parameter (`keyterm` on
+/// Nova-3, `keywords` before it).
+pub fn listen_url(model: &str, language: &str, keywords: &[String]) -> String {
+    let mut url = format!(
+        "{}?model={}&encoding=linear16&sample_rate={}&channels=1&interim_results=true&smart_format=true",
+        DEEPGRAM_LISTEN_URL, model, WHISPER_SAMPLE_RATE
+    );
+    url.push_str("&language=");
+    url.push_str(language);
+    if keywords.is_empty() {
+        return url;
+    }
+
+    let param = if model.starts_with("nova-3") {
+        "keyterm"
+    } else {
+        "keywords"
+    };
+    match reqwest::Url::parse(&url) {
+        Ok(mut parsed) => {
+            parsed.query_pairs_mut().extend_pairs(
+                keywords
+                    .iter()
+                    .take(MAX_KEYWORDS)
+                    .map(|keyword| (param, keyword)),
+            );
+            parsed.into()
+        }
+        Err(_) => url,
+    }
+}
+
+/// 16 kHz samples as little-endian 16-bit PCM
+fn encode_pcm(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|s| ((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).to_le_bytes())
+        .collect()
+}
+
+fn network_error(e: tungstenite::Error) -> TranscriptionError {
+    match e {
+        tungstenite::Error::Http(response) if response.status().as_u16() == 401 => {
+            TranscriptionError::Unauthorized("Deepgram rejected the API key".to_string())
+        }
+        tungstenite::Error::Http(response) if response.status().as_u16() == 429 => {
+            TranscriptionError::RateLimited
+        }
+        tungstenite::Error::Http(response) => {
+            TranscriptionError::ApiError(format!("Deepgram returned {}", response.status()))
+        }
+        e => TranscriptionError::NetworkError(e.to_string()),
+    }
+}
+
+/// Result of a session's final text
+///
+/// The language is the configured one: streaming doesn't detect it.
+pub fn streamed_result(text: String, language: &str, duration: Duration) -> TranscriptionResult {
+    TranscriptionResult {
+        text,
+        language: Some(language.to_string()),
+        duration_ms: duration.as_millis() as u64,
+        provider: "deepgram".to_string(),
+        segments: Vec::new(),
+        model: None,
+    }
+}
+
+/// TCP connection to the first reachable address of a host, bounded by
+/// `CONNECT_TIMEOUT`
+fn connect_tcp(host: &str, port: u16) -> Result<TcpStream, TranscriptionError> {
+    let addrs = (host, port)
+        .to_socket_addrs()
+        .map_err(|e| TranscriptionError::NetworkError(e.to_string()))?;
+    let mut last_error = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(tcp) => return Ok(tcp),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(TranscriptionError::NetworkError(match last_error {
+        Some(e) => e.to_string(),
+        None => format!("{} has no address", host),
+    }))
+}
+
+/// A websocket streaming session
+pub struct DeepgramStream {
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    transcript: DeepgramTranscript,
+    closed: bool,
+}
+
+impl DeepgramStream {
+    /// Open a session; `language` is an ISO 639-1 code
+    ///
+    /// Streaming can't detect the language, so "auto" is refused rather
+    /// than silently transcribed as the model's default language.
+    pub fn connect(
+        api_key: &str,
+        model: &str,
+        language: &str,
+        keywords: &[String],
+    ) -> Result<Self, TranscriptionError> {
+        if language == "auto" {
+            return Err(TranscriptionError::Failed(
+                "Deepgram can't detect the language. Please choose one in settings.".to_string(),
+            ));
+        }
+        let mut request = listen_url(model, language, keywords)
+            .into_client_request()
+            .map_err(network_error)?;
+        let auth = format!("Token {}", api_key.trim())
+            .parse()
+            .map_err(|_| TranscriptionError::Unauthorized("Invalid API key".to_string()))?;
+        request.headers_mut().insert("Authorization", auth);
+
+        // Connect by hand so a stalled server can't hang the handshake
+        let host = request.uri().host().unwrap_or_default().to_string();
+        let tcp = connect_tcp(&host, request.uri().port_u16().unwrap_or(443))?;
+        tcp.set_read_timeout(Some(CONNECT_TIMEOUT))
+            .and_then(|_| tcp.set_write_timeout(Some(CONNECT_TIMEOUT)))
+            .and_then(|_| tcp.set_nodelay(true))
+            .map_err(|e| TranscriptionError::NetworkError(e.to_string()))?;
+        let (socket, _) = tungstenite::client_tls(request, tcp).map_err(|e| match e {
+            HandshakeError::Failure(e) => network_error(e),
+            HandshakeError::Interrupted(_) => {
+                TranscriptionError::Timeout(CONNECT_TIMEOUT.as_secs())
+            }
+        })?;
+
+        // Reads return after a short wait so audio can be sent between them
+        let tcp = match socket.get_ref() {
+            MaybeTlsStream::Plain(tcp) => Some(tcp),
+            MaybeTlsStream::Rustls(tls) => Some(tls.get_ref()),
+            _ => None,
+        };
+        if let Some(tcp) = tcp {
+            tcp.set_read_timeout(Some(POLL_TIMEOUT))
+                .map_err(|e| TranscriptionError::NetworkError(e.to_string()))?;
+        }
+
+        Ok(Self {
+            socket,
+            transcript: DeepgramTranscript::default(),
+            closed: false,
+        })
+    }
+
+    /// Send 16 kHz mono samples
+    pub fn send_audio(&mut self, samples: &[f32]) -> Result<(), TranscriptionError> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        self.socket
+            .send(Message::binary(encode_pcm(samples)))
+            .map_err(network_error)
+    }
+
+    /// Apply the results that have arrived; returns whether the transcript changed
+    pub fn poll(&mut self) -> Result<bool, TranscriptionError> {
+        let mut changed = false;
+        while !self.closed {
+            match self.socket.read() {
+                Ok(Message::Text(json)) => {
+                    if let Some(result) = parse_message(json.as_str())? {
+                        self.transcript.apply(result);
+                        changed = true;
+                    }
+                }
+                Ok(Message::Close(_)) => self.closed = true,
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if matches!(
+                        e.kind(),
+                        std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                    ) =>
+                {
+                    break;
+                }
+                Err(tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed) => {
+                    self.closed = true;
+                }
+                Err(e) => return Err(network_error(e)),
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Transcript received so far
+    pub fn transcript(&self) -> &DeepgramTranscript {
+        &self.transcript
+    }
+
+    /// Ask the server to flush and wait for the stream to close
+    ///
+    /// Returns the finalized text.
+    pub fn finish(mut self, timeout: Duration) -> Result<String, TranscriptionError> {
+        self.socket
+            .send(Message::text(CLOSE_STREAM))
+            .map_err(network_error)?;
+        let deadline = Instant::now() + timeout;
+        while !self.closed {
+            if Instant::now() >= deadline {
+                return Err(TranscriptionError::Timeout(timeout.as_secs()));
+            }
+            self.poll()?;
+        }
+        Ok(self.transcript.final_text())
+    }
+
+    /// End the session without waiting for outstanding results
+    pub fn close(mut self) {
+        let _ = self.socket.send(Message::text(CLOSE_STREAM));
+        let _ = self.socket.close(None);
+    }
+}
+
+/// Deepgram transcription provider
+#[derive(Clone)]
+pub struct DeepgramProvider {
+    model: String,
+    timeout: Duration,
+}
+
+impl DeepgramProvider {
+    /// Create a new Deepgram provider
+    pub fn new(model: Option<String>, timeout_seconds: u64) -> Self {
+        Self {
+            model: model.unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            timeout: Duration::from_secs(timeout_seconds),
+        }
+    }
+
+    /// Get the model identifier
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Get API key from secure storage
+    fn get_api_key(&self) -> Option<String> {
+        SecretsManager::get_deepgram_api_key().ok()
+    }
+}
+
+impl Default for DeepgramProvider {
+    fn default() -> Self {
+        Self::new(None, DEFAULT_TIMEOUT_SECONDS)
+    }
+}
+
+#[async_trait]
+impl TranscriptionProvider for DeepgramProvider {
+    async fn transcribe(
+        &self,
+        audio: &[f32],
+        config: &TranscriptionConfig,
+    ) -> Result<TranscriptionResult, TranscriptionError> {
+        let api_key = self.get_api_key().ok_or_else(|| {
+            TranscriptionError::ApiError(
+                "API key not configured. Please set your Deepgram API key in settings.".to_string(),
+            )
+        })?;
+        if config.translate {
+            return Err(TranscriptionError::Failed(
+                "Deepgram does not translate transcriptions".to_string(),
+            ));
+        }
+
+        let start = Instant::now();
+        let audio = audio.to_vec();
+        let model = self.model.clone();
+        let language = config.language.clone();
+        let keywords = config.keywords.clone();
+        let timeout = self.timeout;
+
+        // The session blocks on its socket, so it runs off the async runtime
+        let text = tokio::task::spawn_blocking(move || {
+            let mut stream = DeepgramStream::connect(&api_key, &model, &language, &keywords)?;
+            for chunk in audio.chunks(CHUNK_SAMPLES) {
+                stream.send_audio(chunk)?;
+                stream.poll()?;
+            }
+            stream.finish(timeout)
+        })
+        .await
+        .map_err(|e| TranscriptionError::Failed(e.to_string()))??;
+
+        Ok(streamed_result(text, &config.language, start.elapsed()))
+    }
+
+    fn name(&self) -> &'static str {
+        "deepgram"
+    }
+
+    fn is_available(&self) -> bool {
+        SecretsManager::has_deepgram_api_key()
+    }
+
+    fn cost_per_minute(&self) -> Option<f64> {
+        // Deepgram pay-as-you-go streaming pricing for Nova-2
+        Some(0.0058)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listen_url() {
+        let url = listen_url("nova-2", "de", &[]);
+        assert!(url.starts_with("wss://api.deepgram.com/v1/listen?model=nova-2"));
+        assert!(url.contains("encoding=linear16&sample_rate=16000"));
+        assert!(url.contains("interim_results=true"));
+        assert!(url.ends_with("&language=de"));
+    }
+
+    #[test]
+    fn test_connect_refuses_auto_language() {
+        let result = DeepgramStream::connect("key", "nova-2", "auto", &[]);
+        assert!(matches!(result, Err(TranscriptionError::Failed(_))));
+    }
+
+    #[test]
+    fn test_listen_url_keywords() {
+        let keywords = vec!["Kubernetes".to_string(), "Dr. Müller".to_string()];
+        let url = listen_url("nova-2", "de", &keywords);
+        assert!(url.contains("&language=de&keywords=Kubernetes&keywords=Dr.+M%C3%BCller"));
+        assert!(listen_url("nova-3", "en", &keywords).contains("&keyterm=Kubernetes"));
+    }
+
+    #[test]
+    fn test_parse_message() {
+        let json = r#"{"type":"Results","is_final":false,"speech_final":false,
+            "channel":{"alternatives":[{"transcript":"hello wor","confidence":0.9}]}}"#;
+        assert_eq!(
+            parse_message(json).unwrap(),
+            Some(DeepgramResult {
+                transcript: "hello wor".to_string(),
+                is_final: false,
+            })
+        );
+
+        let metadata = r#"{"type":"Metadata","request_id":"abc","duration":1.5}"#;
+        assert_eq!(parse_message(metadata).unwrap(), None);
+
+        let error = r#"{"type":"Error","description":"Bad audio"}"#;
+        assert!(matches!(parse_message(error), Err(TranscriptionError::ApiError(e)) if e == "Bad audio"));
+        assert!(parse_message("not json").is_err());
+    }
+
+    #[test]
+    fn test_transcript_replaces_interim_results() {
+        let result = |text: &str, is_final| DeepgramResult {
+            transcript: text.to_string(),
+            is_final,
+        };
+        let mut transcript = DeepgramTranscript::default();
+        transcript.apply(result("hello wor", false));
+        assert_eq!(transcript.text(), "hello wor");
+
+        transcript.apply(result("Hello world.", true));
+        transcript.apply(result("how", false));
+        assert_eq!(transcript.text(), "Hello world. how");
+        assert_eq!(transcript.final_text(), "Hello world.");
+
+        transcript.apply(result("", true));
+        transcript.apply(result("How are you?", true));
+        assert_eq!(transcript.text(), "Hello world. How are you?");
+    }
+
+    #[test]
+    fn test_encode_pcm() {
+        let pcm = encode_pcm(&[0.0, 1.0, -2.0]);
+        assert_eq!(pcm.len(), 6);
+        assert_eq!(&pcm[0..2], &[0, 0]);
+        assert_eq!(i16::from_le_bytes([pcm[2], pcm[3]]), i16::MAX);
+        assert_eq!(i16::from_le_bytes([pcm[4], pcm[5]]), -i16::MAX);
+    }
+}
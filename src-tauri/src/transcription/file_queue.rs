@@ -0,0 +1,380 @@
+//! Audio File Queue
+//!
+//! Audio files opened with the app, passed on the command line or dropped on
+//! the window are queued and transcribed one at a time into the history.
+//! Progress is reported with `file-queue:updated` events carrying the jobs.
+//! Batch folder runs keep their files in a `FileQueue` of their own.
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Finished jobs kept for display
+pub const MAX_FINISHED_JOBS: usize = 50;
+
+/// Event carrying the current jobs
+pub const FILE_QUEUE_EVENT: &str = "file-queue:updated";
+
+/// Global file queue
+static FILE_QUEUE: Mutex<FileQueue> = Mutex::new(FileQueue::new());
+
+/// Where a queued file is in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FileJobStatus {
+    Queued,
+    Processing,
+    Done,
+    Failed,
+    /// Not transcribed because its batch was cancelled
+    Skipped,
+}
+
+/// A queued audio file
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct FileJob {
+    pub id: String,
+    pub path: String,
+    /// File name (path relative to the batch folder for batches), for display
+    pub name: String,
+    pub status: FileJobStatus,
+    pub text: Option<String>,
+    pub error: Option<String>,
+    /// Time spent decoding and transcribing
+    pub elapsed_ms: u64,
+}
+
+/// Queued, running and recently finished file transcriptions
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(transparent)]
+pub struct FileQueue {
+    jobs: Vec<FileJob>,
+    /// Whether a worker is draining the queue
+    #[serde(skip)]
+    running: bool,
+}
+
+impl FileQueue {
+    pub const fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            running: false,
+        }
+    }
+
+    /// Queue audio files, skipping unsupported and already queued ones
+    ///
+    /// Returns the jobs added.
+    pub fn enqueue(&mut self, paths: &[PathBuf]) -> Vec<FileJob> {
+        self.enqueue_from(None, paths)
+    }
+
+    /// Queue audio files found in `folder`, named by their path relative to it
+    pub fn enqueue_from_folder(&mut self, folder: &Path, paths: &[PathBuf]) -> Vec<FileJob> {
+        self.enqueue_from(Some(folder), paths)
+    }
+
+    fn enqueue_from(&mut self, folder: Option<&Path>, paths: &[PathBuf]) -> Vec<FileJob> {
+        let mut added = Vec::new();
+        for path in paths {
+            let path_str = path.to_string_lossy().to_string();
+            let pending = self
+                .jobs
+                .iter()
+                .any(|j| j.path == path_str && j.status == FileJobStatus::Queued);
+            if pending || !crate::audio::is_importable_audio(path) {
+                continue;
+            }
+            let name = match folder.and_then(|folder| path.strip_prefix(folder).ok()) {
+                Some(relative) => Some(relative.to_string_lossy().to_string()),
+                None => path.file_name().map(|n| n.to_string_lossy().to_string()),
+            };
+            let job = FileJob {
+                id: uuid::Uuid::new_v4().to_string(),
+                name: name.unwrap_or_else(|| path_str.clone()),
+                path: path_str,
+                status: FileJobStatus::Queued,
+                text: None,
+                error: None,
+                elapsed_ms: 0,
+            };
+            self.jobs.push(job.clone());
+            added.push(job);
+        }
+        added
+    }
+
+    /// Mark the oldest queued job as processing and return it
+    pub(super) fn start_next(&mut self) -> Option<FileJob> {
+        let job = self
+            .jobs
+            .iter_mut()
+            .find(|j| j.status == FileJobStatus::Queued)?;
+        job.status = FileJobStatus::Processing;
+        Some(job.clone())
+    }
+
+    /// Record the outcome of a job and the time it took
+    pub(super) fn finish(&mut self, id: &str, result: Result<String, String>, elapsed: Duration) {
+        if let Some(job) = self.jobs.iter_mut().find(|j| j.id == id) {
+            job.elapsed_ms = elapsed.as_millis() as u64;
+            match result {
+                Ok(text) => {
+                    job.status = FileJobStatus::Done;
+                    job.text = Some(text);
+                }
+                Err(e) => {
+                    job.status = FileJobStatus::Failed;
+                    job.error = Some(e);
+                }
+            }
+        }
+    }
+
+    /// Mark the queued jobs as skipped
+    pub(super) fn skip_queued(&mut self) {
+        for job in &mut self.jobs {
+            if job.status == FileJobStatus::Queued {
+                job.status = FileJobStatus::Skipped;
+            }
+        }
+    }
+
+    /// Drop the oldest finished jobs beyond `keep`
+    fn prune_finished(&mut self, keep: usize) {
+        let finished = self.jobs.iter().filter(|j| is_finished(j)).count();
+        let mut excess = finished.saturating_sub(keep);
+        self.jobs.retain(|j| {
+            if excess > 0 && is_finished(j) {
+                excess -= 1;
+                return false;
+            }
+            true
+        });
+    }
+
+    /// Remove finished jobs
+    pub fn clear_finished(&mut self) {
+        self.jobs.retain(|j| !is_finished(j));
+    }
+
+    /// All jobs, oldest first
+    pub fn jobs(&self) -> Vec<FileJob> {
+        self.jobs.clone()
+    }
+
+    /// Iterate over the jobs, oldest first
+    pub fn iter(&self) -> std::slice::Iter<'_, FileJob> {
+        self.jobs.iter()
+    }
+
+    /// Position and current state of a job
+    pub fn get(&self, id: &str) -> Option<(usize, &FileJob)> {
+        self.jobs.iter().enumerate().find(|(_, j)| j.id == id)
+    }
+}
+
+impl Default for FileQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_finished(job: &FileJob) -> bool {
+    matches!(
+        job.status,
+        FileJobStatus::Done | FileJobStatus::Failed | FileJobStatus::Skipped
+    )
+}
+
+/// Get the global file queue
+pub fn file_queue() -> &'static Mutex<FileQueue> {
+    &FILE_QUEUE
+}
+
+/// Audio files among command line arguments (flags are skipped)
+pub fn audio_files_from_args<I: IntoIterator<Item = String>>(args: I) -> Vec<PathBuf> {
+    args.into_iter()
+        .filter(|arg| !arg.starts_with('-'))
+        .map(PathBuf::from)
+        .filter(|path| path.is_file() && crate::audio::is_importable_audio(path))
+        .collect()
+}
+
+/// Queue audio files for transcription and start the worker if it is idle
+pub fn enqueue_audio_files(app: &AppHandle, paths: &[PathBuf]) -> Vec<FileJob> {
+    let (added, start_worker) = {
+        let mut queue = file_queue().lock();
+        let added = queue.enqueue(paths);
+        let start_worker = !added.is_empty() && !queue.running;
+        if start_worker {
+            queue.running = true;
+        }
+        (added, start_worker)
+    };
+    if added.is_empty() {
+        return added;
+    }
+
+    tracing::info!("Queued {} audio file(s) for transcription", added.len());
+    publish(app);
+
+    if start_worker {
+        let tasks = app.state::<crate::AppState>().tasks.clone();
+        let app = app.clone();
+        tasks.spawn("file-queue", async move { drain_queue(&app).await });
+    }
+    added
+}
+
+/// Transcribe queued files until the queue is empty
+async fn drain_queue(app: &AppHandle) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let (mut done, mut failed) = (0, 0);
+    loop {
+        let job = {
+            let mut queue = file_queue().lock();
+            let job = queue.start_next();
+            if job.is_none() {
+                queue.running = false;
+            }
+            job
+        };
+        let Some(job) = job else {
+            break;
+        };
+        publish(app);
+
+        let started = Instant::now();
+        let result = transcribe_audio_file(app, Path::new(&job.path)).await;
+        match &result {
+            Ok(_) => done += 1,
+            Err(e) => {
+                tracing::warn!("Failed to transcribe {}: {}", job.path, e);
+                failed += 1;
+            }
+        }
+        {
+            let mut queue = file_queue().lock();
+            queue.finish(&job.id, result, started.elapsed());
+            queue.prune_finished(MAX_FINISHED_JOBS);
+        }
+        publish(app);
+    }
+
+    let body = match failed {
+        0 => format!("{} file(s) transcribed into the history", done),
+        _ => format!("{} file(s) transcribed, {} failed", done, failed),
+    };
+    let _ = app
+        .notification()
+        .builder()
+        .title("File Transcription Complete")
+        .body(&body)
+        .show();
+}
+
+/// Decode and transcribe one file, saving the result to the history
+///
+/// The audio goes through the same pipeline stages (VAD, provider
+/// orchestration) as a recording; returns the final text.
+pub async fn transcribe_audio_file(app: &AppHandle, path: &Path) -> Result<String, String> {
+    let owned = path.to_path_buf();
+    let (samples, sample_rate) =
+        tokio::task::spawn_blocking(move || crate::audio::decode_audio_file(&owned))
+            .await
+            .map_err(|e| e.to_string())?
+            .map_err(|e| e.to_string())?;
+
+    let state = app.state::<crate::AppState>();
+    let config = state.config.read().clone();
+    let mut processed = state
+        .transcription_service
+        .run_pipeline(samples, sample_rate, &config, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    if processed.wants_translation {
+        processed.translation = state
+            .transcription_service
+            .learning_translation(&processed.speech, &config)
+            .await;
+    }
+
+    if let Some(id) = processed.save_to_history(None, None) {
+        let codec = processed.history_audio_codec;
+        if let Err(e) = crate::history::compress_entry_audio(&id, codec) {
+            tracing::warn!("Keeping WAV audio for {}: {}", id, e);
+        }
+        let _ = app.emit("history:updated", ());
+    }
+    processed.record_metrics(&config, processed.timings, false);
+    Ok(processed.text)
+}
+
+/// Send the current jobs to the frontend
+fn publish(app: &AppHandle) {
+    let _ = app.emit(FILE_QUEUE_EVENT, file_queue().lock().jobs());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enqueue_skips_unsupported_and_duplicates() {
+        let mut queue = FileQueue::new();
+        let added = queue.enqueue(&[
+            PathBuf::from("/tmp/a.mp3"),
+            PathBuf::from("/tmp/notes.txt"),
+            PathBuf::from("/tmp/a.mp3"),
+        ]);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].name, "a.mp3");
+        assert_eq!(added[0].status, FileJobStatus::Queued);
+    }
+
+    #[test]
+    fn test_jobs_run_in_order() {
+        let mut queue = FileQueue::new();
+        queue.enqueue(&[PathBuf::from("one.wav"), PathBuf::from("two.wav")]);
+
+        let first = queue.start_next().unwrap();
+        assert_eq!(first.name, "one.wav");
+        let hello = Ok("hello".to_string());
+        queue.finish(&first.id, hello, Duration::from_millis(40));
+
+        let second = queue.start_next().unwrap();
+        queue.finish(&second.id, Err("bad file".to_string()), Duration::ZERO);
+        assert!(queue.start_next().is_none());
+
+        let jobs = queue.jobs();
+        assert_eq!(jobs[0].status, FileJobStatus::Done);
+        assert_eq!(jobs[0].text.as_deref(), Some("hello"));
+        assert_eq!(jobs[0].elapsed_ms, 40);
+        assert_eq!(jobs[1].status, FileJobStatus::Failed);
+
+        queue.clear_finished();
+        assert!(queue.jobs().is_empty());
+    }
+
+    #[test]
+    fn test_audio_files_from_args() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let audio = dir.path().join("memo.wav");
+        std::fs::write(&audio, b"").unwrap();
+        let text = dir.path().join("memo.txt");
+        std::fs::write(&text, b"").unwrap();
+
+        let args = vec![
+            "--minimized".to_string(),
+            audio.to_string_lossy().to_string(),
+            text.to_string_lossy().to_string(),
+            dir.path().join("missing.mp3").to_string_lossy().to_string(),
+        ];
+        assert_eq!(audio_files_from_args(args), vec![audio]);
+    }
+}
@@ -2,23 +2,28 @@
 //!
 //! Cloud transcription using Groq's Whisper API.
 
-use super::{TranscriptionConfig, TranscriptionError, TranscriptionProvider, TranscriptionResult};
+use super::{
+    parse_retry_after, RetryPolicy, TranscriptSegment, TranscriptionConfig, TranscriptionError,
+    TranscriptionProvider, TranscriptionResult,
+};
 use crate::audio::encode_wav;
 use crate::config::SecretsManager;
 use async_trait::async_trait;
 use std::time::{Duration, Instant};
 
 const GROQ_API_URL: &str = "https://api.groq.com/openai/v1/audio/transcriptions";
+const GROQ_TRANSLATION_URL: &str = "https://api.groq.com/openai/v1/audio/translations";
 const DEFAULT_TIMEOUT_SECONDS: u64 = 30;
 const DEFAULT_MAX_RETRIES: u32 = 3;
 const RETRY_BASE_DELAY_MS: u64 = 1000;
 
 /// Groq API transcription provider
+#[derive(Clone)]
 pub struct GroqProvider {
     model: String,
     client: reqwest::Client,
     timeout: Duration,
-    max_retries: u32,
+    retry: RetryPolicy,
 }
 
 impl GroqProvider {
@@ -44,10 +49,20 @@ impl GroqProvider {
             model: model.unwrap_or_else(|| "whisper-large-v3".to_string()),
             client,
             timeout,
-            max_retries,
+            retry: RetryPolicy {
+                max_retries,
+                base_delay: Duration::from_millis(RETRY_BASE_DELAY_MS),
+                ..RetryPolicy::default()
+            },
         }
     }
 
+    /// Set the retry policy
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     /// Get API key from secure storage
     fn get_api_key(&self) -> Option<String> {
         SecretsManager::get_groq_api_key().ok()
@@ -60,13 +75,29 @@ impl GroqProvider {
 
     /// Get the max retries
     pub fn max_retries(&self) -> u32 {
-        self.max_retries
-    }
-
-    /// Calculate delay for retry with exponential backoff
-    fn retry_delay(attempt: u32) -> Duration {
-        let delay_ms = RETRY_BASE_DELAY_MS * 2u64.pow(attempt);
-        Duration::from_millis(delay_ms.min(30_000)) // Cap at 30 seconds
+        self.retry.max_retries
+    }
+
+    /// Resolve DNS and open the TLS session ahead of a transcription
+    ///
+    /// The connection stays in the client's pool, so a transcription sent
+    /// shortly after skips the handshake. Any HTTP response counts as success.
+    pub async fn warm_up(&self) -> Result<(), TranscriptionError> {
+        self.client
+            .head(GROQ_API_URL)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|e| TranscriptionError::NetworkError(e.to_string()))
+    }
+
+    /// Delay before retry `attempt` with the default policy, without jitter
+    pub fn retry_delay(attempt: u32) -> Duration {
+        RetryPolicy {
+            base_delay: Duration::from_millis(RETRY_BASE_DELAY_MS),
+            ..RetryPolicy::default()
+        }
+        .backoff(attempt)
     }
 }
 
@@ -83,7 +114,7 @@ impl TranscriptionProvider for GroqProvider {
 
         // Validate API key format
         if let Err(e) = SecretsManager::validate_groq_api_key(&api_key) {
-            return Err(TranscriptionError::ApiError(format!("Invalid API key: {}", e)));
+            return Err(TranscriptionError::Unauthorized(e.to_string()));
         }
 
         let start = Instant::now();
@@ -92,15 +123,19 @@ impl TranscriptionProvider for GroqProvider {
         let wav_data = encode_wav(audio, 16000, 1);
 
         let mut last_error: Option<TranscriptionError> = None;
+        let mut retry_after: Option<Duration> = None;
 
         // Retry loop with exponential backoff
-        for attempt in 0..=self.max_retries {
+        for attempt in 0..=self.retry.max_retries {
             if attempt > 0 {
-                let delay = Self::retry_delay(attempt - 1);
+                let Some(delay) = self.retry.delay(attempt - 1, retry_after.take()) else {
+                    tracing::warn!("Groq API asked to wait longer than the retry policy allows");
+                    break;
+                };
                 tracing::info!(
                     "Retrying Groq API request (attempt {}/{}) after {:?}",
                     attempt + 1,
-                    self.max_retries + 1,
+                    self.retry.max_retries + 1,
                     delay
                 );
                 tokio::time::sleep(delay).await;
@@ -118,17 +153,20 @@ impl TranscriptionProvider for GroqProvider {
             let mut form = reqwest::multipart::Form::new()
                 .part("file", file_part)
                 .text("model", self.model.clone())
-                .text("response_format", "json");
+                .text("response_format", "verbose_json");
 
-            // Add language if specified
-            if config.language != "auto" {
+            // Add language if specified (translations always produce English)
+            if config.language != "auto" && !config.translate {
                 form = form.text("language", config.language.clone());
             }
+            if let Some(prompt) = &config.prompt {
+                form = form.text("prompt", prompt.clone());
+            }
 
             // Make API request
             let response = match self
                 .client
-                .post(GROQ_API_URL)
+                .post(if config.translate { GROQ_TRANSLATION_URL } else { GROQ_API_URL })
                 .bearer_auth(&api_key)
                 .multipart(form)
                 .send()
@@ -145,7 +183,8 @@ impl TranscriptionProvider for GroqProvider {
 
             // Check for rate limiting (retryable)
             if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
-                tracing::warn!("Groq API rate limited");
+                retry_after = parse_retry_after(response.headers());
+                tracing::warn!("Groq API rate limited (retry after {:?})", retry_after);
                 last_error = Some(TranscriptionError::RateLimited);
                 continue;
             }
@@ -163,10 +202,14 @@ impl TranscriptionProvider for GroqProvider {
 
             // Check for client errors (4xx - not retryable except rate limit)
             if !response.status().is_success() {
+                let status = response.status();
                 let error_text = response
                     .text()
                     .await
                     .unwrap_or_else(|_| "Unknown error".to_string());
+                if status == reqwest::StatusCode::UNAUTHORIZED {
+                    return Err(TranscriptionError::Unauthorized(error_text));
+                }
                 return Err(TranscriptionError::ApiError(error_text));
             }
 
@@ -191,6 +234,8 @@ impl TranscriptionProvider for GroqProvider {
                 language: None,
                 duration_ms,
                 provider: "groq".to_string(),
+                segments: result.segments.into_iter().map(Into::into).collect(),
+                model: None,
             });
         }
 
@@ -216,10 +261,30 @@ impl TranscriptionProvider for GroqProvider {
     }
 }
 
-/// Groq API response
+/// Groq API response (`verbose_json` adds the segments)
 #[derive(serde::Deserialize)]
 struct GroqResponse {
     text: String,
+    #[serde(default)]
+    segments: Vec<GroqSegment>,
+}
+
+/// Timed segment of a Groq response (times in seconds)
+#[derive(serde::Deserialize)]
+struct GroqSegment {
+    start: f64,
+    end: f64,
+    text: String,
+}
+
+impl From<GroqSegment> for TranscriptSegment {
+    fn from(segment: GroqSegment) -> Self {
+        Self {
+            start_ms: (segment.start.max(0.0) * 1000.0).round() as u64,
+            end_ms: (segment.end.max(0.0) * 1000.0).round() as u64,
+            text: segment.text.trim().to_string(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -486,6 +551,20 @@ mod tests {
         assert_eq!(response.unwrap().text, "Line 1\nLine 2");
     }
 
+    #[test]
+    fn test_groq_response_with_segments() {
+        let json = r#"{"text": " Hi. Bye.", "segments": [
+            {"id": 0, "start": 0.0, "end": 1.24, "text": " Hi."},
+            {"id": 1, "start": 1.24, "end": 2.5, "text": " Bye."}
+        ]}"#;
+        let response: GroqResponse = serde_json::from_str(json).unwrap();
+        let segments: Vec<TranscriptSegment> =
+            response.segments.into_iter().map(Into::into).collect();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(segments[0].end_ms, 1240);
+        assert_eq!(segments[1].text, "Bye.");
+    }
+
     #[test]
     fn test_groq_response_missing_text_field() {
         let json = r#"{"error": "something went wrong"}"#;
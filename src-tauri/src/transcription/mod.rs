@@ -2,16 +2,36 @@
 //!
 //! Speech-to-text using local or cloud providers.
 
+mod batch;
+mod capabilities;
+mod cleanup;
+mod custom_endpoint;
+mod deepgram;
+mod file_queue;
 mod groq;
 mod orchestrator;
+mod pipeline;
+mod prefetch;
 mod provider;
+mod replay;
+mod retry;
 mod service;
 mod streaming;
 mod whisper;
 
+pub use batch::*;
+pub use capabilities::*;
+pub use cleanup::*;
+pub use custom_endpoint::*;
+pub use deepgram::*;
+pub use file_queue::*;
 pub use groq::*;
 pub use orchestrator::*;
+pub use pipeline::*;
+pub use prefetch::*;
 pub use provider::*;
+pub use replay::*;
+pub use retry::*;
 pub use service::*;
 pub use streaming::*;
 pub use whisper::*;
@@ -2,12 +2,16 @@
 //!
 //! Manages provider selection and fallback logic.
 
-use super::{TranscriptionConfig, TranscriptionError, TranscriptionProvider, TranscriptionResult};
+use super::{
+    ProviderFailure, TranscriptionConfig, TranscriptionError, TranscriptionProvider,
+    TranscriptionResult,
+};
 
 /// Orchestrates transcription across multiple providers
 pub struct TranscriptionOrchestrator {
     primary: Box<dyn TranscriptionProvider>,
-    fallback: Option<Box<dyn TranscriptionProvider>>,
+    /// Tried in order when the primary provider fails
+    fallbacks: Vec<Box<dyn TranscriptionProvider>>,
 }
 
 impl TranscriptionOrchestrator {
@@ -15,7 +19,7 @@ impl TranscriptionOrchestrator {
     pub fn new(primary: Box<dyn TranscriptionProvider>) -> Self {
         Self {
             primary,
-            fallback: None,
+            fallbacks: Vec::new(),
         }
     }
 
@@ -24,25 +28,46 @@ impl TranscriptionOrchestrator {
         primary: Box<dyn TranscriptionProvider>,
         fallback: Box<dyn TranscriptionProvider>,
     ) -> Self {
-        Self {
-            primary,
-            fallback: Some(fallback),
-        }
+        Self::with_fallbacks(primary, vec![fallback])
+    }
+
+    /// Create orchestrator with fallback providers in priority order
+    pub fn with_fallbacks(
+        primary: Box<dyn TranscriptionProvider>,
+        fallbacks: Vec<Box<dyn TranscriptionProvider>>,
+    ) -> Self {
+        Self { primary, fallbacks }
     }
 
-    /// Transcribe audio using primary provider with optional fallback
+    /// Transcribe audio using primary provider, then each available fallback
+    ///
+    /// Returns the error of the last provider tried when all of them fail.
     pub async fn transcribe(
         &self,
         audio: &[f32],
         config: &TranscriptionConfig,
     ) -> Result<TranscriptionResult, TranscriptionError> {
+        self.try_transcribe(audio, config)
+            .await
+            .map_err(|failure| failure.error)
+    }
+
+    /// Like `transcribe`, also naming the provider that failed last
+    pub async fn try_transcribe(
+        &self,
+        audio: &[f32],
+        config: &TranscriptionConfig,
+    ) -> Result<TranscriptionResult, ProviderFailure> {
         // Validate audio
         if audio.is_empty() {
-            return Err(TranscriptionError::InvalidAudio("Empty audio".to_string()));
+            return Err(ProviderFailure {
+                provider: self.primary.name(),
+                error: TranscriptionError::InvalidAudio("Empty audio".to_string()),
+            });
         }
 
         // Try primary provider
-        match self.primary.transcribe(audio, config).await {
+        let mut failure = match self.primary.transcribe(audio, config).await {
             Ok(result) => {
                 tracing::info!(
                     "Transcription completed by {} in {}ms",
@@ -51,21 +76,38 @@ impl TranscriptionOrchestrator {
                 );
                 return Ok(result);
             }
-            Err(e) => {
-                tracing::warn!("Primary provider {} failed: {}", self.primary.name(), e);
-
-                // Try fallback if available
-                if let Some(fallback) = &self.fallback {
-                    if fallback.is_available() {
-                        tracing::info!("Attempting fallback provider: {}", fallback.name());
-                        return fallback.transcribe(audio, config).await;
-                    }
+            Err(error) => {
+                tracing::warn!("Primary provider {} failed: {}", self.primary.name(), error);
+                ProviderFailure {
+                    provider: self.primary.name(),
+                    error,
                 }
+            }
+        };
 
-                // No fallback available, propagate error
-                return Err(e);
+        // Try fallbacks in order, skipping unconfigured ones
+        for fallback in self.fallbacks.iter().filter(|p| p.is_available()) {
+            tracing::info!("Attempting fallback provider: {}", fallback.name());
+            match fallback.transcribe(audio, config).await {
+                Ok(result) => {
+                    tracing::info!(
+                        "Transcription completed by fallback {} in {}ms",
+                        result.provider,
+                        result.duration_ms
+                    );
+                    return Ok(result);
+                }
+                Err(error) => {
+                    tracing::warn!("Fallback provider {} failed: {}", fallback.name(), error);
+                    failure = ProviderFailure {
+                        provider: fallback.name(),
+                        error,
+                    };
+                }
             }
         }
+
+        Err(failure)
     }
 
     /// Get primary provider name
@@ -73,9 +115,14 @@ impl TranscriptionOrchestrator {
         self.primary.name()
     }
 
-    /// Get fallback provider name if configured
+    /// Get first fallback provider name if configured
     pub fn fallback_provider(&self) -> Option<&'static str> {
-        self.fallback.as_ref().map(|p| p.name())
+        self.fallbacks.first().map(|p| p.name())
+    }
+
+    /// Get all fallback provider names, in priority order
+    pub fn fallback_providers(&self) -> Vec<&'static str> {
+        self.fallbacks.iter().map(|p| p.name()).collect()
     }
 
     /// Check if primary provider is available
@@ -83,9 +130,9 @@ impl TranscriptionOrchestrator {
         self.primary.is_available()
     }
 
-    /// Check if fallback provider is available
+    /// Check if any fallback provider is available
     pub fn is_fallback_available(&self) -> bool {
-        self.fallback.as_ref().map(|p| p.is_available()).unwrap_or(false)
+        self.fallbacks.iter().any(|p| p.is_available())
     }
 }
 
@@ -170,6 +217,8 @@ mod tests {
                     language: Some("en".to_string()),
                     duration_ms: 100,
                     provider: self.name.to_string(),
+                    segments: Vec::new(),
+                    model: None,
                 })
             }
         }
@@ -452,6 +501,60 @@ mod tests {
         assert_eq!(result.unwrap().provider, "whisper.cpp");
     }
 
+    #[tokio::test]
+    async fn test_fallback_chain_tried_in_order() {
+        let second_calls = Arc::new(AtomicU32::new(0));
+        let third_calls = Arc::new(AtomicU32::new(0));
+
+        let orchestrator = TranscriptionOrchestrator::with_fallbacks(
+            Box::new(MockProvider::new("groq").failing()),
+            vec![
+                Box::new(MockProvider::new("deepgram").available(false)),
+                Box::new(
+                    MockProvider::new("custom")
+                        .failing()
+                        .with_call_counter(second_calls.clone()),
+                ),
+                Box::new(MockProvider::new("whisper.cpp").with_call_counter(third_calls.clone())),
+            ],
+        );
+
+        let result = orchestrator
+            .transcribe(&[0.0; 100], &TranscriptionConfig::default())
+            .await;
+
+        assert_eq!(result.unwrap().provider, "whisper.cpp");
+        assert_eq!(second_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(third_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            orchestrator.fallback_providers(),
+            vec!["deepgram", "custom", "whisper.cpp"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fallback_chain_returns_last_error() {
+        let orchestrator = TranscriptionOrchestrator::with_fallbacks(
+            Box::new(MockProvider::new("groq").with_error(TranscriptionError::RateLimited)),
+            vec![
+                Box::new(MockProvider::new("custom").with_error(TranscriptionError::Timeout(60))),
+                Box::new(MockProvider::new("whisper.cpp").available(false)),
+            ],
+        );
+
+        let result = orchestrator
+            .transcribe(&[0.0; 100], &TranscriptionConfig::default())
+            .await;
+
+        assert!(matches!(result.unwrap_err(), TranscriptionError::Timeout(60)));
+
+        let failure = orchestrator
+            .try_transcribe(&[0.0; 100], &TranscriptionConfig::default())
+            .await
+            .unwrap_err();
+        assert_eq!(failure.provider, "custom");
+    }
+
     // ============================================================
     // Timeout Handling Tests
     // ============================================================
@@ -484,6 +587,7 @@ mod tests {
         let config = TranscriptionConfig {
             language: "fr".to_string(),
             translate: false,
+            ..Default::default()
         };
 
         let result = orchestrator.transcribe(&[0.0; 100], &config).await;
@@ -497,6 +601,7 @@ mod tests {
         let config = TranscriptionConfig {
             language: "auto".to_string(),
             translate: true,
+            ..Default::default()
         };
 
         let result = orchestrator.transcribe(&[0.0; 100], &config).await;
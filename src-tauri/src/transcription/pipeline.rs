@@ -0,0 +1,888 @@
+//! Processing Pipeline
+//!
+//! Runs the configured audio stages ahead of transcription and records how
+//! long each stage took, so slow or misconfigured stages can be spotted on
+//! the diagnostics screen.
+
+use crate::audio::{
+    apply_gain, denoise_rnnoise, filter_by_speaker, peak_window_db, resample, resample_chunks,
+    suppress_noise, SileroModel, SpeakerEmbedding, SpeakerModel, SpeechMap, VadConfig,
+    VoiceActivityDetector,
+};
+use crate::config::{
+    NoiseSuppressor, OutputSettings, PipelineStage, PipelineStageSettings, SecretsManager,
+    Settings, TranscriptionSettings, VadEngine,
+};
+use crate::utils::StageTimings;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+/// Sample rate Whisper expects
+pub const WHISPER_SAMPLE_RATE: u32 = 16000;
+
+/// Minimum audio length after resampling (100ms)
+const MIN_SAMPLES: usize = 1600;
+
+/// Number of traces kept for inspection
+const MAX_TRACES: usize = 20;
+
+/// Resampled audio handed to VAD classification at a time (250ms)
+const OVERLAP_BATCH_SAMPLES: usize = 4000;
+
+/// Outcome of one stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum StageStatus {
+    Ran,
+    Skipped,
+    Failed,
+}
+
+/// Timing of one stage
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct StageTrace {
+    pub stage: PipelineStage,
+    pub status: StageStatus,
+    pub duration_ms: f64,
+    /// What the stage did or why it was skipped
+    pub detail: Option<String>,
+}
+
+/// Per-stage timings of one recording
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PipelineTrace {
+    pub timestamp: String,
+    pub profile: Option<String>,
+    pub stages: Vec<StageTrace>,
+    pub total_ms: f64,
+    /// Time saved by classifying VAD frames while resampling
+    pub overlap_saved_ms: f64,
+    /// Error that ended the pipeline, if any
+    pub error: Option<String>,
+}
+
+impl PipelineTrace {
+    pub fn new(profile: Option<&str>) -> Self {
+        Self {
+            timestamp: crate::history::chrono_timestamp(),
+            profile: profile.map(str::to_string),
+            stages: Vec::new(),
+            total_ms: 0.0,
+            overlap_saved_ms: 0.0,
+            error: None,
+        }
+    }
+
+    /// Record a stage that started at `started`
+    pub fn record(
+        &mut self,
+        stage: PipelineStage,
+        status: StageStatus,
+        started: Instant,
+        detail: Option<String>,
+    ) {
+        let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+        self.total_ms += duration_ms;
+        self.stages.push(StageTrace {
+            stage,
+            status,
+            duration_ms,
+            detail,
+        });
+    }
+
+    /// Stage durations as metrics timings (capture and injection are filled in by the caller)
+    pub fn timings(&self) -> StageTimings {
+        let mut timings = StageTimings {
+            overlap_saved_ms: self.overlap_saved_ms.round() as u64,
+            ..StageTimings::default()
+        };
+        for trace in &self.stages {
+            let ms = trace.duration_ms.round() as u64;
+            match trace.stage {
+                PipelineStage::Resample => timings.resample_ms += ms,
+                PipelineStage::Gain
+                | PipelineStage::NoiseSuppression
+                | PipelineStage::SpeakerFilter => timings.filters_ms += ms,
+                PipelineStage::Vad => timings.vad_ms += ms,
+                PipelineStage::Transcribe => timings.inference_ms += ms,
+                PipelineStage::PostProcess => timings.post_process_ms += ms,
+            }
+        }
+        timings
+    }
+
+    /// Record a stage that did not run
+    pub fn skip(&mut self, stage: PipelineStage, reason: &str) {
+        self.stages.push(StageTrace {
+            stage,
+            status: StageStatus::Skipped,
+            duration_ms: 0.0,
+            detail: Some(reason.to_string()),
+        });
+    }
+}
+
+/// Settings for the VAD stage
+pub struct VadStage {
+    /// VAD configuration (None = VAD disabled in settings)
+    pub config: Option<VadConfig>,
+    /// Calibrated noise gate (dBFS)
+    pub noise_gate_db: Option<f32>,
+    /// Silero model when that engine is selected and available
+    pub silero: Option<Arc<SileroModel>>,
+}
+
+impl VadStage {
+    /// VAD stage for the configured input device
+    pub fn from_settings(config: &Settings) -> Self {
+        let input_device = config.audio.input_device.as_deref();
+        let vad_settings = config.audio.vad.effective_for(input_device);
+        Self {
+            config: vad_settings
+                .enabled
+                .then(|| VadConfig::from_settings(&vad_settings)),
+            noise_gate_db: config
+                .audio
+                .vad
+                .calibration_for(input_device)
+                .map(|calibration| calibration.energy_threshold_db),
+            silero: (vad_settings.enabled && vad_settings.engine == VadEngine::Silero)
+                .then(load_silero)
+                .flatten(),
+        }
+    }
+
+    /// Detector for `config` using the selected engine
+    pub fn detector(&self, config: &VadConfig) -> VoiceActivityDetector {
+        match &self.silero {
+            Some(model) => VoiceActivityDetector::with_silero(config.clone(), model.clone()),
+            None => VoiceActivityDetector::with_config(config.clone()),
+        }
+    }
+}
+
+/// Silero VAD model, or None to fall back to WebRTC VAD
+fn load_silero() -> Option<Arc<SileroModel>> {
+    let path = crate::models::silero_vad_path();
+    if !path.exists() {
+        tracing::warn!("Silero VAD model not downloaded yet, using WebRTC VAD");
+        return None;
+    }
+    SileroModel::load_cached(&path)
+        .map_err(|e| tracing::warn!("{}, using WebRTC VAD", e))
+        .ok()
+}
+
+/// Settings for the speaker filter stage
+pub struct SpeakerStage {
+    /// Whether the filter is turned on in settings
+    pub enabled: bool,
+    /// Enrolled voice (None = no profile recorded yet)
+    pub profile: Option<SpeakerEmbedding>,
+    /// Speaker-embedding model (None = not downloaded yet)
+    pub model: Option<Arc<SpeakerModel>>,
+    /// Minimum similarity for a segment to be kept
+    pub threshold: f32,
+}
+
+impl SpeakerStage {
+    /// Speaker filter stage for the enrolled voice profile
+    ///
+    /// The profile is read from the credential store, and only when the
+    /// filter is on.
+    pub fn from_settings(config: &Settings) -> Self {
+        let speaker = &config.audio.speaker_filter;
+        let active = speaker.enabled && speaker.profile.is_some();
+        Self {
+            enabled: speaker.enabled,
+            profile: active.then(load_voice_profile).flatten(),
+            model: active.then(load_speaker_model).flatten(),
+            threshold: speaker.similarity_threshold(),
+        }
+    }
+}
+
+/// Enrolled speaker embedding from the credential store
+fn load_voice_profile() -> Option<SpeakerEmbedding> {
+    SecretsManager::get_voice_profile()
+        .map_err(|e| tracing::warn!("Voice profile unavailable: {}", e))
+        .ok()
+        .and_then(SpeakerEmbedding::normalized)
+}
+
+/// Speaker-embedding model, or None to skip the speaker filter
+fn load_speaker_model() -> Option<Arc<SpeakerModel>> {
+    let path = crate::models::speaker_model_path();
+    if !path.exists() {
+        tracing::warn!("Speaker model not downloaded yet, skipping the speaker filter");
+        return None;
+    }
+    SpeakerModel::load_cached(&path)
+        .map_err(|e| tracing::warn!("{}, skipping the speaker filter", e))
+        .ok()
+}
+
+/// Audio ready for transcription
+pub struct PreparedAudio {
+    /// 16 kHz audio as it entered the VAD stage (kept in history)
+    pub full: Vec<f32>,
+    /// Audio passed to the transcriber
+    pub speech: Vec<f32>,
+    /// Where the speech sits in `full`
+    pub kept: SpeechMap,
+}
+
+impl PreparedAudio {
+    /// Overwrite the audio buffers with zeros and release them
+    pub fn wipe(&mut self) {
+        self.full.zeroize();
+        self.speech.zeroize();
+    }
+}
+
+/// Samples between audio stages, overwritten with zeros when a stage
+/// replaces them or the pipeline stops, if `wipe` is set
+struct StageSamples {
+    samples: Vec<f32>,
+    wipe: bool,
+}
+
+impl StageSamples {
+    fn new(samples: Vec<f32>, wipe: bool) -> Self {
+        Self { samples, wipe }
+    }
+
+    /// Zeroize the samples when wiping, keeping the buffer allocated
+    fn wipe(&mut self) {
+        if self.wipe {
+            self.samples.zeroize();
+        }
+    }
+
+    /// Take the output of a stage
+    fn replace(&mut self, samples: Vec<f32>) {
+        self.wipe();
+        self.samples = samples;
+    }
+
+    fn into_inner(mut self) -> Vec<f32> {
+        std::mem::take(&mut self.samples)
+    }
+}
+
+impl std::ops::Deref for StageSamples {
+    type Target = Vec<f32>;
+
+    fn deref(&self) -> &Vec<f32> {
+        &self.samples
+    }
+}
+
+impl std::ops::DerefMut for StageSamples {
+    fn deref_mut(&mut self) -> &mut Vec<f32> {
+        &mut self.samples
+    }
+}
+
+impl Drop for StageSamples {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+/// Run the audio stages of `pipeline` in order
+///
+/// With `wipe` (no-disk-audio and incognito recordings), each buffer a stage
+/// replaces is zeroized, and so is all audio when a stage fails.
+#[allow(clippy::too_many_arguments)]
+pub fn run_audio_stages(
+    pipeline: &[PipelineStageSettings],
+    samples: Vec<f32>,
+    sample_rate: u32,
+    suppressor: NoiseSuppressor,
+    vad: &VadStage,
+    speaker: &SpeakerStage,
+    wipe: bool,
+    trace: &mut PipelineTrace,
+) -> Result<PreparedAudio, String> {
+    let mut samples = StageSamples::new(samples, wipe);
+    let mut sample_rate = sample_rate;
+    let mut full = None;
+    // Samples of `full` kept by the VAD and later filters
+    let mut kept = None;
+    // VAD decisions made while resampling
+    let mut vad_frames = None;
+    // Loudness before gain, which the calibrated noise gate was measured on
+    let mut ungained_peak_db = None;
+
+    let stages: Vec<&PipelineStageSettings> =
+        pipeline.iter().filter(|s| s.stage.is_audio()).collect();
+    for (index, settings) in stages.iter().enumerate() {
+        let stage = settings.stage;
+        if !settings.enabled {
+            trace.skip(stage, "disabled");
+            continue;
+        }
+
+        let started = Instant::now();
+        let detail = match stage {
+            PipelineStage::Resample => {
+                let from = sample_rate;
+                let vad_next = stages[index + 1..]
+                    .iter()
+                    .find(|s| s.enabled)
+                    .is_some_and(|s| s.stage == PipelineStage::Vad);
+                let overlap = vad.config.as_ref().filter(|_| vad_next);
+
+                let mut detail = format!("{} Hz -> {} Hz", from, WHISPER_SAMPLE_RATE);
+                if from != WHISPER_SAMPLE_RATE {
+                    let resampled = match overlap {
+                        Some(config) => {
+                            let detector = vad.detector(config);
+                            resample_with_vad(&samples, from, &detector).map(
+                                |(audio, frames, saved)| {
+                                    vad_frames = Some(frames);
+                                    trace.overlap_saved_ms += saved.as_secs_f64() * 1000.0;
+                                    detail.push_str(", overlapped with VAD");
+                                    audio
+                                },
+                            )
+                        }
+                        None => resample(&samples, from, WHISPER_SAMPLE_RATE),
+                    };
+                    samples.replace(resampled.map_err(|e| {
+                        trace.record(stage, StageStatus::Failed, started, Some(e.clone()));
+                        format!("Resampling failed: {}", e)
+                    })?);
+                    sample_rate = WHISPER_SAMPLE_RATE;
+                }
+                if samples.len() < MIN_SAMPLES {
+                    trace.record(stage, StageStatus::Failed, started, None);
+                    return Err("Recording too short".to_string());
+                }
+                Some(detail)
+            }
+            PipelineStage::Gain => {
+                if vad.noise_gate_db.is_some() && ungained_peak_db.is_none() {
+                    ungained_peak_db = Some(peak_window_db(&samples, sample_rate));
+                }
+                let gain = apply_gain(&mut samples);
+                Some(format!("x{:.2}", gain))
+            }
+            PipelineStage::NoiseSuppression => match suppressor {
+                NoiseSuppressor::Filter => {
+                    suppress_noise(&mut samples, sample_rate);
+                    None
+                }
+                NoiseSuppressor::Rnnoise => {
+                    samples.replace(denoise_rnnoise(&samples, sample_rate).map_err(|e| {
+                        trace.record(stage, StageStatus::Failed, started, Some(e.clone()));
+                        format!("Noise suppression failed: {}", e)
+                    })?);
+                    Some("RNNoise".to_string())
+                }
+            },
+            PipelineStage::Vad => {
+                full = Some(StageSamples::new(samples.to_vec(), wipe));
+                kept = Some(SpeechMap::identity(samples.len()));
+                let Some(config) = &vad.config else {
+                    trace.skip(stage, "VAD disabled in settings");
+                    continue;
+                };
+
+                // Drop recordings that never rise above the calibrated noise gate
+                if let Some(threshold) = vad.noise_gate_db {
+                    let peak_db =
+                        ungained_peak_db.unwrap_or_else(|| peak_window_db(&samples, sample_rate));
+                    if peak_db < threshold {
+                        tracing::info!(
+                            "Recording peak {:.1} dB below calibrated threshold {:.1} dB",
+                            peak_db,
+                            threshold
+                        );
+                        let detail = Some("below noise gate".to_string());
+                        trace.record(stage, StageStatus::Failed, started, detail);
+                        return Err("No speech detected in recording".to_string());
+                    }
+                }
+
+                let detector = vad.detector(config);
+                let result = match vad_frames.take() {
+                    Some(frames) => Ok(detector.extract_speech(&samples, sample_rate, &frames)),
+                    None => detector.filter_speech(&samples, sample_rate),
+                };
+                match result {
+                    Ok(mut result) => {
+                        tracing::info!(
+                            "VAD filtered: {:.1}% speech ({} segments), {}ms -> {}ms",
+                            result.speech_percentage,
+                            result.speech_segments,
+                            result.original_duration_ms,
+                            result.speech_duration_ms
+                        );
+
+                        // If no speech detected, return early
+                        if result.audio.is_empty() || result.speech_percentage < 1.0 {
+                            if wipe {
+                                result.audio.zeroize();
+                            }
+                            let detail = Some("no speech".to_string());
+                            trace.record(stage, StageStatus::Failed, started, detail);
+                            return Err("No speech detected in recording".to_string());
+                        }
+
+                        samples.replace(result.audio);
+                        kept = Some(result.kept);
+                        Some(format!(
+                            "{}ms -> {}ms",
+                            result.original_duration_ms, result.speech_duration_ms
+                        ))
+                    }
+                    Err(e) => {
+                        tracing::warn!("VAD failed, using full audio: {}", e);
+                        trace.record(stage, StageStatus::Failed, started, Some(e.to_string()));
+                        continue;
+                    }
+                }
+            }
+            PipelineStage::SpeakerFilter => {
+                if !speaker.enabled {
+                    trace.skip(stage, "speaker filter disabled in settings");
+                    continue;
+                }
+                let Some(profile) = &speaker.profile else {
+                    trace.skip(stage, "no voice profile enrolled");
+                    continue;
+                };
+                let Some(model) = &speaker.model else {
+                    trace.skip(stage, "speaker model not available");
+                    continue;
+                };
+                if profile.dim() != model.dim() {
+                    trace.skip(stage, "voice profile recorded with another model");
+                    continue;
+                }
+
+                let result = filter_by_speaker(&samples, profile, speaker.threshold, |segment| {
+                    model
+                        .embed(segment)
+                        .map_err(|e| tracing::warn!("{}, keeping segment", e))
+                        .ok()
+                        .flatten()
+                });
+                tracing::info!(
+                    "Speaker filter: {} segments kept, {} dropped",
+                    result.segments_kept,
+                    result.segments_dropped
+                );
+                if result.audio.is_empty() {
+                    let detail = Some("no enrolled speaker".to_string());
+                    trace.record(stage, StageStatus::Failed, started, detail);
+                    return Err("No speech from the enrolled speaker detected".to_string());
+                }
+
+                samples.replace(result.audio);
+                kept = kept.map(|kept| kept.then(&result.kept));
+                Some(format!(
+                    "{} kept, {} dropped",
+                    result.segments_kept, result.segments_dropped
+                ))
+            }
+            PipelineStage::Transcribe | PipelineStage::PostProcess => continue,
+        };
+        trace.record(stage, StageStatus::Ran, started, detail);
+    }
+
+    Ok(PreparedAudio {
+        kept: kept.unwrap_or_else(|| SpeechMap::identity(samples.len())),
+        full: full.map_or_else(|| samples.to_vec(), StageSamples::into_inner),
+        speech: samples.into_inner(),
+    })
+}
+
+/// Resample to 16 kHz on a worker thread while classifying the VAD frames
+/// of the audio resampled so far
+///
+/// Returns the resampled audio, the frame decisions and the time saved over
+/// running both steps one after the other.
+fn resample_with_vad(
+    samples: &[f32],
+    from: u32,
+    detector: &VoiceActivityDetector,
+) -> Result<(Vec<f32>, Vec<bool>, Duration), String> {
+    let mut classifier = detector
+        .classifier(WHISPER_SAMPLE_RATE)
+        .map_err(|e| e.to_string())?;
+    let started = Instant::now();
+    let (tx, rx) = std::sync::mpsc::channel::<Vec<f32>>();
+
+    std::thread::scope(|scope| {
+        let worker = scope.spawn(move || {
+            let started = Instant::now();
+            let mut batch = Vec::with_capacity(OVERLAP_BATCH_SAMPLES);
+            let result = resample_chunks(samples, from, WHISPER_SAMPLE_RATE, |chunk| {
+                batch.extend_from_slice(chunk);
+                if batch.len() >= OVERLAP_BATCH_SAMPLES {
+                    let _ = tx.send(std::mem::take(&mut batch));
+                }
+            });
+            if !batch.is_empty() {
+                let _ = tx.send(batch);
+            }
+            result.map(|()| started.elapsed())
+        });
+
+        // Ends when the worker is done and drops the sender
+        let mut resampled = Vec::with_capacity(
+            (samples.len() as u64 * WHISPER_SAMPLE_RATE as u64 / from.max(1) as u64) as usize,
+        );
+        let mut classify_time = Duration::ZERO;
+        for batch in rx {
+            let classify_started = Instant::now();
+            classifier.push(&batch);
+            classify_time += classify_started.elapsed();
+            resampled.extend_from_slice(&batch);
+        }
+
+        let resample_time = worker
+            .join()
+            .map_err(|_| "Resampling thread panicked".to_string())??;
+        let saved = (resample_time + classify_time).saturating_sub(started.elapsed());
+        Ok((resampled, classifier.finish(), saved))
+    })
+}
+
+/// Apply spoken commands, vocabulary rules, learned capitalization,
+/// typography and casing to a transcription
+///
+/// `language` picks the voice commands and typography conventions (None =
+/// unknown). The casing transform comes last.
+pub fn post_process(text: &str, settings: &OutputSettings, language: Option<&str>) -> String {
+    let text = crate::output::apply_voice_commands(text, &settings.voice_commands, language);
+    let text = crate::vocabulary::apply_rules(&text);
+    let text = if settings.capitalize_learned_terms {
+        crate::vocabulary::capitalize_learned(&text)
+    } else {
+        text
+    };
+    let text = crate::output::apply_typography(&text, &settings.typography, language);
+    crate::output::apply_casing(&text, settings.casing)
+}
+
+/// Language of a transcription's text
+///
+/// English when translated, else the configured language, else the one the
+/// provider detected.
+pub fn transcript_language<'a>(
+    settings: &'a TranscriptionSettings,
+    detected: Option<&'a str>,
+) -> Option<&'a str> {
+    if settings.translate {
+        Some("en")
+    } else if settings.language != "auto" {
+        Some(&settings.language)
+    } else {
+        detected
+    }
+}
+
+/// Traces of the most recent recordings, newest last
+pub fn pipeline_traces() -> &'static Mutex<VecDeque<PipelineTrace>> {
+    static TRACES: OnceLock<Mutex<VecDeque<PipelineTrace>>> = OnceLock::new();
+    TRACES.get_or_init(|| Mutex::new(VecDeque::with_capacity(MAX_TRACES)))
+}
+
+/// Keep a finished trace for inspection
+pub fn record_trace(trace: PipelineTrace) {
+    let mut traces = pipeline_traces().lock();
+    if traces.len() == MAX_TRACES {
+        traces.pop_front();
+    }
+    traces.push_back(trace);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NO_VAD: VadStage = VadStage {
+        config: None,
+        noise_gate_db: None,
+        silero: None,
+    };
+
+    const NO_SPEAKER: SpeakerStage = SpeakerStage {
+        enabled: false,
+        profile: None,
+        model: None,
+        threshold: 0.0,
+    };
+
+    fn tone(samples: usize) -> Vec<f32> {
+        (0..samples).map(|i| 0.1 * (i as f32 * 0.1).sin()).collect()
+    }
+
+    #[test]
+    fn test_default_pipeline_trace() {
+        let pipeline = PipelineStage::default_pipeline();
+        let mut trace = PipelineTrace::new(None);
+
+        let audio = run_audio_stages(
+            &pipeline,
+            tone(16000),
+            16000,
+            NoiseSuppressor::Filter,
+            &NO_VAD,
+            &NO_SPEAKER,
+            false,
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(audio.speech.len(), 16000);
+
+        let stages: Vec<(PipelineStage, StageStatus)> =
+            trace.stages.iter().map(|s| (s.stage, s.status)).collect();
+        assert_eq!(
+            stages,
+            vec![
+                (PipelineStage::Resample, StageStatus::Ran),
+                (PipelineStage::Gain, StageStatus::Skipped),
+                (PipelineStage::NoiseSuppression, StageStatus::Skipped),
+                (PipelineStage::Vad, StageStatus::Skipped),
+                (PipelineStage::SpeakerFilter, StageStatus::Skipped),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stage_samples_wipe_replaced_audio() {
+        let mut samples = StageSamples::new(vec![0.5; 8], true);
+        let old = samples.as_ptr();
+        samples.wipe();
+        // Zeroizing keeps the allocation, so the old samples can be read back
+        let wiped = unsafe { std::slice::from_raw_parts(old, 8) };
+        assert!(wiped.iter().all(|&s| s == 0.0));
+        samples.replace(vec![0.25; 4]);
+        assert_eq!(samples.into_inner(), vec![0.25; 4]);
+
+        let mut kept = StageSamples::new(vec![0.5; 8], false);
+        kept.wipe();
+        assert_eq!(kept.into_inner(), vec![0.5; 8]);
+
+        let mut trace = PipelineTrace::new(None);
+        let pipeline = PipelineStage::default_pipeline();
+        let audio = run_audio_stages(
+            &pipeline,
+            tone(48000),
+            48000,
+            NoiseSuppressor::Filter,
+            &NO_VAD,
+            &NO_SPEAKER,
+            true,
+            &mut trace,
+        )
+        .unwrap();
+        assert!(audio.speech.len() > 15000);
+        assert_eq!(audio.full, audio.speech);
+    }
+
+    #[test]
+    fn test_gain_stage_runs_when_enabled() {
+        let pipeline: Vec<PipelineStageSettings> = PipelineStage::default_pipeline()
+            .into_iter()
+            .map(|mut s| {
+                s.enabled = true;
+                s
+            })
+            .collect();
+        let mut trace = PipelineTrace::new(Some("notes"));
+
+        let audio = run_audio_stages(
+            &pipeline,
+            tone(16000),
+            16000,
+            NoiseSuppressor::Filter,
+            &NO_VAD,
+            &NO_SPEAKER,
+            false,
+            &mut trace,
+        )
+        .unwrap();
+        let peak = audio.speech.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak > 0.5);
+        assert_eq!(trace.profile.as_deref(), Some("notes"));
+        assert_eq!(trace.stages[1].status, StageStatus::Ran);
+    }
+
+    #[test]
+    fn test_noise_gate_ignores_gain() {
+        let pipeline: Vec<PipelineStageSettings> = PipelineStage::default_pipeline()
+            .into_iter()
+            .map(|mut s| {
+                s.enabled |= s.stage == PipelineStage::Gain;
+                s
+            })
+            .collect();
+        let vad = VadStage {
+            config: Some(VadConfig::default()),
+            noise_gate_db: Some(-20.0),
+            silero: None,
+        };
+        let mut trace = PipelineTrace::new(None);
+
+        // Quiet enough for the gate even though gain makes it loud
+        let quiet: Vec<f32> = tone(16000).iter().map(|s| s * 0.1).collect();
+        let result = run_audio_stages(
+            &pipeline,
+            quiet,
+            16000,
+            NoiseSuppressor::Filter,
+            &vad,
+            &NO_SPEAKER,
+            false,
+            &mut trace,
+        );
+        assert_eq!(
+            result.err().as_deref(),
+            Some("No speech detected in recording")
+        );
+        let gate = trace.stages.last().unwrap();
+        assert_eq!(gate.stage, PipelineStage::Vad);
+        assert_eq!(gate.detail.as_deref(), Some("below noise gate"));
+    }
+
+    #[test]
+    fn test_rnnoise_stage_keeps_length() {
+        let pipeline: Vec<PipelineStageSettings> = PipelineStage::default_pipeline()
+            .into_iter()
+            .map(|mut s| {
+                s.enabled |= s.stage == PipelineStage::NoiseSuppression;
+                s
+            })
+            .collect();
+        let mut trace = PipelineTrace::new(None);
+
+        let audio = run_audio_stages(
+            &pipeline,
+            tone(16000),
+            16000,
+            NoiseSuppressor::Rnnoise,
+            &NO_VAD,
+            &NO_SPEAKER,
+            false,
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(audio.speech.len(), 16000);
+        let noise = &trace.stages[2];
+        assert_eq!(noise.stage, PipelineStage::NoiseSuppression);
+        assert_eq!(noise.status, StageStatus::Ran);
+        assert_eq!(noise.detail.as_deref(), Some("RNNoise"));
+    }
+
+    #[test]
+    fn test_trace_timings() {
+        let mut trace = PipelineTrace::new(None);
+        let stage = |stage, duration_ms| StageTrace {
+            stage,
+            status: StageStatus::Ran,
+            duration_ms,
+            detail: None,
+        };
+        trace.stages = vec![
+            stage(PipelineStage::Resample, 12.4),
+            stage(PipelineStage::Gain, 1.0),
+            stage(PipelineStage::NoiseSuppression, 2.0),
+            stage(PipelineStage::Transcribe, 950.6),
+        ];
+
+        let timings = trace.timings();
+        assert_eq!(timings.resample_ms, 12);
+        assert_eq!(timings.filters_ms, 3);
+        assert_eq!(timings.inference_ms, 951);
+        assert_eq!(timings.vad_ms, 0);
+    }
+
+    #[test]
+    fn test_resample_overlapped_with_vad() {
+        // Two seconds at 48 kHz: silence, then a loud tone
+        let mut samples = vec![0.0; 48000];
+        samples.extend((0..48000).map(|i| 0.5 * (i as f32 * 0.06).sin()));
+
+        let detector = VoiceActivityDetector::with_config(VadConfig::default());
+        let (audio, frames, _) = resample_with_vad(&samples, 48000, &detector).unwrap();
+
+        // Same result as resampling, then classifying
+        let sequential = resample(&samples, 48000, WHISPER_SAMPLE_RATE).unwrap();
+        let mut classifier = detector.classifier(WHISPER_SAMPLE_RATE).unwrap();
+        classifier.push(&sequential);
+        assert_eq!(audio, sequential);
+        assert_eq!(frames, classifier.finish());
+    }
+
+    #[test]
+    fn test_short_recording_fails_resample_stage() {
+        let pipeline = PipelineStage::default_pipeline();
+        let mut trace = PipelineTrace::new(None);
+
+        let result = run_audio_stages(
+            &pipeline,
+            tone(800),
+            16000,
+            NoiseSuppressor::Filter,
+            &NO_VAD,
+            &NO_SPEAKER,
+            false,
+            &mut trace,
+        );
+        assert_eq!(result.err().as_deref(), Some("Recording too short"));
+        assert_eq!(trace.stages[0].status, StageStatus::Failed);
+    }
+
+    #[test]
+    fn test_transcript_language() {
+        let mut settings = TranscriptionSettings::default();
+        assert_eq!(transcript_language(&settings, Some("de")), Some("de"));
+        assert_eq!(transcript_language(&settings, None), None);
+
+        settings.language = "fr".to_string();
+        assert_eq!(transcript_language(&settings, Some("de")), Some("fr"));
+
+        settings.translate = true;
+        assert_eq!(transcript_language(&settings, Some("de")), Some("en"));
+    }
+
+    #[test]
+    fn test_speaker_filter_without_profile_is_skipped() {
+        let pipeline = PipelineStage::default_pipeline();
+        let speaker = SpeakerStage {
+            enabled: true,
+            profile: None,
+            model: None,
+            threshold: 0.8,
+        };
+        let mut trace = PipelineTrace::new(None);
+
+        let audio = run_audio_stages(
+            &pipeline,
+            tone(16000),
+            16000,
+            NoiseSuppressor::Filter,
+            &NO_VAD,
+            &speaker,
+            false,
+            &mut trace,
+        )
+        .unwrap();
+        assert_eq!(audio.speech.len(), 16000);
+        let last = trace.stages.last().unwrap();
+        assert_eq!(last.stage, PipelineStage::SpeakerFilter);
+        assert_eq!(last.detail.as_deref(), Some("no voice profile enrolled"));
+    }
+}
@@ -0,0 +1,234 @@
+//! Warm-State Prefetch
+//!
+//! Pre-dictation signals warm up the active provider before recording
+//! starts: the local model is loaded, or the Groq DNS/TLS session is opened
+//! so the first request reuses a pooled connection. The global input
+//! listener reports the recording shortcut's modifiers being pressed, and
+//! on Windows focus moving to a text field is polled through UI Automation.
+//! The audio backends don't expose the microphone's mute state, so
+//! unmuting is not a signal.
+//!
+//! Each transcription records whether a warm-up ran ahead of it, and the
+//! metrics summary compares their inference time with the others.
+
+use crate::config::PrefetchMode;
+use crate::AppState;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{Modifiers, Shortcut};
+
+/// Minimum time between two warm-ups of the same provider
+pub const PREFETCH_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// How long a warm-up benefits the next transcription (the HTTP client
+/// drops idle pooled connections after 90 seconds)
+pub const WARM_WINDOW: Duration = Duration::from_secs(90);
+
+/// How often the focus watcher checks the focused control
+const FOCUS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Stop flag of the running focus watcher
+static FOCUS_WATCH: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
+/// User activity hinting that dictation is about to start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ActivitySignal {
+    /// Modifier of the recording shortcut is held down
+    ModifierHeld,
+    /// Focus moved to an editable text field
+    TextFieldFocused,
+}
+
+impl ActivitySignal {
+    /// Whether this signal triggers a warm-up in the given mode
+    pub fn triggers(self, mode: PrefetchMode) -> bool {
+        match mode {
+            PrefetchMode::Off => false,
+            PrefetchMode::Conservative => self == Self::ModifierHeld,
+            PrefetchMode::Aggressive => true,
+        }
+    }
+}
+
+/// Rate limiter so bursts of signals cause a single warm-up
+#[derive(Debug, Default)]
+pub struct PrefetchThrottle {
+    last: Option<Instant>,
+}
+
+impl PrefetchThrottle {
+    /// Returns true (and records the attempt) if a warm-up may run now
+    pub fn try_acquire(&mut self, now: Instant) -> bool {
+        if self
+            .last
+            .is_some_and(|last| now.duration_since(last) < PREFETCH_COOLDOWN)
+        {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
+
+/// When the last warm-up finished, until a transcription counts it
+#[derive(Debug, Default)]
+pub struct WarmState {
+    finished: Option<Instant>,
+}
+
+impl WarmState {
+    /// Record a warm-up that finished at `now`
+    pub fn warmed(&mut self, now: Instant) {
+        self.finished = Some(now);
+    }
+
+    /// Whether a warm-up finished within `WARM_WINDOW` before `now`
+    ///
+    /// Each warm-up counts for one transcription.
+    pub fn take(&mut self, now: Instant) -> bool {
+        self.finished
+            .take()
+            .is_some_and(|finished| now.duration_since(finished) <= WARM_WINDOW)
+    }
+}
+
+/// Whether `key` is one of the modifiers of the shortcut `shortcut`
+pub fn is_shortcut_modifier(shortcut: &str, key: rdev::Key) -> bool {
+    let modifier = match key {
+        rdev::Key::ControlLeft | rdev::Key::ControlRight => Modifiers::CONTROL,
+        rdev::Key::ShiftLeft | rdev::Key::ShiftRight => Modifiers::SHIFT,
+        rdev::Key::Alt | rdev::Key::AltGr => Modifiers::ALT,
+        rdev::Key::MetaLeft | rdev::Key::MetaRight => Modifiers::SUPER,
+        _ => return false,
+    };
+    shortcut
+        .parse::<Shortcut>()
+        .is_ok_and(|shortcut| shortcut.mods.contains(modifier))
+}
+
+/// Start or stop the signal sources the mode needs
+///
+/// Modifier presses come from the global input listener shared with mouse
+/// triggers. Text field focus is only watched in aggressive mode, on
+/// Windows.
+pub fn apply_prefetch_settings(app: &AppHandle, mode: PrefetchMode) {
+    crate::shortcuts::set_modifier_warmup(app, mode != PrefetchMode::Off);
+
+    let mut watch = FOCUS_WATCH.lock();
+    let wanted = mode == PrefetchMode::Aggressive && cfg!(windows);
+    match (wanted, watch.is_some()) {
+        (true, false) => *watch = start_focus_watch(app),
+        (false, true) => {
+            if let Some(stop) = watch.take() {
+                stop.store(true, Ordering::SeqCst);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Signal each move of the focus to a text field until the returned flag is set
+fn start_focus_watch(app: &AppHandle) -> Option<Arc<AtomicBool>> {
+    let stop = Arc::new(AtomicBool::new(false));
+    let stopped = stop.clone();
+    let app_handle = app.clone();
+    app.state::<AppState>()
+        .tasks
+        .spawn_thread("focus-watch", move || {
+            let mut was_editable = false;
+            while !stopped.load(Ordering::SeqCst) {
+                let editable = crate::output::focused_control_is_editable();
+                if editable && !was_editable {
+                    on_activity_signal(&app_handle, ActivitySignal::TextFieldFocused);
+                }
+                was_editable = editable;
+                std::thread::sleep(FOCUS_POLL_INTERVAL);
+            }
+        })?;
+    Some(stop)
+}
+
+/// Hook for pre-dictation signals
+///
+/// Cheap to call on every signal; warm-up runs in the background and is
+/// skipped when disabled, throttled, or while recording is in progress.
+pub fn on_activity_signal(app: &AppHandle, signal: ActivitySignal) {
+    let state = app.state::<AppState>();
+    let config = state.config.read().clone();
+
+    if !signal.triggers(config.transcription.prefetch) {
+        return;
+    }
+
+    if !matches!(
+        *state.recording_state.read(),
+        crate::RecordingState::Idle | crate::RecordingState::Error(_)
+    ) {
+        return;
+    }
+
+    let service = state.transcription_service.clone();
+    state.tasks.spawn("prefetch", async move {
+        tracing::debug!("Prefetch triggered by {:?}", signal);
+        service.prefetch(&config).await;
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signal_triggers_by_mode() {
+        assert!(!ActivitySignal::ModifierHeld.triggers(PrefetchMode::Off));
+        assert!(ActivitySignal::ModifierHeld.triggers(PrefetchMode::Conservative));
+        assert!(!ActivitySignal::TextFieldFocused.triggers(PrefetchMode::Conservative));
+        assert!(ActivitySignal::TextFieldFocused.triggers(PrefetchMode::Aggressive));
+    }
+
+    #[test]
+    fn test_is_shortcut_modifier() {
+        assert!(is_shortcut_modifier("Ctrl+Space", rdev::Key::ControlLeft));
+        assert!(is_shortcut_modifier("Ctrl+Shift+D", rdev::Key::ShiftRight));
+        assert!(!is_shortcut_modifier("Ctrl+Space", rdev::Key::ShiftLeft));
+        assert!(!is_shortcut_modifier("Ctrl+Space", rdev::Key::Space));
+        assert!(!is_shortcut_modifier("Ctrl+", rdev::Key::ControlLeft));
+    }
+
+    #[test]
+    fn test_warm_state_counts_once() {
+        let mut warm = WarmState::default();
+        let start = Instant::now();
+        assert!(!warm.take(start));
+
+        warm.warmed(start);
+        assert!(warm.take(start + Duration::from_secs(5)));
+        assert!(!warm.take(start + Duration::from_secs(6)));
+
+        // Too long ago for the connection or model to still be warm
+        warm.warmed(start);
+        assert!(!warm.take(start + WARM_WINDOW + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_throttle_cooldown() {
+        let mut throttle = PrefetchThrottle::default();
+        let start = Instant::now();
+
+        assert!(throttle.try_acquire(start));
+        assert!(!throttle.try_acquire(start + Duration::from_secs(5)));
+        assert!(throttle.try_acquire(start + PREFETCH_COOLDOWN));
+    }
+
+    #[test]
+    fn test_signal_serialization() {
+        let json = serde_json::to_string(&ActivitySignal::TextFieldFocused).unwrap();
+        assert_eq!(json, "\"text-field-focused\"");
+    }
+}
@@ -11,6 +11,10 @@ pub struct TranscriptionConfig {
     pub language: String,
     /// Translate to English
     pub translate: bool,
+    /// Text preceding the audio, biasing recognition toward its words
+    pub prompt: Option<String>,
+    /// Words to boost, for providers that take a list instead of a prompt
+    pub keywords: Vec<String>,
 }
 
 impl Default for TranscriptionConfig {
@@ -18,6 +22,8 @@ impl Default for TranscriptionConfig {
         Self {
             language: "auto".to_string(),
             translate: false,
+            prompt: None,
+            keywords: Vec::new(),
         }
     }
 }
@@ -33,6 +39,18 @@ pub struct TranscriptionResult {
     pub duration_ms: u64,
     /// Provider that performed the transcription
     pub provider: String,
+    /// Timed segments of the text (empty when the provider has no timestamps)
+    pub segments: Vec<TranscriptSegment>,
+    /// Quantization of the local model used (whisper.cpp only)
+    pub model: Option<crate::models::ModelResolution>,
+}
+
+/// A stretch of text with its position in the transcribed audio
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+pub struct TranscriptSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
 }
 
 /// Transcription errors
@@ -53,6 +71,9 @@ pub enum TranscriptionError {
     #[error("API error: {0}")]
     ApiError(String),
 
+    #[error("API key rejected: {0}")]
+    Unauthorized(String),
+
     #[error("Network error: {0}")]
     NetworkError(String),
 
@@ -66,6 +87,15 @@ pub enum TranscriptionError {
     Failed(String),
 }
 
+/// A failed transcription, with the provider that failed last
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{error}")]
+pub struct ProviderFailure {
+    /// Name of the provider, as in `TranscriptionResult::provider`
+    pub provider: &'static str,
+    pub error: TranscriptionError,
+}
+
 /// Trait for transcription providers
 #[async_trait]
 pub trait TranscriptionProvider: Send + Sync {
@@ -106,6 +136,8 @@ mod tests {
             language: Some("en".to_string()),
             duration_ms: 1500,
             provider: "test".to_string(),
+            segments: Vec::new(),
+            model: None,
         };
 
         let cloned = result.clone();
@@ -135,8 +167,10 @@ mod tests {
         let config = TranscriptionConfig {
             language: "fr".to_string(),
             translate: true,
+            ..Default::default()
         };
         assert_eq!(config.language, "fr");
         assert!(config.translate);
+        assert!(config.prompt.is_none());
     }
 }
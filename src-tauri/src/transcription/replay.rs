@@ -0,0 +1,414 @@
+//! Debug Captures and Replay
+//!
+//! When enabled in settings, each recording is saved with its raw audio and
+//! the effective settings it was processed with. Replaying a capture runs
+//! the same pipeline again and reports where the new result diverges from
+//! the original one, which turns "it transcribed this wrong once" into a
+//! reproducible case.
+
+use super::PipelineTrace;
+use crate::config::Settings;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Manifest file name inside a capture directory
+pub const CAPTURE_MANIFEST: &str = "capture.json";
+
+/// Audio file name inside a capture directory
+pub const CAPTURE_AUDIO: &str = "audio.wav";
+
+/// Number of captures kept on disk
+const MAX_CAPTURES: usize = 20;
+
+/// Characters of context shown around a divergence
+const EXCERPT_CONTEXT: usize = 20;
+
+/// Debug capture errors
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid capture manifest: {0}")]
+    Manifest(#[from] serde_json::Error),
+
+    #[error("Invalid capture audio: {0}")]
+    Audio(#[from] hound::Error),
+}
+
+/// What was recorded alongside the audio of a capture
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CaptureManifest {
+    pub app_version: String,
+    pub timestamp: String,
+    /// Sample rate of the raw device audio
+    pub sample_rate: u32,
+    pub profile: Option<String>,
+    /// Effective settings the recording was processed with
+    pub settings: Settings,
+    /// Transcriber output before post-processing
+    pub raw_text: Option<String>,
+    /// Final text after post-processing
+    pub text: Option<String>,
+    /// Error that ended the pipeline, if any
+    pub error: Option<String>,
+}
+
+/// A capture loaded from disk
+pub struct DebugCapture {
+    pub path: PathBuf,
+    pub manifest: CaptureManifest,
+    pub samples: Vec<f32>,
+}
+
+/// Capture listing entry
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct CaptureInfo {
+    pub path: String,
+    pub timestamp: String,
+    pub profile: Option<String>,
+    pub text: Option<String>,
+    pub error: Option<String>,
+}
+
+/// First point where two results differ
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct Divergence {
+    /// Character index of the first difference
+    pub position: usize,
+    /// Original text around the difference
+    pub original: String,
+    /// Replayed text around the difference
+    pub replayed: String,
+}
+
+/// Outcome of replaying a capture
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ReplayReport {
+    pub capture: String,
+    pub original_raw: Option<String>,
+    pub replayed_raw: Option<String>,
+    pub original_text: Option<String>,
+    pub replayed_text: Option<String>,
+    pub raw_divergence: Option<Divergence>,
+    pub text_divergence: Option<Divergence>,
+    /// Same text and same error as the original run
+    pub matches: bool,
+    pub original_error: Option<String>,
+    pub error: Option<String>,
+    pub trace: PipelineTrace,
+    /// Reasons the replay may legitimately differ
+    pub notes: Vec<String>,
+}
+
+impl ReplayReport {
+    /// Compare a replay against the original run of `capture`
+    pub fn new(
+        capture: &DebugCapture,
+        replayed_raw: Option<String>,
+        replayed_text: Option<String>,
+        error: Option<String>,
+        trace: PipelineTrace,
+    ) -> Self {
+        let manifest = &capture.manifest;
+        let diverge = |original: &Option<String>, replayed: &Option<String>| {
+            find_divergence(
+                original.as_deref().unwrap_or_default(),
+                replayed.as_deref().unwrap_or_default(),
+            )
+        };
+        let raw_divergence = diverge(&manifest.raw_text, &replayed_raw);
+        let text_divergence = diverge(&manifest.text, &replayed_text);
+        let matches =
+            raw_divergence.is_none() && text_divergence.is_none() && manifest.error == error;
+
+        Self {
+            capture: capture.path.display().to_string(),
+            original_raw: manifest.raw_text.clone(),
+            replayed_raw,
+            original_text: manifest.text.clone(),
+            replayed_text,
+            raw_divergence,
+            text_divergence,
+            matches,
+            original_error: manifest.error.clone(),
+            error,
+            trace,
+            notes: replay_notes(manifest),
+        }
+    }
+}
+
+/// Known sources of divergence unrelated to the pipeline itself
+fn replay_notes(manifest: &CaptureManifest) -> Vec<String> {
+    let mut notes = Vec::new();
+    if manifest.app_version != env!("CARGO_PKG_VERSION") {
+        notes.push(format!(
+            "Captured with version {}, replayed with {}",
+            manifest.app_version,
+            env!("CARGO_PKG_VERSION")
+        ));
+    }
+    if manifest.settings.transcription.provider != crate::config::TranscriptionProvider::Local {
+        notes.push("Cloud transcription is not guaranteed to be deterministic".to_string());
+    }
+    if manifest.settings.output.capitalize_learned_terms {
+        notes.push("Learned terms may have changed since the capture".to_string());
+    }
+    notes
+}
+
+/// First difference between two texts, with some context on each side
+pub fn find_divergence(original: &str, replayed: &str) -> Option<Divergence> {
+    if original == replayed {
+        return None;
+    }
+
+    let original: Vec<char> = original.chars().collect();
+    let replayed: Vec<char> = replayed.chars().collect();
+    let position = original
+        .iter()
+        .zip(&replayed)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let excerpt = |text: &[char]| {
+        let start = position.saturating_sub(EXCERPT_CONTEXT);
+        let end = (position + EXCERPT_CONTEXT).min(text.len());
+        text.get(start..end).unwrap_or_default().iter().collect()
+    };
+
+    Some(Divergence {
+        position,
+        original: excerpt(&original),
+        replayed: excerpt(&replayed),
+    })
+}
+
+/// Directory holding the active workspace's debug captures
+pub fn captures_dir() -> PathBuf {
+    crate::config::workspace_dir().join("debug-captures")
+}
+
+/// Recording being captured, saved once its outcome is known
+pub struct PendingCapture {
+    samples: Vec<f32>,
+    sample_rate: u32,
+    profile: Option<String>,
+    settings: Settings,
+}
+
+impl PendingCapture {
+    pub fn new(
+        samples: Vec<f32>,
+        sample_rate: u32,
+        profile: Option<&str>,
+        settings: &Settings,
+    ) -> Self {
+        Self {
+            samples,
+            sample_rate,
+            profile: profile.map(str::to_string),
+            settings: settings.clone(),
+        }
+    }
+
+    /// Save to the captures directory, logging failures
+    pub fn save(self, raw_text: Option<&str>, text: Option<&str>, error: Option<&str>) {
+        let manifest = CaptureManifest {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: crate::history::chrono_timestamp(),
+            sample_rate: self.sample_rate,
+            profile: self.profile,
+            settings: self.settings,
+            raw_text: raw_text.map(str::to_string),
+            text: text.map(str::to_string),
+            error: error.map(str::to_string),
+        };
+        if let Err(e) = save_capture(&captures_dir(), &manifest, &self.samples) {
+            tracing::warn!("Failed to save debug capture: {}", e);
+        }
+    }
+}
+
+/// Save a capture in a new directory under `dir`, pruning the oldest ones
+pub fn save_capture(
+    dir: &Path,
+    manifest: &CaptureManifest,
+    samples: &[f32],
+) -> Result<PathBuf, CaptureError> {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let path = dir.join(format!("capture-{}", millis));
+    std::fs::create_dir_all(&path)?;
+
+    // Float samples, so the replay sees exactly the captured audio
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: manifest.sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(path.join(CAPTURE_AUDIO), spec)?;
+    for &sample in samples {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+
+    std::fs::write(path.join(CAPTURE_MANIFEST), serde_json::to_string_pretty(manifest)?)?;
+    prune_captures(dir, MAX_CAPTURES);
+
+    tracing::debug!("Debug capture saved to {:?}", path);
+    Ok(path)
+}
+
+/// Load a capture from its directory or its manifest file
+pub fn load_capture(path: &Path) -> Result<DebugCapture, CaptureError> {
+    let dir = if path.is_dir() {
+        path
+    } else {
+        path.parent().unwrap_or(path)
+    };
+
+    let manifest: CaptureManifest =
+        serde_json::from_str(&std::fs::read_to_string(dir.join(CAPTURE_MANIFEST))?)?;
+    let samples = hound::WavReader::open(dir.join(CAPTURE_AUDIO))?
+        .into_samples::<f32>()
+        .collect::<Result<Vec<f32>, _>>()?;
+
+    Ok(DebugCapture {
+        path: dir.to_path_buf(),
+        manifest,
+        samples,
+    })
+}
+
+/// Captures under `dir`, newest first
+pub fn list_captures(dir: &Path) -> Vec<CaptureInfo> {
+    capture_dirs(dir)
+        .into_iter()
+        .rev()
+        .filter_map(|path| {
+            let content = std::fs::read_to_string(path.join(CAPTURE_MANIFEST)).ok()?;
+            let manifest: CaptureManifest = serde_json::from_str(&content).ok()?;
+            Some(CaptureInfo {
+                path: path.display().to_string(),
+                timestamp: manifest.timestamp,
+                profile: manifest.profile,
+                text: manifest.text,
+                error: manifest.error,
+            })
+        })
+        .collect()
+}
+
+/// Capture directories under `dir`, oldest first
+fn capture_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_dir()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("capture-"))
+        })
+        .collect();
+    // By creation time, which is the numeric suffix
+    dirs.sort_by_key(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str()?.strip_prefix("capture-")?.parse::<u128>().ok())
+            .unwrap_or(0)
+    });
+    dirs
+}
+
+fn prune_captures(dir: &Path, keep: usize) {
+    let dirs = capture_dirs(dir);
+    let excess = dirs.len().saturating_sub(keep);
+    for path in &dirs[..excess] {
+        if let Err(e) = std::fs::remove_dir_all(path) {
+            tracing::warn!("Failed to remove old debug capture {:?}: {}", path, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(text: &str) -> CaptureManifest {
+        CaptureManifest {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            timestamp: "2026-01-01T00:00:00".to_string(),
+            sample_rate: 48000,
+            profile: Some("notes".to_string()),
+            settings: Settings::default(),
+            raw_text: Some(text.to_string()),
+            text: Some(text.to_string()),
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_find_divergence() {
+        assert_eq!(find_divergence("hello world", "hello world"), None);
+
+        let divergence = find_divergence("hello world", "hello word").unwrap();
+        assert_eq!(divergence.position, 9);
+        assert_eq!(divergence.original, "hello world");
+        assert_eq!(divergence.replayed, "hello word");
+
+        // Prefix: diverges where the shorter text ends
+        let divergence = find_divergence("café", "café au lait").unwrap();
+        assert_eq!(divergence.position, 4);
+        assert_eq!(divergence.original, "café");
+    }
+
+    #[test]
+    fn test_divergence_excerpt_is_bounded() {
+        let original = format!("{}a{}", "x".repeat(100), "y".repeat(100));
+        let replayed = format!("{}b{}", "x".repeat(100), "y".repeat(100));
+        let divergence = find_divergence(&original, &replayed).unwrap();
+        assert_eq!(divergence.position, 100);
+        assert_eq!(divergence.original.chars().count(), 2 * EXCERPT_CONTEXT);
+        assert!(divergence.replayed.starts_with('x') && divergence.replayed.contains('b'));
+    }
+
+    #[test]
+    fn test_capture_round_trip_and_pruning() {
+        let dir = tempfile::tempdir().unwrap();
+        let samples = vec![0.125, -0.5, 0.333_333, 0.0];
+
+        let path = save_capture(dir.path(), &manifest("hello"), &samples).unwrap();
+        let capture = load_capture(&path.join(CAPTURE_MANIFEST)).unwrap();
+        assert_eq!(capture.samples, samples);
+        assert_eq!(capture.manifest.sample_rate, 48000);
+        assert_eq!(capture.manifest.text.as_deref(), Some("hello"));
+
+        let report = ReplayReport::new(
+            &capture,
+            Some("hello".to_string()),
+            Some("hello".to_string()),
+            None,
+            PipelineTrace::new(None),
+        );
+        assert!(report.matches);
+
+        for i in 0..5 {
+            std::fs::create_dir_all(dir.path().join(format!("capture-{}", i))).unwrap();
+        }
+        prune_captures(dir.path(), 3);
+        let remaining = capture_dirs(dir.path());
+        assert_eq!(remaining.len(), 3);
+        assert!(remaining.contains(&path));
+        assert_eq!(list_captures(dir.path()).len(), 1);
+    }
+}
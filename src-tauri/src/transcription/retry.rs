@@ -0,0 +1,176 @@
+//! Retry Policy
+//!
+//! Exponential backoff with jitter for cloud provider requests. Rate limited
+//! requests wait at least as long as the server asks (`Retry-After`), and
+//! are given up when it asks for longer than the longest backoff.
+
+use super::TranscriptionError;
+use crate::config::RetrySettings;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// When and how often to retry a failed request
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each further one
+    pub base_delay: Duration,
+    /// Longest delay between attempts
+    pub max_delay: Duration,
+    /// Fraction of each delay that is randomized (0.0 to 1.0)
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_settings(&RetrySettings::default())
+    }
+}
+
+impl RetryPolicy {
+    /// Policy from the user's retry settings
+    pub fn from_settings(settings: &RetrySettings) -> Self {
+        Self {
+            max_retries: settings.max_retries,
+            base_delay: Duration::from_millis(settings.base_delay_ms as u64),
+            max_delay: Duration::from_millis(settings.max_delay_ms as u64),
+            jitter: settings.jitter_percent.min(100) as f64 / 100.0,
+        }
+    }
+
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Whether a request that failed with `error` may succeed when sent again
+    pub fn is_retryable(error: &TranscriptionError) -> bool {
+        matches!(
+            error,
+            TranscriptionError::NetworkError(_)
+                | TranscriptionError::RateLimited
+                | TranscriptionError::Timeout(_)
+        )
+    }
+
+    /// Backoff before retry `retry` (0-based), without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.checked_pow(retry).unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Delay before retry `retry` (0-based), or `None` to give up
+    ///
+    /// `retry_after` is the delay a rate limited response asked for.
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        self.delay_with(retry, retry_after, random_unit())
+    }
+
+    /// `delay` with the random draw `random` in [0, 1)
+    fn delay_with(&self, retry: u32, retry_after: Option<Duration>, random: f64) -> Option<Duration> {
+        if retry >= self.max_retries {
+            return None;
+        }
+        // Waiting less than asked only earns another rate limit
+        if retry_after.is_some_and(|after| after > self.max_delay) {
+            return None;
+        }
+
+        // Spread retries of concurrent clients by shortening the backoff randomly
+        let backoff = self.backoff(retry);
+        let jittered = backoff.mul_f64(1.0 - self.jitter.clamp(0.0, 1.0) * random);
+        Some(retry_after.map_or(jittered, |after| jittered.max(after)))
+    }
+}
+
+/// Delay a rate limited response asks for, in whole seconds
+pub fn parse_retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Random number in [0, 1) (not suitable for anything but jitter)
+fn random_unit() -> f64 {
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_retries: 3,
+            base_delay: Duration::from_millis(1000),
+            max_delay: Duration::from_secs(5),
+            jitter: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = policy();
+        assert_eq!(policy.backoff(0), Duration::from_millis(1000));
+        assert_eq!(policy.backoff(2), Duration::from_millis(4000));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+        assert_eq!(policy.backoff(64), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_delay_jitter_and_attempts() {
+        let policy = policy();
+        assert_eq!(policy.delay_with(1, None, 0.0), Some(Duration::from_millis(2000)));
+        assert_eq!(policy.delay_with(1, None, 0.5), Some(Duration::from_millis(1500)));
+        assert_eq!(policy.delay_with(3, None, 0.0), None);
+        assert_eq!(RetryPolicy::none().delay(0, None), None);
+
+        let delay = policy.delay(0, None).unwrap();
+        assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_rate_limit_waits_for_retry_after() {
+        let policy = policy();
+        let after = Some(Duration::from_secs(3));
+        assert_eq!(policy.delay_with(0, after, 0.0), Some(Duration::from_secs(3)));
+        // Longer than the longest backoff: give up instead
+        assert_eq!(policy.delay_with(0, Some(Duration::from_secs(60)), 0.0), None);
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        assert!(RetryPolicy::is_retryable(&TranscriptionError::RateLimited));
+        assert!(RetryPolicy::is_retryable(&TranscriptionError::NetworkError(String::new())));
+        assert!(!RetryPolicy::is_retryable(&TranscriptionError::ApiError(String::new())));
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = reqwest::header::HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+        headers.insert(reqwest::header::RETRY_AFTER, "7".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(Duration::from_secs(7)));
+        headers.insert(
+            reqwest::header::RETRY_AFTER,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+}
@@ -3,18 +3,35 @@
 //! Centralized service for managing transcription operations.
 //! Handles provider caching, status tracking, and shared logic.
 
-use super::{GroqProvider, TranscriptionConfig, TranscriptionProvider, TranscriptionResult, WhisperProvider};
-use crate::audio::{resample, VadAggressiveness, VadConfig, VoiceActivityDetector};
-use crate::config::{Settings, TranscriptionProvider as ConfigProvider};
+use super::{
+    changed_words, cleanup_transcript, load_capture, post_process, record_trace,
+    run_audio_stages, streamed_result, transcript_language, unsent_text, CustomEndpointProvider,
+    DeepgramProvider, GroqProvider, PendingCapture, PipelineTrace, PrefetchThrottle, ProviderFailure,
+    ReplayReport, RetryPolicy, SpeakerStage, StageStatus, TranscriptionConfig,
+    TranscriptionOrchestrator,
+    TranscriptSegment, TranscriptionProvider, TranscriptionResult, VadStage, WarmState, WhisperProvider, WHISPER_SAMPLE_RATE,
+};
+use crate::audio::{trim_silence, SpeechMap, TranscodeError};
+use crate::config::{
+    AudioSource, HistoryAudio, HistoryAudioCodec, HookEvent, OutputMode, OutputSettings,
+    OutputSink, PipelineStage, RetrySettings, Settings, TranscriptionProvider as ConfigProvider,
+};
+use crate::models::ModelResolution;
 use crate::output;
-use crate::utils::{metrics, TranscriptionRecord};
+use crate::vocabulary::KeywordIndex;
+use crate::utils::{
+    fire_hooks, metrics, notify_transcription_error, observe_latency, HookPayload, StageTimings,
+    TranscriptionRecord, SLO_VIOLATED_EVENT,
+};
 use parking_lot::RwLock;
+use schemars::JsonSchema;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
+use zeroize::Zeroize;
 
 /// Transcription status information
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
 pub struct TranscriptionStatus {
     pub provider: String,
     pub model: String,
@@ -39,6 +56,208 @@ impl Default for TranscriptionStatus {
     }
 }
 
+/// Payload of the `transcription:complete` event
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
+pub struct TranscriptionComplete {
+    pub text: String,
+    /// Quantization of the local model used, when it transcribed the text
+    pub model: Option<ModelResolution>,
+    /// Delivery result of each configured output sink, in order
+    pub sinks: Vec<output::SinkResult>,
+    /// Where the time between stopping the recording and delivery went
+    pub timings: StageTimings,
+}
+
+/// Event naming the provider that produced a transcription
+pub const PROVIDER_USED_EVENT: &str = "transcription:provider";
+
+/// Payload of the `transcription:provider` event
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
+pub struct ProviderUsed {
+    /// Name of the provider that succeeded ("whisper.cpp", "groq", ...)
+    pub provider: String,
+    /// Whether the active provider failed and a fallback took over
+    pub fallback: bool,
+}
+
+/// Payload of the `transcription:alert` event
+#[derive(Debug, Clone, serde::Serialize, JsonSchema)]
+pub struct KeywordAlert {
+    /// Alert keywords found, in configured order
+    pub keywords: Vec<String>,
+    pub text: String,
+}
+
+/// Why a recording produced no transcription
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum PipelineError {
+    /// An audio stage rejected the recording (too short, no speech, ...)
+    #[error("{0}")]
+    Audio(String),
+    /// The transcriber failed
+    #[error("{0}")]
+    Transcription(ProviderFailure),
+}
+
+/// Level below which trimmed history audio counts as silence
+const HISTORY_TRIM_THRESHOLD_DB: f32 = -40.0;
+
+/// Silence kept around the sound in trimmed history audio
+const HISTORY_TRIM_PADDING_MS: u32 = 200;
+
+/// When the user stopped a recording and how long the capture took to drain
+#[derive(Debug, Clone, Copy)]
+pub struct RecordingStop {
+    /// Start of the stop-to-paste latency
+    pub started_at: std::time::Instant,
+    /// Stopping the capture stream and collecting its samples
+    pub capture_drain: std::time::Duration,
+}
+
+impl RecordingStop {
+    /// Stop that started at `started_at` and finished draining now
+    pub fn since(started_at: std::time::Instant) -> Self {
+        Self {
+            started_at,
+            capture_drain: started_at.elapsed(),
+        }
+    }
+}
+
+/// A recording that went through the whole pipeline
+pub struct ProcessedRecording {
+    /// Final text after post-processing
+    pub text: String,
+    /// English translation of the text, in language learning mode
+    pub translation: Option<String>,
+    /// Language learning mode wants an English translation of the speech
+    pub wants_translation: bool,
+    /// Transcriber output before post-processing
+    pub transcription: TranscriptionResult,
+    /// 16 kHz audio as it entered the VAD stage
+    pub audio: Vec<f32>,
+    /// Audio passed to the transcriber
+    pub speech: Vec<f32>,
+    /// Where the speech sits in `audio`
+    pub kept: SpeechMap,
+    /// Duration of the audio passed to the transcriber
+    pub filtered_audio_ms: u64,
+    /// Which audio to keep in history
+    pub history_audio: HistoryAudio,
+    /// Encoding of the audio kept in history
+    pub history_audio_codec: HistoryAudioCodec,
+    /// Keep the audio out of history (no-disk-audio privacy mode)
+    pub discard_audio: bool,
+    /// Stage timings (capture and injection left at zero)
+    pub timings: StageTimings,
+    /// Alert keywords found in the text
+    pub alerts: Vec<String>,
+}
+
+impl ProcessedRecording {
+    /// Duration of the recording after resampling
+    pub fn audio_duration_ms(&self) -> u64 {
+        (self.audio.len() as u64 * 1000) / WHISPER_SAMPLE_RATE as u64
+    }
+
+    /// Overwrite the audio buffers with zeros and release them
+    pub fn wipe_audio(&mut self) {
+        self.audio.zeroize();
+        self.speech.zeroize();
+    }
+
+    /// The final text as delivered to the output sinks
+    pub fn sink_payload(&self) -> output::SinkPayload {
+        output::SinkPayload {
+            text: self.text.clone(),
+            timestamp: crate::history::chrono_timestamp(),
+            duration_ms: Some(self.audio_duration_ms()),
+            language: self.transcription.language.clone(),
+        }
+    }
+
+    /// Transcript segments timed on the recording rather than on the speech
+    /// the filters kept, so they line up with `recording_ms`
+    fn recording_segments(&self) -> Vec<TranscriptSegment> {
+        self.transcription
+            .segments
+            .iter()
+            .map(|segment| {
+                let (start_ms, end_ms) =
+                    self.kept
+                        .to_input_ms(segment.start_ms, segment.end_ms, WHISPER_SAMPLE_RATE);
+                TranscriptSegment {
+                    start_ms,
+                    end_ms,
+                    text: segment.text.clone(),
+                }
+            })
+            .collect()
+    }
+
+    /// Add the text, its audio and an optional window screenshot to history,
+    /// unless the text is empty
+    ///
+    /// `source` labels what was recorded (None for files). Returns the id of
+    /// the added entry.
+    pub fn save_to_history(
+        &self,
+        screenshot_path: Option<String>,
+        source: Option<AudioSource>,
+    ) -> Option<String> {
+        if self.text.is_empty() {
+            return None;
+        }
+        let samples = match self.history_audio {
+            HistoryAudio::Full => &self.audio[..],
+            HistoryAudio::Trimmed => trim_silence(
+                &self.audio,
+                WHISPER_SAMPLE_RATE,
+                HISTORY_TRIM_THRESHOLD_DB,
+                HISTORY_TRIM_PADDING_MS,
+            ),
+            HistoryAudio::Speech => &self.speech[..],
+        };
+        let id = crate::history::add_transcription_with_audio(
+            self.text.clone(),
+            self.transcription.duration_ms,
+            self.transcription.provider.clone(),
+            self.transcription.language.clone(),
+            samples,
+            WHISPER_SAMPLE_RATE,
+            crate::history::EntryDetails {
+                screenshot_path,
+                alerts: self.alerts.clone(),
+                recording_ms: (samples.len() != self.audio.len())
+                    .then(|| self.audio_duration_ms()),
+                segments: self.recording_segments(),
+                translation: self.translation.clone(),
+                source,
+                discard_audio: self.discard_audio,
+            },
+        );
+        Some(id)
+    }
+
+    /// Record performance metrics for this recording
+    pub fn record_metrics(&self, config: &Settings, timings: StageTimings, prefetched: bool) {
+        let record = TranscriptionRecord::builder()
+            .audio_duration_ms(self.audio_duration_ms())
+            .processing_time_ms(self.transcription.duration_ms)
+            .provider(&self.transcription.provider)
+            .model(config.transcription.local.model_name())
+            .gpu_used(config.transcription.local.gpu_enabled)
+            .threads_used(config.transcription.local.threads)
+            .vad_enabled(config.audio.vad.enabled)
+            .vad_filtered_ms(self.filtered_audio_ms)
+            .result_chars(self.text.len())
+            .timings(timings)
+            .prefetched(prefetched)
+            .build();
+        metrics().write().record_transcription(record);
+    }
+}
+
 /// Cached Whisper provider data
 struct CachedWhisper {
     provider: WhisperProvider,
@@ -47,12 +266,28 @@ struct CachedWhisper {
     threads: usize,
 }
 
+/// Cached Groq provider data (keeps the HTTP connection pool alive)
+struct CachedGroq {
+    provider: GroqProvider,
+    model: String,
+    timeout_seconds: u32,
+    retry: RetrySettings,
+}
+
 /// Centralized transcription service
 pub struct TranscriptionService {
     /// Cached Whisper provider
     cached_whisper: RwLock<Option<CachedWhisper>>,
+    /// Cached Groq provider
+    cached_groq: RwLock<Option<CachedGroq>>,
+    /// Rate limit for warm-state prefetch
+    prefetch_throttle: parking_lot::Mutex<PrefetchThrottle>,
+    /// Last warm-up not yet counted by a transcription
+    warm_state: parking_lot::Mutex<WarmState>,
     /// Transcription status
     status: RwLock<TranscriptionStatus>,
+    /// Quantization the local model was last resolved to
+    model_resolution: RwLock<Option<ModelResolution>>,
 }
 
 impl TranscriptionService {
@@ -60,7 +295,11 @@ impl TranscriptionService {
     pub fn new() -> Self {
         Self {
             cached_whisper: RwLock::new(None),
+            cached_groq: RwLock::new(None),
+            prefetch_throttle: parking_lot::Mutex::new(PrefetchThrottle::default()),
+            warm_state: parking_lot::Mutex::new(WarmState::default()),
             status: RwLock::new(TranscriptionStatus::default()),
+            model_resolution: RwLock::new(None),
         }
     }
 
@@ -75,8 +314,10 @@ impl TranscriptionService {
         status.provider = match config.transcription.provider {
             ConfigProvider::Local => "local".to_string(),
             ConfigProvider::Groq => "groq".to_string(),
+            ConfigProvider::Deepgram => "deepgram".to_string(),
+            ConfigProvider::Custom => "custom".to_string(),
         };
-        status.model = format!("{:?}", config.transcription.local.model).to_lowercase();
+        status.model = config.transcription.local.model_name();
 
         // Check if model is loaded
         let cached = self.cached_whisper.read();
@@ -86,8 +327,7 @@ impl TranscriptionService {
     /// Preload the Whisper model (call during startup or settings change)
     pub fn preload_model(&self, config: &Settings) -> Result<(), String> {
         if config.transcription.provider == ConfigProvider::Local {
-            let model_path = crate::config::models_dir()
-                .join(config.transcription.local.model_filename());
+            let model_path = self.local_model_path(config);
             let threads = config.transcription.local.threads;
             let gpu_enabled = config.transcription.local.gpu_enabled;
 
@@ -96,6 +336,33 @@ impl TranscriptionService {
         Ok(())
     }
 
+    /// Path of the configured local model, or of another downloaded
+    /// quantization of it when the requested one is missing
+    fn local_model_path(&self, config: &Settings) -> PathBuf {
+        let local = &config.transcription.local;
+        if let Some(id) = &local.custom_model {
+            match crate::models::custom_model_path(id).filter(|path| path.exists()) {
+                Some(path) => {
+                    *self.model_resolution.write() = None;
+                    return path;
+                }
+                None => tracing::warn!(
+                    "Imported model '{}' is missing, using {:?} instead",
+                    id,
+                    local.model
+                ),
+            }
+        }
+        let (path, resolution) = crate::models::resolve_model(&local.model, &local.quantization);
+        *self.model_resolution.write() = Some(resolution);
+        path
+    }
+
+    /// Quantization the local model was last resolved to
+    pub fn model_resolution(&self) -> Option<ModelResolution> {
+        self.model_resolution.read().clone()
+    }
+
     /// Ensure Whisper model is loaded (with caching)
     fn ensure_whisper_loaded(
         &self,
@@ -103,6 +370,7 @@ impl TranscriptionService {
         threads: usize,
         gpu_enabled: bool,
     ) -> Result<(), String> {
+        crate::models::record_model_use(&model_path);
         let needs_load = {
             let cached = self.cached_whisper.read();
             match &*cached {
@@ -142,6 +410,185 @@ impl TranscriptionService {
         Ok(())
     }
 
+    /// Get the Groq provider for the current settings, reusing its connection pool
+    fn groq_provider(&self, config: &Settings) -> GroqProvider {
+        let groq = &config.transcription.groq;
+        let retry = &config.transcription.retry;
+        if let Some(cached) = self.cached_groq.read().as_ref() {
+            if cached.model == groq.model
+                && cached.timeout_seconds == groq.timeout_seconds
+                && cached.retry == *retry
+            {
+                return cached.provider.clone();
+            }
+        }
+
+        let provider =
+            GroqProvider::with_timeout(Some(groq.model.clone()), groq.timeout_seconds as u64)
+                .with_retry(RetryPolicy::from_settings(retry));
+        *self.cached_groq.write() = Some(CachedGroq {
+            provider: provider.clone(),
+            model: groq.model.clone(),
+            timeout_seconds: groq.timeout_seconds,
+            retry: retry.clone(),
+        });
+        provider
+    }
+
+    /// Get the cached Whisper provider for these settings without loading it
+    ///
+    /// The model loads on first use. A provider for other settings than the
+    /// cached one is not cached, so a fallback never evicts the loaded model.
+    fn whisper_provider(
+        &self,
+        model_path: PathBuf,
+        threads: usize,
+        gpu_enabled: bool,
+    ) -> WhisperProvider {
+        let mut cached = self.cached_whisper.write();
+        match &*cached {
+            Some(c)
+                if c.model_path == model_path
+                    && c.gpu_enabled == gpu_enabled
+                    && c.threads == threads =>
+            {
+                c.provider.clone()
+            }
+            Some(_) => WhisperProvider::with_gpu(model_path, threads, gpu_enabled),
+            None => {
+                let provider = WhisperProvider::with_gpu(model_path.clone(), threads, gpu_enabled);
+                *cached = Some(CachedWhisper {
+                    provider: provider.clone(),
+                    model_path,
+                    gpu_enabled,
+                    threads,
+                });
+                provider
+            }
+        }
+    }
+
+    /// Build the provider for `kind`
+    ///
+    /// The local model is loaded ahead only with `preload`; a fallback loads
+    /// it when it is actually needed.
+    fn provider_for(
+        &self,
+        kind: &ConfigProvider,
+        config: &Settings,
+        preload: bool,
+    ) -> Box<dyn TranscriptionProvider> {
+        match kind {
+            ConfigProvider::Groq => Box::new(self.groq_provider(config)),
+            ConfigProvider::Deepgram => {
+                let deepgram = &config.transcription.deepgram;
+                Box::new(DeepgramProvider::new(
+                    Some(deepgram.model.clone()),
+                    deepgram.timeout_seconds as u64,
+                ))
+            }
+            ConfigProvider::Custom => {
+                let custom = &config.transcription.custom;
+                Box::new(
+                    CustomEndpointProvider::new(
+                        custom.base_url.clone(),
+                        Some(custom.model.clone()),
+                        custom.get_token(),
+                        custom.timeout_seconds as u64,
+                    )
+                    .with_retry(RetryPolicy::from_settings(&config.transcription.retry)),
+                )
+            }
+            ConfigProvider::Local => {
+                let local = &config.transcription.local;
+                let model_path = self.local_model_path(config);
+                if preload {
+                    // On failure the provider retries and reports the error itself
+                    let loaded = self.ensure_whisper_loaded(
+                        model_path.clone(),
+                        local.threads,
+                        local.gpu_enabled,
+                    );
+                    if let Err(e) = loaded {
+                        tracing::warn!("Failed to preload Whisper model: {}", e);
+                    }
+                }
+                Box::new(
+                    self.whisper_provider(model_path, local.threads, local.gpu_enabled)
+                        .with_beam_size(local.beam_size as usize)
+                        .with_temperature(local.temperature)
+                        .with_no_speech_threshold(local.no_speech_threshold),
+                )
+            }
+        }
+    }
+
+    /// Warm up the configured provider ahead of a recording
+    ///
+    /// Loads the local model if needed, or opens the Groq connection.
+    /// Throttled by `PREFETCH_COOLDOWN`.
+    pub async fn prefetch(self: &Arc<Self>, config: &Settings) {
+        if crate::utils::prefetch_suspended() {
+            tracing::debug!("Provider warm-up suspended by the memory budget");
+            return;
+        }
+        if !self.prefetch_throttle.lock().try_acquire(std::time::Instant::now()) {
+            return;
+        }
+
+        let start = std::time::Instant::now();
+        let result = match config.transcription.provider {
+            ConfigProvider::Groq => self
+                .groq_provider(config)
+                .warm_up()
+                .await
+                .map_err(|e| e.to_string()),
+            // Each streaming session opens its own connection
+            ConfigProvider::Deepgram => return,
+            // Self-hosted servers are usually local; nothing worth warming up
+            ConfigProvider::Custom => return,
+            ConfigProvider::Local => {
+                if self.get_status().model_loaded {
+                    return;
+                }
+                let service = self.clone();
+                let config = config.clone();
+                tauri::async_runtime::spawn_blocking(move || service.preload_model(&config))
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r)
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                self.warm_state.lock().warmed(std::time::Instant::now());
+                tracing::info!("Provider warmed up in {}ms", start.elapsed().as_millis());
+            }
+            Err(e) => tracing::debug!("Provider warm-up failed: {}", e),
+        }
+    }
+
+    /// Whether a warm-up ran ahead of the transcription finishing now
+    pub fn take_warm_up(&self) -> bool {
+        self.warm_state.lock().take(std::time::Instant::now())
+    }
+
+    /// Path of the loaded Whisper model, and whether it runs on the GPU
+    pub fn loaded_model(&self) -> Option<(PathBuf, bool)> {
+        let cached = self.cached_whisper.read();
+        let c = cached.as_ref().filter(|c| c.provider.is_model_loaded())?;
+        Some((
+            c.model_path.clone(),
+            c.gpu_enabled && WhisperProvider::is_gpu_available(),
+        ))
+    }
+
+    /// Time since the loaded Whisper model was last used
+    pub fn model_idle_time(&self) -> Option<std::time::Duration> {
+        self.cached_whisper.read().as_ref()?.provider.idle_time()
+    }
+
     /// Unload the model to free memory
     pub fn unload_model(&self) {
         let mut cached = self.cached_whisper.write();
@@ -154,12 +601,12 @@ impl TranscriptionService {
         status.model_loaded = false;
     }
 
-    /// Perform transcription with the configured provider
+    /// Perform transcription with the configured provider and its fallbacks
     pub async fn transcribe(
         &self,
         samples: &[f32],
         config: &Settings,
-    ) -> Result<TranscriptionResult, String> {
+    ) -> Result<TranscriptionResult, ProviderFailure> {
         // Update status
         {
             let mut status = self.status.write();
@@ -167,44 +614,15 @@ impl TranscriptionService {
             status.last_error = None;
         }
 
-        let transcription_config = TranscriptionConfig {
-            language: config.transcription.language.clone(),
-            translate: false,
-        };
-
-        let result = match config.transcription.provider {
-            ConfigProvider::Groq => {
-                let provider = GroqProvider::with_timeout(
-                    Some(config.transcription.groq.model.clone()),
-                    config.transcription.groq.timeout_seconds as u64,
-                );
-                provider
-                    .transcribe(samples, &transcription_config)
-                    .await
-                    .map_err(|e| e.to_string())
-            }
-            ConfigProvider::Local => {
-                let model_path = crate::config::models_dir()
-                    .join(config.transcription.local.model_filename());
-                let threads = config.transcription.local.threads;
-                let gpu_enabled = config.transcription.local.gpu_enabled;
-
-                // Ensure model is loaded and get a clone of the provider
-                self.ensure_whisper_loaded(model_path, threads, gpu_enabled)?;
-
-                // Get a clone of the cached provider (cheap because context is Arc)
-                let provider = {
-                    let cached = self.cached_whisper.read();
-                    cached.as_ref().ok_or("Provider not initialized")?.provider.clone()
-                };
-
-                // Transcribe using the cloned provider (no lock held across await)
-                provider
-                    .transcribe(samples, &transcription_config)
-                    .await
-                    .map_err(|e| e.to_string())
-            }
-        };
+        // Try the active provider, then the configured fallbacks in order
+        let result = self
+            .transcribe_with_chain(
+                samples,
+                config,
+                &config.transcription.provider_chain(),
+                config.transcription.translate,
+            )
+            .await;
 
         // Update status with result
         {
@@ -217,7 +635,7 @@ impl TranscriptionService {
                     status.last_error = None;
                 }
                 Err(e) => {
-                    status.last_error = Some(e.clone());
+                    status.last_error = Some(e.to_string());
                 }
             }
         }
@@ -225,190 +643,890 @@ impl TranscriptionService {
         result
     }
 
-    /// Process recording: resample, apply VAD, transcribe, and output
-    pub async fn process_recording(
-        self: &Arc<Self>,
-        app: &AppHandle,
-        raw_samples: Vec<f32>,
-        device_sample_rate: u32,
-    ) -> Result<String, String> {
-        use tauri_plugin_notification::NotificationExt;
+    /// Transcribe, unless the recording was already streamed to Deepgram for
+    /// live partials: that session's final text is used instead of sending
+    /// the recording a second time
+    async fn transcribe_or_streamed(
+        &self,
+        samples: &[f32],
+        config: &Settings,
+        streamed: Option<tokio::sync::oneshot::Receiver<String>>,
+    ) -> Result<TranscriptionResult, ProviderFailure> {
+        let started = std::time::Instant::now();
+        let text = match streamed {
+            Some(streamed) => streamed.await.ok(),
+            None => None,
+        };
+        if let Some(text) = text {
+            let result = streamed_result(text, &config.transcription.language, started.elapsed());
+            let mut status = self.status.write();
+            status.last_result = Some(result.text.clone());
+            status.last_duration_ms = Some(result.duration_ms);
+            status.last_error = None;
+            return Ok(result);
+        }
+        self.transcribe(samples, config).await
+    }
 
-        let state = app.state::<crate::AppState>();
+    /// Translate speech to English with the providers that can translate
+    ///
+    /// Leaves the status untouched: this is the second pass of language
+    /// learning mode, after `transcribe` produced the original text.
+    pub async fn translate(
+        &self,
+        samples: &[f32],
+        config: &Settings,
+    ) -> Result<TranscriptionResult, String> {
+        let chain = config.transcription.translation_chain();
+        if chain.is_empty() {
+            return Err("No configured provider can translate".to_string());
+        }
+        self.transcribe_with_chain(samples, config, &chain, true)
+            .await
+            .map_err(|e| e.to_string())
+    }
 
-        // Resample to 16kHz for Whisper
-        const WHISPER_SAMPLE_RATE: u32 = 16000;
-        let samples = if device_sample_rate != WHISPER_SAMPLE_RATE {
-            resample(&raw_samples, device_sample_rate, WHISPER_SAMPLE_RATE)
-                .map_err(|e| format!("Resampling failed: {}", e))?
-        } else {
-            raw_samples
-        };
+    /// English side of language learning mode, post-processed like the text
+    ///
+    /// A second transcription pass, so callers run it once the text has been
+    /// delivered. Returns None when the translation failed.
+    pub async fn learning_translation(&self, speech: &[f32], config: &Settings) -> Option<String> {
+        match self.translate(speech, config).await {
+            Ok(translated) => {
+                let text = post_process(&translated.text, &config.output, Some("en"));
+                Some(text)
+            }
+            Err(e) => {
+                tracing::warn!("Translation for language learning failed: {}", e);
+                None
+            }
+        }
+    }
 
-        // Check for minimum audio
-        if samples.len() < 1600 {
-            return Err("Recording too short".to_string());
+    /// Try each provider of `chain` in order until one succeeds
+    async fn transcribe_with_chain(
+        &self,
+        samples: &[f32],
+        config: &Settings,
+        chain: &[ConfigProvider],
+        translate: bool,
+    ) -> Result<TranscriptionResult, ProviderFailure> {
+        let transcription_config = transcription_config(config, translate);
+        let orchestrator = TranscriptionOrchestrator::with_fallbacks(
+            self.provider_for(&chain[0], config, true),
+            chain[1..]
+                .iter()
+                .map(|kind| self.provider_for(kind, config, false))
+                .collect(),
+        );
+        let mut result = orchestrator
+            .try_transcribe(samples, &transcription_config)
+            .await?;
+        if result.provider == "whisper.cpp" {
+            result.model = self.model_resolution();
         }
+        Ok(result)
+    }
 
-        // Get config
-        let config = state.config.read().clone();
+    /// Transcribe part of a recording in progress with the local model
+    ///
+    /// Skips the pipeline stages and leaves the status untouched, so the
+    /// final transcription of the recording is unaffected.
+    pub async fn transcribe_partial(
+        &self,
+        samples: &[f32],
+        device_sample_rate: u32,
+        config: &Settings,
+    ) -> Result<String, String> {
+        if config.transcription.provider != ConfigProvider::Local {
+            return Err("Live partials need the local provider".to_string());
+        }
+        let samples = crate::audio::resample(samples, device_sample_rate, WHISPER_SAMPLE_RATE)?;
 
-        // Apply Voice Activity Detection if enabled
-        let samples_for_transcription = if config.audio.vad.enabled {
-            let vad_mode = match config.audio.vad.aggressiveness {
-                0 => VadAggressiveness::Quality,
-                1 => VadAggressiveness::LowBitrate,
-                2 => VadAggressiveness::Aggressive,
-                _ => VadAggressiveness::VeryAggressive,
-            };
+        let local = &config.transcription.local;
+        let model_path = self.local_model_path(config);
+        self.ensure_whisper_loaded(model_path, local.threads, local.gpu_enabled)?;
+        let provider = {
+            let cached = self.cached_whisper.read();
+            cached.as_ref().ok_or("Provider not initialized")?.provider.clone()
+        };
 
-            let vad_config = VadConfig {
-                mode: vad_mode,
-                min_speech_duration_ms: config.audio.vad.min_speech_duration_ms,
-                padding_ms: config.audio.vad.padding_ms,
-                frame_duration_ms: 30,
-            };
+        let transcription_config = transcription_config(config, config.transcription.translate);
+        provider
+            .transcribe(&samples, &transcription_config)
+            .await
+            .map(|r| r.text)
+            .map_err(|e| e.to_string())
+    }
 
-            let vad = VoiceActivityDetector::with_config(vad_config);
+    /// Run a recording through the configured pipeline stages and transcribe it
+    ///
+    /// Does not depend on Tauri: output, history and notifications are left to
+    /// the caller. Saves a debug capture when enabled in `config`.
+    pub async fn run_pipeline(
+        &self,
+        raw_samples: Vec<f32>,
+        device_sample_rate: u32,
+        config: &Settings,
+        profile: Option<&str>,
+    ) -> Result<ProcessedRecording, PipelineError> {
+        self.run_pipeline_streamed(raw_samples, device_sample_rate, config, profile, None)
+            .await
+    }
 
-            match vad.filter_speech(&samples, WHISPER_SAMPLE_RATE) {
-                Ok(vad_result) => {
-                    tracing::info!(
-                        "VAD filtered: {:.1}% speech ({} segments), {}ms -> {}ms",
-                        vad_result.speech_percentage,
-                        vad_result.speech_segments,
-                        vad_result.original_duration_ms,
-                        vad_result.speech_duration_ms
-                    );
+    /// `run_pipeline`, taking the transcript from a live streaming session
+    /// when it delivers one
+    async fn run_pipeline_streamed(
+        &self,
+        raw_samples: Vec<f32>,
+        device_sample_rate: u32,
+        config: &Settings,
+        profile: Option<&str>,
+        streamed: Option<tokio::sync::oneshot::Receiver<String>>,
+    ) -> Result<ProcessedRecording, PipelineError> {
+        let vad = VadStage::from_settings(config);
+        let speaker = SpeakerStage::from_settings(config);
+        let save_capture = config.recording.save_debug_captures && !config.privacy.no_disk_audio;
+        let capture = save_capture.then(|| {
+            PendingCapture::new(raw_samples.clone(), device_sample_rate, profile, config)
+        });
 
-                    // If no speech detected, return early
-                    if vad_result.audio.is_empty() || vad_result.speech_percentage < 1.0 {
-                        return Err("No speech detected in recording".to_string());
-                    }
+        // Run the audio stages in their configured order
+        let mut trace = PipelineTrace::new(profile);
+        let wipe = config.privacy.no_disk_audio;
+        let mut audio = match run_audio_stages(
+            &config.audio.pipeline,
+            raw_samples,
+            device_sample_rate,
+            config.audio.noise_suppressor,
+            &vad,
+            &speaker,
+            wipe,
+            &mut trace,
+        ) {
+            Ok(audio) => audio,
+            Err(e) => {
+                trace.error = Some(e.clone());
+                record_trace(trace);
+                if let Some(capture) = capture {
+                    capture.save(None, None, Some(&e));
+                }
+                return Err(PipelineError::Audio(e));
+            }
+        };
+
+        // Perform transcription
+        let started = std::time::Instant::now();
+        let transcription = match self
+            .transcribe_or_streamed(&audio.speech, config, streamed)
+            .await
+        {
+            Ok(transcription) => {
+                trace.record(
+                    PipelineStage::Transcribe,
+                    StageStatus::Ran,
+                    started,
+                    Some(transcription.provider.clone()),
+                );
+                transcription
+            }
+            Err(failure) => {
+                let e = failure.to_string();
+                trace.record(PipelineStage::Transcribe, StageStatus::Failed, started, None);
+                trace.error = Some(e.clone());
+                record_trace(trace);
+                if let Some(capture) = capture {
+                    capture.save(None, None, Some(&e));
+                }
+                if wipe {
+                    audio.wipe();
+                }
+                return Err(PipelineError::Transcription(failure));
+            }
+        };
 
-                    vad_result.audio
+        let started = std::time::Instant::now();
+        let language =
+            transcript_language(&config.transcription, transcription.language.as_deref());
+        let mut notes = Vec::new();
+        let processed = post_process(&transcription.text, &config.output, language);
+        // The language model gets the text after voice commands and vocabulary ran
+        let text = if config.output.cleanup.enabled && !processed.trim().is_empty() {
+            match cleanup_transcript(&processed, &config.output.cleanup).await {
+                Ok(cleaned) => {
+                    notes.push("cleaned up".to_string());
+                    cleaned
                 }
                 Err(e) => {
-                    tracing::warn!("VAD failed, using full audio: {}", e);
-                    samples.clone()
+                    tracing::warn!("Transcript cleanup failed, using processed text: {}", e);
+                    notes.push(format!("cleanup failed: {}", e));
+                    processed
                 }
             }
         } else {
-            samples.clone()
+            processed
         };
+        let alerts = KeywordIndex::new(&config.alerts.keywords).find(&text);
+        if !alerts.is_empty() {
+            notes.push(format!("alerts: {}", alerts.join(", ")));
+        }
+        let detail = (!notes.is_empty()).then(|| notes.join("; "));
+        trace.record(PipelineStage::PostProcess, StageStatus::Ran, started, detail);
+        let timings = trace.timings();
+        record_trace(trace);
+        if let Some(capture) = capture {
+            capture.save(Some(&transcription.text), Some(&text), None);
+        }
 
-        // Calculate audio durations for metrics
-        let original_audio_ms = (samples.len() as u64 * 1000) / WHISPER_SAMPLE_RATE as u64;
-        let filtered_audio_ms = (samples_for_transcription.len() as u64 * 1000) / WHISPER_SAMPLE_RATE as u64;
-        let vad_was_enabled = config.audio.vad.enabled;
+        // Language learning: the English side is a second pass, left to the caller
+        let wants_translation = config.transcription.language_learning
+            && !config.transcription.translate
+            && language != Some("en")
+            && !text.is_empty();
+        tracing::info!(
+            "Transcription complete: '{}' ({}ms, {})",
+            text,
+            transcription.duration_ms,
+            transcription.provider
+        );
+
+        Ok(ProcessedRecording {
+            text,
+            translation: None,
+            wants_translation,
+            transcription,
+            filtered_audio_ms: (audio.speech.len() as u64 * 1000) / WHISPER_SAMPLE_RATE as u64,
+            audio: audio.full,
+            speech: audio.speech,
+            kept: audio.kept,
+            history_audio: config.recording.history_audio,
+            history_audio_codec: config.recording.history_audio_codec,
+            discard_audio: config.privacy.no_disk_audio,
+            timings,
+            alerts,
+        })
+    }
 
-        // Perform transcription
-        let result = self.transcribe(&samples_for_transcription, &config).await;
+    /// Process recording: run the configured pipeline stages, transcribe, and output
+    ///
+    /// `stop` times the stop-to-paste latency and how long stopping the
+    /// capture took, reported in the stage timings. `profile` selects a recording profile whose overrides
+    /// apply to this recording. An `incognito` recording is only pasted and
+    /// kept in memory until it expires; nothing about it reaches the disk.
+    pub async fn process_recording(
+        self: &Arc<Self>,
+        app: &AppHandle,
+        raw_samples: Vec<f32>,
+        device_sample_rate: u32,
+        stop: RecordingStop,
+        profile: Option<&str>,
+        incognito: bool,
+    ) -> Result<String, String> {
+        use tauri_plugin_notification::NotificationExt;
 
-        match result {
-            Ok(transcription) => {
-                let text = transcription.text.clone();
-                tracing::info!(
-                    "Transcription complete: '{}' ({}ms, {})",
-                    text,
-                    transcription.duration_ms,
-                    transcription.provider
-                );
+        let state = app.state::<crate::AppState>();
+        let source = std::mem::take(&mut *state.audio_source.write());
+
+        // Get config, with the recording profile and translation override applied
+        let translate = state.translate_override.write().take();
+        let config = {
+            let config = state.config.read();
+            if incognito {
+                config.effective_for_incognito()
+            } else {
+                config.effective_for_profile(profile)
+            }
+        }
+        .with_translate(translate);
+
+        // Stop type-as-you-speak before the final text is delivered
+        let live_transcript = state.live_transcript.lock().take();
+        let live_sentences = state.live_sentences.lock().take();
+        let pasted = live_sentences
+            .map(|sentences| sentences.lock().finish())
+            .unwrap_or_default();
+
+        let mut processed = match self
+            .run_pipeline_streamed(
+                raw_samples,
+                device_sample_rate,
+                &config,
+                profile,
+                live_transcript,
+            )
+            .await
+        {
+            Ok(processed) => processed,
+            Err(PipelineError::Audio(e)) => return Err(e),
+            Err(PipelineError::Transcription(failure)) => {
+                let e = failure.to_string();
+                tracing::error!("Transcription failed: {}", e);
+                let _ = app.emit("transcription:error", &e);
+                notify_transcription_error(app, &failure);
+                crate::api::publish_event(if incognito {
+                    crate::api::ApiEvent::ProcessingFinished
+                } else {
+                    crate::api::ApiEvent::TranscriptionFailed { error: e.clone() }
+                });
 
-                // Record performance metrics
-                let record = TranscriptionRecord::builder()
-                    .audio_duration_ms(original_audio_ms)
-                    .processing_time_ms(transcription.duration_ms)
-                    .provider(&transcription.provider)
-                    .model(format!("{:?}", config.transcription.local.model).to_lowercase())
-                    .gpu_used(config.transcription.local.gpu_enabled)
-                    .threads_used(config.transcription.local.threads)
-                    .vad_enabled(vad_was_enabled)
-                    .vad_filtered_ms(filtered_audio_ms)
-                    .result_chars(text.len())
-                    .build();
-                metrics().write().record_transcription(record);
-
-                // Save to history with audio (only if not empty)
-                if !text.is_empty() {
-                    crate::history::add_transcription_with_audio(
-                        text.clone(),
-                        transcription.duration_ms,
-                        transcription.provider.clone(),
-                        transcription.language.clone(),
-                        &samples,
-                        WHISPER_SAMPLE_RATE,
-                    );
-                    let _ = app.emit("history:updated", ());
+                return Err(e);
+            }
+        };
+        let text = processed.text.clone();
+
+        // Say when another quantization stood in for the requested one
+        if let Some(resolution) = processed.transcription.model.as_ref() {
+            if resolution.is_fallback() {
+                crate::commands::models::report_quantization_fallback(app, resolution, &config);
+            }
+        }
+
+        // Name the provider that succeeded, which may be a fallback
+        let provider = processed.transcription.provider.clone();
+        let fallback = provider != provider_name(&config.transcription.provider);
+        if fallback {
+            tracing::info!("Transcribed by fallback provider {}", provider);
+        }
+        let _ = app.emit(PROVIDER_USED_EVENT, ProviderUsed { provider, fallback });
+
+        // Capture the target window before the paste changes it
+        let screenshot = if config.output.capture_screenshots && !text.is_empty() {
+            output::capture_focused_window(
+                &crate::history::screenshots_dir(),
+                &config.output.screenshot_excluded_apps,
+            )
+        } else {
+            None
+        };
+
+        // Save to history with audio (only if not empty)
+        let entry_id = if incognito {
+            if !text.is_empty() {
+                keep_incognito(app, text.clone(), config.recording.incognito_minutes);
+            }
+            None
+        } else {
+            processed.save_to_history(screenshot, Some(source))
+        };
+        if entry_id.is_some() {
+            let _ = app.emit("history:updated", ());
+        }
+
+        // Hooks are disabled in incognito settings
+        let run_hooks = !text.is_empty();
+        let payload = HookPayload {
+            text: Some(text.clone()),
+            profile: profile.map(str::to_string),
+            success: None,
+        };
+        if run_hooks {
+            fire_hooks(
+                &state.tasks,
+                &config.hooks,
+                HookEvent::TranscriptionCompleted,
+                payload.clone(),
+            );
+        }
+
+        // Sentences pasted while recording are not typed again
+        let paste_text = if pasted.is_empty() {
+            text.clone()
+        } else {
+            let changed = changed_words(&pasted, &text);
+            if changed > 0 {
+                tracing::info!("{} pasted word(s) differ from the final text", changed);
+            }
+            let rest = unsent_text(&pasted, &text);
+            if config.output.smart_spacing || rest.is_empty() {
+                rest.to_string()
+            } else {
+                format!(" {}", rest)
+            }
+        };
+
+        // Deliver the text to each output sink
+        let started = std::time::Instant::now();
+        let sinks = self
+            .dispatch_sinks(
+                &processed.sink_payload(),
+                &paste_text,
+                app,
+                &config.output,
+                incognito,
+            )
+            .await;
+        let stop_to_paste_ms = stop.started_at.elapsed().as_millis() as u64;
+        if run_hooks {
+            let success = Some(sinks.iter().all(|sink| sink.success));
+            fire_hooks(
+                &state.tasks,
+                &config.hooks,
+                HookEvent::OutputInjected,
+                HookPayload { success, ..payload },
+            );
+        }
+        let timings = StageTimings {
+            capture_drain_ms: stop.capture_drain.as_millis() as u64,
+            injection_ms: started.elapsed().as_millis() as u64,
+            ..processed.timings
+        };
+        tracing::debug!("Stage timings: {:?}", timings);
+
+        // Record performance metrics
+        processed.record_metrics(&config, timings, self.take_warm_up());
+
+        // Encode the saved audio once the text is delivered
+        if let Some(id) = entry_id.clone() {
+            let codec = processed.history_audio_codec;
+            let app = app.clone();
+            state.tasks.spawn("audio-compression", async move {
+                let compress_id = id.clone();
+                let compressed = tokio::task::spawn_blocking(move || {
+                    crate::history::compress_entry_audio(&compress_id, codec)
+                })
+                .await;
+                match compressed {
+                    Ok(Ok(true)) => {
+                        let _ = app.emit("history:updated", ());
+                    }
+                    Ok(Ok(false)) => {}
+                    Ok(Err(e)) => report_compression_failure(&app, &id, &e),
+                    Err(e) => tracing::warn!("Audio compression task failed: {}", e),
                 }
+            });
+        }
 
-                // Output the text
-                if let Err(e) = self.output_text(&text, app).await {
-                    tracing::error!("Failed to output text: {}", e);
+        // Translate for language learning once the text is delivered
+        if let Some(id) = entry_id.filter(|_| processed.wants_translation) {
+            let mut speech = std::mem::take(&mut processed.speech);
+            let service = Arc::clone(self);
+            let config = config.clone();
+            let app = app.clone();
+            state.tasks.spawn("learning-translation", async move {
+                let translation = service.learning_translation(&speech, &config).await;
+                speech.zeroize();
+                if let Some(translation) = translation {
+                    save_translation(&app, &id, translation);
                 }
+            });
+        }
 
-                // Emit success event
-                let _ = app.emit("transcription:complete", &text);
+        // Nothing needs the audio past this point
+        if incognito || config.privacy.no_disk_audio {
+            processed.wipe_audio();
+        }
 
-                // Notify user
-                let preview = if text.len() > 50 {
-                    format!("{}...", &text[..50])
-                } else if text.is_empty() {
-                    "(No speech detected)".to_string()
-                } else {
-                    text.clone()
-                };
+        // Alert when the latency target keeps being missed
+        if let Some(report) = observe_latency(stop_to_paste_ms, timings, &config) {
+            tracing::warn!(
+                "Latency target of {}ms missed {} times in a row (p95 {}ms)",
+                report.target_ms,
+                report.consecutive_misses,
+                report.p95_ms
+            );
+            if config.latency.notify {
+                let hint = report
+                    .remediations
+                    .first()
+                    .map(|r| format!(" {}.", r.message))
+                    .unwrap_or_default();
                 let _ = app
                     .notification()
                     .builder()
-                    .title("Transcription Complete")
-                    .body(&preview)
+                    .title("Dictation Is Slow")
+                    .body(format!(
+                        "The last {} dictations took over {:.1}s.{}",
+                        report.consecutive_misses,
+                        report.target_ms as f64 / 1000.0,
+                        hint
+                    ))
                     .show();
-
-                Ok(text)
             }
-            Err(e) => {
-                tracing::error!("Transcription failed: {}", e);
-                let _ = app.emit("transcription:error", &e);
+            let _ = app.emit(SLO_VIOLATED_EVENT, report);
+        }
+
+        // Incognito dictations stay out of the transcript viewer and the API
+        if incognito {
+            crate::api::publish_event(crate::api::ApiEvent::ProcessingFinished);
+        } else {
+            crate::commands::viewer::viewer_line(app, &text);
+            crate::api::publish_event(crate::api::ApiEvent::TranscriptionCompleted(
+                crate::api::LatestTranscription {
+                    text: text.clone(),
+                    timestamp: crate::history::chrono_timestamp(),
+                    profile: profile.map(str::to_string),
+                },
+            ));
+        }
+
+        // Emit success event
+        let _ = app.emit(
+            "transcription:complete",
+            TranscriptionComplete {
+                text: text.clone(),
+                model: processed.transcription.model.clone(),
+                sinks,
+                timings,
+            },
+        );
+
+        // Notifications can outlive the dictation in the notification center
+        if incognito {
+            let _ = app
+                .notification()
+                .builder()
+                .title("Incognito Dictation")
+                .body(format!(
+                    "Pasted. Wiped from memory in {} min.",
+                    config.recording.incognito_minutes
+                ))
+                .show();
+            return Ok(text);
+        }
 
+        // Raise keyword alerts
+        if !processed.alerts.is_empty() {
+            tracing::info!("Alert keywords transcribed: {:?}", processed.alerts);
+            let _ = app.emit(
+                "transcription:alert",
+                KeywordAlert {
+                    keywords: processed.alerts.clone(),
+                    text: text.clone(),
+                },
+            );
+            if config.alerts.notify {
                 let _ = app
                     .notification()
                     .builder()
-                    .title("Transcription Failed")
-                    .body(&e)
+                    .title(format!("Keyword Alert: {}", processed.alerts.join(", ")))
+                    .body(&text)
                     .show();
+                return Ok(text);
+            }
+        }
+
+        // Notify user
+        let preview = if text.len() > 50 {
+            format!("{}...", &text[..50])
+        } else if text.is_empty() {
+            "(No speech detected)".to_string()
+        } else {
+            text.clone()
+        };
+        let _ = app
+            .notification()
+            .builder()
+            .title("Transcription Complete")
+            .body(&preview)
+            .show();
+
+        Ok(text)
+    }
+
+    /// Re-run a debug capture through the pipeline and compare the results
+    ///
+    /// Uses the settings stored in the capture and produces no output,
+    /// history or notifications.
+    pub async fn replay_capture(&self, path: &std::path::Path) -> Result<ReplayReport, String> {
+        let capture = load_capture(path).map_err(|e| e.to_string())?;
+        let settings = &capture.manifest.settings;
+        let vad = VadStage::from_settings(settings);
+        let speaker = SpeakerStage::from_settings(settings);
+
+        let mut trace = PipelineTrace::new(capture.manifest.profile.as_deref());
+        let audio = match run_audio_stages(
+            &settings.audio.pipeline,
+            capture.samples.clone(),
+            capture.manifest.sample_rate,
+            settings.audio.noise_suppressor,
+            &vad,
+            &speaker,
+            settings.privacy.no_disk_audio,
+            &mut trace,
+        ) {
+            Ok(audio) => audio,
+            Err(e) => {
+                trace.error = Some(e.clone());
+                return Ok(ReplayReport::new(&capture, None, None, Some(e), trace));
+            }
+        };
 
-                Err(e)
+        let started = std::time::Instant::now();
+        let report = match self.transcribe(&audio.speech, settings).await {
+            Ok(transcription) => {
+                trace.record(PipelineStage::Transcribe, StageStatus::Ran, started, None);
+                let started = std::time::Instant::now();
+                let language = transcript_language(
+                    &settings.transcription,
+                    transcription.language.as_deref(),
+                );
+                let text = post_process(&transcription.text, &settings.output, language);
+                trace.record(PipelineStage::PostProcess, StageStatus::Ran, started, None);
+                ReplayReport::new(&capture, Some(transcription.text), Some(text), None, trace)
+            }
+            Err(e) => {
+                trace.record(PipelineStage::Transcribe, StageStatus::Failed, started, None);
+                trace.error = Some(e.to_string());
+                ReplayReport::new(&capture, None, None, Some(e.to_string()), trace)
             }
+        };
+
+        tracing::info!(
+            "Replayed {:?}: {}",
+            report.capture,
+            if report.matches { "matches" } else { "diverged" }
+        );
+        Ok(report)
+    }
+
+    /// Deliver text to each sink in order, returning per-sink results
+    ///
+    /// The paste sink gets `paste_text`, which leaves out text already pasted
+    /// while recording. A failing sink does not stop the following ones.
+    /// Webhooks are posted in the background so their retries don't hold up
+    /// the other sinks; their result only says the delivery started, and a
+    /// failed delivery is notified. `concealed` keeps the text out of
+    /// clipboard history (incognito dictations).
+    pub async fn dispatch_sinks(
+        &self,
+        payload: &output::SinkPayload,
+        paste_text: &str,
+        app: &AppHandle,
+        settings: &OutputSettings,
+        concealed: bool,
+    ) -> Vec<output::SinkResult> {
+        let text = payload.text.as_str();
+        let timestamp = &payload.timestamp;
+        if text.is_empty() {
+            tracing::info!("Empty transcription, nothing to output");
+            return Vec::new();
+        }
+
+        if settings.sinks.is_empty() {
+            tracing::info!("No output sinks, transcription kept in history only");
+            return Vec::new();
         }
+
+        let mut results = Vec::with_capacity(settings.sinks.len());
+
+        for sink in &settings.sinks {
+            let mut method = None;
+            let result = match sink {
+                OutputSink::Paste if paste_text.is_empty() => Ok(()),
+                OutputSink::Paste => self
+                    .output_text(paste_text, app, settings, concealed)
+                    .await
+                    .map(|m| {
+                        method = Some(m);
+                    }),
+                OutputSink::Clipboard => {
+                    output::set_formatted_text(text, settings.clipboard_format, concealed)
+                        .map_err(|e| format!("Clipboard error: {}", e))
+                }
+                OutputSink::AppendToFile { path, header } => {
+                    output::append_to_file(path, text, timestamp, *header)
+                        .map_err(|e| e.to_string())
+                }
+                OutputSink::MarkdownJournal {
+                    folder,
+                    template,
+                    tags,
+                } => output::append_to_journal(folder, template, tags, text, timestamp)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string()),
+                OutputSink::Webhook {
+                    url,
+                    auth_header,
+                    max_retries,
+                } => {
+                    let retry = RetryPolicy {
+                        max_retries: *max_retries,
+                        ..RetryPolicy::default()
+                    };
+                    let auth = match auth_header.as_deref().map(str::trim) {
+                        Some(name) => crate::config::SecretsManager::get_webhook_auth(url)
+                            .map(|value| Some((name.to_string(), value)))
+                            .map_err(|_| "No webhook authorization configured".to_string()),
+                        None => Ok(None),
+                    };
+                    auth.map(|auth| spawn_webhook(app, url.clone(), payload.clone(), auth, retry))
+                }
+            };
+
+            if let Err(e) = &result {
+                tracing::error!("Output sink {} failed: {}", sink.label(), e);
+            }
+            results.push(output::SinkResult::new(sink.label(), result).with_method(method));
+        }
+
+        results
     }
 
     /// Output transcribed text (clipboard + paste or popup)
-    async fn output_text(&self, text: &str, app: &AppHandle) -> Result<(), String> {
+    ///
+    /// Shared by fresh transcriptions and history re-pastes so both follow
+    /// the same output rules. Returns how the text was delivered. Text put on
+    /// the clipboard is kept out of clipboard history when `concealed`.
+    pub async fn output_text(
+        &self,
+        text: &str,
+        app: &AppHandle,
+        settings: &OutputSettings,
+        concealed: bool,
+    ) -> Result<output::InjectionMethod, String> {
+        let audit = &settings.injection_audit;
+        if !audit.enabled || text.is_empty() {
+            return self
+                .deliver_text(text, app, settings, concealed)
+                .await
+                .map(|(method, _)| method);
+        }
+
+        // Capture the target before delivery can move focus
+        let target = output::get_active_window().map(|w| w.process_name);
+        let result = self.deliver_text(text, app, settings, concealed).await;
+        output::injection_audit().append(
+            &output::InjectionRecord::new(target, text.chars().count(), &result),
+            audit.retention_days,
+        );
+        result.map(|(method, _)| method)
+    }
+
+    /// Deliver text, returning the method used and how it was verified
+    async fn deliver_text(
+        &self,
+        text: &str,
+        app: &AppHandle,
+        settings: &OutputSettings,
+        concealed: bool,
+    ) -> Result<(output::InjectionMethod, Option<output::Verification>), String> {
+        use tauri_plugin_notification::NotificationExt;
+
         if text.is_empty() {
             tracing::info!("Empty transcription, nothing to output");
-            return Ok(());
+            return Ok((output::InjectionMethod::ClipboardOnly, None));
         }
 
-        let should_paste = output::should_auto_paste();
+        // An open dictation box collects transcriptions until the user sends them
+        if let Some(updated) = app
+            .state::<crate::AppState>()
+            .dictation_box
+            .lock()
+            .append(text)
+        {
+            let _ = app.emit("dictation-box:updated", updated);
+            tracing::info!("Transcription added to dictation box");
+            return Ok((output::InjectionMethod::DictationBox, None));
+        }
 
-        if should_paste {
-            output::copy_to_clipboard(text)
+        let selection = output::effective_selection(settings.selection);
+        let format = settings.clipboard_format;
+
+        // Typing mode skips the clipboard, except when an input method would
+        // recompose the keystrokes
+        let route = output::delivery_route(
+            output::is_secure_input_enabled(),
+            output::should_auto_paste(),
+            settings.mode == OutputMode::Type && !output::is_ime_active(),
+            selection.uses_clipboard(),
+        );
+
+        // Secure Input (macOS) swallows synthetic keystrokes; keep the text
+        // on the clipboard and tell the user why nothing was pasted
+        if route == output::DeliveryRoute::SecureInput {
+            output::set_selection_text(text, selection, format, concealed)
                 .map_err(|e| format!("Clipboard error: {}", e))?;
+            let holder = output::secure_input_holder();
+            let holder_name = holder
+                .as_ref()
+                .map(|h| h.display_name())
+                .unwrap_or_else(|| "another app".to_string());
+            tracing::warn!("Secure Input is enabled by {}, skipping paste", holder_name);
+
+            let _ = app.emit("output:secure-input", &holder);
+            let _ = app
+                .notification()
+                .builder()
+                .title("Paste Blocked by Secure Input")
+                .body(format!(
+                    "{} has Secure Input enabled. The text was copied to the clipboard.",
+                    holder_name
+                ))
+                .show();
+            return Ok((output::InjectionMethod::ClipboardOnly, None));
+        }
 
+        if route == output::DeliveryRoute::Popup {
+            let _ = output::set_selection_text(text, selection, format, concealed);
+            let _ = app.emit("show:popup", text);
+            tracing::info!("Showing popup (GigaWhisper is active window)");
+            return Ok((output::InjectionMethod::Popup, None));
+        }
+        let typing = route == output::DeliveryRoute::Type;
+
+        // Fit the leading space to the text already before the caret
+        let window = output::get_active_window().map(|w| w.id);
+        let spaced;
+        let text = if settings.smart_spacing && (typing || selection.uses_clipboard()) {
+            spaced = output::apply_smart_spacing(text, output::char_before_caret(window));
+            spaced.as_str()
+        } else {
+            text
+        };
+
+        if typing {
             tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            let report = output::type_into_focus(text, settings.typing_rate).await;
+            tracing::info!("Text typed ({:?})", report.verification);
+            match report.verification {
+                output::Verification::Confirmed | output::Verification::Unverified => {
+                    output::remember_injection(window, text);
+                    return Ok((report.method, Some(report.verification)));
+                }
+                verification => {
+                    // Part of the text may have been typed, so don't paste it again
+                    output::set_selection_text(text, selection, format, concealed)
+                        .map_err(|e| format!("Clipboard error: {}", e))?;
+                    let _ = app
+                        .notification()
+                        .builder()
+                        .title("Typing Failed")
+                        .body("The text could not be typed and was copied to the clipboard.")
+                        .show();
+                    return Ok((output::InjectionMethod::ClipboardOnly, Some(verification)));
+                }
+            }
+        }
 
-            output::send_ctrl_v()
-                .map_err(|e| format!("Keyboard error: {}", e))?;
+        output::set_selection_text(text, selection, format, concealed)
+            .map_err(|e| format!("Clipboard error: {}", e))?;
 
-            tracing::info!("Text pasted to active window");
+        if route == output::DeliveryRoute::PrimarySelection {
+            tracing::info!("Text placed in primary selection");
+            return Ok((output::InjectionMethod::PrimarySelection, None));
+        }
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+        let report = output::inject_text(text).await;
+        tracing::info!(
+            "Text output via {:?} ({:?})",
+            report.method,
+            report.verification
+        );
+
+        if report.method == output::InjectionMethod::ClipboardOnly {
+            let _ = app
+                .notification()
+                .builder()
+                .title("Paste Failed")
+                .body("The text could not be inserted and was copied to the clipboard. Paste it with Ctrl+V.")
+                .show();
         } else {
-            let _ = output::copy_to_clipboard(text);
-            let _ = app.emit("show:popup", text);
-            tracing::info!("Showing popup (GigaWhisper is active window)");
+            output::remember_injection(window, text);
         }
 
-        Ok(())
+        Ok((report.method, Some(report.verification)))
     }
 }
 
@@ -418,6 +1536,120 @@ impl Default for TranscriptionService {
     }
 }
 
+/// Words biasing recognition, from the transcription vocabulary only
+///
+/// Learned terms stay out: a wrong one would otherwise be reinforced by
+/// every prompt.
+pub fn recognition_terms(config: &Settings) -> Vec<String> {
+    config.transcription.vocabulary_terms()
+}
+
+/// Request options shared by every provider of a recording
+fn transcription_config(config: &Settings, translate: bool) -> TranscriptionConfig {
+    let keywords = recognition_terms(config);
+    TranscriptionConfig {
+        language: config.transcription.language.clone(),
+        translate,
+        prompt: config.transcription.recognition_prompt(&keywords),
+        keywords,
+    }
+}
+
+/// Name a configured provider reports in its results
+fn provider_name(kind: &ConfigProvider) -> &'static str {
+    match kind {
+        ConfigProvider::Local => "whisper.cpp",
+        ConfigProvider::Groq => "groq",
+        ConfigProvider::Deepgram => "deepgram",
+        ConfigProvider::Custom => "custom",
+    }
+}
+
+/// Post a transcription to a webhook in the background, notifying a failure
+fn spawn_webhook(
+    app: &AppHandle,
+    url: String,
+    payload: output::SinkPayload,
+    auth: Option<(String, String)>,
+    retry: RetryPolicy,
+) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let app = app.clone();
+    let tasks = app.state::<crate::AppState>().tasks.clone();
+    tasks.spawn("webhook", async move {
+        let auth = auth
+            .as_ref()
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+        if let Err(e) = output::post_webhook(&url, &payload, auth, &retry).await {
+            tracing::error!("Webhook delivery to {} failed: {}", url, e);
+            let _ = app
+                .notification()
+                .builder()
+                .title("Webhook Delivery Failed")
+                .body(format!("{}: {}", url, e))
+                .show();
+        }
+    });
+}
+
+/// Attach a language learning translation to history entry `id`
+fn save_translation(app: &AppHandle, id: &str, translation: String) {
+    let history = crate::history::get_history();
+    let mut history = history.write();
+    if history.set_translation(id, translation) {
+        if let Err(e) = history.save() {
+            tracing::error!("Failed to save history: {}", e);
+        }
+        let _ = app.emit("history:updated", ());
+    }
+}
+
+/// Log why the audio of entry `id` stayed WAV, telling the user once per
+/// run when `ffmpeg` is missing
+fn report_compression_failure(app: &AppHandle, id: &str, error: &TranscodeError) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tauri_plugin_notification::NotificationExt;
+
+    static REPORTED: AtomicBool = AtomicBool::new(false);
+
+    tracing::warn!("Keeping WAV audio for {}: {}", id, error);
+    let missing = matches!(error, TranscodeError::StorageEncoderMissing(_));
+    if missing && !REPORTED.swap(true, Ordering::Relaxed) {
+        let body = format!("{}. Install ffmpeg or choose WAV audio in settings.", error);
+        let _ = app
+            .notification()
+            .builder()
+            .title("Recordings Kept as WAV")
+            .body(body)
+            .show();
+    }
+}
+
+/// Keep an incognito dictation in memory and schedule its wipe
+fn keep_incognito(app: &AppHandle, text: String, minutes: u32) {
+    let ttl = std::time::Duration::from_secs(minutes as u64 * 60);
+    crate::history::incognito_store()
+        .lock()
+        .add(text, ttl, std::time::Instant::now());
+    let _ = app.emit("incognito:updated", ());
+    crate::tray::refresh_tray_menu(app);
+
+    let tasks = app.state::<crate::AppState>().tasks.clone();
+    let app = app.clone();
+    tasks.spawn("incognito-wipe", async move {
+        tokio::time::sleep(ttl).await;
+        let wiped = crate::history::incognito_store()
+            .lock()
+            .purge_expired(std::time::Instant::now());
+        if wiped > 0 {
+            tracing::info!("Wiped {} expired incognito dictation(s)", wiped);
+            let _ = app.emit("incognito:updated", ());
+            crate::tray::refresh_tray_menu(&app);
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
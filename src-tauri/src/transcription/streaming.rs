@@ -11,9 +11,34 @@
 //! - Chunked processing with overlapping windows
 //!
 //! **Current Implementation**: Segment callbacks for progress feedback.
+//!
+//! **Live Partials**: While recording, `LivePartials` collects captured audio
+//! and re-transcribes the current window every `partial_interval_ms` of new
+//! audio. Windows are capped at `MAX_PARTIAL_WINDOW_SECS`; a full window's
+//! text is committed and the next window starts empty. `AdaptiveChunking`
+//! stretches or shortens that interval from how long each partial takes.
+//!
+//! **Type As You Speak**: `SentenceStabilizer` picks the sentences of the
+//! partials that stopped changing so they can be pasted right away; the final
+//! transcription then only delivers what follows the pasted words.
 
 use std::sync::Arc;
 use parking_lot::Mutex;
+use serde::Serialize;
+use crate::utils::ChunkingStats;
+
+/// Event carrying a `PartialTranscript`
+pub const PARTIAL_EVENT: &str = "transcription:partial";
+
+/// Longest audio window re-transcribed for a partial result
+pub const MAX_PARTIAL_WINDOW_SECS: u32 = 30;
+
+/// Share of each partial interval the adaptive interval aims to spend
+/// transcribing, leaving the rest for capture and slower windows
+const TARGET_PARTIAL_LOAD: f64 = 0.5;
+
+/// Adaptive partial intervals are rounded to this step (ms)
+const PARTIAL_INTERVAL_STEP_MS: u32 = 100;
 
 /// Callback type for streaming transcription progress
 pub type StreamingCallback = Box<dyn Fn(StreamingEvent) + Send + 'static>;
@@ -123,6 +148,329 @@ impl Default for StreamingState {
     }
 }
 
+/// Partial text of a recording in progress
+#[derive(Debug, Clone, PartialEq, Serialize, schemars::JsonSchema)]
+pub struct PartialTranscript {
+    /// Text so far (replaces the previous partial)
+    pub text: String,
+    /// Audio covered by the text
+    pub audio_ms: u64,
+    /// Increases with each partial of a recording
+    pub sequence: u32,
+}
+
+/// Audio window re-transcribed while recording
+pub struct LivePartials {
+    /// Current window at the device sample rate
+    window: Vec<f32>,
+    sample_rate: u32,
+    /// Samples added since the window was last taken
+    pending: usize,
+    /// Samples between partial transcriptions
+    interval: usize,
+    /// Text of completed windows
+    committed: String,
+    /// Samples in completed windows
+    committed_samples: u64,
+    sequence: u32,
+}
+
+impl LivePartials {
+    pub fn new(sample_rate: u32, interval_ms: u32) -> Self {
+        Self {
+            window: Vec::new(),
+            sample_rate,
+            pending: 0,
+            interval: (sample_rate as u64 * interval_ms as u64 / 1000).max(1) as usize,
+            committed: String::new(),
+            committed_samples: 0,
+            sequence: 0,
+        }
+    }
+
+    /// Change the audio between partial transcriptions
+    pub fn set_interval_ms(&mut self, interval_ms: u32) {
+        self.interval = (self.sample_rate as u64 * interval_ms as u64 / 1000).max(1) as usize;
+    }
+
+    /// New audio since the window was last taken, in milliseconds
+    pub fn pending_ms(&self) -> u64 {
+        self.pending as u64 * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    /// Add captured samples
+    pub fn push(&mut self, samples: &[f32]) {
+        self.window.extend_from_slice(samples);
+        self.pending += samples.len();
+    }
+
+    /// Whether enough new audio arrived for another partial
+    pub fn is_due(&self) -> bool {
+        self.pending >= self.interval || (self.pending > 0 && self.is_window_full())
+    }
+
+    fn is_window_full(&self) -> bool {
+        self.window.len() >= (self.sample_rate * MAX_PARTIAL_WINDOW_SECS) as usize
+    }
+
+    /// Audio to transcribe next, at the device sample rate
+    pub fn take_window(&mut self) -> Vec<f32> {
+        self.pending = 0;
+        self.window.clone()
+    }
+
+    /// Combine the transcription of the window last taken with earlier text
+    ///
+    /// A full window is committed, so the next one starts empty.
+    pub fn apply(&mut self, window_text: &str, window_len: usize) -> PartialTranscript {
+        let window_text = window_text.trim();
+        let text = match (self.committed.is_empty(), window_text.is_empty()) {
+            (true, _) => window_text.to_string(),
+            (false, true) => self.committed.clone(),
+            (false, false) => format!("{} {}", self.committed, window_text),
+        };
+        let audio_samples = self.committed_samples + window_len as u64;
+
+        if window_len >= (self.sample_rate * MAX_PARTIAL_WINDOW_SECS) as usize {
+            self.committed = text.clone();
+            self.committed_samples = audio_samples;
+            self.window.drain(..window_len.min(self.window.len()));
+        }
+
+        self.sequence += 1;
+        PartialTranscript {
+            text,
+            audio_ms: audio_samples * 1000 / self.sample_rate.max(1) as u64,
+            sequence: self.sequence,
+        }
+    }
+}
+
+/// Interval between live partials, adapted to their real-time factor
+///
+/// Each partial re-transcribes the whole window, so on a slow machine a short
+/// interval queues partials behind each other and on a fast one a long
+/// interval only adds lag. After each partial the interval moves halfway
+/// towards the one where transcribing takes `TARGET_PARTIAL_LOAD` of it,
+/// within the configured bounds.
+#[derive(Debug, Clone)]
+pub struct AdaptiveChunking {
+    min_ms: u32,
+    max_ms: u32,
+    stats: ChunkingStats,
+}
+
+impl AdaptiveChunking {
+    /// Start at `interval_ms`; `adaptive = false` keeps it fixed but still
+    /// measures the partials
+    pub fn new(interval_ms: u32, min_ms: u32, max_ms: u32, adaptive: bool) -> Self {
+        let (min_ms, max_ms) = (min_ms.min(max_ms), max_ms.max(min_ms));
+        let interval_ms = if adaptive {
+            interval_ms.clamp(min_ms, max_ms)
+        } else {
+            interval_ms
+        };
+        Self {
+            min_ms,
+            max_ms,
+            stats: ChunkingStats {
+                interval_ms,
+                adaptive,
+                ..ChunkingStats::default()
+            },
+        }
+    }
+
+    /// Current interval between partials, in milliseconds
+    pub fn interval_ms(&self) -> u32 {
+        self.stats.interval_ms
+    }
+
+    pub fn stats(&self) -> ChunkingStats {
+        self.stats
+    }
+
+    /// Take the time a partial of `audio_ms` of new audio took to transcribe
+    /// and return the next interval
+    pub fn record(&mut self, processing_ms: u64, audio_ms: u64) -> u32 {
+        if audio_ms == 0 {
+            return self.stats.interval_ms;
+        }
+        let rtf = processing_ms as f64 / audio_ms as f64;
+        self.stats.avg_real_time_factor = if self.stats.partials == 0 {
+            rtf
+        } else {
+            self.stats.avg_real_time_factor * 0.7 + rtf * 0.3
+        };
+        self.stats.last_real_time_factor = rtf;
+        self.stats.partials += 1;
+        if !self.stats.adaptive {
+            return self.stats.interval_ms;
+        }
+
+        let current = self.stats.interval_ms as f64;
+        let target = processing_ms as f64 / TARGET_PARTIAL_LOAD;
+        let step = PARTIAL_INTERVAL_STEP_MS as f64;
+        let next = ((current + (target - current) / 2.0) / step).round() * step;
+        let next = (next as u32).clamp(self.min_ms, self.max_ms);
+
+        if next > self.stats.interval_ms {
+            self.stats.increases += 1;
+        } else if next < self.stats.interval_ms {
+            self.stats.decreases += 1;
+        }
+        self.stats.interval_ms = next;
+        next
+    }
+}
+
+/// Sentences pasted while recording
+///
+/// A sentence is pasted once a later sentence has started and its text was
+/// the same in two consecutive partials. Pasted text is never re-typed: the
+/// final transcription is aligned to it character by character.
+#[derive(Debug, Default)]
+pub struct SentenceStabilizer {
+    /// Unpasted sentences of the previous partial
+    previous: Vec<String>,
+    /// Sentences pasted so far, in order
+    pasted: Vec<String>,
+    /// Text typed for the pasted sentences, after post-processing
+    typed: String,
+    /// Set once the recording stopped; nothing more is pasted
+    closed: bool,
+}
+
+impl SentenceStabilizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether any sentence was pasted
+    pub fn has_pasted(&self) -> bool {
+        !self.pasted.is_empty()
+    }
+
+    /// Take a new partial and return the sentences that became stable
+    ///
+    /// The returned sentences count as pasted.
+    pub fn update(&mut self, partial: &str) -> Vec<String> {
+        if self.closed {
+            return Vec::new();
+        }
+        let sentences = split_sentences(unsent_text(&self.pasted.join(" "), partial));
+        // The last sentence may still be in progress
+        let stable: Vec<String> = sentences
+            .iter()
+            .take(sentences.len().saturating_sub(1))
+            .zip(&self.previous)
+            .take_while(|(sentence, previous)| sentence == previous && ends_sentence(sentence))
+            .map(|(sentence, _)| sentence.clone())
+            .collect();
+
+        self.previous = sentences[stable.len()..].to_vec();
+        self.pasted.extend(stable.iter().cloned());
+        stable
+    }
+
+    /// Record the text typed for a sentence returned by `update`
+    pub fn typed(&mut self, text: &str) {
+        self.typed.push_str(text);
+    }
+
+    /// Stop pasting and return the text typed so far
+    pub fn finish(&mut self) -> String {
+        self.closed = true;
+        self.previous.clear();
+        self.pasted.clear();
+        std::mem::take(&mut self.typed).trim().to_string()
+    }
+}
+
+/// Split text into sentences, keeping their punctuation
+pub fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        current.push(c);
+        // "3.5" or "e.g." mid-word don't end a sentence; CJK needs no space
+        let ends = matches!(c, '。' | '！' | '？')
+            || (matches!(c, '.' | '!' | '?' | '…')
+                && chars.peek().is_none_or(|next| next.is_whitespace()));
+        if ends {
+            let sentence = current.trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence.to_string());
+            }
+            current.clear();
+        }
+    }
+    let rest = current.trim();
+    if !rest.is_empty() {
+        sentences.push(rest.to_string());
+    }
+    sentences
+}
+
+fn ends_sentence(sentence: &str) -> bool {
+    sentence
+        .chars()
+        .last()
+        .is_some_and(|c| matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？'))
+}
+
+/// Whitespace between words; the no-break spaces of typography
+/// ("Vraiment\u{202f}?") belong to the word they follow
+fn is_word_break(c: char) -> bool {
+    c.is_whitespace() && !crate::output::is_no_break_space(c)
+}
+
+/// Text after as many letters and digits as `sent` has
+///
+/// Counting characters rather than words also aligns scripts written
+/// without spaces. Punctuation right after the last one, including a
+/// no-break space before it, counts as sent.
+pub fn unsent_text<'a>(sent: &str, text: &'a str) -> &'a str {
+    let mut remaining = sent.chars().filter(|c| c.is_alphanumeric()).count();
+    let mut rest = text.trim_start();
+    if remaining == 0 {
+        return rest;
+    }
+    while remaining > 0 {
+        let Some(c) = rest.chars().next() else {
+            break;
+        };
+        if c.is_alphanumeric() {
+            remaining -= 1;
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    rest.trim_start_matches(|c: char| !c.is_alphanumeric() && !is_word_break(c))
+        .trim_start()
+}
+
+/// Words of `sent` that differ from the start of `text` (case and
+/// punctuation ignored, so "va\u{202f}?" and "va ?" are the same word)
+pub fn changed_words(sent: &str, text: &str) -> usize {
+    let words = |text: &str| -> Vec<String> {
+        text.split_whitespace()
+            .map(|word| {
+                word.chars()
+                    .filter(|c| c.is_alphanumeric())
+                    .flat_map(char::to_lowercase)
+                    .collect::<String>()
+            })
+            .filter(|word| !word.is_empty())
+            .collect()
+    };
+    let mut words_of_text = words(text).into_iter();
+    words(sent)
+        .into_iter()
+        .filter(|word| words_of_text.next().as_ref() != Some(word))
+        .count()
+}
+
 /// Documentation: Streaming Transcription Status
 ///
 /// # Current Status
@@ -164,6 +512,46 @@ mod tests {
     use super::*;
     use std::sync::atomic::{AtomicU32, Ordering};
 
+    #[test]
+    fn test_adaptive_chunking_grows_when_partials_fall_behind() {
+        let mut chunking = AdaptiveChunking::new(1500, 1000, 5000, true);
+        // 1.2 s to transcribe 1.5 s of new audio: aim for 2.4 s
+        assert_eq!(chunking.record(1200, 1500), 2000);
+        assert_eq!(chunking.record(1200, 2000), 2200);
+        assert!((chunking.stats().last_real_time_factor - 0.6).abs() < 1e-9);
+
+        for _ in 0..10 {
+            chunking.record(8000, 5000);
+        }
+        assert_eq!(chunking.interval_ms(), 5000);
+        let stats = chunking.stats();
+        assert_eq!(stats.partials, 12);
+        assert_eq!(stats.decreases, 0);
+        assert!(stats.increases >= 3);
+    }
+
+    #[test]
+    fn test_adaptive_chunking_shrinks_with_headroom() {
+        let mut chunking = AdaptiveChunking::new(3000, 1000, 5000, true);
+        assert_eq!(chunking.record(200, 3000), 1700);
+        for _ in 0..5 {
+            chunking.record(100, 1000);
+        }
+        assert_eq!(chunking.interval_ms(), 1000);
+        assert_eq!(chunking.stats().increases, 0);
+    }
+
+    #[test]
+    fn test_fixed_chunking_still_measures() {
+        let mut chunking = AdaptiveChunking::new(1500, 1000, 5000, false);
+        assert_eq!(chunking.record(3000, 1500), 1500);
+        assert_eq!(chunking.record(0, 0), 1500);
+        let stats = chunking.stats();
+        assert!(!stats.adaptive);
+        assert_eq!(stats.partials, 1);
+        assert!((stats.avg_real_time_factor - 2.0).abs() < 1e-9);
+    }
+
     // ============================================================
     // StreamingState Creation Tests
     // ============================================================
@@ -608,6 +996,106 @@ mod tests {
         assert_eq!(state.full_text(), "Second");
     }
 
+    // ============================================================
+    // Live Partials Tests
+    // ============================================================
+
+    #[test]
+    fn test_live_partials_due_every_interval() {
+        let mut live = LivePartials::new(1000, 1500);
+        live.push(&[0.0; 1000]);
+        assert!(!live.is_due());
+        live.push(&[0.0; 600]);
+        assert!(live.is_due());
+
+        let window = live.take_window();
+        assert_eq!(window.len(), 1600);
+        assert!(!live.is_due());
+
+        let partial = live.apply(" Hello ", window.len());
+        assert_eq!(partial.text, "Hello");
+        assert_eq!(partial.audio_ms, 1600);
+        assert_eq!(partial.sequence, 1);
+    }
+
+    #[test]
+    fn test_live_partials_commit_full_window() {
+        let rate = 100;
+        let mut live = LivePartials::new(rate, 1000);
+        live.push(&vec![0.0; (rate * MAX_PARTIAL_WINDOW_SECS) as usize]);
+        let window = live.take_window();
+        // Audio captured while the window was being transcribed
+        live.push(&[0.0; 50]);
+        assert_eq!(live.apply("first part", window.len()).text, "first part");
+
+        assert_eq!(live.take_window().len(), 50);
+        let partial = live.apply("second", 50);
+        assert_eq!(partial.text, "first part second");
+        assert_eq!(partial.audio_ms, (MAX_PARTIAL_WINDOW_SECS as u64) * 1000 + 500);
+        assert_eq!(partial.sequence, 2);
+    }
+
+    // ============================================================
+    // Sentence Stabilizer Tests
+    // ============================================================
+
+    #[test]
+    fn test_split_sentences() {
+        assert_eq!(
+            split_sentences("Hello there. It costs 3.5 euros! Really? and then"),
+            vec!["Hello there.", "It costs 3.5 euros!", "Really?", "and then"]
+        );
+        assert_eq!(split_sentences("你好。世界"), vec!["你好。", "世界"]);
+        assert!(split_sentences("  ").is_empty());
+    }
+
+    #[test]
+    fn test_stabilizer_pastes_unchanged_sentences() {
+        let mut stabilizer = SentenceStabilizer::new();
+        assert!(stabilizer.update("Hello world. How are").is_empty());
+        // Changed since the previous partial
+        assert!(stabilizer.update("Hello, world. How are you").is_empty());
+        assert_eq!(stabilizer.update("Hello, world. How are you? I"), vec!["Hello, world."]);
+        assert!(stabilizer.has_pasted());
+        // The last sentence is never pasted, even when complete
+        assert!(stabilizer.update("Hello, world. How are you?").is_empty());
+        assert_eq!(
+            stabilizer.update("Hello, world. How are you? Fine."),
+            vec!["How are you?"]
+        );
+
+        stabilizer.typed("Hello, world.");
+        stabilizer.typed(" How are you?");
+        assert_eq!(stabilizer.finish(), "Hello, world. How are you?");
+        assert!(stabilizer
+            .update("Hello, world. How are you? Fine. Bye")
+            .is_empty());
+    }
+
+    #[test]
+    fn test_unsent_text_and_corrections() {
+        let sent = "Hello, world. How are you?";
+        let final_text = "Hello world. How are you? Fine, thanks.";
+        assert_eq!(unsent_text(sent, final_text), "Fine, thanks.");
+        assert_eq!(changed_words(sent, final_text), 0);
+
+        assert_eq!(changed_words(sent, "Hello word. Who are you? Fine."), 2);
+        assert_eq!(unsent_text(sent, "Hello world."), "");
+        assert_eq!(unsent_text("", " Hi there"), "Hi there");
+        // No spaces between words
+        assert_eq!(unsent_text("你好。", "你好。今天很好。"), "今天很好。");
+        assert_eq!(unsent_text("今日は", "今日は、いい天気"), "いい天気");
+
+        // French typography puts no-break spaces before punctuation
+        let sent = "Bonjour\u{202f}! Ça va\u{202f}?";
+        let final_text = "Bonjour\u{202f}! Ça va\u{202f}? «\u{a0}Oui\u{a0}».";
+        assert_eq!(unsent_text(sent, final_text), "«\u{a0}Oui\u{a0}».");
+        let quoted = "Il a dit «\u{a0}oui\u{a0}». Bien.";
+        assert_eq!(unsent_text("Il a dit «\u{a0}oui\u{a0}»", quoted), "Bien.");
+        assert_eq!(changed_words(sent, final_text), 0);
+        assert_eq!(changed_words(sent, "Bonjour ! Ça va ?"), 0);
+    }
+
     // ============================================================
     // Documentation Module Tests
     // ============================================================
@@ -4,7 +4,10 @@
 //! Supports GPU acceleration via Vulkan (AMD/Intel/NVIDIA) or CUDA (NVIDIA).
 //! Includes automatic CPU thread optimization.
 
-use super::{TranscriptionConfig, TranscriptionError, TranscriptionProvider, TranscriptionResult};
+use super::{
+    TranscriptSegment, TranscriptionConfig, TranscriptionError, TranscriptionProvider,
+    TranscriptionResult,
+};
 use crate::utils::get_optimal_threads;
 use async_trait::async_trait;
 use parking_lot::Mutex;
@@ -18,6 +21,28 @@ const DEFAULT_TRANSCRIPTION_TIMEOUT_SECS: u64 = 300;
 /// Default idle timeout before unloading model (10 minutes)
 const DEFAULT_IDLE_TIMEOUT_SECS: u64 = 600;
 
+/// How whisper.cpp picks tokens
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodingOptions {
+    /// Beam search width (1 = greedy decoding)
+    pub beam_size: usize,
+    /// Temperature of the first pass
+    pub temperature: f32,
+    /// No-speech probability above which a segment is dropped
+    pub no_speech_threshold: f32,
+}
+
+impl Default for DecodingOptions {
+    /// Greedy decoding with whisper.cpp's own thresholds
+    fn default() -> Self {
+        Self {
+            beam_size: 1,
+            temperature: 0.0,
+            no_speech_threshold: 0.6,
+        }
+    }
+}
+
 /// Whisper.cpp transcription provider
 pub struct WhisperProvider {
     model_path: PathBuf,
@@ -27,6 +52,8 @@ pub struct WhisperProvider {
     /// Actual threads to use (resolved from configured or auto-detected)
     effective_threads: usize,
     gpu_enabled: bool,
+    /// Beam width, temperature and no-speech threshold
+    decoding: DecodingOptions,
     /// Transcription timeout
     timeout: Duration,
     /// Last time the model was used for transcription
@@ -47,6 +74,7 @@ impl WhisperProvider {
             configured_threads: threads,
             effective_threads,
             gpu_enabled: false,
+            decoding: DecodingOptions::default(),
             timeout: Duration::from_secs(DEFAULT_TRANSCRIPTION_TIMEOUT_SECS),
             last_use: Arc::new(Mutex::new(None)),
             idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
@@ -64,6 +92,7 @@ impl WhisperProvider {
             configured_threads: threads,
             effective_threads,
             gpu_enabled,
+            decoding: DecodingOptions::default(),
             timeout: Duration::from_secs(DEFAULT_TRANSCRIPTION_TIMEOUT_SECS),
             last_use: Arc::new(Mutex::new(None)),
             idle_timeout: Duration::from_secs(DEFAULT_IDLE_TIMEOUT_SECS),
@@ -76,6 +105,24 @@ impl WhisperProvider {
         self
     }
 
+    /// Decode with beam search of `beam_size` candidates (1 = greedy)
+    pub fn with_beam_size(mut self, beam_size: usize) -> Self {
+        self.decoding.beam_size = beam_size.max(1);
+        self
+    }
+
+    /// Start decoding at `temperature` (0.0 = most likely tokens)
+    pub fn with_temperature(mut self, temperature: f32) -> Self {
+        self.decoding.temperature = temperature.max(0.0);
+        self
+    }
+
+    /// Drop segments whose no-speech probability exceeds `threshold`
+    pub fn with_no_speech_threshold(mut self, threshold: f32) -> Self {
+        self.decoding.no_speech_threshold = threshold;
+        self
+    }
+
     /// Set custom idle timeout before unloading model
     pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
         self.idle_timeout = idle_timeout;
@@ -87,6 +134,16 @@ impl WhisperProvider {
         self.timeout
     }
 
+    /// Get the beam search width (1 = greedy decoding)
+    pub fn beam_size(&self) -> usize {
+        self.decoding.beam_size
+    }
+
+    /// Get the decoding options
+    pub fn decoding(&self) -> DecodingOptions {
+        self.decoding
+    }
+
     /// Get the current idle timeout
     pub fn idle_timeout(&self) -> Duration {
         self.idle_timeout
@@ -192,8 +249,17 @@ impl WhisperProvider {
             ))
         })?;
 
-        let ctx = whisper_rs::WhisperContext::new_with_params(path_str, params)
-            .map_err(|e| TranscriptionError::Failed(e.to_string()))?;
+        let ctx = whisper_rs::WhisperContext::new_with_params(path_str, params).map_err(|e| {
+            if use_gpu {
+                TranscriptionError::Failed(format!(
+                    "GPU initialization failed ({}): {}",
+                    Self::gpu_backend_name(),
+                    e
+                ))
+            } else {
+                TranscriptionError::Failed(e.to_string())
+            }
+        })?;
 
         *self.context.lock() = Some(ctx);
 
@@ -229,6 +295,7 @@ impl WhisperProvider {
         audio: Vec<f32>,
         config: TranscriptionConfig,
         threads: usize,
+        decoding: DecodingOptions,
     ) -> Result<TranscriptionResult, TranscriptionError> {
         let start = Instant::now();
 
@@ -243,14 +310,22 @@ impl WhisperProvider {
             .map_err(|e| TranscriptionError::Failed(e.to_string()))?;
 
         // Configure parameters
-        let mut params = whisper_rs::FullParams::new(whisper_rs::SamplingStrategy::Greedy {
-            best_of: 1,
-        });
+        let strategy = if decoding.beam_size > 1 {
+            whisper_rs::SamplingStrategy::BeamSearch {
+                beam_size: decoding.beam_size as i32,
+                patience: -1.0,
+            }
+        } else {
+            whisper_rs::SamplingStrategy::Greedy { best_of: 1 }
+        };
+        let mut params = whisper_rs::FullParams::new(strategy);
 
         params.set_n_threads(threads as i32);
         params.set_print_progress(false);
         params.set_print_realtime(false);
         params.set_print_timestamps(false);
+        params.set_temperature(decoding.temperature);
+        params.set_no_speech_thold(decoding.no_speech_threshold);
 
         // Set language if specified
         if config.language != "auto" {
@@ -259,6 +334,11 @@ impl WhisperProvider {
 
         params.set_translate(config.translate);
 
+        // Bias recognition toward the user's vocabulary
+        if let Some(prompt) = &config.prompt {
+            params.set_initial_prompt(&prompt.replace('\0', ""));
+        }
+
         // Run inference
         state
             .full(params, &audio)
@@ -270,19 +350,37 @@ impl WhisperProvider {
             .map_err(|e| TranscriptionError::Failed(e.to_string()))?;
 
         let mut text = String::new();
+        let mut segments = Vec::new();
         for i in 0..num_segments {
             if let Ok(segment) = state.full_get_segment_text(i) {
                 text.push_str(&segment);
+                // Segment times are in centiseconds
+                let t0 = state.full_get_segment_t0(i).unwrap_or_default().max(0) as u64;
+                let t1 = state.full_get_segment_t1(i).unwrap_or_default().max(0) as u64;
+                segments.push(TranscriptSegment {
+                    start_ms: t0 * 10,
+                    end_ms: t1 * 10,
+                    text: segment.trim().to_string(),
+                });
             }
         }
 
+        // Language the model detected (or was given)
+        let language = state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .map(str::to_string);
+
         let duration_ms = start.elapsed().as_millis() as u64;
 
         Ok(TranscriptionResult {
             text: text.trim().to_string(),
-            language: None,
+            language,
             duration_ms,
             provider: "whisper.cpp".to_string(),
+            segments,
+            model: None,
         })
     }
 }
@@ -296,6 +394,7 @@ impl Clone for WhisperProvider {
             configured_threads: self.configured_threads,
             effective_threads: self.effective_threads,
             gpu_enabled: self.gpu_enabled,
+            decoding: self.decoding,
             timeout: self.timeout,
             last_use: self.last_use.clone(),
             idle_timeout: self.idle_timeout,
@@ -320,13 +419,14 @@ impl TranscriptionProvider for WhisperProvider {
         let audio_vec = audio.to_vec();
         let config_clone = config.clone();
         let threads = self.effective_threads;
+        let decoding = self.decoding;
         let timeout_duration = self.timeout;
         let timeout_secs = timeout_duration.as_secs();
 
         // Run transcription in blocking thread pool with timeout
         // This avoids holding the MutexGuard across an await point
         let transcription_task = tokio::task::spawn_blocking(move || {
-            Self::transcribe_sync(context, audio_vec, config_clone, threads)
+            Self::transcribe_sync(context, audio_vec, config_clone, threads, decoding)
         });
 
         let result = match tokio::time::timeout(timeout_duration, transcription_task).await {
@@ -422,6 +522,36 @@ mod tests {
         assert_eq!(provider.timeout(), Duration::from_secs(60));
     }
 
+    #[test]
+    fn test_with_beam_size() {
+        let provider = WhisperProvider::new(PathBuf::from("/fake/model.bin"), 0);
+        assert_eq!(provider.beam_size(), 1);
+
+        let provider = provider.with_beam_size(5);
+        assert_eq!(provider.clone().beam_size(), 5);
+        assert_eq!(provider.with_beam_size(0).beam_size(), 1);
+    }
+
+    #[test]
+    fn test_with_decoding_options() {
+        let provider = WhisperProvider::new(PathBuf::from("/fake/model.bin"), 0);
+        assert_eq!(provider.decoding(), DecodingOptions::default());
+
+        let provider = provider
+            .with_beam_size(3)
+            .with_temperature(0.4)
+            .with_no_speech_threshold(0.8);
+        assert_eq!(
+            provider.clone().decoding(),
+            DecodingOptions {
+                beam_size: 3,
+                temperature: 0.4,
+                no_speech_threshold: 0.8,
+            }
+        );
+        assert_eq!(provider.with_temperature(-1.0).decoding().temperature, 0.0);
+    }
+
     #[test]
     fn test_with_timeout_zero() {
         let provider = WhisperProvider::new(PathBuf::from("/fake/model.bin"), 0)
@@ -2,28 +2,52 @@
 //!
 //! System tray menu construction and event handling.
 
+use crate::audio::AudioCapture;
+use crate::config::QualityPreset;
+use crate::{AppState, RecordingState};
+use parking_lot::Mutex;
 use tauri::{
-    menu::{Menu, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconEvent},
-    Manager,
+    AppHandle, Emitter, Manager, Wry,
 };
+use tauri_plugin_notification::NotificationExt;
+
+/// Menu id prefix for input device entries
+const DEVICE_ITEM_PREFIX: &str = "device:";
+
+/// Menu id of the "system default" input device entry
+const DEFAULT_DEVICE_ITEM: &str = "device-default";
+
+/// Menu id prefix for quality preset entries
+const PRESET_ITEM_PREFIX: &str = "preset:";
+
+/// Menu id prefix for workspace entries
+const WORKSPACE_ITEM_PREFIX: &str = "workspace:";
+
+/// Tray tooltip while idle
+const TOOLTIP: &str = "GigaWhisper - Voice Transcription";
+
+/// Menu id of the entry wiping incognito dictations
+const WIPE_INCOGNITO_ITEM: &str = "wipe-incognito";
+
+/// Window in the foreground when the pointer reached the tray icon
+///
+/// Clicking the tray hands the foreground to the taskbar, so menu entries
+/// acting on the user's window read it from here.
+static MENU_TARGET: Mutex<Option<isize>> = Mutex::new(None);
 
 /// Setup system tray
 /// Uses the tray icon created from tauri.conf.json (id: "main") and adds menu + events
 pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    // Create menu items
-    let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-    // Build menu
-    let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+    let menu = build_menu(app.handle())?;
 
     // Get existing tray icon created from tauri.conf.json (id: "main")
     let tray = app.tray_by_id("main").ok_or("Tray icon 'main' not found")?;
 
     // Set menu on existing tray
     tray.set_menu(Some(menu))?;
-    tray.set_tooltip(Some("GigaWhisper - Voice Transcription"))?;
+    tray.set_tooltip(Some(TOOLTIP))?;
 
     // Set up menu event handler
     tray.on_menu_event(|app, event| {
@@ -31,25 +55,239 @@ pub fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     });
 
     // Set up tray icon click event handler
-    tray.on_tray_icon_event(|tray, event| {
-        if let TrayIconEvent::Click {
+    tray.on_tray_icon_event(|tray, event| match event {
+        TrayIconEvent::Click {
             button: MouseButton::Left,
             button_state: MouseButtonState::Up,
             ..
-        } = event
-        {
+        } => {
             // Show main window on left click
             if let Some(window) = tray.app_handle().get_webview_window("main") {
                 let _ = window.show();
                 let _ = window.set_focus();
             }
         }
+        // Remember the user's window and refresh the device list before the menu opens
+        TrayIconEvent::Enter { .. } => {
+            *MENU_TARGET.lock() = crate::commands::dictation_box::focused_target();
+            refresh_tray_menu(tray.app_handle());
+        }
+        TrayIconEvent::Click {
+            button: MouseButton::Right,
+            button_state: MouseButtonState::Down,
+            ..
+        } => refresh_tray_menu(tray.app_handle()),
+        _ => {}
     });
 
     tracing::info!("System tray setup complete");
     Ok(())
 }
 
+/// Build the tray menu with the current input device list
+fn build_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let show_item = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
+    let dictation_item =
+        MenuItem::with_id(app, "dictation-box", "Dictation Box", true, None::<&str>)?;
+    let viewer_item =
+        MenuItem::with_id(app, "transcript-viewer", "Transcript Viewer", true, None::<&str>)?;
+    let devices_menu = build_device_submenu(app)?;
+    let quality_menu = build_quality_submenu(app)?;
+    let workspaces_menu = build_workspace_submenu(app)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let quit_item = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
+
+    let menu = Menu::with_items(
+        app,
+        &[
+            &show_item,
+            &dictation_item,
+            &viewer_item,
+            &devices_menu,
+            &quality_menu,
+            &workspaces_menu,
+            &separator,
+            &quit_item,
+        ],
+    )?;
+
+    // Only present while incognito dictations are held in memory
+    let incognito = crate::history::incognito_store().lock().len();
+    if incognito > 0 {
+        let wipe_item = MenuItem::with_id(
+            app,
+            WIPE_INCOGNITO_ITEM,
+            format!("Wipe Incognito Dictations ({})", incognito),
+            true,
+            None::<&str>,
+        )?;
+        menu.insert(&wipe_item, 3)?;
+    }
+
+    Ok(menu)
+}
+
+/// Build the "Quality" submenu, with the active preset checked
+fn build_quality_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let active = app.state::<AppState>().config.read().transcription.quality_preset;
+
+    let submenu = Submenu::with_id(app, "quality", "Quality", true)?;
+    for preset in QualityPreset::NAMED {
+        submenu.append(&CheckMenuItem::with_id(
+            app,
+            format!("{}{}", PRESET_ITEM_PREFIX, preset.display_name().to_lowercase()),
+            preset.display_name(),
+            true,
+            preset == active,
+            None::<&str>,
+        )?)?;
+    }
+    // Custom is reached by changing a setting, not picked here
+    if active == QualityPreset::Custom {
+        submenu.append(&PredefinedMenuItem::separator(app)?)?;
+        submenu.append(&CheckMenuItem::with_id(
+            app,
+            "preset-custom",
+            QualityPreset::Custom.display_name(),
+            false,
+            true,
+            None::<&str>,
+        )?)?;
+    }
+
+    Ok(submenu)
+}
+
+/// Build the "Workspace" submenu, with the active workspace checked
+fn build_workspace_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let workspaces = app.state::<AppState>().config.read().workspaces.clone();
+
+    let submenu = Submenu::with_id(app, "workspaces", "Workspace", true)?;
+    for workspace in &workspaces.list {
+        submenu.append(&CheckMenuItem::with_id(
+            app,
+            format!("{}{}", WORKSPACE_ITEM_PREFIX, workspace.id),
+            &workspace.name,
+            true,
+            workspace.id == workspaces.active,
+            None::<&str>,
+        )?)?;
+    }
+
+    Ok(submenu)
+}
+
+/// Build the "Microphone" submenu, with the active device checked
+fn build_device_submenu(app: &AppHandle) -> tauri::Result<Submenu<Wry>> {
+    let selected = app
+        .state::<AppState>()
+        .config
+        .read()
+        .audio
+        .input_device
+        .clone();
+
+    let submenu = Submenu::with_id(app, "devices", "Microphone", true)?;
+    submenu.append(&CheckMenuItem::with_id(
+        app,
+        DEFAULT_DEVICE_ITEM,
+        "System Default",
+        true,
+        selected.is_none(),
+        None::<&str>,
+    )?)?;
+
+    match AudioCapture::list_devices() {
+        Ok(devices) if !devices.is_empty() => {
+            submenu.append(&PredefinedMenuItem::separator(app)?)?;
+            for device in devices {
+                let checked = selected.as_deref() == Some(device.id.as_str());
+                submenu.append(&CheckMenuItem::with_id(
+                    app,
+                    format!("{}{}", DEVICE_ITEM_PREFIX, device.id),
+                    &device.name,
+                    true,
+                    checked,
+                    None::<&str>,
+                )?)?;
+            }
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to list audio devices for tray: {}", e),
+    }
+
+    Ok(submenu)
+}
+
+/// Rebuild the tray menu (device list and checked state)
+pub fn refresh_tray_menu(app: &AppHandle) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+
+    match build_menu(app) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                tracing::warn!("Failed to update tray menu: {}", e);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to build tray menu: {}", e),
+    }
+}
+
+/// Show in the tray tooltip whether an incognito recording is in progress
+pub fn set_incognito_recording(app: &AppHandle, active: bool) {
+    let Some(tray) = app.tray_by_id("main") else {
+        return;
+    };
+    let tooltip = if active {
+        "GigaWhisper - Incognito recording"
+    } else {
+        TOOLTIP
+    };
+    if let Err(e) = tray.set_tooltip(Some(tooltip)) {
+        tracing::warn!("Failed to update tray tooltip: {}", e);
+    }
+}
+
+/// Switch the input device from the tray
+///
+/// Saves the selection immediately. When idle, any held capture is released
+/// so the next recording opens the new device; an active recording keeps its
+/// device until it ends.
+fn select_input_device(app: &AppHandle, device: Option<String>) {
+    let state = app.state::<AppState>();
+
+    let settings = {
+        let mut config = state.config.write();
+        if config.audio.input_device == device {
+            return;
+        }
+        config.audio.input_device = device.clone();
+        config.clone()
+    };
+
+    if let Err(e) = settings.save() {
+        tracing::error!("Failed to save input device: {}", e);
+    }
+
+    let is_idle = matches!(
+        *state.recording_state.read(),
+        RecordingState::Idle | RecordingState::Error(_)
+    );
+    if is_idle {
+        state.audio_capture.lock().take();
+    }
+
+    tracing::info!(
+        "Input device switched from tray: {}",
+        device.as_deref().unwrap_or("system default")
+    );
+    let _ = app.emit("audio:device-changed", &device);
+
+    refresh_tray_menu(app);
+}
+
 /// Handle tray menu events
 fn handle_menu_event(app: &tauri::AppHandle, item_id: &str) {
     match item_id {
@@ -59,10 +297,59 @@ fn handle_menu_event(app: &tauri::AppHandle, item_id: &str) {
                 let _ = window.set_focus();
             }
         }
+        "dictation-box" => {
+            let target = MENU_TARGET
+                .lock()
+                .take()
+                .or_else(crate::commands::dictation_box::focused_target);
+            if let Err(e) = crate::commands::dictation_box::show_dictation_box(app, target) {
+                tracing::error!("Failed to open dictation box: {}", e);
+            }
+        }
+        "transcript-viewer" => {
+            if let Err(e) = crate::commands::viewer::show_viewer(app) {
+                tracing::error!("Failed to open transcript viewer: {}", e);
+            }
+        }
         "quit" => {
             tracing::info!("Quit requested from tray");
             app.exit(0);
         }
-        _ => {}
+        WIPE_INCOGNITO_ITEM => {
+            let wiped = crate::history::incognito_store().lock().clear();
+            tracing::info!("Wiped {} incognito dictation(s) from tray", wiped);
+            let _ = app.emit("incognito:updated", ());
+            refresh_tray_menu(app);
+        }
+        DEFAULT_DEVICE_ITEM => select_input_device(app, None),
+        id => {
+            if let Some(device) = id.strip_prefix(DEVICE_ITEM_PREFIX) {
+                select_input_device(app, Some(device.to_string()));
+            } else if let Some(name) = id.strip_prefix(PRESET_ITEM_PREFIX) {
+                let preset = QualityPreset::NAMED
+                    .into_iter()
+                    .find(|p| p.display_name().eq_ignore_ascii_case(name));
+                let applied =
+                    preset.map(|p| crate::commands::settings::apply_quality_preset(app, p, false));
+                if let Some(Err(e)) = applied {
+                    tracing::warn!("Failed to set quality preset from tray: {}", e);
+                    // Downloading is confirmed in the settings window
+                    let _ = app
+                        .notification()
+                        .builder()
+                        .title("Quality Preset Not Changed")
+                        .body(format!("{}. Choose it in settings to download it.", e))
+                        .show();
+                }
+                // Restore the checked state
+                refresh_tray_menu(app);
+            } else if let Some(workspace) = id.strip_prefix(WORKSPACE_ITEM_PREFIX) {
+                if let Err(e) = crate::commands::workspace::activate_workspace(app, workspace) {
+                    tracing::warn!("Failed to switch workspace from tray: {}", e);
+                    // Restore the checked state
+                    refresh_tray_menu(app);
+                }
+            }
+        }
     }
 }
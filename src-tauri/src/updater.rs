@@ -4,6 +4,7 @@
 //! Supports variant-aware updates (CPU/Vulkan/CUDA).
 
 use crate::build_info::{BUILD_VARIANT, BUILD_VARIANT_DISPLAY};
+use schemars::JsonSchema;
 use tauri::{AppHandle, Emitter, Runtime};
 use tauri_plugin_updater::{Update, UpdaterExt};
 
@@ -82,7 +83,7 @@ pub async fn check_for_updates<R: Runtime>(app: AppHandle<R>) {
 }
 
 /// Update information sent to the frontend
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, serde::Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateInfo {
     pub current_version: String,
@@ -120,7 +121,8 @@ pub async fn install_update(app: AppHandle) -> Result<(), String> {
             move |chunk_length, content_length: Option<u64>| {
                 let progress = content_length
                     .map(|total| (chunk_length as f64 / total as f64 * 100.0) as u32);
-                let _ = app_clone.emit(
+                crate::utils::emit_throttled(
+                    &app_clone,
                     "update-download-progress",
                     DownloadProgress {
                         downloaded: chunk_length,
@@ -145,7 +147,7 @@ pub async fn install_update(app: AppHandle) -> Result<(), String> {
 }
 
 /// Download progress information
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, serde::Serialize, JsonSchema)]
 pub struct DownloadProgress {
     pub downloaded: usize,
     pub total: Option<u64>,
@@ -169,7 +171,7 @@ pub fn get_build_variant() -> BuildVariantInfo {
 }
 
 /// Build variant information
-#[derive(Clone, serde::Serialize)]
+#[derive(Clone, serde::Serialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BuildVariantInfo {
     pub variant: String,
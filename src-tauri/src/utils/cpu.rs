@@ -3,7 +3,7 @@
 //! Detect CPU capabilities for optimal whisper.cpp configuration.
 
 /// CPU information for optimization
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, schemars::JsonSchema)]
 pub struct CpuInfo {
     /// Number of physical CPU cores
     pub physical_cores: usize,
@@ -0,0 +1,276 @@
+//! Event Throttling
+//!
+//! High-frequency events (download progress, speech activity) are rate
+//! limited per event name. Updates arriving faster than the limit are
+//! coalesced: the latest payload replaces any pending one and is delivered
+//! once the interval has elapsed, so the frontend always ends up with the
+//! final value and producers never wait on the webview.
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Maximum rates (events per second) for throttled events
+const EVENT_RATES: &[(&str, u32)] = &[
+    ("model-download-progress", 10),
+    ("update-download-progress", 10),
+    ("audio:speech-active", 20),
+    ("audio:level", 20),
+];
+
+/// Delivery counters for one event
+#[derive(Debug, Clone, Default, Serialize, JsonSchema)]
+pub struct EventStats {
+    pub event: String,
+    /// Payloads delivered to the frontend
+    pub emitted: u64,
+    /// Payloads replaced by a newer one before delivery
+    pub coalesced: u64,
+}
+
+/// What to do with an offered payload
+#[derive(Debug, PartialEq)]
+pub enum Offer {
+    /// Deliver now
+    Emit(serde_json::Value),
+    /// Held back; schedule a flush after this delay if one isn't pending
+    Deferred(Option<Duration>),
+}
+
+/// Rate limit state of a single event
+#[derive(Debug)]
+pub struct EventChannel {
+    min_interval: Duration,
+    last_emit: Option<Instant>,
+    pending: Option<serde_json::Value>,
+    flush_scheduled: bool,
+    emitted: u64,
+    coalesced: u64,
+}
+
+impl EventChannel {
+    pub fn new(max_per_second: u32) -> Self {
+        Self {
+            min_interval: Duration::from_secs(1) / max_per_second.max(1),
+            last_emit: None,
+            pending: None,
+            flush_scheduled: false,
+            emitted: 0,
+            coalesced: 0,
+        }
+    }
+
+    /// Offer a payload at `now`
+    pub fn offer(&mut self, now: Instant, payload: serde_json::Value) -> Offer {
+        let wait = self
+            .last_emit
+            .map(|last| self.min_interval.saturating_sub(now.duration_since(last)))
+            .unwrap_or_default();
+
+        if wait.is_zero() && self.pending.is_none() {
+            self.last_emit = Some(now);
+            self.emitted += 1;
+            return Offer::Emit(payload);
+        }
+
+        if self.pending.replace(payload).is_some() {
+            self.coalesced += 1;
+        }
+        if self.flush_scheduled {
+            Offer::Deferred(None)
+        } else {
+            self.flush_scheduled = true;
+            Offer::Deferred(Some(wait))
+        }
+    }
+
+    /// Take the pending payload for delivery
+    pub fn take_pending(&mut self, now: Instant) -> Option<serde_json::Value> {
+        self.flush_scheduled = false;
+        let payload = self.pending.take()?;
+        self.last_emit = Some(now);
+        self.emitted += 1;
+        Some(payload)
+    }
+
+    fn stats(&self, event: &str) -> EventStats {
+        EventStats {
+            event: event.to_string(),
+            emitted: self.emitted,
+            coalesced: self.coalesced,
+        }
+    }
+}
+
+/// Central throttler for frontend events
+#[derive(Default)]
+pub struct EventThrottler {
+    channels: Mutex<HashMap<&'static str, EventChannel>>,
+}
+
+impl EventThrottler {
+    /// Emit an event, throttling it if it has a configured rate
+    pub fn emit<S: Serialize + Clone>(
+        &'static self,
+        app: &AppHandle,
+        event: &'static str,
+        payload: S,
+    ) {
+        let Some(rate) = rate_for(event) else {
+            let _ = app.emit(event, payload);
+            return;
+        };
+
+        let payload = match serde_json::to_value(payload) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Failed to serialize {} payload: {}", event, e);
+                return;
+            }
+        };
+
+        let offer = self
+            .channels
+            .lock()
+            .entry(event)
+            .or_insert_with(|| EventChannel::new(rate))
+            .offer(Instant::now(), payload);
+
+        match offer {
+            Offer::Emit(payload) => {
+                let _ = app.emit(event, payload);
+            }
+            Offer::Deferred(Some(delay)) => {
+                let app = app.clone();
+                let tasks = app.state::<crate::AppState>().tasks.clone();
+                tasks.spawn("event-flush", async move {
+                    tokio::time::sleep(delay).await;
+                    self.flush(&app, event);
+                });
+            }
+            Offer::Deferred(None) => {}
+        }
+    }
+
+    /// Deliver a pending payload immediately
+    ///
+    /// Call before a terminal event (e.g. "download complete") so a late
+    /// progress update can't arrive after it.
+    pub fn flush(&self, app: &AppHandle, event: &str) {
+        let payload = self
+            .channels
+            .lock()
+            .get_mut(event)
+            .and_then(|channel| channel.take_pending(Instant::now()));
+        if let Some(payload) = payload {
+            let _ = app.emit(event, payload);
+        }
+    }
+
+    /// Delivery counters of every throttled event seen so far
+    pub fn stats(&self) -> Vec<EventStats> {
+        let mut stats: Vec<EventStats> = self
+            .channels
+            .lock()
+            .iter()
+            .map(|(event, channel)| channel.stats(event))
+            .collect();
+        stats.sort_by(|a, b| a.event.cmp(&b.event));
+        stats
+    }
+}
+
+fn rate_for(event: &str) -> Option<u32> {
+    EVENT_RATES
+        .iter()
+        .find(|(name, _)| *name == event)
+        .map(|(_, rate)| *rate)
+}
+
+/// Global event throttler
+pub fn event_throttler() -> &'static EventThrottler {
+    static THROTTLER: OnceLock<EventThrottler> = OnceLock::new();
+    THROTTLER.get_or_init(EventThrottler::default)
+}
+
+/// Emit an event to a single window
+///
+/// Returns false when the window doesn't exist (yet); listeners in other
+/// windows never see the event.
+pub fn emit_to_window<S: Serialize + Clone>(
+    app: &AppHandle,
+    label: &str,
+    event: &str,
+    payload: S,
+) -> bool {
+    if app.get_webview_window(label).is_none() {
+        return false;
+    }
+    match app.emit_to(label, event, payload) {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("Failed to emit {} to {}: {}", event, label, e);
+            false
+        }
+    }
+}
+
+/// Emit an event through the global throttler
+pub fn emit_throttled<S: Serialize + Clone>(app: &AppHandle, event: &'static str, payload: S) {
+    event_throttler().emit(app, event, payload);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_first_offer_emits() {
+        let mut channel = EventChannel::new(10);
+        assert_eq!(channel.offer(Instant::now(), json!(1)), Offer::Emit(json!(1)));
+    }
+
+    #[test]
+    fn test_burst_is_coalesced() {
+        let mut channel = EventChannel::new(10);
+        let start = Instant::now();
+
+        channel.offer(start, json!(1));
+        let offer = channel.offer(start + Duration::from_millis(10), json!(2));
+        assert_eq!(offer, Offer::Deferred(Some(Duration::from_millis(90))));
+        // Only one flush is scheduled per interval
+        assert_eq!(
+            channel.offer(start + Duration::from_millis(20), json!(3)),
+            Offer::Deferred(None)
+        );
+
+        // Latest value wins
+        assert_eq!(channel.take_pending(start + Duration::from_millis(100)), Some(json!(3)));
+        let stats = channel.stats("test");
+        assert_eq!(stats.emitted, 2);
+        assert_eq!(stats.coalesced, 1);
+    }
+
+    #[test]
+    fn test_emits_again_after_interval() {
+        let mut channel = EventChannel::new(10);
+        let start = Instant::now();
+
+        channel.offer(start, json!(1));
+        assert_eq!(
+            channel.offer(start + Duration::from_millis(150), json!(2)),
+            Offer::Emit(json!(2))
+        );
+    }
+
+    #[test]
+    fn test_unthrottled_events() {
+        assert_eq!(rate_for("model-download-progress"), Some(10));
+        assert_eq!(rate_for("transcription:complete"), None);
+    }
+}
@@ -0,0 +1,307 @@
+//! Error Guidance
+//!
+//! Maps the errors users can fix themselves (microphone access, missing
+//! model, locked keyring, ...) to a short explanation and the settings page
+//! where the fix lives. Failed transcriptions are recognized from their
+//! error type and the provider that failed; other errors reach this module
+//! as strings and are recognized from their messages.
+
+use crate::transcription::{ProviderFailure, TranscriptionError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+/// Event sent with the guidance for an error shown in a notification
+pub const ERROR_GUIDANCE_EVENT: &str = "error:guidance";
+
+/// Errors with known remedies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ErrorCode {
+    /// The OS denied access to the microphone
+    MicPermission,
+    /// No input device is connected
+    NoMicrophone,
+    /// The configured Whisper model is not downloaded
+    ModelMissing,
+    /// The credential store is locked or unavailable
+    KeyringLocked,
+    /// The GPU backend failed to initialize
+    GpuInitFailed,
+    /// Groq rejected the API key (HTTP 401)
+    GroqUnauthorized,
+    /// Groq kept rate limiting after all retries (HTTP 429)
+    GroqRateLimited,
+    /// Deepgram rejected the API key
+    DeepgramUnauthorized,
+    /// Deepgram is rate limiting the API key
+    DeepgramRateLimited,
+    /// The custom server rejected the access token
+    EndpointUnauthorized,
+    /// The custom server kept rate limiting after all retries
+    EndpointRateLimited,
+}
+
+impl ErrorCode {
+    /// Recognize a failed transcription from its error and provider
+    pub fn for_transcription(failure: &ProviderFailure) -> Option<Self> {
+        match (&failure.error, failure.provider) {
+            (TranscriptionError::Unauthorized(_), "groq") => Some(Self::GroqUnauthorized),
+            (TranscriptionError::Unauthorized(_), "deepgram") => Some(Self::DeepgramUnauthorized),
+            (TranscriptionError::Unauthorized(_), "custom") => Some(Self::EndpointUnauthorized),
+            (TranscriptionError::RateLimited, "groq") => Some(Self::GroqRateLimited),
+            (TranscriptionError::RateLimited, "deepgram") => Some(Self::DeepgramRateLimited),
+            (TranscriptionError::RateLimited, "custom") => Some(Self::EndpointRateLimited),
+            (TranscriptionError::ModelNotFound(_), _) => Some(Self::ModelMissing),
+            // Credential store and GPU failures are only described in the message
+            (error, _) => Self::classify(&error.to_string()),
+        }
+    }
+
+    /// Recognize an error from its message
+    pub fn classify(error: &str) -> Option<Self> {
+        let error = error.to_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| error.contains(n));
+
+        if has(&["model not found"]) {
+            Some(Self::ModelMissing)
+        } else if has(&["gpu initialization failed"]) {
+            Some(Self::GpuInitFailed)
+        } else if has(&["credential store"]) {
+            Some(Self::KeyringLocked)
+        } else if has(&["no default input device", "device not found"]) {
+            Some(Self::NoMicrophone)
+        } else if has(&["audio", "stream"])
+            && has(&["access is denied", "0x80070005", "permission", "not authorized"])
+        {
+            Some(Self::MicPermission)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the guidance action leads
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "kebab-case")]
+pub enum GuidanceLink {
+    /// A tab of the settings page ("general", "transcription", "audio")
+    Settings { tab: &'static str },
+    /// A system settings page, opened with the shell
+    System { url: &'static str },
+}
+
+/// A button that takes the user to the fix
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct GuidanceAction {
+    pub label: &'static str,
+    pub link: GuidanceLink,
+}
+
+/// What went wrong and how to fix it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ErrorGuidance {
+    pub code: ErrorCode,
+    pub title: &'static str,
+    pub message: &'static str,
+    pub action: Option<GuidanceAction>,
+}
+
+/// Guidance for an error code
+pub fn error_guidance(code: ErrorCode) -> ErrorGuidance {
+    let settings = |label, tab| {
+        Some(GuidanceAction {
+            label,
+            link: GuidanceLink::Settings { tab },
+        })
+    };
+
+    let (title, message, action) = match code {
+        ErrorCode::MicPermission => (
+            "Microphone Access Denied",
+            "GigaWhisper is not allowed to use the microphone. Allow microphone access in the system privacy settings, then try again.",
+            microphone_privacy_url()
+                .map(|url| GuidanceAction {
+                    label: "Open privacy settings",
+                    link: GuidanceLink::System { url },
+                })
+                .or_else(|| settings("Choose a microphone", "audio")),
+        ),
+        ErrorCode::NoMicrophone => (
+            "No Microphone Found",
+            "Connect a microphone or pick another input device in the audio settings.",
+            settings("Open audio settings", "audio"),
+        ),
+        ErrorCode::ModelMissing => (
+            "Model Not Downloaded",
+            "The selected Whisper model is not on this computer. Download it or choose another model.",
+            settings("Manage models", "transcription"),
+        ),
+        ErrorCode::KeyringLocked => (
+            "Credential Store Unavailable",
+            "API keys could not be read from the system keyring. Unlock the keyring (log in again on Linux) and retry.",
+            settings("Open transcription settings", "transcription"),
+        ),
+        ErrorCode::GpuInitFailed => (
+            "GPU Acceleration Failed",
+            "The GPU could not be initialized. Update the graphics driver or turn off GPU acceleration.",
+            settings("Open transcription settings", "transcription"),
+        ),
+        ErrorCode::GroqUnauthorized => (
+            "Groq API Key Rejected",
+            "Groq did not accept the API key. Paste a valid key from console.groq.com.",
+            settings("Update API key", "transcription"),
+        ),
+        ErrorCode::GroqRateLimited => (
+            "Groq Rate Limit Reached",
+            "Groq is rate limiting this API key. Wait a minute, or add the local model as a fallback provider.",
+            settings("Configure fallback", "transcription"),
+        ),
+        ErrorCode::DeepgramUnauthorized => (
+            "Deepgram API Key Rejected",
+            "Deepgram did not accept the API key. Paste a valid key from console.deepgram.com.",
+            settings("Update API key", "transcription"),
+        ),
+        ErrorCode::DeepgramRateLimited => (
+            "Deepgram Rate Limit Reached",
+            "Deepgram is rate limiting this API key. Wait a minute, or add the local model as a fallback provider.",
+            settings("Configure fallback", "transcription"),
+        ),
+        ErrorCode::EndpointUnauthorized => (
+            "Server Rejected the Token",
+            "The transcription server did not accept the access token. Check the token in the transcription settings.",
+            settings("Update token", "transcription"),
+        ),
+        ErrorCode::EndpointRateLimited => (
+            "Server Rate Limit Reached",
+            "The transcription server is rate limiting requests. Wait a minute, or add the local model as a fallback provider.",
+            settings("Configure fallback", "transcription"),
+        ),
+    };
+
+    ErrorGuidance {
+        code,
+        title,
+        message,
+        action,
+    }
+}
+
+/// Privacy page of the system settings for microphone access
+fn microphone_privacy_url() -> Option<&'static str> {
+    if cfg!(target_os = "windows") {
+        Some("ms-settings:privacy-microphone")
+    } else if cfg!(target_os = "macos") {
+        Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone")
+    } else {
+        None
+    }
+}
+
+/// Guidance for an error message, when it is a known error
+pub fn guidance_for(error: &str) -> Option<ErrorGuidance> {
+    ErrorCode::classify(error).map(error_guidance)
+}
+
+/// Show the guidance in a notification and send `error:guidance` so the
+/// window can offer the fix
+pub fn notify_guidance(app: &AppHandle, guidance: &ErrorGuidance) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let _ = app.emit(ERROR_GUIDANCE_EVENT, guidance);
+    let _ = app
+        .notification()
+        .builder()
+        .title(guidance.title)
+        .body(guidance.message)
+        .show();
+}
+
+/// Notify the user of a failed transcription, with guidance when it is a
+/// known failure of the provider
+pub fn notify_transcription_error(app: &AppHandle, failure: &ProviderFailure) {
+    use tauri_plugin_notification::NotificationExt;
+
+    match ErrorCode::for_transcription(failure).map(error_guidance) {
+        Some(guidance) => notify_guidance(app, &guidance),
+        None => {
+            let _ = app
+                .notification()
+                .builder()
+                .title("Transcription Failed")
+                .body(failure.to_string())
+                .show();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let classify = ErrorCode::classify;
+        assert_eq!(
+            classify("Model not found: C:\\models\\ggml-base.bin"),
+            Some(ErrorCode::ModelMissing)
+        );
+        assert_eq!(
+            classify("Failed to start audio capture: Stream error: Access is denied. (0x80070005)"),
+            Some(ErrorCode::MicPermission)
+        );
+        assert_eq!(
+            classify("Failed to initialize audio: No default input device"),
+            Some(ErrorCode::NoMicrophone)
+        );
+        assert_eq!(classify("Transcription failed: out of memory"), None);
+        // Provider errors are only recognized from their type
+        assert_eq!(classify("Rate limited"), None);
+    }
+
+    #[test]
+    fn test_for_transcription() {
+        use TranscriptionError::{ApiError, ModelNotFound, RateLimited, Unauthorized};
+
+        let code =
+            |provider, error| ErrorCode::for_transcription(&ProviderFailure { provider, error });
+        let rejected = || Unauthorized("Invalid API key".to_string());
+
+        assert_eq!(code("groq", rejected()), Some(ErrorCode::GroqUnauthorized));
+        assert_eq!(
+            code("deepgram", rejected()),
+            Some(ErrorCode::DeepgramUnauthorized)
+        );
+        assert_eq!(
+            code("custom", rejected()),
+            Some(ErrorCode::EndpointUnauthorized)
+        );
+        assert_eq!(
+            code("deepgram", RateLimited),
+            Some(ErrorCode::DeepgramRateLimited)
+        );
+        assert_eq!(
+            code("custom", RateLimited),
+            Some(ErrorCode::EndpointRateLimited)
+        );
+        let missing = ModelNotFound("ggml-base.bin".to_string());
+        assert_eq!(code("whisper.cpp", missing), Some(ErrorCode::ModelMissing));
+        // A message mentioning an API key is not a rejected key
+        let message = ApiError("Invalid API key format".to_string());
+        assert_eq!(code("groq", message), None);
+    }
+
+    #[test]
+    fn test_error_guidance() {
+        let code: ErrorCode = serde_json::from_str("\"keyring-locked\"").unwrap();
+        let guidance = error_guidance(code);
+        assert_eq!(guidance.code, ErrorCode::KeyringLocked);
+        assert_eq!(
+            guidance.action.unwrap().link,
+            GuidanceLink::Settings {
+                tab: "transcription"
+            }
+        );
+        assert!(error_guidance(ErrorCode::MicPermission).action.is_some());
+    }
+}
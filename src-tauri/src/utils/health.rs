@@ -0,0 +1,49 @@
+//! Health Report
+//!
+//! Snapshot of environment checks shown on the diagnostics screen.
+
+use super::{event_throttler, platform_capabilities, EventStats, PlatformCapabilities};
+use crate::audio::{last_capture_mode, CaptureModeReport};
+use crate::config::{storage_report, storage_warnings, StorageReport};
+use schemars::JsonSchema;
+use serde::Serialize;
+
+/// Environment health summary
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct HealthReport {
+    pub version: &'static str,
+    pub build_variant: &'static str,
+    pub platform: PlatformCapabilities,
+    /// Throttled event delivery counters
+    pub events: Vec<EventStats>,
+    /// Buffer mode of the last microphone stream (None before the first recording)
+    pub capture: Option<CaptureModeReport>,
+    /// Where settings, history and models are stored
+    pub storage: StorageReport,
+    /// Problems the user can act on
+    pub warnings: Vec<String>,
+}
+
+/// Collect the current health report
+pub fn health_report() -> HealthReport {
+    let platform = platform_capabilities().clone();
+    let mut warnings = platform.guidance.clone();
+    let capture = last_capture_mode();
+    if let Some(reason) = capture.as_ref().and_then(|c| c.fallback_reason.as_ref()) {
+        warnings.push(format!(
+            "The microphone refused low-latency capture and runs in shared mode: {}",
+            reason
+        ));
+    }
+    warnings.extend(storage_warnings());
+
+    HealthReport {
+        version: env!("CARGO_PKG_VERSION"),
+        build_variant: crate::build_info::BUILD_VARIANT,
+        platform,
+        events: event_throttler().stats(),
+        capture,
+        storage: storage_report(),
+        warnings,
+    }
+}
@@ -0,0 +1,189 @@
+//! Automation Hooks
+//!
+//! Runs user-configured executables at lifecycle points. A hook receives
+//! the event name and details in `GIGAWHISPER_*` environment variables and
+//! the transcription (if any) on stdin. Hooks run in the background and are
+//! killed when they exceed the configured timeout; their failures are only
+//! logged.
+
+use super::TaskRegistry;
+use crate::config::{Hook, HookEvent, HookSettings};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Longest text passed in `GIGAWHISPER_TEXT` (stdin always gets all of it)
+pub const MAX_ENV_TEXT_CHARS: usize = 8000;
+
+/// Details passed to hooks
+#[derive(Debug, Clone, Default)]
+pub struct HookPayload {
+    pub text: Option<String>,
+    pub profile: Option<String>,
+    /// Whether every output sink succeeded (`output-injected` only)
+    pub success: Option<bool>,
+}
+
+impl HookPayload {
+    /// Environment variables describing the event
+    pub fn env(&self, event: HookEvent) -> Vec<(&'static str, String)> {
+        let mut env = vec![("GIGAWHISPER_EVENT", event.name().to_string())];
+        if let Some(text) = &self.text {
+            env.push(("GIGAWHISPER_TEXT", text.chars().take(MAX_ENV_TEXT_CHARS).collect()));
+        }
+        if let Some(profile) = &self.profile {
+            env.push(("GIGAWHISPER_PROFILE", profile.clone()));
+        }
+        if let Some(success) = self.success {
+            env.push(("GIGAWHISPER_SUCCESS", success.to_string()));
+        }
+        env
+    }
+}
+
+/// Hook errors
+#[derive(Debug, thiserror::Error)]
+pub enum HookError {
+    #[error("Failed to run hook: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Hook timed out after {0}s")]
+    Timeout(u64),
+
+    #[error("Hook exited with {0}")]
+    Failed(std::process::ExitStatus),
+}
+
+/// Run one hook and wait for it, killing it after `timeout`
+pub async fn run_hook(
+    hook: &Hook,
+    event: HookEvent,
+    payload: &HookPayload,
+    timeout: Duration,
+) -> Result<(), HookError> {
+    let mut child = tokio::process::Command::new(hook.command.trim())
+        .args(&hook.args)
+        .envs(payload.env(event))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    // A hook that ignores stdin may close it early; that is not an error.
+    // The write counts against the timeout too: a hook that never reads a
+    // long text would block it.
+    let stdin = child.stdin.take();
+    let text = payload.text.clone().unwrap_or_default();
+    let write = async move {
+        if let Some(mut stdin) = stdin {
+            let _ = stdin.write_all(text.as_bytes()).await;
+        }
+    };
+    let run = async { tokio::join!(write, child.wait_with_output()).1 };
+
+    let output = tokio::time::timeout(timeout, run)
+        .await
+        .map_err(|_| HookError::Timeout(timeout.as_secs()))??;
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.trim().is_empty() {
+        tracing::debug!("Hook {} output: {}", hook.command, stdout.trim());
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            tracing::debug!("Hook {} error output: {}", hook.command, stderr.trim());
+        }
+        return Err(HookError::Failed(output.status));
+    }
+    Ok(())
+}
+
+/// Start the hooks configured for `event` in the background
+pub fn fire_hooks(
+    tasks: &TaskRegistry,
+    settings: &HookSettings,
+    event: HookEvent,
+    payload: HookPayload,
+) {
+    let hooks: Vec<Hook> = settings.for_event(event).cloned().collect();
+    if hooks.is_empty() {
+        return;
+    }
+    let timeout = Duration::from_secs(settings.timeout_secs as u64);
+
+    tasks.spawn("hooks", async move {
+        for hook in &hooks {
+            match run_hook(hook, event, &payload, timeout).await {
+                Ok(()) => tracing::debug!("Hook {} ran for {}", hook.command, event.name()),
+                Err(e) => tracing::warn!("Hook {} for {}: {}", hook.command, event.name(), e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_env() {
+        let payload = HookPayload {
+            text: Some("x".repeat(MAX_ENV_TEXT_CHARS + 10)),
+            profile: Some("email".to_string()),
+            success: Some(true),
+        };
+        let env = payload.env(HookEvent::OutputInjected);
+        assert_eq!(env[0], ("GIGAWHISPER_EVENT", "output-injected".to_string()));
+        assert_eq!(env[1].1.len(), MAX_ENV_TEXT_CHARS);
+        assert_eq!(env[2], ("GIGAWHISPER_PROFILE", "email".to_string()));
+        assert_eq!(env[3], ("GIGAWHISPER_SUCCESS", "true".to_string()));
+
+        assert_eq!(HookPayload::default().env(HookEvent::RecordingStarted).len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_run_hook_passes_text_and_times_out() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let out = dir.path().join("out.txt");
+        let hook = Hook {
+            event: HookEvent::TranscriptionCompleted,
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                format!("cat > '{}'; test \"$GIGAWHISPER_TEXT\" = hello", out.display()),
+            ],
+        };
+        let payload = HookPayload {
+            text: Some("hello".to_string()),
+            ..Default::default()
+        };
+        run_hook(&hook, hook.event, &payload, Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(&out).unwrap(), "hello");
+
+        let slow = Hook {
+            args: vec!["-c".to_string(), "sleep 5".to_string()],
+            ..hook
+        };
+        let result = run_hook(&slow, slow.event, &payload, Duration::from_millis(200)).await;
+        assert!(matches!(result, Err(HookError::Timeout(_))));
+
+        // More text than the pipe holds, to a hook that never reads it
+        let long = HookPayload {
+            text: Some("x".repeat(1 << 20)),
+            ..Default::default()
+        };
+        let slow = Hook {
+            args: vec!["-c".to_string(), "sleep 30".to_string()],
+            ..slow
+        };
+        let started = std::time::Instant::now();
+        let result = run_hook(&slow, slow.event, &long, Duration::from_millis(200)).await;
+        assert!(matches!(result, Err(HookError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(10));
+    }
+}
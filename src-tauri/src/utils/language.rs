@@ -0,0 +1,22 @@
+//! Language Codes
+//!
+//! Helpers for the language codes found in settings and history entries,
+//! which may carry a region subtag ("pt-BR", "en_US").
+
+/// Language without its region subtag ("pt-BR" -> "pt")
+pub fn primary_language(language: &str) -> &str {
+    language.split(['-', '_']).next().unwrap_or(language)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_primary_language() {
+        assert_eq!(primary_language("pt-BR"), "pt");
+        assert_eq!(primary_language("en_US"), "en");
+        assert_eq!(primary_language("fr"), "fr");
+        assert_eq!(primary_language(""), "");
+    }
+}
@@ -0,0 +1,324 @@
+//! Latency Objective
+//!
+//! Tracks the stop-to-paste latency of dictations against the configured
+//! target. When several dictations in a row miss it, a `latency:slo-violated`
+//! event reports the percentiles along with remediations picked from where
+//! the time went.
+
+use super::StageTimings;
+use crate::config::{Settings, TranscriptionProvider};
+use crate::transcription::WhisperProvider;
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Event sent when the latency target is repeatedly missed
+pub const SLO_VIOLATED_EVENT: &str = "latency:slo-violated";
+
+/// Dictations kept for percentiles
+const MAX_SAMPLES: usize = 100;
+
+/// Share of the latency a step must take before a remediation targets it
+const DOMINANT_SHARE: f64 = 0.5;
+
+/// Global latency tracker
+static LATENCY: Mutex<LatencyTracker> = Mutex::new(LatencyTracker::new());
+
+/// What to change to bring latency down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum RemediationKind {
+    SmallerModel,
+    EnableGpu,
+    EnableVad,
+    ReducePasteDelay,
+}
+
+/// A suggested settings change
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct Remediation {
+    pub kind: RemediationKind,
+    pub message: String,
+}
+
+/// Latency of recent dictations against the target
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct LatencyReport {
+    /// Target stop-to-paste latency (ms, 0 = no target)
+    pub target_ms: u32,
+    /// Dictations measured
+    pub samples: usize,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    /// Share of dictations within the target (0.0-1.0)
+    pub within_target: f64,
+    /// Dictations in a row that missed the target
+    pub consecutive_misses: u32,
+    /// Average time per step of the dictations that missed the target
+    pub miss_timings: StageTimings,
+    pub remediations: Vec<Remediation>,
+}
+
+/// Stop-to-paste latency of one dictation and where the time went
+#[derive(Debug, Clone, Copy)]
+struct LatencySample {
+    total_ms: u64,
+    timings: StageTimings,
+}
+
+/// Recent dictation timings and the current run of misses
+pub struct LatencyTracker {
+    samples: VecDeque<LatencySample>,
+    misses: u32,
+    /// Whether the current run of misses was already reported
+    alerted: bool,
+}
+
+impl LatencyTracker {
+    pub const fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            misses: 0,
+            alerted: false,
+        }
+    }
+
+    /// Record a dictation that took `total_ms` from stop to paste
+    ///
+    /// Returns true once per run of misses, when it reaches the alert threshold.
+    pub fn observe(&mut self, total_ms: u64, timings: StageTimings, settings: &Settings) -> bool {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(LatencySample { total_ms, timings });
+
+        let target = settings.latency.target_ms as u64;
+        if target == 0 || total_ms <= target {
+            self.misses = 0;
+            self.alerted = false;
+            return false;
+        }
+        self.misses += 1;
+        if self.misses >= settings.latency.alert_after && !self.alerted {
+            self.alerted = true;
+            return true;
+        }
+        false
+    }
+
+    /// Percentiles and remediations for the recorded dictations
+    pub fn report(&self, settings: &Settings) -> LatencyReport {
+        let target = settings.latency.target_ms as u64;
+        let mut totals: Vec<u64> = self.samples.iter().map(|s| s.total_ms).collect();
+        totals.sort_unstable();
+
+        let within = totals.iter().filter(|&&t| target == 0 || t <= target).count();
+        let misses: Vec<&StageTimings> = self
+            .samples
+            .iter()
+            .filter(|s| target > 0 && s.total_ms > target)
+            .map(|s| &s.timings)
+            .collect();
+        let miss_timings = StageTimings::average(misses.into_iter());
+
+        LatencyReport {
+            target_ms: settings.latency.target_ms,
+            samples: totals.len(),
+            p50_ms: percentile(&totals, 0.5),
+            p95_ms: percentile(&totals, 0.95),
+            within_target: match totals.len() {
+                0 => 1.0,
+                n => within as f64 / n as f64,
+            },
+            consecutive_misses: self.misses,
+            remediations: suggest_remediations(&miss_timings, settings),
+            miss_timings,
+        }
+    }
+
+    /// Forget recorded dictations
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the global latency tracker
+pub fn latency_tracker() -> &'static Mutex<LatencyTracker> {
+    &LATENCY
+}
+
+/// Record a dictation's stop-to-paste latency and step timings, returning a
+/// report when an alert is due
+pub fn observe_latency(
+    total_ms: u64,
+    timings: StageTimings,
+    settings: &Settings,
+) -> Option<LatencyReport> {
+    let mut tracker = latency_tracker().lock();
+    tracker
+        .observe(total_ms, timings, settings)
+        .then(|| tracker.report(settings))
+}
+
+/// Value at `p` (0.0-1.0) of sorted values
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let index = (sorted.len() as f64 * p) as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+/// Settings changes that address the steps taking most of the time
+pub fn suggest_remediations(timings: &StageTimings, settings: &Settings) -> Vec<Remediation> {
+    let total = timings.total_ms();
+    if total == 0 {
+        return Vec::new();
+    }
+    let share = |ms: u64| ms as f64 / total as f64;
+    let mut remediations = Vec::new();
+
+    if share(timings.inference_ms) >= DOMINANT_SHARE {
+        let local = &settings.transcription.local;
+        if settings.transcription.provider == TranscriptionProvider::Local {
+            if !local.gpu_enabled && WhisperProvider::is_gpu_available() {
+                remediations.push(Remediation {
+                    kind: RemediationKind::EnableGpu,
+                    message: "Enable GPU acceleration".to_string(),
+                });
+            }
+            // Imported models have no smaller variant
+            if let (None, Some(smaller)) = (&local.custom_model, local.model.smaller()) {
+                remediations.push(Remediation {
+                    kind: RemediationKind::SmallerModel,
+                    message: format!("Switch to a smaller model: {}", smaller.display_name()),
+                });
+            }
+        }
+        if !settings.audio.vad.enabled {
+            remediations.push(Remediation {
+                kind: RemediationKind::EnableVad,
+                message: "Enable voice activity detection so silence isn't transcribed"
+                    .to_string(),
+            });
+        }
+    }
+
+    if share(timings.injection_ms) >= DOMINANT_SHARE / 2.0 && settings.output.paste_delay > 0 {
+        remediations.push(Remediation {
+            kind: RemediationKind::ReducePasteDelay,
+            message: format!(
+                "Lower the paste delay (currently {} ms)",
+                settings.output.paste_delay
+            ),
+        });
+    }
+    remediations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::WhisperModel;
+
+    fn timings(inference_ms: u64, injection_ms: u64) -> StageTimings {
+        StageTimings {
+            capture_drain_ms: 20,
+            inference_ms,
+            injection_ms,
+            ..StageTimings::default()
+        }
+    }
+
+    /// Observe a dictation whose latency is the sum of its steps
+    fn observe(tracker: &mut LatencyTracker, t: StageTimings, settings: &Settings) -> bool {
+        tracker.observe(t.total_ms(), t, settings)
+    }
+
+    #[test]
+    fn test_alert_once_per_run_of_misses() {
+        let mut settings = Settings::default();
+        settings.latency.alert_after = 2;
+        let mut tracker = LatencyTracker::new();
+
+        assert!(!observe(&mut tracker, timings(3000, 100), &settings));
+        assert!(observe(&mut tracker, timings(3000, 100), &settings));
+        assert!(!observe(&mut tracker, timings(3000, 100), &settings));
+        assert_eq!(tracker.report(&settings).consecutive_misses, 3);
+
+        // Meeting the target ends the run
+        assert!(!observe(&mut tracker, timings(500, 100), &settings));
+        assert!(!observe(&mut tracker, timings(3000, 100), &settings));
+        assert!(observe(&mut tracker, timings(3000, 100), &settings));
+
+        settings.latency.target_ms = 0;
+        assert!(!observe(&mut tracker, timings(9000, 100), &settings));
+    }
+
+    #[test]
+    fn test_report_percentiles() {
+        let settings = Settings::default();
+        let mut tracker = LatencyTracker::new();
+        for inference_ms in [400, 600, 800, 1000, 5000] {
+            observe(&mut tracker, timings(inference_ms, 80), &settings);
+        }
+
+        let report = tracker.report(&settings);
+        assert_eq!(report.samples, 5);
+        assert_eq!(report.p50_ms, 900);
+        assert_eq!(report.p95_ms, 5100);
+        assert!((report.within_target - 0.8).abs() < 1e-9);
+        assert_eq!(report.miss_timings.inference_ms, 5000);
+
+        tracker.reset();
+        assert_eq!(tracker.report(&settings).samples, 0);
+    }
+
+    #[test]
+    fn test_latency_includes_time_between_steps() {
+        let mut settings = Settings::default();
+        settings.latency.alert_after = 1;
+        let mut tracker = LatencyTracker::new();
+
+        // Fast steps, but the paste landed long after the stop
+        assert!(tracker.observe(9000, timings(300, 50), &settings));
+        let report = tracker.report(&settings);
+        assert_eq!(report.p50_ms, 9000);
+        assert_eq!(report.miss_timings.inference_ms, 300);
+    }
+
+    #[test]
+    fn test_remediations_follow_slow_step() {
+        let mut settings = Settings::default();
+        settings.transcription.local.model = WhisperModel::Medium;
+        settings.audio.vad.enabled = false;
+
+        let kinds = |t: StageTimings, s: &Settings| {
+            suggest_remediations(&t, s)
+                .into_iter()
+                .map(|r| r.kind)
+                .collect::<Vec<_>>()
+        };
+        let slow_inference = kinds(timings(3000, 100), &settings);
+        assert!(slow_inference.contains(&RemediationKind::SmallerModel));
+        assert!(slow_inference.contains(&RemediationKind::EnableVad));
+        assert!(!slow_inference.contains(&RemediationKind::ReducePasteDelay));
+
+        settings.output.paste_delay = 500;
+        assert_eq!(
+            kinds(timings(200, 900), &settings),
+            vec![RemediationKind::ReducePasteDelay]
+        );
+
+        settings.transcription.local.model = WhisperModel::Tiny;
+        settings.audio.vad.enabled = true;
+        assert!(!kinds(timings(3000, 0), &settings).contains(&RemediationKind::SmallerModel));
+    }
+}
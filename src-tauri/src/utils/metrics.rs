@@ -4,6 +4,7 @@
 //! These metrics help users understand and optimize their configuration.
 
 use parking_lot::RwLock;
+use schemars::JsonSchema;
 use serde::Serialize;
 use std::collections::VecDeque;
 use std::time::{Duration, Instant};
@@ -34,6 +35,8 @@ pub struct PerformanceMetrics {
     model_load_time_ms: Option<u64>,
     /// Current model memory usage estimate (bytes)
     estimated_model_memory: Option<u64>,
+    /// Chunking of the latest live partials
+    chunking: Option<ChunkingStats>,
 }
 
 impl PerformanceMetrics {
@@ -46,6 +49,7 @@ impl PerformanceMetrics {
             total_processing_ms: 0,
             model_load_time_ms: None,
             estimated_model_memory: None,
+            chunking: None,
         }
     }
 
@@ -66,12 +70,20 @@ impl PerformanceMetrics {
         self.estimated_model_memory = Some(estimated_memory);
     }
 
+    /// Record how live partials are currently chunked
+    pub fn record_chunking(&mut self, stats: ChunkingStats) {
+        self.chunking = Some(stats);
+    }
+
     /// Get summary statistics
     pub fn get_summary(&self) -> MetricsSummary {
         let count = self.transcriptions.len();
 
         if count == 0 {
-            return MetricsSummary::default();
+            return MetricsSummary {
+                chunking: self.chunking,
+                ..MetricsSummary::default()
+            };
         }
 
         let total_processing: u64 = self.transcriptions.iter().map(|r| r.processing_time_ms).sum();
@@ -122,6 +134,9 @@ impl PerformanceMetrics {
             model_load_time_ms: self.model_load_time_ms,
             estimated_model_memory_bytes: self.estimated_model_memory,
             vad_savings_ms,
+            avg_timings: StageTimings::average(self.transcriptions.iter().map(|r| &r.timings)),
+            chunking: self.chunking,
+            prefetch: PrefetchStats::from_records(&self.transcriptions),
         }
     }
 
@@ -135,12 +150,24 @@ impl PerformanceMetrics {
             .collect()
     }
 
+    /// Number of transcription records kept
+    pub fn record_count(&self) -> usize {
+        self.transcriptions.len()
+    }
+
+    /// Drop all but the `keep` most recent records (session totals are kept)
+    pub fn trim(&mut self, keep: usize) {
+        let excess = self.transcriptions.len().saturating_sub(keep);
+        self.transcriptions.drain(..excess);
+    }
+
     /// Reset metrics
     pub fn reset(&mut self) {
         self.transcriptions.clear();
         self.session_start = Instant::now();
         self.total_audio_ms = 0;
         self.total_processing_ms = 0;
+        self.chunking = None;
         // Keep model load time as it's still valid
     }
 }
@@ -152,7 +179,7 @@ impl Default for PerformanceMetrics {
 }
 
 /// Record of a single transcription operation
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
 pub struct TranscriptionRecord {
     /// Timestamp when transcription started (unix ms)
     pub timestamp_ms: u64,
@@ -176,6 +203,70 @@ pub struct TranscriptionRecord {
     pub vad_filtered_ms: Option<u64>,
     /// Number of characters in result
     pub result_chars: usize,
+    /// Where the time went
+    pub timings: StageTimings,
+    /// Whether a warm-up ran ahead of it
+    pub prefetched: bool,
+}
+
+/// Time spent in each step of one transcription (ms)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct StageTimings {
+    /// Stopping the capture stream and collecting its samples
+    pub capture_drain_ms: u64,
+    pub resample_ms: u64,
+    /// Gain and noise suppression
+    pub filters_ms: u64,
+    /// Noise gate and speech filtering
+    pub vad_ms: u64,
+    /// Speech to text
+    pub inference_ms: u64,
+    /// Vocabulary rules and learned capitalization
+    pub post_process_ms: u64,
+    /// Delivering the text to the output sinks
+    pub injection_ms: u64,
+    /// Resampling time hidden by classifying VAD frames concurrently (not
+    /// part of the total)
+    pub overlap_saved_ms: u64,
+}
+
+impl StageTimings {
+    /// Sum of all steps
+    pub fn total_ms(&self) -> u64 {
+        self.capture_drain_ms
+            + self.resample_ms
+            + self.filters_ms
+            + self.vad_ms
+            + self.inference_ms
+            + self.post_process_ms
+            + self.injection_ms
+    }
+
+    /// Per-step average over `records`
+    pub fn average<'a>(records: impl ExactSizeIterator<Item = &'a StageTimings>) -> Self {
+        let count = records.len().max(1) as u64;
+        let mut sum = Self::default();
+        for t in records {
+            sum.capture_drain_ms += t.capture_drain_ms;
+            sum.resample_ms += t.resample_ms;
+            sum.filters_ms += t.filters_ms;
+            sum.vad_ms += t.vad_ms;
+            sum.inference_ms += t.inference_ms;
+            sum.post_process_ms += t.post_process_ms;
+            sum.injection_ms += t.injection_ms;
+            sum.overlap_saved_ms += t.overlap_saved_ms;
+        }
+        Self {
+            capture_drain_ms: sum.capture_drain_ms / count,
+            resample_ms: sum.resample_ms / count,
+            filters_ms: sum.filters_ms / count,
+            vad_ms: sum.vad_ms / count,
+            inference_ms: sum.inference_ms / count,
+            post_process_ms: sum.post_process_ms / count,
+            injection_ms: sum.injection_ms / count,
+            overlap_saved_ms: sum.overlap_saved_ms / count,
+        }
+    }
 }
 
 impl TranscriptionRecord {
@@ -208,6 +299,8 @@ impl TranscriptionRecordBuilder {
                 vad_enabled: false,
                 vad_filtered_ms: None,
                 result_chars: 0,
+                timings: StageTimings::default(),
+                prefetched: false,
             },
         }
     }
@@ -262,13 +355,41 @@ impl TranscriptionRecordBuilder {
         self
     }
 
+    pub fn timings(mut self, timings: StageTimings) -> Self {
+        self.record.timings = timings;
+        self
+    }
+
+    pub fn prefetched(mut self, prefetched: bool) -> Self {
+        self.record.prefetched = prefetched;
+        self
+    }
+
     pub fn build(self) -> TranscriptionRecord {
         self.record
     }
 }
+/// How live partial transcriptions are chunked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, JsonSchema)]
+pub struct ChunkingStats {
+    /// New audio between partial transcriptions now (ms)
+    pub interval_ms: u32,
+    /// Whether the interval follows the partials' real-time factor
+    pub adaptive: bool,
+    /// Processing time of the last partial over the new audio it covered
+    pub last_real_time_factor: f64,
+    /// Moving average of that factor
+    pub avg_real_time_factor: f64,
+    /// Partials transcribed in the recording
+    pub partials: u32,
+    /// Times the interval grew because partials fell behind
+    pub increases: u32,
+    /// Times the interval shrank because partials had headroom
+    pub decreases: u32,
+}
 
 /// Summary of performance metrics
-#[derive(Debug, Clone, Serialize, Default)]
+#[derive(Debug, Clone, Serialize, Default, JsonSchema)]
 pub struct MetricsSummary {
     /// Number of transcriptions recorded
     pub transcription_count: usize,
@@ -296,6 +417,51 @@ pub struct MetricsSummary {
     pub estimated_model_memory_bytes: Option<u64>,
     /// Total audio saved by VAD filtering (ms)
     pub vad_savings_ms: u64,
+    /// Average time spent in each step
+    pub avg_timings: StageTimings,
+    /// Chunking of the latest live partials, if any ran
+    pub chunking: Option<ChunkingStats>,
+    /// Effect of warm-ups, once one ran
+    pub prefetch: Option<PrefetchStats>,
+}
+
+/// Inference time of transcriptions with and without a warm-up ahead
+#[derive(Debug, Clone, Default, PartialEq, Serialize, JsonSchema)]
+pub struct PrefetchStats {
+    /// Provider compared, the one of the latest transcription
+    pub provider: String,
+    pub warm_count: usize,
+    pub cold_count: usize,
+    /// Average inference time after a warm-up (ms)
+    pub avg_warm_inference_ms: u64,
+    /// Average inference time without one (ms)
+    pub avg_cold_inference_ms: u64,
+}
+
+impl PrefetchStats {
+    /// Compare the records of the latest provider; None before any warm-up
+    fn from_records(records: &VecDeque<TranscriptionRecord>) -> Option<Self> {
+        let provider = &records.back()?.provider;
+        let (warm, cold): (Vec<_>, Vec<_>) = records
+            .iter()
+            .filter(|r| &r.provider == provider)
+            .partition(|r| r.prefetched);
+        if warm.is_empty() {
+            return None;
+        }
+
+        let average = |records: &[&TranscriptionRecord]| {
+            let total: u64 = records.iter().map(|r| r.timings.inference_ms).sum();
+            total / records.len().max(1) as u64
+        };
+        Some(Self {
+            provider: provider.clone(),
+            warm_count: warm.len(),
+            cold_count: cold.len(),
+            avg_warm_inference_ms: average(&warm),
+            avg_cold_inference_ms: average(&cold),
+        })
+    }
 }
 
 impl MetricsSummary {
@@ -349,6 +515,65 @@ mod tests {
         assert!((summary.avg_real_time_factor - 0.2).abs() < 0.01);
     }
 
+    #[test]
+    fn test_average_timings() {
+        let mut metrics = PerformanceMetrics::new();
+        for inference_ms in [1000, 2000] {
+            let timings = StageTimings {
+                capture_drain_ms: 20,
+                inference_ms,
+                injection_ms: 150,
+                ..StageTimings::default()
+            };
+            metrics.record_transcription(TranscriptionRecord::builder().timings(timings).build());
+        }
+
+        let avg = metrics.get_summary().avg_timings;
+        assert_eq!(avg.inference_ms, 1500);
+        assert_eq!(avg.capture_drain_ms, 20);
+        assert_eq!(avg.total_ms(), 1670);
+    }
+
+    #[test]
+    fn test_prefetch_stats() {
+        let mut metrics = PerformanceMetrics::new();
+        let record = |provider: &str, inference_ms, prefetched| {
+            TranscriptionRecord::builder()
+                .provider(provider)
+                .timings(StageTimings {
+                    inference_ms,
+                    ..StageTimings::default()
+                })
+                .prefetched(prefetched)
+                .build()
+        };
+        metrics.record_transcription(record("groq", 900, false));
+        assert_eq!(metrics.get_summary().prefetch, None);
+
+        metrics.record_transcription(record("local", 3000, false));
+        metrics.record_transcription(record("groq", 1100, false));
+        metrics.record_transcription(record("groq", 600, true));
+
+        let stats = metrics.get_summary().prefetch.unwrap();
+        assert_eq!(stats.provider, "groq");
+        assert_eq!((stats.warm_count, stats.cold_count), (1, 2));
+        assert_eq!(stats.avg_warm_inference_ms, 600);
+        assert_eq!(stats.avg_cold_inference_ms, 1000);
+    }
+
+    #[test]
+    fn test_trim_keeps_recent_records() {
+        let mut metrics = PerformanceMetrics::new();
+        for ms in [100, 200, 300] {
+            metrics.record_transcription(TranscriptionRecord::builder().audio_duration_ms(ms).build());
+        }
+
+        metrics.trim(1);
+        assert_eq!(metrics.record_count(), 1);
+        assert_eq!(metrics.get_recent(5)[0].audio_duration_ms, 300);
+        assert_eq!(metrics.total_audio_ms, 600);
+    }
+
     #[test]
     fn test_rtf_calculation() {
         let record = TranscriptionRecord::builder()
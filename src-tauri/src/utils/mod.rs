@@ -3,7 +3,25 @@
 //! System detection and helper functions.
 
 mod cpu;
+mod events;
+mod guidance;
+mod health;
+mod hooks;
+mod language;
+mod latency;
 mod metrics;
+mod platform;
+mod resources;
+mod tasks;
 
 pub use cpu::*;
+pub use events::*;
+pub use guidance::*;
+pub use health::*;
+pub use hooks::*;
+pub use language::*;
+pub use latency::*;
 pub use metrics::*;
+pub use platform::*;
+pub use resources::*;
+pub use tasks::*;
@@ -0,0 +1,308 @@
+//! Platform Capabilities
+//!
+//! Detect the desktop session (Windows, macOS, X11, Wayland) and which
+//! clipboard and key injection backends are usable in it. Wayland does not
+//! let applications read focus or synthesize input, so paste goes through
+//! external tools (wl-clipboard, wtype, ydotool) when they are installed.
+
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// Desktop session type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayServer {
+    Windows,
+    MacOs,
+    X11,
+    Wayland,
+    Unknown,
+}
+
+impl DisplayServer {
+    /// Detect the current session
+    pub fn detect() -> Self {
+        if cfg!(windows) {
+            Self::Windows
+        } else if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else {
+            Self::from_env(|key| std::env::var(key).ok())
+        }
+    }
+
+    /// Detect a Unix session from its environment variables
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let is_set = |key: &str| var(key).is_some_and(|v| !v.is_empty());
+        let session_type = var("XDG_SESSION_TYPE").unwrap_or_default().to_lowercase();
+
+        if is_set("WAYLAND_DISPLAY") || session_type == "wayland" {
+            Self::Wayland
+        } else if is_set("DISPLAY") || session_type == "x11" {
+            Self::X11
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// How the clipboard is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ClipboardBackend {
+    /// In-process clipboard access (Win32, AppKit, X11)
+    Native,
+    /// `wl-copy` / `wl-paste` from wl-clipboard
+    WlClipboard,
+}
+
+/// How the paste keystroke is synthesized
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PasteBackend {
+    /// Win32 SendInput
+    SendInput,
+    /// `wtype` (wlroots-based compositors)
+    Wtype,
+    /// `ydotool` (any compositor, needs the ydotoold daemon)
+    Ydotool,
+    /// `xdotool` (X11)
+    Xdotool,
+}
+
+impl PasteBackend {
+    /// External program used by this backend, if any
+    pub fn program(self) -> Option<&'static str> {
+        match self {
+            Self::SendInput => None,
+            Self::Wtype => Some("wtype"),
+            Self::Ydotool => Some("ydotool"),
+            Self::Xdotool => Some("xdotool"),
+        }
+    }
+
+    /// Arguments sending Ctrl+V
+    pub fn paste_args(self) -> &'static [&'static str] {
+        match self {
+            Self::SendInput => &[],
+            Self::Wtype => &["-M", "ctrl", "v", "-m", "ctrl"],
+            // Linux input event codes: 29 = left ctrl, 47 = v
+            Self::Ydotool => &["key", "29:1", "47:1", "47:0", "29:0"],
+            Self::Xdotool => &["key", "--clearmodifiers", "ctrl+v"],
+        }
+    }
+
+    /// Arguments typing `text` directly
+    ///
+    /// `delay_ms` is the pause between keystrokes; None keeps the tool's default.
+    pub fn type_args(self, text: &str, delay_ms: Option<u64>) -> Vec<String> {
+        let (command, delay_flag): (&[&str], &str) = match self {
+            Self::SendInput => return Vec::new(),
+            Self::Wtype => (&[], "-d"),
+            Self::Ydotool => (&["type"], "--key-delay"),
+            Self::Xdotool => (&["type", "--clearmodifiers"], "--delay"),
+        };
+        let mut args: Vec<String> = command.iter().map(|s| s.to_string()).collect();
+        if let Some(delay) = delay_ms {
+            args.push(delay_flag.to_string());
+            args.push(delay.to_string());
+        }
+        args.push("--".to_string());
+        args.push(text.to_string());
+        args
+    }
+}
+
+/// What output and shortcut features work in the current session
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct PlatformCapabilities {
+    pub display_server: DisplayServer,
+    pub clipboard: ClipboardBackend,
+    /// Paste backends in order of preference (empty = no automatic paste)
+    pub paste: Vec<PasteBackend>,
+    /// Whether global shortcuts are delivered regardless of the focused window
+    pub global_shortcuts: bool,
+    /// Setup hints for missing capabilities
+    pub guidance: Vec<String>,
+}
+
+impl PlatformCapabilities {
+    /// Detect capabilities of the running session
+    pub fn detect() -> Self {
+        Self::resolve(DisplayServer::detect(), |tool| find_executable(tool).is_some())
+    }
+
+    /// Resolve capabilities for a session given which external tools exist
+    pub fn resolve(display_server: DisplayServer, has_tool: impl Fn(&str) -> bool) -> Self {
+        let mut clipboard = ClipboardBackend::Native;
+        let mut paste = Vec::new();
+        let mut global_shortcuts = true;
+        let mut guidance = Vec::new();
+
+        match display_server {
+            DisplayServer::Windows => paste.push(PasteBackend::SendInput),
+            DisplayServer::MacOs => {
+                guidance.push(
+                    "Automatic paste is not available on macOS yet; transcriptions are copied to the clipboard."
+                        .to_string(),
+                );
+            }
+            DisplayServer::X11 => {
+                if has_tool("xdotool") {
+                    paste.push(PasteBackend::Xdotool);
+                } else {
+                    guidance.push("Install xdotool to enable automatic paste.".to_string());
+                }
+            }
+            DisplayServer::Wayland => {
+                if has_tool("wl-copy") && has_tool("wl-paste") {
+                    clipboard = ClipboardBackend::WlClipboard;
+                } else {
+                    guidance.push(
+                        "Install wl-clipboard so transcriptions reach native Wayland apps through the clipboard."
+                            .to_string(),
+                    );
+                }
+
+                for backend in [PasteBackend::Wtype, PasteBackend::Ydotool] {
+                    if backend.program().is_some_and(&has_tool) {
+                        paste.push(backend);
+                    }
+                }
+                if paste.is_empty() {
+                    guidance.push(
+                        "Install wtype (wlroots compositors) or ydotool with the ydotoold service (GNOME, KDE) to enable automatic paste."
+                            .to_string(),
+                    );
+                }
+
+                global_shortcuts = false;
+                guidance.push(
+                    "Wayland only delivers global shortcuts to GigaWhisper while an X11 app has focus. Enable your compositor's X11 shortcut forwarding (e.g. KDE \"Legacy X11 App Support\") or start recordings from the tray."
+                        .to_string(),
+                );
+            }
+            DisplayServer::Unknown => {
+                global_shortcuts = false;
+                guidance.push(
+                    "No graphical session detected; shortcuts and automatic paste are unavailable."
+                        .to_string(),
+                );
+            }
+        }
+
+        Self {
+            display_server,
+            clipboard,
+            paste,
+            global_shortcuts,
+            guidance,
+        }
+    }
+
+    /// Whether a paste keystroke can be synthesized
+    pub fn can_paste(&self) -> bool {
+        !self.paste.is_empty()
+    }
+}
+
+/// Capabilities of the running session, detected once
+pub fn platform_capabilities() -> &'static PlatformCapabilities {
+    static CAPABILITIES: OnceLock<PlatformCapabilities> = OnceLock::new();
+    CAPABILITIES.get_or_init(PlatformCapabilities::detect)
+}
+
+/// Find an executable on PATH
+pub fn find_executable(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path)
+        .map(|dir| dir.join(name))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| {
+            vars.iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| v.to_string())
+        }
+    }
+
+    #[test]
+    fn test_display_server_from_env() {
+        assert_eq!(
+            DisplayServer::from_env(env(&[("WAYLAND_DISPLAY", "wayland-0"), ("DISPLAY", ":0")])),
+            DisplayServer::Wayland
+        );
+        assert_eq!(
+            DisplayServer::from_env(env(&[("XDG_SESSION_TYPE", "wayland")])),
+            DisplayServer::Wayland
+        );
+        assert_eq!(DisplayServer::from_env(env(&[("DISPLAY", ":0")])), DisplayServer::X11);
+        assert_eq!(
+            DisplayServer::from_env(env(&[("WAYLAND_DISPLAY", "")])),
+            DisplayServer::Unknown
+        );
+    }
+
+    #[test]
+    fn test_wayland_with_tools() {
+        let caps = PlatformCapabilities::resolve(DisplayServer::Wayland, |_| true);
+        assert_eq!(caps.clipboard, ClipboardBackend::WlClipboard);
+        assert_eq!(caps.paste, vec![PasteBackend::Wtype, PasteBackend::Ydotool]);
+        assert!(!caps.global_shortcuts);
+        assert_eq!(caps.guidance.len(), 1);
+    }
+
+    #[test]
+    fn test_wayland_without_tools() {
+        let caps = PlatformCapabilities::resolve(DisplayServer::Wayland, |_| false);
+        assert_eq!(caps.clipboard, ClipboardBackend::Native);
+        assert!(!caps.can_paste());
+        assert_eq!(caps.guidance.len(), 3);
+    }
+
+    #[test]
+    fn test_x11_uses_xdotool() {
+        let caps = PlatformCapabilities::resolve(DisplayServer::X11, |tool| tool == "xdotool");
+        assert_eq!(caps.paste, vec![PasteBackend::Xdotool]);
+        assert!(caps.global_shortcuts);
+        assert!(caps.guidance.is_empty());
+    }
+
+    #[test]
+    fn test_type_args() {
+        assert_eq!(PasteBackend::Wtype.type_args("hi", None), vec!["--", "hi"]);
+        assert_eq!(
+            PasteBackend::Xdotool.type_args("-x", None),
+            vec!["type", "--clearmodifiers", "--", "-x"]
+        );
+        assert_eq!(
+            PasteBackend::Ydotool.type_args("hi", Some(20)),
+            vec!["type", "--key-delay", "20", "--", "hi"]
+        );
+        assert_eq!(
+            PasteBackend::Wtype.type_args("hi", Some(5)),
+            vec!["-d", "5", "--", "hi"]
+        );
+    }
+}
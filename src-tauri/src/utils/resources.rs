@@ -0,0 +1,405 @@
+//! Memory Budget
+//!
+//! Keeps idle memory use within the RAM and VRAM caps of the resource
+//! settings. Over a cap, the cheapest measures come first: in-memory caches
+//! are shrunk, then provider warm-up is suspended so the model isn't loaded
+//! ahead of a recording, and finally the Whisper model is unloaded (never
+//! while recording or transcribing). The model is also unloaded after the
+//! configured idle time.
+
+use crate::config::{Settings, TranscriptionProvider};
+use crate::transcription::{TranscriptionService, WhisperProvider};
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// How often the budget is checked
+pub const BUDGET_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Metrics records kept when caches are shrunk
+const SHRUNK_METRICS_RECORDS: usize = 10;
+
+/// Whether warm-up is suspended to stay within the budget
+static PREFETCH_SUSPENDED: AtomicBool = AtomicBool::new(false);
+
+/// Step bringing memory use back within the budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BudgetAction {
+    /// Drop pipeline traces, old metrics and finished file jobs
+    ShrinkCaches,
+    /// Stop loading the model ahead of recordings
+    SuspendPrefetch,
+    /// Unload the Whisper model
+    UnloadModel,
+}
+
+/// Memory held by an in-memory cache
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct CacheUsage {
+    pub name: &'static str,
+    pub entries: usize,
+    /// Approximate size (bytes)
+    pub bytes: u64,
+}
+
+/// Current memory use against the budget
+#[derive(Debug, Clone, PartialEq, Serialize, JsonSchema)]
+pub struct ResourceUsage {
+    /// Resident memory of the process (None where the platform doesn't report it)
+    pub process_ram_bytes: Option<u64>,
+    /// File name of the loaded Whisper model
+    pub model: Option<String>,
+    /// Loaded model held in RAM
+    pub model_ram_bytes: u64,
+    /// Loaded model held in VRAM (estimated from the model size)
+    pub model_vram_bytes: u64,
+    /// Size of the configured local model, loaded or not (0 for cloud providers)
+    pub configured_model_bytes: u64,
+    /// Whether the configured model loads into VRAM
+    pub configured_model_gpu: bool,
+    pub caches: Vec<CacheUsage>,
+    pub max_ram_bytes: Option<u64>,
+    pub max_vram_bytes: Option<u64>,
+    pub prefetch_suspended: bool,
+}
+
+impl ResourceUsage {
+    /// RAM in use: the process's resident memory, or the model and caches
+    /// where that is unknown
+    pub fn ram_bytes(&self) -> u64 {
+        self.process_ram_bytes
+            .unwrap_or_else(|| self.model_ram_bytes + self.cache_bytes())
+    }
+
+    /// Memory held by the in-memory caches
+    pub fn cache_bytes(&self) -> u64 {
+        self.caches.iter().map(|c| c.bytes).sum()
+    }
+
+    /// Whether RAM use is over its cap
+    pub fn over_ram(&self) -> bool {
+        self.max_ram_bytes.is_some_and(|max| self.ram_bytes() > max)
+    }
+
+    /// Whether VRAM use is over its cap
+    pub fn over_vram(&self) -> bool {
+        self.max_vram_bytes.is_some_and(|max| self.model_vram_bytes > max)
+    }
+
+    /// Steps bringing usage back within the budget, cheapest first
+    ///
+    /// Warm-up stays suspended for as long as loading the configured model
+    /// would exceed a cap, so an unloaded model isn't loaded straight back.
+    pub fn plan(&self) -> Vec<BudgetAction> {
+        let mut actions = Vec::new();
+        let mut ram = self.ram_bytes();
+        if self.over_ram() && self.cache_bytes() > 0 {
+            actions.push(BudgetAction::ShrinkCaches);
+            ram = ram.saturating_sub(self.cache_bytes());
+        }
+
+        let (load_ram, load_vram) = match self.configured_model_gpu {
+            true => (0, self.configured_model_bytes),
+            false => (self.configured_model_bytes, 0),
+        };
+        let without_model = ram.saturating_sub(self.model_ram_bytes);
+        if self.max_ram_bytes.is_some_and(|max| without_model + load_ram > max)
+            || self.max_vram_bytes.is_some_and(|max| load_vram > max)
+        {
+            actions.push(BudgetAction::SuspendPrefetch);
+        }
+
+        let ram_over = self.max_ram_bytes.is_some_and(|max| ram > max);
+        if (ram_over && self.model_ram_bytes > 0) || self.over_vram() {
+            actions.push(BudgetAction::UnloadModel);
+        }
+        actions
+    }
+}
+
+/// Whether warm-up is suspended to stay within the budget
+pub fn prefetch_suspended() -> bool {
+    PREFETCH_SUSPENDED.load(Ordering::Relaxed)
+}
+
+/// Measure memory use against the budget in `settings`
+pub fn resource_usage(settings: &Settings, service: &TranscriptionService) -> ResourceUsage {
+    let file_size = |path: &std::path::Path| std::fs::metadata(path).map_or(0, |m| m.len());
+
+    let (model, model_ram_bytes, model_vram_bytes) = match service.loaded_model() {
+        Some((path, on_gpu)) => {
+            let bytes = file_size(&path);
+            let name = path.file_name().map(|n| n.to_string_lossy().to_string());
+            match on_gpu {
+                true => (name, 0, bytes),
+                false => (name, bytes, 0),
+            }
+        }
+        None => (None, 0, 0),
+    };
+
+    let local = &settings.transcription.local;
+    let configured_model_bytes = match settings.transcription.provider {
+        TranscriptionProvider::Local => {
+            match file_size(&crate::config::find_model(&local.model_filename())) {
+                0 => local.estimated_model_size(),
+                bytes => bytes,
+            }
+        }
+        TranscriptionProvider::Groq
+        | TranscriptionProvider::Deepgram
+        | TranscriptionProvider::Custom => 0,
+    };
+
+    ResourceUsage {
+        process_ram_bytes: process_ram_bytes(),
+        model,
+        model_ram_bytes,
+        model_vram_bytes,
+        configured_model_bytes,
+        configured_model_gpu: local.gpu_enabled && WhisperProvider::is_gpu_available(),
+        caches: cache_usage(),
+        max_ram_bytes: settings.resources.max_ram_bytes(),
+        max_vram_bytes: settings.resources.max_vram_bytes(),
+        prefetch_suspended: prefetch_suspended(),
+    }
+}
+
+/// Size of the in-memory caches that can be shrunk
+fn cache_usage() -> Vec<CacheUsage> {
+    fn json_bytes<T: Serialize>(items: &[T]) -> u64 {
+        items
+            .iter()
+            .map(|item| serde_json::to_vec(item).map_or(0, |json| json.len() as u64))
+            .sum()
+    }
+
+    let traces: Vec<_> = crate::transcription::pipeline_traces().lock().iter().cloned().collect();
+    let metrics = super::metrics().read().get_recent(usize::MAX);
+    let jobs = crate::transcription::file_queue().lock().jobs();
+    let batch_files = crate::transcription::batch()
+        .lock()
+        .as_ref()
+        .map(|run| run.files.jobs())
+        .unwrap_or_default();
+
+    vec![
+        CacheUsage {
+            name: "pipeline-traces",
+            entries: traces.len(),
+            bytes: json_bytes(&traces),
+        },
+        CacheUsage {
+            name: "metrics",
+            entries: metrics.len(),
+            bytes: json_bytes(&metrics),
+        },
+        CacheUsage {
+            name: "file-queue",
+            entries: jobs.len(),
+            bytes: json_bytes(&jobs),
+        },
+        CacheUsage {
+            name: "batch",
+            entries: batch_files.len(),
+            bytes: json_bytes(&batch_files),
+        },
+    ]
+}
+
+/// Drop pipeline traces, old metrics, finished file jobs and a finished batch
+fn shrink_caches(app: &AppHandle) {
+    use crate::transcription::{batch, file_queue, pipeline_traces, FILE_QUEUE_EVENT};
+
+    pipeline_traces().lock().clear();
+    super::metrics().write().trim(SHRUNK_METRICS_RECORDS);
+
+    let jobs = {
+        let mut queue = file_queue().lock();
+        queue.clear_finished();
+        queue.jobs()
+    };
+    let _ = app.emit(FILE_QUEUE_EVENT, jobs);
+
+    let mut batch = batch().lock();
+    if batch.as_ref().is_some_and(|run| !run.is_active()) {
+        *batch = None;
+    }
+}
+
+/// Unload an idle model and bring memory use back within the budget
+///
+/// Returns the steps that were due.
+pub fn enforce_budget(app: &AppHandle) -> Vec<BudgetAction> {
+    let state = app.state::<crate::AppState>();
+    let settings = state.config.read().clone();
+    let service = &state.transcription_service;
+    let busy = !matches!(
+        *state.recording_state.read(),
+        crate::RecordingState::Idle | crate::RecordingState::Error(_)
+    ) || service.get_status().is_transcribing;
+
+    let idle_unload = settings.resources.idle_unload_minutes as u64 * 60;
+    if idle_unload > 0
+        && !busy
+        && service
+            .model_idle_time()
+            .is_some_and(|idle| idle.as_secs() >= idle_unload)
+    {
+        tracing::info!(
+            "Unloading Whisper model after {} idle minute(s)",
+            settings.resources.idle_unload_minutes
+        );
+        service.unload_model();
+    }
+
+    let usage = resource_usage(&settings, service);
+    let actions = usage.plan();
+
+    let suspend = actions.contains(&BudgetAction::SuspendPrefetch);
+    if PREFETCH_SUSPENDED.swap(suspend, Ordering::Relaxed) != suspend {
+        match suspend {
+            true => tracing::info!("Provider warm-up suspended: the model would exceed the memory budget"),
+            false => tracing::info!("Provider warm-up resumed"),
+        }
+    }
+
+    for action in &actions {
+        match action {
+            BudgetAction::ShrinkCaches => {
+                tracing::debug!(
+                    "Memory use {} MB over budget; shrinking caches",
+                    usage.ram_bytes() / (1024 * 1024)
+                );
+                shrink_caches(app);
+            }
+            BudgetAction::SuspendPrefetch => {}
+            BudgetAction::UnloadModel if busy => {
+                tracing::debug!("Over memory budget; model unload deferred while busy");
+            }
+            BudgetAction::UnloadModel => {
+                tracing::info!("Unloading Whisper model to stay within the memory budget");
+                service.unload_model();
+            }
+        }
+    }
+    actions
+}
+
+/// Resident memory of the process
+#[cfg(target_os = "linux")]
+fn process_ram_bytes() -> Option<u64> {
+    parse_vm_rss(&std::fs::read_to_string("/proc/self/status").ok()?)
+}
+
+/// Resident memory of the process
+#[cfg(windows)]
+fn process_ram_bytes() -> Option<u64> {
+    use windows::Win32::System::ProcessStatus::{GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS};
+    use windows::Win32::System::Threading::GetCurrentProcess;
+
+    let mut counters = PROCESS_MEMORY_COUNTERS::default();
+    // SAFETY: the counters struct outlives the call and its size is passed along
+    unsafe {
+        GetProcessMemoryInfo(
+            GetCurrentProcess(),
+            &mut counters,
+            std::mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+        )
+        .ok()?;
+    }
+    Some(counters.WorkingSetSize as u64)
+}
+
+/// Resident memory of the process
+#[cfg(not(any(target_os = "linux", windows)))]
+fn process_ram_bytes() -> Option<u64> {
+    None
+}
+
+/// Resident memory from the contents of `/proc/self/status`
+#[cfg(any(target_os = "linux", test))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    fn usage(ram_mb: u64, model_mb: u64, cache_mb: u64) -> ResourceUsage {
+        ResourceUsage {
+            process_ram_bytes: Some(ram_mb * MB),
+            model: (model_mb > 0).then(|| "ggml-small.bin".to_string()),
+            model_ram_bytes: model_mb * MB,
+            model_vram_bytes: 0,
+            configured_model_bytes: 466 * MB,
+            configured_model_gpu: false,
+            caches: vec![CacheUsage {
+                name: "metrics",
+                entries: 100,
+                bytes: cache_mb * MB,
+            }],
+            max_ram_bytes: Some(1024 * MB),
+            max_vram_bytes: None,
+            prefetch_suspended: false,
+        }
+    }
+
+    #[test]
+    fn test_plan_within_budget() {
+        assert!(usage(800, 466, 2).plan().is_empty());
+        let mut unlimited = usage(4000, 466, 2);
+        unlimited.max_ram_bytes = None;
+        assert!(unlimited.plan().is_empty());
+    }
+
+    #[test]
+    fn test_plan_shrinks_caches_first() {
+        // Shrinking the caches is enough
+        assert_eq!(usage(1030, 466, 20).plan(), vec![BudgetAction::ShrinkCaches]);
+
+        // The model has to go, and must not be warmed up again
+        assert_eq!(
+            usage(1400, 466, 20).plan(),
+            vec![
+                BudgetAction::ShrinkCaches,
+                BudgetAction::SuspendPrefetch,
+                BudgetAction::UnloadModel
+            ]
+        );
+
+        // Once unloaded, warm-up stays suspended without further steps
+        assert_eq!(usage(940, 0, 0).plan(), vec![BudgetAction::SuspendPrefetch]);
+    }
+
+    #[test]
+    fn test_plan_vram_cap() {
+        let mut gpu = usage(300, 0, 0);
+        gpu.model_vram_bytes = 1500 * MB;
+        gpu.configured_model_bytes = 1500 * MB;
+        gpu.configured_model_gpu = true;
+        gpu.max_vram_bytes = Some(1024 * MB);
+        assert_eq!(
+            gpu.plan(),
+            vec![BudgetAction::SuspendPrefetch, BudgetAction::UnloadModel]
+        );
+
+        gpu.max_vram_bytes = Some(2048 * MB);
+        assert!(gpu.plan().is_empty());
+    }
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tgigawhisper\nVmPeak:\t  900000 kB\nVmRSS:\t  123456 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(123456 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tgigawhisper\n"), None);
+    }
+}
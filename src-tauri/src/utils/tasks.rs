@@ -0,0 +1,270 @@
+//! Background Tasks
+//!
+//! Registry of work spawned outside of a command's own lifetime (shortcut
+//! handlers, indicator updates, update checks, speech monitoring). Every
+//! task is listed until it finishes so stuck work shows up in diagnostics,
+//! and async tasks still running at shutdown are aborted instead of being
+//! torn down mid-flight by the runtime.
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tauri::async_runtime::JoinHandle;
+
+/// How a background task runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskKind {
+    /// Future on the async runtime (abortable)
+    Async,
+    /// Dedicated OS thread (stops on its own once its input ends)
+    Thread,
+}
+
+/// Snapshot of a running background task
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct BackgroundTask {
+    pub id: u64,
+    pub name: &'static str,
+    pub kind: TaskKind,
+    pub running_ms: u64,
+}
+
+struct TaskEntry {
+    name: &'static str,
+    kind: TaskKind,
+    started_at: Instant,
+    handle: Option<JoinHandle<()>>,
+}
+
+/// Removes a task's entry when the task ends, even by panicking
+struct Registration {
+    tasks: Arc<Mutex<HashMap<u64, TaskEntry>>>,
+    id: u64,
+}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        self.tasks.lock().remove(&self.id);
+    }
+}
+
+/// Tracks spawned background work
+#[derive(Clone, Default)]
+pub struct TaskRegistry {
+    tasks: Arc<Mutex<HashMap<u64, TaskEntry>>>,
+    next_id: Arc<AtomicU64>,
+    shutting_down: Arc<AtomicBool>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn a future on the async runtime
+    ///
+    /// Returns `None` once shutdown has started.
+    pub fn spawn<F>(&self, name: &'static str, future: F) -> Option<u64>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        if self.is_shutting_down() {
+            tracing::debug!("Not starting task '{}' during shutdown", name);
+            return None;
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let registration = self.register(id);
+
+        // Hold the lock until the entry is inserted so a task finishing
+        // immediately can't try to remove itself first
+        let mut guard = self.tasks.lock();
+        let handle = tauri::async_runtime::spawn(async move {
+            let _registration = registration;
+            future.await;
+        });
+        guard.insert(id, TaskEntry::new(name, TaskKind::Async, Some(handle)));
+        Some(id)
+    }
+
+    /// Spawn a closure on a dedicated thread
+    ///
+    /// For blocking work that can't live on the async runtime (e.g. `!Send`
+    /// state). Threads can't be aborted; they are expected to return once
+    /// their input channel closes.
+    pub fn spawn_thread<F>(&self, name: &'static str, f: F) -> Option<u64>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        if self.is_shutting_down() {
+            tracing::debug!("Not starting thread '{}' during shutdown", name);
+            return None;
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let registration = self.register(id);
+
+        let mut guard = self.tasks.lock();
+        let spawned = std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let _registration = registration;
+                f();
+            });
+        match spawned {
+            Ok(_) => {
+                guard.insert(id, TaskEntry::new(name, TaskKind::Thread, None));
+                Some(id)
+            }
+            Err(e) => {
+                tracing::error!("Failed to spawn thread '{}': {}", name, e);
+                None
+            }
+        }
+    }
+
+    /// Currently running tasks, oldest first
+    pub fn list(&self) -> Vec<BackgroundTask> {
+        let now = Instant::now();
+        let mut tasks: Vec<BackgroundTask> = self
+            .tasks
+            .lock()
+            .iter()
+            .map(|(id, entry)| BackgroundTask {
+                id: *id,
+                name: entry.name,
+                kind: entry.kind,
+                running_ms: now.duration_since(entry.started_at).as_millis() as u64,
+            })
+            .collect();
+        tasks.sort_by_key(|task| task.id);
+        tasks
+    }
+
+    /// Abort a running async task
+    ///
+    /// Returns false if the task is unknown, finished, or a thread.
+    pub fn cancel(&self, id: u64) -> bool {
+        let mut tasks = self.tasks.lock();
+        match tasks.get(&id).map(|entry| entry.kind) {
+            Some(TaskKind::Async) => {
+                if let Some(handle) = tasks.remove(&id).and_then(|entry| entry.handle) {
+                    handle.abort();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Refuse new tasks and abort running async ones
+    pub fn shutdown(&self) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        let mut tasks = self.tasks.lock();
+        let mut aborted = 0;
+        tasks.retain(|_, entry| match entry.handle.take() {
+            Some(handle) => {
+                handle.abort();
+                aborted += 1;
+                false
+            }
+            None => true,
+        });
+
+        if aborted > 0 || !tasks.is_empty() {
+            tracing::info!(
+                "Shutdown: aborted {} background task(s), {} thread(s) still winding down",
+                aborted,
+                tasks.len()
+            );
+        }
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.shutting_down.load(Ordering::SeqCst)
+    }
+
+    fn register(&self, id: u64) -> Registration {
+        Registration {
+            tasks: self.tasks.clone(),
+            id,
+        }
+    }
+}
+
+impl TaskEntry {
+    fn new(name: &'static str, kind: TaskKind, handle: Option<JoinHandle<()>>) -> Self {
+        Self {
+            name,
+            kind,
+            started_at: Instant::now(),
+            handle,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_thread_is_listed_until_it_finishes() {
+        let registry = TaskRegistry::new();
+        let (tx, rx) = mpsc::channel::<()>();
+
+        registry.spawn_thread("test-thread", move || {
+            let _ = rx.recv();
+        });
+        let tasks = registry.list();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, "test-thread");
+        assert_eq!(tasks[0].kind, TaskKind::Thread);
+        assert!(!registry.cancel(tasks[0].id));
+
+        drop(tx);
+        for _ in 0..100 {
+            if registry.list().is_empty() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("finished thread was not removed");
+    }
+
+    #[test]
+    fn test_panicking_thread_is_removed() {
+        let registry = TaskRegistry::new();
+        registry.spawn_thread("test-panic", || panic!("task failed"));
+
+        // Printing the panic (and its backtrace) can take a while
+        for _ in 0..1000 {
+            if registry.list().is_empty() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        panic!("panicked thread was not removed");
+    }
+
+    #[test]
+    fn test_shutdown_aborts_async_tasks() {
+        let registry = TaskRegistry::new();
+        registry.spawn("test-sleep", async {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        });
+        assert_eq!(registry.list().len(), 1);
+
+        registry.shutdown();
+        assert!(registry.list().is_empty());
+        assert!(registry.spawn("too-late", async {}).is_none());
+    }
+}
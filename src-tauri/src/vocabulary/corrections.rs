@@ -0,0 +1,357 @@
+//! Correction Feedback
+//!
+//! Fixes the user makes to transcriptions, kept locally. Each correction is
+//! diffed word by word against the original; a fix seen in enough different
+//! entries becomes a replacement rule, and its capitalized words become
+//! vocabulary terms.
+
+use super::ReplacementRule;
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Number of different entries a fix must appear in before it becomes a rule
+pub const PROMOTE_AFTER: usize = 2;
+
+/// Longest phrase (in words) learned from a single fix
+pub const MAX_PHRASE_WORDS: usize = 4;
+
+/// Longest text (in words) diffed; longer rewrites teach nothing reliable
+pub const MAX_DIFF_WORDS: usize = 300;
+
+/// Maximum number of corrections kept
+pub const MAX_CORRECTIONS: usize = 500;
+
+/// Maximum number of pending fixes kept
+pub const MAX_PENDING_FIXES: usize = 1000;
+
+/// Global correction store
+static CORRECTIONS: OnceLock<RwLock<CorrectionStore>> = OnceLock::new();
+
+/// A transcription as corrected by the user
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Correction {
+    pub entry_id: String,
+    pub original: String,
+    pub corrected: String,
+    /// When the correction was submitted (ISO 8601)
+    pub timestamp: String,
+}
+
+/// A word-level fix not yet seen often enough to become a rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingFix {
+    pub from: String,
+    pub to: String,
+    /// Entries the fix was seen in
+    pub entry_ids: Vec<String>,
+}
+
+/// What a correction taught the vocabulary
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct CorrectionOutcome {
+    /// Fixes found in the correction
+    pub fixes: usize,
+    /// Rules added or updated in the vocabulary
+    pub rules: Vec<ReplacementRule>,
+    /// Terms added to the vocabulary
+    pub terms: Vec<String>,
+}
+
+/// Submitted corrections and the fixes learned from them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CorrectionStore {
+    corrections: Vec<Correction>,
+    pending: Vec<PendingFix>,
+}
+
+impl CorrectionStore {
+    /// Load corrections from disk
+    pub fn load() -> Self {
+        let path = corrections_file_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(store) => return store,
+                    Err(e) => tracing::warn!("Failed to parse corrections file: {}", e),
+                },
+                Err(e) => tracing::warn!("Failed to read corrections file: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    /// Save corrections to disk
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let path = corrections_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, content)
+    }
+
+    /// Submitted corrections, newest first
+    pub fn corrections(&self) -> Vec<Correction> {
+        self.corrections.iter().rev().cloned().collect()
+    }
+
+    /// Fixes waiting to be seen again
+    pub fn pending(&self) -> &[PendingFix] {
+        &self.pending
+    }
+
+    /// Record a correction and return the fixes that became rules
+    pub fn record(
+        &mut self,
+        entry_id: &str,
+        original: &str,
+        corrected: &str,
+    ) -> (usize, Vec<ReplacementRule>) {
+        self.corrections.push(Correction {
+            entry_id: entry_id.to_string(),
+            original: original.to_string(),
+            corrected: corrected.to_string(),
+            timestamp: crate::history::chrono_timestamp(),
+        });
+        if self.corrections.len() > MAX_CORRECTIONS {
+            let excess = self.corrections.len() - MAX_CORRECTIONS;
+            self.corrections.drain(..excess);
+        }
+
+        let fixes = diff_fixes(original, corrected);
+        let mut promoted = Vec::new();
+        for (from, to) in &fixes {
+            let position = self.pending.iter().position(|p| {
+                p.from.to_lowercase() == from.to_lowercase() && p.to == *to
+            });
+            let index = match position {
+                Some(index) => index,
+                None => {
+                    self.pending.push(PendingFix {
+                        from: from.clone(),
+                        to: to.clone(),
+                        entry_ids: Vec::new(),
+                    });
+                    self.pending.len() - 1
+                }
+            };
+
+            let fix = &mut self.pending[index];
+            if !fix.entry_ids.iter().any(|id| id == entry_id) {
+                fix.entry_ids.push(entry_id.to_string());
+            }
+            if fix.entry_ids.len() >= PROMOTE_AFTER {
+                let fix = self.pending.remove(index);
+                promoted.push(ReplacementRule::new(fix.from, fix.to));
+            }
+        }
+
+        // Keep the most recently seen fixes
+        if self.pending.len() > MAX_PENDING_FIXES {
+            let excess = self.pending.len() - MAX_PENDING_FIXES;
+            self.pending.drain(..excess);
+        }
+
+        (fixes.len(), promoted)
+    }
+}
+
+/// Word-level fixes between a transcription and its correction
+///
+/// Returns `(from, to)` phrases for each changed run of words. Pure
+/// insertions and deletions, runs longer than `MAX_PHRASE_WORDS` and
+/// sentence capitalization are skipped: they are edits, not recognition
+/// mistakes.
+pub fn diff_fixes(original: &str, corrected: &str) -> Vec<(String, String)> {
+    let a = words(original);
+    let b = words(corrected);
+    if a.len() > MAX_DIFF_WORDS || b.len() > MAX_DIFF_WORDS {
+        return Vec::new();
+    }
+
+    // Longest common subsequence table, filled from the end
+    let mut lcs = vec![vec![0u16; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut fixes = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    let (mut run_a, mut run_b) = (0, 0);
+    loop {
+        let matched = i < a.len() && j < b.len() && a[i] == b[j];
+        let done = i == a.len() && j == b.len();
+        if matched || done {
+            push_fix(&mut fixes, &a[run_a..i], &b[run_b..j]);
+            if done {
+                break;
+            }
+            i += 1;
+            j += 1;
+            run_a = i;
+            run_b = j;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    fixes
+}
+
+fn push_fix(fixes: &mut Vec<(String, String)>, from: &[&str], to: &[&str]) {
+    let usable = |run: &[&str]| !run.is_empty() && run.len() <= MAX_PHRASE_WORDS;
+    if usable(from) && usable(to) {
+        let (from, to) = (from.join(" "), to.join(" "));
+        if !is_initial_case_fix(&from, &to) {
+            fixes.push((from, to));
+        }
+    }
+}
+
+/// Words of a text, without surrounding punctuation
+fn words(text: &str) -> Vec<&str> {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Whether a fix only changes the case of the first letter, as when
+/// capitalizing the start of a sentence
+fn is_initial_case_fix(from: &str, to: &str) -> bool {
+    fn lower_first(s: &str) -> String {
+        let mut chars = s.chars();
+        chars
+            .next()
+            .map(|c| c.to_lowercase().chain(chars).collect())
+            .unwrap_or_default()
+    }
+    lower_first(from) == lower_first(to)
+}
+
+/// Capitalized words of learned rules, to bias recognition
+fn rule_terms(rules: &[ReplacementRule]) -> Vec<String> {
+    rules
+        .iter()
+        .flat_map(|rule| words(&rule.to))
+        .filter(|w| w.chars().count() >= 2 && w.chars().any(char::is_uppercase))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Get the corrections file path
+fn corrections_file_path() -> PathBuf {
+    crate::config::workspace_dir().join("corrections.json")
+}
+
+/// Get or initialize the global correction store
+pub fn get_corrections() -> &'static RwLock<CorrectionStore> {
+    CORRECTIONS.get_or_init(|| RwLock::new(CorrectionStore::load()))
+}
+
+/// Record a correction and grow the vocabulary from repeated fixes
+pub fn learn_from_correction(
+    entry_id: &str,
+    original: &str,
+    corrected: &str,
+) -> Result<CorrectionOutcome, super::VocabularyError> {
+    let (fixes, promoted) = {
+        let mut store = get_corrections().write();
+        let result = store.record(entry_id, original, corrected);
+        store.save()?;
+        result
+    };
+
+    let mut outcome = CorrectionOutcome {
+        fixes,
+        ..Default::default()
+    };
+    if !promoted.is_empty() {
+        let terms = rule_terms(&promoted);
+        let mut vocabulary = super::get_vocabulary().write();
+        (outcome.rules, outcome.terms) = vocabulary.learn(promoted, terms);
+        vocabulary.save()?;
+        tracing::info!(
+            "Learned {} rules and {} terms from corrections",
+            outcome.rules.len(),
+            outcome.terms.len()
+        );
+    }
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fix(from: &str, to: &str) -> (String, String) {
+        (from.to_string(), to.to_string())
+    }
+
+    #[test]
+    fn test_diff_fixes_finds_replaced_runs() {
+        assert_eq!(
+            diff_fixes("deploy it with cube cuddle, then check", "Deploy it with kubectl, then check"),
+            vec![fix("cube cuddle", "kubectl")]
+        );
+        assert_eq!(
+            diff_fixes("ask lukasz", "ask Łukasz."),
+            vec![fix("lukasz", "Łukasz")]
+        );
+    }
+
+    #[test]
+    fn test_diff_fixes_skips_edits() {
+        // Insertions, deletions and rewrites are not recognition mistakes
+        assert!(diff_fixes("send the report", "send the report today").is_empty());
+        assert!(diff_fixes("send the full report", "send the report").is_empty());
+        assert!(diff_fixes(
+            "one two three four five",
+            "completely different sentence written by hand"
+        )
+        .is_empty());
+        assert!(diff_fixes("same text", "same text").is_empty());
+        assert!(diff_fixes("well done", "Well done").is_empty());
+        assert_eq!(diff_fixes("open github", "open GitHub"), vec![fix("github", "GitHub")]);
+    }
+
+    #[test]
+    fn test_fix_promoted_after_distinct_entries() {
+        let mut store = CorrectionStore::default();
+        let (fixes, promoted) = store.record("a", "open git hub", "open GitHub");
+        assert_eq!(fixes, 1);
+        assert!(promoted.is_empty());
+
+        // Correcting the same entry again does not count twice
+        let (_, promoted) = store.record("a", "open git hub", "open GitHub");
+        assert!(promoted.is_empty());
+
+        let (_, promoted) = store.record("b", "Git hub is down", "GitHub is down");
+        assert_eq!(promoted, vec![ReplacementRule::new("git hub", "GitHub")]);
+        assert!(store.pending().is_empty());
+        assert_eq!(store.corrections().len(), 3);
+        assert_eq!(store.corrections()[0].entry_id, "b");
+    }
+
+    #[test]
+    fn test_rule_terms() {
+        let rules = vec![
+            ReplacementRule::new("git hub", "GitHub"),
+            ReplacementRule::new("cube cuddle", "kubectl on Azure"),
+            ReplacementRule::new("a", "A"),
+        ];
+        assert_eq!(rule_terms(&rules), vec!["GitHub", "Azure"]);
+    }
+}
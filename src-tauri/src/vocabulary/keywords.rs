@@ -0,0 +1,97 @@
+//! Keyword Alerts
+//!
+//! Index of alert keywords matched against transcriptions, so a dictation
+//! mentioning "action item" or the user's name can be flagged.
+
+use std::collections::HashMap;
+
+/// Alert keywords indexed by their first word
+#[derive(Debug, Clone, Default)]
+pub struct KeywordIndex {
+    /// First word -> (keyword position, keyword words)
+    by_first_word: HashMap<String, Vec<(usize, Vec<String>)>>,
+    /// Keywords as configured, in order
+    keywords: Vec<String>,
+}
+
+impl KeywordIndex {
+    /// Build an index of `keywords`, ignoring empty ones
+    pub fn new(keywords: &[String]) -> Self {
+        let mut index = Self::default();
+        for keyword in keywords {
+            let words = words(keyword);
+            let Some(first) = words.first().cloned() else {
+                continue;
+            };
+            index
+                .by_first_word
+                .entry(first)
+                .or_default()
+                .push((index.keywords.len(), words));
+            index.keywords.push(keyword.trim().to_string());
+        }
+        index
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Keywords occurring in `text` as whole words, case-insensitive, in configured order
+    pub fn find(&self, text: &str) -> Vec<String> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let text = words(text);
+        let mut found = vec![false; self.keywords.len()];
+        for (start, word) in text.iter().enumerate() {
+            for (position, keyword) in self.by_first_word.get(word).into_iter().flatten() {
+                if text[start..].starts_with(keyword) {
+                    found[*position] = true;
+                }
+            }
+        }
+
+        self.keywords
+            .iter()
+            .zip(found)
+            .filter(|(_, found)| *found)
+            .map(|(keyword, _)| keyword.clone())
+            .collect()
+    }
+}
+
+/// Lowercase words of `text`, punctuation dropped
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(keywords: &[&str]) -> KeywordIndex {
+        KeywordIndex::new(&keywords.iter().map(|k| k.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_find_phrases_case_insensitive() {
+        let index = index(&["Action item", "Alice", "deadline"]);
+        assert_eq!(
+            index.find("alice, one more ACTION ITEM: ship it."),
+            vec!["Action item", "Alice"]
+        );
+        assert!(index.find("No actions, items or Alicent here").is_empty());
+    }
+
+    #[test]
+    fn test_find_unicode_and_repeats() {
+        let index = index(&["Émile", "  ", "rendez-vous"]);
+        assert_eq!(index.find("émile, ÉMILE et le rendez vous"), vec!["Émile", "rendez-vous"]);
+        assert!(KeywordIndex::new(&[]).find("anything").is_empty());
+    }
+}
@@ -0,0 +1,348 @@
+//! Learned Terms
+//!
+//! Proper nouns the user typed in when correcting a transcription
+//! ("Łukasz", "Kubernetes"), re-capitalized in later output. Only edits
+//! teach terms: learning from the model's own output would reinforce its
+//! mistakes. Scores decay over time so one-off names fade out instead of
+//! accumulating forever.
+
+use super::ReplacementRule;
+use parking_lot::{Mutex, RwLock};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Score half-life in seconds (30 days)
+pub const DECAY_HALF_LIFE_SECS: u64 = 30 * 24 * 3600;
+
+/// Minimum decayed score before a term is applied
+pub const APPLY_THRESHOLD: f32 = 2.0;
+
+/// Maximum number of learned terms kept
+pub const MAX_LEARNED_TERMS: usize = 500;
+
+/// Minimum time between two writes of the learned terms
+pub const LEARNED_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Global learned terms instance
+static LEARNED_TERMS: OnceLock<RwLock<LearnedTerms>> = OnceLock::new();
+
+/// Whether learned terms changed since they were last written
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// When the learned terms were last written
+static LAST_SAVE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// A capitalized term seen in transcriptions
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LearnedTerm {
+    /// Term with its preferred capitalization
+    pub term: String,
+    /// Occurrence score at `last_seen` (decays afterwards)
+    pub score: f32,
+    /// Unix timestamp (seconds) of the last occurrence
+    pub last_seen: u64,
+}
+
+impl LearnedTerm {
+    /// Score after applying time decay
+    pub fn decayed_score(&self, now: u64) -> f32 {
+        let elapsed = now.saturating_sub(self.last_seen) as f32;
+        self.score * 0.5f32.powf(elapsed / DECAY_HALF_LIFE_SECS as f32)
+    }
+}
+
+/// Learned proper noun list
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LearnedTerms {
+    terms: Vec<LearnedTerm>,
+}
+
+impl LearnedTerms {
+    /// Load learned terms from disk
+    pub fn load() -> Self {
+        let path = learned_terms_file_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(terms) => return terms,
+                    Err(e) => tracing::warn!("Failed to parse learned terms file: {}", e),
+                },
+                Err(e) => tracing::warn!("Failed to read learned terms file: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    /// Save learned terms to disk
+    pub fn save(&self) -> Result<(), std::io::Error> {
+        let path = learned_terms_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+        std::fs::write(&path, content)
+    }
+
+    /// All learned terms, highest decayed score first
+    pub fn terms(&self, now: u64) -> Vec<LearnedTerm> {
+        let mut terms = self.terms.clone();
+        terms.sort_by(|a, b| b.decayed_score(now).total_cmp(&a.decayed_score(now)));
+        terms
+    }
+
+    /// Record capitalized terms found in a text
+    pub fn observe(&mut self, text: &str, now: u64) {
+        self.observe_terms(extract_proper_nouns(text), now);
+    }
+
+    /// Record the capitalized terms an edit put in, returns true if any
+    ///
+    /// Terms already written the same way in `original` are the model's and
+    /// are not counted.
+    pub fn observe_edit(&mut self, original: &str, edited: &str, now: u64) -> bool {
+        let original: Vec<&str> = original
+            .split_whitespace()
+            .map(|raw| raw.trim_matches(|c: char| !c.is_alphanumeric()))
+            .collect();
+        let added: Vec<String> = extract_proper_nouns(edited)
+            .into_iter()
+            .filter(|term| !original.contains(&term.as_str()))
+            .collect();
+        let changed = !added.is_empty();
+        self.observe_terms(added, now);
+        changed
+    }
+
+    fn observe_terms(&mut self, candidates: Vec<String>, now: u64) {
+        for candidate in candidates {
+            match self
+                .terms
+                .iter_mut()
+                .find(|t| t.term.to_lowercase() == candidate.to_lowercase())
+            {
+                Some(existing) => {
+                    existing.score = existing.decayed_score(now) + 1.0;
+                    existing.last_seen = now;
+                    existing.term = candidate;
+                }
+                None => self.terms.push(LearnedTerm {
+                    term: candidate,
+                    score: 1.0,
+                    last_seen: now,
+                }),
+            }
+        }
+
+        if self.terms.len() > MAX_LEARNED_TERMS {
+            self.terms
+                .sort_by(|a, b| b.decayed_score(now).total_cmp(&a.decayed_score(now)));
+            self.terms.truncate(MAX_LEARNED_TERMS);
+        }
+    }
+
+    /// Re-capitalize known terms in a text
+    pub fn apply(&self, text: &str, now: u64) -> String {
+        self.terms
+            .iter()
+            .filter(|t| t.decayed_score(now) >= APPLY_THRESHOLD)
+            .fold(text.to_string(), |acc, t| {
+                ReplacementRule::new(t.term.clone(), t.term.clone()).apply(&acc)
+            })
+    }
+
+    /// Remove a single term (case-insensitive), returns true if found
+    pub fn remove(&mut self, term: &str) -> bool {
+        let len_before = self.terms.len();
+        let term = term.to_lowercase();
+        self.terms.retain(|t| t.term.to_lowercase() != term);
+        self.terms.len() != len_before
+    }
+
+    /// Remove terms whose decayed score fell below `min_score`, returns the count removed
+    pub fn prune(&mut self, min_score: f32, now: u64) -> usize {
+        let len_before = self.terms.len();
+        self.terms.retain(|t| t.decayed_score(now) >= min_score);
+        len_before - self.terms.len()
+    }
+}
+
+/// Extract likely proper nouns: capitalized words that do not start a sentence,
+/// plus words with inner capitals ("GitHub") anywhere
+pub fn extract_proper_nouns(text: &str) -> Vec<String> {
+    let mut found = Vec::new();
+    let mut sentence_start = true;
+
+    for raw in text.split_whitespace() {
+        let word = raw.trim_matches(|c: char| !c.is_alphanumeric());
+        let ends_sentence = raw.ends_with(['.', '!', '?']);
+
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            let rest: Vec<char> = chars.collect();
+            let is_word = word.chars().count() >= 2 && word.chars().all(|c| c.is_alphabetic());
+            let inner_capital = rest.iter().any(|c| c.is_uppercase())
+                && rest.iter().any(|c| c.is_lowercase());
+            let all_caps = rest.iter().all(|c| c.is_uppercase());
+
+            if is_word
+                && first.is_uppercase()
+                && !all_caps
+                && (!sentence_start || inner_capital)
+            {
+                found.push(word.to_string());
+            }
+        }
+
+        sentence_start = ends_sentence;
+    }
+
+    found
+}
+
+/// Current unix time in seconds
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Get the learned terms file path
+fn learned_terms_file_path() -> PathBuf {
+    crate::config::workspace_dir().join("learned_terms.json")
+}
+
+/// Get or initialize the global learned terms instance
+pub fn get_learned_terms() -> &'static RwLock<LearnedTerms> {
+    LEARNED_TERMS.get_or_init(|| RwLock::new(LearnedTerms::load()))
+}
+
+/// Capitalize known terms in a transcription
+pub fn capitalize_learned(text: &str) -> String {
+    get_learned_terms().read().apply(text, unix_now())
+}
+
+/// Learn the capitalized terms the user put in when editing a transcription
+///
+/// Writes happen at most once per `LEARNED_SAVE_INTERVAL`; changes in
+/// between wait for the next edit, a workspace switch or exit (see
+/// `save_learned_terms`).
+pub fn learn_from_edit(original: &str, edited: &str) {
+    let changed = get_learned_terms()
+        .write()
+        .observe_edit(original, edited, unix_now());
+    if !changed {
+        return;
+    }
+    SAVE_PENDING.store(true, Ordering::SeqCst);
+    let due = LAST_SAVE
+        .lock()
+        .is_none_or(|last| last.elapsed() >= LEARNED_SAVE_INTERVAL);
+    if due {
+        save_learned_terms();
+    }
+}
+
+/// Write the learned terms if they changed since the last save
+pub fn save_learned_terms() {
+    if SAVE_PENDING.swap(false, Ordering::SeqCst) {
+        *LAST_SAVE.lock() = Some(Instant::now());
+        if let Err(e) = get_learned_terms().read().save() {
+            tracing::error!("Failed to save learned terms: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 3600;
+
+    #[test]
+    fn test_extract_skips_sentence_starts() {
+        let nouns = extract_proper_nouns("Yesterday I met Łukasz. Then we deployed Kubernetes!");
+        assert_eq!(nouns, vec!["Łukasz", "Kubernetes"]);
+    }
+
+    #[test]
+    fn test_extract_inner_capitals_anywhere() {
+        let nouns = extract_proper_nouns("GitHub is down, check the OK status and NASA.");
+        assert_eq!(nouns, vec!["GitHub"]);
+    }
+
+    #[test]
+    fn test_observe_edit_counts_added_terms_only() {
+        let mut learned = LearnedTerms::default();
+        assert!(learned.observe_edit(
+            "ask lukasz about Kubernetes",
+            "ask Łukasz about Kubernetes",
+            0
+        ));
+        let terms = learned.terms(0);
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].term, "Łukasz");
+
+        // Rewording without new names teaches nothing
+        assert!(!learned.observe_edit("ask Łukasz now", "ask Łukasz later", 0));
+        assert_eq!(learned.terms(0)[0].score, 1.0);
+    }
+
+    #[test]
+    fn test_apply_requires_threshold() {
+        let mut learned = LearnedTerms::default();
+        learned.observe("we use Kubernetes", 0);
+        assert_eq!(learned.apply("deploy to kubernetes", 0), "deploy to kubernetes");
+
+        learned.observe("really, Kubernetes", 0);
+        assert_eq!(learned.apply("deploy to kubernetes", 0), "deploy to Kubernetes");
+    }
+
+    #[test]
+    fn test_decay_halves_score() {
+        let term = LearnedTerm {
+            term: "Rust".to_string(),
+            score: 4.0,
+            last_seen: 0,
+        };
+        let decayed = term.decayed_score(DECAY_HALF_LIFE_SECS);
+        assert!((decayed - 2.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_old_terms_stop_applying() {
+        let mut learned = LearnedTerms::default();
+        learned.observe("with Kubernetes and Kubernetes", 0);
+        assert_eq!(learned.apply("kubernetes", 0), "Kubernetes");
+        assert_eq!(learned.apply("kubernetes", 90 * DAY), "kubernetes");
+    }
+
+    #[test]
+    fn test_remove_and_prune() {
+        let mut learned = LearnedTerms::default();
+        learned.observe("met Alice and Bob", 0);
+        learned.observe("met Alice again", 10 * DAY);
+
+        assert!(learned.remove("BOB"));
+        assert!(!learned.remove("Carol"));
+
+        let removed = learned.prune(5.0, 10 * DAY);
+        assert_eq!(removed, 1);
+        assert!(learned.terms(10 * DAY).is_empty());
+    }
+
+    #[test]
+    fn test_terms_sorted_by_score() {
+        let mut learned = LearnedTerms::default();
+        learned.observe("hi Alice and Bob", 0);
+        learned.observe("hi Bob", 0);
+        let terms = learned.terms(0);
+        assert_eq!(terms[0].term, "Bob");
+        assert_eq!(terms[1].term, "Alice");
+    }
+}
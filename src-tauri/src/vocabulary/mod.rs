@@ -0,0 +1,542 @@
+//! Vocabulary Module
+//!
+//! Custom vocabulary terms and text replacement rules, with a versioned
+//! JSON pack format for sharing domain dictionaries, alert keyword
+//! spotting, and rules learned from the user's corrections.
+
+mod corrections;
+mod keywords;
+mod learned;
+
+pub use corrections::*;
+pub use keywords::*;
+pub use learned::*;
+
+use parking_lot::RwLock;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use thiserror::Error;
+
+/// Format identifier written into every exported rules pack
+pub const RULES_PACK_FORMAT: &str = "gigawhisper-rules";
+
+/// Current rules pack version
+pub const RULES_PACK_VERSION: u32 = 1;
+
+/// Maximum size of a downloaded rules pack (1 MB)
+pub const MAX_PACK_SIZE: usize = 1024 * 1024;
+
+/// Global vocabulary instance
+static VOCABULARY: OnceLock<RwLock<Vocabulary>> = OnceLock::new();
+
+/// Vocabulary errors
+#[derive(Debug, Error)]
+pub enum VocabularyError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid rules pack: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Unsupported rules pack format: {0}")]
+    UnsupportedFormat(String),
+
+    #[error("Unsupported rules pack version {0} (max supported: {RULES_PACK_VERSION})")]
+    UnsupportedVersion(u32),
+
+    #[error("Download failed: {0}")]
+    Download(String),
+}
+
+/// A single text replacement rule applied after transcription
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ReplacementRule {
+    /// Text to look for
+    pub from: String,
+    /// Replacement text
+    pub to: String,
+    /// Match case exactly
+    pub case_sensitive: bool,
+    /// Only match whole words
+    pub whole_word: bool,
+}
+
+impl Default for ReplacementRule {
+    fn default() -> Self {
+        Self {
+            from: String::new(),
+            to: String::new(),
+            case_sensitive: false,
+            whole_word: true,
+        }
+    }
+}
+
+impl ReplacementRule {
+    /// Create a case-insensitive whole-word rule
+    pub fn new(from: impl Into<String>, to: impl Into<String>) -> Self {
+        Self {
+            from: from.into(),
+            to: to.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Key used to detect duplicate rules when merging
+    fn merge_key(&self) -> String {
+        if self.case_sensitive {
+            self.from.clone()
+        } else {
+            self.from.to_lowercase()
+        }
+    }
+
+    /// Apply this rule to a text
+    pub fn apply(&self, text: &str) -> String {
+        if self.from.is_empty() {
+            return text.to_string();
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut rest = text;
+        let mut prev: Option<char> = None;
+
+        while let Some(c) = rest.chars().next() {
+            if let Some(len) = self.match_len(rest) {
+                let next = rest[len..].chars().next();
+                let at_boundary = !self.whole_word
+                    || (prev.is_none_or(|p| !p.is_alphanumeric())
+                        && next.is_none_or(|n| !n.is_alphanumeric()));
+
+                if at_boundary {
+                    output.push_str(&self.to);
+                    prev = rest[..len].chars().last();
+                    rest = &rest[len..];
+                    continue;
+                }
+            }
+
+            output.push(c);
+            prev = Some(c);
+            rest = &rest[c.len_utf8()..];
+        }
+
+        output
+    }
+
+    /// Byte length of the match at the start of `text`, if any
+    fn match_len(&self, text: &str) -> Option<usize> {
+        let mut chars = text.char_indices();
+        for expected in self.from.chars() {
+            let (_, actual) = chars.next()?;
+            let equal = if self.case_sensitive {
+                actual == expected
+            } else {
+                actual.to_lowercase().eq(expected.to_lowercase())
+            };
+            if !equal {
+                return None;
+            }
+        }
+        Some(chars.next().map(|(i, _)| i).unwrap_or(text.len()))
+    }
+}
+
+/// How an imported pack is combined with the existing vocabulary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportMode {
+    /// Keep existing entries, add new ones, imported rules win on conflicts
+    #[default]
+    Merge,
+    /// Replace the whole vocabulary with the pack contents
+    Replace,
+}
+
+/// Result of an import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ImportSummary {
+    pub terms_added: usize,
+    pub rules_added: usize,
+    pub rules_updated: usize,
+}
+
+/// Shareable, versioned rules pack
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulesPack {
+    /// Always `RULES_PACK_FORMAT`
+    pub format: String,
+    /// Pack format version
+    pub version: u32,
+    /// Display name of the pack
+    #[serde(default)]
+    pub name: String,
+    /// Free-form description (domain, author, ...)
+    #[serde(default)]
+    pub description: String,
+    /// Vocabulary terms
+    #[serde(default)]
+    pub terms: Vec<String>,
+    /// Replacement rules
+    #[serde(default)]
+    pub rules: Vec<ReplacementRule>,
+}
+
+impl RulesPack {
+    /// Parse and validate a pack from JSON
+    pub fn from_json(json: &str) -> Result<Self, VocabularyError> {
+        let pack: RulesPack = serde_json::from_str(json)?;
+        if pack.format != RULES_PACK_FORMAT {
+            return Err(VocabularyError::UnsupportedFormat(pack.format));
+        }
+        if pack.version == 0 || pack.version > RULES_PACK_VERSION {
+            return Err(VocabularyError::UnsupportedVersion(pack.version));
+        }
+        Ok(pack)
+    }
+}
+
+/// User vocabulary: custom terms and replacement rules
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq, JsonSchema)]
+#[serde(default)]
+pub struct Vocabulary {
+    /// Domain terms used to bias recognition
+    pub terms: Vec<String>,
+    /// Replacement rules applied in order after transcription
+    pub rules: Vec<ReplacementRule>,
+}
+
+impl Vocabulary {
+    /// Load vocabulary from disk
+    pub fn load() -> Self {
+        let path = vocabulary_file_path();
+        if path.exists() {
+            match std::fs::read_to_string(&path) {
+                Ok(content) => match serde_json::from_str(&content) {
+                    Ok(vocabulary) => return vocabulary,
+                    Err(e) => tracing::warn!("Failed to parse vocabulary file: {}", e),
+                },
+                Err(e) => tracing::warn!("Failed to read vocabulary file: {}", e),
+            }
+        }
+        Self::default()
+    }
+
+    /// Save vocabulary to disk
+    pub fn save(&self) -> Result<(), VocabularyError> {
+        let path = vocabulary_file_path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        tracing::debug!("Vocabulary saved to {:?}", path);
+        Ok(())
+    }
+
+    /// Apply all replacement rules to a text
+    pub fn apply(&self, text: &str) -> String {
+        self.rules
+            .iter()
+            .fold(text.to_string(), |acc, rule| rule.apply(&acc))
+    }
+
+    /// Build a shareable pack from this vocabulary
+    pub fn to_pack(&self, name: &str, description: &str) -> RulesPack {
+        RulesPack {
+            format: RULES_PACK_FORMAT.to_string(),
+            version: RULES_PACK_VERSION,
+            name: name.to_string(),
+            description: description.to_string(),
+            terms: self.terms.clone(),
+            rules: self.rules.clone(),
+        }
+    }
+
+    /// Import a pack into this vocabulary
+    pub fn import(&mut self, pack: RulesPack, mode: ImportMode) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+
+        if mode == ImportMode::Replace {
+            self.terms.clear();
+            self.rules.clear();
+        }
+
+        for term in pack.terms {
+            let term = term.trim().to_string();
+            if term.is_empty() || self.terms.iter().any(|t| t.eq_ignore_ascii_case(&term)) {
+                continue;
+            }
+            self.terms.push(term);
+            summary.terms_added += 1;
+        }
+
+        for rule in pack.rules {
+            if rule.from.is_empty() {
+                continue;
+            }
+            let key = rule.merge_key();
+            match self.rules.iter_mut().find(|r| r.merge_key() == key) {
+                Some(existing) => {
+                    if *existing != rule {
+                        *existing = rule;
+                        summary.rules_updated += 1;
+                    }
+                }
+                None => {
+                    self.rules.push(rule);
+                    summary.rules_added += 1;
+                }
+            }
+        }
+
+        summary
+    }
+
+    /// Merge rules and terms learned from corrections
+    ///
+    /// Returns the rules that were added or changed and the terms added.
+    pub fn learn(
+        &mut self,
+        rules: Vec<ReplacementRule>,
+        terms: Vec<String>,
+    ) -> (Vec<ReplacementRule>, Vec<String>) {
+        let terms_before = self.terms.len();
+        let rules_before = self.rules.clone();
+        let pack = RulesPack {
+            format: RULES_PACK_FORMAT.to_string(),
+            version: RULES_PACK_VERSION,
+            name: "Corrections".to_string(),
+            description: String::new(),
+            terms,
+            rules: rules.clone(),
+        };
+        self.import(pack, ImportMode::Merge);
+
+        let changed = rules
+            .into_iter()
+            .filter(|rule| !rules_before.contains(rule))
+            .collect();
+        (changed, self.terms[terms_before..].to_vec())
+    }
+}
+
+/// Get the vocabulary file path
+fn vocabulary_file_path() -> PathBuf {
+    crate::config::workspace_dir().join("vocabulary.json")
+}
+
+/// Get or initialize the global vocabulary instance
+pub fn get_vocabulary() -> &'static RwLock<Vocabulary> {
+    VOCABULARY.get_or_init(|| RwLock::new(Vocabulary::load()))
+}
+
+/// Reload the vocabulary, learned terms and corrections from the active
+/// workspace
+pub fn reload_vocabulary() {
+    *get_vocabulary().write() = Vocabulary::load();
+    *get_learned_terms().write() = LearnedTerms::load();
+    *get_corrections().write() = CorrectionStore::load();
+}
+
+/// Apply the user's replacement rules to a transcription
+pub fn apply_rules(text: &str) -> String {
+    get_vocabulary().read().apply(text)
+}
+
+/// Export the current vocabulary as a rules pack file
+pub fn export_to_file(path: &Path, name: &str, description: &str) -> Result<(), VocabularyError> {
+    let pack = get_vocabulary().read().to_pack(name, description);
+    std::fs::write(path, serde_json::to_string_pretty(&pack)?)?;
+    tracing::info!("Vocabulary exported to {:?}", path);
+    Ok(())
+}
+
+/// Import a rules pack JSON string into the global vocabulary and persist it
+pub fn import_pack_json(json: &str, mode: ImportMode) -> Result<ImportSummary, VocabularyError> {
+    let pack = RulesPack::from_json(json)?;
+    let mut vocabulary = get_vocabulary().write();
+    let summary = vocabulary.import(pack, mode);
+    vocabulary.save()?;
+    tracing::info!(
+        "Rules pack imported: {} terms, {} rules added, {} updated",
+        summary.terms_added,
+        summary.rules_added,
+        summary.rules_updated
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pack(terms: &[&str], rules: Vec<ReplacementRule>) -> RulesPack {
+        RulesPack {
+            format: RULES_PACK_FORMAT.to_string(),
+            version: RULES_PACK_VERSION,
+            name: "test".to_string(),
+            description: String::new(),
+            terms: terms.iter().map(|t| t.to_string()).collect(),
+            rules,
+        }
+    }
+
+    #[test]
+    fn test_rule_whole_word_case_insensitive() {
+        let rule = ReplacementRule::new("cube cuddle", "kubectl");
+        assert_eq!(rule.apply("Run Cube Cuddle apply"), "Run kubectl apply");
+        assert_eq!(rule.apply("cube cuddles"), "cube cuddles");
+    }
+
+    #[test]
+    fn test_rule_substring_and_case_sensitive() {
+        let rule = ReplacementRule {
+            from: "js".to_string(),
+            to: "JS".to_string(),
+            case_sensitive: true,
+            whole_word: false,
+        };
+        assert_eq!(rule.apply("nodejs and Js"), "nodeJS and Js");
+    }
+
+    #[test]
+    fn test_rule_unicode() {
+        let rule = ReplacementRule::new("lukasz", "Łukasz");
+        assert_eq!(rule.apply("hi lukasz, ça va"), "hi Łukasz, ça va");
+        let rule = ReplacementRule::new("ÉCOLE", "école");
+        assert_eq!(rule.apply("l'École"), "l'école");
+    }
+
+    #[test]
+    fn test_rule_empty_from_is_noop() {
+        let rule = ReplacementRule::new("", "x");
+        assert_eq!(rule.apply("text"), "text");
+    }
+
+    #[test]
+    fn test_vocabulary_applies_rules_in_order() {
+        let vocabulary = Vocabulary {
+            terms: vec![],
+            rules: vec![
+                ReplacementRule::new("gigawhisper", "GigaWhisper"),
+                ReplacementRule::new("GigaWhisper app", "GigaWhisper"),
+            ],
+        };
+        assert_eq!(vocabulary.apply("open gigawhisper app"), "open GigaWhisper");
+    }
+
+    #[test]
+    fn test_import_merge_semantics() {
+        let mut vocabulary = Vocabulary {
+            terms: vec!["Kubernetes".to_string()],
+            rules: vec![ReplacementRule::new("k eights", "k8s")],
+        };
+
+        let summary = vocabulary.import(
+            pack(
+                &["kubernetes", "Terraform", " "],
+                vec![
+                    ReplacementRule::new("K Eights", "K8s"),
+                    ReplacementRule::new("tee eff", "tf"),
+                ],
+            ),
+            ImportMode::Merge,
+        );
+
+        assert_eq!(
+            summary,
+            ImportSummary {
+                terms_added: 1,
+                rules_added: 1,
+                rules_updated: 1
+            }
+        );
+        assert_eq!(vocabulary.terms, vec!["Kubernetes", "Terraform"]);
+        assert_eq!(vocabulary.rules.len(), 2);
+        assert_eq!(vocabulary.rules[0].to, "K8s");
+    }
+
+    #[test]
+    fn test_import_replace_semantics() {
+        let mut vocabulary = Vocabulary {
+            terms: vec!["Old".to_string()],
+            rules: vec![ReplacementRule::new("a", "b")],
+        };
+        vocabulary.import(pack(&["New"], vec![]), ImportMode::Replace);
+        assert_eq!(vocabulary.terms, vec!["New"]);
+        assert!(vocabulary.rules.is_empty());
+    }
+
+    #[test]
+    fn test_learn_reports_changes() {
+        let mut vocabulary = Vocabulary {
+            terms: vec!["GitHub".to_string()],
+            rules: vec![
+                ReplacementRule::new("k eights", "k8s"),
+                ReplacementRule::new("cube cuddle", "kube cuddle"),
+            ],
+        };
+        let (rules, terms) = vocabulary.learn(
+            vec![
+                ReplacementRule::new("git hub", "GitHub"),
+                ReplacementRule::new("k eights", "k8s"),
+                ReplacementRule::new("cube cuddle", "kubectl"),
+            ],
+            vec!["GitHub".to_string(), "Azure".to_string()],
+        );
+
+        assert_eq!(
+            rules,
+            vec![
+                ReplacementRule::new("git hub", "GitHub"),
+                ReplacementRule::new("cube cuddle", "kubectl"),
+            ]
+        );
+        assert_eq!(terms, vec!["Azure"]);
+        assert_eq!(vocabulary.apply("push to git hub"), "push to GitHub");
+    }
+
+    #[test]
+    fn test_pack_roundtrip() {
+        let vocabulary = Vocabulary {
+            terms: vec!["Rust".to_string()],
+            rules: vec![ReplacementRule::new("rust see", "rustc")],
+        };
+        let json = serde_json::to_string(&vocabulary.to_pack("Dev", "Programming terms")).unwrap();
+        let parsed = RulesPack::from_json(&json).unwrap();
+        assert_eq!(parsed.name, "Dev");
+        assert_eq!(parsed.terms, vocabulary.terms);
+        assert_eq!(parsed.rules, vocabulary.rules);
+    }
+
+    #[test]
+    fn test_pack_rejects_unknown_format_and_version() {
+        let json = r#"{"format": "other", "version": 1}"#;
+        assert!(matches!(
+            RulesPack::from_json(json),
+            Err(VocabularyError::UnsupportedFormat(_))
+        ));
+
+        let json = format!(r#"{{"format": "{}", "version": 99}}"#, RULES_PACK_FORMAT);
+        assert!(matches!(
+            RulesPack::from_json(&json),
+            Err(VocabularyError::UnsupportedVersion(99))
+        ));
+
+        assert!(matches!(
+            RulesPack::from_json("not json"),
+            Err(VocabularyError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_pack_minimal_fields() {
+        let json = format!(r#"{{"format": "{}", "version": 1}}"#, RULES_PACK_FORMAT);
+        let pack = RulesPack::from_json(&json).unwrap();
+        assert!(pack.terms.is_empty());
+        assert!(pack.rules.is_empty());
+    }
+}
@@ -166,6 +166,8 @@ impl TranscriptionProvider for MockTranscriptionProvider {
                 language: Some("en".to_string()),
                 duration_ms: self.delay_ms.unwrap_or(50),
                 provider: self.name.to_string(),
+                segments: Vec::new(),
+                model: None,
             })
         }
     }
@@ -985,6 +987,7 @@ mod language_config_tests {
         let config = TranscriptionConfig {
             language: "auto".to_string(),
             translate: false,
+            ..Default::default()
         };
 
         let result = orchestrator.transcribe(&audio, &config).await;
@@ -1002,6 +1005,7 @@ mod language_config_tests {
             let config = TranscriptionConfig {
                 language: lang.to_string(),
                 translate: false,
+                ..Default::default()
             };
 
             let result = orchestrator.transcribe(&audio, &config).await;
@@ -1019,6 +1023,7 @@ mod language_config_tests {
         let config = TranscriptionConfig {
             language: "fr".to_string(),
             translate: true, // Translate French to English
+            ..Default::default()
         };
 
         let result = orchestrator.transcribe(&audio, &config).await;
@@ -0,0 +1,2074 @@
+// Generated from the Rust command and event types by
+// `UPDATE_BINDINGS=1 cargo test bindings`. Do not edit by hand.
+
+import { invoke } from '@tauri-apps/api/core';
+import { listen, type UnlistenFn } from '@tauri-apps/api/event';
+
+/** User activity hinting that dictation is about to start */
+export type ActivitySignal = 'modifier-held' | 'text-field-focused';
+
+/** Keyword alert settings */
+export type AlertSettings = {
+  /** Words or phrases to watch for (case-insensitive, whole words) */
+  keywords?: string[];
+  /** Show a notification when a keyword is transcribed */
+  notify?: boolean;
+};
+
+/** Client as shown in the UI, without its token hash */
+export type ApiClientInfo = {
+  id: string;
+  name: string;
+  scopes: ApiScope[];
+  created_at: string;
+  last_used_at?: string | null;
+};
+
+/** What a client may do */
+export type ApiScope = 'read-history' | 'trigger-recording' | 'read-audio';
+
+/**
+ * Local API server
+ *
+ * Listens on 127.0.0.1 only; every call needs the token of a registered API client with the matching scope.
+ */
+export type ApiServerSettings = {
+  /** Run the server */
+  enabled?: boolean;
+  port?: number;
+};
+
+/** DTO for audio device */
+export type AudioDeviceDto = {
+  id: string;
+  name: string;
+  is_default: boolean;
+};
+
+/** Output format of a transcoded recording */
+export type AudioFormat = 'wav' | 'opus' | 'mp3';
+
+/** Audio level of one metering window, as linear amplitudes from 0.0 to 1.0 */
+export type AudioLevel = {
+  /** Root mean square of the window */
+  rms: number;
+  /** Largest absolute sample of the window */
+  peak: number;
+};
+
+/** Audio input settings */
+export type AudioSettings = {
+  /** Input device ID (None = default) */
+  input_device?: string | null;
+  /** Voice Activity Detection settings */
+  vad?: VadSettings;
+  /** Processing stages between capture and text, in order */
+  pipeline?: PipelineStageSettings[];
+  /** Drop speech from voices other than the enrolled one */
+  speaker_filter?: SpeakerFilterSettings;
+  /** Device buffer mode (low latency helps streaming) */
+  capture_mode?: CaptureMode;
+  /** What is recorded: the input device or the system audio output */
+  source?: AudioSource;
+  /** Algorithm used by the noise suppression stage */
+  noise_suppressor?: NoiseSuppressor;
+};
+
+/** What a recording captures */
+export type AudioSource = 'microphone' | 'system-audio';
+
+/** One audited call */
+export type AuditEntry = {
+  timestamp: string;
+  /** What was called (e.g. `start_recording`, `client.create`) */
+  action: string;
+  /** Calling client (None when the token was rejected or for local changes) */
+  client_id?: string | null;
+  client_name?: string | null;
+  /** Scope the call required */
+  scope?: ApiScope | null;
+  allowed: boolean;
+  /** Why the call was refused, or what changed */
+  detail?: string | null;
+};
+
+/** Snapshot of a running background task */
+export type BackgroundTask = {
+  id: number;
+  name: string;
+  kind: TaskKind;
+  running_ms: number;
+};
+
+/** Progress of a batch after a file finished */
+export type BatchProgress = {
+  batch_id: string;
+  /** Position of the file in the batch (0-based) */
+  index: number;
+  total: number;
+  file: FileJob;
+};
+
+/** A batch of files from one folder */
+export type BatchRun = {
+  id: string;
+  folder: string;
+  state: BatchState;
+  files: FileJob[];
+  started_at: string;
+  finished_at?: string | null;
+};
+
+/** State of a batch */
+export type BatchState = 'running' | 'paused' | 'cancelled' | 'completed';
+
+/** Summary of a finished batch */
+export type BatchSummary = {
+  id: string;
+  folder: string;
+  state: BatchState;
+  total: number;
+  done: number;
+  failed: number;
+  skipped: number;
+  /** Time spent on files, excluding pauses */
+  elapsed_ms: number;
+  /** Files that failed, with their errors */
+  failures: FileJob[];
+};
+
+/** Build variant information */
+export type BuildVariantInfo = {
+  variant: string;
+  displayName: string;
+};
+
+/** Memory held by an in-memory cache */
+export type CacheUsage = {
+  name: string;
+  entries: number;
+  /** Approximate size (bytes) */
+  bytes: number;
+};
+
+/** Calibration analysis result */
+export type CalibrationReport = {
+  /** Ambient noise level (90th percentile of silence windows, dBFS) */
+  noise_floor_db: number;
+  /** Typical speech level (75th percentile of speech windows, dBFS) */
+  speech_level_db: number;
+  /** Speech to noise separation in dB */
+  snr_db: number;
+  /** Recommended VAD aggressiveness (0-3) */
+  aggressiveness: number;
+  /** Recommended minimum speech duration in ms */
+  min_speech_duration_ms: number;
+  /** Recommended energy gate: recordings whose loudest window stays below are dropped */
+  energy_threshold_db: number;
+};
+
+/** Capture listing entry */
+export type CaptureInfo = {
+  path: string;
+  timestamp: string;
+  profile?: string | null;
+  text?: string | null;
+  error?: string | null;
+};
+
+/** How the input device buffers audio */
+export type CaptureMode = 'shared' | 'low-latency';
+
+/** Buffer mode a device stream was opened with, for diagnostics */
+export type CaptureModeReport = {
+  /** Mode asked for in the settings */
+  requested: CaptureMode;
+  /** Mode the stream runs in */
+  active: CaptureMode;
+  /** Device buffer size, when fixed */
+  buffer_frames?: number | null;
+  /** Device buffer latency, when fixed */
+  buffer_ms?: number | null;
+  /** Why low-latency mode fell back to shared mode */
+  fallback_reason?: string | null;
+};
+
+/** How live partial transcriptions are chunked */
+export type ChunkingStats = {
+  /** New audio between partial transcriptions now (ms) */
+  interval_ms: number;
+  /** Whether the interval follows the partials' real-time factor */
+  adaptive: boolean;
+  /** Processing time of the last partial over the new audio it covered */
+  last_real_time_factor: number;
+  /** Moving average of that factor */
+  avg_real_time_factor: number;
+  /** Partials transcribed in the recording */
+  partials: number;
+  /** Times the interval grew because partials fell behind */
+  increases: number;
+  /** Times the interval shrank because partials had headroom */
+  decreases: number;
+};
+
+/** Language model service used for transcript cleanup */
+export type CleanupProvider = 'groq' | 'openai' | 'ollama';
+
+/**
+ * Transcript cleanup by a language model
+ *
+ * The transcript is sent with the prompt to an OpenAI-compatible chat completions endpoint; the raw transcript is used when the request fails or times out.
+ */
+export type CleanupSettings = {
+  /** Rewrite each transcript before output */
+  enabled?: boolean;
+  provider?: CleanupProvider;
+  /** Server URL (empty = the provider's default). A custom URL must use https unless it is on this computer, and is sent its own token rather than the provider's API key */
+  base_url?: string;
+  /** Model name (empty = the provider's default) */
+  model?: string;
+  /** Instructions sent with each transcript */
+  prompt?: string;
+  /** Seconds to wait for the rewrite before using the raw transcript */
+  timeout_seconds?: number;
+  /** Whether an OpenAI API key is configured (actual key stored in the credential manager) */
+  openai_key_configured?: boolean;
+  /** Whether a token for the custom `base_url` is configured (actual token stored in the credential manager) */
+  endpoint_token_configured?: boolean;
+};
+
+/** How the clipboard is written */
+export type ClipboardBackend = 'native' | 'wl-clipboard';
+
+/** Clipboard payload format */
+export type ClipboardFormat = 'plain-text' | 'html';
+
+/** DTO for history entry */
+export type ClipboardHistoryEntry = {
+  id: string;
+  text: string;
+  timestamp: number;
+  duration_ms: number;
+  provider: string;
+};
+
+/** Why a configured shortcut can't be used */
+export type ConflictReason =
+  | { type: 'invalid'; error: string }
+  | { type: 'duplicate'; other: string }
+  | { type: 'unavailable'; error: string };
+
+/** What a correction taught the vocabulary */
+export type CorrectionOutcome = {
+  /** Fixes found in the correction */
+  fixes: number;
+  /** Rules added or updated in the vocabulary */
+  rules: ReplacementRule[];
+  /** Terms added to the vocabulary */
+  terms: string[];
+};
+
+/** CPU information for optimization */
+export type CpuInfo = {
+  /** Number of physical CPU cores */
+  physical_cores: number;
+  /** Number of logical CPU cores (including hyperthreading) */
+  logical_cores: number;
+  /** Recommended number of threads for whisper.cpp */
+  recommended_threads: number;
+  /** Whether the CPU supports AVX2 */
+  has_avx2: boolean;
+  /** Whether the CPU supports AVX512 */
+  has_avx512: boolean;
+};
+
+/**
+ * Self-hosted OpenAI-compatible server settings
+ *
+ * For servers such as faster-whisper-server, LocalAI or Speaches that expose `/v1/audio/transcriptions`.
+ */
+export type CustomEndpointSettings = {
+  /** Server URL, e.g. "http://localhost:8000" (with or without "/v1") */
+  base_url?: string;
+  /** Whether a bearer token is configured (actual token stored in the credential manager) */
+  token_configured?: boolean;
+  /** Model name sent with each request */
+  model?: string;
+  /** Request timeout in seconds (default: 60) */
+  timeout_seconds?: number;
+};
+
+/** A model imported by the user */
+export type CustomModel = {
+  /** Identifier settings refer to, also part of the file name */
+  id: string;
+  /** Name shown in the model list */
+  name: string;
+  /** File name in the user's models directory */
+  filename: string;
+  size_bytes: number;
+  /** Whether the model transcribes other languages than English */
+  multilingual: boolean;
+  /** Unix timestamp of the import (seconds) */
+  imported_at: number;
+};
+
+/** Separator between the integer and fractional parts of numbers */
+export type DecimalSeparator = 'point' | 'comma' | 'keep';
+
+/** Deepgram streaming API settings */
+export type DeepgramSettings = {
+  /** Whether an API key is configured (actual key stored in the credential manager) */
+  api_key_configured?: boolean;
+  /** Model identifier */
+  model?: string;
+  /** Seconds to wait for the final result (default: 30) */
+  timeout_seconds?: number;
+};
+
+/** Input device change during a recording (see `AudioCapture::subscribe_device_events`) */
+export type DeviceEvent =
+  | { type: 'disconnected'; device: string }
+  | { type: 'reconnected'; device: string };
+
+/** Result of an input device test */
+export type DeviceTestReport = {
+  /** Largest absolute sample (0.0 to 1.0) */
+  peak: number;
+  /** RMS level of the whole recording (0.0 to 1.0) */
+  rms: number;
+  /** Peak level in dBFS */
+  peak_db: number;
+  /** RMS level in dBFS */
+  rms_db: number;
+  /** The input reached full scale */
+  clipping: boolean;
+  /** Nothing above the noise was heard; the device may be muted */
+  silent: boolean;
+  /** Peak of each of `WAVEFORM_POINTS` equal slices of the recording */
+  waveform: number[];
+  /** Length of the recording in milliseconds */
+  duration_ms: number;
+  /** Whether the recording was played back */
+  played_back: boolean;
+};
+
+/** Dictation box state */
+export type DictationBox = {
+  open: boolean;
+  /** Current (possibly user-edited) text */
+  text: string;
+};
+
+/** Desktop session type */
+export type DisplayServer = 'windows' | 'mac-os' | 'x11' | 'wayland' | 'unknown';
+
+/** First point where two results differ */
+export type Divergence = {
+  /** Character index of the first difference */
+  position: number;
+  /** Original text around the difference */
+  original: string;
+  /** Replayed text around the difference */
+  replayed: string;
+};
+
+/** Download progress information */
+export type DownloadProgress = {
+  downloaded: number;
+  total?: number | null;
+  percent?: number | null;
+};
+
+/** Errors with known remedies */
+export type ErrorCode =
+  | 'mic-permission'
+  | 'no-microphone'
+  | 'model-missing'
+  | 'keyring-locked'
+  | 'gpu-init-failed'
+  | 'groq-unauthorized'
+  | 'groq-rate-limited'
+  | 'deepgram-unauthorized'
+  | 'deepgram-rate-limited'
+  | 'endpoint-unauthorized'
+  | 'endpoint-rate-limited';
+
+/** What went wrong and how to fix it */
+export type ErrorGuidance = {
+  code: ErrorCode;
+  title: string;
+  message: string;
+  action?: GuidanceAction | null;
+};
+
+/** Delivery counters for one event */
+export type EventStats = {
+  event: string;
+  /** Payloads delivered to the frontend */
+  emitted: number;
+  /** Payloads replaced by a newer one before delivery */
+  coalesced: number;
+};
+
+/** A queued audio file */
+export type FileJob = {
+  id: string;
+  path: string;
+  /** File name (path relative to the batch folder for batches), for display */
+  name: string;
+  status: FileJobStatus;
+  text?: string | null;
+  error?: string | null;
+  /** Time spent decoding and transcribing */
+  elapsed_ms: number;
+};
+
+/** Where a queued file is in the queue */
+export type FileJobStatus = 'queued' | 'processing' | 'done' | 'failed' | 'skipped';
+
+/** Gamepad button usable as a record trigger */
+export type GamepadTrigger =
+  | 'south'
+  | 'east'
+  | 'north'
+  | 'west'
+  | 'left_bumper'
+  | 'right_bumper'
+  | 'left_trigger'
+  | 'right_trigger'
+  | 'select'
+  | 'start'
+  | 'any';
+
+/**
+ * Record shortcut gestures
+ *
+ * When enabled, a tap toggles recording, a double-tap pastes the last transcription again and a long press records push-to-talk style, whatever the recording mode. A tap only acts once the double-tap window has passed.
+ */
+export type GestureSettings = {
+  enabled?: boolean;
+  /** Longest gap between the taps of a double-tap, in milliseconds */
+  double_tap_ms?: number;
+  /** Shortest hold that counts as a long press, in milliseconds */
+  long_press_ms?: number;
+};
+
+/** GPU backend selection for whisper acceleration */
+export type GpuBackend = 'cpu' | 'vulkan' | 'cuda';
+
+/** GPU acceleration information */
+export type GpuInfo = {
+  /** Whether GPU acceleration is available in this build */
+  available: boolean;
+  /** Name of the GPU backend (CUDA, Vulkan, or None) */
+  backend: string;
+  /** Whether GPU is currently enabled in settings */
+  enabled: boolean;
+};
+
+/** Groq API settings */
+export type GroqSettings = {
+  /** Whether an API key is configured (actual key stored in Windows Credential Manager) */
+  api_key_configured?: boolean;
+  /** Model identifier */
+  model?: string;
+  /** Request timeout in seconds (default: 30) */
+  timeout_seconds?: number;
+};
+
+/** A button that takes the user to the fix */
+export type GuidanceAction = {
+  label: string;
+  link: GuidanceLink;
+};
+
+/** Where the guidance action leads */
+export type GuidanceLink = { kind: 'settings'; tab: string } | { kind: 'system'; url: string };
+
+/** Environment health summary */
+export type HealthReport = {
+  version: string;
+  build_variant: string;
+  platform: PlatformCapabilities;
+  /** Throttled event delivery counters */
+  events: EventStats[];
+  /** Buffer mode of the last microphone stream (None before the first recording) */
+  capture?: CaptureModeReport | null;
+  /** Where settings, history and models are stored */
+  storage: StorageReport;
+  /** Problems the user can act on */
+  warnings: string[];
+};
+
+/** Audio saved with history entries */
+export type HistoryAudio = 'full' | 'trimmed' | 'speech';
+
+/**
+ * Encoding of audio saved with history entries
+ *
+ * FLAC and Opus are encoded with `ffmpeg` in the background after the text is delivered; recordings stay WAV, with a notification, when it is not installed.
+ */
+export type HistoryAudioCodec = 'wav' | 'flac' | 'opus';
+
+/** A single transcription entry in history */
+export type HistoryEntry = {
+  /** Unique identifier */
+  id: string;
+  /** The transcribed text */
+  text: string;
+  /** Timestamp when transcription was created (ISO 8601) */
+  timestamp: string;
+  /** Duration of the recording in milliseconds */
+  duration_ms: number;
+  /** Provider used (whisper.cpp or groq) */
+  provider: string;
+  /** Language detected/used */
+  language?: string | null;
+  /** Path to the audio file (optional, for playback) */
+  audio_path?: string | null;
+  /** Peak amplitude per segment of the audio (0-255), for waveform thumbnails */
+  waveform?: number[];
+  /** Thumbnail of the window the text was sent to (optional, opt-in) */
+  screenshot_path?: string | null;
+  /** Alert keywords found in the text */
+  alerts?: string[];
+  /** Title, generated from the first sentence unless set by the user */
+  title?: string;
+  /** Free-form note attached by the user */
+  note?: string | null;
+  /** Length of the recording before silence was trimmed from the saved audio */
+  recording_ms?: number | null;
+  /** Timed segments of the transcription, for subtitles */
+  segments?: TranscriptSegment[];
+  /** English translation of the text, kept in language learning mode */
+  translation?: string | null;
+  /** User-defined tags, in the order they were added */
+  tags?: string[];
+  /** What was recorded (None for transcribed files and older entries) */
+  source?: AudioSource | null;
+};
+
+/** Export file format */
+export type HistoryExportFormat = 'json' | 'csv' | 'markdown';
+
+/** Filters of a history search; unset filters match every entry */
+export type HistoryQuery = {
+  /** Words that must all appear in the text, title, note or translation (any case) */
+  text?: string | null;
+  /** Earliest timestamp (ISO 8601, inclusive) */
+  from?: string | null;
+  /** Latest timestamp (ISO 8601, inclusive; a date alone covers the whole day) */
+  to?: string | null;
+  /** Provider name ("whisper.cpp", "groq", ...) */
+  provider?: string | null;
+  /** Language code, region subtags ignored */
+  language?: string | null;
+  /** Tags that must all be on the entry (any case) */
+  tags?: string[];
+  /** Matching entries to skip, for paging */
+  offset?: number;
+  /** Maximum number of entries returned */
+  limit?: number | null;
+};
+
+/** A page of history search results, newest first */
+export type HistorySearchResult = {
+  entries: HistoryEntry[];
+  /** Number of matching entries, including those outside the page */
+  total: number;
+};
+
+/** An executable run at a lifecycle point */
+export type Hook = {
+  event: HookEvent;
+  /** Executable to run (not interpreted by a shell) */
+  command: string;
+  args?: string[];
+};
+
+/** Lifecycle point a hook runs at */
+export type HookEvent = 'recording-started' | 'transcription-completed' | 'output-injected';
+
+/** Automation hook settings */
+export type HookSettings = {
+  /** Run the configured hooks */
+  enabled?: boolean;
+  /** Seconds a hook may run before it is killed */
+  timeout_secs?: number;
+  hooks?: Hook[];
+};
+
+/** How an imported pack is combined with the existing vocabulary */
+export type ImportMode = 'merge' | 'replace';
+
+/** Result of an import */
+export type ImportSummary = {
+  terms_added: number;
+  rules_added: number;
+  rules_updated: number;
+};
+
+/** An incognito dictation as shown to the frontend */
+export type IncognitoEntry = {
+  id: string;
+  text: string;
+  /** When the dictation was made (ISO 8601) */
+  timestamp: string;
+  /** Seconds until the dictation is wiped */
+  expires_in_secs: number;
+};
+
+/** Recording indicator position */
+export type IndicatorPosition = 'cursor' | 'center' | 'corner';
+
+/** Indicator state as seen by the window */
+export type IndicatorSnapshot = {
+  visible: boolean;
+  state?: IndicatorState | null;
+  /** Time spent in the current state */
+  elapsed_ms: number;
+  /** The recording is incognito (shown distinctly) */
+  incognito: boolean;
+};
+
+/** What the indicator displays */
+export type IndicatorState = 'recording' | 'processing';
+
+/** Injection audit log settings */
+export type InjectionAuditSettings = {
+  /** Record each injection (time, target app, method, character count) */
+  enabled?: boolean;
+  /** Days records are kept */
+  retention_days?: number;
+};
+
+/** How text reached its destination */
+export type InjectionMethod =
+  | 'paste'
+  | 'ui-automation'
+  | 'typing'
+  | 'clipboard-only'
+  | 'primary-selection'
+  | 'popup'
+  | 'dictation-box';
+
+/** One injection */
+export type InjectionRecord = {
+  timestamp: string;
+  /** Process name of the target window */
+  app?: string | null;
+  /** How the text was delivered (None when delivery failed) */
+  method?: InjectionMethod | null;
+  verification?: Verification | null;
+  /** Number of characters delivered */
+  chars: number;
+  success: boolean;
+  error?: string | null;
+};
+
+/** A problem found by the integrity check */
+export type IntegrityIssue =
+  | { kind: 'missing-audio'; id: string; path: string }
+  | { kind: 'corrupted-audio'; id: string; path: string }
+  | { kind: 'missing-screenshot'; id: string; path: string }
+  | { kind: 'orphaned-file'; path: string; size_bytes: number }
+  | { kind: 'corrupted-row'; index?: number | null; error: string };
+
+/** Result of an integrity check */
+export type IntegrityReport = {
+  entries_checked: number;
+  files_checked: number;
+  issues: IntegrityIssue[];
+  /** Whether the issues were repaired */
+  repaired: boolean;
+  /** Space reclaimed by deleting orphaned files */
+  freed_bytes: number;
+};
+
+/** Newly issued token, returned once */
+export type IssuedToken = {
+  client: ApiClientInfo;
+  token: string;
+};
+
+/** Payload of the `transcription:alert` event */
+export type KeywordAlert = {
+  /** Alert keywords found, in configured order */
+  keywords: string[];
+  text: string;
+};
+
+/** Latency of recent dictations against the target */
+export type LatencyReport = {
+  /** Target stop-to-paste latency (ms, 0 = no target) */
+  target_ms: number;
+  /** Dictations measured */
+  samples: number;
+  p50_ms: number;
+  p95_ms: number;
+  /** Share of dictations within the target (0.0-1.0) */
+  within_target: number;
+  /** Dictations in a row that missed the target */
+  consecutive_misses: number;
+  /** Average time per step of the dictations that missed the target */
+  miss_timings: StageTimings;
+  remediations: Remediation[];
+};
+
+/** Latency objective for dictations */
+export type LatencySettings = {
+  /** Target time from stopping a recording to delivering its text (ms, 0 = no target) */
+  target_ms?: number;
+  /** Consecutive dictations over the target before raising an alert */
+  alert_after?: number;
+  /** Show a notification when the target is repeatedly missed */
+  notify?: boolean;
+};
+
+/** A capitalized term seen in transcriptions */
+export type LearnedTerm = {
+  /** Term with its preferred capitalization */
+  term: string;
+  /** Occurrence score at `last_seen` (decays afterwards) */
+  score: number;
+  /** Unix timestamp (seconds) of the last occurrence */
+  last_seen: number;
+};
+
+/** Local whisper.cpp settings */
+export type LocalTranscriptionSettings = {
+  /** Whisper model size */
+  model?: WhisperModel;
+  /** Model quantization level (F16, Q8_0, Q5_1) */
+  quantization?: ModelQuantization;
+  /** Download the requested quantization when only another one is on disk (that one is used meanwhile) */
+  download_missing_quantization?: boolean;
+  /** Imported model used instead of `model` (id from the custom model list) */
+  custom_model?: string | null;
+  /** Candidates kept while decoding (1 = greedy, faster; more = beam search, more accurate) */
+  beam_size?: number;
+  /** Sampling temperature of the first decoding pass (0 = always the most likely token; whisper.cpp raises it on its own when a pass fails) */
+  temperature?: number;
+  /** No-speech probability above which a segment is dropped as silence */
+  no_speech_threshold?: number;
+  /** Number of CPU threads (0 = auto-detect optimal) */
+  threads?: number;
+  /** Enable GPU acceleration */
+  gpu_enabled?: boolean;
+  /** GPU backend to use when gpu_enabled is true */
+  gpu_backend?: GpuBackend;
+};
+
+/** Summary of performance metrics */
+export type MetricsSummary = {
+  /** Number of transcriptions recorded */
+  transcription_count: number;
+  /** Session duration in ms */
+  session_duration_ms: number;
+  /** Average processing time per transcription (ms) */
+  avg_processing_ms: number;
+  /** Average audio duration per transcription (ms) */
+  avg_audio_ms: number;
+  /** Average real-time factor (< 1.0 = faster than real-time) */
+  avg_real_time_factor: number;
+  /** Fastest transcription time (ms) */
+  fastest_ms: number;
+  /** Slowest transcription time (ms) */
+  slowest_ms: number;
+  /** 95th percentile processing time (ms) */
+  p95_ms: number;
+  /** Total audio processed in session (ms) */
+  total_audio_processed_ms: number;
+  /** Total processing time in session (ms) */
+  total_processing_time_ms: number;
+  /** Model load time (ms) */
+  model_load_time_ms?: number | null;
+  /** Estimated model memory usage (bytes) */
+  estimated_model_memory_bytes?: number | null;
+  /** Total audio saved by VAD filtering (ms) */
+  vad_savings_ms: number;
+  /** Average time spent in each step */
+  avg_timings: StageTimings;
+  /** Chunking of the latest live partials, if any ran */
+  chunking?: ChunkingStats | null;
+  /** Effect of warm-ups, once one ran */
+  prefetch?: PrefetchStats | null;
+};
+
+/** Output document format */
+export type MinutesFormat = 'docx' | 'pdf';
+
+/** Payload of the `model-download-cancelled` event */
+export type ModelDownloadCancelled = {
+  model: string;
+};
+
+/** Payload of the `model-download-complete` event */
+export type ModelDownloadComplete = {
+  model: string;
+  /** Path of the downloaded model file */
+  path: string;
+};
+
+/** Payload of the `model-download-error` event */
+export type ModelDownloadError = {
+  model: string;
+  error: string;
+};
+
+/** Payload of the `model-download-progress` event */
+export type ModelDownloadProgress = {
+  model: string;
+  downloaded_bytes: number;
+  total_bytes: number;
+  percentage: number;
+  speed_bps: number;
+};
+
+/** Model information */
+export type ModelInfo = {
+  model: string;
+  quantization: string;
+  path: string;
+  size_bytes: number;
+  downloaded: boolean;
+  is_quantized: boolean;
+};
+
+/** Pruning of unused model downloads */
+export type ModelPruningSettings = {
+  /** Suggest removing models unused for `unused_days` (nothing is deleted without confirmation) */
+  enabled?: boolean;
+  /** Days without use before a model is suggested for removal */
+  unused_days?: number;
+};
+
+/** Quantization type for Whisper models */
+export type ModelQuantization = 'f16' | 'q8_0' | 'q5_1';
+
+/** Which quantization of a model is used for the one requested */
+export type ModelResolution = {
+  model: WhisperModel;
+  requested: ModelQuantization;
+  /** Quantization on disk that is loaded (None when no variant is downloaded) */
+  used?: ModelQuantization | null;
+};
+
+/**
+ * Where models are stored
+ *
+ * Settings and history always stay in the OS user's own directories; only the large model files can be shared between users.
+ */
+export type ModelStorageSettings = {
+  /** Absolute path of a model directory shared by all users (None = per-user). When it is read-only, its models are used and downloads go to the user's own directory. */
+  shared_dir?: string | null;
+};
+
+/**
+ * Mouse button usable as a record trigger
+ *
+ * macOS only reports the left and right buttons to the listener, so mouse triggers work on Windows and X11 only.
+ */
+export type MouseTrigger = 'middle' | 'back' | 'forward';
+
+/**
+ * MQTT publishing, for home automation
+ *
+ * Recording state goes to `<topic>/state` ("recording", "processing", "idle" or "error") and final transcripts to `<topic>/transcript`. The broker password is kept in the credential store.
+ */
+export type MqttSettings = {
+  /** Publish to the broker */
+  enabled?: boolean;
+  /** Broker host name or IP address */
+  host?: string;
+  port?: number;
+  /** Topic prefix */
+  topic?: string;
+  client_id?: string;
+  /** User name, if the broker requires authentication */
+  username?: string | null;
+  /** Ask the broker to keep the last state for new subscribers */
+  retain?: boolean;
+};
+
+/** Algorithm of the noise suppression stage */
+export type NoiseSuppressor = 'filter' | 'rnnoise';
+
+/** Recording left behind by a previous session */
+export type OrphanedRecording = {
+  /** Captured duration in seconds */
+  duration_secs: number;
+  /** Sample rate of the captured audio */
+  sample_rate: number;
+  /** Unix timestamp (seconds) of the last checkpoint flush */
+  saved_at: number;
+};
+
+/** How transcriptions are inserted at the cursor */
+export type OutputMode = 'paste' | 'type';
+
+/** Output behavior settings */
+export type OutputSettings = {
+  /** Auto-capitalize first letter */
+  auto_capitalize?: boolean;
+  /** Add punctuation automatically */
+  auto_punctuation?: boolean;
+  /** Delay before paste (milliseconds) */
+  paste_delay?: number;
+  /** How text is inserted at the cursor */
+  mode?: OutputMode;
+  /** Characters per second in typing mode */
+  typing_rate?: number;
+  /** Re-apply the capitalization of proper nouns learned from corrections */
+  capitalize_learned_terms?: boolean;
+  /** Destinations for each transcription, in order (empty = history only) */
+  sinks?: OutputSink[];
+  /** Selection receiving pasted text (Linux; other platforms always use the clipboard) */
+  selection?: PasteSelection;
+  /** Formats placed on the clipboard */
+  clipboard_format?: ClipboardFormat;
+  /** Add or drop the leading space depending on the text before the caret */
+  smart_spacing?: boolean;
+  /** Attach a thumbnail of the target window to each history entry */
+  capture_screenshots?: boolean;
+  /** Apps never captured (matched against the process name, case-insensitive) */
+  screenshot_excluded_apps?: string[];
+  /** Compliance log of text injections */
+  injection_audit?: InjectionAuditSettings;
+  /** Paste each sentence while still recording, once it stops changing (local provider only) */
+  type_as_you_speak?: boolean;
+  /** Locale conventions for quotes, apostrophes, spacing and decimals */
+  typography?: TypographySettings;
+  /** Case transform applied last */
+  casing?: TextCasing;
+  /** Spoken punctuation and formatting ("comma", "new line") */
+  voice_commands?: VoiceCommandSettings;
+  /** Rewriting of transcripts by a language model before output */
+  cleanup?: CleanupSettings;
+};
+
+/** Transcription output destination */
+export type OutputSink =
+  | { type: 'paste' }
+  | { type: 'clipboard' }
+  | { type: 'append-to-file'; path: string; header?: boolean }
+  | { type: 'markdown-journal'; folder: string; template?: string; tags?: string[] }
+  | { type: 'webhook'; url: string; auth_header?: string | null; max_retries?: number };
+
+/** Partial text of a recording in progress */
+export type PartialTranscript = {
+  /** Text so far (replaces the previous partial) */
+  text: string;
+  /** Audio covered by the text */
+  audio_ms: number;
+  /** Increases with each partial of a recording */
+  sequence: number;
+};
+
+/** How the paste keystroke is synthesized */
+export type PasteBackend = 'send-input' | 'wtype' | 'ydotool' | 'xdotool';
+
+/** X11/Wayland selection used for pasting */
+export type PasteSelection = 'clipboard' | 'primary' | 'both';
+
+/** Stage of the audio to text pipeline */
+export type PipelineStage =
+  | 'gain'
+  | 'noise_suppression'
+  | 'vad'
+  | 'speaker_filter'
+  | 'resample'
+  | 'transcribe'
+  | 'post_process';
+
+/** Pipeline stage as shown in the pipeline editor */
+export type PipelineStageInfo = {
+  stage: PipelineStage;
+  enabled: boolean;
+  /** Required stages can be moved but not disabled */
+  required: boolean;
+};
+
+/** One configured pipeline stage */
+export type PipelineStageSettings = {
+  stage: PipelineStage;
+  enabled: boolean;
+};
+
+/** Per-stage timings of one recording */
+export type PipelineTrace = {
+  timestamp: string;
+  profile?: string | null;
+  stages: StageTrace[];
+  total_ms: number;
+  /** Time saved by classifying VAD frames while resampling */
+  overlap_saved_ms: number;
+  /** Error that ended the pipeline, if any */
+  error?: string | null;
+};
+
+/** What output and shortcut features work in the current session */
+export type PlatformCapabilities = {
+  display_server: DisplayServer;
+  clipboard: ClipboardBackend;
+  /** Paste backends in order of preference (empty = no automatic paste) */
+  paste: PasteBackend[];
+  /** Whether global shortcuts are delivered regardless of the focused window */
+  global_shortcuts: boolean;
+  /** Setup hints for missing capabilities */
+  guidance: string[];
+};
+
+/** How eagerly to warm up the provider before dictation */
+export type PrefetchMode = 'off' | 'conservative' | 'aggressive';
+
+/** Inference time of transcriptions with and without a warm-up ahead */
+export type PrefetchStats = {
+  /** Provider compared, the one of the latest transcription */
+  provider: string;
+  warm_count: number;
+  cold_count: number;
+  /** Average inference time after a warm-up (ms) */
+  avg_warm_inference_ms: number;
+  /** Average inference time without one (ms) */
+  avg_cold_inference_ms: number;
+};
+
+/** Model a quality preset selects that is not downloaded */
+export type PresetModel = {
+  model: WhisperModel;
+  quantization: ModelQuantization;
+  /** Download size in bytes */
+  size_bytes: number;
+};
+
+/**
+ * Privacy settings
+ *
+ * With `no_disk_audio`, recordings only live in memory: history entries are saved without audio, no crash-recovery checkpoint or debug capture is written, and the recording's audio buffers are zeroed once the text is delivered. Copies made while transcribing are freed without being overwritten. The history encryption key is kept in the credential store.
+ */
+export type PrivacySettings = {
+  /** Never write recorded audio to disk */
+  no_disk_audio?: boolean;
+  /** Encrypt the history file at rest */
+  encrypt_history?: boolean;
+};
+
+/** What a provider supports */
+export type ProviderCapabilities = {
+  provider: TranscriptionProvider;
+  /** Model the capabilities apply to */
+  model: string;
+  /** Languages that can be selected (ISO 639-1) */
+  languages: string[];
+  /** Whether "auto" language detection is offered */
+  language_detection: boolean;
+  /** Longest recording accepted, in seconds (None = no provider limit) */
+  max_audio_secs?: number | null;
+  /** Partial results while transcribing */
+  streaming: boolean;
+  /** Segment timestamps in results */
+  timestamps: boolean;
+  /** Translation to English */
+  translation: boolean;
+  /** Whether the provider can be used right now */
+  available: boolean;
+  /** Why the provider can't be used, when unavailable */
+  unavailable_reason?: string | null;
+};
+
+/** Payload of the `transcription:provider` event */
+export type ProviderUsed = {
+  /** Name of the provider that succeeded ("whisper.cpp", "groq", ...) */
+  provider: string;
+  /** Whether the active provider failed and a fallback took over */
+  fallback: boolean;
+};
+
+/** A downloaded model suggested for removal */
+export type PruneCandidate = {
+  model: string;
+  quantization: string;
+  path: string;
+  size_bytes: number;
+  /** ISO 8601 time of the last use (or the download) */
+  last_used: string;
+  unused_days: number;
+};
+
+/** Result of `prune_unused_models` */
+export type PruneReport = {
+  /** Models unused for the requested number of days */
+  models: PruneCandidate[];
+  /** Whether the models were deleted */
+  deleted: boolean;
+  /** Disk space freed (bytes) */
+  freed_bytes: number;
+  /** Models that could not be deleted, with the reason */
+  errors: string[];
+};
+
+/**
+ * Speed vs accuracy setting picked with one switch
+ *
+ * A preset sets the model size, quantization, VAD aggressiveness, beam size and GPU use; changing any of those by hand makes it `Custom`.
+ */
+export type QualityPreset = 'fast' | 'balanced' | 'accurate' | 'custom';
+
+/** Payload of the `models:quantization-fallback` event */
+export type QuantizationFallback = {
+  model: WhisperModel;
+  /** Quantization set in the settings */
+  requested: ModelQuantization;
+  /** Downloaded quantization used instead */
+  used: ModelQuantization;
+  /** Whether the requested quantization is being downloaded */
+  downloading: boolean;
+};
+
+/** Quotation marks used for double quotes */
+export type QuoteStyle =
+  | 'keep'
+  | 'straight'
+  | 'curly'
+  | 'low-high'
+  | 'low-right'
+  | 'guillemets'
+  | 'reversed-guillemets'
+  | 'corner';
+
+/** Lightweight view of a history entry for quick-paste lookups */
+export type RecentText = {
+  /** Entry identifier (pass to `paste_history_entry`) */
+  id: string;
+  /** The transcribed text */
+  text: string;
+  /** Timestamp when transcription was created (ISO 8601) */
+  timestamp: string;
+  title: string;
+};
+
+/** Recording mode */
+export type RecordingMode = 'push-to-talk' | 'toggle';
+
+/**
+ * Recording profile bound to its own shortcut
+ *
+ * Unset fields fall back to the global settings.
+ */
+export type RecordingProfile = {
+  /** Unique identifier */
+  id?: string;
+  /** Display name */
+  name?: string;
+  /** Global shortcut that records with this profile */
+  shortcut?: string;
+  /** Language code override (ISO 639-1 or "auto") */
+  language?: string | null;
+  /** Provider override */
+  provider?: TranscriptionProvider | null;
+  /** Local model override */
+  local_model?: WhisperModel | null;
+  /** Groq model override */
+  groq_model?: string | null;
+  /** Translation to English override */
+  translate?: boolean | null;
+  /** Output sinks override */
+  sinks?: OutputSink[] | null;
+  /** Learned-term capitalization override */
+  capitalize_learned_terms?: boolean | null;
+  /** Smart spacing override */
+  smart_spacing?: boolean | null;
+  /** Typography normalization override */
+  typography?: boolean | null;
+  /** Text casing override */
+  casing?: TextCasing | null;
+  /** Audio source override */
+  source?: AudioSource | null;
+};
+
+/** Recording behavior settings */
+export type RecordingSettings = {
+  /** Recording mode: push-to-talk or toggle */
+  mode?: RecordingMode;
+  /** Maximum recording duration in seconds (0 = unlimited) */
+  max_duration?: number;
+  /** Auto-stop after silence (milliseconds, 0 = disabled) */
+  silence_timeout?: number;
+  /** Keep the raw audio and settings of each recording for replay */
+  save_debug_captures?: boolean;
+  /** Minutes an incognito dictation stays in memory before it is wiped */
+  incognito_minutes?: number;
+  /** Audio kept with each history entry */
+  history_audio?: HistoryAudio;
+  /** Encoding of the audio kept with each history entry */
+  history_audio_codec?: HistoryAudioCodec;
+};
+
+/** DTO for recording state */
+export type RecordingStateDto = {
+  state: string;
+  duration_ms?: number | null;
+  error?: string | null;
+};
+
+/** A suggested settings change */
+export type Remediation = {
+  kind: RemediationKind;
+  message: string;
+};
+
+/** What to change to bring latency down */
+export type RemediationKind = 'smaller-model' | 'enable-gpu' | 'enable-vad' | 'reduce-paste-delay';
+
+/** A single text replacement rule applied after transcription */
+export type ReplacementRule = {
+  /** Text to look for */
+  from?: string;
+  /** Replacement text */
+  to?: string;
+  /** Match case exactly */
+  case_sensitive?: boolean;
+  /** Only match whole words */
+  whole_word?: boolean;
+};
+
+/** Outcome of replaying a capture */
+export type ReplayReport = {
+  capture: string;
+  original_raw?: string | null;
+  replayed_raw?: string | null;
+  original_text?: string | null;
+  replayed_text?: string | null;
+  raw_divergence?: Divergence | null;
+  text_divergence?: Divergence | null;
+  /** Same text and same error as the original run */
+  matches: boolean;
+  original_error?: string | null;
+  error?: string | null;
+  trace: PipelineTrace;
+  /** Reasons the replay may legitimately differ */
+  notes: string[];
+};
+
+/**
+ * Memory budget settings
+ *
+ * Caps apply while idle; 0 means no cap.
+ */
+export type ResourceSettings = {
+  /** Most RAM the app should keep in use (MB) */
+  max_ram_mb?: number;
+  /** Most VRAM the Whisper model should keep in use (MB) */
+  max_vram_mb?: number;
+  /** Unload the Whisper model after this many idle minutes (0 = keep it loaded) */
+  idle_unload_minutes?: number;
+};
+
+/** Current memory use against the budget */
+export type ResourceUsage = {
+  /** Resident memory of the process (None where the platform doesn't report it) */
+  process_ram_bytes?: number | null;
+  /** File name of the loaded Whisper model */
+  model?: string | null;
+  /** Loaded model held in RAM */
+  model_ram_bytes: number;
+  /** Loaded model held in VRAM (estimated from the model size) */
+  model_vram_bytes: number;
+  /** Size of the configured local model, loaded or not (0 for cloud providers) */
+  configured_model_bytes: number;
+  /** Whether the configured model loads into VRAM */
+  configured_model_gpu: boolean;
+  caches: CacheUsage[];
+  max_ram_bytes?: number | null;
+  max_vram_bytes?: number | null;
+  prefetch_suspended: boolean;
+};
+
+/** Retry policy of cloud providers (exponential backoff with jitter) */
+export type RetrySettings = {
+  /** Retries after the first attempt (0 disables retrying) */
+  max_retries?: number;
+  /** Delay before the first retry, doubled for each further one */
+  base_delay_ms?: number;
+  /** Longest delay between attempts; rate limits asking for longer fail */
+  max_delay_ms?: number;
+  /** Share of each delay that is randomized, in percent */
+  jitter_percent?: number;
+};
+
+/** Process holding Secure Input */
+export type SecureInputHolder = {
+  pid: number;
+  /** Executable name, if it could be resolved */
+  process_name?: string | null;
+};
+
+/** Main settings structure */
+export type Settings = {
+  /** Schema version for migration support When this version is lower than CURRENT_SCHEMA_VERSION, migrations will be applied */
+  schema_version?: number;
+  recording?: RecordingSettings;
+  shortcuts?: ShortcutSettings;
+  transcription?: TranscriptionSettings;
+  audio?: AudioSettings;
+  output?: OutputSettings;
+  ui?: UiSettings;
+  /** Keywords raising an alert when transcribed */
+  alerts?: AlertSettings;
+  /** Scripts run at recording and transcription lifecycle points */
+  hooks?: HookSettings;
+  /** Recording profiles, each bound to its own shortcut */
+  profiles?: RecordingProfile[];
+  /** Separate history, vocabulary and output contexts */
+  workspaces?: WorkspaceSettings;
+  /** Memory caps for models and caches */
+  resources?: ResourceSettings;
+  /** Stop-to-paste latency target */
+  latency?: LatencySettings;
+  /** Removal of downloaded models that are no longer used */
+  model_pruning?: ModelPruningSettings;
+  /** Model directory shared by the users of the machine */
+  model_storage?: ModelStorageSettings;
+  /** Local HTTP and websocket API for external tools */
+  api_server?: ApiServerSettings;
+  /** Publishing of recording state and transcripts to an MQTT broker */
+  mqtt?: MqttSettings;
+  /** Keeping audio and transcripts off the disk */
+  privacy?: PrivacySettings;
+};
+
+/** A model directory shared by the users of the machine */
+export type SharedModelsDir = {
+  path: string;
+  /** Whether this user can download models into it */
+  writable: boolean;
+};
+
+/** Configured shortcut that can't be registered */
+export type ShortcutConflict = {
+  /** Setting name, or the name of a recording profile */
+  name: string;
+  shortcut: string;
+  reason: ConflictReason;
+};
+
+/** Keyboard shortcut settings */
+export type ShortcutSettings = {
+  /** Main recording shortcut */
+  record?: string;
+  /** Cancel recording shortcut, active only while recording (empty = disabled) */
+  cancel?: string;
+  /** Open settings shortcut (empty = disabled) */
+  settings?: string;
+  /** Open the history quick-paste palette (empty = disabled) */
+  palette?: string;
+  /** Record an incognito dictation, kept in memory only (empty = disabled) */
+  incognito?: string;
+  /** Show or hide the main window (empty = disabled) */
+  toggle_window?: string;
+  /** Paste the last transcription again (empty = disabled) */
+  repaste_last?: string;
+  /** Cycle the recording profile used by the record shortcut (empty = disabled) */
+  switch_profile?: string;
+  /** Record the system audio output instead of the microphone (empty = disabled) */
+  system_audio?: string;
+  /** Tap, double-tap and long-press gestures on record shortcuts */
+  gestures?: GestureSettings;
+  /** Mouse and gamepad buttons acting as the record shortcut */
+  triggers?: TriggerSettings;
+};
+
+/** Outcome of delivering a transcription to one sink */
+export type SinkResult = {
+  /** Sink label (see `OutputSink::label`) */
+  sink: string;
+  success: boolean;
+  error?: string | null;
+  /** How the text was inserted (paste sink only) */
+  method?: InjectionMethod | null;
+};
+
+/** Speaker verification settings */
+export type SpeakerFilterSettings = {
+  /** Drop segments that don't match the voice profile */
+  enabled?: boolean;
+  /** How closely a segment must match the profile (0.0 = lenient, 1.0 = strict) */
+  sensitivity?: number;
+  /** Enrolled voice, recorded during onboarding or from the settings */
+  profile?: VoiceProfile | null;
+};
+
+/** Outcome of one stage */
+export type StageStatus = 'ran' | 'skipped' | 'failed';
+
+/** Time spent in each step of one transcription (ms) */
+export type StageTimings = {
+  /** Stopping the capture stream and collecting its samples */
+  capture_drain_ms: number;
+  resample_ms: number;
+  /** Gain and noise suppression */
+  filters_ms: number;
+  /** Noise gate and speech filtering */
+  vad_ms: number;
+  /** Speech to text */
+  inference_ms: number;
+  /** Vocabulary rules and learned capitalization */
+  post_process_ms: number;
+  /** Delivering the text to the output sinks */
+  injection_ms: number;
+  /** Resampling time hidden by classifying VAD frames concurrently (not part of the total) */
+  overlap_saved_ms: number;
+};
+
+/** Timing of one stage */
+export type StageTrace = {
+  stage: PipelineStage;
+  status: StageStatus;
+  duration_ms: number;
+  /** What the stage did or why it was skipped */
+  detail?: string | null;
+};
+
+/** Where each kind of data is stored, for the health report */
+export type StorageReport = {
+  config_dir: string;
+  data_dir: string;
+  /** Directory new models are downloaded to */
+  models_dir: string;
+  shared_models?: SharedModelsDir | null;
+};
+
+/** Subtitle file format */
+export type SubtitleFormat = 'srt' | 'vtt';
+
+/** A tag in use and the number of entries carrying it */
+export type TagCount = {
+  tag: string;
+  entries: number;
+};
+
+/** How a background task runs */
+export type TaskKind = 'async' | 'thread';
+
+/** Case transform of transcriptions */
+export type TextCasing = 'keep' | 'sentence' | 'lower' | 'upper' | 'title';
+
+/** Application theme */
+export type Theme = 'system' | 'light' | 'dark';
+
+/** A stretch of text with its position in the transcribed audio */
+export type TranscriptSegment = {
+  start_ms: number;
+  end_ms: number;
+  text: string;
+};
+
+/** Transcript viewer state */
+export type TranscriptViewer = {
+  paused: boolean;
+  lines: ViewerLine[];
+  /** Partial of the recording in progress */
+  partial?: string | null;
+};
+
+/** Payload of the `transcription:complete` event */
+export type TranscriptionComplete = {
+  text: string;
+  /** Quantization of the local model used, when it transcribed the text */
+  model?: ModelResolution | null;
+  /** Delivery result of each configured output sink, in order */
+  sinks: SinkResult[];
+  /** Where the time between stopping the recording and delivery went */
+  timings: StageTimings;
+};
+
+/** Transcription provider selection */
+export type TranscriptionProvider = 'local' | 'groq' | 'deepgram' | 'custom';
+
+/** Record of a single transcription operation */
+export type TranscriptionRecord = {
+  /** Timestamp when transcription started (unix ms) */
+  timestamp_ms: number;
+  /** Audio duration in milliseconds */
+  audio_duration_ms: number;
+  /** Processing time in milliseconds */
+  processing_time_ms: number;
+  /** Real-time factor (processing_time / audio_duration) */
+  real_time_factor: number;
+  /** Provider used (local/groq) */
+  provider: string;
+  /** Model name/size */
+  model: string;
+  /** Whether GPU was used */
+  gpu_used: boolean;
+  /** Number of threads used */
+  threads_used: number;
+  /** Whether VAD was enabled */
+  vad_enabled: boolean;
+  /** Audio duration after VAD filtering (if enabled) */
+  vad_filtered_ms?: number | null;
+  /** Number of characters in result */
+  result_chars: number;
+  /** Where the time went */
+  timings: StageTimings;
+  /** Whether a warm-up ran ahead of it */
+  prefetched: boolean;
+};
+
+/** Transcription settings */
+export type TranscriptionSettings = {
+  /** Active provider */
+  provider?: TranscriptionProvider;
+  /** Providers tried in order when the active one fails */
+  fallback_providers?: TranscriptionProvider[];
+  /** Language code (ISO 639-1) or "auto" */
+  language?: string;
+  /** Local whisper.cpp settings */
+  local?: LocalTranscriptionSettings;
+  /** Groq API settings */
+  groq?: GroqSettings;
+  /** Deepgram streaming API settings */
+  deepgram?: DeepgramSettings;
+  /** Self-hosted OpenAI-compatible server settings */
+  custom?: CustomEndpointSettings;
+  /** Retries of failed cloud requests */
+  retry?: RetrySettings;
+  /** Warm up the provider on pre-dictation signals */
+  prefetch?: PrefetchMode;
+  /** Translate transcriptions to English */
+  translate?: boolean;
+  /** Text given to the model as what came before the recording, to steer its spelling and style (e.g. "Cardiology consult notes.") */
+  initial_prompt?: string;
+  /** Words the model should recognize: names, jargon, code identifiers */
+  vocabulary?: string[];
+  /** Named bundle of model, decoding, VAD and GPU settings */
+  quality_preset?: QualityPreset;
+  /** Keep an English translation next to each transcript, for language practice (transcribes each recording a second time, after delivery; moot when translating) */
+  language_learning?: boolean;
+  /** Emit partial text while recording (local and Deepgram providers; Deepgram's streaming session then also gives the final text) */
+  live_partials?: boolean;
+  /** Audio between partial transcriptions, in milliseconds */
+  partial_interval_ms?: number;
+  /** Adjust the partial interval to how fast partials transcribe, starting from `partial_interval_ms` */
+  adaptive_partials?: boolean;
+  /** Shortest adaptive partial interval, in milliseconds */
+  partial_interval_min_ms?: number;
+  /** Longest adaptive partial interval, in milliseconds */
+  partial_interval_max_ms?: number;
+};
+
+/** Transcription status information */
+export type TranscriptionStatus = {
+  provider: string;
+  model: string;
+  model_loaded: boolean;
+  is_transcribing: boolean;
+  last_result?: string | null;
+  last_duration_ms?: number | null;
+  last_error?: string | null;
+};
+
+/**
+ * Record triggers besides the keyboard
+ *
+ * Each trigger acts like the record shortcut, following the recording mode and gestures. Foot pedals usually present themselves as gamepads.
+ */
+export type TriggerSettings = {
+  /** Mouse button (None = disabled) */
+  mouse_button?: MouseTrigger | null;
+  /** Gamepad or pedal button (None = disabled) */
+  gamepad_button?: GamepadTrigger | null;
+};
+
+/** Typographic conventions of a language */
+export type TypographyConventions = {
+  /** Language code (ISO 639-1) */
+  language?: string;
+  quotes?: QuoteStyle;
+  decimal_separator?: DecimalSeparator;
+  /** Replace straight apostrophes with typographic ones (’) */
+  typographic_apostrophes?: boolean;
+  /** Non-breaking spaces before ; : ! ? and inside « » (French) */
+  space_before_punctuation?: boolean;
+};
+
+/** Typography normalization settings */
+export type TypographySettings = {
+  /** Apply the conventions of the transcription's language before injection */
+  enabled?: boolean;
+  /** Conventions replacing the built-in ones of a language */
+  languages?: TypographyConventions[];
+};
+
+/** UI settings */
+export type UiSettings = {
+  /** Show recording indicator */
+  show_indicator?: boolean;
+  /** Indicator position */
+  indicator_position?: IndicatorPosition;
+  /** Application theme */
+  theme?: Theme;
+  /** Start minimized to tray */
+  start_minimized?: boolean;
+  /** Minimize to tray instead of taskbar */
+  minimize_to_tray?: boolean;
+};
+
+/** Update information sent to the frontend */
+export type UpdateInfo = {
+  currentVersion: string;
+  newVersion: string;
+  body?: string | null;
+  variant: string;
+};
+
+/** VAD values measured by the calibration wizard for one input device */
+export type VadCalibration = {
+  /** VAD aggressiveness (0-3) */
+  aggressiveness: number;
+  /** Minimum speech segment duration in ms */
+  min_speech_duration_ms: number;
+  /** Recordings whose loudest window stays below this level (dBFS) are treated as silence */
+  energy_threshold_db: number;
+  /** Measured ambient noise floor (dBFS) */
+  noise_floor_db: number;
+};
+
+/** Voice activity detector implementation */
+export type VadEngine = 'webrtc' | 'silero';
+
+/** Voice Activity Detection settings */
+export type VadSettings = {
+  /** Enable VAD filtering before transcription */
+  enabled?: boolean;
+  /** Detector classifying speech in recordings */
+  engine?: VadEngine;
+  /** VAD aggressiveness (0-3, higher = more aggressive) */
+  aggressiveness?: number;
+  /** Minimum speech segment duration in ms */
+  min_speech_duration_ms?: number;
+  /** Padding around speech segments in ms */
+  padding_ms?: number;
+  /** Cut trailing silence as soon as recording stops, using the live VAD */
+  trim_on_stop?: boolean;
+  /** Per-device calibration results, keyed by input device ID ("default" for the system default) */
+  calibrations?: Record<string, VadCalibration>;
+};
+
+/** Whether an injection could be confirmed */
+export type Verification = 'confirmed' | 'unverified' | 'failed' | 'focus-changed';
+
+/** A finished transcription */
+export type ViewerLine = {
+  text: string;
+  timestamp: string;
+};
+
+/** Change to apply to the view */
+export type ViewerUpdate =
+  | { type: 'partial'; text: string }
+  | { type: 'line'; line: ViewerLine }
+  | { type: 'cleared' }
+  | { type: 'paused'; paused: boolean };
+
+/** User vocabulary: custom terms and replacement rules */
+export type Vocabulary = {
+  /** Domain terms used to bias recognition */
+  terms?: string[];
+  /** Replacement rules applied in order after transcription */
+  rules?: ReplacementRule[];
+};
+
+/** A spoken phrase and the text replacing it */
+export type VoiceCommand = {
+  /** Words to say, matched in any case */
+  phrase?: string;
+  /** Text inserted instead (punctuation attaches to the previous word) */
+  text?: string;
+  /** Language the command applies to (ISO 639-1, None = every language) */
+  language?: string | null;
+};
+
+/** Spoken punctuation and formatting commands */
+export type VoiceCommandSettings = {
+  /** Replace spoken commands with the characters they name */
+  enabled?: boolean;
+  /** Commands added to, or replacing, the built-in ones */
+  commands?: VoiceCommand[];
+};
+
+/**
+ * Enrolled voice of the user
+ *
+ * The speaker embedding itself is kept in the credential store (see `SecretsManager::set_voice_profile`), not in the settings file.
+ */
+export type VoiceProfile = {
+  /** When the profile was recorded (ISO 8601) */
+  enrolled_at: string;
+};
+
+/** Whisper model sizes */
+export type WhisperModel = 'tiny' | 'base' | 'small' | 'medium' | 'large';
+
+/** A separate context with its own history, vocabulary and output settings */
+export type Workspace = {
+  /** Stable identifier, also the name of the workspace's data directory */
+  id: string;
+  /** Display name */
+  name: string;
+  /** Output settings of the workspace while it is inactive (the active workspace uses `Settings::output`; None = inherit on first switch) */
+  output?: OutputSettings | null;
+};
+
+/** Workspace list and the active workspace */
+export type WorkspaceSettings = {
+  /** Id of the active workspace */
+  active?: string;
+  list?: Workspace[];
+};
+
+export const commands = {
+  getSettings: () =>
+    invoke<Settings>('get_settings'),
+  saveSettings: (settings: Settings) =>
+    invoke<null>('save_settings', { settings }),
+  checkShortcuts: (settings: Settings) =>
+    invoke<ShortcutConflict[]>('check_shortcuts', { settings }),
+  setQualityPreset: (preset: QualityPreset, download?: boolean | null) =>
+    invoke<Settings>('set_quality_preset', { preset, download }),
+  presetMissingModel: (preset: QualityPreset) =>
+    invoke<PresetModel | null>('preset_missing_model', { preset }),
+  getAudioDevices: () =>
+    invoke<AudioDeviceDto[]>('get_audio_devices'),
+  calibrateVad: (deviceId: string | null, apply: boolean) =>
+    invoke<CalibrationReport>('calibrate_vad', { deviceId, apply }),
+  testAudioDevice: (deviceId: string | null, playback: boolean) =>
+    invoke<DeviceTestReport>('test_audio_device', { deviceId, playback }),
+  enrollVoiceProfile: (deviceId?: string | null) =>
+    invoke<VoiceProfile>('enroll_voice_profile', { deviceId }),
+  clearVoiceProfile: () =>
+    invoke<null>('clear_voice_profile'),
+  getAlertKeywords: () =>
+    invoke<string[]>('get_alert_keywords'),
+  addAlertKeyword: (keyword: string) =>
+    invoke<string[]>('add_alert_keyword', { keyword }),
+  removeAlertKeyword: (keyword: string) =>
+    invoke<string[]>('remove_alert_keyword', { keyword }),
+  getBuiltinVoiceCommands: (language: string) =>
+    invoke<VoiceCommand[]>('get_builtin_voice_commands', { language }),
+  setGroqApiKey: (apiKey: string) =>
+    invoke<null>('set_groq_api_key', { apiKey }),
+  hasGroqApiKey: () =>
+    invoke<boolean>('has_groq_api_key'),
+  clearGroqApiKey: () =>
+    invoke<null>('clear_groq_api_key'),
+  validateGroqApiKey: (apiKey: string) =>
+    invoke<null>('validate_groq_api_key', { apiKey }),
+  setDeepgramApiKey: (apiKey: string) =>
+    invoke<null>('set_deepgram_api_key', { apiKey }),
+  hasDeepgramApiKey: () =>
+    invoke<boolean>('has_deepgram_api_key'),
+  clearDeepgramApiKey: () =>
+    invoke<null>('clear_deepgram_api_key'),
+  validateDeepgramApiKey: (apiKey: string) =>
+    invoke<null>('validate_deepgram_api_key', { apiKey }),
+  setCustomEndpointToken: (token: string) =>
+    invoke<null>('set_custom_endpoint_token', { token }),
+  hasCustomEndpointToken: () =>
+    invoke<boolean>('has_custom_endpoint_token'),
+  clearCustomEndpointToken: () =>
+    invoke<null>('clear_custom_endpoint_token'),
+  setOpenaiApiKey: (apiKey: string) =>
+    invoke<null>('set_openai_api_key', { apiKey }),
+  hasOpenaiApiKey: () =>
+    invoke<boolean>('has_openai_api_key'),
+  clearOpenaiApiKey: () =>
+    invoke<null>('clear_openai_api_key'),
+  setCleanupEndpointToken: (token: string) =>
+    invoke<null>('set_cleanup_endpoint_token', { token }),
+  hasCleanupEndpointToken: () =>
+    invoke<boolean>('has_cleanup_endpoint_token'),
+  clearCleanupEndpointToken: () =>
+    invoke<null>('clear_cleanup_endpoint_token'),
+  setWebhookAuth: (url: string, value: string) =>
+    invoke<null>('set_webhook_auth', { url, value }),
+  hasWebhookAuth: (url: string) =>
+    invoke<boolean>('has_webhook_auth', { url }),
+  clearWebhookAuth: (url: string) =>
+    invoke<null>('clear_webhook_auth', { url }),
+  setMqttPassword: (password: string) =>
+    invoke<null>('set_mqtt_password', { password }),
+  hasMqttPassword: () =>
+    invoke<boolean>('has_mqtt_password'),
+  clearMqttPassword: () =>
+    invoke<null>('clear_mqtt_password'),
+  startRecording: (profile?: string | null, source?: AudioSource | null, translate?: boolean | null) =>
+    invoke<null>('start_recording', { profile, source, translate }),
+  stopRecording: () =>
+    invoke<string>('stop_recording'),
+  cancelRecording: () =>
+    invoke<null>('cancel_recording'),
+  getRecordingState: () =>
+    invoke<RecordingStateDto>('get_recording_state'),
+  getIndicatorState: () =>
+    invoke<IndicatorSnapshot>('get_indicator_state'),
+  getCurrentLevel: () =>
+    invoke<AudioLevel | null>('get_current_level'),
+  getOrphanedRecording: () =>
+    invoke<OrphanedRecording | null>('get_orphaned_recording'),
+  transcribeOrphanedRecording: () =>
+    invoke<string>('transcribe_orphaned_recording'),
+  discardOrphanedRecording: () =>
+    invoke<null>('discard_orphaned_recording'),
+  getTranscriptionStatus: () =>
+    invoke<TranscriptionStatus>('get_transcription_status'),
+  getProviderCapabilities: () =>
+    invoke<ProviderCapabilities[]>('get_provider_capabilities'),
+  transcribeFile: (path: string) =>
+    invoke<string>('transcribe_file', { path }),
+  enqueueAudioFiles: (paths: string[]) =>
+    invoke<FileJob[]>('enqueue_audio_files', { paths }),
+  startBatch: (folder: string, recursive?: boolean | null) =>
+    invoke<BatchRun>('start_batch', { folder, recursive }),
+  pauseBatch: () =>
+    invoke<null>('pause_batch'),
+  resumeBatch: () =>
+    invoke<null>('resume_batch'),
+  cancelBatch: () =>
+    invoke<null>('cancel_batch'),
+  getBatch: () =>
+    invoke<BatchRun | null>('get_batch'),
+  getFileQueue: () =>
+    invoke<FileJob[]>('get_file_queue'),
+  clearFileQueue: () =>
+    invoke<null>('clear_file_queue'),
+  preloadModel: () =>
+    invoke<null>('preload_model'),
+  unloadModel: () =>
+    invoke<null>('unload_model'),
+  signalActivity: (signal: ActivitySignal) =>
+    invoke<null>('signal_activity', { signal }),
+  getGpuInfo: () =>
+    invoke<GpuInfo>('get_gpu_info'),
+  getCpuInfo: () =>
+    invoke<CpuInfo>('get_cpu_info'),
+  getMetricsSummary: () =>
+    invoke<MetricsSummary>('get_metrics_summary'),
+  getRecentMetrics: (count?: number | null) =>
+    invoke<TranscriptionRecord[]>('get_recent_metrics', { count }),
+  resetMetrics: () =>
+    invoke<null>('reset_metrics'),
+  getLatencyReport: () =>
+    invoke<LatencyReport>('get_latency_report'),
+  getPipelineConfig: () =>
+    invoke<PipelineStageInfo[]>('get_pipeline_config'),
+  getPipelineTraces: (count?: number | null) =>
+    invoke<PipelineTrace[]>('get_pipeline_traces', { count }),
+  listDebugCaptures: () =>
+    invoke<CaptureInfo[]>('list_debug_captures'),
+  replayRecording: (path: string) =>
+    invoke<ReplayReport>('replay_recording', { path }),
+  getHealthReport: () =>
+    invoke<HealthReport>('get_health_report'),
+  getBackgroundTasks: () =>
+    invoke<BackgroundTask[]>('get_background_tasks'),
+  getResourceUsage: () =>
+    invoke<ResourceUsage>('get_resource_usage'),
+  getErrorGuidance: (code: ErrorCode) =>
+    invoke<ErrorGuidance>('get_error_guidance', { code }),
+  openDictationBox: () =>
+    invoke<null>('open_dictation_box'),
+  getDictationBox: () =>
+    invoke<DictationBox>('get_dictation_box'),
+  updateDictationBox: (text: string) =>
+    invoke<null>('update_dictation_box', { text }),
+  sendDictationBox: (text?: string | null) =>
+    invoke<InjectionMethod>('send_dictation_box', { text }),
+  closeDictationBox: () =>
+    invoke<null>('close_dictation_box'),
+  openViewer: () =>
+    invoke<null>('open_viewer'),
+  closeViewer: () =>
+    invoke<null>('close_viewer'),
+  getViewer: () =>
+    invoke<TranscriptViewer>('get_viewer'),
+  setViewerPaused: (paused: boolean) =>
+    invoke<null>('set_viewer_paused', { paused }),
+  clearViewer: () =>
+    invoke<null>('clear_viewer'),
+  pasteText: (text: string) =>
+    invoke<null>('paste_text', { text }),
+  getHistory: () =>
+    invoke<ClipboardHistoryEntry[]>('get_history'),
+  getInjectionAudit: (limit?: number | null) =>
+    invoke<InjectionRecord[]>('get_injection_audit', { limit }),
+  exportInjectionAudit: (path: string) =>
+    invoke<number>('export_injection_audit', { path }),
+  listModels: () =>
+    invoke<ModelInfo[]>('list_models'),
+  isModelDownloaded: (model: string) =>
+    invoke<boolean>('is_model_downloaded', { model }),
+  downloadModel: (model: string) =>
+    invoke<string>('download_model', { model }),
+  deleteModel: (model: string) =>
+    invoke<null>('delete_model', { model }),
+  importModel: (path: string, name: string) =>
+    invoke<CustomModel>('import_model', { path, name }),
+  listCustomModels: () =>
+    invoke<CustomModel[]>('list_custom_models'),
+  deleteCustomModel: (id: string) =>
+    invoke<null>('delete_custom_model', { id }),
+  cancelModelDownload: (model: string) =>
+    invoke<boolean>('cancel_model_download', { model }),
+  isModelDownloading: (model: string) =>
+    invoke<boolean>('is_model_downloading', { model }),
+  pruneUnusedModels: (days: number | null, confirm: boolean) =>
+    invoke<PruneReport>('prune_unused_models', { days, confirm }),
+  getRecommendedModel: () =>
+    invoke<string>('get_recommended_model'),
+  getTranscriptionHistory: () =>
+    invoke<HistoryEntry[]>('get_transcription_history'),
+  getHistoryEntry: (id: string) =>
+    invoke<HistoryEntry | null>('get_history_entry', { id }),
+  deleteHistoryEntry: (id: string) =>
+    invoke<boolean>('delete_history_entry', { id }),
+  clearHistory: () =>
+    invoke<null>('clear_history'),
+  getHistoryCount: () =>
+    invoke<number>('get_history_count'),
+  getIncognitoEntries: () =>
+    invoke<IncognitoEntry[]>('get_incognito_entries'),
+  clearIncognitoEntries: () =>
+    invoke<number>('clear_incognito_entries'),
+  getAudioData: (id: string, format?: AudioFormat | null, bitrateKbps?: number | null, sampleRate?: number | null) =>
+    invoke<string>('get_audio_data', { id, format, bitrateKbps, sampleRate }),
+  getScreenshotData: (id: string) =>
+    invoke<string>('get_screenshot_data', { id }),
+  exportMeetingMinutes: (id: string, format: MinutesFormat, path: string, template?: string | null) =>
+    invoke<null>('export_meeting_minutes', { id, format, path, template }),
+  exportSubtitles: (id: string, format: SubtitleFormat, path: string) =>
+    invoke<null>('export_subtitles', { id, format, path }),
+  exportHistory: (format: HistoryExportFormat, path: string, query?: HistoryQuery | null) =>
+    invoke<number>('export_history', { format, path, query }),
+  setEntryTitle: (id: string, title: string) =>
+    invoke<string>('set_entry_title', { id, title }),
+  setEntryNote: (id: string, note: string) =>
+    invoke<null>('set_entry_note', { id, note }),
+  addEntryTag: (id: string, tag: string) =>
+    invoke<string[]>('add_entry_tag', { id, tag }),
+  removeEntryTag: (id: string, tag: string) =>
+    invoke<string[]>('remove_entry_tag', { id, tag }),
+  getHistoryTags: () =>
+    invoke<TagCount[]>('get_history_tags'),
+  submitCorrection: (entryId: string, correctedText: string) =>
+    invoke<CorrectionOutcome>('submit_correction', { entryId, correctedText }),
+  getRecentTexts: (limit?: number | null) =>
+    invoke<RecentText[]>('get_recent_texts', { limit }),
+  fuzzySearchHistory: (query: string, limit?: number | null) =>
+    invoke<RecentText[]>('fuzzy_search_history', { query, limit }),
+  searchHistory: (query: HistoryQuery) =>
+    invoke<HistorySearchResult>('search_history', { query }),
+  verifyHistoryIntegrity: (repair: boolean) =>
+    invoke<IntegrityReport>('verify_history_integrity', { repair }),
+  pasteHistoryEntry: (id: string) =>
+    invoke<null>('paste_history_entry', { id }),
+  listApiClients: () =>
+    invoke<ApiClientInfo[]>('list_api_clients'),
+  createApiClient: (name: string, scopes: ApiScope[]) =>
+    invoke<IssuedToken>('create_api_client', { name, scopes }),
+  updateApiClientScopes: (id: string, scopes: ApiScope[]) =>
+    invoke<ApiClientInfo>('update_api_client_scopes', { id, scopes }),
+  rotateApiClientToken: (id: string) =>
+    invoke<IssuedToken>('rotate_api_client_token', { id }),
+  deleteApiClient: (id: string) =>
+    invoke<null>('delete_api_client', { id }),
+  getApiAuditLog: (limit?: number | null) =>
+    invoke<AuditEntry[]>('get_api_audit_log', { limit }),
+  getVocabulary: () =>
+    invoke<Vocabulary>('get_vocabulary'),
+  saveVocabulary: (vocabulary: Vocabulary) =>
+    invoke<null>('save_vocabulary', { vocabulary }),
+  exportVocabulary: (path: string, name?: string | null, description?: string | null) =>
+    invoke<null>('export_vocabulary', { path, name, description }),
+  importVocabulary: (path: string, mode?: ImportMode | null) =>
+    invoke<ImportSummary>('import_vocabulary', { path, mode }),
+  applyRulesPack: (url: string, mode?: ImportMode | null) =>
+    invoke<ImportSummary>('apply_rules_pack', { url, mode }),
+  getLearnedTerms: () =>
+    invoke<LearnedTerm[]>('get_learned_terms'),
+  removeLearnedTerm: (term: string) =>
+    invoke<boolean>('remove_learned_term', { term }),
+  pruneLearnedTerms: (minScore?: number | null) =>
+    invoke<number>('prune_learned_terms', { minScore }),
+  listWorkspaces: () =>
+    invoke<WorkspaceSettings>('list_workspaces'),
+  createWorkspace: (name: string) =>
+    invoke<Workspace>('create_workspace', { name }),
+  switchWorkspace: (id: string) =>
+    invoke<null>('switch_workspace', { id }),
+  deleteWorkspace: (id: string) =>
+    invoke<null>('delete_workspace', { id }),
+  installUpdate: () =>
+    invoke<null>('install_update'),
+  restartApp: () =>
+    invoke<null>('restart_app'),
+  getBuildVariant: () =>
+    invoke<BuildVariantInfo>('get_build_variant'),
+};
+
+/** Payload of each event sent to the UI */
+export type EventPayloads = {
+  'history:updated': null;
+  'settings:preset-changed': QualityPreset;
+  'incognito:updated': null;
+  'calibration:phase': string;
+  'enrollment:phase': string;
+  'model-download-progress': ModelDownloadProgress;
+  'model-download-complete': ModelDownloadComplete;
+  'model-download-error': ModelDownloadError;
+  'model-download-cancelled': ModelDownloadCancelled;
+  'models:prune-suggested': PruneReport;
+  'models:quantization-fallback': QuantizationFallback;
+  'error:guidance': ErrorGuidance;
+  'recording:max-duration-reached': number;
+  'audio:level': AudioLevel;
+  'audio:device': DeviceEvent;
+  'audio:speech-active': boolean;
+  'audio:device-changed': string | null;
+  'recording:state-changed': string;
+  'recording:processing': null;
+  'recording:recovered': OrphanedRecording;
+  'transcription:partial': PartialTranscript;
+  'transcription:provider': ProviderUsed;
+  'transcription:complete': TranscriptionComplete;
+  'transcription:alert': KeywordAlert;
+  'transcription:error': string;
+  'latency:slo-violated': LatencyReport;
+  'indicator:state': IndicatorSnapshot;
+  'viewer:updated': ViewerUpdate;
+  'file-queue:updated': FileJob[];
+  'batch:progress': BatchProgress;
+  'batch:completed': BatchSummary;
+  'dictation-box:opened': null;
+  'dictation-box:closed': null;
+  'dictation-box:updated': string;
+  'output:secure-input': SecureInputHolder | null;
+  'show:popup': string;
+  'workspace:changed': string;
+  'shortcuts:profile-switched': string | null;
+  'palette:open': null;
+  'navigate:settings': null;
+  'update-available': UpdateInfo;
+  'update-download-progress': DownloadProgress;
+  'update-installed': null;
+};
+
+/** Listen to an event with its typed payload */
+export function listenTo<E extends keyof EventPayloads>(
+  event: E,
+  handler: (payload: EventPayloads[E]) => void,
+): Promise<UnlistenFn> {
+  return listen<EventPayloads[E]>(event, (e) => handler(e.payload));
+}
@@ -1,16 +1,27 @@
 import { useEffect, useState, useRef } from 'react';
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { Copy, Trash2, Clock, RefreshCw, Play, Square, AlertTriangle } from 'lucide-react';
-
-interface HistoryEntry {
-  id: string;
-  text: string;
-  timestamp: string;
-  duration_ms: number;
-  provider: string;
-  language: string | null;
-  audio_path: string | null;
+import { Copy, Trash2, Clock, RefreshCw, Play, Square, AlertTriangle, Bell, Tag, X, Volume2 } from 'lucide-react';
+import { commands, type HistoryEntry, type TagCount } from '../bindings';
+
+function ScreenshotThumbnail({ id }: { id: string }) {
+  const [src, setSrc] = useState<string | null>(null);
+
+  useEffect(() => {
+    commands
+      .getScreenshotData(id)
+      .then(setSrc)
+      .catch((e) => console.error('Failed to load screenshot:', e));
+  }, [id]);
+
+  if (!src) return null;
+  return (
+    <img
+      src={src}
+      alt="Window at the time of dictation"
+      className="mt-3 max-h-40 rounded border border-gray-200 dark:border-gray-700"
+    />
+  );
 }
 
 export function HistoryPanel() {
@@ -20,12 +31,19 @@ export function HistoryPanel() {
   const [playingId, setPlayingId] = useState<string | null>(null);
   const [loadingAudioId, setLoadingAudioId] = useState<string | null>(null);
   const [showClearConfirm, setShowClearConfirm] = useState(false);
+  const [tags, setTags] = useState<TagCount[]>([]);
+  const [tagFilter, setTagFilter] = useState<string | null>(null);
+  const [taggingId, setTaggingId] = useState<string | null>(null);
+  const [tagDraft, setTagDraft] = useState('');
   const audioRef = useRef<HTMLAudioElement | null>(null);
 
   const loadHistory = async () => {
     try {
-      const history = await invoke<HistoryEntry[]>('get_transcription_history');
+      const history = tagFilter
+        ? (await commands.searchHistory({ tags: [tagFilter], limit: 500 })).entries
+        : await commands.getTranscriptionHistory();
       setEntries(history);
+      setTags(await commands.getHistoryTags());
     } catch (e) {
       console.error('Failed to load history:', e);
     } finally {
@@ -40,11 +58,36 @@ export function HistoryPanel() {
     const unsubscribe = listen('history:updated', () => {
       loadHistory();
     });
+    // Each workspace has its own history
+    const unsubscribeWorkspace = listen('workspace:changed', () => {
+      loadHistory();
+    });
 
     return () => {
       unsubscribe.then((fn) => fn());
+      unsubscribeWorkspace.then((fn) => fn());
     };
-  }, []);
+  }, [tagFilter]);
+
+  const addTag = async (id: string) => {
+    const tag = tagDraft.trim();
+    setTaggingId(null);
+    setTagDraft('');
+    if (!tag) return;
+    try {
+      await commands.addEntryTag(id, tag);
+    } catch (e) {
+      console.error('Failed to add tag:', e);
+    }
+  };
+
+  const removeTag = async (id: string, tag: string) => {
+    try {
+      await commands.removeEntryTag(id, tag);
+    } catch (e) {
+      console.error('Failed to remove tag:', e);
+    }
+  };
 
   const copyToClipboard = async (text: string, id: string) => {
     try {
@@ -58,7 +101,7 @@ export function HistoryPanel() {
 
   const deleteEntry = async (id: string) => {
     try {
-      await invoke('delete_history_entry', { id });
+      await commands.deleteHistoryEntry(id);
       setEntries((prev) => prev.filter((e) => e.id !== id));
       // Stop playing if this entry was playing
       if (playingId === id) {
@@ -79,10 +122,8 @@ export function HistoryPanel() {
 
       setLoadingAudioId(id);
 
-      // Get audio data as base64
-      const audioData = await invoke<string>('get_audio_data', { id });
-
-      const audio = new Audio(audioData);
+      // Stream the recording through the range-enabled audio protocol
+      const audio = new Audio(convertFileSrc(id, 'gwaudio'));
       audioRef.current = audio;
 
       audio.onended = () => {
@@ -124,7 +165,7 @@ export function HistoryPanel() {
   const clearAllHistory = async () => {
     try {
       stopAudio();
-      await invoke('clear_history');
+      await commands.clearHistory();
       setEntries([]);
       setShowClearConfirm(false);
     } catch (e) {
@@ -173,6 +214,26 @@ export function HistoryPanel() {
         )}
       </div>
 
+      {/* Tag filter */}
+      {tags.length > 0 && (
+        <div className="flex flex-wrap items-center gap-1.5">
+          <Tag className="w-4 h-4 text-gray-400" />
+          {tags.map(({ tag, entries: count }) => (
+            <button
+              key={tag}
+              onClick={() => setTagFilter(tagFilter === tag ? null : tag)}
+              className={`px-2 py-0.5 rounded-full text-xs transition-colors ${
+                tagFilter === tag
+                  ? 'bg-blue-600 text-white'
+                  : 'bg-gray-100 dark:bg-gray-700 text-gray-600 dark:text-gray-300 hover:bg-gray-200 dark:hover:bg-gray-600'
+              }`}
+            >
+              {tag} ({count})
+            </button>
+          ))}
+        </div>
+      )}
+
       {/* Empty state */}
       {entries.length === 0 && (
         <div className="card p-12 text-center">
@@ -193,11 +254,91 @@ export function HistoryPanel() {
             key={entry.id}
             className="card p-4 group hover:shadow-md transition-shadow duration-200"
           >
+            {entry.title && (
+              <h3 className="mb-1 text-sm font-medium text-gray-900 dark:text-gray-100 truncate">
+                {entry.title}
+              </h3>
+            )}
+
             {/* Text content */}
             <p className="text-sm text-gray-800 dark:text-gray-200 whitespace-pre-wrap break-words">
               {entry.text}
             </p>
 
+            {entry.translation && (
+              <p className="mt-1 text-sm text-gray-500 dark:text-gray-400 whitespace-pre-wrap break-words">
+                {entry.translation}
+              </p>
+            )}
+
+            {entry.note && (
+              <p className="mt-2 text-xs italic text-gray-500 dark:text-gray-400 whitespace-pre-wrap break-words">
+                {entry.note}
+              </p>
+            )}
+
+            {entry.alerts && entry.alerts.length > 0 && (
+              <div className="mt-2 flex flex-wrap gap-1.5">
+                {entry.alerts.map((keyword) => (
+                  <span
+                    key={keyword}
+                    className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-amber-100 dark:bg-amber-900/30 text-amber-700 dark:text-amber-400 text-xs"
+                  >
+                    <Bell className="w-3 h-3" />
+                    {keyword}
+                  </span>
+                ))}
+              </div>
+            )}
+
+            {/* Tags */}
+            <div className="mt-2 flex flex-wrap items-center gap-1.5">
+              {entry.tags?.map((tag) => (
+                <span
+                  key={tag}
+                  className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-blue-50 dark:bg-blue-900/30 text-blue-700 dark:text-blue-300 text-xs"
+                >
+                  {tag}
+                  <button
+                    onClick={() => removeTag(entry.id, tag)}
+                    className="hover:text-blue-900 dark:hover:text-blue-100"
+                    title="Remove tag"
+                  >
+                    <X className="w-3 h-3" />
+                  </button>
+                </span>
+              ))}
+              {taggingId === entry.id ? (
+                <input
+                  autoFocus
+                  value={tagDraft}
+                  maxLength={40}
+                  onChange={(e) => setTagDraft(e.target.value)}
+                  onBlur={() => addTag(entry.id)}
+                  onKeyDown={(e) => {
+                    if (e.key === 'Enter') addTag(entry.id);
+                    if (e.key === 'Escape') {
+                      setTaggingId(null);
+                      setTagDraft('');
+                    }
+                  }}
+                  placeholder="Tag"
+                  className="w-24 px-2 py-0.5 text-xs border border-gray-300 dark:border-gray-600 rounded-full bg-white dark:bg-gray-700"
+                />
+              ) : (
+                <button
+                  onClick={() => setTaggingId(entry.id)}
+                  className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full text-xs text-gray-400 hover:text-gray-600 dark:hover:text-gray-300 opacity-0 group-hover:opacity-100 transition-opacity"
+                  title="Add tag"
+                >
+                  <Tag className="w-3 h-3" />
+                  Add tag
+                </button>
+              )}
+            </div>
+
+            {entry.screenshot_path && <ScreenshotThumbnail id={entry.id} />}
+
             {/* Metadata and actions */}
             <div className="mt-3 flex items-center justify-between text-xs text-gray-500 dark:text-gray-400">
               <div className="flex items-center gap-3">
@@ -224,6 +365,15 @@ export function HistoryPanel() {
                 )}
                 <span>{formatTimestamp(entry.timestamp)}</span>
                 <span>{formatDuration(entry.duration_ms)}</span>
+                {entry.source === 'system-audio' && (
+                  <span
+                    className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full bg-purple-50 dark:bg-purple-900/30 text-purple-700 dark:text-purple-300"
+                    title="Recorded from the system audio output"
+                  >
+                    <Volume2 className="w-3 h-3" />
+                    System audio
+                  </span>
+                )}
               </div>
 
               {/* Actions */}
@@ -140,4 +140,70 @@ describe('ModelSelector', () => {
       expect(baseRadio).toHaveAttribute('aria-checked', 'true');
     });
   });
+
+  describe('imported models', () => {
+    const customModel = {
+      id: 'medical',
+      name: 'Medical',
+      filename: 'custom-medical.bin',
+      size_bytes: 488636416,
+      multilingual: false,
+      imported_at: 1700000000,
+    };
+
+    beforeEach(() => {
+      vi.mocked(invoke).mockImplementation((cmd: string) => {
+        if (cmd === 'list_models') return Promise.resolve(mockModels);
+        if (cmd === 'list_custom_models') return Promise.resolve([customModel]);
+        if (cmd === 'import_model') return Promise.resolve({ ...customModel, id: 'legal' });
+        return Promise.resolve(undefined);
+      });
+    });
+
+    it('should select an imported model instead of the built-in one', async () => {
+      const onCustomModelChange = vi.fn();
+      render(
+        <ModelSelector
+          value="base"
+          onChange={mockOnChange}
+          customModel="medical"
+          onCustomModelChange={onCustomModelChange}
+        />
+      );
+
+      await waitFor(() => {
+        expect(screen.getByText(/English only/i)).toBeInTheDocument();
+      });
+      const radios = screen.getAllByRole('radio');
+      const checked = radios.filter((el) => el.getAttribute('aria-checked') === 'true');
+      expect(checked).toHaveLength(1);
+      expect(checked[0].textContent).toContain('Medical');
+
+      fireEvent.click(screen.getByText('Medical').closest('div[role="radio"]')!);
+      expect(onCustomModelChange).toHaveBeenCalledWith('medical');
+    });
+
+    it('should import a model file and select it', async () => {
+      const onCustomModelChange = vi.fn();
+      render(
+        <ModelSelector value="base" onChange={mockOnChange} onCustomModelChange={onCustomModelChange} />
+      );
+
+      fireEvent.change(screen.getByPlaceholderText(/Path to a whisper.cpp model/i), {
+        target: { value: '/home/me/ggml-legal.bin' },
+      });
+      fireEvent.change(screen.getByPlaceholderText('Display name'), {
+        target: { value: 'Legal' },
+      });
+      fireEvent.click(screen.getByText('Import'));
+
+      await waitFor(() => {
+        expect(invoke).toHaveBeenCalledWith('import_model', {
+          path: '/home/me/ggml-legal.bin',
+          name: 'Legal',
+        });
+        expect(onCustomModelChange).toHaveBeenCalledWith('legal');
+      });
+    });
+  });
 });
@@ -1,21 +1,20 @@
-import { Download, Check, Loader2, Trash2, X } from 'lucide-react';
+import { Download, Check, Loader2, Trash2, Upload, X } from 'lucide-react';
 import { useEffect, useState } from 'react';
-import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { commands, type CustomModel, type ModelInfo as ModelListing } from '../bindings';
 
 type WhisperModel = 'tiny' | 'base' | 'small' | 'medium' | 'large';
 
 interface ModelSelectorProps {
   value: WhisperModel;
   onChange: (value: WhisperModel) => void;
+  /** Id of the selected imported model, which takes precedence over `value` */
+  customModel?: string | null;
+  onCustomModelChange?: (id: string) => void;
 }
 
-interface ModelInfo {
-  model: WhisperModel;
-  path: string;
-  size_bytes: number;
-  downloaded: boolean;
-}
+/** A listed model, narrowed to the sizes this selector offers */
+type ModelInfo = ModelListing & { model: WhisperModel };
 
 interface DownloadProgress {
   model: string;
@@ -33,7 +32,7 @@ const modelDescriptions: Record<WhisperModel, string> = {
   large: 'Best accuracy, requires GPU',
 };
 
-function formatBytes(bytes: number): string {
+export function formatBytes(bytes: number): string {
   if (bytes < 1024) return `${bytes} B`;
   if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(1)} KB`;
   if (bytes < 1024 * 1024 * 1024) return `${(bytes / (1024 * 1024)).toFixed(0)} MB`;
@@ -46,8 +45,17 @@ function formatSpeed(bps: number): string {
   return `${(bps / (1024 * 1024)).toFixed(1)} MB/s`;
 }
 
-export function ModelSelector({ value, onChange }: ModelSelectorProps) {
+export function ModelSelector({
+  value,
+  onChange,
+  customModel,
+  onCustomModelChange,
+}: ModelSelectorProps) {
   const [models, setModels] = useState<ModelInfo[]>([]);
+  const [customModels, setCustomModels] = useState<CustomModel[]>([]);
+  const [importPath, setImportPath] = useState('');
+  const [importName, setImportName] = useState('');
+  const [importing, setImporting] = useState(false);
   const [downloading, setDownloading] = useState<string | null>(null);
   const [progress, setProgress] = useState<DownloadProgress | null>(null);
   const [error, setError] = useState<string | null>(null);
@@ -90,8 +98,9 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
 
   const loadModels = async () => {
     try {
-      const modelList = await invoke<ModelInfo[]>('list_models');
+      const modelList = (await commands.listModels()) as ModelInfo[];
       setModels(modelList);
+      setCustomModels((await commands.listCustomModels()) ?? []);
     } catch (e) {
       console.error('Failed to load models:', e);
     }
@@ -101,7 +110,7 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
     setError(null);
     setDownloading(model);
     try {
-      await invoke('download_model', { model });
+      await commands.downloadModel(model);
     } catch (e) {
       setError(`Download failed: ${e}`);
       setDownloading(null);
@@ -110,7 +119,32 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
 
   const handleDelete = async (model: WhisperModel) => {
     try {
-      await invoke('delete_model', { model });
+      await commands.deleteModel(model);
+      loadModels();
+    } catch (e) {
+      setError(`Delete failed: ${e}`);
+    }
+  };
+
+  const handleImport = async () => {
+    setError(null);
+    setImporting(true);
+    try {
+      const model = await commands.importModel(importPath.trim(), importName.trim());
+      setImportPath('');
+      setImportName('');
+      await loadModels();
+      onCustomModelChange?.(model.id);
+    } catch (e) {
+      setError(`Import failed: ${e}`);
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const handleDeleteCustom = async (id: string) => {
+    try {
+      await commands.deleteCustomModel(id);
       loadModels();
     } catch (e) {
       setError(`Delete failed: ${e}`);
@@ -119,7 +153,7 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
 
   const handleCancelDownload = async (model: WhisperModel) => {
     try {
-      await invoke('cancel_model_download', { model });
+      await commands.cancelModelDownload(model);
     } catch (e) {
       console.error('Failed to cancel download:', e);
     }
@@ -133,6 +167,7 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
         </div>
       )}
       {models.map((model) => {
+        const selected = !customModel && value === model.model;
         const isDownloading = downloading === model.model;
         const currentProgress = isDownloading && progress?.model === model.model ? progress : null;
 
@@ -140,11 +175,11 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
           <div
             key={model.model}
             role="radio"
-            aria-checked={value === model.model}
+            aria-checked={selected}
             aria-disabled={!model.downloaded && !isDownloading}
             tabIndex={model.downloaded ? 0 : -1}
             className={`flex items-center justify-between p-3 border rounded-lg cursor-pointer transition-colors ${
-              value === model.model
+              selected
                 ? 'border-blue-500 bg-blue-50 dark:bg-blue-900/20'
                 : 'border-gray-200 dark:border-gray-700 hover:border-gray-300 dark:hover:border-gray-600'
             } ${!model.downloaded && !isDownloading ? 'opacity-75' : ''}`}
@@ -160,12 +195,12 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
               {/* Selection indicator */}
               <div
                 className={`w-4 h-4 rounded-full border-2 flex items-center justify-center flex-shrink-0 ${
-                  value === model.model
+                  selected
                     ? 'border-blue-500 bg-blue-500'
                     : 'border-gray-300 dark:border-gray-600'
                 }`}
               >
-                {value === model.model && <Check className="w-3 h-3 text-white" />}
+                {selected && <Check className="w-3 h-3 text-white" />}
               </div>
 
               {/* Model info */}
@@ -248,6 +283,102 @@ export function ModelSelector({ value, onChange }: ModelSelectorProps) {
           </div>
         );
       })}
+
+      {/* Imported models */}
+      {customModels.map((model) => {
+        const selected = customModel === model.id;
+        return (
+          <div
+            key={model.id}
+            role="radio"
+            aria-checked={selected}
+            tabIndex={0}
+            className={`flex items-center justify-between p-3 border rounded-lg cursor-pointer transition-colors ${
+              selected
+                ? 'border-blue-500 bg-blue-50 dark:bg-blue-900/20'
+                : 'border-gray-200 dark:border-gray-700 hover:border-gray-300 dark:hover:border-gray-600'
+            }`}
+            onClick={() => onCustomModelChange?.(model.id)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter' || e.key === ' ') {
+                e.preventDefault();
+                onCustomModelChange?.(model.id);
+              }
+            }}
+          >
+            <div className="flex items-center gap-3 flex-1 min-w-0">
+              <div
+                className={`w-4 h-4 rounded-full border-2 flex items-center justify-center flex-shrink-0 ${
+                  selected ? 'border-blue-500 bg-blue-500' : 'border-gray-300 dark:border-gray-600'
+                }`}
+              >
+                {selected && <Check className="w-3 h-3 text-white" />}
+              </div>
+              <div className="min-w-0 flex-1">
+                <div className="font-medium truncate">
+                  {model.name}{' '}
+                  <span className="text-sm text-gray-500 dark:text-gray-400">
+                    ({formatBytes(model.size_bytes)})
+                  </span>
+                </div>
+                <div className="text-sm text-gray-500 dark:text-gray-400 truncate">
+                  Imported model{model.multilingual ? '' : ', English only'}
+                </div>
+              </div>
+            </div>
+            <button
+              onClick={(e) => {
+                e.stopPropagation();
+                handleDeleteCustom(model.id);
+              }}
+              className="p-1 ml-2 text-gray-400 hover:text-red-500 transition-colors"
+              title="Delete model"
+            >
+              <Trash2 className="w-4 h-4" />
+            </button>
+          </div>
+        );
+      })}
+
+      {/* Import form */}
+      {onCustomModelChange && (
+        <div className="p-3 border border-dashed border-gray-300 dark:border-gray-600 rounded-lg space-y-2">
+          <div className="text-sm font-medium">Import a model</div>
+          <input
+            type="text"
+            value={importPath}
+            onChange={(e) => setImportPath(e.target.value)}
+            placeholder="Path to a whisper.cpp model (ggml-*.bin)"
+            className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm"
+          />
+          <div className="flex gap-2">
+            <input
+              type="text"
+              value={importName}
+              onChange={(e) => setImportName(e.target.value)}
+              maxLength={64}
+              placeholder="Display name"
+              className="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm"
+            />
+            <button
+              onClick={handleImport}
+              disabled={importing || !importPath.trim() || !importName.trim()}
+              className="flex items-center gap-1 px-3 py-2 text-sm text-white bg-blue-600 rounded-md hover:bg-blue-700 disabled:opacity-50"
+            >
+              {importing ? (
+                <Loader2 className="w-4 h-4 animate-spin" />
+              ) : (
+                <Upload className="w-4 h-4" />
+              )}
+              Import
+            </button>
+          </div>
+          <p className="text-xs text-gray-500 dark:text-gray-400">
+            GGML files only. GGUF files are rejected, as whisper.cpp can't load Whisper models in
+            that format; convert them with whisper.cpp's scripts first.
+          </p>
+        </div>
+      )}
     </div>
   );
 }
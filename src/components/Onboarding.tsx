@@ -1,7 +1,7 @@
 import { useState, useEffect } from 'react';
-import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
-import { Mic, Settings, Sparkles, ChevronRight, ChevronLeft, Keyboard, Sun, Moon, Monitor, Star, Download, Check, Loader2 } from 'lucide-react';
+import { Mic, Settings, Sparkles, ChevronRight, ChevronLeft, Keyboard, Sun, Moon, Monitor, Star, Download, Check, Loader2, UserCheck } from 'lucide-react';
+import { commands, listenTo } from '../bindings';
 
 interface OnboardingProps {
   onComplete: () => void;
@@ -32,6 +32,7 @@ export function Onboarding({ onComplete }: OnboardingProps) {
   const [downloading, setDownloading] = useState(false);
   const [downloadProgress, setDownloadProgress] = useState(0);
   const [downloadComplete, setDownloadComplete] = useState(false);
+  const [enrollment, setEnrollment] = useState<'idle' | 'downloading' | 'recording' | 'done' | 'error'>('idle');
 
   // Listen for download progress
   useEffect(() => {
@@ -76,35 +77,46 @@ export function Onboarding({ onComplete }: OnboardingProps) {
     setDownloading(true);
     setDownloadProgress(0);
     try {
-      await invoke('download_model', { model: selectedModel });
+      await commands.downloadModel(selectedModel);
     } catch (e) {
       console.error('Download failed:', e);
       setDownloading(false);
     }
   };
 
+  const handleEnrollVoice = async () => {
+    setEnrollment('recording');
+    // The speaker model is downloaded before the first recording
+    const unlisten = await listenTo('enrollment:phase', (phase) => {
+      if (phase === 'downloading' || phase === 'recording') {
+        setEnrollment(phase);
+      }
+    });
+    try {
+      await commands.enrollVoiceProfile(null);
+      setEnrollment('done');
+    } catch (e) {
+      console.error('Voice enrollment failed:', e);
+      setEnrollment('error');
+    } finally {
+      unlisten();
+    }
+  };
+
   const saveSettings = async () => {
     try {
       // Get current settings
-      const settings = await invoke<Record<string, unknown>>('get_settings');
+      const settings = await commands.getSettings();
 
       // Update theme and model
-      const updatedSettings = {
+      await commands.saveSettings({
         ...settings,
-        ui: {
-          ...(settings.ui as Record<string, unknown>),
-          theme: selectedTheme,
-        },
+        ui: { ...settings.ui, theme: selectedTheme },
         transcription: {
-          ...(settings.transcription as Record<string, unknown>),
-          local: {
-            ...((settings.transcription as Record<string, unknown>).local as Record<string, unknown>),
-            model: selectedModel,
-          },
+          ...settings.transcription,
+          local: { ...settings.transcription?.local, model: selectedModel },
         },
-      };
-
-      await invoke('save_settings', { settings: updatedSettings });
+      });
     } catch (e) {
       console.error('Failed to save settings:', e);
     }
@@ -411,6 +423,29 @@ export function Onboarding({ onComplete }: OnboardingProps) {
                 <div className="text-gray-500 dark:text-gray-400 text-xs">Push-to-talk or Toggle mode</div>
               </div>
             </div>
+            <div className="flex items-start gap-3">
+              <div className="p-1.5 bg-green-100 dark:bg-green-900/30 rounded-lg">
+                <UserCheck className="w-4 h-4 text-green-600 dark:text-green-400" />
+              </div>
+              <div>
+                <div className="font-medium">Only transcribe your voice (optional)</div>
+                <div className="text-gray-500 dark:text-gray-400 text-xs">
+                  {enrollment === 'downloading' && 'Downloading the voice model...'}
+                  {enrollment === 'recording' && 'Listening... read a few sentences aloud for 10 seconds'}
+                  {enrollment === 'done' && 'Voice profile saved, other voices will be ignored'}
+                  {enrollment === 'error' && 'Not enough speech recorded, please try again'}
+                  {enrollment === 'idle' && 'Ignore other people speaking in the room'}
+                </div>
+                {(enrollment === 'idle' || enrollment === 'error') && (
+                  <button
+                    onClick={handleEnrollVoice}
+                    className="mt-1 text-xs font-medium text-blue-600 dark:text-blue-400 hover:underline"
+                  >
+                    Learn my voice
+                  </button>
+                )}
+              </div>
+            </div>
           </div>
         </div>
       </div>
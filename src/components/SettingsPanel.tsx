@@ -1,9 +1,23 @@
 import { useState, useEffect, useCallback } from 'react';
-import { invoke } from '@tauri-apps/api/core';
-import { useSettings } from '../hooks/useSettings';
+import {
+  useSettings,
+  type AudioSource,
+  type GamepadTrigger,
+  type MouseTrigger,
+  type NoiseSuppressor,
+  type VadEngine,
+} from '../hooks/useSettings';
 import { HotkeyInput } from './HotkeyInput';
-import { ModelSelector } from './ModelSelector';
+import { ModelSelector, formatBytes } from './ModelSelector';
 import { ProviderToggle } from './ProviderToggle';
+import { ShortcutProfiles } from './ShortcutProfiles';
+import {
+  commands,
+  type AudioDeviceDto,
+  type DeviceTestReport,
+  type PresetModel,
+  type QualityPreset,
+} from '../bindings';
 import { Sun, Moon, Monitor, Eye, EyeOff, Check, X, Loader2 } from 'lucide-react';
 
 // Separate component for Groq API Key management (uses secure storage)
@@ -19,7 +33,7 @@ function GroqApiKeyInput() {
   useEffect(() => {
     const checkApiKey = async () => {
       try {
-        const exists = await invoke<boolean>('has_groq_api_key');
+        const exists = await commands.hasGroqApiKey();
         setHasKey(exists);
       } catch (e) {
         console.error('Failed to check API key:', e);
@@ -39,9 +53,9 @@ function GroqApiKeyInput() {
 
     try {
       // Validate the key format first
-      await invoke('validate_groq_api_key', { apiKey: apiKey.trim() });
+      await commands.validateGroqApiKey(apiKey.trim());
       // Save the key securely
-      await invoke('set_groq_api_key', { apiKey: apiKey.trim() });
+      await commands.setGroqApiKey(apiKey.trim());
       setHasKey(true);
       setIsEditing(false);
       setApiKey('');
@@ -57,7 +71,7 @@ function GroqApiKeyInput() {
     setError(null);
 
     try {
-      await invoke('clear_groq_api_key');
+      await commands.clearGroqApiKey();
       setHasKey(false);
       setApiKey('');
     } catch (e) {
@@ -161,21 +175,108 @@ function GroqApiKeyInput() {
   );
 }
 
-interface AudioDevice {
-  name: string;
-  is_default: boolean;
+// Record a short sample from the selected microphone and show its levels
+function DeviceTest({ deviceId }: { deviceId: string | null }) {
+  const [testing, setTesting] = useState(false);
+  const [playback, setPlayback] = useState(true);
+  const [report, setReport] = useState<DeviceTestReport | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
+  const runTest = async () => {
+    setTesting(true);
+    setError(null);
+    setReport(null);
+    try {
+      setReport(await commands.testAudioDevice(deviceId, playback));
+    } catch (e) {
+      setError(String(e));
+    } finally {
+      setTesting(false);
+    }
+  };
+
+  return (
+    <div className="mt-2">
+      <div className="flex items-center gap-3">
+        <button
+          onClick={runTest}
+          disabled={testing}
+          className="flex items-center gap-1 px-3 py-1.5 text-sm bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md disabled:opacity-50"
+        >
+          {testing && <Loader2 className="w-4 h-4 animate-spin" />}
+          {testing ? 'Speak now...' : 'Test Microphone'}
+        </button>
+        <label className="flex items-center gap-2 text-sm">
+          <input
+            type="checkbox"
+            checked={playback}
+            onChange={(e) => setPlayback(e.target.checked)}
+            className="w-4 h-4 text-blue-600 rounded focus:ring-blue-500"
+          />
+          Play back the recording
+        </label>
+      </div>
+      {report && (
+        <div className="mt-2">
+          <div className="flex items-center gap-px h-8">
+            {report.waveform.map((peak, i) => (
+              <div
+                key={i}
+                className="flex-1 bg-blue-500 rounded-sm"
+                style={{ height: `${Math.max(4, peak * 100)}%` }}
+              />
+            ))}
+          </div>
+          <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+            Peak {report.peak_db.toFixed(1)} dB, average {report.rms_db.toFixed(1)} dB
+          </p>
+          {report.silent && (
+            <p className="mt-1 text-xs text-amber-600 dark:text-amber-400">
+              Nothing was heard. Is the microphone muted?
+            </p>
+          )}
+          {report.clipping && (
+            <p className="mt-1 text-xs text-amber-600 dark:text-amber-400">
+              The input is clipping. Lower the microphone gain.
+            </p>
+          )}
+        </div>
+      )}
+      {error && <p className="mt-1 text-xs text-red-500">{error}</p>}
+    </div>
+  );
 }
 
 export function SettingsPanel() {
-  const { settings, updateSettings, saving, error } = useSettings();
+  const { settings, updateSettings, applyQualityPreset, saving, error, shortcutConflicts } =
+    useSettings();
   const [activeTab, setActiveTab] = useState<'general' | 'transcription' | 'audio'>('general');
-  const [audioDevices, setAudioDevices] = useState<AudioDevice[]>([]);
+  const [audioDevices, setAudioDevices] = useState<AudioDeviceDto[]>([]);
+  const [pendingPreset, setPendingPreset] = useState<{
+    preset: QualityPreset;
+    model: PresetModel;
+  } | null>(null);
+
+  // Presets whose model isn't downloaded wait for the user to confirm the download
+  const choosePreset = async (preset: QualityPreset) => {
+    setPendingPreset(null);
+    try {
+      const model = await commands.presetMissingModel(preset);
+      if (model) {
+        setPendingPreset({ preset, model });
+        return;
+      }
+    } catch (e) {
+      console.error('Failed to check the preset model:', e);
+    }
+    applyQualityPreset(preset);
+  };
 
   // Fetch audio devices on mount
   useEffect(() => {
     const loadDevices = async () => {
       try {
-        const devices = await invoke<AudioDevice[]>('get_audio_devices');
+        const devices = await commands.getAudioDevices();
         setAudioDevices(devices);
       } catch (e) {
         console.error('Failed to load audio devices:', e);
@@ -222,11 +323,28 @@ export function SettingsPanel() {
       {/* Content */}
       <div className="p-6 space-y-6">
         {/* Error display */}
-        {error && (
+        {error && shortcutConflicts.length === 0 && (
           <div className="p-3 text-sm text-red-600 dark:text-red-400 bg-red-50 dark:bg-red-900/20 rounded-lg" role="alert">
             {error}
           </div>
         )}
+        {shortcutConflicts.length > 0 && (
+          <div className="p-3 text-sm text-red-600 dark:text-red-400 bg-red-50 dark:bg-red-900/20 rounded-lg" role="alert">
+            <p className="font-medium">Some shortcuts can't be used, so settings were not saved:</p>
+            <ul className="mt-1 list-disc list-inside">
+              {shortcutConflicts.map((conflict) => (
+                <li key={`${conflict.name}-${conflict.shortcut}`}>
+                  <span className="font-mono">{conflict.shortcut}</span> ({conflict.name}):{' '}
+                  {conflict.reason.type === 'duplicate'
+                    ? `already used by the ${conflict.reason.other} shortcut`
+                    : conflict.reason.type === 'unavailable'
+                      ? 'taken by the system or another application'
+                      : 'not a valid key combination'}
+                </li>
+              ))}
+            </ul>
+          </div>
+        )}
 
         {activeTab === 'general' && (
           <>
@@ -287,6 +405,186 @@ export function SettingsPanel() {
               />
             </div>
 
+            {/* Action shortcuts (empty = disabled) */}
+            <div className="space-y-2">
+              <label className="block text-sm font-medium">Action Shortcuts</label>
+              {(
+                [
+                  ['toggle_window', 'Show or hide window'],
+                  ['repaste_last', 'Re-paste last transcription'],
+                  ['switch_profile', 'Switch recording profile'],
+                  ['system_audio', 'Record system audio'],
+                ] as const
+              ).map(([key, label]) => (
+                <div key={key} className="flex items-center gap-2">
+                  <span className="w-56 text-sm text-gray-600 dark:text-gray-300">{label}</span>
+                  <div className="flex-1">
+                    <HotkeyInput
+                      value={settings.shortcuts[key] ?? ''}
+                      onChange={(shortcut) =>
+                        updateSettings({
+                          ...settings,
+                          shortcuts: { ...settings.shortcuts, [key]: shortcut },
+                        })
+                      }
+                    />
+                  </div>
+                  {settings.shortcuts[key] && (
+                    <button
+                      type="button"
+                      onClick={() =>
+                        updateSettings({
+                          ...settings,
+                          shortcuts: { ...settings.shortcuts, [key]: '' },
+                        })
+                      }
+                      className="text-xs text-gray-500 hover:text-gray-700 dark:text-gray-400"
+                    >
+                      Clear
+                    </button>
+                  )}
+                </div>
+              ))}
+            </div>
+
+            {/* Mouse and gamepad record triggers */}
+            <div className="space-y-2">
+              <label className="block text-sm font-medium">Other Record Triggers</label>
+              <div className="flex items-center gap-2">
+                <span className="w-56 text-sm text-gray-600 dark:text-gray-300">Mouse button</span>
+                <select
+                  value={settings.shortcuts.triggers?.mouse_button ?? ''}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      shortcuts: {
+                        ...settings.shortcuts,
+                        triggers: {
+                          gamepad_button: settings.shortcuts.triggers?.gamepad_button ?? null,
+                          mouse_button: (e.target.value || null) as MouseTrigger | null,
+                        },
+                      },
+                    })
+                  }
+                  className="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                >
+                  <option value="">None</option>
+                  <option value="middle">Middle button</option>
+                  <option value="back">Back side button</option>
+                  <option value="forward">Forward side button</option>
+                </select>
+              </div>
+              <div className="flex items-center gap-2">
+                <span className="w-56 text-sm text-gray-600 dark:text-gray-300">
+                  Gamepad or foot pedal
+                </span>
+                <select
+                  value={settings.shortcuts.triggers?.gamepad_button ?? ''}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      shortcuts: {
+                        ...settings.shortcuts,
+                        triggers: {
+                          mouse_button: settings.shortcuts.triggers?.mouse_button ?? null,
+                          gamepad_button: (e.target.value || null) as GamepadTrigger | null,
+                        },
+                      },
+                    })
+                  }
+                  className="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                >
+                  <option value="">None</option>
+                  <option value="any">Any button (pedals)</option>
+                  <option value="south">A / Cross</option>
+                  <option value="east">B / Circle</option>
+                  <option value="north">Y / Triangle</option>
+                  <option value="west">X / Square</option>
+                  <option value="left_bumper">Left bumper</option>
+                  <option value="right_bumper">Right bumper</option>
+                  <option value="left_trigger">Left trigger</option>
+                  <option value="right_trigger">Right trigger</option>
+                  <option value="select">Select / Back</option>
+                  <option value="start">Start</option>
+                </select>
+              </div>
+              <p className="text-xs text-gray-500 dark:text-gray-400">
+                Act like the record shortcut. Mouse buttons are not available on macOS, and side
+                buttons keep navigating back or forward in the focused app.
+              </p>
+            </div>
+
+            {/* Record shortcut gestures */}
+            <div className="space-y-2">
+              <div className="flex items-start gap-3">
+                <input
+                  type="checkbox"
+                  id="shortcut-gestures"
+                  checked={settings.shortcuts.gestures?.enabled ?? false}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      shortcuts: {
+                        ...settings.shortcuts,
+                        gestures: {
+                          double_tap_ms: 300,
+                          long_press_ms: 400,
+                          ...settings.shortcuts.gestures,
+                          enabled: e.target.checked,
+                        },
+                      },
+                    })
+                  }
+                  className="rounded text-blue-600 mt-0.5"
+                />
+                <div>
+                  <label htmlFor="shortcut-gestures" className="font-medium text-sm cursor-pointer">
+                    Record shortcut gestures
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Tap to start or stop, double-tap to re-paste the last transcription, hold to
+                    record only while held
+                  </p>
+                </div>
+              </div>
+              {settings.shortcuts.gestures?.enabled && (
+                <div className="ml-7 flex flex-wrap gap-4">
+                  {(
+                    [
+                      ['double_tap_ms', 'Double-tap window', 100, 1000],
+                      ['long_press_ms', 'Long-press after', 200, 3000],
+                    ] as const
+                  ).map(([key, label, min, max]) => (
+                    <label key={key} className="flex items-center gap-2 text-sm">
+                      {label}
+                      <input
+                        type="number"
+                        min={min}
+                        max={max}
+                        step={50}
+                        value={settings.shortcuts.gestures?.[key]}
+                        onChange={(e) =>
+                          settings.shortcuts.gestures &&
+                          updateSettings({
+                            ...settings,
+                            shortcuts: {
+                              ...settings.shortcuts,
+                              gestures: {
+                                ...settings.shortcuts.gestures,
+                                [key]: Number(e.target.value),
+                              },
+                            },
+                          })
+                        }
+                        className="w-24 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                      />
+                      ms
+                    </label>
+                  ))}
+                </div>
+              )}
+            </div>
+
             {/* UI Options with descriptions */}
             <div className="space-y-4">
               <div className="flex items-start gap-3">
@@ -434,15 +732,132 @@ export function SettingsPanel() {
                 onChange={(provider) =>
                   updateSettings({
                     ...settings,
-                    transcription: { ...settings.transcription, provider },
+                    transcription: {
+                      ...settings.transcription,
+                      provider,
+                      // Deepgram can't translate
+                      ...(provider === 'deepgram' ? { translate: false } : {}),
+                    },
+                  })
+                }
+              />
+            </div>
+
+            {/* Translation */}
+            <div className="flex items-start gap-3">
+              <input
+                type="checkbox"
+                id="translate"
+                checked={settings.transcription.translate ?? false}
+                disabled={settings.transcription.provider === 'deepgram'}
+                onChange={(e) =>
+                  updateSettings({
+                    ...settings,
+                    transcription: { ...settings.transcription, translate: e.target.checked },
+                  })
+                }
+                className="rounded text-blue-600 mt-0.5"
+              />
+              <div>
+                <label htmlFor="translate" className="font-medium text-sm cursor-pointer">
+                  Translate to English
+                </label>
+                <p className="text-xs text-gray-500 dark:text-gray-400">
+                  Output an English translation of what you say instead of a transcript (not
+                  available with Deepgram; on Groq it needs whisper-large-v3)
+                </p>
+              </div>
+            </div>
+
+            {/* Prompt and vocabulary */}
+            <div>
+              <label className="block text-sm font-medium mb-2">Initial Prompt</label>
+              <textarea
+                value={settings.transcription.initial_prompt ?? ''}
+                maxLength={800}
+                rows={2}
+                placeholder="Weekly engineering sync about the billing service."
+                onChange={(e) =>
+                  updateSettings({
+                    ...settings,
+                    transcription: { ...settings.transcription, initial_prompt: e.target.value },
                   })
                 }
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500 text-sm"
               />
+              <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                Context given to the model before each recording. Its spelling and punctuation
+                steer the transcript (not used by Deepgram).
+              </p>
+            </div>
+
+            <div>
+              <label className="block text-sm font-medium mb-2">Vocabulary</label>
+              <textarea
+                value={(settings.transcription.vocabulary ?? []).join('\n')}
+                rows={3}
+                placeholder={'Kubernetes\nDr. Müller'}
+                onChange={(e) =>
+                  updateSettings({
+                    ...settings,
+                    transcription: {
+                      ...settings.transcription,
+                      vocabulary: e.target.value.split('\n'),
+                    },
+                  })
+                }
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500 text-sm"
+              />
+              <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                Names and jargon to recognize, one per line. Added to the prompt, and sent to
+                Deepgram as keywords.
+              </p>
             </div>
 
             {/* Local Settings */}
             {settings.transcription.provider === 'local' && (
               <>
+                <div>
+                  <label className="block text-sm font-medium mb-2">Quality</label>
+                  <select
+                    value={settings.transcription.quality_preset ?? 'custom'}
+                    onChange={(e) => choosePreset(e.target.value as QualityPreset)}
+                    className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                  >
+                    <option value="fast">Fast</option>
+                    <option value="balanced">Balanced</option>
+                    <option value="accurate">Accurate</option>
+                    <option value="custom">Custom</option>
+                  </select>
+                  {pendingPreset && (
+                    <div className="mt-2 flex items-center gap-2">
+                      <p className="flex-1 text-xs text-amber-600 dark:text-amber-400">
+                        The {pendingPreset.preset} preset uses the {pendingPreset.model.model} model
+                        ({formatBytes(pendingPreset.model.size_bytes)}), which isn't downloaded yet.
+                      </p>
+                      <button
+                        onClick={() => {
+                          applyQualityPreset(pendingPreset.preset, true);
+                          setPendingPreset(null);
+                        }}
+                        className="px-3 py-1.5 text-sm bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md"
+                      >
+                        Download and switch
+                      </button>
+                      <button
+                        onClick={() => setPendingPreset(null)}
+                        className="px-3 py-1.5 text-sm bg-gray-100 dark:bg-gray-700 hover:bg-gray-200 dark:hover:bg-gray-600 rounded-md"
+                      >
+                        Cancel
+                      </button>
+                    </div>
+                  )}
+                  <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                    Presets pick the model, decoding and voice detection for you. Changing any of
+                    them switches to Custom.
+                  </p>
+                </div>
+
                 <div>
                   <label className="block text-sm font-medium mb-2">Whisper Model</label>
                   <ModelSelector
@@ -452,7 +867,17 @@ export function SettingsPanel() {
                         ...settings,
                         transcription: {
                           ...settings.transcription,
-                          local: { ...settings.transcription.local, model },
+                          local: { ...settings.transcription.local, model, custom_model: null },
+                        },
+                      })
+                    }
+                    customModel={settings.transcription.local.custom_model}
+                    onCustomModelChange={(id) =>
+                      updateSettings({
+                        ...settings,
+                        transcription: {
+                          ...settings.transcription,
+                          local: { ...settings.transcription.local, custom_model: id },
                         },
                       })
                     }
@@ -485,6 +910,108 @@ export function SettingsPanel() {
                     </p>
                   </div>
                 </div>
+
+                {/* Decoding */}
+                <div>
+                  <label className="block text-sm font-medium mb-2">Decoding</label>
+                  <div className="flex items-center gap-2">
+                    <select
+                      value={(settings.transcription.local.beam_size ?? 1) > 1 ? 'beam' : 'greedy'}
+                      onChange={(e) =>
+                        updateSettings({
+                          ...settings,
+                          transcription: {
+                            ...settings.transcription,
+                            local: {
+                              ...settings.transcription.local,
+                              beam_size: e.target.value === 'beam' ? 5 : 1,
+                            },
+                          },
+                        })
+                      }
+                      className="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                    >
+                      <option value="greedy">Greedy (faster)</option>
+                      <option value="beam">Beam search (more accurate)</option>
+                    </select>
+                    {(settings.transcription.local.beam_size ?? 1) > 1 && (
+                      <>
+                        <input
+                          type="number"
+                          min={2}
+                          max={8}
+                          value={settings.transcription.local.beam_size}
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              transcription: {
+                                ...settings.transcription,
+                                local: {
+                                  ...settings.transcription.local,
+                                  beam_size: Number(e.target.value),
+                                },
+                              },
+                            })
+                          }
+                          className="w-20 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        <span className="text-xs text-gray-500 dark:text-gray-400">beams</span>
+                      </>
+                    )}
+                  </div>
+                  <div className="mt-2 grid grid-cols-2 gap-2">
+                    <label className="text-xs text-gray-500 dark:text-gray-400">
+                      Temperature
+                      <input
+                        type="number"
+                        min={0}
+                        max={1}
+                        step={0.1}
+                        value={settings.transcription.local.temperature ?? 0}
+                        onChange={(e) =>
+                          updateSettings({
+                            ...settings,
+                            transcription: {
+                              ...settings.transcription,
+                              local: {
+                                ...settings.transcription.local,
+                                temperature: Number(e.target.value),
+                              },
+                            },
+                          })
+                        }
+                        className="mt-1 w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+                      />
+                    </label>
+                    <label className="text-xs text-gray-500 dark:text-gray-400">
+                      No-speech threshold
+                      <input
+                        type="number"
+                        min={0}
+                        max={1}
+                        step={0.05}
+                        value={settings.transcription.local.no_speech_threshold ?? 0.6}
+                        onChange={(e) =>
+                          updateSettings({
+                            ...settings,
+                            transcription: {
+                              ...settings.transcription,
+                              local: {
+                                ...settings.transcription.local,
+                                no_speech_threshold: Number(e.target.value),
+                              },
+                            },
+                          })
+                        }
+                        className="mt-1 w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 text-sm text-gray-900 dark:text-gray-100"
+                      />
+                    </label>
+                  </div>
+                  <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                    Higher temperature gives more varied guesses; 0 always picks the most likely
+                    words. Segments above the no-speech threshold are dropped as silence.
+                  </p>
+                </div>
               </>
             )}
 
@@ -492,6 +1019,19 @@ export function SettingsPanel() {
             {settings.transcription.provider === 'groq' && (
               <GroqApiKeyInput />
             )}
+
+            {/* Shortcut profiles */}
+            <div>
+              <label className="block text-sm font-medium mb-2">Shortcut Profiles</label>
+              <p className="mb-2 text-xs text-gray-500 dark:text-gray-400">
+                Extra record shortcuts with their own provider and language, e.g. Groq with
+                translation on Ctrl+Shift+Space
+              </p>
+              <ShortcutProfiles
+                profiles={settings.profiles ?? []}
+                onChange={(profiles) => updateSettings({ ...settings, profiles })}
+              />
+            </div>
           </>
         )}
 
@@ -525,6 +1065,340 @@ export function SettingsPanel() {
                   No additional microphones detected
                 </p>
               )}
+              <DeviceTest deviceId={settings.audio.input_device} />
+            </div>
+
+            {/* Audio Source */}
+            <div>
+              <label className="block text-sm font-medium mb-2">Record From</label>
+              <select
+                value={settings.audio.source ?? 'microphone'}
+                onChange={(e) =>
+                  updateSettings({
+                    ...settings,
+                    audio: { ...settings.audio, source: e.target.value as AudioSource },
+                  })
+                }
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+              >
+                <option value="microphone">Input device</option>
+                <option value="system-audio">System audio (meetings, calls)</option>
+              </select>
+              <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                System audio uses loopback on Windows. On macOS and Linux it needs a monitor
+                or loopback input device such as BlackHole.
+              </p>
+            </div>
+
+            {/* Noise Suppression */}
+            <div>
+              <label className="block text-sm font-medium mb-2">Noise Suppression</label>
+              <select
+                value={
+                  settings.audio.pipeline?.find((s) => s.stage === 'noise_suppression')?.enabled
+                    ? settings.audio.noise_suppressor ?? 'filter'
+                    : 'off'
+                }
+                onChange={(e) => {
+                  const value = e.target.value;
+                  updateSettings({
+                    ...settings,
+                    audio: {
+                      ...settings.audio,
+                      pipeline: settings.audio.pipeline?.map((s) =>
+                        s.stage === 'noise_suppression' ? { ...s, enabled: value !== 'off' } : s
+                      ),
+                      noise_suppressor:
+                        value === 'off'
+                          ? settings.audio.noise_suppressor
+                          : (value as NoiseSuppressor),
+                    },
+                  });
+                }}
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+              >
+                <option value="off">Off</option>
+                <option value="filter">Basic filter</option>
+                <option value="rnnoise">RNNoise (fans, keyboards)</option>
+              </select>
+              <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                RNNoise removes steady background noise more thoroughly but takes longer.
+              </p>
+            </div>
+
+            {/* Speech Detection */}
+            {settings.audio.vad && (
+              <div>
+                <label className="block text-sm font-medium mb-2">Speech Detection</label>
+                <select
+                  value={settings.audio.vad.engine ?? 'webrtc'}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      audio: {
+                        ...settings.audio,
+                        vad: { ...settings.audio.vad!, engine: e.target.value as VadEngine },
+                      },
+                    })
+                  }
+                  className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                >
+                  <option value="webrtc">WebRTC (fast)</option>
+                  <option value="silero">Silero (better with music and soft speech)</option>
+                </select>
+                <p className="mt-1 text-xs text-gray-500 dark:text-gray-400">
+                  The Silero model (about 2 MB) is downloaded when selected; WebRTC is used
+                  until it is ready.
+                </p>
+              </div>
+            )}
+
+            <div>
+              <label className="block text-sm font-medium mb-2">Insert Text By</label>
+              <select
+                value={settings.output.mode ?? 'paste'}
+                onChange={(e) =>
+                  updateSettings({
+                    ...settings,
+                    output: { ...settings.output, mode: e.target.value as 'paste' | 'type' },
+                  })
+                }
+                className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+              >
+                <option value="paste">Pasting (uses the clipboard)</option>
+                <option value="type">Typing (clipboard untouched)</option>
+              </select>
+              {settings.output.mode === 'type' && (
+                <div className="mt-2 flex items-center gap-2">
+                  <input
+                    type="number"
+                    min={5}
+                    max={1000}
+                    value={settings.output.typing_rate ?? 100}
+                    onChange={(e) =>
+                      updateSettings({
+                        ...settings,
+                        output: { ...settings.output, typing_rate: Number(e.target.value) },
+                      })
+                    }
+                    className="w-24 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                  />
+                  <span className="text-xs text-gray-500 dark:text-gray-400">
+                    characters per second
+                  </span>
+                </div>
+              )}
+            </div>
+
+            <div>
+              <label className="block text-sm font-medium mb-2">Send Transcriptions To</label>
+              {(() => {
+                const sinks = settings.output.sinks ?? [{ type: 'paste' as const }];
+                const fileSink = sinks.find((sink) => sink.type === 'append-to-file');
+                const journalSink = sinks.find((sink) => sink.type === 'markdown-journal');
+                const webhookSink = sinks.find((sink) => sink.type === 'webhook');
+                const target =
+                  sinks.length === 0 ? 'none' : sinks.length === 1 ? sinks[0].type : 'custom';
+                return (
+                  <>
+                    <select
+                      value={target}
+                      onChange={(e) => {
+                        const value = e.target.value;
+                        const next =
+                          value === 'none'
+                            ? []
+                            : value === 'append-to-file'
+                              ? [
+                                  fileSink ?? {
+                                    type: 'append-to-file' as const,
+                                    path: '',
+                                    header: true,
+                                  },
+                                ]
+                              : value === 'markdown-journal'
+                                ? [
+                                    journalSink ?? {
+                                      type: 'markdown-journal' as const,
+                                      folder: '',
+                                      template: '- {time} {text} {tags}',
+                                      tags: [],
+                                    },
+                                  ]
+                                : value === 'webhook'
+                                  ? [
+                                      webhookSink ?? {
+                                        type: 'webhook' as const,
+                                        url: '',
+                                        auth_header: null,
+                                        max_retries: 2,
+                                      },
+                                    ]
+                                  : [{ type: value as 'paste' | 'clipboard' }];
+                        updateSettings({
+                          ...settings,
+                          output: { ...settings.output, sinks: next },
+                        });
+                      }}
+                      className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                    >
+                      <option value="paste">Focused app (paste or type)</option>
+                      <option value="clipboard">Clipboard only</option>
+                      <option value="append-to-file">Notes file</option>
+                      <option value="markdown-journal">Markdown daily note</option>
+                      <option value="webhook">Webhook</option>
+                      <option value="none">Nowhere (history only)</option>
+                      {target === 'custom' && <option value="custom">Custom (settings file)</option>}
+                    </select>
+                    {target === 'append-to-file' && fileSink?.type === 'append-to-file' && (
+                      <div className="mt-2 space-y-2">
+                        <input
+                          type="text"
+                          value={fileSink.path}
+                          placeholder="C:\Notes\{date}.md"
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              output: {
+                                ...settings.output,
+                                sinks: [{ ...fileSink, path: e.target.value }],
+                              },
+                            })
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        <label className="flex items-center gap-2 text-xs text-gray-500 dark:text-gray-400">
+                          <input
+                            type="checkbox"
+                            checked={fileSink.header}
+                            onChange={(e) =>
+                              updateSettings({
+                                ...settings,
+                                output: {
+                                  ...settings.output,
+                                  sinks: [{ ...fileSink, header: e.target.checked }],
+                                },
+                              })
+                            }
+                            className="rounded text-blue-600"
+                          />
+                          Write a timestamp before each transcription ({'{date}'} in the path
+                          expands to the day)
+                        </label>
+                      </div>
+                    )}
+                    {target === 'markdown-journal' && journalSink?.type === 'markdown-journal' && (
+                      <div className="mt-2 space-y-2">
+                        <input
+                          type="text"
+                          value={journalSink.folder}
+                          placeholder="Daily notes folder (e.g. your Obsidian vault's Daily folder)"
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              output: {
+                                ...settings.output,
+                                sinks: [{ ...journalSink, folder: e.target.value }],
+                              },
+                            })
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        <input
+                          type="text"
+                          value={journalSink.template}
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              output: {
+                                ...settings.output,
+                                sinks: [{ ...journalSink, template: e.target.value }],
+                              },
+                            })
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 font-mono text-sm"
+                        />
+                        <input
+                          type="text"
+                          value={journalSink.tags.join(' ')}
+                          placeholder="Tags, e.g. dictation inbox"
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              output: {
+                                ...settings.output,
+                                sinks: [
+                                  {
+                                    ...journalSink,
+                                    tags: e.target.value.split(/\s+/).filter(Boolean),
+                                  },
+                                ],
+                              },
+                            })
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        <p className="text-xs text-gray-500 dark:text-gray-400">
+                          Each day gets a YYYY-MM-DD.md note. The template can use {'{text}'},{' '}
+                          {'{date}'}, {'{time}'} and {'{tags}'}; dates and times are in UTC.
+                        </p>
+                      </div>
+                    )}
+                    {target === 'webhook' && webhookSink?.type === 'webhook' && (
+                      <div className="mt-2 space-y-2">
+                        <input
+                          type="url"
+                          value={webhookSink.url}
+                          placeholder="https://example.com/webhook"
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              output: {
+                                ...settings.output,
+                                sinks: [{ ...webhookSink, url: e.target.value }],
+                              },
+                            })
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        <input
+                          type="text"
+                          value={webhookSink.auth_header ?? ''}
+                          placeholder="Auth header name (optional, e.g. Authorization)"
+                          onChange={(e) =>
+                            updateSettings({
+                              ...settings,
+                              output: {
+                                ...settings.output,
+                                sinks: [{ ...webhookSink, auth_header: e.target.value || null }],
+                              },
+                            })
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        {webhookSink.auth_header && (
+                          <input
+                            type="password"
+                            placeholder="Header value (stored in the credential manager for this URL)"
+                            onBlur={(e) =>
+                              e.target.value &&
+                              commands
+                                .setWebhookAuth(webhookSink.url, e.target.value)
+                                .catch(console.error)
+                            }
+                            className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                          />
+                        )}
+                        <p className="text-xs text-gray-500 dark:text-gray-400">
+                          Each transcription is posted as JSON with its text, timestamp,
+                          duration and language. Failed deliveries are retried{' '}
+                          {webhookSink.max_retries} times.
+                        </p>
+                      </div>
+                    )}
+                  </>
+                );
+              })()}
             </div>
 
             {/* Output Options with descriptions */}
@@ -574,6 +1448,237 @@ export function SettingsPanel() {
                   </p>
                 </div>
               </div>
+
+              <div className="flex items-start gap-3">
+                <input
+                  type="checkbox"
+                  id="voice-commands"
+                  checked={settings.output.voice_commands?.enabled ?? false}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      output: {
+                        ...settings.output,
+                        voice_commands: {
+                          commands: settings.output.voice_commands?.commands ?? [],
+                          enabled: e.target.checked,
+                        },
+                      },
+                    })
+                  }
+                  className="rounded text-blue-600 mt-0.5"
+                />
+                <div>
+                  <label htmlFor="voice-commands" className="font-medium text-sm cursor-pointer">
+                    Spoken punctuation
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Say "comma", "period" or "new line" to insert them
+                  </p>
+                </div>
+              </div>
+
+              <div className="flex items-start gap-3">
+                <input
+                  type="checkbox"
+                  id="llm-cleanup"
+                  checked={settings.output.cleanup?.enabled ?? false}
+                  onChange={(e) =>
+                    settings.output.cleanup &&
+                    updateSettings({
+                      ...settings,
+                      output: {
+                        ...settings.output,
+                        cleanup: { ...settings.output.cleanup, enabled: e.target.checked },
+                      },
+                    })
+                  }
+                  className="rounded text-blue-600 mt-0.5"
+                />
+                <div className="flex-1">
+                  <label htmlFor="llm-cleanup" className="font-medium text-sm cursor-pointer">
+                    AI cleanup
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Rewrite transcripts with a language model before output; the raw text is
+                    used if it fails
+                  </p>
+                  {settings.output.cleanup?.enabled && (
+                    <select
+                      value={settings.output.cleanup.provider}
+                      onChange={(e) =>
+                        settings.output.cleanup &&
+                        updateSettings({
+                          ...settings,
+                          output: {
+                            ...settings.output,
+                            cleanup: {
+                              ...settings.output.cleanup,
+                              provider: e.target.value as 'groq' | 'openai' | 'ollama',
+                            },
+                          },
+                        })
+                      }
+                      className="mt-2 w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700 focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                    >
+                      <option value="groq">Groq</option>
+                      <option value="openai">OpenAI</option>
+                      <option value="ollama">Ollama (local)</option>
+                    </select>
+                  )}
+                </div>
+              </div>
+
+              <div className="flex items-start gap-3">
+                <input
+                  type="checkbox"
+                  id="mqtt"
+                  checked={settings.mqtt?.enabled ?? false}
+                  onChange={(e) =>
+                    settings.mqtt &&
+                    updateSettings({
+                      ...settings,
+                      mqtt: { ...settings.mqtt, enabled: e.target.checked },
+                    })
+                  }
+                  className="rounded text-blue-600 mt-0.5"
+                />
+                <div className="flex-1">
+                  <label htmlFor="mqtt" className="font-medium text-sm cursor-pointer">
+                    Publish to MQTT
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Send recording state and transcripts to a home automation broker
+                  </p>
+                  {settings.mqtt?.enabled && (
+                    <div className="mt-2 space-y-2">
+                      <div className="flex gap-2">
+                        <input
+                          type="text"
+                          value={settings.mqtt.host}
+                          placeholder="Broker host"
+                          onChange={(e) =>
+                            settings.mqtt &&
+                            updateSettings({
+                              ...settings,
+                              mqtt: { ...settings.mqtt, host: e.target.value },
+                            })
+                          }
+                          className="flex-1 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                        <input
+                          type="number"
+                          min={1}
+                          max={65535}
+                          value={settings.mqtt.port}
+                          onChange={(e) =>
+                            settings.mqtt &&
+                            updateSettings({
+                              ...settings,
+                              mqtt: { ...settings.mqtt, port: Number(e.target.value) },
+                            })
+                          }
+                          className="w-24 px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                      </div>
+                      <input
+                        type="text"
+                        value={settings.mqtt.topic}
+                        placeholder="Topic prefix"
+                        onChange={(e) =>
+                          settings.mqtt &&
+                          updateSettings({
+                            ...settings,
+                            mqtt: { ...settings.mqtt, topic: e.target.value },
+                          })
+                        }
+                        className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                      />
+                      <input
+                        type="text"
+                        value={settings.mqtt.username ?? ''}
+                        placeholder="User name (optional)"
+                        onChange={(e) =>
+                          settings.mqtt &&
+                          updateSettings({
+                            ...settings,
+                            mqtt: { ...settings.mqtt, username: e.target.value || null },
+                          })
+                        }
+                        className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                      />
+                      {settings.mqtt.username && (
+                        <input
+                          type="password"
+                          placeholder="Password (stored in the credential manager)"
+                          onBlur={(e) =>
+                            e.target.value &&
+                            commands.setMqttPassword(e.target.value).catch(console.error)
+                          }
+                          className="w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700"
+                        />
+                      )}
+                      <p className="text-xs text-gray-500 dark:text-gray-400">
+                        State goes to {settings.mqtt.topic}/state and transcripts to{' '}
+                        {settings.mqtt.topic}/transcript.
+                      </p>
+                    </div>
+                  )}
+                </div>
+              </div>
+
+              <div className="flex items-start gap-3">
+                <input
+                  type="checkbox"
+                  id="no-disk-audio"
+                  checked={settings.privacy?.no_disk_audio ?? false}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      privacy: {
+                        encrypt_history: settings.privacy?.encrypt_history ?? false,
+                        no_disk_audio: e.target.checked,
+                      },
+                    })
+                  }
+                  className="rounded text-blue-600 mt-0.5"
+                />
+                <div>
+                  <label htmlFor="no-disk-audio" className="font-medium text-sm cursor-pointer">
+                    Keep audio in memory only
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Recordings are never written to disk; history keeps the text without
+                    playback
+                  </p>
+                </div>
+              </div>
+
+              <div className="flex items-start gap-3">
+                <input
+                  type="checkbox"
+                  id="encrypt-history"
+                  checked={settings.privacy?.encrypt_history ?? false}
+                  onChange={(e) =>
+                    updateSettings({
+                      ...settings,
+                      privacy: {
+                        no_disk_audio: settings.privacy?.no_disk_audio ?? false,
+                        encrypt_history: e.target.checked,
+                      },
+                    })
+                  }
+                  className="rounded text-blue-600 mt-0.5"
+                />
+                <div>
+                  <label htmlFor="encrypt-history" className="font-medium text-sm cursor-pointer">
+                    Encrypt history
+                  </label>
+                  <p className="text-xs text-gray-500 dark:text-gray-400">
+                    Store transcripts encrypted with a key kept in the credential manager
+                  </p>
+                </div>
+              </div>
             </div>
           </>
         )}
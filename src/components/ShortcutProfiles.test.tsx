@@ -0,0 +1,66 @@
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { render, screen, fireEvent } from '@testing-library/react';
+import { ShortcutProfiles } from './ShortcutProfiles';
+import type { RecordingProfile } from '../hooks/useSettings';
+
+const groqTranslate: RecordingProfile = {
+  id: 'translate',
+  name: 'Translate',
+  shortcut: 'Ctrl+Shift+Space',
+  language: null,
+  provider: 'groq',
+  local_model: null,
+  groq_model: null,
+  translate: true,
+};
+
+describe('ShortcutProfiles', () => {
+  const mockOnChange = vi.fn();
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it('should render each profile with its overrides', () => {
+    render(<ShortcutProfiles profiles={[groqTranslate]} onChange={mockOnChange} />);
+
+    expect(screen.getByDisplayValue('Translate')).toBeInTheDocument();
+    expect(screen.getByLabelText('Provider')).toHaveValue('groq');
+    expect(screen.getByLabelText('Language')).toHaveValue('');
+    expect(screen.getByLabelText('Translation')).toHaveValue('translate');
+  });
+
+  it('should add a profile using the global settings', () => {
+    render(<ShortcutProfiles profiles={[]} onChange={mockOnChange} />);
+
+    fireEvent.click(screen.getByText('Add shortcut'));
+
+    const [profiles] = mockOnChange.mock.calls[0];
+    expect(profiles).toHaveLength(1);
+    expect(profiles[0]).toMatchObject({ provider: null, language: null, translate: null });
+  });
+
+  it('should update the language override', () => {
+    render(<ShortcutProfiles profiles={[groqTranslate]} onChange={mockOnChange} />);
+
+    fireEvent.change(screen.getByLabelText('Language'), { target: { value: 'fr' } });
+
+    expect(mockOnChange).toHaveBeenCalledWith([{ ...groqTranslate, language: 'fr' }]);
+  });
+
+  it('should update the translation override', () => {
+    render(<ShortcutProfiles profiles={[groqTranslate]} onChange={mockOnChange} />);
+
+    fireEvent.change(screen.getByLabelText('Translation'), { target: { value: '' } });
+
+    expect(mockOnChange).toHaveBeenCalledWith([{ ...groqTranslate, translate: null }]);
+  });
+
+  it('should remove a profile', () => {
+    render(<ShortcutProfiles profiles={[groqTranslate]} onChange={mockOnChange} />);
+
+    fireEvent.click(screen.getByLabelText('Remove Translate'));
+
+    expect(mockOnChange).toHaveBeenCalledWith([]);
+  });
+});
@@ -0,0 +1,140 @@
+import { Plus, Trash2 } from 'lucide-react';
+import type { RecordingProfile } from '../hooks/useSettings';
+import { HotkeyInput } from './HotkeyInput';
+
+interface ShortcutProfilesProps {
+  profiles: RecordingProfile[];
+  onChange: (profiles: RecordingProfile[]) => void;
+}
+
+const LANGUAGES: [string, string][] = [
+  ['auto', 'Auto-detect'],
+  ['en', 'English'],
+  ['fr', 'French'],
+  ['de', 'German'],
+  ['es', 'Spanish'],
+  ['it', 'Italian'],
+  ['pt', 'Portuguese'],
+  ['ja', 'Japanese'],
+  ['ko', 'Korean'],
+  ['zh', 'Chinese'],
+];
+
+const inputClass =
+  'w-full px-3 py-2 border border-gray-300 dark:border-gray-600 rounded-md bg-white dark:bg-gray-700';
+
+function newProfile(count: number): RecordingProfile {
+  return {
+    id: `profile-${Date.now()}`,
+    name: `Profile ${count + 1}`,
+    shortcut: '',
+    language: null,
+    provider: null,
+    local_model: null,
+    groq_model: null,
+    translate: null,
+  };
+}
+
+/** Extra record shortcuts, each with its own provider and language */
+export function ShortcutProfiles({ profiles, onChange }: ShortcutProfilesProps) {
+  const update = (index: number, changes: Partial<RecordingProfile>) =>
+    onChange(profiles.map((p, i) => (i === index ? { ...p, ...changes } : p)));
+
+  return (
+    <div className="space-y-3">
+      {profiles.map((profile, index) => (
+        <div
+          key={profile.id}
+          className="p-3 space-y-2 border border-gray-200 dark:border-gray-700 rounded-lg"
+        >
+          <div className="flex items-center gap-2">
+            <input
+              type="text"
+              value={profile.name}
+              aria-label="Profile name"
+              onChange={(e) => update(index, { name: e.target.value })}
+              className={inputClass}
+            />
+            <button
+              type="button"
+              aria-label={`Remove ${profile.name}`}
+              onClick={() => onChange(profiles.filter((_, i) => i !== index))}
+              className="p-2 text-gray-500 hover:text-red-600"
+            >
+              <Trash2 className="w-4 h-4" />
+            </button>
+          </div>
+          <HotkeyInput
+            value={profile.shortcut}
+            onChange={(shortcut) => update(index, { shortcut })}
+          />
+          <div className="flex gap-2">
+            <select
+              value={profile.provider ?? ''}
+              aria-label="Provider"
+              onChange={(e) =>
+                update(index, {
+                  provider: (e.target.value || null) as RecordingProfile['provider'],
+                })
+              }
+              className={inputClass}
+            >
+              <option value="">Default provider</option>
+              <option value="local">Local</option>
+              <option value="groq">Groq</option>
+              <option value="deepgram">Deepgram</option>
+              <option value="custom">Custom server</option>
+            </select>
+            <select
+              value={profile.language ?? ''}
+              aria-label="Language"
+              onChange={(e) => update(index, { language: e.target.value || null })}
+              className={inputClass}
+            >
+              <option value="">Default language</option>
+              {LANGUAGES.map(([code, label]) => (
+                <option key={code} value={code}>
+                  {label}
+                </option>
+              ))}
+            </select>
+          </div>
+          <select
+            value={profile.translate === null ? '' : profile.translate ? 'translate' : 'original'}
+            aria-label="Translation"
+            onChange={(e) =>
+              update(index, {
+                translate: e.target.value === '' ? null : e.target.value === 'translate',
+              })
+            }
+            className={inputClass}
+          >
+            <option value="">Default translation</option>
+            <option value="translate">Translate to English</option>
+            <option value="original">Keep the spoken language</option>
+          </select>
+          <label className="flex items-center gap-2 text-sm">
+            <input
+              type="checkbox"
+              checked={profile.source === 'system-audio'}
+              onChange={(e) =>
+                update(index, { source: e.target.checked ? 'system-audio' : null })
+              }
+              className="rounded text-blue-600"
+            />
+            Record system audio instead of the microphone
+          </label>
+        </div>
+      ))}
+      <button
+        type="button"
+        onClick={() => onChange([...profiles, newProfile(profiles.length)])}
+        className="flex items-center gap-1 text-sm text-blue-600 hover:text-blue-700 dark:text-blue-400"
+      >
+        <Plus className="w-4 h-4" />
+        Add shortcut
+      </button>
+    </div>
+  );
+}
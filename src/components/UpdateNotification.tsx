@@ -1,7 +1,7 @@
 import { useEffect, useState } from 'react';
 import { listen } from '@tauri-apps/api/event';
-import { invoke } from '@tauri-apps/api/core';
 import { Download, X, RefreshCw, CheckCircle } from 'lucide-react';
+import { commands } from '../bindings';
 
 interface UpdateInfo {
   currentVersion: string;
@@ -58,7 +58,7 @@ export function UpdateNotification() {
     setError(null);
 
     try {
-      await invoke('install_update');
+      await commands.installUpdate();
     } catch (e) {
       setError(e as string);
       setState('available');
@@ -66,7 +66,7 @@ export function UpdateNotification() {
   };
 
   const handleRestart = async () => {
-    await invoke('restart_app');
+    await commands.restartApp();
   };
 
   const handleDismiss = () => {
@@ -75,7 +75,11 @@ describe('useRecording', () => {
       await result.current.startRecording();
     });
 
-    expect(invoke).toHaveBeenCalledWith('start_recording');
+    expect(invoke).toHaveBeenCalledWith('start_recording', {
+      profile: undefined,
+      source: undefined,
+      translate: undefined,
+    });
 
     unmount();
   });
@@ -1,6 +1,6 @@
 import { useState, useCallback, useEffect, useRef } from 'react';
-import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
+import { commands } from '../bindings';
 
 interface RecordingState {
   state: 'idle' | 'recording' | 'processing' | 'error';
@@ -75,7 +75,7 @@ export function useRecording() {
 
   const startRecording = useCallback(async () => {
     try {
-      await invoke('start_recording');
+      await commands.startRecording();
       // State will be updated via event listener
     } catch (error) {
       setState({ state: 'error', error: String(error) });
@@ -84,7 +84,7 @@ export function useRecording() {
 
   const stopRecording = useCallback(async () => {
     try {
-      await invoke('stop_recording');
+      await commands.stopRecording();
       // State will be updated via event listener
     } catch (error) {
       setState({ state: 'error', error: String(error) });
@@ -93,7 +93,7 @@ export function useRecording() {
 
   const cancelRecording = useCallback(async () => {
     try {
-      await invoke('cancel_recording');
+      await commands.cancelRecording();
       setState({ state: 'idle' });
     } catch (error) {
       setState({ state: 'error', error: String(error) });
@@ -104,8 +104,12 @@ export function useRecording() {
   useEffect(() => {
     const fetchInitialState = async () => {
       try {
-        const backendState = await invoke<RecordingState>('get_recording_state');
-        setState(backendState);
+        const backendState = await commands.getRecordingState();
+        setState({
+          state: backendState.state as RecordingState['state'],
+          duration_ms: backendState.duration_ms ?? undefined,
+          error: backendState.error ?? undefined,
+        });
       } catch (error) {
         console.error('Failed to get initial recording state:', error);
       }
@@ -145,6 +145,36 @@ describe('useSettings', () => {
     expect(result.current.saving).toBe(false);
   });
 
+  it('should report shortcut conflicts when save fails', async () => {
+    const conflict = {
+      name: 'settings',
+      shortcut: 'Ctrl+Space',
+      reason: { type: 'duplicate', other: 'record' },
+    };
+    vi.mocked(invoke)
+      .mockResolvedValueOnce(mockSettings) // Initial load
+      .mockRejectedValueOnce('settings shortcut Ctrl+Space is already used') // Save error
+      .mockResolvedValueOnce([conflict]); // Conflict check
+
+    const { result } = renderHook(() => useSettings());
+
+    await waitFor(() => {
+      expect(result.current.loading).toBe(false);
+    });
+
+    const updatedSettings = {
+      ...mockSettings,
+      shortcuts: { ...mockSettings.shortcuts, settings: 'Ctrl+Space' },
+    };
+
+    await act(async () => {
+      await result.current.updateSettings(updatedSettings);
+    });
+
+    expect(invoke).toHaveBeenCalledWith('check_shortcuts', { settings: updatedSettings });
+    expect(result.current.shortcutConflicts).toEqual([conflict]);
+  });
+
   it('should reset settings', async () => {
     const defaultSettings = { ...mockSettings };
     vi.mocked(invoke)
@@ -1,5 +1,45 @@
 import { useState, useEffect, useCallback } from 'react';
-import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
+import {
+  commands,
+  type QualityPreset,
+  type Settings as BackendSettings,
+  type ShortcutConflict,
+} from '../bindings';
+
+type TranscriptionProvider = 'local' | 'groq' | 'deepgram' | 'custom';
+
+// Shortcut recording with its own overrides (null = global setting)
+export type MouseTrigger = 'middle' | 'back' | 'forward';
+
+export type AudioSource = 'microphone' | 'system-audio';
+export type NoiseSuppressor = 'filter' | 'rnnoise';
+export type VadEngine = 'webrtc' | 'silero';
+
+export type GamepadTrigger =
+  | 'any'
+  | 'south'
+  | 'east'
+  | 'north'
+  | 'west'
+  | 'left_bumper'
+  | 'right_bumper'
+  | 'left_trigger'
+  | 'right_trigger'
+  | 'select'
+  | 'start';
+
+export interface RecordingProfile {
+  id: string;
+  name: string;
+  shortcut: string;
+  language: string | null;
+  provider: TranscriptionProvider | null;
+  local_model: 'tiny' | 'base' | 'small' | 'medium' | 'large' | null;
+  groq_model: string | null;
+  translate: boolean | null;
+  source?: AudioSource | null;
+}
 
 // Settings types matching Rust structs
 interface Settings {
@@ -7,17 +47,48 @@ interface Settings {
     mode: 'push-to-talk' | 'toggle';
     max_duration: number;
     silence_timeout: number;
+    save_debug_captures?: boolean;
+    incognito_minutes?: number;
+    history_audio?: 'full' | 'trimmed' | 'speech';
+    history_audio_codec?: 'wav' | 'flac' | 'opus';
   };
   shortcuts: {
     record: string;
     cancel: string;
     settings: string;
+    incognito?: string;
+    toggle_window?: string;
+    repaste_last?: string;
+    switch_profile?: string;
+    system_audio?: string;
+    gestures?: {
+      enabled: boolean;
+      double_tap_ms: number;
+      long_press_ms: number;
+    };
+    triggers?: {
+      mouse_button: MouseTrigger | null;
+      gamepad_button: GamepadTrigger | null;
+    };
   };
   transcription: {
-    provider: 'local' | 'groq';
+    provider: 'local' | 'groq' | 'deepgram' | 'custom';
+    fallback_providers?: ('local' | 'groq' | 'deepgram' | 'custom')[];
     language: string;
+    retry?: {
+      max_retries: number;
+      base_delay_ms: number;
+      max_delay_ms: number;
+      jitter_percent: number;
+    };
+    quality_preset?: QualityPreset;
     local: {
       model: 'tiny' | 'base' | 'small' | 'medium' | 'large';
+      quantization?: 'f16' | 'q8_0' | 'q5_1';
+      custom_model?: string | null;
+      beam_size?: number;
+      temperature?: number;
+      no_speech_threshold?: number;
       threads: number;
       gpu_enabled: boolean;
     };
@@ -26,14 +97,140 @@ interface Settings {
       model: string;
       timeout_seconds: number;
     };
+    deepgram?: {
+      api_key_configured: boolean;
+      model: string;
+      timeout_seconds: number;
+    };
+    custom?: {
+      base_url: string;
+      token_configured: boolean;
+      model: string;
+      timeout_seconds: number;
+    };
+    translate?: boolean;
+    initial_prompt?: string;
+    vocabulary?: string[];
+    language_learning?: boolean;
+    live_partials?: boolean;
+    partial_interval_ms?: number;
+    adaptive_partials?: boolean;
+    partial_interval_min_ms?: number;
+    partial_interval_max_ms?: number;
   };
   audio: {
     input_device: string | null;
+    capture_mode?: 'shared' | 'low-latency';
+    source?: AudioSource;
+    pipeline?: { stage: string; enabled: boolean }[];
+    noise_suppressor?: NoiseSuppressor;
+    vad?: { enabled: boolean; engine?: VadEngine; aggressiveness: number };
+    speaker_filter?: {
+      enabled: boolean;
+      sensitivity: number;
+      profile: { enrolled_at: string } | null;
+    };
   };
   output: {
     auto_capitalize: boolean;
     auto_punctuation: boolean;
     paste_delay: number;
+    mode?: 'paste' | 'type';
+    sinks?: (
+      | { type: 'paste' }
+      | { type: 'clipboard' }
+      | { type: 'append-to-file'; path: string; header: boolean }
+      | { type: 'markdown-journal'; folder: string; template: string; tags: string[] }
+      | { type: 'webhook'; url: string; auth_header: string | null; max_retries: number }
+    )[];
+    typing_rate?: number;
+    injection_audit?: {
+      enabled: boolean;
+      retention_days: number;
+    };
+    type_as_you_speak?: boolean;
+    casing?: 'keep' | 'sentence' | 'lower' | 'upper' | 'title';
+    voice_commands?: {
+      enabled: boolean;
+      commands: { phrase: string; text: string; language: string | null }[];
+    };
+    cleanup?: {
+      enabled: boolean;
+      provider: 'groq' | 'openai' | 'ollama';
+      base_url: string;
+      model: string;
+      prompt: string;
+      timeout_seconds: number;
+      openai_key_configured: boolean;
+      endpoint_token_configured: boolean;
+    };
+    typography?: {
+      enabled: boolean;
+      languages: {
+        language: string;
+        quotes:
+          | 'keep'
+          | 'straight'
+          | 'curly'
+          | 'low-high'
+          | 'low-right'
+          | 'guillemets'
+          | 'reversed-guillemets'
+          | 'corner';
+        decimal_separator: 'keep' | 'point' | 'comma';
+        typographic_apostrophes: boolean;
+        space_before_punctuation: boolean;
+      }[];
+    };
+  };
+  alerts?: {
+    keywords: string[];
+    notify: boolean;
+  };
+  hooks?: {
+    enabled: boolean;
+    timeout_secs: number;
+    hooks: {
+      event: 'recording-started' | 'transcription-completed' | 'output-injected';
+      command: string;
+      args: string[];
+    }[];
+  };
+  workspaces?: {
+    active: string;
+    list: { id: string; name: string; output: Settings['output'] | null }[];
+  };
+  resources?: {
+    max_ram_mb: number;
+    max_vram_mb: number;
+    idle_unload_minutes: number;
+  };
+  latency?: {
+    target_ms: number;
+    alert_after: number;
+    notify: boolean;
+  };
+  profiles?: RecordingProfile[];
+  model_pruning?: {
+    enabled: boolean;
+    unused_days: number;
+  };
+  api_server?: {
+    enabled: boolean;
+    port: number;
+  };
+  mqtt?: {
+    enabled: boolean;
+    host: string;
+    port: number;
+    topic: string;
+    client_id: string;
+    username: string | null;
+    retain: boolean;
+  };
+  privacy?: {
+    no_disk_audio: boolean;
+    encrypt_history: boolean;
   };
   ui: {
     show_indicator: boolean;
@@ -44,17 +241,26 @@ interface Settings {
   };
 }
 
+/** Configured shortcut that can't be registered, from `check_shortcuts` */
+export type { ShortcutConflict };
+
+// The generated settings type leaves every field optional since each has a
+// default; the backend always sends complete settings.
+const fetchSettings = async () => (await commands.getSettings()) as unknown as Settings;
+const toBackend = (settings: Settings) => settings as unknown as BackendSettings;
+
 export function useSettings() {
   const [settings, setSettings] = useState<Settings | null>(null);
   const [loading, setLoading] = useState(true);
   const [saving, setSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [shortcutConflicts, setShortcutConflicts] = useState<ShortcutConflict[]>([]);
 
   // Load settings on mount
   useEffect(() => {
     const loadSettings = async () => {
       try {
-        const data = await invoke<Settings>('get_settings');
+        const data = await fetchSettings();
         setSettings(data);
         setError(null);
       } catch (err) {
@@ -65,6 +271,20 @@ export function useSettings() {
     };
 
     loadSettings();
+
+    // Switching workspace swaps the output settings shown here
+    const unsubscribe = listen('workspace:changed', () => {
+      loadSettings();
+    });
+    // A preset picked from the tray changes the model and decoding values
+    const unsubscribePreset = listen('settings:preset-changed', () => {
+      loadSettings();
+    });
+
+    return () => {
+      unsubscribe.then((fn) => fn());
+      unsubscribePreset.then((fn) => fn());
+    };
   }, []);
 
   // Save settings with debounce
@@ -73,7 +293,35 @@ export function useSettings() {
     setSaving(true);
 
     try {
-      await invoke('save_settings', { settings: newSettings });
+      await commands.saveSettings(toBackend(newSettings));
+      // A preset may have set other values, or been dropped by this edit
+      const preset = newSettings.transcription.quality_preset;
+      if (preset && preset !== 'custom') {
+        setSettings(await fetchSettings());
+      }
+      setError(null);
+      setShortcutConflicts([]);
+    } catch (err) {
+      setError(String(err));
+      // Point at the shortcuts that made the save fail, if any
+      try {
+        const conflicts = await commands.checkShortcuts(toBackend(newSettings));
+        setShortcutConflicts(conflicts ?? []);
+      } catch {
+        setShortcutConflicts([]);
+      }
+    } finally {
+      setSaving(false);
+    }
+  }, []);
+
+  // Switch to a quality preset; the backend sets its values. `download`
+  // confirms downloading a model the preset needs.
+  const applyQualityPreset = useCallback(async (preset: QualityPreset, download = false) => {
+    setSaving(true);
+    try {
+      const settings = await commands.setQualityPreset(preset, download);
+      setSettings(settings as unknown as Settings);
       setError(null);
     } catch (err) {
       setError(String(err));
@@ -87,7 +335,7 @@ export function useSettings() {
     setLoading(true);
     try {
       // Get fresh default settings from backend
-      const data = await invoke<Settings>('get_settings');
+      const data = await fetchSettings();
       setSettings(data);
       setError(null);
     } catch (err) {
@@ -102,7 +350,9 @@ export function useSettings() {
     loading,
     saving,
     error,
+    shortcutConflicts,
     updateSettings,
+    applyQualityPreset,
     resetSettings,
   };
 }
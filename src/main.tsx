@@ -4,6 +4,8 @@ import { getCurrentWindow } from '@tauri-apps/api/window';
 import App from './App';
 import { RecordingIndicatorWindow } from './windows/RecordingIndicator';
 import { PopupWindow } from './windows/PopupWindow';
+import { DictationBoxWindow } from './windows/DictationBoxWindow';
+import { TranscriptViewerWindow } from './windows/TranscriptViewerWindow';
 import './styles/globals.css';
 
 // Get current window label to render appropriate component
@@ -22,6 +24,10 @@ function getWindowComponent() {
       return <RecordingIndicatorWindow />;
     case 'popup':
       return <PopupWindow />;
+    case 'dictation-box':
+      return <DictationBoxWindow />;
+    case 'transcript-viewer':
+      return <TranscriptViewerWindow />;
     default:
       return <App />;
   }
@@ -0,0 +1,91 @@
+import { useEffect, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { Send, X } from 'lucide-react';
+import { commands } from '../bindings';
+import './PopupWindow.css';
+
+export function DictationBoxWindow() {
+  const [text, setText] = useState('');
+  const [sending, setSending] = useState(false);
+
+  // Load current state and follow backend updates
+  useEffect(() => {
+    commands
+      .getDictationBox()
+      .then((state) => setText(state.text))
+      .catch((e) => console.error('Failed to load dictation box:', e));
+
+    const unsubscribers = [
+      listen<string>('dictation-box:updated', (event) => setText(event.payload)),
+      listen('dictation-box:opened', () => setText('')),
+    ];
+
+    return () => {
+      unsubscribers.forEach((unsubscribe) => unsubscribe.then((fn) => fn()));
+    };
+  }, []);
+
+  const handleChange = (value: string) => {
+    setText(value);
+    commands.updateDictationBox(value).catch((e) =>
+      console.error('Failed to update dictation box:', e)
+    );
+  };
+
+  const handleSend = async () => {
+    setSending(true);
+    try {
+      await commands.sendDictationBox(text);
+    } catch (e) {
+      console.error('Failed to send dictation box:', e);
+    } finally {
+      setSending(false);
+    }
+  };
+
+  const handleClose = () => {
+    commands.closeDictationBox().catch((e) => console.error('Failed to close dictation box:', e));
+  };
+
+  return (
+    <div className="popup-container">
+      <div className="popup-window">
+        {/* Header */}
+        <div className="popup-header">
+          <span className="popup-title">Dictation</span>
+          <button onClick={handleClose} className="popup-close-btn" title="Discard">
+            <X className="w-4 h-4" />
+          </button>
+        </div>
+
+        {/* Editor */}
+        <div className="popup-content">
+          <textarea
+            className="popup-text w-full h-28 resize-none bg-transparent outline-none"
+            value={text}
+            placeholder="Start dictating..."
+            onChange={(e) => handleChange(e.target.value)}
+            onKeyDown={(e) => {
+              if (e.key === 'Enter' && (e.ctrlKey || e.metaKey)) {
+                e.preventDefault();
+                handleSend();
+              }
+            }}
+          />
+        </div>
+
+        {/* Footer */}
+        <div className="popup-footer">
+          <button
+            onClick={handleSend}
+            disabled={sending || !text.trim()}
+            className="popup-copy-btn"
+          >
+            <Send className="w-4 h-4" />
+            Send
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
@@ -1,8 +1,8 @@
 import { useEffect, useState } from 'react';
 import { listen } from '@tauri-apps/api/event';
 import { getCurrentWindow } from '@tauri-apps/api/window';
-import { invoke } from '@tauri-apps/api/core';
 import { Copy, Check, X } from 'lucide-react';
+import { commands } from '../bindings';
 import './PopupWindow.css';
 
 // Helper function to apply theme
@@ -22,12 +22,6 @@ function applyTheme(theme: 'system' | 'light' | 'dark') {
   }
 }
 
-interface Settings {
-  ui: {
-    theme: 'system' | 'light' | 'dark';
-  };
-}
-
 export function PopupWindow() {
   const [text, setText] = useState('');
   const [copied, setCopied] = useState(false);
@@ -36,7 +30,7 @@ export function PopupWindow() {
   useEffect(() => {
     const loadTheme = async () => {
       try {
-        const settings = await invoke<Settings>('get_settings');
+        const settings = await commands.getSettings();
         if (settings?.ui?.theme) {
           applyTheme(settings.ui.theme);
         }
@@ -1,24 +1,45 @@
 import { useEffect, useState, useRef } from 'react';
 import { listen } from '@tauri-apps/api/event';
+import { commands, type IndicatorSnapshot, type IndicatorState } from '../bindings';
 import './RecordingIndicator.css';
 
-type IndicatorState = 'recording' | 'processing';
+interface AudioLevel {
+  rms: number;
+  peak: number;
+}
+
+/** Bar heights relative to the level, highest in the middle */
+const BAR_SHAPE = [0.35, 0.5, 0.65, 0.8, 0.9, 1, 1, 0.9, 0.8, 0.65, 0.5, 0.35];
+
+/** Map an RMS amplitude to 0..1 on a -60..0 dBFS scale */
+function meterValue(rms: number): number {
+  if (rms <= 0) return 0;
+  return Math.min(1, Math.max(0, (20 * Math.log10(rms) + 60) / 60));
+}
+
+type DeviceEvent =
+  | { type: 'disconnected'; device: string }
+  | { type: 'reconnected'; device: string };
 
 export function RecordingIndicatorWindow() {
   const [duration, setDuration] = useState(0);
   const [state, setState] = useState<IndicatorState>('recording');
+  const [incognito, setIncognito] = useState(false);
+  // Live input level; null until the first level arrives
+  const [level, setLevel] = useState<number | null>(null);
+  // Input device lost mid-recording, until capture resumes
+  const [deviceLost, setDeviceLost] = useState(false);
   const timerRef = useRef<number | null>(null);
   const startTimeRef = useRef<number>(Date.now());
 
-  // Function to start/restart the timer
-  const startTimer = () => {
+  // Function to start/restart the timer from the backend's elapsed time
+  const startTimer = (elapsedMs: number) => {
     // Clear any existing timer
     if (timerRef.current) {
       clearInterval(timerRef.current);
     }
-    // Reset start time and duration
-    startTimeRef.current = Date.now();
-    setDuration(0);
+    startTimeRef.current = Date.now() - elapsedMs;
+    setDuration(elapsedMs);
     // Start new timer
     timerRef.current = window.setInterval(() => {
       setDuration(Date.now() - startTimeRef.current);
@@ -33,31 +54,46 @@ export function RecordingIndicatorWindow() {
     }
   };
 
-  // Listen for state changes from backend
+  // Sync with the backend indicator controller
   useEffect(() => {
-    // Listen for all state changes
-    const unsubStateChanged = listen<string>('recording:state-changed', (event) => {
-      const newState = event.payload;
-      if (newState === 'recording') {
-        // Restart timer when recording starts
-        startTimer();
+    const apply = (snapshot: IndicatorSnapshot) => {
+      setIncognito(snapshot.incognito);
+      if (snapshot.state === 'recording') {
+        startTimer(snapshot.elapsed_ms);
         setState('recording');
-      } else if (newState === 'processing') {
-        // Stop timer when processing
+      } else if (snapshot.state === 'processing') {
         stopTimer();
         setState('processing');
+        setLevel(null);
+        setDeviceLost(false);
+      } else {
+        stopTimer();
+        setLevel(null);
+        setDeviceLost(false);
       }
-    });
+    };
 
-    // Also listen for the specific indicator event
-    const unsubProcessing = listen('indicator:processing', () => {
-      stopTimer();
-      setState('processing');
+    const unsubState = listen<IndicatorSnapshot>('indicator:state', (event) => {
+      apply(event.payload);
+    });
+    const unsubLevel = listen<AudioLevel>('audio:level', (event) => {
+      setLevel(meterValue(event.payload.rms));
     });
+    const unsubDevice = listen<DeviceEvent>('audio:device', (event) => {
+      setDeviceLost(event.payload.type === 'disconnected');
+      setLevel(null);
+    });
+
+    // Catch up on a state change sent before the listener was registered
+    commands
+      .getIndicatorState()
+      .then(apply)
+      .catch(() => {});
 
     return () => {
-      unsubStateChanged.then((fn) => fn());
-      unsubProcessing.then((fn) => fn());
+      unsubState.then((fn) => fn());
+      unsubLevel.then((fn) => fn());
+      unsubDevice.then((fn) => fn());
       stopTimer();
     };
   }, []);
@@ -71,7 +107,7 @@ export function RecordingIndicatorWindow() {
 
   return (
     <div className="recording-indicator-container">
-      <div className="recording-indicator">
+      <div className={`recording-indicator${incognito ? ' incognito' : ''}`}>
         {state === 'recording' ? (
           <>
             {/* Recording pulse */}
@@ -80,21 +116,25 @@ export function RecordingIndicatorWindow() {
               <div className="recording-dot-pulse" />
             </div>
 
-            {/* Waveform animation */}
+            {/* Level meter, animated until the first level arrives */}
             <div className="waveform">
-              {[...Array(12)].map((_, i) => (
+              {BAR_SHAPE.map((shape, i) => (
                 <div
                   key={i}
-                  className="waveform-bar"
-                  style={{
-                    animationDelay: `${i * 0.05}s`,
-                  }}
+                  className={`waveform-bar${level !== null ? ' level-bar' : ''}`}
+                  style={
+                    level !== null
+                      ? { height: `${6 + 18 * level * shape}px` }
+                      : { animationDelay: `${i * 0.05}s` }
+                  }
                 />
               ))}
             </div>
 
             {/* Duration */}
             <div className="recording-duration">{formatDuration(duration)}</div>
+            {incognito && <div className="incognito-label">Incognito</div>}
+            {deviceLost && <div className="reconnecting-label">Reconnecting</div>}
           </>
         ) : (
           <>
@@ -0,0 +1,110 @@
+import { useEffect, useRef, useState } from 'react';
+import { listen } from '@tauri-apps/api/event';
+import { Eraser, Pause, Play, X } from 'lucide-react';
+import { commands, type ViewerLine } from '../bindings';
+import './PopupWindow.css';
+
+interface TranscriptViewerState {
+  paused: boolean;
+  lines: ViewerLine[];
+  partial: string | null;
+}
+
+type ViewerUpdate =
+  | { type: 'partial'; text: string }
+  | { type: 'line'; line: ViewerLine }
+  | { type: 'cleared' }
+  | { type: 'paused'; paused: boolean };
+
+export function TranscriptViewerWindow() {
+  const [state, setState] = useState<TranscriptViewerState>({
+    paused: false,
+    lines: [],
+    partial: null,
+  });
+  const bottomRef = useRef<HTMLDivElement>(null);
+
+  // Load current state and follow backend updates
+  useEffect(() => {
+    commands
+      .getViewer()
+      .then((viewer) => setState({ ...viewer, partial: viewer.partial ?? null }))
+      .catch((e) => console.error('Failed to load transcript viewer:', e));
+
+    const unsubscribe = listen<ViewerUpdate>('viewer:updated', (event) => {
+      const update = event.payload;
+      setState((current) => {
+        switch (update.type) {
+          case 'partial':
+            return { ...current, partial: update.text };
+          case 'line':
+            return { ...current, partial: null, lines: [...current.lines, update.line].slice(-200) };
+          case 'cleared':
+            return { ...current, partial: null, lines: [] };
+          case 'paused':
+            return { ...current, paused: update.paused };
+        }
+      });
+    });
+
+    return () => {
+      unsubscribe.then((fn) => fn());
+    };
+  }, []);
+
+  // Keep the latest text in view
+  useEffect(() => {
+    bottomRef.current?.scrollIntoView({ behavior: 'smooth' });
+  }, [state.lines, state.partial]);
+
+  const handlePause = () => {
+    commands.setViewerPaused(!state.paused).catch((e) =>
+      console.error('Failed to pause transcript viewer:', e)
+    );
+  };
+
+  const handleClear = () => {
+    commands.clearViewer().catch((e) => console.error('Failed to clear transcript viewer:', e));
+  };
+
+  const handleClose = () => {
+    commands.closeViewer().catch((e) => console.error('Failed to close transcript viewer:', e));
+  };
+
+  return (
+    <div className="popup-container">
+      <div className="popup-window">
+        {/* Header */}
+        <div className="popup-header" data-tauri-drag-region>
+          <span className="popup-title">{state.paused ? 'Transcript (paused)' : 'Transcript'}</span>
+          <div className="flex gap-1">
+            <button
+              onClick={handlePause}
+              className="popup-close-btn"
+              title={state.paused ? 'Resume' : 'Pause'}
+            >
+              {state.paused ? <Play className="w-4 h-4" /> : <Pause className="w-4 h-4" />}
+            </button>
+            <button onClick={handleClear} className="popup-close-btn" title="Clear">
+              <Eraser className="w-4 h-4" />
+            </button>
+            <button onClick={handleClose} className="popup-close-btn" title="Close">
+              <X className="w-4 h-4" />
+            </button>
+          </div>
+        </div>
+
+        {/* Transcript */}
+        <div className="popup-content overflow-y-auto">
+          {state.lines.map((line, i) => (
+            <p key={`${line.timestamp}-${i}`} className="popup-text text-3xl mb-3">
+              {line.text}
+            </p>
+          ))}
+          {state.partial && <p className="popup-text text-3xl opacity-60">{state.partial}</p>}
+          <div ref={bottomRef} />
+        </div>
+      </div>
+    </div>
+  );
+}